@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use apollo_batcher_types::batcher_types::{
     BatcherResult,
@@ -24,6 +25,7 @@ use apollo_batcher_types::batcher_types::{
 use apollo_batcher_types::errors::BatcherError;
 use apollo_class_manager_types::transaction_converter::TransactionConverter;
 use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_committer_types::{CommitterClientError, SharedCommitterClient};
 use apollo_infra::component_definitions::{default_component_start_fn, ComponentStarter};
 use apollo_l1_provider_types::errors::{L1ProviderClientError, L1ProviderError};
 use apollo_l1_provider_types::{SessionState, SharedL1ProviderClient};
@@ -41,9 +43,10 @@ use indexmap::IndexSet;
 use mockall::automock;
 use starknet_api::block::{BlockHeaderWithoutHash, BlockNumber};
 use starknet_api::consensus_transaction::InternalConsensusTransaction;
-use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::core::{ContractAddress, GlobalRoot, Nonce};
 use starknet_api::state::ThinStateDiff;
 use starknet_api::transaction::TransactionHash;
+use thiserror::Error;
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, instrument, trace, Instrument};
 
@@ -86,15 +89,30 @@ use crate::utils::{
     ProposalTask,
 };
 
+// The interval between attempts to commit blocks to the committer, while it fails.
+const COMMITTER_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+// The total time to commit a block to the committer, including the blocks it's missing and the
+// attempts while it fails. Decided blocks wait for their state root, so this is kept short.
+pub(crate) const COMMITTER_TIMEOUT: Duration = Duration::from_secs(10);
+// The number of attempts to catch up the committer with the storage on startup before giving up.
+// The blocks left uncommitted are backfilled when the next block is committed.
+const COMMITTER_CATCH_UP_MAX_ATTEMPTS: usize = 5;
+
+// The interval between prunings of the storage history.
+const HISTORY_PRUNING_INTERVAL: Duration = Duration::from_secs(10);
+
 type OutputStreamReceiver = tokio::sync::mpsc::UnboundedReceiver<InternalConsensusTransaction>;
 type InputStreamSender = tokio::sync::mpsc::Sender<InternalConsensusTransaction>;
 
 pub struct Batcher {
     pub config: BatcherConfig,
     pub storage_reader: Arc<dyn BatcherStorageReaderTrait>,
-    pub storage_writer: Box<dyn BatcherStorageWriterTrait>,
+    /// Shared with the task that prunes the storage history in the background.
+    pub storage_writer: Arc<Mutex<Box<dyn BatcherStorageWriterTrait>>>,
     pub l1_provider_client: SharedL1ProviderClient,
     pub mempool_client: SharedMempoolClient,
+    /// Fed with the state diff of each committed block, to maintain the global state roots.
+    pub committer_client: Option<SharedCommitterClient>,
     pub transaction_converter: TransactionConverter,
 
     /// Used to create block builders.
@@ -136,6 +154,7 @@ impl Batcher {
         storage_writer: Box<dyn BatcherStorageWriterTrait>,
         l1_provider_client: SharedL1ProviderClient,
         mempool_client: SharedMempoolClient,
+        committer_client: Option<SharedCommitterClient>,
         transaction_converter: TransactionConverter,
         block_builder_factory: Box<dyn BlockBuilderFactoryTrait>,
         pre_confirmed_block_writer_factory: Box<dyn PreconfirmedBlockWriterFactoryTrait>,
@@ -143,9 +162,10 @@ impl Batcher {
         Self {
             config,
             storage_reader,
-            storage_writer,
+            storage_writer: Arc::new(Mutex::new(storage_writer)),
             l1_provider_client,
             mempool_client,
+            committer_client,
             transaction_converter,
             block_builder_factory,
             pre_confirmed_block_writer_factory,
@@ -539,12 +559,17 @@ impl Batcher {
         let address_to_nonce = state_diff.nonces.iter().map(|(k, v)| (*k, *v)).collect();
         self.commit_proposal_and_block(
             height,
-            state_diff,
+            state_diff.clone(),
             address_to_nonce,
             l1_transaction_hashes.iter().copied().collect(),
             Default::default(),
         )
         .await?;
+        // The header of a synced block already has its state root, so the block is added even if
+        // the committer fails, and is committed to it along with a later block.
+        if let Err(err) = self.commit_block_to_committer(height, &state_diff).await {
+            error!("Failed to commit synced block {height} to the committer: {err}");
+        }
         LAST_SYNCED_BLOCK.set_lossy(block_number.0);
         SYNCED_TRANSACTIONS.increment(
             (account_transaction_hashes.len() + l1_transaction_hashes.len()).try_into().unwrap(),
@@ -582,6 +607,13 @@ impl Batcher {
                 .count(),
         )
         .expect("Number of reverted transactions should fit in u64");
+        // The state root is written into the header of the decided block, so the decision fails
+        // without it rather than publish a different header than the other nodes. The committer
+        // is updated before the other components, since they can't be reverted.
+        let state_root = self.commit_block_to_committer(height, &state_diff).await.map_err(|err| {
+            error!("Failed to commit block {height} to the committer: {err}");
+            BatcherError::InternalError
+        })?;
         self.commit_proposal_and_block(
             height,
             state_diff.clone(),
@@ -599,6 +631,7 @@ impl Batcher {
 
         Ok(DecisionReachedResponse {
             state_diff,
+            state_root,
             l2_gas_used: block_execution_artifacts.l2_gas_used,
             central_objects: CentralObjects {
                 execution_infos,
@@ -629,10 +662,12 @@ impl Batcher {
         trace!("Rejected transactions: {:#?}, State diff: {:#?}.", rejected_tx_hashes, state_diff);
 
         // Commit the proposal to the storage.
-        self.storage_writer.commit_proposal(height, state_diff).map_err(|err| {
-            error!("Failed to commit proposal to storage: {}", err);
-            BatcherError::InternalError
-        })?;
+        self.storage_writer.lock().await.commit_proposal(height, state_diff.clone()).map_err(
+            |err| {
+                error!("Failed to commit proposal to storage: {}", err);
+                BatcherError::InternalError
+            },
+        )?;
 
         // Notify the L1 provider of the new block.
         let rejected_l1_handler_tx_hashes = rejected_tx_hashes
@@ -667,7 +702,7 @@ impl Batcher {
                 }
             }
             // Rollback the state diff in the storage.
-            self.storage_writer.revert_block(height);
+            self.storage_writer.lock().await.revert_block(height);
             return Err(BatcherError::InternalError);
         }
 
@@ -686,6 +721,103 @@ impl Batcher {
         Ok(())
     }
 
+    /// Commits the block at `height` to the committer, if configured, and returns its state root.
+    /// Retries while the committer fails, for up to `COMMITTER_TIMEOUT` in total.
+    async fn commit_block_to_committer(
+        &self,
+        height: BlockNumber,
+        state_diff: &ThinStateDiff,
+    ) -> Result<Option<GlobalRoot>, CommitterSyncError> {
+        let Some(committer_client) = &self.committer_client else {
+            return Ok(None);
+        };
+        let commit_with_retries = async {
+            loop {
+                match self.try_commit_block_to_committer(committer_client, height, state_diff).await
+                {
+                    Ok(state_root) => return state_root,
+                    Err(err) => error!(
+                        "Failed to commit block {height} to the committer, retrying in \
+                         {COMMITTER_RETRY_INTERVAL:?}: {err}"
+                    ),
+                }
+                tokio::time::sleep(COMMITTER_RETRY_INTERVAL).await;
+            }
+        };
+        tokio::time::timeout(COMMITTER_TIMEOUT, commit_with_retries)
+            .await
+            .map(Some)
+            .map_err(|_| CommitterSyncError::Timeout(COMMITTER_TIMEOUT))
+    }
+
+    async fn try_commit_block_to_committer(
+        &self,
+        committer_client: &SharedCommitterClient,
+        height: BlockNumber,
+        state_diff: &ThinStateDiff,
+    ) -> Result<GlobalRoot, CommitterSyncError> {
+        let next_height = self.backfill_committer(committer_client, height).await?;
+        if next_height > height {
+            // The committer has the block from an earlier attempt, whose response was lost or whose
+            // block wasn't stored after all. Its state diff may differ, so it's committed again.
+            revert_committer_blocks(committer_client, next_height, height).await?;
+        }
+        Ok(committer_client.commit_block(height, state_diff.clone()).await?)
+    }
+
+    /// Commits to the committer the blocks below `height` it is missing, reading their state
+    /// diffs from the storage. Returns the committer's next height.
+    async fn backfill_committer(
+        &self,
+        committer_client: &SharedCommitterClient,
+        height: BlockNumber,
+    ) -> Result<BlockNumber, CommitterSyncError> {
+        let mut next_height = committer_client.get_next_height().await?;
+        if next_height < height {
+            info!("Backfilling the committer from block {next_height} to block {height}.");
+        }
+        while next_height < height {
+            let state_diff = self
+                .storage_reader
+                .state_diff(next_height)?
+                .ok_or(CommitterSyncError::MissingStateDiff(next_height))?;
+            committer_client.commit_block(next_height, state_diff).await?;
+            next_height = next_height.unchecked_next();
+        }
+        Ok(next_height)
+    }
+
+    /// Brings the committer, if configured, up to the blocks in the storage. If the committer keeps
+    /// failing, the blocks are left to be committed to it along with the next block.
+    async fn catch_up_committer(&self, storage_height: BlockNumber) {
+        let Some(committer_client) = &self.committer_client else {
+            return;
+        };
+        for attempt in 1..=COMMITTER_CATCH_UP_MAX_ATTEMPTS {
+            match self.backfill_committer(committer_client, storage_height).await {
+                Ok(_) => return,
+                Err(err) => error!(
+                    "Failed to catch up the committer with the storage (attempt \
+                     {attempt}/{COMMITTER_CATCH_UP_MAX_ATTEMPTS}): {err}"
+                ),
+            }
+            if attempt < COMMITTER_CATCH_UP_MAX_ATTEMPTS {
+                tokio::time::sleep(COMMITTER_RETRY_INTERVAL).await;
+            }
+        }
+        error!("Giving up on catching up the committer with the storage.");
+    }
+
+    /// Reverts the blocks from `height` on in the committer, if configured, so that their state
+    /// roots aren't reused when the heights are decided again.
+    async fn revert_committer_block(&self, height: BlockNumber) -> Result<(), CommitterSyncError> {
+        let Some(committer_client) = &self.committer_client else {
+            return Ok(());
+        };
+        let next_height = committer_client.get_next_height().await?;
+        revert_committer_blocks(committer_client, next_height, height).await
+    }
+
     async fn is_active(&self, proposal_id: ProposalId) -> bool {
         *self.active_proposal.lock().await == Some(proposal_id)
     }
@@ -858,7 +990,12 @@ impl Batcher {
             self.abort_active_height().await;
         }
 
-        self.storage_writer.revert_block(height);
+        // The committer is reverted first, so that a failure leaves both reverts to be retried.
+        self.revert_committer_block(height).await.map_err(|err| {
+            error!("Failed to revert block {height} in the committer: {err}");
+            BatcherError::InternalError
+        })?;
+        self.storage_writer.lock().await.revert_block(height);
         STORAGE_HEIGHT.decrement(1);
         REVERTED_BLOCKS.increment(1);
         Ok(())
@@ -870,6 +1007,7 @@ pub fn create_batcher(
     mempool_client: SharedMempoolClient,
     l1_provider_client: SharedL1ProviderClient,
     class_manager_client: SharedClassManagerClient,
+    committer_client: Option<SharedCommitterClient>,
     pre_confirmed_cende_client: Arc<dyn PreconfirmedCendeClientTrait>,
 ) -> Batcher {
     let (storage_reader, storage_writer) = apollo_storage::open_storage(config.storage.clone())
@@ -901,22 +1039,62 @@ pub fn create_batcher(
         storage_writer,
         l1_provider_client,
         mempool_client,
+        committer_client,
         transaction_converter,
         block_builder_factory,
         pre_confirmed_block_writer_factory,
     )
 }
 
+/// Reverts the latest blocks of the committer, whose next height is `next_height`, down to the
+/// block at `height`.
+async fn revert_committer_blocks(
+    committer_client: &SharedCommitterClient,
+    mut next_height: BlockNumber,
+    height: BlockNumber,
+) -> Result<(), CommitterSyncError> {
+    while next_height > height {
+        next_height = next_height.prev().expect("The next height is above another height.");
+        committer_client.revert_block(next_height).await?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+enum CommitterSyncError {
+    #[error(transparent)]
+    CommitterClientError(#[from] CommitterClientError),
+    #[error("The state diff of block {0} is missing from the storage.")]
+    MissingStateDiff(BlockNumber),
+    #[error(transparent)]
+    StorageError(#[from] apollo_storage::StorageError),
+    #[error("Timed out after {0:?}.")]
+    Timeout(Duration),
+}
+
 #[cfg_attr(test, automock)]
 pub trait BatcherStorageReaderTrait: Send + Sync {
     /// Returns the next height that the batcher should work on.
     fn height(&self) -> apollo_storage::StorageResult<BlockNumber>;
+
+    /// Returns the state diff of the block at `height`, if it is stored.
+    fn state_diff(
+        &self,
+        height: BlockNumber,
+    ) -> apollo_storage::StorageResult<Option<ThinStateDiff>>;
 }
 
 impl BatcherStorageReaderTrait for apollo_storage::StorageReader {
     fn height(&self) -> apollo_storage::StorageResult<BlockNumber> {
         self.begin_ro_txn()?.get_state_marker()
     }
+
+    fn state_diff(
+        &self,
+        height: BlockNumber,
+    ) -> apollo_storage::StorageResult<Option<ThinStateDiff>> {
+        self.begin_ro_txn()?.get_state_diff(height)
+    }
 }
 
 #[cfg_attr(test, automock)]
@@ -928,6 +1106,10 @@ pub trait BatcherStorageWriterTrait: Send + Sync {
     ) -> apollo_storage::StorageResult<()>;
 
     fn revert_block(&mut self, height: BlockNumber);
+
+    /// Prunes a bounded part of the history that is outside of the storage history retention, if
+    /// configured. Returns the pruning marker.
+    fn prune_history(&mut self) -> apollo_storage::StorageResult<BlockNumber>;
 }
 
 impl BatcherStorageWriterTrait for apollo_storage::StorageWriter {
@@ -937,13 +1119,39 @@ impl BatcherStorageWriterTrait for apollo_storage::StorageWriter {
         state_diff: ThinStateDiff,
     ) -> apollo_storage::StorageResult<()> {
         // TODO(AlonH): write casms.
-        self.begin_rw_txn()?.append_state_diff(height, state_diff)?.commit()
+        self.begin_rw_txn()?.append_state_diff(height, state_diff)?.commit()?;
+        Ok(())
     }
 
     // This function will panic if there is a storage failure to revert the block.
     fn revert_block(&mut self, height: BlockNumber) {
         revert_block(self, height);
     }
+
+    fn prune_history(&mut self) -> apollo_storage::StorageResult<BlockNumber> {
+        self.prune_history_outside_retention()
+    }
+}
+
+// Prunes the storage history periodically. Each pruning is bounded, and runs on a blocking thread
+// while holding the writer, so committing blocks waits for at most one bounded pruning.
+async fn prune_history_periodically(
+    storage_writer: Arc<Mutex<Box<dyn BatcherStorageWriterTrait>>>,
+) {
+    let mut interval = tokio::time::interval(HISTORY_PRUNING_INTERVAL);
+    loop {
+        interval.tick().await;
+        let mut storage_writer = storage_writer.clone().lock_owned().await;
+        let result = tokio::task::spawn_blocking(move || storage_writer.prune_history())
+            .await
+            .expect("History pruning should not panic.");
+        match result {
+            Ok(pruning_marker) => {
+                debug!("Pruned the history of the blocks before block {pruning_marker}.")
+            }
+            Err(err) => error!("Failed to prune the history: {err}"),
+        }
+    }
 }
 
 #[async_trait]
@@ -955,5 +1163,7 @@ impl ComponentStarter for Batcher {
             .height()
             .expect("Failed to get height from storage during batcher creation.");
         register_metrics(storage_height);
+        tokio::spawn(prune_history_periodically(self.storage_writer.clone()));
+        self.catch_up_committer(storage_height).await;
     }
 }
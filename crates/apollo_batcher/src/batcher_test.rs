@@ -22,6 +22,7 @@ use apollo_batcher_types::batcher_types::{
 use apollo_batcher_types::errors::BatcherError;
 use apollo_class_manager_types::transaction_converter::TransactionConverter;
 use apollo_class_manager_types::{EmptyClassManagerClient, SharedClassManagerClient};
+use apollo_committer_types::{MockCommitterClient, SharedCommitterClient};
 use apollo_infra::component_client::ClientError;
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_l1_provider_types::errors::{L1ProviderClientError, L1ProviderError};
@@ -34,14 +35,15 @@ use blockifier::abi::constants;
 use indexmap::{indexmap, IndexSet};
 use metrics_exporter_prometheus::PrometheusBuilder;
 use mockall::predicate::eq;
+use mockall::Sequence;
 use rstest::rstest;
 use starknet_api::block::{BlockHeaderWithoutHash, BlockInfo, BlockNumber};
 use starknet_api::consensus_transaction::InternalConsensusTransaction;
-use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::core::{ContractAddress, GlobalRoot, Nonce};
 use starknet_api::state::ThinStateDiff;
 use starknet_api::test_utils::CHAIN_ID_FOR_TESTS;
 use starknet_api::transaction::TransactionHash;
-use starknet_api::{contract_address, nonce, tx_hash};
+use starknet_api::{contract_address, felt, nonce, tx_hash};
 use validator::Validate;
 
 use crate::batcher::{Batcher, MockBatcherStorageReaderTrait, MockBatcherStorageWriterTrait};
@@ -119,6 +121,7 @@ struct MockDependencies {
     block_builder_factory: MockBlockBuilderFactoryTrait,
     pre_confirmed_block_writer_factory: MockPreconfirmedBlockWriterFactoryTrait,
     class_manager_client: SharedClassManagerClient,
+    committer_client: Option<MockCommitterClient>,
 }
 
 impl Default for MockDependencies {
@@ -147,15 +150,19 @@ impl Default for MockDependencies {
             (mock_writer, non_working_candidate_tx_sender, non_working_pre_confirmed_tx_sender)
         });
 
+        let mut storage_writer = MockBatcherStorageWriterTrait::new();
+        storage_writer.expect_prune_history().returning(|| Ok(BlockNumber(0)));
+
         Self {
             storage_reader,
-            storage_writer: MockBatcherStorageWriterTrait::new(),
+            storage_writer,
             l1_provider_client: MockL1ProviderClient::new(),
             mempool_client,
             block_builder_factory,
             pre_confirmed_block_writer_factory,
             // TODO(noamsp): use MockClassManagerClient
             class_manager_client: Arc::new(EmptyClassManagerClient),
+            committer_client: None,
         }
     }
 }
@@ -167,6 +174,9 @@ async fn create_batcher(mock_dependencies: MockDependencies) -> Batcher {
         Box::new(mock_dependencies.storage_writer),
         Arc::new(mock_dependencies.l1_provider_client),
         Arc::new(mock_dependencies.mempool_client),
+        mock_dependencies
+            .committer_client
+            .map(|committer_client| Arc::new(committer_client) as SharedCommitterClient),
         TransactionConverter::new(
             mock_dependencies.class_manager_client,
             CHAIN_ID_FOR_TESTS.clone(),
@@ -902,6 +912,178 @@ async fn add_sync_block() {
     );
 }
 
+#[rstest]
+#[tokio::test]
+async fn add_sync_block_feeds_committer() {
+    let mut mock_dependencies = MockDependencies::default();
+    mock_dependencies.storage_writer.expect_commit_proposal().returning(|_, _| Ok(()));
+    mock_dependencies.mempool_client.expect_commit_block().returning(|_| Ok(()));
+    mock_dependencies.l1_provider_client.expect_commit_block().returning(|_, _, _| Ok(()));
+
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT));
+    committer_client
+        .expect_commit_block()
+        .times(1)
+        .with(eq(INITIAL_HEIGHT), eq(test_state_diff()))
+        .returning(|_, _| Ok(GlobalRoot::default()));
+    mock_dependencies.committer_client = Some(committer_client);
+
+    let mut batcher = create_batcher(mock_dependencies).await;
+
+    let sync_block = SyncBlock {
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: INITIAL_HEIGHT,
+            ..Default::default()
+        },
+        state_diff: test_state_diff(),
+        ..Default::default()
+    };
+    batcher.add_sync_block(sync_block).await.unwrap();
+}
+
+#[rstest]
+#[tokio::test]
+async fn start_backfills_committer_from_storage() {
+    let mut mock_dependencies = MockDependencies::default();
+    let missing_height = INITIAL_HEIGHT.prev().unwrap();
+    mock_dependencies
+        .storage_reader
+        .expect_state_diff()
+        .times(1)
+        .with(eq(missing_height))
+        .returning(|_| Ok(Some(test_state_diff())));
+
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().times(1).returning(move || Ok(missing_height));
+    committer_client
+        .expect_commit_block()
+        .times(1)
+        .with(eq(missing_height), eq(test_state_diff()))
+        .returning(|_, _| Ok(GlobalRoot::default()));
+    mock_dependencies.committer_client = Some(committer_client);
+
+    create_batcher(mock_dependencies).await;
+}
+
+#[rstest]
+#[tokio::test]
+async fn start_prunes_history_in_background() {
+    let mut mock_dependencies = MockDependencies::default();
+    mock_dependencies.storage_writer.checkpoint();
+    let (pruned_sender, mut pruned_receiver) = tokio::sync::mpsc::unbounded_channel();
+    mock_dependencies.storage_writer.expect_prune_history().returning(move || {
+        // The receiver is dropped when the test ends, while the pruning task still runs.
+        let _ = pruned_sender.send(());
+        Ok(BlockNumber(0))
+    });
+
+    let _batcher = create_batcher(mock_dependencies).await;
+    pruned_receiver.recv().await.expect("The history should be pruned.");
+}
+
+#[rstest]
+#[tokio::test(start_paused = true)]
+async fn committer_failures_are_retried() {
+    let mut mock_dependencies = MockDependencies::default();
+    mock_dependencies.storage_writer.expect_commit_proposal().returning(|_, _| Ok(()));
+    mock_dependencies.mempool_client.expect_commit_block().returning(|_| Ok(()));
+    mock_dependencies.l1_provider_client.expect_commit_block().returning(|_, _, _| Ok(()));
+
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT));
+    let mut seq = Sequence::new();
+    committer_client
+        .expect_commit_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_, _| Err(ClientError::CommunicationFailure("Unavailable".into()).into()));
+    committer_client
+        .expect_commit_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(INITIAL_HEIGHT), eq(test_state_diff()))
+        .returning(|_, _| Ok(GlobalRoot::default()));
+    mock_dependencies.committer_client = Some(committer_client);
+
+    let mut batcher = create_batcher(mock_dependencies).await;
+    let sync_block = SyncBlock {
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: INITIAL_HEIGHT,
+            ..Default::default()
+        },
+        state_diff: test_state_diff(),
+        ..Default::default()
+    };
+    batcher.add_sync_block(sync_block).await.unwrap();
+}
+
+#[rstest]
+#[tokio::test(start_paused = true)]
+async fn add_sync_block_despite_committer_failures() {
+    let mut mock_dependencies = MockDependencies::default();
+    mock_dependencies.storage_writer.expect_commit_proposal().times(1).returning(|_, _| Ok(()));
+    mock_dependencies.mempool_client.expect_commit_block().returning(|_| Ok(()));
+    mock_dependencies.l1_provider_client.expect_commit_block().returning(|_, _, _| Ok(()));
+
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT));
+    committer_client
+        .expect_commit_block()
+        .returning(|_, _| Err(ClientError::CommunicationFailure("Unavailable".into()).into()));
+    mock_dependencies.committer_client = Some(committer_client);
+
+    let mut batcher = create_batcher(mock_dependencies).await;
+    let sync_block = SyncBlock {
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: INITIAL_HEIGHT,
+            ..Default::default()
+        },
+        state_diff: test_state_diff(),
+        ..Default::default()
+    };
+    // The header of the synced block has its state root, so the block is added regardless.
+    batcher.add_sync_block(sync_block).await.unwrap();
+}
+
+#[rstest]
+#[tokio::test]
+async fn committer_block_is_committed_again() {
+    let mut mock_dependencies = MockDependencies::default();
+    mock_dependencies.storage_writer.expect_commit_proposal().returning(|_, _| Ok(()));
+    mock_dependencies.mempool_client.expect_commit_block().returning(|_| Ok(()));
+    mock_dependencies.l1_provider_client.expect_commit_block().returning(|_, _, _| Ok(()));
+
+    // The committer has the block from an earlier attempt, which may have another state diff.
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT.unchecked_next()));
+    let mut seq = Sequence::new();
+    committer_client
+        .expect_revert_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(INITIAL_HEIGHT))
+        .returning(|_| Ok(()));
+    committer_client
+        .expect_commit_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(INITIAL_HEIGHT), eq(test_state_diff()))
+        .returning(|_, _| Ok(GlobalRoot::default()));
+    mock_dependencies.committer_client = Some(committer_client);
+
+    let mut batcher = create_batcher(mock_dependencies).await;
+    let sync_block = SyncBlock {
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: INITIAL_HEIGHT,
+            ..Default::default()
+        },
+        state_diff: test_state_diff(),
+        ..Default::default()
+    };
+    batcher.add_sync_block(sync_block).await.unwrap();
+}
+
 #[rstest]
 #[tokio::test]
 async fn add_sync_block_mismatch_block_number() {
@@ -950,6 +1132,50 @@ async fn revert_block() {
     assert_eq!(REVERTED_BLOCKS.parse_numeric_metric::<usize>(&metrics), Some(1));
 }
 
+#[tokio::test]
+async fn revert_block_reverts_committer() {
+    let mut mock_dependencies = MockDependencies::default();
+    let mut seq = Sequence::new();
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT));
+    committer_client
+        .expect_revert_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(LATEST_BLOCK_IN_STORAGE))
+        .returning(|_| Ok(()));
+    mock_dependencies.committer_client = Some(committer_client);
+    mock_dependencies
+        .storage_writer
+        .expect_revert_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(LATEST_BLOCK_IN_STORAGE))
+        .returning(|_| ());
+
+    let mut batcher = create_batcher(mock_dependencies).await;
+    batcher.revert_block(RevertBlockInput { height: LATEST_BLOCK_IN_STORAGE }).await.unwrap();
+}
+
+#[tokio::test]
+async fn revert_block_fails_if_committer_fails() {
+    let mut mock_dependencies = MockDependencies::default();
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT));
+    committer_client
+        .expect_revert_block()
+        .returning(|_| Err(ClientError::CommunicationFailure("Unavailable".into()).into()));
+    mock_dependencies.committer_client = Some(committer_client);
+    // The storage is left as is, so the revert can be retried.
+    mock_dependencies.storage_writer.expect_revert_block().never();
+
+    let mut batcher = create_batcher(mock_dependencies).await;
+    assert_eq!(
+        batcher.revert_block(RevertBlockInput { height: LATEST_BLOCK_IN_STORAGE }).await,
+        Err(BatcherError::InternalError)
+    );
+}
+
 #[tokio::test]
 async fn revert_block_mismatch_block_number() {
     let mut batcher = create_batcher(MockDependencies::default()).await;
@@ -1061,6 +1287,69 @@ async fn decision_reached() {
 }
 
 #[rstest]
+#[tokio::test]
+async fn decision_reached_returns_state_root() {
+    let mut mock_dependencies = MockDependencies::default();
+    mock_dependencies.l1_provider_client.expect_start_block().returning(|_, _| Ok(()));
+    mock_dependencies.mempool_client.expect_commit_block().returning(|_| Ok(()));
+    mock_dependencies.l1_provider_client.expect_commit_block().returning(|_, _, _| Ok(()));
+    mock_create_builder_for_propose_block(
+        &mut mock_dependencies.block_builder_factory,
+        vec![],
+        Ok(BlockExecutionArtifacts::create_for_testing()),
+    );
+
+    // The committer is updated before the storage.
+    let state_diff = BlockExecutionArtifacts::create_for_testing().thin_state_diff();
+    let state_root = GlobalRoot(felt!("0x1"));
+    let mut seq = Sequence::new();
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT));
+    committer_client
+        .expect_commit_block()
+        .times(1)
+        .in_sequence(&mut seq)
+        .with(eq(INITIAL_HEIGHT), eq(state_diff))
+        .returning(move |_, _| Ok(state_root));
+    mock_dependencies.committer_client = Some(committer_client);
+    mock_dependencies
+        .storage_writer
+        .expect_commit_proposal()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_, _| Ok(()));
+
+    let response = batcher_propose_and_commit_block(mock_dependencies).await.unwrap();
+    assert_eq!(response.state_root, Some(state_root));
+}
+
+#[tokio::test(start_paused = true)]
+async fn decision_reached_fails_if_committer_fails() {
+    let mut mock_dependencies = MockDependencies::default();
+    mock_dependencies.l1_provider_client.expect_start_block().returning(|_, _| Ok(()));
+    mock_create_builder_for_propose_block(
+        &mut mock_dependencies.block_builder_factory,
+        vec![],
+        Ok(BlockExecutionArtifacts::create_for_testing()),
+    );
+
+    let mut committer_client = MockCommitterClient::new();
+    committer_client.expect_get_next_height().returning(|| Ok(INITIAL_HEIGHT));
+    committer_client
+        .expect_commit_block()
+        .returning(|_, _| Err(ClientError::CommunicationFailure("Unavailable".into()).into()));
+    mock_dependencies.committer_client = Some(committer_client);
+    // The block isn't published without its state root.
+    mock_dependencies.storage_writer.expect_commit_proposal().never();
+    mock_dependencies.l1_provider_client.expect_commit_block().never();
+    mock_dependencies.mempool_client.expect_commit_block().never();
+
+    assert_eq!(
+        batcher_propose_and_commit_block(mock_dependencies).await,
+        Err(BatcherError::InternalError)
+    );
+}
+
 #[tokio::test]
 async fn decision_reached_no_executed_proposal() {
     let expected_error = BatcherError::ExecutedProposalNotFound { proposal_id: PROPOSAL_ID };
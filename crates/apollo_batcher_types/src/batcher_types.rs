@@ -9,7 +9,7 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHashAndNumber, BlockInfo, BlockNumber};
 use starknet_api::consensus_transaction::InternalConsensusTransaction;
-use starknet_api::core::StateDiffCommitment;
+use starknet_api::core::{GlobalRoot, StateDiffCommitment};
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::state::ThinStateDiff;
 use starknet_api::transaction::TransactionHash;
@@ -117,6 +117,8 @@ pub struct DecisionReachedResponse {
     // TODO(Yael): Consider passing the state_diff as CommitmentStateDiff inside CentralObjects.
     // Today the ThinStateDiff is used for the state sync but it may not be needed in the future.
     pub state_diff: ThinStateDiff,
+    // The global state root after the block, if a committer is configured.
+    pub state_root: Option<GlobalRoot>,
     pub l2_gas_used: GasAmount,
     pub central_objects: CentralObjects,
 }
@@ -47,6 +47,7 @@ use futures::stream;
 use futures_util::{pin_mut, select, Stream, StreamExt};
 use indexmap::IndexMap;
 use papyrus_common::pending_classes::PendingClasses;
+use papyrus_common::sync_notifications::SyncNotification;
 use serde::{Deserialize, Serialize};
 use sources::base_layer::BaseLayerSourceError;
 use starknet_api::block::{
@@ -62,7 +63,7 @@ use starknet_api::contract_class::ContractClass;
 use starknet_api::core::{ClassHash, CompiledClassHash, SequencerPublicKey};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{StateDiff, ThinStateDiff};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 use tokio::task::{spawn_blocking, JoinError};
 use tracing::{debug, error, info, instrument, trace, warn};
 
@@ -83,6 +84,10 @@ const PENDING_SLEEP_DURATION: Duration = Duration::from_millis(500);
 // Sleep duration, in seconds, between sync progress checks.
 const SLEEP_TIME_SYNC_PROGRESS: Duration = Duration::from_secs(300);
 
+// Sleep duration, in seconds, between pruning rounds of the history that is outside of the
+// storage history retention.
+const SLEEP_TIME_HISTORY_PRUNING: Duration = Duration::from_secs(10);
+
 // The first starknet version where we can send sierras to the class manager without casms and it
 // will compile them, in a backward-compatible manner.
 const STARKNET_VERSION_TO_COMPILE_FROM: StarknetVersion = StarknetVersion::V0_12_0;
@@ -184,6 +189,8 @@ pub struct GenericStateSync<
     config: SyncConfig,
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
+    // Notifies readers of the storage about the changes to the synced chain.
+    sync_notifications: broadcast::Sender<SyncNotification>,
     central_source: Arc<TCentralSource>,
     pending_source: Arc<TPendingSource>,
     pending_classes: Arc<RwLock<PendingClasses>>,
@@ -267,6 +274,7 @@ pub enum SyncEvent {
         block_number: BlockNumber,
         block_hash: BlockHash,
     },
+    PruneHistory,
 }
 
 impl<
@@ -359,6 +367,7 @@ impl<
             self.shared_highest_block.clone(),
             self.pending_data.clone(),
             self.pending_classes.clone(),
+            self.sync_notifications.clone(),
             self.config.block_propagation_sleep_duration,
             self.config.collect_pending_data,
             PENDING_SLEEP_DURATION,
@@ -395,12 +404,14 @@ impl<
         // TODO(DvirYo): fix the bug and remove this check.
         let check_sync_progress =
             check_sync_progress(self.reader.clone(), self.config.store_sierras_and_casms).fuse();
+        let history_pruning_stream = stream_history_pruning_events().fuse();
         pin_mut!(
             block_stream,
             state_diff_stream,
             compiled_class_stream,
             base_layer_block_stream,
-            check_sync_progress
+            check_sync_progress,
+            history_pruning_stream
         );
 
         loop {
@@ -411,6 +422,7 @@ impl<
               res = compiled_class_stream.next() => res,
               res = base_layer_block_stream.next() => res,
               res = check_sync_progress.next() => res,
+              res = history_pruning_stream.next() => res,
               complete => break,
             }
             .expect("Received None as a sync event.")?;
@@ -457,6 +469,7 @@ impl<
             SyncEvent::NewBaseLayerBlock { block_number, block_hash } => {
                 self.store_base_layer_block(block_number, block_hash).await
             }
+            SyncEvent::PruneHistory => self.prune_history().await,
             SyncEvent::NoProgress => Err(StateSyncError::NoProgress),
         }
     }
@@ -609,6 +622,9 @@ impl<
 
         // Info the user on syncing the block once all the data is stored.
         info!("SYNC_NEW_BLOCK: Added block {} with hash {:#064x}.", block_number, block_hash.0);
+        // The blocks are stored before their state diffs, so the block is now fully stored. Sending
+        // fails only when there are no receivers, which is fine.
+        let _ = self.sync_notifications.send(SyncNotification::NewBlock(block_number));
 
         Ok(())
     }
@@ -685,6 +701,7 @@ impl<
         block_number: BlockNumber,
         block_hash: BlockHash,
     ) -> StateSyncResult {
+        let sync_notifications = self.sync_notifications.clone();
         self.perform_storage_writes(move |writer| {
             let txn = writer.begin_rw_txn()?;
             // Missing header can be because of a base layer reorg, the matching header may be
@@ -705,12 +722,26 @@ impl<
                 info!("Verified block {block_number} hash against base layer.");
                 txn.update_base_layer_block_marker(&block_number.unchecked_next())?.commit()?;
                 CENTRAL_SYNC_BASE_LAYER_MARKER.set_lossy(block_number.unchecked_next().0);
+                let _ = sync_notifications
+                    .send(SyncNotification::AcceptedOnL1(block_number.unchecked_next()));
             }
             Ok(())
         })
         .await
     }
 
+    // Prunes the history that is outside of the storage history retention, if configured, and
+    // backfills the event keys index of blocks written before it existed.
+    async fn prune_history(&mut self) -> StateSyncResult {
+        self.perform_storage_writes(|writer| {
+            let pruning_marker = writer.prune_history_outside_retention()?;
+            debug!("Pruned the history of the blocks before block {pruning_marker}.");
+            writer.backfill_event_keys_index()?;
+            Ok(())
+        })
+        .await
+    }
+
     // Compares the block's parent hash to the stored block.
     fn verify_parent_block_hash(
         &self,
@@ -758,14 +789,29 @@ impl<
 
         // Revert last blocks if needed.
         let mut last_block_in_storage = header_marker.prev();
+        let mut reverted_blocks = vec![];
         while let Some(block_number) = last_block_in_storage {
             if self.should_revert_block(block_number).await? {
+                let reverted_header = self.reader.begin_ro_txn()?.get_block_header(block_number)?;
                 self.revert_block(block_number).await?;
+                if let Some(header) = reverted_header {
+                    reverted_blocks
+                        .push(BlockHashAndNumber { hash: header.block_hash, number: block_number });
+                }
                 last_block_in_storage = block_number.prev();
             } else {
                 break;
             }
         }
+        // The blocks are reverted from the last one.
+        if let (Some(last_reverted), Some(first_reverted)) =
+            (reverted_blocks.first(), reverted_blocks.last())
+        {
+            let _ = self.sync_notifications.send(SyncNotification::Reorg {
+                first_reverted: *first_reverted,
+                last_reverted: *last_reverted,
+            });
+        }
         Ok(())
     }
 
@@ -840,6 +886,7 @@ fn stream_new_blocks<
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     block_propagation_sleep_duration: Duration,
     collect_pending_data: bool,
     pending_sleep_duration: Duration,
@@ -865,6 +912,7 @@ fn stream_new_blocks<
                         pending_source.clone(),
                         pending_data.clone(),
                         pending_classes.clone(),
+                        sync_notifications.clone(),
                         pending_sleep_duration,
                     ).await?;
                 }
@@ -949,6 +997,7 @@ impl StateSync {
         shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
         pending_data: Arc<RwLock<PendingData>>,
         pending_classes: Arc<RwLock<PendingClasses>>,
+        sync_notifications: broadcast::Sender<SyncNotification>,
         central_source: CentralSource,
         pending_source: PendingSource,
         base_layer_source: Option<EthereumBaseLayerSource>,
@@ -961,6 +1010,7 @@ impl StateSync {
             config,
             shared_highest_block,
             pending_data,
+            sync_notifications,
             pending_classes,
             central_source: Arc::new(central_source),
             pending_source: Arc::new(pending_source),
@@ -1106,3 +1156,13 @@ fn check_sync_progress(
         }
     }
 }
+
+// Yields an event for pruning the history periodically.
+fn stream_history_pruning_events() -> impl Stream<Item = Result<SyncEvent, StateSyncError>> {
+    try_stream! {
+        loop {
+            tokio::time::sleep(SLEEP_TIME_HISTORY_PRUNING).await;
+            yield SyncEvent::PruneHistory;
+        }
+    }
+}
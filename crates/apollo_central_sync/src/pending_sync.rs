@@ -8,10 +8,11 @@ use apollo_storage::StorageReader;
 use futures::stream::FuturesUnordered;
 use futures_util::{FutureExt, StreamExt};
 use papyrus_common::pending_classes::{PendingClasses, PendingClassesTrait};
+use papyrus_common::sync_notifications::SyncNotification;
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::core::ClassHash;
 use starknet_types_core::felt::Felt;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, trace};
 
 use crate::sources::central::CentralSourceTrait;
@@ -28,6 +29,7 @@ pub(crate) async fn sync_pending_data<
     pending_source: Arc<TPendingSource>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     sleep_duration: Duration,
 ) -> Result<(), StateSyncError> {
     let txn = reader.begin_ro_txn()?;
@@ -52,6 +54,7 @@ pub(crate) async fn sync_pending_data<
             pending_source.clone(),
             pending_data.clone(),
             pending_classes.clone(),
+            sync_notifications.clone(),
             Duration::ZERO,
         )
         .boxed(),
@@ -111,6 +114,7 @@ pub(crate) async fn sync_pending_data<
                         pending_source.clone(),
                         pending_data.clone(),
                         pending_classes.clone(),
+                        sync_notifications.clone(),
                         sleep_duration,
                     )
                     .boxed(),
@@ -122,6 +126,7 @@ pub(crate) async fn sync_pending_data<
                     pending_source.clone(),
                     pending_data.clone(),
                     pending_classes.clone(),
+                    sync_notifications.clone(),
                     sleep_duration,
                 )
                 .boxed(),
@@ -143,6 +148,7 @@ async fn get_pending_data<TPendingSource: PendingSourceTrait + Sync + Send + 'st
     pending_source: Arc<TPendingSource>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     sleep_duration: Duration,
 ) -> Result<PendingSyncTaskResult, StateSyncError> {
     tokio::time::sleep(sleep_duration).await;
@@ -177,6 +183,7 @@ async fn get_pending_data<TPendingSource: PendingSourceTrait + Sync + Send + 'st
             pending_classes.write().await.clear();
         }
         *pending_data.write().await = new_pending_data;
+        let _ = sync_notifications.send(SyncNotification::PendingDataUpdated);
         Ok(PendingSyncTaskResult::DownloadedNewPendingData)
     } else {
         debug!("Pending block wasn't updated. Waiting for pending block to be updated.");
@@ -17,6 +17,7 @@ use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use futures::StreamExt;
 use indexmap::IndexMap;
 use papyrus_common::pending_classes::{ApiContractClass, PendingClasses};
+use papyrus_common::sync_notifications::SYNC_NOTIFICATIONS_CHANNEL_CAPACITY;
 use starknet_api::block::{
     Block,
     BlockBody,
@@ -31,7 +32,7 @@ use starknet_api::core::{ClassHash, CompiledClassHash, SequencerPublicKey};
 use starknet_api::crypto::utils::PublicKey;
 use starknet_api::felt;
 use starknet_api::state::{SierraContractClass, StateDiff, StateNumber};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 use tokio::time::sleep;
 use tracing::{debug, error};
 
@@ -129,6 +130,7 @@ async fn run_sync(
         config,
         shared_highest_block: Arc::new(RwLock::new(None)),
         pending_data: Arc::new(RwLock::new(PendingData::default())),
+        sync_notifications: broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY).0,
         central_source: Arc::new(central),
         pending_source: Arc::new(pending_source),
         pending_classes: Arc::new(RwLock::new(PendingClasses::default())),
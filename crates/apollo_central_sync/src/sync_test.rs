@@ -20,6 +20,7 @@ use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use futures_util::StreamExt;
 use indexmap::IndexMap;
 use papyrus_common::pending_classes::{ApiContractClass, PendingClasses, PendingClassesTrait};
+use papyrus_common::sync_notifications::{SyncNotification, SYNC_NOTIFICATIONS_CHANNEL_CAPACITY};
 use pretty_assertions::assert_eq;
 use starknet_api::block::{BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
 use starknet_api::core::{ClassHash, CompiledClassHash, Nonce};
@@ -27,7 +28,7 @@ use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContract
 use starknet_api::hash::StarkHash;
 use starknet_api::state::{SierraContractClass, StateDiff};
 use starknet_api::{contract_address, felt, storage_key};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 
 use crate::sources::base_layer::MockBaseLayerSourceTrait;
 use crate::sources::central::MockCentralSourceTrait;
@@ -183,10 +184,13 @@ async fn store_base_layer_block_test() {
         .commit()
         .unwrap();
 
+    let (sync_notifications, mut sync_notifications_receiver) =
+        broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY);
     let mut gen_state_sync = GenericStateSync {
         config: SyncConfig::default(),
         shared_highest_block: Arc::new(RwLock::new(None)),
         pending_data: Arc::new(RwLock::new(PendingData::default())),
+        sync_notifications,
         central_source: Arc::new(MockCentralSourceTrait::new()),
         pending_source: Arc::new(MockPendingSourceTrait::new()),
         pending_classes: Arc::new(RwLock::new(PendingClasses::default())),
@@ -212,6 +216,10 @@ async fn store_base_layer_block_test() {
     let base_layer_marker =
         gen_state_sync.reader.begin_ro_txn().unwrap().get_base_layer_block_marker().unwrap();
     assert_eq!(base_layer_marker, BlockNumber(1));
+    assert_eq!(
+        sync_notifications_receiver.try_recv().unwrap(),
+        SyncNotification::AcceptedOnL1(BlockNumber(1))
+    );
 }
 
 // Adds to the storage 'headers_num' headers.
@@ -278,6 +286,7 @@ async fn test_pending_sync(
         Arc::new(mock_pending_source),
         pending_data_lock.clone(),
         pending_classes_lock.clone(),
+        broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY).0,
         Duration::ZERO,
     )
     .await
@@ -0,0 +1,466 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use apollo_committer_types::{
+    CommitterError,
+    CommitterResult,
+    ContractLeafData,
+    ContractStorageKeys,
+    MerkleNode,
+    StorageProof,
+    StorageProofRequest,
+    TrieProof,
+};
+use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use apollo_infra::component_definitions::ComponentStarter;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::GlobalRoot;
+use starknet_api::state::ThinStateDiff;
+use starknet_committer::block_committer::commit::commit_block;
+use starknet_committer::block_committer::input::{
+    contract_address_into_node_index,
+    ConfigImpl,
+    Input,
+    StarknetStorageKey,
+    StarknetStorageValue,
+    StateDiff,
+};
+use starknet_committer::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use starknet_committer::patricia_merkle_tree::types::{
+    class_hash_into_node_index,
+    CompiledClassHash,
+};
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::merkle_proof::{
+    fetch_merkle_proof,
+    MerkleProof,
+    ProofNode,
+    ProofNodes,
+};
+use starknet_patricia::patricia_merkle_tree::node_data::inner_node::{BinaryData, EdgeData};
+use starknet_patricia::patricia_merkle_tree::node_data::leaf::Leaf;
+use starknet_patricia::patricia_merkle_tree::types::NodeIndex;
+use starknet_patricia_storage::errors::StorageError;
+use starknet_patricia_storage::mdbx_storage::MdbxStorage;
+use starknet_patricia_storage::storage_trait::{
+    create_db_key,
+    DbKey,
+    DbKeyPrefix,
+    DbValue,
+    Storage,
+};
+use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::{Poseidon, StarkHash};
+use tokio::sync::RwLock;
+use tracing::info;
+use validator::Validate;
+
+#[cfg(test)]
+#[path = "committer_test.rs"]
+mod committer_test;
+
+const GLOBAL_STATE_VERSION: &[u8] = b"STARKNET_STATE_V0";
+
+// Keys of the committer's own records, kept in the storage of the tries. The prefixes differ from
+// those of the trie nodes, so the records can't collide with them.
+const BLOCK_ROOTS_PREFIX: &[u8] = b"committer_block_roots";
+const NEXT_HEIGHT_KEY: &[u8] = b"committer_next_height";
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
+pub struct CommitterConfig {
+    // Path of the directory of the database which persists the state tries.
+    pub storage_path: PathBuf,
+}
+
+impl Default for CommitterConfig {
+    fn default() -> Self {
+        Self { storage_path: "/data/committer/state_tries".into() }
+    }
+}
+
+impl SerializeConfig for CommitterConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from([ser_param(
+            "storage_path",
+            &self.storage_path,
+            "Path of the directory of the database which persists the contracts, classes and \
+             storage tries.",
+            ParamPrivacyInput::Public,
+        )])
+    }
+}
+
+/// The roots of the tries that make up the global state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StateRoots {
+    pub contracts_trie_root_hash: HashOutput,
+    pub classes_trie_root_hash: HashOutput,
+}
+
+impl StateRoots {
+    /// Returns the global state root, which commits to both tries.
+    pub fn global_root(&self) -> GlobalRoot {
+        let contracts_trie_root = self.contracts_trie_root_hash.0;
+        let classes_trie_root = self.classes_trie_root_hash.0;
+        if classes_trie_root == Felt::ZERO {
+            return GlobalRoot(contracts_trie_root);
+        }
+        GlobalRoot(Poseidon::hash_array(&[
+            Felt::from_bytes_be_slice(GLOBAL_STATE_VERSION),
+            contracts_trie_root,
+            classes_trie_root,
+        ]))
+    }
+
+    fn serialize(&self) -> DbValue {
+        DbValue(
+            [
+                self.contracts_trie_root_hash.0.to_bytes_be(),
+                self.classes_trie_root_hash.0.to_bytes_be(),
+            ]
+            .concat(),
+        )
+    }
+
+    fn deserialize(value: &DbValue) -> Self {
+        let (contracts_trie_root, classes_trie_root) = value.0.split_at(value.0.len() / 2);
+        Self {
+            contracts_trie_root_hash: HashOutput(Felt::from_bytes_be_slice(contracts_trie_root)),
+            classes_trie_root_hash: HashOutput(Felt::from_bytes_be_slice(classes_trie_root)),
+        }
+    }
+}
+
+/// Maintains the Patricia-Merkle tries of the global state, by applying the state diff of each
+/// block in order, and serves the resulting state roots.
+///
+/// The tries are persisted along with the roots after each block, so that the committer resumes
+/// from the last committed block on restart. Nodes are never deleted, so the tries of every
+/// committed block remain readable. The nodes are content-addressed, so reverting a block only
+/// requires restoring the roots of the previous block.
+///
+/// Clones share the same state: reads, such as proofs and roots, are served concurrently, and only
+/// the write of a committed block excludes them.
+#[derive(Clone)]
+pub struct Committer {
+    state: Arc<RwLock<CommitterState>>,
+}
+
+pub(crate) struct CommitterState {
+    pub(crate) storage: MdbxStorage,
+    next_height: BlockNumber,
+    latest_roots: StateRoots,
+}
+
+impl Committer {
+    pub fn new(config: CommitterConfig) -> CommitterResult<Self> {
+        let storage = MdbxStorage::open(&config.storage_path).map_err(to_storage_error)?;
+        let next_height = match storage
+            .get(&DbKey(NEXT_HEIGHT_KEY.to_vec()))
+            .map_err(to_storage_error)?
+        {
+            Some(value) => {
+                BlockNumber(u64::from_be_bytes(value.0.as_slice().try_into().map_err(|_| {
+                    CommitterError::StorageError(format!(
+                        "The stored next height should be 8 bytes, got {}.",
+                        value.0.len()
+                    ))
+                })?))
+            }
+            None => BlockNumber::default(),
+        };
+        let latest_roots = match next_height.prev() {
+            Some(latest_height) => read_block_roots(&storage, latest_height)?.ok_or_else(|| {
+                CommitterError::StorageError(format!(
+                    "The roots of the latest committed block {latest_height} are missing."
+                ))
+            })?,
+            None => StateRoots::default(),
+        };
+        info!("Opened the committer's storage. Next height: {next_height}.");
+
+        Ok(Self {
+            state: Arc::new(RwLock::new(CommitterState { storage, next_height, latest_roots })),
+        })
+    }
+
+    pub async fn next_height(&self) -> BlockNumber {
+        self.state.read().await.next_height
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn state(&self) -> tokio::sync::RwLockReadGuard<'_, CommitterState> {
+        self.state.read().await
+    }
+
+    /// Applies the state diff of the block at `height` to the tries, persists them and returns
+    /// the new global state root.
+    pub async fn commit_block(
+        &self,
+        height: BlockNumber,
+        state_diff: ThinStateDiff,
+    ) -> CommitterResult<GlobalRoot> {
+        // The new nodes are computed under a read lock, so that proofs of committed blocks are
+        // still served meanwhile.
+        let (filled_forest, roots) = {
+            let state = self.state.read().await;
+            check_next_height(&state, height)?;
+            let input = Input {
+                state_diff: to_committer_state_diff(state_diff),
+                contracts_trie_root_hash: state.latest_roots.contracts_trie_root_hash,
+                classes_trie_root_hash: state.latest_roots.classes_trie_root_hash,
+                config: ConfigImpl::default(),
+            };
+            let filled_forest = commit_block(input, &state.storage).await.map_err(|e| {
+                CommitterError::BlockCommitmentFailed { height, message: e.to_string() }
+            })?;
+            let roots = StateRoots {
+                contracts_trie_root_hash: filled_forest.get_contract_root_hash(),
+                classes_trie_root_hash: filled_forest.get_compiled_class_root_hash(),
+            };
+            (filled_forest, roots)
+        };
+
+        let mut state = self.state.write().await;
+        // Another commit of this height may have completed while the lock was released.
+        check_next_height(&state, height)?;
+
+        // The new nodes, the roots and the next height are persisted in a single batch, so that the
+        // storage always reflects a whole number of blocks.
+        filled_forest.write_to_storage(&mut state.storage);
+        state.storage.set(block_roots_key(height), roots.serialize());
+        let next_height = height.unchecked_next();
+        state
+            .storage
+            .set(DbKey(NEXT_HEIGHT_KEY.to_vec()), DbValue(next_height.0.to_be_bytes().to_vec()));
+        state.storage.flush().map_err(to_storage_error)?;
+
+        state.next_height = next_height;
+        state.latest_roots = roots;
+        let global_root = roots.global_root();
+        info!("Committed block {height}. Global state root: {global_root:?}.");
+        Ok(global_root)
+    }
+
+    /// Reverts the latest committed block, which must be at `height`, so that the tries are back
+    /// to their state after the previous block, and `height` is committed next.
+    pub async fn revert_block(&self, height: BlockNumber) -> CommitterResult<()> {
+        let mut state = self.state.write().await;
+        let latest_height =
+            state.next_height.prev().ok_or(CommitterError::BlockNotCommitted(height))?;
+        if height != latest_height {
+            return Err(CommitterError::UnexpectedHeight {
+                expected_height: latest_height,
+                got: height,
+            });
+        }
+        let previous_roots = match height.prev() {
+            Some(previous_height) => committed_block_roots(&state, previous_height)?,
+            None => StateRoots::default(),
+        };
+
+        // The nodes of the reverted block are left in the storage, since they may be shared with
+        // other blocks, and are unreachable from the restored roots otherwise.
+        state.storage.delete(&block_roots_key(height));
+        state
+            .storage
+            .set(DbKey(NEXT_HEIGHT_KEY.to_vec()), DbValue(height.0.to_be_bytes().to_vec()));
+        state.storage.flush().map_err(to_storage_error)?;
+
+        state.next_height = height;
+        state.latest_roots = previous_roots;
+        info!("Reverted block {height}.");
+        Ok(())
+    }
+
+    /// Returns the global state root after the block at `height`.
+    pub async fn get_state_root(&self, height: BlockNumber) -> CommitterResult<GlobalRoot> {
+        let state = self.state.read().await;
+        committed_block_roots(&state, height).map(|roots| roots.global_root())
+    }
+
+    /// Returns Merkle proofs of the requested leaves in the tries of the block at `height`.
+    pub async fn get_storage_proof(
+        &self,
+        height: BlockNumber,
+        request: StorageProofRequest,
+    ) -> CommitterResult<StorageProof> {
+        let state = self.state.read().await;
+        let roots = committed_block_roots(&state, height)?;
+
+        let class_indices: Vec<NodeIndex> =
+            request.class_hashes.iter().map(class_hash_into_node_index).collect();
+        let classes_proof = fetch_proof::<CompiledClassHash>(
+            &state.storage,
+            roots.classes_trie_root_hash,
+            &class_indices,
+        )?
+        .nodes;
+
+        let contract_indices: Vec<NodeIndex> =
+            request.contract_addresses.iter().map(contract_address_into_node_index).collect();
+        let contracts_proof = fetch_proof::<ContractState>(
+            &state.storage,
+            roots.contracts_trie_root_hash,
+            &contract_indices,
+        )?;
+
+        // The storage roots of the contracts whose storage is requested. The leaves of the
+        // requested contracts are reused, and the rest are fetched together in a single proof.
+        let mut contract_states = contracts_proof.leaves.clone();
+        let missing_contract_indices: Vec<NodeIndex> = request
+            .contracts_storage_keys
+            .iter()
+            .map(|ContractStorageKeys { contract_address, .. }| {
+                contract_address_into_node_index(contract_address)
+            })
+            .filter(|index| !contract_states.contains_key(index))
+            .collect();
+        if !missing_contract_indices.is_empty() {
+            contract_states.extend(
+                fetch_proof::<ContractState>(
+                    &state.storage,
+                    roots.contracts_trie_root_hash,
+                    &missing_contract_indices,
+                )?
+                .leaves,
+            );
+        }
+
+        let contract_leaves_data = contract_indices
+            .iter()
+            .map(|index| {
+                let contract_state = contract_states.get(index).cloned().unwrap_or_default();
+                ContractLeafData {
+                    nonce: contract_state.nonce,
+                    class_hash: contract_state.class_hash,
+                    storage_root: contract_state.storage_root_hash.0,
+                }
+            })
+            .collect();
+
+        let contracts_storage_proofs = request
+            .contracts_storage_keys
+            .iter()
+            .map(|ContractStorageKeys { contract_address, storage_keys }| {
+                let storage_root_hash = contract_states
+                    .get(&contract_address_into_node_index(contract_address))
+                    .map(|contract_state| contract_state.storage_root_hash)
+                    .unwrap_or_default();
+                let storage_indices: Vec<NodeIndex> = storage_keys
+                    .iter()
+                    .map(|key| NodeIndex::from(&StarknetStorageKey(*key)))
+                    .collect();
+                let storage_proof = fetch_proof::<StarknetStorageValue>(
+                    &state.storage,
+                    storage_root_hash,
+                    &storage_indices,
+                )?;
+                Ok(to_trie_proof(storage_proof.nodes))
+            })
+            .collect::<CommitterResult<_>>()?;
+
+        Ok(StorageProof {
+            classes_proof: to_trie_proof(classes_proof),
+            contracts_proof: to_trie_proof(contracts_proof.nodes),
+            contract_leaves_data,
+            contracts_storage_proofs,
+            contracts_tree_root: roots.contracts_trie_root_hash.0,
+            classes_tree_root: roots.classes_trie_root_hash.0,
+        })
+    }
+}
+
+impl ComponentStarter for Committer {}
+
+fn block_roots_key(height: BlockNumber) -> DbKey {
+    create_db_key(DbKeyPrefix::new(BLOCK_ROOTS_PREFIX), &height.0.to_be_bytes())
+}
+
+fn check_next_height(state: &CommitterState, height: BlockNumber) -> CommitterResult<()> {
+    if height != state.next_height {
+        return Err(CommitterError::UnexpectedHeight {
+            expected_height: state.next_height,
+            got: height,
+        });
+    }
+    Ok(())
+}
+
+fn committed_block_roots(
+    state: &CommitterState,
+    height: BlockNumber,
+) -> CommitterResult<StateRoots> {
+    if height >= state.next_height {
+        return Err(CommitterError::BlockNotCommitted(height));
+    }
+    read_block_roots(&state.storage, height)?.ok_or(CommitterError::BlockNotCommitted(height))
+}
+
+fn fetch_proof<L: Leaf>(
+    storage: &MdbxStorage,
+    root_hash: HashOutput,
+    leaf_indices: &[NodeIndex],
+) -> CommitterResult<MerkleProof<L>> {
+    fetch_merkle_proof(storage, root_hash, leaf_indices)
+        .map_err(|e| CommitterError::StorageError(e.to_string()))
+}
+
+fn read_block_roots(
+    storage: &impl Storage,
+    height: BlockNumber,
+) -> CommitterResult<Option<StateRoots>> {
+    let value = storage.get(&block_roots_key(height)).map_err(to_storage_error)?;
+    Ok(value.as_ref().map(StateRoots::deserialize))
+}
+
+fn to_storage_error(error: StorageError) -> CommitterError {
+    CommitterError::StorageError(error.to_string())
+}
+
+fn to_trie_proof(proof_nodes: ProofNodes) -> TrieProof {
+    proof_nodes
+        .into_iter()
+        .map(|(hash, node)| {
+            let node = match node {
+                ProofNode::Binary(BinaryData { left_hash, right_hash }) => {
+                    MerkleNode::Binary { left: left_hash.0, right: right_hash.0 }
+                }
+                ProofNode::Edge(EdgeData { bottom_hash, path_to_bottom }) => MerkleNode::Edge {
+                    path: Felt::from(&path_to_bottom.path),
+                    length: path_to_bottom.length.into(),
+                    child: bottom_hash.0,
+                },
+            };
+            (hash.0, node)
+        })
+        .collect()
+}
+
+fn to_committer_state_diff(state_diff: ThinStateDiff) -> StateDiff {
+    StateDiff {
+        address_to_class_hash: state_diff.deployed_contracts.into_iter().collect(),
+        address_to_nonce: state_diff.nonces.into_iter().collect(),
+        class_hash_to_compiled_class_hash: state_diff
+            .declared_classes
+            .into_iter()
+            .map(|(class_hash, compiled_class_hash)| {
+                (class_hash, CompiledClassHash(compiled_class_hash.0))
+            })
+            .collect(),
+        storage_updates: state_diff
+            .storage_diffs
+            .into_iter()
+            .map(|(address, updates)| {
+                let updates: HashMap<_, _> = updates
+                    .into_iter()
+                    .map(|(key, value)| (StarknetStorageKey(key), StarknetStorageValue(value)))
+                    .collect();
+                (address, updates)
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,272 @@
+use apollo_committer_types::{
+    CommitterError,
+    ContractLeafData,
+    ContractStorageKeys,
+    MerkleNode,
+    StorageProofRequest,
+};
+use indexmap::indexmap;
+use rstest::{fixture, rstest};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{CompiledClassHash, GlobalRoot};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::{class_hash, contract_address, felt, nonce, storage_key};
+use starknet_patricia_storage::mdbx_storage::MdbxStorage;
+use starknet_patricia_storage::storage_trait::{DbKey, DbValue, Storage};
+use tempfile::TempDir;
+
+use crate::committer::{read_block_roots, Committer, CommitterConfig, NEXT_HEIGHT_KEY};
+
+#[fixture]
+fn storage_dir() -> TempDir {
+    tempfile::tempdir().unwrap()
+}
+
+fn config(storage_dir: &TempDir) -> CommitterConfig {
+    CommitterConfig { storage_path: storage_dir.path().join("state_tries") }
+}
+
+fn state_diff(value: u8) -> ThinStateDiff {
+    ThinStateDiff {
+        deployed_contracts: indexmap! { contract_address!("0x1") => class_hash!("0x2") },
+        storage_diffs: indexmap! {
+            contract_address!("0x1") => indexmap! { storage_key!("0x3") => felt!(value) },
+        },
+        declared_classes: indexmap! { class_hash!("0x2") => CompiledClassHash(felt!("0x4")) },
+        deprecated_declared_classes: vec![],
+        nonces: indexmap! { contract_address!("0x1") => nonce!(value) },
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn commit_blocks(storage_dir: TempDir) {
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+    assert_eq!(committer.next_height().await, BlockNumber(0));
+
+    let first_root = committer.commit_block(BlockNumber(0), state_diff(1)).await.unwrap();
+    let second_root = committer.commit_block(BlockNumber(1), state_diff(2)).await.unwrap();
+    assert_ne!(first_root, GlobalRoot::default());
+    assert_ne!(first_root, second_root);
+
+    assert_eq!(committer.next_height().await, BlockNumber(2));
+    assert_eq!(committer.get_state_root(BlockNumber(0)).await, Ok(first_root));
+    assert_eq!(committer.get_state_root(BlockNumber(1)).await, Ok(second_root));
+    assert_eq!(
+        committer.get_state_root(BlockNumber(2)).await,
+        Err(CommitterError::BlockNotCommitted(BlockNumber(2)))
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn empty_state_diff_keeps_state_root(storage_dir: TempDir) {
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+
+    let empty_state_root =
+        committer.commit_block(BlockNumber(0), ThinStateDiff::default()).await.unwrap();
+    assert_eq!(empty_state_root, GlobalRoot::default());
+
+    let root = committer.commit_block(BlockNumber(1), state_diff(1)).await.unwrap();
+    assert_eq!(committer.commit_block(BlockNumber(2), ThinStateDiff::default()).await, Ok(root));
+}
+
+#[rstest]
+#[tokio::test]
+async fn commit_block_at_unexpected_height(storage_dir: TempDir) {
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+
+    assert_eq!(
+        committer.commit_block(BlockNumber(1), state_diff(1)).await,
+        Err(CommitterError::UnexpectedHeight {
+            expected_height: BlockNumber(0),
+            got: BlockNumber(1)
+        })
+    );
+    assert_eq!(committer.next_height().await, BlockNumber(0));
+}
+
+#[rstest]
+#[tokio::test]
+async fn resume_after_restart(storage_dir: TempDir) {
+    // A reference committer, which is never restarted.
+    let reference_dir = tempfile::tempdir().unwrap();
+    let reference_committer = Committer::new(config(&reference_dir)).unwrap();
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+    for height in 0..2_u8 {
+        let root = committer.commit_block(BlockNumber(height.into()), state_diff(height)).await;
+        assert_eq!(
+            reference_committer.commit_block(BlockNumber(height.into()), state_diff(height)).await,
+            root
+        );
+    }
+    drop(committer);
+
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+    assert_eq!(committer.next_height().await, BlockNumber(2));
+    for height in 0..2 {
+        assert_eq!(
+            committer.get_state_root(BlockNumber(height)).await,
+            reference_committer.get_state_root(BlockNumber(height)).await
+        );
+    }
+
+    // The tries are restored, so the next block builds on the state before the restart.
+    assert_eq!(
+        committer.commit_block(BlockNumber(2), state_diff(3)).await,
+        reference_committer.commit_block(BlockNumber(2), state_diff(3)).await
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn storage_proof_of_historical_block(storage_dir: TempDir) {
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+    committer.commit_block(BlockNumber(0), state_diff(1)).await.unwrap();
+    committer.commit_block(BlockNumber(1), state_diff(2)).await.unwrap();
+
+    let request = StorageProofRequest {
+        class_hashes: vec![class_hash!("0x2")],
+        contract_addresses: vec![contract_address!("0x1"), contract_address!("0x5")],
+        contracts_storage_keys: vec![ContractStorageKeys {
+            contract_address: contract_address!("0x1"),
+            storage_keys: vec![storage_key!("0x3")],
+        }],
+    };
+    let proof = committer.get_storage_proof(BlockNumber(0), request.clone()).await.unwrap();
+
+    let roots =
+        read_block_roots(&committer.state().await.storage, BlockNumber(0)).unwrap().unwrap();
+    assert_eq!(proof.contracts_tree_root, roots.contracts_trie_root_hash.0);
+    assert_eq!(proof.classes_tree_root, roots.classes_trie_root_hash.0);
+    assert!(proof.classes_proof.contains_key(&proof.classes_tree_root));
+    assert!(proof.contracts_proof.contains_key(&proof.contracts_tree_root));
+
+    // The second contract is not deployed, so its leaf is empty.
+    let deployed_contract_leaf = &proof.contract_leaves_data[0];
+    assert_eq!(deployed_contract_leaf.nonce, nonce!(1));
+    assert_eq!(deployed_contract_leaf.class_hash, class_hash!("0x2"));
+    assert_eq!(proof.contract_leaves_data[1], ContractLeafData::default());
+
+    // The storage trie has a single leaf, so its root is an edge to the value of the block.
+    let storage_proof = &proof.contracts_storage_proofs[0];
+    assert_eq!(storage_proof.len(), 1);
+    assert!(matches!(
+        storage_proof.get(&deployed_contract_leaf.storage_root),
+        Some(MerkleNode::Edge { child, .. }) if *child == felt!(1_u8)
+    ));
+
+    // Proofs are served over the roots of each block.
+    let latest_proof = committer.get_storage_proof(BlockNumber(1), request.clone()).await.unwrap();
+    assert_ne!(latest_proof.contracts_tree_root, proof.contracts_tree_root);
+    assert_eq!(
+        committer.get_storage_proof(BlockNumber(2), request).await,
+        Err(CommitterError::BlockNotCommitted(BlockNumber(2)))
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn storage_proof_of_contract_not_requested(storage_dir: TempDir) {
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+    committer.commit_block(BlockNumber(0), state_diff(1)).await.unwrap();
+
+    let contracts_storage_keys = vec![ContractStorageKeys {
+        contract_address: contract_address!("0x1"),
+        storage_keys: vec![storage_key!("0x3")],
+    }];
+    let with_contract = StorageProofRequest {
+        contract_addresses: vec![contract_address!("0x1")],
+        contracts_storage_keys: contracts_storage_keys.clone(),
+        ..Default::default()
+    };
+    let without_contract = StorageProofRequest { contracts_storage_keys, ..Default::default() };
+
+    // The storage root of a contract is resolved whether or not its leaf is requested.
+    let expected = committer.get_storage_proof(BlockNumber(0), with_contract).await.unwrap();
+    let proof = committer.get_storage_proof(BlockNumber(0), without_contract).await.unwrap();
+    assert_eq!(proof.contracts_storage_proofs, expected.contracts_storage_proofs);
+    assert!(proof.contract_leaves_data.is_empty());
+}
+
+#[rstest]
+#[tokio::test]
+async fn revert_blocks(storage_dir: TempDir) {
+    // A reference committer, which never commits the reverted block.
+    let reference_dir = tempfile::tempdir().unwrap();
+    let reference_committer = Committer::new(config(&reference_dir)).unwrap();
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+    let first_root = committer.commit_block(BlockNumber(0), state_diff(1)).await.unwrap();
+    reference_committer.commit_block(BlockNumber(0), state_diff(1)).await.unwrap();
+    committer.commit_block(BlockNumber(1), state_diff(2)).await.unwrap();
+
+    assert_eq!(
+        committer.revert_block(BlockNumber(0)).await,
+        Err(CommitterError::UnexpectedHeight {
+            expected_height: BlockNumber(1),
+            got: BlockNumber(0)
+        })
+    );
+    committer.revert_block(BlockNumber(1)).await.unwrap();
+    assert_eq!(committer.next_height().await, BlockNumber(1));
+    assert_eq!(committer.get_state_root(BlockNumber(0)).await, Ok(first_root));
+    assert_eq!(
+        committer.get_state_root(BlockNumber(1)).await,
+        Err(CommitterError::BlockNotCommitted(BlockNumber(1)))
+    );
+
+    // The revert persists, and the next block builds on the state before the reverted block.
+    drop(committer);
+    let committer = Committer::new(config(&storage_dir)).unwrap();
+    assert_eq!(committer.next_height().await, BlockNumber(1));
+    assert_eq!(
+        committer.commit_block(BlockNumber(1), state_diff(3)).await,
+        reference_committer.commit_block(BlockNumber(1), state_diff(3)).await
+    );
+
+    committer.revert_block(BlockNumber(1)).await.unwrap();
+    committer.revert_block(BlockNumber(0)).await.unwrap();
+    assert_eq!(committer.next_height().await, BlockNumber(0));
+    assert_eq!(
+        committer.revert_block(BlockNumber(0)).await,
+        Err(CommitterError::BlockNotCommitted(BlockNumber(0)))
+    );
+}
+
+#[rstest]
+fn open_fails_on_missing_roots(storage_dir: TempDir) {
+    let config = config(&storage_dir);
+    let mut storage = MdbxStorage::open(&config.storage_path).unwrap();
+    storage.set(DbKey(NEXT_HEIGHT_KEY.to_vec()), DbValue(1_u64.to_be_bytes().to_vec()));
+    storage.flush().unwrap();
+    drop(storage);
+
+    assert!(matches!(Committer::new(config), Err(CommitterError::StorageError(_))));
+}
+
+#[rstest]
+fn storage_serves_unflushed_writes(storage_dir: TempDir) {
+    let path = config(&storage_dir).storage_path;
+    let [flushed_key, deleted_key, pending_key] =
+        [b"flushed", b"deleted", b"pending"].map(|key| DbKey(key.to_vec()));
+    let mut storage = MdbxStorage::open(&path).unwrap();
+    storage.set(DbKey(flushed_key.0.clone()), DbValue(vec![1]));
+    storage.set(DbKey(deleted_key.0.clone()), DbValue(vec![2]));
+    storage.flush().unwrap();
+
+    storage.delete(&deleted_key);
+    storage.set(DbKey(pending_key.0.clone()), DbValue(vec![3]));
+    let keys = [flushed_key, deleted_key, pending_key];
+    assert_eq!(
+        storage.mget(&keys).unwrap(),
+        vec![Some(DbValue(vec![1])), None, Some(DbValue(vec![3]))]
+    );
+
+    // Writes that aren't flushed are lost on reopen.
+    drop(storage);
+    let storage = MdbxStorage::open(&path).unwrap();
+    assert_eq!(
+        storage.mget(&keys).unwrap(),
+        vec![Some(DbValue(vec![1])), Some(DbValue(vec![2])), None]
+    );
+}
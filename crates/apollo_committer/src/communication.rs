@@ -0,0 +1,33 @@
+use apollo_committer_types::{CommitterRequest, CommitterResponse};
+use apollo_infra::component_definitions::ComponentRequestHandler;
+use apollo_infra::component_server::{ConcurrentLocalComponentServer, RemoteComponentServer};
+use async_trait::async_trait;
+
+use crate::committer::Committer;
+
+pub type LocalCommitterServer =
+    ConcurrentLocalComponentServer<Committer, CommitterRequest, CommitterResponse>;
+pub type RemoteCommitterServer = RemoteComponentServer<CommitterRequest, CommitterResponse>;
+
+#[async_trait]
+impl ComponentRequestHandler<CommitterRequest, CommitterResponse> for Committer {
+    async fn handle_request(&mut self, request: CommitterRequest) -> CommitterResponse {
+        match request {
+            CommitterRequest::CommitBlock(height, state_diff) => {
+                CommitterResponse::CommitBlock(self.commit_block(height, state_diff).await)
+            }
+            CommitterRequest::RevertBlock(height) => {
+                CommitterResponse::RevertBlock(self.revert_block(height).await)
+            }
+            CommitterRequest::GetStateRoot(height) => {
+                CommitterResponse::GetStateRoot(self.get_state_root(height).await)
+            }
+            CommitterRequest::GetNextHeight => {
+                CommitterResponse::GetNextHeight(Ok(self.next_height().await))
+            }
+            CommitterRequest::GetStorageProof(height, request) => {
+                CommitterResponse::GetStorageProof(self.get_storage_proof(height, request).await)
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+pub mod committer;
+pub mod communication;
+
+use apollo_committer_types::CommitterResult;
+
+use crate::committer::{Committer, CommitterConfig};
+
+pub fn create_committer(config: CommitterConfig) -> CommitterResult<Committer> {
+    Committer::new(config)
+}
@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
+use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::impl_debug_for_infra_requests_and_responses;
+use apollo_proc_macros::handle_all_response_variants;
+use async_trait::async_trait;
+#[cfg(any(feature = "testing", test))]
+use mockall::automock;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, ContractAddress, GlobalRoot, Nonce};
+use starknet_api::state::{StorageKey, ThinStateDiff};
+use starknet_types_core::felt::Felt;
+use strum_macros::AsRefStr;
+use thiserror::Error;
+
+pub type CommitterResult<T> = Result<T, CommitterError>;
+pub type CommitterClientResult<T> = Result<T, CommitterClientError>;
+
+pub type LocalCommitterClient = LocalComponentClient<CommitterRequest, CommitterResponse>;
+pub type RemoteCommitterClient = RemoteComponentClient<CommitterRequest, CommitterResponse>;
+pub type SharedCommitterClient = Arc<dyn CommitterClient>;
+pub type CommitterRequestAndResponseSender =
+    ComponentRequestAndResponseSender<CommitterRequest, CommitterResponse>;
+
+/// Serves the global state roots of the blocks, computed from their state diffs.
+#[cfg_attr(any(feature = "testing", test), automock)]
+#[async_trait]
+pub trait CommitterClient: Send + Sync {
+    /// Applies the state diff of the block at the given height to the state tries, and returns
+    /// the resulting global state root. Blocks must be committed in order.
+    async fn commit_block(
+        &self,
+        height: BlockNumber,
+        state_diff: ThinStateDiff,
+    ) -> CommitterClientResult<GlobalRoot>;
+
+    /// Reverts the latest committed block, which must be at the given height, so that it's
+    /// committed next.
+    async fn revert_block(&self, height: BlockNumber) -> CommitterClientResult<()>;
+
+    /// Returns the global state root after the block at the given height.
+    async fn get_state_root(&self, height: BlockNumber) -> CommitterClientResult<GlobalRoot>;
+
+    /// Returns the height of the next block to commit.
+    async fn get_next_height(&self) -> CommitterClientResult<BlockNumber>;
+
+    /// Returns Merkle proofs of the given classes, contracts and storage entries in the state
+    /// tries after the block at the given height.
+    async fn get_storage_proof(
+        &self,
+        height: BlockNumber,
+        request: StorageProofRequest,
+    ) -> CommitterClientResult<StorageProof>;
+}
+
+/// The leaves to prove in each of the state tries.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageProofRequest {
+    pub class_hashes: Vec<ClassHash>,
+    pub contract_addresses: Vec<ContractAddress>,
+    pub contracts_storage_keys: Vec<ContractStorageKeys>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractStorageKeys {
+    pub contract_address: ContractAddress,
+    pub storage_keys: Vec<StorageKey>,
+}
+
+/// An inner node of a state trie.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MerkleNode {
+    Binary { left: Felt, right: Felt },
+    Edge { path: Felt, length: u8, child: Felt },
+}
+
+/// The nodes on the paths from the root of a trie to the proven leaves, by hash.
+pub type TrieProof = BTreeMap<Felt, MerkleNode>;
+
+/// The contents of a leaf of the contracts trie.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractLeafData {
+    pub nonce: Nonce,
+    pub class_hash: ClassHash,
+    pub storage_root: Felt,
+}
+
+/// Merkle proofs of a [StorageProofRequest], against the trie roots of a committed block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageProof {
+    pub classes_proof: TrieProof,
+    pub contracts_proof: TrieProof,
+    // The leaves of the requested contracts, in the order of the request. Undeployed contracts
+    // have an empty leaf.
+    pub contract_leaves_data: Vec<ContractLeafData>,
+    // The proofs in the storage tries, in the order of the request.
+    pub contracts_storage_proofs: Vec<TrieProof>,
+    pub contracts_tree_root: Felt,
+    pub classes_tree_root: Felt,
+}
+
+#[derive(Clone, Serialize, Deserialize, AsRefStr)]
+pub enum CommitterRequest {
+    CommitBlock(BlockNumber, ThinStateDiff),
+    RevertBlock(BlockNumber),
+    GetStateRoot(BlockNumber),
+    GetNextHeight,
+    GetStorageProof(BlockNumber, StorageProofRequest),
+}
+impl_debug_for_infra_requests_and_responses!(CommitterRequest);
+
+#[derive(Clone, Serialize, Deserialize, AsRefStr)]
+pub enum CommitterResponse {
+    CommitBlock(CommitterResult<GlobalRoot>),
+    RevertBlock(CommitterResult<()>),
+    GetStateRoot(CommitterResult<GlobalRoot>),
+    GetNextHeight(CommitterResult<BlockNumber>),
+    GetStorageProof(CommitterResult<StorageProof>),
+}
+impl_debug_for_infra_requests_and_responses!(CommitterResponse);
+
+#[derive(Clone, Debug, Error, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommitterError {
+    #[error("Failed to commit block {height}: {message}")]
+    BlockCommitmentFailed { height: BlockNumber, message: String },
+    #[error("Unexpected height: expected {expected_height}, got {got}.")]
+    UnexpectedHeight { expected_height: BlockNumber, got: BlockNumber },
+    #[error("Block {0} is not committed.")]
+    BlockNotCommitted(BlockNumber),
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum CommitterClientError {
+    #[error(transparent)]
+    ClientError(#[from] ClientError),
+    #[error(transparent)]
+    CommitterError(#[from] CommitterError),
+}
+
+#[async_trait]
+impl<ComponentClientType> CommitterClient for ComponentClientType
+where
+    ComponentClientType: Send + Sync + ComponentClient<CommitterRequest, CommitterResponse>,
+{
+    async fn commit_block(
+        &self,
+        height: BlockNumber,
+        state_diff: ThinStateDiff,
+    ) -> CommitterClientResult<GlobalRoot> {
+        let request = CommitterRequest::CommitBlock(height, state_diff);
+        handle_all_response_variants!(
+            CommitterResponse,
+            CommitBlock,
+            CommitterClientError,
+            CommitterError,
+            Direct
+        )
+    }
+
+    async fn revert_block(&self, height: BlockNumber) -> CommitterClientResult<()> {
+        let request = CommitterRequest::RevertBlock(height);
+        handle_all_response_variants!(
+            CommitterResponse,
+            RevertBlock,
+            CommitterClientError,
+            CommitterError,
+            Direct
+        )
+    }
+
+    async fn get_state_root(&self, height: BlockNumber) -> CommitterClientResult<GlobalRoot> {
+        let request = CommitterRequest::GetStateRoot(height);
+        handle_all_response_variants!(
+            CommitterResponse,
+            GetStateRoot,
+            CommitterClientError,
+            CommitterError,
+            Direct
+        )
+    }
+
+    async fn get_next_height(&self) -> CommitterClientResult<BlockNumber> {
+        let request = CommitterRequest::GetNextHeight;
+        handle_all_response_variants!(
+            CommitterResponse,
+            GetNextHeight,
+            CommitterClientError,
+            CommitterError,
+            Direct
+        )
+    }
+
+    async fn get_storage_proof(
+        &self,
+        height: BlockNumber,
+        request: StorageProofRequest,
+    ) -> CommitterClientResult<StorageProof> {
+        let request = CommitterRequest::GetStorageProof(height, request);
+        handle_all_response_variants!(
+            CommitterResponse,
+            GetStorageProof,
+            CommitterClientError,
+            CommitterError,
+            Direct
+        )
+    }
+}
@@ -30,10 +30,21 @@ impl SierraToNativeCompiler {
         &self,
         contract_class: ContractClass,
     ) -> Result<AotContractExecutor, CompilationUtilError> {
+        let output_file = NamedTempFile::new()?;
+        self.compile_into(contract_class, output_file.path())?;
+        load_executor(output_file.path())
+    }
+
+    /// Compiles the contract class into a shared library at `output_path`, alongside its contract
+    /// info at `output_path` with a `json` extension.
+    pub fn compile_into(
+        &self,
+        contract_class: ContractClass,
+        output_path: &Path,
+    ) -> Result<(), CompilationUtilError> {
         let compiler_binary_path = &self.path_to_binary;
 
-        let output_file = NamedTempFile::new()?;
-        let output_file_path = output_file.path().to_str().ok_or(
+        let output_file_path = output_path.to_str().ok_or(
             CompilationUtilError::UnexpectedError("Failed to get output file path".to_owned()),
         )?;
         let optimization_level = self.config.optimization_level.to_string();
@@ -49,13 +60,22 @@ impl SierraToNativeCompiler {
             &additional_args,
             resource_limits,
         )?;
-
-        Ok(AotContractExecutor::from_path(Path::new(&output_file_path))
-            .map_err(|e| CompilationUtilError::CompilationError(e.to_string()))?
-            .unwrap())
+        Ok(())
     }
 }
 
+/// Loads a contract compiled by [`SierraToNativeCompiler::compile_into`].
+pub fn load_executor(path: &Path) -> Result<AotContractExecutor, CompilationUtilError> {
+    AotContractExecutor::from_path(path)
+        .map_err(|e| CompilationUtilError::CompilationError(e.to_string()))?
+        .ok_or_else(|| {
+            CompilationUtilError::UnexpectedError(format!(
+                "The compiled contract at {} is not ready to be loaded.",
+                path.display()
+            ))
+        })
+}
+
 // Returns the OUT_DIR. This function is only operable at run time.
 fn out_dir() -> PathBuf {
     env!("RUNTIME_ACCESSIBLE_OUT_DIR").into()
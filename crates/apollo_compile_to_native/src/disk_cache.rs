@@ -0,0 +1,318 @@
+//! An on-disk cache of compiled contracts, persisting them across restarts.
+//!
+//! Artifacts are content-addressed by the class hash, the compiler version and the optimization
+//! level, so an artifact compiled with different settings is never loaded. Each artifact consists
+//! of the shared library, its contract info and a checksum of both. The checksum is written last
+//! and verified on every load, so partially written or corrupted artifacts are discarded.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use apollo_compilation_utils::errors::CompilationUtilError;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use cairo_native::executor::AotContractExecutor;
+use sha2::{Digest, Sha256};
+use starknet_api::core::ClassHash;
+use tempfile::{Builder, NamedTempFile};
+use tracing::{debug, info, warn};
+
+use crate::compiler::{load_executor, SierraToNativeCompiler};
+use crate::constants::REQUIRED_CAIRO_NATIVE_VERSION;
+
+#[cfg(test)]
+#[path = "disk_cache_test.rs"]
+pub mod disk_cache_test;
+
+const LIBRARY_EXTENSION: &str = "so";
+// Must match the extension the compiler gives the contract info, next to the library.
+const CONTRACT_INFO_EXTENSION: &str = "json";
+const CHECKSUM_EXTENSION: &str = "sha256";
+const ARTIFACT_EXTENSIONS: [&str; 3] =
+    [LIBRARY_EXTENSION, CONTRACT_INFO_EXTENSION, CHECKSUM_EXTENSION];
+// Artifact names are hex digests, so staging files never collide with them.
+const STAGING_PREFIX: &str = "staging-";
+
+struct CachedArtifact {
+    name: String,
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// A size-bounded, on-disk cache of compiled contracts. When full, the least recently used
+/// artifacts are evicted.
+#[derive(Debug)]
+pub struct NativeDiskCache {
+    cache_dir: PathBuf,
+    max_cache_size: u64,
+    /// The compilation settings the artifacts depend on, other than the class itself.
+    compilation_settings: String,
+    /// The names of the artifacts being loaded, which aren't evicted. Also serializes looking up,
+    /// storing and evicting artifacts.
+    lock: Mutex<Vec<String>>,
+}
+
+impl NativeDiskCache {
+    pub fn new(
+        cache_dir: PathBuf,
+        max_cache_size: u64,
+        optimization_level: u8,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(&cache_dir)?;
+        remove_staging_files(&cache_dir)?;
+        Ok(Self {
+            cache_dir,
+            max_cache_size,
+            compilation_settings: format!(
+                "cairo-native:{REQUIRED_CAIRO_NATIVE_VERSION}/opt-level:{optimization_level}"
+            ),
+            lock: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Loads the class's cached artifact, if it exists and is intact.
+    pub fn get(&self, class_hash: ClassHash) -> Option<AotContractExecutor> {
+        self.load(class_hash, load_executor)
+    }
+
+    /// Compiles the class into the cache, and loads the cached artifact.
+    pub fn compile(
+        &self,
+        compiler: &SierraToNativeCompiler,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> Result<AotContractExecutor, CompilationUtilError> {
+        self.store(
+            class_hash,
+            |output_path| compiler.compile_into(contract_class, output_path),
+            load_executor,
+        )
+    }
+
+    /// Loads the class's cached artifact with `load`, if it exists and is intact. Corrupted
+    /// artifacts, and ones that fail to load, are removed. The artifact is verified and loaded
+    /// without holding the lock, which only keeps it from being evicted meanwhile.
+    fn load<T>(
+        &self,
+        class_hash: ClassHash,
+        load: impl FnOnce(&Path) -> Result<T, CompilationUtilError>,
+    ) -> Option<T> {
+        let name = self.artifact_name(class_hash);
+        let expected_checksum = {
+            let mut loading = self.lock();
+            let expected_checksum = self.read_checksum(&name)?;
+            loading.push(name.clone());
+            expected_checksum
+        };
+
+        let result = self
+            .verify(&name, &expected_checksum)
+            .and_then(|()| load(&self.artifact_path(&name, LIBRARY_EXTENSION)));
+
+        let _loading = self.done_loading(&name);
+        match result {
+            Ok(loaded) => {
+                debug!("Loaded the cached native artifact of class {class_hash}.");
+                Some(loaded)
+            }
+            Err(err) => {
+                warn!("Failed to load the cached native artifact of class {class_hash}: {err}");
+                self.remove(&name);
+                None
+            }
+        }
+    }
+
+    /// Returns the checksum of the artifact, if it exists, and marks it as recently used. Must be
+    /// called with the lock held.
+    fn read_checksum(&self, name: &str) -> Option<String> {
+        let checksum_path = self.artifact_path(name, CHECKSUM_EXTENSION);
+        let expected_checksum = match fs::read_to_string(&checksum_path) {
+            Ok(expected_checksum) => expected_checksum,
+            Err(err) if err.kind() == ErrorKind::NotFound => return None,
+            Err(err) => {
+                warn!("Failed to read the checksum of the cached native artifact {name}: {err}");
+                return None;
+            }
+        };
+        if let Err(err) = set_last_used(&checksum_path, SystemTime::now()) {
+            warn!("Failed to mark the cached native artifact {name} as used: {err}");
+        }
+        Some(expected_checksum)
+    }
+
+    fn verify(&self, name: &str, expected_checksum: &str) -> Result<(), CompilationUtilError> {
+        let checksum = checksum(
+            &self.artifact_path(name, LIBRARY_EXTENSION),
+            &self.artifact_path(name, CONTRACT_INFO_EXTENSION),
+        )?;
+        if checksum != expected_checksum {
+            return Err(CompilationUtilError::UnexpectedError(
+                "The artifact is corrupted.".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stores the artifact that `compile` writes to the given path, and loads the stored library
+    /// with `load`. Evicts other artifacts if the cache exceeds its size limit.
+    fn store<T>(
+        &self,
+        class_hash: ClassHash,
+        compile: impl FnOnce(&Path) -> Result<(), CompilationUtilError>,
+        load: impl FnOnce(&Path) -> Result<T, CompilationUtilError>,
+    ) -> Result<T, CompilationUtilError> {
+        let name = self.artifact_name(class_hash);
+        // Compile within the cache directory, so storing the artifact is only a rename.
+        let staging_path = self.staging_file()?.into_temp_path();
+        let staging_contract_info_path = staging_path.with_extension(CONTRACT_INFO_EXTENSION);
+
+        let result = compile(&staging_path).and_then(|()| {
+            let checksum = checksum(&staging_path, &staging_contract_info_path)?;
+            {
+                let mut loading = self.lock();
+                let checksum_path = self.artifact_path(&name, CHECKSUM_EXTENSION);
+                remove_if_exists(&checksum_path)?;
+                fs::rename(&staging_path, self.artifact_path(&name, LIBRARY_EXTENSION))?;
+                fs::rename(
+                    &staging_contract_info_path,
+                    self.artifact_path(&name, CONTRACT_INFO_EXTENSION),
+                )?;
+                // Written last, so only complete artifacts are ever looked up.
+                let mut checksum_file = self.staging_file()?;
+                checksum_file.write_all(checksum.as_bytes())?;
+                checksum_file.persist(&checksum_path).map_err(|err| err.error)?;
+
+                self.evict_except(&name, &loading);
+                loading.push(name.clone());
+            }
+            let result = load(&self.artifact_path(&name, LIBRARY_EXTENSION));
+            drop(self.done_loading(&name));
+            result
+        });
+        if result.is_err() {
+            let _ = remove_if_exists(&staging_contract_info_path);
+        }
+        result
+    }
+
+    /// Evicts the least recently used artifacts, other than `name` and the ones being loaded, until
+    /// the cache fits in its size limit.
+    fn evict_except(&self, name: &str, loading: &[String]) {
+        let mut artifacts = match self.artifacts() {
+            Ok(artifacts) => artifacts,
+            Err(err) => {
+                warn!("Failed to list the cached native artifacts: {err}");
+                return;
+            }
+        };
+        let mut cache_size: u64 = artifacts.iter().map(|artifact| artifact.size).sum();
+        artifacts.sort_by_key(|artifact| artifact.last_used);
+        for artifact in artifacts {
+            if cache_size <= self.max_cache_size {
+                break;
+            }
+            if artifact.name == name || loading.contains(&artifact.name) {
+                continue;
+            }
+            info!("Evicting the cached native artifact {}.", artifact.name);
+            self.remove(&artifact.name);
+            cache_size -= artifact.size;
+        }
+    }
+
+    fn artifacts(&self) -> io::Result<Vec<CachedArtifact>> {
+        let mut artifacts = Vec::new();
+        for entry in fs::read_dir(&self.cache_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(CHECKSUM_EXTENSION)
+            {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let last_used = fs::metadata(&path)?.modified()?;
+            let size = ARTIFACT_EXTENSIONS
+                .iter()
+                .filter_map(|extension| fs::metadata(self.artifact_path(name, extension)).ok())
+                .map(|metadata| metadata.len())
+                .sum();
+            artifacts.push(CachedArtifact { name: name.to_owned(), size, last_used });
+        }
+        Ok(artifacts)
+    }
+
+    /// Removes the artifact, starting with its checksum so it's no longer looked up.
+    fn remove(&self, name: &str) {
+        for extension in [CHECKSUM_EXTENSION, LIBRARY_EXTENSION, CONTRACT_INFO_EXTENSION] {
+            if let Err(err) = remove_if_exists(&self.artifact_path(name, extension)) {
+                warn!("Failed to remove the cached native artifact {name}.{extension}: {err}");
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<String>> {
+        self.lock.lock().expect("Lock should not be poisoned.")
+    }
+
+    /// Unmarks the artifact as being loaded, and returns the held lock.
+    fn done_loading(&self, name: &str) -> MutexGuard<'_, Vec<String>> {
+        let mut loading = self.lock();
+        let index = loading
+            .iter()
+            .position(|loading_name| loading_name == name)
+            .expect("The artifact should be marked as being loaded.");
+        loading.swap_remove(index);
+        loading
+    }
+
+    fn staging_file(&self) -> io::Result<NamedTempFile> {
+        Builder::new().prefix(STAGING_PREFIX).tempfile_in(&self.cache_dir)
+    }
+
+    fn artifact_name(&self, class_hash: ClassHash) -> String {
+        let key = format!("{class_hash}/{}", self.compilation_settings);
+        format!("{:x}", Sha256::digest(key))
+    }
+
+    fn artifact_path(&self, name: &str, extension: &str) -> PathBuf {
+        self.cache_dir.join(format!("{name}.{extension}"))
+    }
+}
+
+/// The checksum of an artifact's library and contract info.
+fn checksum(library_path: &Path, contract_info_path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(fs::read(library_path)?);
+    hasher.update(fs::read(contract_info_path)?);
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn set_last_used(path: &Path, last_used: SystemTime) -> io::Result<()> {
+    OpenOptions::new().write(true).open(path)?.set_modified(last_used)
+}
+
+/// Removes the files left by a store that was interrupted, e.g., by a crash.
+fn remove_staging_files(cache_dir: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(cache_dir)? {
+        let path = entry?.path();
+        let is_staging_file = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(STAGING_PREFIX));
+        if is_staging_file {
+            remove_if_exists(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
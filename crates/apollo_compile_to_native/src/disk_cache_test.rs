@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use apollo_compilation_utils::errors::CompilationUtilError;
+use assert_matches::assert_matches;
+use starknet_api::class_hash;
+use starknet_api::core::ClassHash;
+use tempfile::TempDir;
+
+use crate::disk_cache::{set_last_used, NativeDiskCache, CHECKSUM_EXTENSION};
+
+const OPTIMIZATION_LEVEL: u8 = 2;
+const ARTIFACT_SIZE: u64 = 100;
+// The artifact along with its hex encoded checksum.
+const CACHED_ARTIFACT_SIZE: u64 = ARTIFACT_SIZE + 64;
+
+/// Stores an artifact of `ARTIFACT_SIZE` bytes, without actually compiling.
+fn store_fake_artifact(cache: &NativeDiskCache, class_hash: ClassHash, content: u8) {
+    cache
+        .store(
+            class_hash,
+            |output_path| {
+                fs::write(output_path, [content; ARTIFACT_SIZE as usize - 1])?;
+                fs::write(output_path.with_extension("json"), [content])?;
+                Ok(())
+            },
+            |_library_path| Ok(()),
+        )
+        .unwrap();
+}
+
+/// Returns the path of the class's cached library, without loading it.
+fn lookup(cache: &NativeDiskCache, class_hash: ClassHash) -> Option<PathBuf> {
+    cache.load(class_hash, |library_path| Ok(library_path.to_path_buf()))
+}
+
+fn files_in(dir: &Path) -> usize {
+    fs::read_dir(dir).unwrap().count()
+}
+
+#[test]
+fn lookup_stored_artifact() {
+    let dir = TempDir::new().unwrap();
+    let cache = NativeDiskCache::new(dir.path().into(), u64::MAX, OPTIMIZATION_LEVEL).unwrap();
+    assert_eq!(lookup(&cache, class_hash!(1_u8)), None);
+
+    store_fake_artifact(&cache, class_hash!(1_u8), 1);
+    let library_path = lookup(&cache, class_hash!(1_u8)).unwrap();
+    assert_eq!(fs::read(library_path).unwrap(), [1; ARTIFACT_SIZE as usize - 1]);
+    assert_eq!(lookup(&cache, class_hash!(2_u8)), None);
+
+    // The cache persists across restarts.
+    let cache = NativeDiskCache::new(dir.path().into(), u64::MAX, OPTIMIZATION_LEVEL).unwrap();
+    assert!(lookup(&cache, class_hash!(1_u8)).is_some());
+
+    // Artifacts compiled with different settings are not loaded.
+    let cache = NativeDiskCache::new(dir.path().into(), u64::MAX, OPTIMIZATION_LEVEL + 1).unwrap();
+    assert_eq!(lookup(&cache, class_hash!(1_u8)), None);
+}
+
+#[test]
+fn corrupted_artifact_is_removed() {
+    let dir = TempDir::new().unwrap();
+    let cache = NativeDiskCache::new(dir.path().into(), u64::MAX, OPTIMIZATION_LEVEL).unwrap();
+    store_fake_artifact(&cache, class_hash!(1_u8), 1);
+
+    let library_path = lookup(&cache, class_hash!(1_u8)).unwrap();
+    fs::write(&library_path, [2; ARTIFACT_SIZE as usize - 1]).unwrap();
+
+    assert_eq!(lookup(&cache, class_hash!(1_u8)), None);
+    assert_eq!(files_in(dir.path()), 0);
+}
+
+#[test]
+fn failed_compilation_is_not_cached() {
+    let dir = TempDir::new().unwrap();
+    let cache = NativeDiskCache::new(dir.path().into(), u64::MAX, OPTIMIZATION_LEVEL).unwrap();
+
+    let result = cache.store(
+        class_hash!(1_u8),
+        |output_path| {
+            fs::write(output_path.with_extension("json"), [1])?;
+            Err(CompilationUtilError::CompilationError("Compilation failed.".to_owned()))
+        },
+        |_library_path| Ok(()),
+    );
+
+    assert_matches!(result, Err(CompilationUtilError::CompilationError(..)));
+    assert_eq!(lookup(&cache, class_hash!(1_u8)), None);
+    assert_eq!(files_in(dir.path()), 0);
+}
+
+#[test]
+fn interrupted_store_is_cleaned_up_on_restart() {
+    let dir = TempDir::new().unwrap();
+    let cache = NativeDiskCache::new(dir.path().into(), u64::MAX, OPTIMIZATION_LEVEL).unwrap();
+    store_fake_artifact(&cache, class_hash!(1_u8), 1);
+    let staging_path = cache.staging_file().unwrap().into_temp_path().keep().unwrap();
+    fs::write(staging_path.with_extension("json"), [2]).unwrap();
+
+    let cache = NativeDiskCache::new(dir.path().into(), u64::MAX, OPTIMIZATION_LEVEL).unwrap();
+
+    assert!(!staging_path.exists());
+    assert!(lookup(&cache, class_hash!(1_u8)).is_some());
+    // Only the stored artifact's library, contract info and checksum remain.
+    assert_eq!(files_in(dir.path()), 3);
+}
+
+#[test]
+fn least_recently_used_artifacts_are_evicted() {
+    let dir = TempDir::new().unwrap();
+    let cache =
+        NativeDiskCache::new(dir.path().into(), 2 * CACHED_ARTIFACT_SIZE, OPTIMIZATION_LEVEL)
+            .unwrap();
+    let now = SystemTime::now();
+    for (i, class_hash) in [class_hash!(1_u8), class_hash!(2_u8)].into_iter().enumerate() {
+        store_fake_artifact(&cache, class_hash, 1);
+        let checksum_path =
+            cache.artifact_path(&cache.artifact_name(class_hash), CHECKSUM_EXTENSION);
+        set_last_used(&checksum_path, now - Duration::from_secs(100 - u64::try_from(i).unwrap()))
+            .unwrap();
+    }
+    // Using the older artifact makes the other one the least recently used.
+    assert!(lookup(&cache, class_hash!(1_u8)).is_some());
+
+    store_fake_artifact(&cache, class_hash!(3_u8), 3);
+
+    assert!(lookup(&cache, class_hash!(1_u8)).is_some());
+    assert_eq!(lookup(&cache, class_hash!(2_u8)), None);
+    assert!(lookup(&cache, class_hash!(3_u8)).is_some());
+}
+
+#[test]
+fn artifacts_being_loaded_are_not_evicted() {
+    let dir = TempDir::new().unwrap();
+    let cache =
+        NativeDiskCache::new(dir.path().into(), CACHED_ARTIFACT_SIZE, OPTIMIZATION_LEVEL).unwrap();
+    store_fake_artifact(&cache, class_hash!(1_u8), 1);
+
+    // The lock isn't held while loading, so other artifacts can be stored meanwhile.
+    let loaded = cache.load(class_hash!(1_u8), |_library_path| {
+        store_fake_artifact(&cache, class_hash!(2_u8), 2);
+        Ok(())
+    });
+
+    assert_eq!(loaded, Some(()));
+    assert!(lookup(&cache, class_hash!(1_u8)).is_some());
+    assert!(lookup(&cache, class_hash!(2_u8)).is_some());
+}
@@ -3,6 +3,7 @@
 // Include the compilation modules
 pub mod compiler;
 pub mod constants;
+pub mod disk_cache;
 
 #[cfg(test)]
 #[path = "compile_test.rs"]
@@ -13,6 +13,7 @@ pub const DEFAULT_MAX_FILE_SIZE: u64 = 50 * 1024 * 1024;
 pub const DEFAULT_MAX_CPU_TIME: u64 = 600;
 pub const DEFAULT_MAX_MEMORY_USAGE: u64 = 15 * 1024 * 1024 * 1024;
 pub const DEFAULT_OPTIMIZATION_LEVEL: u8 = 2;
+pub const DEFAULT_MAX_CACHE_SIZE: u64 = 10 * 1024 * 1024 * 1024;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct SierraCompilationConfig {
@@ -26,6 +27,11 @@ pub struct SierraCompilationConfig {
     pub optimization_level: u8,
     /// Compiler binary path.
     pub compiler_binary_path: Option<PathBuf>,
+    /// Directory of the on-disk cache of compiled artifacts, which persists them across restarts.
+    /// If None, compiled artifacts are not cached on disk.
+    pub cache_dir: Option<PathBuf>,
+    /// The on-disk cache's total size limit (in bytes).
+    pub max_cache_size: u64,
 }
 
 impl Default for SierraCompilationConfig {
@@ -36,6 +42,8 @@ impl Default for SierraCompilationConfig {
             max_cpu_time: Some(DEFAULT_MAX_CPU_TIME),
             max_memory_usage: Some(DEFAULT_MAX_MEMORY_USAGE),
             optimization_level: DEFAULT_OPTIMIZATION_LEVEL,
+            cache_dir: None,
+            max_cache_size: DEFAULT_MAX_CACHE_SIZE,
         }
     }
 }
@@ -48,18 +56,29 @@ impl SierraCompilationConfig {
             max_cpu_time: Some(20),
             max_memory_usage: Some(5 * 1024 * 1024 * 1024),
             optimization_level: 0,
+            cache_dir: None,
+            max_cache_size: DEFAULT_MAX_CACHE_SIZE,
         }
     }
 }
 
 impl SerializeConfig for SierraCompilationConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        let mut dump = BTreeMap::from([ser_param(
-            "optimization_level",
-            &self.optimization_level,
-            "The level of optimization to apply during compilation.",
-            ParamPrivacyInput::Public,
-        )]);
+        let mut dump = BTreeMap::from([
+            ser_param(
+                "optimization_level",
+                &self.optimization_level,
+                "The level of optimization to apply during compilation.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_cache_size",
+                &self.max_cache_size,
+                "Limitation of the on-disk cache's total size (bytes). The least recently used \
+                 artifacts are evicted beyond it.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
         dump.extend(ser_optional_param(
             &self.compiler_binary_path,
             "".into(),
@@ -67,6 +86,14 @@ impl SerializeConfig for SierraCompilationConfig {
             "The path to the Sierra-to-Native compiler binary.",
             ParamPrivacyInput::Public,
         ));
+        dump.extend(ser_optional_param(
+            &self.cache_dir,
+            "".into(),
+            "cache_dir",
+            "Directory of the on-disk cache of compiled Cairo Native artifacts, which persists \
+             them across restarts.",
+            ParamPrivacyInput::Public,
+        ));
         dump.extend(ser_optional_param(
             &self.max_file_size,
             DEFAULT_MAX_FILE_SIZE,
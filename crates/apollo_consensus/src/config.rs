@@ -3,18 +3,25 @@
 //! such as the validator ID, the network topic of the consensus, and the starting block height.
 
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use apollo_config::converters::{
     deserialize_float_seconds_to_duration,
     deserialize_seconds_to_duration,
 };
-use apollo_config::dumping::{prepend_sub_config_name, ser_param, SerializeConfig};
+use apollo_config::dumping::{
+    prepend_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_protobuf::consensus::DEFAULT_VALIDATOR_ID;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::evidence::DEFAULT_MAX_EVIDENCE;
 use crate::types::ValidatorId;
 
 /// Configuration for consensus.
@@ -36,6 +43,15 @@ pub struct ConsensusConfig {
     pub future_round_limit: u32,
     /// How many rounds should we cache for future heights.
     pub future_height_round_limit: u32,
+    /// Path of the write-ahead log, which persists this node's votes and proposals so they survive
+    /// a restart. If None, they aren't persisted.
+    pub wal_path: Option<PathBuf>,
+    /// Path of the file in which evidence of validators equivocating is stored. If None, the
+    /// evidence is only kept in memory.
+    pub evidence_path: Option<PathBuf>,
+    /// The maximal number of pieces of evidence kept. When exceeded, the oldest evidence is
+    /// dropped.
+    pub max_evidence: usize,
 }
 
 impl SerializeConfig for ConsensusConfig {
@@ -77,7 +93,29 @@ impl SerializeConfig for ConsensusConfig {
                 "How many rounds should we cache for future heights.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_evidence",
+                &self.max_evidence,
+                "The maximal number of pieces of equivocation evidence kept. When exceeded, the \
+                 oldest evidence is dropped.",
+                ParamPrivacyInput::Public,
+            ),
         ]);
+        config.extend(ser_optional_param(
+            &self.wal_path,
+            "".into(),
+            "wal_path",
+            "Path of the consensus write-ahead log, used to resume a height after a restart.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(ser_optional_param(
+            &self.evidence_path,
+            "".into(),
+            "evidence_path",
+            "Path of the file storing evidence of validators equivocating. If not set, the \
+             evidence is only kept in memory.",
+            ParamPrivacyInput::Public,
+        ));
         config.extend(prepend_sub_config_name(self.timeouts.dump(), "timeouts"));
         config
     }
@@ -93,6 +131,9 @@ impl Default for ConsensusConfig {
             future_height_limit: 10,
             future_round_limit: 10,
             future_height_round_limit: 1,
+            wal_path: None,
+            evidence_path: None,
+            max_evidence: DEFAULT_MAX_EVIDENCE,
         }
     }
 }
@@ -153,11 +194,27 @@ pub struct StreamHandlerConfig {
     pub channel_buffer_capacity: usize,
     /// The maximum number of streams that can be open at the same time.
     pub max_streams: usize,
+    /// The maximum number of messages an inbound message may skip ahead of the next expected
+    /// message in its stream.
+    pub max_message_gap: u64,
+    /// The maximum size of an inbound stream, in bytes.
+    pub max_stream_size: usize,
+    /// The maximum size of the out of order inbound messages buffered for a single peer, in bytes.
+    pub max_buffered_bytes_per_peer: usize,
+    /// The maximum size of the out of order inbound messages buffered for all peers, in bytes.
+    pub max_buffered_bytes: usize,
 }
 
 impl Default for StreamHandlerConfig {
     fn default() -> Self {
-        Self { channel_buffer_capacity: 1000, max_streams: 100 }
+        Self {
+            channel_buffer_capacity: 1000,
+            max_streams: 100,
+            max_message_gap: 1000,
+            max_stream_size: 100 * 1024 * 1024,
+            max_buffered_bytes_per_peer: 10 * 1024 * 1024,
+            max_buffered_bytes: 100 * 1024 * 1024,
+        }
     }
 }
 
@@ -176,6 +233,33 @@ impl SerializeConfig for StreamHandlerConfig {
                 "The maximum number of streams that can be open at the same time.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_message_gap",
+                &self.max_message_gap,
+                "The maximum number of messages an inbound message may skip ahead of the next \
+                 expected message in its stream.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_stream_size",
+                &self.max_stream_size,
+                "The maximum size of an inbound stream, in bytes.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_buffered_bytes_per_peer",
+                &self.max_buffered_bytes_per_peer,
+                "The maximum size of the out of order inbound messages buffered for a single \
+                 peer, in bytes.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_buffered_bytes",
+                &self.max_buffered_bytes,
+                "The maximum size of the out of order inbound messages buffered for all peers, in \
+                 bytes.",
+                ParamPrivacyInput::Public,
+            ),
         ])
     }
 }
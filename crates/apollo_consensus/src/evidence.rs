@@ -0,0 +1,202 @@
+//! Evidence of validators misbehaving in consensus.
+//!
+//! A validator equivocates when it signs two different votes of the same type for the same height
+//! and round. Since both votes are signed, together they prove the misbehavior to anyone, and can
+//! later be used to slash the validator's stake.
+//!
+//! [`EvidencePool`] collects the evidence found while running consensus. It can be persisted to a
+//! file, in which case evidence found before a restart is kept. The pool holds a bounded amount of
+//! evidence, dropping the oldest when full, and the file is compacted accordingly.
+
+#[cfg(test)]
+#[path = "evidence_test.rs"]
+mod evidence_test;
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use apollo_infra_utils::json_lines::{
+    read_records,
+    rewrite_records,
+    write_record,
+    JsonLinesError,
+    JsonLinesResult,
+};
+use apollo_protobuf::consensus::{Vote, VoteType};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use tracing::{info, warn};
+
+use crate::metrics::CONSENSUS_EQUIVOCATION_EVIDENCE;
+use crate::types::{Round, ValidatorId};
+
+/// Proof that a validator sent two conflicting votes: same type, height and round, but for
+/// different proposals.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Evidence {
+    pub first_vote: Vote,
+    pub second_vote: Vote,
+}
+
+impl Evidence {
+    /// Returns evidence if the votes conflict, otherwise None.
+    pub fn from_conflicting_votes(first_vote: Vote, second_vote: Vote) -> Option<Self> {
+        let conflicting = first_vote.vote_type == second_vote.vote_type
+            && first_vote.height == second_vote.height
+            && first_vote.round == second_vote.round
+            && first_vote.voter == second_vote.voter
+            && first_vote.block_hash != second_vote.block_hash;
+        conflicting.then_some(Self { first_vote, second_vote })
+    }
+
+    pub fn voter(&self) -> ValidatorId {
+        self.first_vote.voter
+    }
+
+    pub fn height(&self) -> BlockNumber {
+        BlockNumber(self.first_vote.height)
+    }
+
+    pub fn round(&self) -> Round {
+        self.first_vote.round
+    }
+
+    pub fn vote_type(&self) -> &VoteType {
+        &self.first_vote.vote_type
+    }
+
+    // A validator can equivocate many times in the same step, but one piece of evidence suffices.
+    fn is_duplicate_of(&self, other: &Self) -> bool {
+        self.voter() == other.voter()
+            && self.height() == other.height()
+            && self.round() == other.round()
+            && self.vote_type() == other.vote_type()
+    }
+}
+
+pub const DEFAULT_MAX_EVIDENCE: usize = 1000;
+
+pub type EvidenceError = JsonLinesError;
+
+pub type EvidenceResult<T> = JsonLinesResult<T>;
+
+#[derive(Debug)]
+struct EvidenceFile {
+    path: PathBuf,
+    file: File,
+    // The number of records in the file, including ones already dropped from the pool.
+    n_records: usize,
+}
+
+impl EvidenceFile {
+    fn append(&mut self, evidence: &Evidence) -> EvidenceResult<()> {
+        write_record(&mut self.file, evidence)?;
+        self.file.sync_data()?;
+        self.n_records += 1;
+        Ok(())
+    }
+
+    fn compact(&mut self, evidence: &mut VecDeque<Evidence>) -> EvidenceResult<()> {
+        self.file = rewrite_records(&self.path, evidence.make_contiguous())?;
+        self.n_records = evidence.len();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct EvidencePoolInner {
+    evidence: VecDeque<Evidence>,
+    max_evidence: usize,
+    file: Option<EvidenceFile>,
+}
+
+/// The evidence collected by consensus. Cloning returns a handle to the same pool, so the node can
+/// query the evidence while consensus is running. The default pool is kept in memory only.
+#[derive(Clone, Debug)]
+pub struct EvidencePool {
+    inner: Arc<Mutex<EvidencePoolInner>>,
+}
+
+impl Default for EvidencePool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_EVIDENCE)
+    }
+}
+
+impl EvidencePool {
+    /// Creates a pool, kept in memory only, holding up to `max_evidence` pieces of evidence.
+    pub fn new(max_evidence: usize) -> Self {
+        Self::from_inner(EvidencePoolInner { evidence: VecDeque::new(), max_evidence, file: None })
+    }
+
+    /// Creates a pool which persists the evidence to the file at `path`, loading the most recent
+    /// `max_evidence` pieces of evidence previously stored there.
+    pub fn open(path: &Path, max_evidence: usize) -> EvidenceResult<Self> {
+        let mut evidence: VecDeque<Evidence> = read_records(path)?.into();
+        evidence.drain(..evidence.len().saturating_sub(max_evidence));
+        // Rewrite the file, dropping a partially written last record if there is one.
+        let file = rewrite_records(path, evidence.make_contiguous())?;
+        let file = EvidenceFile { path: path.to_owned(), file, n_records: evidence.len() };
+        Ok(Self::from_inner(EvidencePoolInner { evidence, max_evidence, file: Some(file) }))
+    }
+
+    fn from_inner(inner: EvidencePoolInner) -> Self {
+        Self { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Adds evidence to the pool, persisting it if the pool is backed by a file. Returns false,
+    /// without adding it, if the pool already holds evidence for the same voter and step.
+    ///
+    /// Failing to persist the evidence doesn't fail consensus; the evidence is kept in memory.
+    pub fn add(&self, evidence: Evidence) -> bool {
+        let mut inner = self.inner.lock().expect("Evidence pool lock poisoned.");
+        let inner = &mut *inner;
+        if inner.evidence.iter().any(|known| known.is_duplicate_of(&evidence)) {
+            return false;
+        }
+        info!("Collected equivocation evidence: {evidence:?}");
+        CONSENSUS_EQUIVOCATION_EVIDENCE.increment(1);
+        if let Some(file) = &mut inner.file {
+            if let Err(e) = file.append(&evidence) {
+                warn!("Failed to persist equivocation evidence to {:?}: {e}", file.path);
+            }
+        }
+        inner.evidence.push_back(evidence);
+        if inner.evidence.len() > inner.max_evidence {
+            inner.evidence.pop_front();
+        }
+
+        // Compact once the file holds twice the evidence the pool does, so that the cost of
+        // rewriting it is amortized over many additions.
+        if let Some(file) = &mut inner.file {
+            if file.n_records >= 2 * inner.max_evidence.max(1) {
+                if let Err(e) = file.compact(&mut inner.evidence) {
+                    warn!("Failed to compact the equivocation evidence file {:?}: {e}", file.path);
+                }
+            }
+        }
+        true
+    }
+
+    /// All the evidence in the pool, in the order it was collected.
+    pub fn evidence(&self) -> Vec<Evidence> {
+        self.filter(|_| true)
+    }
+
+    /// The evidence of equivocations at `height`.
+    pub fn evidence_at_height(&self, height: BlockNumber) -> Vec<Evidence> {
+        self.filter(|evidence| evidence.height() == height)
+    }
+
+    /// The evidence against `voter`.
+    pub fn evidence_against(&self, voter: ValidatorId) -> Vec<Evidence> {
+        self.filter(|evidence| evidence.voter() == voter)
+    }
+
+    fn filter(&self, predicate: impl Fn(&Evidence) -> bool) -> Vec<Evidence> {
+        let inner = self.inner.lock().expect("Evidence pool lock poisoned.");
+        inner.evidence.iter().filter(|evidence| predicate(evidence)).cloned().collect()
+    }
+}
@@ -0,0 +1,107 @@
+use apollo_protobuf::consensus::DEFAULT_VALIDATOR_ID;
+use starknet_api::block::BlockNumber;
+use starknet_types_core::felt::Felt;
+use tempfile::TempDir;
+
+use crate::evidence::{Evidence, EvidencePool, DEFAULT_MAX_EVIDENCE};
+use crate::test_utils::{precommit, prevote};
+use crate::types::ValidatorId;
+
+fn evidence(height: u64, voter: ValidatorId) -> Evidence {
+    Evidence::from_conflicting_votes(
+        prevote(Some(Felt::ONE), height, 0, voter),
+        prevote(Some(Felt::TWO), height, 0, voter),
+    )
+    .unwrap()
+}
+
+#[test]
+fn only_conflicting_votes_are_evidence() {
+    let voter = DEFAULT_VALIDATOR_ID.into();
+    let vote = prevote(Some(Felt::ONE), 1, 0, voter);
+    assert!(Evidence::from_conflicting_votes(vote.clone(), vote.clone()).is_none());
+    for other in [
+        precommit(Some(Felt::TWO), 1, 0, voter),
+        prevote(Some(Felt::TWO), 2, 0, voter),
+        prevote(Some(Felt::TWO), 1, 1, voter),
+        prevote(Some(Felt::TWO), 1, 0, (DEFAULT_VALIDATOR_ID + 1).into()),
+    ] {
+        assert!(Evidence::from_conflicting_votes(vote.clone(), other).is_none());
+    }
+    // A nil vote conflicts with a vote for a proposal.
+    assert!(Evidence::from_conflicting_votes(vote, prevote(None, 1, 0, voter)).is_some());
+}
+
+#[test]
+fn pool_ignores_duplicate_evidence() {
+    let voter = DEFAULT_VALIDATOR_ID.into();
+    let pool = EvidencePool::default();
+    assert!(pool.add(evidence(1, voter)));
+    // Another equivocation by the same voter in the same step.
+    let duplicate = Evidence::from_conflicting_votes(
+        prevote(Some(Felt::ONE), 1, 0, voter),
+        prevote(None, 1, 0, voter),
+    )
+    .unwrap();
+    assert!(!pool.add(duplicate));
+    assert_eq!(pool.evidence(), vec![evidence(1, voter)]);
+}
+
+#[test]
+fn query_evidence() {
+    let voter_1 = DEFAULT_VALIDATOR_ID.into();
+    let voter_2 = (DEFAULT_VALIDATOR_ID + 1).into();
+    let pool = EvidencePool::default();
+    for evidence in [evidence(1, voter_1), evidence(1, voter_2), evidence(2, voter_1)] {
+        assert!(pool.add(evidence));
+    }
+
+    assert_eq!(pool.evidence().len(), 3);
+    assert_eq!(
+        pool.evidence_at_height(BlockNumber(1)),
+        vec![evidence(1, voter_1), evidence(1, voter_2)]
+    );
+    assert_eq!(pool.evidence_against(voter_1), vec![evidence(1, voter_1), evidence(2, voter_1)]);
+    assert!(pool.evidence_at_height(BlockNumber(3)).is_empty());
+}
+
+#[test]
+fn evidence_survives_reopening() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("evidence");
+    let voter = DEFAULT_VALIDATOR_ID.into();
+
+    let pool = EvidencePool::open(&path, DEFAULT_MAX_EVIDENCE).unwrap();
+    pool.add(evidence(1, voter));
+    drop(pool);
+
+    let pool = EvidencePool::open(&path, DEFAULT_MAX_EVIDENCE).unwrap();
+    assert_eq!(pool.evidence(), vec![evidence(1, voter)]);
+    assert!(!pool.add(evidence(1, voter)));
+    pool.add(evidence(2, voter));
+    drop(pool);
+
+    let pool = EvidencePool::open(&path, DEFAULT_MAX_EVIDENCE).unwrap();
+    assert_eq!(pool.evidence(), vec![evidence(1, voter), evidence(2, voter)]);
+}
+
+#[test]
+fn pool_keeps_the_most_recent_evidence() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("evidence");
+    let voter = DEFAULT_VALIDATOR_ID.into();
+
+    let pool = EvidencePool::open(&path, 2).unwrap();
+    for height in 1..=5 {
+        assert!(pool.add(evidence(height, voter)));
+    }
+    assert_eq!(pool.evidence(), vec![evidence(4, voter), evidence(5, voter)]);
+    drop(pool);
+
+    // The file is compacted, and only the most recent evidence is loaded.
+    assert!(std::fs::read_to_string(&path).unwrap().lines().count() < 5);
+    let pool = EvidencePool::open(&path, 2).unwrap();
+    assert_eq!(pool.evidence(), vec![evidence(4, voter), evidence(5, voter)]);
+    let pool = EvidencePool::open(&path, 1).unwrap();
+    assert_eq!(pool.evidence(), vec![evidence(5, voter)]);
+}
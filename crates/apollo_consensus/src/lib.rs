@@ -29,6 +29,8 @@ pub mod config;
 pub mod types;
 pub use manager::{run_consensus, RunConsensusArguments};
 #[allow(missing_docs)]
+pub mod evidence;
+#[allow(missing_docs)]
 pub mod metrics;
 #[allow(missing_docs)]
 pub mod simulation_network_receiver;
@@ -41,6 +43,10 @@ mod single_height_consensus;
 mod state_machine;
 #[allow(missing_docs)]
 pub mod votes_threshold;
+#[allow(missing_docs)]
+pub mod wal;
 
+#[cfg(test)]
+pub(crate) mod simulator;
 #[cfg(test)]
 pub(crate) mod test_utils;
@@ -10,13 +10,15 @@
 mod manager_test;
 
 use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 use apollo_network::network_manager::BroadcastTopicClientTrait;
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_protobuf::consensus::{ProposalInit, Vote};
 use apollo_protobuf::converters::ProtobufConversionError;
-use apollo_time::time::{sleep_until, Clock, DefaultClock};
+use apollo_time::time::{sleep_until, Clock};
 use futures::channel::mpsc;
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt};
@@ -24,6 +26,7 @@ use starknet_api::block::BlockNumber;
 use tracing::{debug, error, info, instrument, trace};
 
 use crate::config::TimeoutsConfig;
+use crate::evidence::EvidencePool;
 use crate::metrics::{
     register_metrics,
     CONSENSUS_BLOCK_NUMBER,
@@ -36,6 +39,7 @@ use crate::metrics::{
 use crate::single_height_consensus::{ShcReturn, SingleHeightConsensus};
 use crate::types::{BroadcastVoteChannel, ConsensusContext, ConsensusError, Decision, ValidatorId};
 use crate::votes_threshold::QuorumType;
+use crate::wal::ConsensusWal;
 
 /// Arguments for running consensus.
 #[derive(Clone, Debug)]
@@ -54,6 +58,13 @@ pub struct RunConsensusArguments {
     pub sync_retry_interval: Duration,
     /// Set to Byzantine by default. Using Honest means we trust all validators. Use with caution!
     pub quorum_type: QuorumType,
+    /// Path of the write-ahead log, used to resume a height with the same commitments after a
+    /// restart. If None, the node's commitments aren't persisted.
+    pub wal_path: Option<PathBuf>,
+    /// Collects evidence of validators equivocating. Shared with the node, which can query it.
+    pub evidence_pool: EvidencePool,
+    /// The clock used to schedule sync retries.
+    pub clock: Arc<dyn Clock>,
 }
 
 /// Run consensus indefinitely.
@@ -91,6 +102,9 @@ where
         run_consensus_args.sync_retry_interval,
         run_consensus_args.quorum_type,
         run_consensus_args.timeouts,
+        run_consensus_args.wal_path,
+        run_consensus_args.evidence_pool,
+        run_consensus_args.clock,
     );
     loop {
         let must_observer = current_height < run_consensus_args.start_active_height;
@@ -139,7 +153,7 @@ type ProposalReceiverTuple<T> = (ProposalInit, mpsc::Receiver<T>);
 
 /// Runs Tendermint repeatedly across different heights. Handles issues which are not explicitly
 /// part of the single height consensus algorithm (e.g. messages from future heights).
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct MultiHeightManager<ContextT: ConsensusContext> {
     validator_id: ValidatorId,
     future_votes: BTreeMap<u64, Vec<Vote>>,
@@ -148,6 +162,9 @@ struct MultiHeightManager<ContextT: ConsensusContext> {
     // Mapping: { Height : { Round : (Init, Receiver)}}
     cached_proposals: BTreeMap<u64, BTreeMap<u32, ProposalReceiverTuple<ContextT::ProposalPart>>>,
     timeouts: TimeoutsConfig,
+    wal_path: Option<PathBuf>,
+    evidence_pool: EvidencePool,
+    clock: Arc<dyn Clock>,
 }
 
 impl<ContextT: ConsensusContext> MultiHeightManager<ContextT> {
@@ -157,6 +174,9 @@ impl<ContextT: ConsensusContext> MultiHeightManager<ContextT> {
         sync_retry_interval: Duration,
         quorum_type: QuorumType,
         timeouts: TimeoutsConfig,
+        wal_path: Option<PathBuf>,
+        evidence_pool: EvidencePool,
+        clock: Arc<dyn Clock>,
     ) -> Self {
         Self {
             validator_id,
@@ -165,6 +185,9 @@ impl<ContextT: ConsensusContext> MultiHeightManager<ContextT> {
             future_votes: BTreeMap::new(),
             cached_proposals: BTreeMap::new(),
             timeouts,
+            wal_path,
+            evidence_pool,
+            clock,
         }
     }
 
@@ -241,14 +264,19 @@ impl<ContextT: ConsensusContext> MultiHeightManager<ContextT> {
             return Ok(RunHeightRes::Sync);
         }
 
-        let validators = context.validators(height).await;
-        let is_observer = must_observer || !validators.contains(&self.validator_id);
+        let validators = context.validators(height).await?;
+        let is_observer = must_observer || !validators.contains_key(&self.validator_id);
         info!(
             "START_HEIGHT: running consensus for height {:?}. is_observer: {}, validators: {:?}",
             height, is_observer, validators,
         );
         CONSENSUS_BLOCK_NUMBER.set_lossy(height.0);
 
+        // Observers make no commitments, so have nothing to persist.
+        let wal = match &self.wal_path {
+            Some(wal_path) if !is_observer => Some(ConsensusWal::open(wal_path, height)?),
+            _ => None,
+        };
         let mut shc = SingleHeightConsensus::new(
             height,
             is_observer,
@@ -256,6 +284,8 @@ impl<ContextT: ConsensusContext> MultiHeightManager<ContextT> {
             validators,
             self.quorum_type,
             self.timeouts.clone(),
+            wal,
+            self.evidence_pool.clone(),
         );
         let mut shc_events = FuturesUnordered::new();
 
@@ -271,8 +301,7 @@ impl<ContextT: ConsensusContext> MultiHeightManager<ContextT> {
         }
 
         // Loop over incoming proposals, messages, and self generated events.
-        let clock = DefaultClock;
-        let mut sync_poll_deadline = clock.now() + self.sync_retry_interval;
+        let mut sync_poll_deadline = self.clock.now() + self.sync_retry_interval;
         loop {
             self.report_max_cached_block_number_metric(height);
             let shc_return = tokio::select! {
@@ -288,7 +317,7 @@ impl<ContextT: ConsensusContext> MultiHeightManager<ContextT> {
                 },
                 // Using sleep_until to make sure that we won't restart the sleep due to other
                 // events occuring.
-                _ = sleep_until(sync_poll_deadline, &clock) => {
+                _ = sleep_until(sync_poll_deadline, self.clock.as_ref()) => {
                     sync_poll_deadline += self.sync_retry_interval;
                     if context.try_sync(height).await {
                         return Ok(RunHeightRes::Sync);
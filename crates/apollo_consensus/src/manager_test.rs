@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 use std::vec;
 
@@ -9,6 +10,7 @@ use apollo_network::network_manager::test_utils::{
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_protobuf::consensus::{Vote, DEFAULT_VALIDATOR_ID};
 use apollo_test_utils::{get_rng, GetTestInstance};
+use apollo_time::time::DefaultClock;
 use futures::channel::{mpsc, oneshot};
 use futures::{FutureExt, SinkExt};
 use lazy_static::lazy_static;
@@ -17,8 +19,16 @@ use starknet_types_core::felt::Felt;
 
 use super::{run_consensus, MultiHeightManager, RunHeightRes};
 use crate::config::TimeoutsConfig;
-use crate::test_utils::{precommit, prevote, proposal_init, MockTestContext, TestProposalPart};
-use crate::types::ValidatorId;
+use crate::evidence::EvidencePool;
+use crate::test_utils::{
+    expect_vote_signing,
+    precommit,
+    prevote,
+    proposal_init,
+    MockTestContext,
+    TestProposalPart,
+};
+use crate::types::{ValidatorId, ValidatorSet};
 use crate::votes_threshold::QuorumType;
 use crate::RunConsensusArguments;
 
@@ -99,10 +109,13 @@ async fn manager_multiple_heights_unordered() {
     send(&mut sender, precommit(Some(Felt::ONE), 1, 0, *PROPOSER_ID)).await;
 
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
     // Run the manager for height 1.
     context.expect_try_sync().returning(|_| false);
     expect_validate_proposal(&mut context, Felt::ONE, 1);
-    context.expect_validators().returning(move |_| vec![*PROPOSER_ID, *VALIDATOR_ID]);
+    context
+        .expect_validators()
+        .returning(move |_| Ok(ValidatorSet::from([(*PROPOSER_ID, 1), (*VALIDATOR_ID, 1)])));
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
     context.expect_set_height_and_round().returning(move |_, _| ());
     context.expect_broadcast().returning(move |_| Ok(()));
@@ -112,6 +125,9 @@ async fn manager_multiple_heights_unordered() {
         SYNC_RETRY_INTERVAL,
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
+        Arc::new(DefaultClock),
     );
     let mut subscriber_channels = subscriber_channels.into();
     let decision = manager
@@ -145,12 +161,15 @@ async fn manager_multiple_heights_unordered() {
 async fn run_consensus_sync() {
     // Set expectations.
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
     let (decision_tx, decision_rx) = oneshot::channel();
 
     let (mut proposal_receiver_sender, proposal_receiver_receiver) = mpsc::channel(CHANNEL_SIZE);
 
     expect_validate_proposal(&mut context, Felt::TWO, 1);
-    context.expect_validators().returning(move |_| vec![*PROPOSER_ID, *VALIDATOR_ID]);
+    context
+        .expect_validators()
+        .returning(move |_| Ok(ValidatorSet::from([(*PROPOSER_ID, 1), (*VALIDATOR_ID, 1)])));
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
     context.expect_set_height_and_round().returning(move |_, _| ());
     context.expect_broadcast().returning(move |_| Ok(()));
@@ -187,6 +206,9 @@ async fn run_consensus_sync() {
         timeouts: TIMEOUTS.clone(),
         sync_retry_interval: SYNC_RETRY_INTERVAL,
         quorum_type: QuorumType::Byzantine,
+        wal_path: None,
+        evidence_pool: EvidencePool::default(),
+        clock: Arc::new(DefaultClock),
     };
     // Start at height 1.
     tokio::spawn(async move {
@@ -223,11 +245,17 @@ async fn test_timeouts() {
     send(&mut sender, precommit(None, 1, 0, *VALIDATOR_ID_3)).await;
 
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
     context.expect_set_height_and_round().returning(move |_, _| ());
     expect_validate_proposal(&mut context, Felt::ONE, 2);
-    context
-        .expect_validators()
-        .returning(move |_| vec![*PROPOSER_ID, *VALIDATOR_ID, *VALIDATOR_ID_2, *VALIDATOR_ID_3]);
+    context.expect_validators().returning(move |_| {
+        Ok(ValidatorSet::from([
+            (*PROPOSER_ID, 1),
+            (*VALIDATOR_ID, 1),
+            (*VALIDATOR_ID_2, 1),
+            (*VALIDATOR_ID_3, 1),
+        ]))
+    });
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
     context.expect_try_sync().returning(|_| false);
 
@@ -248,6 +276,9 @@ async fn test_timeouts() {
         SYNC_RETRY_INTERVAL,
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
+        Arc::new(DefaultClock),
     );
     let manager_handle = tokio::spawn(async move {
         let decision = manager
@@ -286,6 +317,7 @@ async fn timely_message_handling() {
     // TODO(matan): Make run_height more generic so don't need mock network?
     // Check that, even when sync is immediately ready, consensus still handles queued messages.
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
     context.expect_try_sync().returning(|_| true);
 
     // Send messages
@@ -309,6 +341,9 @@ async fn timely_message_handling() {
         SYNC_RETRY_INTERVAL,
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
+        Arc::new(DefaultClock),
     );
     let res = manager
         .run_height(
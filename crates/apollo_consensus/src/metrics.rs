@@ -22,12 +22,14 @@ define_metrics!(
         MetricCounter { CONSENSUS_OUTBOUND_STREAM_STARTED, "consensus_outbound_stream_started", "The total number of outbound streams started", init=0 },
         MetricCounter { CONSENSUS_OUTBOUND_STREAM_FINISHED, "consensus_outbound_stream_finished", "The total number of outbound streams finished", init=0 },
         MetricCounter { CONSENSUS_INBOUND_STREAM_STARTED, "consensus_inbound_stream_started", "The total number of inbound streams started", init=0 },
-        MetricCounter { CONSENSUS_INBOUND_STREAM_EVICTED, "consensus_inbound_stream_evicted", "The total number of inbound streams evicted due to cache capacity", init=0 },
         MetricCounter { CONSENSUS_INBOUND_STREAM_FINISHED, "consensus_inbound_stream_finished", "The total number of inbound streams finished", init=0 },
         // TODO(Matan): remove this metric.
         MetricCounter { CONSENSUS_ROUND_ABOVE_ZERO, "consensus_round_above_zero", "The number of times the consensus round has increased above zero", init=0 },
         MetricCounter { CONSENSUS_CONFLICTING_VOTES, "consensus_conflicting_votes", "The number of times consensus has received conflicting votes", init=0 },
+        MetricCounter { CONSENSUS_EQUIVOCATION_EVIDENCE, "consensus_equivocation_evidence", "The number of distinct equivocations for which evidence was collected", init=0 },
+        MetricCounter { CONSENSUS_INVALID_VOTE_SIGNATURES, "consensus_invalid_vote_signatures", "The number of votes dropped due to a missing or invalid signature", init=0 },
         LabeledMetricCounter { CONSENSUS_TIMEOUTS, "consensus_timeouts", "The number of times consensus has timed out", init=0, labels = CONSENSUS_TIMEOUT_LABELS },
+        LabeledMetricCounter { CONSENSUS_INBOUND_STREAM_EVICTED, "consensus_inbound_stream_evicted", "The total number of inbound streams evicted, by reason", init=0, labels = CONSENSUS_STREAM_EVICTION_LABELS },
     },
 );
 
@@ -46,6 +48,35 @@ generate_permutation_labels! {
     (LABEL_NAME_TIMEOUT_REASON, TimeoutReason),
 }
 
+pub const LABEL_NAME_EVICTION_REASON: &str = "eviction_reason";
+
+#[derive(Clone, Copy, Debug, IntoStaticStr, EnumIter, EnumVariantNames)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum StreamEvictionReason {
+    /// Too many streams are open.
+    MaxStreams,
+    /// A message skipped too far ahead of the next expected message.
+    MessageGap,
+    /// The stream is too large.
+    StreamSize,
+    /// The peer has too many bytes buffered.
+    PeerBufferSize,
+    /// All peers together have too many bytes buffered.
+    TotalBufferSize,
+}
+
+impl StreamEvictionReason {
+    /// Whether the limit was exceeded by a single peer, which should therefore be reported.
+    pub(crate) fn is_peer_fault(&self) -> bool {
+        matches!(self, Self::MessageGap | Self::StreamSize | Self::PeerBufferSize)
+    }
+}
+
+generate_permutation_labels! {
+    CONSENSUS_STREAM_EVICTION_LABELS,
+    (LABEL_NAME_EVICTION_REASON, StreamEvictionReason),
+}
+
 pub(crate) fn register_metrics() {
     CONSENSUS_BLOCK_NUMBER.register();
     CONSENSUS_ROUND.register();
@@ -69,5 +100,7 @@ pub(crate) fn register_metrics() {
     CONSENSUS_OUTBOUND_STREAM_FINISHED.register();
     CONSENSUS_ROUND_ABOVE_ZERO.register();
     CONSENSUS_CONFLICTING_VOTES.register();
+    CONSENSUS_EQUIVOCATION_EVIDENCE.register();
+    CONSENSUS_INVALID_VOTE_SIGNATURES.register();
     CONSENSUS_TIMEOUTS.register();
 }
@@ -0,0 +1,742 @@
+//! In-process simulation of a network of consensus nodes, used to test consensus under faults.
+//!
+//! Each node runs [`run_consensus`] against a [`SimulatedContext`], and all nodes share a simulated
+//! network. Time is virtual (the simulation must run on a paused tokio runtime), so long scenarios
+//! complete instantly.
+//!
+//! Faults are scheduled on the virtual timeline: network partitions, extra delays and crashes. Some
+//! nodes can also be made byzantine. Like
+//! [`NetworkReceiver`](crate::simulation_network_receiver::NetworkReceiver), all random choices
+//! (message drops and delays) are derived from the seed and the message itself, rather than from a
+//! shared random generator. This makes them indifferent to the order in which the nodes' tasks
+//! happen to run, so a failing scenario can be reproduced from its seed.
+//!
+//! The nodes choose between their ready inputs at random (`tokio::select!`). To keep runs
+//! reproducible, messages are delivered one at a time, in an order derived from the seed, and only
+//! once the nodes handled all the events already due. This way a node never has more than one
+//! message ready to choose from.
+//!
+//! At the end of the simulation, [`SimulationResult`] is used to check safety (no two different
+//! decisions for the same height) and liveness (every live honest node reached the target height).
+
+#[cfg(test)]
+#[path = "simulator_test.rs"]
+mod simulator_test;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use apollo_network::network_manager::test_utils::mock_register_broadcast_topic;
+use apollo_network_types::network_types::{BroadcastedMessageMetadata, OpaquePeerId};
+use apollo_network_types::test_utils::get_peer_id;
+use apollo_protobuf::consensus::{ProposalInit, Vote, DEFAULT_VALIDATOR_ID};
+use apollo_protobuf::converters::ProtobufConversionError;
+use apollo_time::time::{Clock, DateTime};
+use async_trait::async_trait;
+use futures::channel::{mpsc, oneshot};
+use futures::StreamExt;
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_types_core::felt::Felt;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::config::TimeoutsConfig;
+use crate::evidence::{Evidence, EvidencePool};
+use crate::types::{
+    BroadcastVoteChannel,
+    ConsensusContext,
+    ConsensusError,
+    ProposalCommitment,
+    Round,
+    ValidatorId,
+    ValidatorSet,
+};
+use crate::votes_threshold::QuorumType;
+use crate::{run_consensus, RunConsensusArguments};
+
+/// Index of a node in the simulation. The node's validator ID is `DEFAULT_VALIDATOR_ID + index`.
+pub(crate) type NodeIndex = usize;
+
+const CHANNEL_SIZE: usize = 1000;
+// How often the simulation checks whether all nodes reached the target height.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+// The number of scheduling rounds after which the nodes are assumed to have handled all the events
+// already due. Handling an event involves at most a few tasks waking each other, without waiting
+// for time to pass.
+const SETTLE_ROUNDS: usize = 16;
+
+/// How a byzantine node deviates from the protocol.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ByzantineBehavior {
+    /// Sends every vote together with a conflicting vote for the same step.
+    DoubleVote,
+    /// Sends nothing.
+    Silent,
+    /// Proposes blocks which honest nodes consider invalid.
+    WrongProposal,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Fault {
+    /// Messages between nodes in different groups are dropped. Nodes not listed in any group are
+    /// isolated.
+    Partition(Vec<Vec<NodeIndex>>),
+    /// Messages sent by the node are delayed by an additional duration.
+    Delay(NodeIndex, Duration),
+    /// The node stops running. When the fault ends, the node restarts from the height after the
+    /// last one it learned of, like a restarted sequencer does.
+    Crash(NodeIndex),
+}
+
+/// A fault which is active from `start` until `end` (forever if None), measured from the beginning
+/// of the simulation.
+#[derive(Clone, Debug)]
+pub(crate) struct ScheduledFault {
+    pub fault: Fault,
+    pub start: Duration,
+    pub end: Option<Duration>,
+}
+
+impl ScheduledFault {
+    fn is_active(&self, elapsed: Duration) -> bool {
+        self.start <= elapsed && self.end.is_none_or(|end| elapsed < end)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct SimulationConfig {
+    pub seed: u64,
+    pub num_nodes: usize,
+    /// The simulation ends successfully once all live honest nodes learned of this many heights.
+    pub num_heights: u64,
+    pub timeouts: TimeoutsConfig,
+    pub sync_retry_interval: Duration,
+    /// Each message is delayed by a duration in [min_delay, max_delay].
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+    /// Probability of dropping each message [0, 1].
+    pub drop_probability: f64,
+    pub byzantine: BTreeMap<NodeIndex, ByzantineBehavior>,
+    pub faults: Vec<ScheduledFault>,
+    /// The simulation ends after this much virtual time, even if not all heights were reached.
+    pub time_limit: Duration,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            num_nodes: 4,
+            num_heights: 5,
+            timeouts: TimeoutsConfig {
+                proposal_timeout: Duration::from_millis(500),
+                prevote_timeout: Duration::from_millis(200),
+                precommit_timeout: Duration::from_millis(200),
+            },
+            sync_retry_interval: Duration::from_millis(100),
+            min_delay: Duration::from_millis(5),
+            max_delay: Duration::from_millis(50),
+            drop_probability: 0.0,
+            byzantine: BTreeMap::new(),
+            faults: Vec::new(),
+            time_limit: Duration::from_secs(60),
+        }
+    }
+}
+
+pub(crate) fn validator_id(node: NodeIndex) -> ValidatorId {
+    (DEFAULT_VALIDATOR_ID + u64::try_from(node).unwrap()).into()
+}
+
+/// The block which honest nodes propose (or accept) for the given height and round.
+fn valid_block(height: BlockNumber, round: Round) -> ProposalCommitment {
+    BlockHash(Felt::from(height.0) * Felt::from(1000) + Felt::from(round) + Felt::ONE)
+}
+
+// A block which honest nodes reject.
+fn invalid_block(height: BlockNumber, round: Round) -> ProposalCommitment {
+    BlockHash(-valid_block(height, round).0)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum SimulatedProposalPart {
+    Init(ProposalInit),
+    Fin(ProposalCommitment),
+}
+
+impl From<ProposalInit> for SimulatedProposalPart {
+    fn from(init: ProposalInit) -> Self {
+        Self::Init(init)
+    }
+}
+
+impl TryFrom<SimulatedProposalPart> for ProposalInit {
+    type Error = ProtobufConversionError;
+    fn try_from(part: SimulatedProposalPart) -> Result<Self, Self::Error> {
+        match part {
+            SimulatedProposalPart::Init(init) => Ok(init),
+            _ => Err(ProtobufConversionError::WrongEnumVariant {
+                type_description: "SimulatedProposalPart",
+                expected: "Init",
+                value_as_str: format!("{part:?}"),
+            }),
+        }
+    }
+}
+
+impl From<SimulatedProposalPart> for Vec<u8> {
+    fn from(part: SimulatedProposalPart) -> Vec<u8> {
+        match part {
+            SimulatedProposalPart::Init(init) => [vec![0], init.into()].concat(),
+            SimulatedProposalPart::Fin(block) => [vec![1], block.0.to_bytes_be().to_vec()].concat(),
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for SimulatedProposalPart {
+    type Error = ProtobufConversionError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        match value.split_first() {
+            Some((0, init)) => Ok(Self::Init(init.to_vec().try_into()?)),
+            Some((1, block)) => Ok(Self::Fin(BlockHash(Felt::from_bytes_be_slice(block)))),
+            _ => Err(ProtobufConversionError::BytesDataLengthMismatch {
+                type_description: "SimulatedProposalPart",
+                num_expected: 1,
+                value: value.clone(),
+            }),
+        }
+    }
+}
+
+// The channels through which the network delivers messages to a running node.
+#[derive(Clone)]
+struct NodeInbox {
+    votes: mpsc::Sender<(Result<Vote, ProtobufConversionError>, BroadcastedMessageMetadata)>,
+    proposals: mpsc::Sender<mpsc::Receiver<SimulatedProposalPart>>,
+}
+
+enum Message {
+    Vote(Vote),
+    Proposal(ProposalInit, ProposalCommitment),
+}
+
+#[derive(Default)]
+struct Ledger {
+    // Blocks decided by consensus, per height and node.
+    decisions: BTreeMap<BlockNumber, BTreeMap<NodeIndex, ProposalCommitment>>,
+    // The heights each node learned of via sync.
+    synced: BTreeMap<NodeIndex, BTreeSet<BlockNumber>>,
+    // The next height each node needs to learn of.
+    next_height: BTreeMap<NodeIndex, BlockNumber>,
+    evidence: Vec<(NodeIndex, Evidence)>,
+    errors: Vec<(NodeIndex, ConsensusError)>,
+}
+
+// A message waiting to be delivered, keyed by its delivery time, recipient and a random tie
+// breaker.
+type PendingDeliveries = BTreeMap<(Instant, NodeIndex, u64), (NodeIndex, Message)>;
+
+// State shared by all the nodes in the simulation.
+struct SimulationState {
+    config: SimulationConfig,
+    start: Instant,
+    inboxes: Mutex<Vec<Option<NodeInbox>>>,
+    pending_deliveries: Mutex<PendingDeliveries>,
+    // Notified when a message is sent, so its delivery can be scheduled.
+    message_sent: Notify,
+    // How many times each message was sent, so that resends are treated as new messages.
+    send_counts: Mutex<HashMap<u64, u32>>,
+    ledger: Mutex<Ledger>,
+}
+
+impl SimulationState {
+    fn elapsed(&self) -> Duration {
+        Instant::now() - self.start
+    }
+
+    fn active_faults(&self) -> impl Iterator<Item = &Fault> {
+        let elapsed = self.elapsed();
+        self.config.faults.iter().filter(move |f| f.is_active(elapsed)).map(|f| &f.fault)
+    }
+
+    fn is_honest(&self, node: NodeIndex) -> bool {
+        !self.config.byzantine.contains_key(&node)
+    }
+
+    fn is_crashed(&self, node: NodeIndex) -> bool {
+        self.active_faults().any(|fault| matches!(fault, Fault::Crash(n) if *n == node))
+    }
+
+    // Nodes which crash without restarting aren't expected to make progress.
+    fn never_restarts(&self, node: NodeIndex) -> bool {
+        self.config
+            .faults
+            .iter()
+            .any(|f| f.end.is_none() && matches!(f.fault, Fault::Crash(n) if n == node))
+    }
+
+    fn are_connected(&self, from: NodeIndex, to: NodeIndex) -> bool {
+        self.active_faults().all(|fault| match fault {
+            Fault::Partition(groups) => {
+                groups.iter().any(|group| group.contains(&from) && group.contains(&to))
+            }
+            _ => true,
+        })
+    }
+
+    // A pseudo random number, derived from the seed and `key`, and the number of times `key` was
+    // used before.
+    fn random(&self, key: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let key_hash = hasher.finish();
+        let mut send_counts = self.send_counts.lock().unwrap();
+        let count = send_counts.entry(key_hash).or_default();
+        *count += 1;
+
+        let mut hasher = DefaultHasher::new();
+        self.config.seed.hash(&mut hasher);
+        key_hash.hash(&mut hasher);
+        count.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Sends a message from one node to another, applying the network conditions.
+    fn send(&self, from: NodeIndex, to: NodeIndex, message: Message) {
+        if !self.are_connected(from, to) {
+            return;
+        }
+        let random = match &message {
+            Message::Vote(vote) => self.random((from, to, vote)),
+            Message::Proposal(init, block) => {
+                self.random((from, to, init.height, init.round, init.valid_round, block))
+            }
+        };
+        // Use independent bits for deciding whether to drop the message and for its delay.
+        let to_unit_interval =
+            |bits: u64| f64::from(u32::try_from(bits).unwrap()) / f64::from(u32::MAX);
+        if to_unit_interval(random & u64::from(u32::MAX)) < self.config.drop_probability {
+            return;
+        }
+        let delay_range = self.config.max_delay - self.config.min_delay;
+        let mut delay = self.config.min_delay + delay_range.mul_f64(to_unit_interval(random >> 32));
+        for fault in self.active_faults() {
+            if let Fault::Delay(node, extra_delay) = fault {
+                if *node == from {
+                    delay += *extra_delay;
+                }
+            }
+        }
+
+        self.pending_deliveries
+            .lock()
+            .unwrap()
+            .insert((Instant::now() + delay, to, random), (from, message));
+        self.message_sent.notify_one();
+    }
+
+    // Delivers the sent messages when they are due, one at a time. Runs until aborted.
+    async fn deliver_messages(self: Arc<Self>) {
+        loop {
+            let message_sent = self.message_sent.notified();
+            let next_delivery_time =
+                self.pending_deliveries.lock().unwrap().first_key_value().map(|(key, _)| key.0);
+            match next_delivery_time {
+                Some(delivery_time) if delivery_time <= Instant::now() => {
+                    settle().await;
+                    let ((_, to, _), (from, message)) =
+                        self.pending_deliveries.lock().unwrap().pop_first().unwrap();
+                    self.deliver(from, to, message);
+                }
+                Some(delivery_time) => {
+                    // Biased, so a message sent at the delivery time is scheduled before
+                    // delivering. The simulation itself must be deterministic.
+                    tokio::select! {
+                        biased;
+                        _ = message_sent => {}
+                        _ = tokio::time::sleep_until(delivery_time) => {}
+                    }
+                }
+                None => message_sent.await,
+            }
+        }
+    }
+
+    fn deliver(&self, from: NodeIndex, to: NodeIndex, message: Message) {
+        // Messages are delivered to the node currently running, which may have restarted since the
+        // message was sent.
+        let Some(mut inbox) = self.inboxes.lock().unwrap()[to].clone() else {
+            return;
+        };
+        // Sending fails if the node crashed in the meantime.
+        match message {
+            Message::Vote(vote) => {
+                let metadata = BroadcastedMessageMetadata {
+                    originator_id: OpaquePeerId::private_new(get_peer_id(
+                        u8::try_from(from).unwrap(),
+                    )),
+                    encoded_message_length: 0,
+                };
+                let _ = inbox.votes.try_send((Ok(vote), metadata));
+            }
+            Message::Proposal(init, block) => {
+                let (mut content_sender, content_receiver) = mpsc::channel(2);
+                content_sender.try_send(SimulatedProposalPart::Init(init)).unwrap();
+                content_sender.try_send(SimulatedProposalPart::Fin(block)).unwrap();
+                let _ = inbox.proposals.try_send(content_receiver);
+            }
+        }
+    }
+
+    fn send_to_all(&self, from: NodeIndex, message: impl Fn() -> Message) {
+        for to in (0..self.config.num_nodes).filter(|to| *to != from) {
+            self.send(from, to, message());
+        }
+    }
+}
+
+// Lets the nodes handle the events which are already due.
+async fn settle() {
+    for _ in 0..SETTLE_ROUNDS {
+        tokio::task::yield_now().await;
+    }
+}
+
+// A clock which follows tokio's (virtual) time, unlike the default clock which follows the
+// system's.
+#[derive(Debug)]
+struct VirtualClock {
+    start: Instant,
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> DateTime {
+        DateTime::UNIX_EPOCH + (Instant::now() - self.start)
+    }
+}
+
+/// A [`ConsensusContext`] which builds and validates placeholder blocks, and communicates through
+/// the simulated network.
+pub(crate) struct SimulatedContext {
+    node: NodeIndex,
+    byzantine: Option<ByzantineBehavior>,
+    state: Arc<SimulationState>,
+}
+
+impl SimulatedContext {
+    fn broadcast_proposal(&self, init: ProposalInit, block: ProposalCommitment) {
+        if self.byzantine == Some(ByzantineBehavior::Silent) {
+            return;
+        }
+        self.state.send_to_all(self.node, || Message::Proposal(init, block));
+    }
+}
+
+#[async_trait]
+impl ConsensusContext for SimulatedContext {
+    type ProposalPart = SimulatedProposalPart;
+
+    async fn build_proposal(
+        &mut self,
+        init: ProposalInit,
+        _timeout: Duration,
+    ) -> oneshot::Receiver<ProposalCommitment> {
+        let block = match self.byzantine {
+            Some(ByzantineBehavior::WrongProposal) => invalid_block(init.height, init.round),
+            _ => valid_block(init.height, init.round),
+        };
+        self.broadcast_proposal(init, block);
+        let (block_sender, block_receiver) = oneshot::channel();
+        block_sender.send(block).unwrap();
+        block_receiver
+    }
+
+    async fn validate_proposal(
+        &mut self,
+        init: ProposalInit,
+        timeout: Duration,
+        mut content: mpsc::Receiver<SimulatedProposalPart>,
+    ) -> oneshot::Receiver<ProposalCommitment> {
+        let (block_sender, block_receiver) = oneshot::channel();
+        // A reproposal carries the block proposed in its valid round.
+        let expected_block = valid_block(init.height, init.valid_round.unwrap_or(init.round));
+        tokio::spawn(async move {
+            let Ok(Some(SimulatedProposalPart::Fin(block))) =
+                tokio::time::timeout(timeout, content.next()).await
+            else {
+                return;
+            };
+            // Dropping the sender marks the proposal as invalid.
+            if block == expected_block {
+                let _ = block_sender.send(block);
+            }
+        });
+        block_receiver
+    }
+
+    async fn repropose(&mut self, id: ProposalCommitment, init: ProposalInit) {
+        self.broadcast_proposal(init, id);
+    }
+
+    async fn validators(&self, _height: BlockNumber) -> Result<ValidatorSet, ConsensusError> {
+        Ok((0..self.state.config.num_nodes).map(|node| (validator_id(node), 1)).collect())
+    }
+
+    fn proposer(&self, height: BlockNumber, round: Round) -> ValidatorId {
+        let num_nodes = u64::try_from(self.state.config.num_nodes).unwrap();
+        let proposer = (height.0 + u64::from(round)) % num_nodes;
+        validator_id(usize::try_from(proposer).unwrap())
+    }
+
+    async fn broadcast(&mut self, message: Vote) -> Result<(), ConsensusError> {
+        match self.byzantine {
+            Some(ByzantineBehavior::Silent) => {}
+            Some(ByzantineBehavior::DoubleVote) => {
+                let conflicting_vote = Vote {
+                    block_hash: match message.block_hash {
+                        Some(_) => None,
+                        None => Some(invalid_block(BlockNumber(message.height), message.round)),
+                    },
+                    ..message.clone()
+                };
+                self.state.send_to_all(self.node, || Message::Vote(message.clone()));
+                self.state.send_to_all(self.node, || Message::Vote(conflicting_vote.clone()));
+            }
+            _ => self.state.send_to_all(self.node, || Message::Vote(message.clone())),
+        }
+        Ok(())
+    }
+
+    async fn sign_vote(&self, vote: Vote) -> Result<Vote, ConsensusError> {
+        Ok(vote)
+    }
+
+    fn verify_vote(&self, _vote: &Vote) -> bool {
+        true
+    }
+
+    async fn decision_reached(
+        &mut self,
+        block: ProposalCommitment,
+        precommits: Vec<Vote>,
+    ) -> Result<(), ConsensusError> {
+        let height = BlockNumber(precommits[0].height);
+        info!("Node {} decided {block:?} at height {height}.", self.node);
+        let mut ledger = self.state.ledger.lock().unwrap();
+        ledger.decisions.entry(height).or_default().insert(self.node, block);
+        ledger.next_height.insert(self.node, height.unchecked_next());
+        Ok(())
+    }
+
+    async fn try_sync(&mut self, height: BlockNumber) -> bool {
+        let mut ledger = self.state.ledger.lock().unwrap();
+        // Sync only learns of blocks decided by honest nodes.
+        let decided = ledger.decisions.get(&height).is_some_and(|decisions| {
+            decisions.keys().any(|node| *node != self.node && self.state.is_honest(*node))
+        });
+        if decided {
+            ledger.synced.entry(self.node).or_default().insert(height);
+            ledger.next_height.insert(self.node, height.unchecked_next());
+        }
+        decided
+    }
+
+    async fn set_height_and_round(&mut self, _height: BlockNumber, _round: Round) {}
+
+    async fn report_evidence(&mut self, evidence: Evidence) {
+        self.state.ledger.lock().unwrap().evidence.push((self.node, evidence));
+    }
+}
+
+/// A simulation of `num_nodes` consensus nodes. Must be run on a paused tokio runtime (e.g.
+/// `#[tokio::test(start_paused = true)]`).
+pub(crate) struct Simulation {
+    state: Arc<SimulationState>,
+    nodes: Vec<Option<JoinHandle<()>>>,
+}
+
+impl Simulation {
+    pub(crate) fn new(config: SimulationConfig) -> Self {
+        assert!((0.0..=1.0).contains(&config.drop_probability));
+        assert!(config.min_delay <= config.max_delay);
+        let num_nodes = config.num_nodes;
+        let state = Arc::new(SimulationState {
+            config,
+            start: Instant::now(),
+            inboxes: Mutex::new(vec![None; num_nodes]),
+            pending_deliveries: Mutex::new(BTreeMap::new()),
+            message_sent: Notify::new(),
+            send_counts: Mutex::new(HashMap::new()),
+            ledger: Mutex::new(Ledger::default()),
+        });
+        Self { state, nodes: (0..num_nodes).map(|_| None).collect() }
+    }
+
+    /// Runs the simulation until all live honest nodes reached `num_heights`, or the time limit
+    /// passed.
+    pub(crate) async fn run(mut self) -> SimulationResult {
+        let config = self.state.config.clone();
+        info!("Running consensus simulation: {config:?}");
+        let delivery_task = tokio::spawn(Arc::clone(&self.state).deliver_messages());
+        for node in 0..config.num_nodes {
+            self.start_node(node);
+        }
+        while self.state.elapsed() < config.time_limit && !self.result().is_live() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            for node in 0..config.num_nodes {
+                match (self.state.is_crashed(node), self.nodes[node].is_some()) {
+                    (true, true) => self.stop_node(node),
+                    (false, false) => self.start_node(node),
+                    _ => {}
+                }
+            }
+        }
+        for node in 0..config.num_nodes {
+            self.stop_node(node);
+        }
+        delivery_task.abort();
+        self.result()
+    }
+
+    fn start_node(&mut self, node: NodeIndex) {
+        // Like a restarted sequencer, observe the first unknown height and only participate from
+        // the one after it, to avoid voting differently than before crashing.
+        let observe_height =
+            self.state.ledger.lock().unwrap().next_height.get(&node).copied().unwrap_or_default();
+        let active_height = if observe_height == BlockNumber(0) {
+            observe_height
+        } else {
+            observe_height.unchecked_next()
+        };
+        info!("Starting node {node} at height {observe_height}.");
+
+        let (votes_sender, votes_receiver) = mpsc::channel(CHANNEL_SIZE);
+        let (proposals_sender, proposals_receiver) = mpsc::channel(CHANNEL_SIZE);
+        self.state.inboxes.lock().unwrap()[node] =
+            Some(NodeInbox { votes: votes_sender, proposals: proposals_sender });
+        // Votes are broadcast by the context, so only the client's peer reporting is used.
+        let broadcast_topic_client =
+            mock_register_broadcast_topic().unwrap().subscriber_channels.broadcast_topic_client;
+        let vote_channel = BroadcastVoteChannel {
+            broadcasted_messages_receiver: Box::new(votes_receiver),
+            broadcast_topic_client,
+        };
+
+        let config = &self.state.config;
+        let args = RunConsensusArguments {
+            start_active_height: active_height,
+            start_observe_height: observe_height,
+            validator_id: validator_id(node),
+            consensus_delay: Duration::ZERO,
+            timeouts: config.timeouts.clone(),
+            sync_retry_interval: config.sync_retry_interval,
+            quorum_type: QuorumType::Byzantine,
+            wal_path: None,
+            evidence_pool: EvidencePool::default(),
+            clock: Arc::new(VirtualClock { start: self.state.start }),
+        };
+        let context = SimulatedContext {
+            node,
+            byzantine: config.byzantine.get(&node).copied(),
+            state: Arc::clone(&self.state),
+        };
+        let state = Arc::clone(&self.state);
+        self.nodes[node] = Some(tokio::spawn(async move {
+            if let Err(e) = run_consensus(args, context, vote_channel, proposals_receiver).await {
+                warn!("Node {node} failed: {e:?}");
+                state.ledger.lock().unwrap().errors.push((node, e));
+            }
+        }));
+    }
+
+    fn stop_node(&mut self, node: NodeIndex) {
+        if let Some(handle) = self.nodes[node].take() {
+            info!("Stopping node {node}.");
+            handle.abort();
+        }
+        self.state.inboxes.lock().unwrap()[node] = None;
+    }
+
+    fn result(&self) -> SimulationResult {
+        let ledger = self.state.ledger.lock().unwrap();
+        let config = &self.state.config;
+        SimulationResult {
+            num_heights: config.num_heights,
+            live_honest_nodes: (0..config.num_nodes)
+                .filter(|node| self.state.is_honest(*node) && !self.state.never_restarts(*node))
+                .collect(),
+            honest_nodes: (0..config.num_nodes)
+                .filter(|node| self.state.is_honest(*node))
+                .collect(),
+            decisions: ledger.decisions.clone(),
+            synced: ledger.synced.clone(),
+            next_height: ledger.next_height.clone(),
+            evidence: ledger.evidence.clone(),
+            errors: ledger.errors.iter().map(|(node, e)| (*node, e.to_string())).collect(),
+            elapsed: self.state.elapsed(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct SimulationResult {
+    pub num_heights: u64,
+    pub honest_nodes: BTreeSet<NodeIndex>,
+    /// Honest nodes which don't crash permanently.
+    pub live_honest_nodes: BTreeSet<NodeIndex>,
+    /// Blocks decided by consensus, per height and node.
+    pub decisions: BTreeMap<BlockNumber, BTreeMap<NodeIndex, ProposalCommitment>>,
+    /// The heights each node learned of via sync.
+    pub synced: BTreeMap<NodeIndex, BTreeSet<BlockNumber>>,
+    /// The next height each node needs to learn of.
+    pub next_height: BTreeMap<NodeIndex, BlockNumber>,
+    /// Evidence reported by each node.
+    pub evidence: Vec<(NodeIndex, Evidence)>,
+    /// Errors which stopped a node.
+    pub errors: Vec<(NodeIndex, String)>,
+    /// The virtual time the simulation ran for.
+    pub elapsed: Duration,
+}
+
+impl SimulationResult {
+    /// Whether all live honest nodes learned of the first `num_heights` heights.
+    pub fn is_live(&self) -> bool {
+        self.live_honest_nodes.iter().all(|node| {
+            self.next_height.get(node).is_some_and(|height| height.0 >= self.num_heights)
+        })
+    }
+
+    /// Panics if honest nodes decided different blocks for the same height, or if a node failed.
+    pub fn assert_safety(&self) {
+        assert!(self.errors.is_empty(), "Nodes failed: {:?}", self.errors);
+        for (height, decisions) in &self.decisions {
+            let honest_blocks: BTreeSet<_> = decisions
+                .iter()
+                .filter(|(node, _)| self.honest_nodes.contains(node))
+                .map(|(_, block)| block)
+                .collect();
+            assert!(
+                honest_blocks.len() <= 1,
+                "Conflicting decisions at height {height}: {decisions:?}"
+            );
+        }
+    }
+
+    /// Panics if not all live honest nodes learned of the first `num_heights` heights.
+    pub fn assert_liveness(&self) {
+        assert!(
+            self.is_live(),
+            "Not all live honest nodes {:?} reached height {} after {:?}: {:?}",
+            self.live_honest_nodes,
+            self.num_heights,
+            self.elapsed,
+            self.next_height
+        );
+    }
+}
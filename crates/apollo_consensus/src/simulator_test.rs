@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use test_case::test_case;
+
+use crate::simulator::{
+    validator_id,
+    ByzantineBehavior,
+    Fault,
+    ScheduledFault,
+    Simulation,
+    SimulationConfig,
+};
+
+#[tokio::test(start_paused = true)]
+async fn reaches_consensus_despite_dropped_messages() {
+    let result = Simulation::new(SimulationConfig { drop_probability: 0.1, ..Default::default() })
+        .run()
+        .await;
+
+    result.assert_safety();
+    result.assert_liveness();
+}
+
+#[tokio::test(start_paused = true)]
+async fn same_seed_same_result() {
+    let config = SimulationConfig { seed: 7, drop_probability: 0.2, ..Default::default() };
+
+    let first = Simulation::new(config.clone()).run().await;
+    let second = Simulation::new(config).run().await;
+
+    assert_eq!(first.decisions, second.decisions);
+    assert_eq!(first.synced, second.synced);
+    assert_eq!(first.elapsed, second.elapsed);
+}
+
+#[tokio::test(start_paused = true)]
+async fn recovers_after_partition_heals() {
+    let heal = Duration::from_secs(5);
+    let result = Simulation::new(SimulationConfig {
+        faults: vec![ScheduledFault {
+            fault: Fault::Partition(vec![vec![0, 1], vec![2, 3]]),
+            start: Duration::ZERO,
+            end: Some(heal),
+        }],
+        ..Default::default()
+    })
+    .run()
+    .await;
+
+    result.assert_safety();
+    result.assert_liveness();
+    // Neither side has a quorum, so nothing can be decided before the partition heals.
+    assert!(result.elapsed > heal);
+}
+
+#[tokio::test(start_paused = true)]
+async fn progresses_with_a_slow_node() {
+    let result = Simulation::new(SimulationConfig {
+        faults: vec![ScheduledFault {
+            fault: Fault::Delay(0, Duration::from_secs(1)),
+            start: Duration::ZERO,
+            end: None,
+        }],
+        ..Default::default()
+    })
+    .run()
+    .await;
+
+    result.assert_safety();
+    result.assert_liveness();
+}
+
+#[test_case(ByzantineBehavior::DoubleVote; "double_vote")]
+#[test_case(ByzantineBehavior::Silent; "silent")]
+#[test_case(ByzantineBehavior::WrongProposal; "wrong_proposal")]
+#[tokio::test(start_paused = true)]
+async fn tolerates_a_byzantine_node(behavior: ByzantineBehavior) {
+    let result = Simulation::new(SimulationConfig {
+        byzantine: BTreeMap::from([(1, behavior)]),
+        drop_probability: 0.05,
+        ..Default::default()
+    })
+    .run()
+    .await;
+
+    result.assert_safety();
+    result.assert_liveness();
+    let equivocated =
+        result.evidence.iter().any(|(_, evidence)| evidence.voter() == validator_id(1));
+    assert_eq!(equivocated, behavior == ByzantineBehavior::DoubleVote);
+    assert!(result.evidence.iter().all(|(_, evidence)| evidence.voter() == validator_id(1)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn crashed_node_catches_up_after_restart() {
+    let result = Simulation::new(SimulationConfig {
+        num_heights: 10,
+        faults: vec![ScheduledFault {
+            fault: Fault::Crash(2),
+            start: Duration::from_millis(300),
+            end: Some(Duration::from_secs(3)),
+        }],
+        ..Default::default()
+    })
+    .run()
+    .await;
+
+    result.assert_safety();
+    result.assert_liveness();
+    // The heights decided while the node was down are learned via sync.
+    assert!(!result.synced[&2].is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn no_progress_without_a_quorum() {
+    let result = Simulation::new(SimulationConfig {
+        byzantine: BTreeMap::from([(0, ByzantineBehavior::Silent), (3, ByzantineBehavior::Silent)]),
+        time_limit: Duration::from_secs(10),
+        ..Default::default()
+    })
+    .run()
+    .await;
+
+    result.assert_safety();
+    assert!(!result.is_live());
+    assert!(result.decisions.is_empty());
+}
@@ -23,10 +23,12 @@ use starknet_api::block::BlockNumber;
 use tracing::{debug, info, instrument, trace, warn};
 
 use crate::config::TimeoutsConfig;
+use crate::evidence::{Evidence, EvidencePool};
 use crate::metrics::{
     CONSENSUS_BUILD_PROPOSAL_FAILED,
     CONSENSUS_BUILD_PROPOSAL_TOTAL,
     CONSENSUS_CONFLICTING_VOTES,
+    CONSENSUS_INVALID_VOTE_SIGNATURES,
     CONSENSUS_PROPOSALS_INVALID,
     CONSENSUS_PROPOSALS_VALIDATED,
     CONSENSUS_PROPOSALS_VALID_INIT,
@@ -40,8 +42,11 @@ use crate::types::{
     ProposalCommitment,
     Round,
     ValidatorId,
+    ValidatorSet,
+    VotingWeight,
 };
 use crate::votes_threshold::QuorumType;
+use crate::wal::{ConsensusWal, WalEntry};
 
 /// The SHC can either update the manager of a decision or return tasks that should be run without
 /// blocking further calls to itself.
@@ -164,7 +169,7 @@ impl ShcTask {
 #[derive(Serialize, Deserialize)]
 pub(crate) struct SingleHeightConsensus {
     height: BlockNumber,
-    validators: Vec<ValidatorId>,
+    validators: ValidatorSet,
     id: ValidatorId,
     timeouts: TimeoutsConfig,
     state_machine: StateMachine,
@@ -173,21 +178,42 @@ pub(crate) struct SingleHeightConsensus {
     precommits: HashMap<(Round, ValidatorId), Vote>,
     last_prevote: Option<Vote>,
     last_precommit: Option<Vote>,
+    // Used to persist this node's commitments, so they survive a restart. None for observers, or
+    // if the WAL is disabled.
+    #[serde(skip)]
+    wal: Option<ConsensusWal>,
+    #[serde(skip)]
+    evidence_pool: EvidencePool,
+    // Entries written to the WAL before a restart, to be replayed on `start`.
+    wal_entries: Vec<WalEntry>,
+    // Rounds in which this node started building a proposal before a restart, along with the
+    // built proposal's commitment if building completed.
+    restored_build_proposals: HashMap<Round, Option<ProposalCommitment>>,
+    // The valid value restored from the WAL. Its content may be unknown to the context after the
+    // restart.
+    restored_valid_value_round: Option<(ProposalCommitment, Round)>,
+    // The locked and valid values most recently written to the WAL.
+    wal_locked_value_round: Option<(ProposalCommitment, Round)>,
+    wal_valid_value_round: Option<(ProposalCommitment, Round)>,
 }
 
 impl SingleHeightConsensus {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         height: BlockNumber,
         is_observer: bool,
         id: ValidatorId,
-        validators: Vec<ValidatorId>,
+        validators: ValidatorSet,
         quroum_type: QuorumType,
         timeouts: TimeoutsConfig,
+        wal: Option<(ConsensusWal, Vec<WalEntry>)>,
+        evidence_pool: EvidencePool,
     ) -> Self {
-        // TODO(matan): Use actual weights, not just `len`.
-        let n_validators =
-            u64::try_from(validators.len()).expect("Should have way less than u64::MAX validators");
-        let state_machine = StateMachine::new(id, n_validators, is_observer, quroum_type);
+        let state_machine = StateMachine::new(id, &validators, is_observer, quroum_type);
+        let (wal, wal_entries) = match wal {
+            Some((wal, wal_entries)) => (Some(wal), wal_entries),
+            None => (None, Vec::new()),
+        };
         Self {
             height,
             validators,
@@ -199,6 +225,13 @@ impl SingleHeightConsensus {
             precommits: HashMap::new(),
             last_prevote: None,
             last_precommit: None,
+            wal,
+            evidence_pool,
+            wal_entries,
+            restored_build_proposals: HashMap::new(),
+            restored_valid_value_round: None,
+            wal_locked_value_round: None,
+            wal_valid_value_round: None,
         }
     }
 
@@ -208,9 +241,15 @@ impl SingleHeightConsensus {
         context: &mut ContextT,
     ) -> Result<ShcReturn, ConsensusError> {
         context.set_height_and_round(self.height, self.state_machine.round()).await;
+        let restored_tasks = self.restore_from_wal()?;
         let leader_fn = |round: Round| -> ValidatorId { context.proposer(self.height, round) };
         let events = self.state_machine.start(&leader_fn);
-        let ret = self.handle_state_machine_events(context, events).await;
+        let ret = match self.handle_state_machine_events(context, events).await {
+            Ok(ShcReturn::Tasks(tasks)) => {
+                Ok(ShcReturn::Tasks(restored_tasks.into_iter().chain(tasks).collect()))
+            }
+            ret => ret,
+        };
         // Defensive programming. We don't expect the height and round to have changed from the
         // start of this method.
         context.set_height_and_round(self.height, self.state_machine.round()).await;
@@ -332,12 +371,20 @@ impl SingleHeightConsensus {
                 let sm_events = self.state_machine.handle_event(
                     StateMachineEvent::Proposal(proposal_id, round, valid_round),
                     &leader_fn,
-                );
+                )?;
                 self.handle_state_machine_events(context, sm_events).await
             }
             ShcEvent::BuildProposal(StateMachineEvent::GetProposal(proposal_id, round)) => {
-                if proposal_id.is_none() {
-                    CONSENSUS_BUILD_PROPOSAL_FAILED.increment(1);
+                match proposal_id {
+                    None => CONSENSUS_BUILD_PROPOSAL_FAILED.increment(1),
+                    // Persist the commitment before voting on it, unless it was restored.
+                    Some(proposal_id)
+                        if self.restored_build_proposals.get(&round)
+                            != Some(&Some(proposal_id)) =>
+                    {
+                        self.write_to_wal(WalEntry::BuiltProposal(proposal_id, round))?;
+                    }
+                    Some(_) => {}
                 }
                 let old = self.proposals.insert(round, proposal_id);
                 assert!(old.is_none(), "There should be no entry for round {round} when proposing");
@@ -351,10 +398,10 @@ impl SingleHeightConsensus {
                     |round: Round| -> ValidatorId { context.proposer(self.height, round) };
                 let sm_events = self
                     .state_machine
-                    .handle_event(StateMachineEvent::GetProposal(proposal_id, round), &leader_fn);
+                    .handle_event(StateMachineEvent::GetProposal(proposal_id, round), &leader_fn)?;
                 self.handle_state_machine_events(context, sm_events).await
             }
-            _ => unimplemented!("Unexpected event: {:?}", event),
+            _ => Err(ConsensusError::InternalInconsistency(format!("Unexpected event: {event:?}"))),
         };
         context.set_height_and_round(self.height, self.state_machine.round()).await;
         ret
@@ -366,7 +413,7 @@ impl SingleHeightConsensus {
         event: StateMachineEvent,
     ) -> Result<ShcReturn, ConsensusError> {
         let leader_fn = |round: Round| -> ValidatorId { context.proposer(self.height, round) };
-        let sm_events = self.state_machine.handle_event(event, &leader_fn);
+        let sm_events = self.state_machine.handle_event(event, &leader_fn)?;
         self.handle_state_machine_events(context, sm_events).await
     }
 
@@ -378,9 +425,14 @@ impl SingleHeightConsensus {
         vote: Vote,
     ) -> Result<ShcReturn, ConsensusError> {
         trace!("Received {:?}", vote);
-        if !self.validators.contains(&vote.voter) {
+        let Some(&voter_weight) = self.validators.get(&vote.voter) else {
             debug!("Ignoring vote from non validator: vote={:?}", vote);
             return Ok(ShcReturn::Tasks(Vec::new()));
+        };
+        if !context.verify_vote(&vote) {
+            warn!("Ignoring vote with an invalid signature: vote={:?}", vote);
+            CONSENSUS_INVALID_VOTE_SIGNATURES.increment(1);
+            return Ok(ShcReturn::Tasks(Vec::new()));
         }
 
         let (votes, sm_vote) = match vote.vote_type {
@@ -401,6 +453,12 @@ impl SingleHeightConsensus {
                 if old.block_hash != vote.block_hash {
                     warn!("Conflicting votes: old={:?}, new={:?}", old, vote);
                     CONSENSUS_CONFLICTING_VOTES.increment(1);
+                    // Both votes passed signature verification, so they prove the equivocation.
+                    let evidence = Evidence::from_conflicting_votes(old.clone(), vote)
+                        .expect("Votes from the same entry must only differ in block hash.");
+                    if self.evidence_pool.add(evidence.clone()) {
+                        context.report_evidence(evidence).await;
+                    }
                     return Ok(ShcReturn::Tasks(Vec::new()));
                 } else {
                     // Replay, ignore.
@@ -410,7 +468,7 @@ impl SingleHeightConsensus {
         }
         info!("Accepting {:?}", vote);
         let leader_fn = |round: Round| -> ValidatorId { context.proposer(self.height, round) };
-        let sm_events = self.state_machine.handle_event(sm_vote, &leader_fn);
+        let sm_events = self.state_machine.handle_vote(sm_vote, voter_weight, &leader_fn)?;
         let ret = self.handle_state_machine_events(context, sm_events).await;
         context.set_height_and_round(self.height, self.state_machine.round()).await;
         ret
@@ -422,13 +480,14 @@ impl SingleHeightConsensus {
         context: &mut ContextT,
         mut events: VecDeque<StateMachineEvent>,
     ) -> Result<ShcReturn, ConsensusError> {
+        self.write_locks_to_wal()?;
         let mut ret_val = Vec::new();
         while let Some(event) = events.pop_front() {
             trace!("Handling sm event: {:?}", event);
             match event {
                 StateMachineEvent::GetProposal(proposal_id, round) => {
                     ret_val.extend(
-                        self.handle_state_machine_get_proposal(context, proposal_id, round).await,
+                        self.handle_state_machine_get_proposal(context, proposal_id, round).await?,
                     );
                 }
                 StateMachineEvent::Proposal(proposal_id, round, valid_round) => {
@@ -481,11 +540,34 @@ impl SingleHeightConsensus {
         context: &mut ContextT,
         proposal_id: Option<ProposalCommitment>,
         round: Round,
-    ) -> Vec<ShcTask> {
+    ) -> Result<Vec<ShcTask>, ConsensusError> {
         assert!(
             proposal_id.is_none(),
             "StateMachine is requesting a new proposal, but provided a content id."
         );
+        if let Some(restored_proposal_id) = self.restored_build_proposals.get(&round) {
+            // Building a different proposal for the same round would be equivocation. If the
+            // proposal was completed before the restart, resume with its commitment. Otherwise, let
+            // this round time out.
+            let (fin_sender, fin_receiver) = oneshot::channel();
+            match restored_proposal_id {
+                Some(restored_proposal_id) => {
+                    info!(
+                        "Resuming with the proposal built for round {round} before restarting: \
+                         {restored_proposal_id:?}."
+                    );
+                    fin_sender.send(*restored_proposal_id).expect("The receiver is alive.");
+                }
+                None => {
+                    warn!(
+                        "Already started building a proposal for round {round} before restarting. \
+                         Not rebuilding."
+                    );
+                }
+            }
+            return Ok(vec![ShcTask::BuildProposal(round, fin_receiver)]);
+        }
+        self.write_to_wal(WalEntry::BuildProposal(round))?;
 
         // TODO(Matan): Figure out how to handle failed proposal building. I believe this should be
         // handled by applying timeoutPropose when we are the leader.
@@ -493,7 +575,7 @@ impl SingleHeightConsensus {
             ProposalInit { height: self.height, round, proposer: self.id, valid_round: None };
         CONSENSUS_BUILD_PROPOSAL_TOTAL.increment(1);
         let fin_receiver = context.build_proposal(init, self.timeouts.proposal_timeout).await;
-        vec![ShcTask::BuildProposal(round, fin_receiver)]
+        Ok(vec![ShcTask::BuildProposal(round, fin_receiver)])
     }
 
     async fn handle_state_machine_proposal<ContextT: ConsensusContext>(
@@ -508,6 +590,17 @@ impl SingleHeightConsensus {
             return;
         };
         let proposal_id = proposal_id.expect("Reproposal must have a valid ID");
+        if !self.proposals.contains_key(&valid_round)
+            && self.restored_valid_value_round == Some((proposal_id, valid_round))
+        {
+            // The valid value was restored from the WAL, but its content didn't survive the
+            // restart.
+            warn!(
+                "Cannot repropose {proposal_id:?} from round {valid_round}, its content was lost \
+                 on restart."
+            );
+            return;
+        }
 
         let id = self
             .proposals
@@ -536,7 +629,18 @@ impl SingleHeightConsensus {
         round: Round,
         vote_type: VoteType,
     ) -> Result<Vec<ShcTask>, ConsensusError> {
-        let (votes, last_vote, task) = match vote_type {
+        let vote = Vote {
+            vote_type,
+            height: self.height.0,
+            round,
+            block_hash: proposal_id,
+            voter: self.id,
+            signature: None,
+        };
+        let vote = context.sign_vote(vote).await?;
+        // Persist the vote before sending it, so that we don't vote differently after a restart.
+        self.write_to_wal(WalEntry::Vote(vote.clone()))?;
+        let (votes, last_vote, task) = match vote.vote_type {
             VoteType::Prevote => (
                 &mut self.prevotes,
                 &mut self.last_prevote,
@@ -554,13 +658,6 @@ impl SingleHeightConsensus {
                 ),
             ),
         };
-        let vote = Vote {
-            vote_type,
-            height: self.height.0,
-            round,
-            block_hash: proposal_id,
-            voter: self.id,
-        };
         if let Some(old) = votes.insert((round, self.id), vote.clone()) {
             return Err(ConsensusError::InternalInconsistency(format!(
                 "State machine should not send repeat votes: old={old:?}, new={vote:?}"
@@ -609,27 +706,131 @@ impl SingleHeightConsensus {
                 "StateMachine block hash should match the stored block. Shc.block_id: {block}"
             )));
         }
+        let mut vote_weight: VotingWeight = 0;
         let supporting_precommits: Vec<Vote> = self
             .validators
             .iter()
-            .filter_map(|v| {
+            .filter_map(|(v, weight)| {
                 let vote = self.precommits.get(&(round, *v))?;
-                if vote.block_hash == Some(proposal_id) { Some(vote.clone()) } else { None }
+                if vote.block_hash != Some(proposal_id) {
+                    return None;
+                }
+                vote_weight = vote_weight.checked_add(*weight).expect("Vote weight overflow.");
+                Some(vote.clone())
             })
             .collect();
-
-        // TODO(matan): Check actual weights.
-        let vote_weight = u64::try_from(supporting_precommits.len())
-            .expect("Should have way less than u64::MAX supporting votes");
         let total_weight = self.state_machine.total_weight();
 
         if !self.state_machine.quorum().is_met(vote_weight, total_weight) {
             let msg = format!(
-                "Not enough supporting votes. num_supporting_votes: {vote_weight} out of \
+                "Not enough supporting votes. supporting_weight: {vote_weight} out of \
                  {total_weight}. supporting_votes: {supporting_precommits:?}",
             );
             return Err(invalid_decision(msg));
         }
         Ok(ShcReturn::Decision(Decision { precommits: supporting_precommits, block }))
     }
+
+    /// Replays the entries written to the WAL before a restart, so this node resumes the height
+    /// with the same commitments. Returns the tasks for rebroadcasting the restored votes.
+    fn restore_from_wal(&mut self) -> Result<Vec<ShcTask>, ConsensusError> {
+        let entries = std::mem::take(&mut self.wal_entries);
+        if entries.is_empty() {
+            return Ok(Vec::new());
+        }
+        info!("Restoring {} entries from the consensus WAL.", entries.len());
+        let mut round = 0;
+        let mut own_votes = Vec::new();
+        let mut locked_value_round = None;
+        let mut valid_value_round = None;
+        for entry in entries {
+            match entry {
+                WalEntry::Vote(vote) => {
+                    if vote.voter != self.id || vote.height != self.height.0 {
+                        return Err(ConsensusError::InternalInconsistency(format!(
+                            "WAL contains a vote which this node couldn't have sent: {vote:?}"
+                        )));
+                    }
+                    round = round.max(vote.round);
+                    let (votes, last_vote, sm_vote) = match vote.vote_type {
+                        VoteType::Prevote => (
+                            &mut self.prevotes,
+                            &mut self.last_prevote,
+                            StateMachineEvent::Prevote(vote.block_hash, vote.round),
+                        ),
+                        VoteType::Precommit => (
+                            &mut self.precommits,
+                            &mut self.last_precommit,
+                            StateMachineEvent::Precommit(vote.block_hash, vote.round),
+                        ),
+                    };
+                    if last_vote.as_ref().is_none_or(|last_vote| last_vote.round < vote.round) {
+                        *last_vote = Some(vote.clone());
+                    }
+                    votes.insert((vote.round, self.id), vote);
+                    own_votes.push(sm_vote);
+                }
+                WalEntry::BuildProposal(build_round) => {
+                    round = round.max(build_round);
+                    self.restored_build_proposals.insert(build_round, None);
+                }
+                WalEntry::BuiltProposal(proposal_id, build_round) => {
+                    round = round.max(build_round);
+                    self.restored_build_proposals.insert(build_round, Some(proposal_id));
+                }
+                WalEntry::LockedValue(value, lock_round) => {
+                    locked_value_round = Some((value, lock_round));
+                }
+                WalEntry::ValidValue(value, valid_round) => {
+                    valid_value_round = Some((value, valid_round));
+                }
+            }
+        }
+        self.state_machine.restore(round, &own_votes, locked_value_round, valid_value_round);
+        self.wal_locked_value_round = locked_value_round;
+        self.wal_valid_value_round = valid_value_round;
+        self.restored_valid_value_round = valid_value_round;
+
+        // Other nodes may have missed our votes while we were down.
+        let mut tasks = Vec::new();
+        if let Some(vote) = &self.last_prevote {
+            tasks.push(ShcTask::Prevote(
+                self.timeouts.prevote_timeout,
+                StateMachineEvent::Prevote(vote.block_hash, vote.round),
+            ));
+        }
+        if let Some(vote) = &self.last_precommit {
+            tasks.push(ShcTask::Precommit(
+                self.timeouts.precommit_timeout,
+                StateMachineEvent::Precommit(vote.block_hash, vote.round),
+            ));
+        }
+        Ok(tasks)
+    }
+
+    // Write changes to the state machine's locked and valid values to the WAL.
+    fn write_locks_to_wal(&mut self) -> Result<(), ConsensusError> {
+        let locked_value_round = self.state_machine.locked_value_round();
+        if locked_value_round != self.wal_locked_value_round {
+            if let Some((value, round)) = locked_value_round {
+                self.write_to_wal(WalEntry::LockedValue(value, round))?;
+            }
+            self.wal_locked_value_round = locked_value_round;
+        }
+        let valid_value_round = self.state_machine.valid_value_round();
+        if valid_value_round != self.wal_valid_value_round {
+            if let Some((value, round)) = valid_value_round {
+                self.write_to_wal(WalEntry::ValidValue(value, round))?;
+            }
+            self.wal_valid_value_round = valid_value_round;
+        }
+        Ok(())
+    }
+
+    fn write_to_wal(&mut self, entry: WalEntry) -> Result<(), ConsensusError> {
+        if let Some(wal) = &mut self.wal {
+            wal.append(entry)?;
+        }
+        Ok(())
+    }
 }
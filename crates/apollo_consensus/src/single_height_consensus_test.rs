@@ -3,24 +3,39 @@ use futures::channel::{mpsc, oneshot};
 use futures::SinkExt;
 use lazy_static::lazy_static;
 use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::crypto::utils::Signature;
 use starknet_types_core::felt::Felt;
+use tempfile::TempDir;
 use test_case::test_case;
 
 use super::SingleHeightConsensus;
 use crate::config::TimeoutsConfig;
+use crate::evidence::{Evidence, EvidencePool};
 use crate::single_height_consensus::{ShcEvent, ShcReturn, ShcTask};
 use crate::state_machine::StateMachineEvent;
-use crate::test_utils::{precommit, prevote, MockTestContext, TestBlock, TestProposalPart};
-use crate::types::ValidatorId;
+use crate::test_utils::{
+    expect_vote_signing,
+    precommit,
+    prevote,
+    MockTestContext,
+    TestBlock,
+    TestProposalPart,
+};
+use crate::types::{ValidatorId, ValidatorSet};
 use crate::votes_threshold::QuorumType;
+use crate::wal::{ConsensusWal, WalEntry};
 
 lazy_static! {
     static ref PROPOSER_ID: ValidatorId = DEFAULT_VALIDATOR_ID.into();
     static ref VALIDATOR_ID_1: ValidatorId = (DEFAULT_VALIDATOR_ID + 1).into();
     static ref VALIDATOR_ID_2: ValidatorId = (DEFAULT_VALIDATOR_ID + 2).into();
     static ref VALIDATOR_ID_3: ValidatorId = (DEFAULT_VALIDATOR_ID + 3).into();
-    static ref VALIDATORS: Vec<ValidatorId> =
-        vec![*PROPOSER_ID, *VALIDATOR_ID_1, *VALIDATOR_ID_2, *VALIDATOR_ID_3];
+    static ref VALIDATORS: ValidatorSet = ValidatorSet::from([
+        (*PROPOSER_ID, 1),
+        (*VALIDATOR_ID_1, 1),
+        (*VALIDATOR_ID_2, 1),
+        (*VALIDATOR_ID_3, 1),
+    ]);
     static ref BLOCK: TestBlock = TestBlock { content: vec![1, 2, 3], id: BlockHash(Felt::ONE) };
     static ref PROPOSAL_INIT: ProposalInit =
         ProposalInit { proposer: *PROPOSER_ID, ..Default::default() };
@@ -32,6 +47,11 @@ lazy_static! {
 }
 
 const CHANNEL_SIZE: usize = 1;
+const SIGNATURE: Signature = Signature { r: Felt::ONE, s: Felt::TWO };
+
+fn signed(vote: Vote) -> Vote {
+    Vote { signature: Some(SIGNATURE), ..vote }
+}
 
 fn prevote_task(block_felt: Option<Felt>, round: u32) -> ShcTask {
     ShcTask::Prevote(
@@ -72,14 +92,17 @@ async fn handle_proposal(
 #[tokio::test]
 async fn proposer() {
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
 
     let mut shc = SingleHeightConsensus::new(
         BlockNumber(0),
         false,
         *PROPOSER_ID,
-        VALIDATORS.to_vec(),
+        VALIDATORS.clone(),
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
     );
 
     context.expect_proposer().times(1).returning(move |_, _| *PROPOSER_ID);
@@ -151,15 +174,18 @@ async fn proposer() {
 #[tokio::test]
 async fn validator(repeat_proposal: bool) {
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
 
     // Creation calls to `context.validators`.
     let mut shc = SingleHeightConsensus::new(
         BlockNumber(0),
         false,
         *VALIDATOR_ID_1,
-        VALIDATORS.to_vec(),
+        VALIDATORS.clone(),
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
     );
 
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
@@ -219,19 +245,94 @@ async fn validator(repeat_proposal: bool) {
     assert!(decision.precommits.into_iter().all(|item| precommits.contains(&item)));
 }
 
+#[tokio::test]
+async fn votes_must_be_signed() {
+    let mut context = MockTestContext::new();
+    // Sign votes with a dummy signature, and only accept votes which carry one.
+    context.expect_sign_vote().returning(|vote| Ok(Vote { signature: Some(SIGNATURE), ..vote }));
+    context.expect_verify_vote().returning(|vote| vote.signature == Some(SIGNATURE));
+
+    let mut shc = SingleHeightConsensus::new(
+        BlockNumber(0),
+        false,
+        *VALIDATOR_ID_1,
+        VALIDATORS.clone(),
+        QuorumType::Byzantine,
+        TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
+    );
+
+    context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
+    context.expect_validate_proposal().times(1).returning(move |_, _, _| {
+        let (block_sender, block_receiver) = oneshot::channel();
+        block_sender.send(BLOCK.id).unwrap();
+        block_receiver
+    });
+    context.expect_set_height_and_round().returning(move |_, _| ());
+    context
+        .expect_broadcast()
+        .times(1)
+        .withf(move |msg: &Vote| msg == &signed(prevote(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_1)))
+        .returning(move |_| Ok(()));
+    handle_proposal(&mut shc, &mut context).await;
+    shc.handle_event(&mut context, VALIDATE_PROPOSAL_EVENT.clone()).await.unwrap();
+
+    // Unsigned votes are ignored, so they don't count towards a quorum.
+    for voter in [*PROPOSER_ID, *VALIDATOR_ID_2] {
+        assert_eq!(
+            shc.handle_vote(&mut context, prevote(Some(BLOCK.id.0), 0, 0, voter)).await,
+            Ok(ShcReturn::Tasks(Vec::new()))
+        );
+    }
+
+    context
+        .expect_broadcast()
+        .times(1)
+        .withf(move |msg: &Vote| msg == &signed(precommit(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_1)))
+        .returning(move |_| Ok(()));
+    assert_eq!(
+        shc.handle_vote(&mut context, signed(prevote(Some(BLOCK.id.0), 0, 0, *PROPOSER_ID))).await,
+        Ok(ShcReturn::Tasks(Vec::new()))
+    );
+    assert_eq!(
+        shc.handle_vote(&mut context, signed(prevote(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_2)))
+            .await,
+        Ok(ShcReturn::Tasks(vec![timeout_prevote_task(0), precommit_task(Some(BLOCK.id.0), 0)]))
+    );
+
+    shc.handle_vote(&mut context, signed(precommit(Some(BLOCK.id.0), 0, 0, *PROPOSER_ID)))
+        .await
+        .unwrap();
+    let ShcReturn::Decision(decision) = shc
+        .handle_vote(&mut context, signed(precommit(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_2)))
+        .await
+        .unwrap()
+    else {
+        panic!("Expected decision");
+    };
+    // The decision is backed by signed precommits, including our own.
+    assert_eq!(decision.precommits.len(), 3);
+    assert!(decision.precommits.iter().all(|vote| vote.signature == Some(SIGNATURE)));
+}
+
 #[test_case(true; "repeat")]
 #[test_case(false; "equivocation")]
 #[tokio::test]
 async fn vote_twice(same_vote: bool) {
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
 
+    let evidence_pool = EvidencePool::default();
     let mut shc = SingleHeightConsensus::new(
         BlockNumber(0),
         false,
         *VALIDATOR_ID_1,
-        VALIDATORS.to_vec(),
+        VALIDATORS.clone(),
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        evidence_pool.clone(),
     );
 
     context.expect_proposer().times(1).returning(move |_, _| *PROPOSER_ID);
@@ -274,8 +375,17 @@ async fn vote_twice(same_vote: bool) {
 
     let second_vote =
         if same_vote { first_vote.clone() } else { precommit(Some(Felt::TWO), 0, 0, *PROPOSER_ID) };
+    let expected_evidence = (!same_vote)
+        .then(|| Evidence { first_vote: first_vote.clone(), second_vote: second_vote.clone() });
+    let reported_evidence = expected_evidence.clone();
+    context
+        .expect_report_evidence()
+        .times(usize::from(!same_vote))
+        .withf(move |evidence| Some(evidence) == reported_evidence.as_ref())
+        .return_const(());
     let res = shc.handle_vote(&mut context, second_vote.clone()).await;
     assert_eq!(res, Ok(ShcReturn::Tasks(Vec::new())));
+    assert_eq!(evidence_pool.evidence(), expected_evidence.into_iter().collect::<Vec<_>>());
 
     let ShcReturn::Decision(decision) = shc
         .handle_vote(&mut context, precommit(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_2))
@@ -290,14 +400,17 @@ async fn vote_twice(same_vote: bool) {
 #[tokio::test]
 async fn rebroadcast_votes() {
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
 
     let mut shc = SingleHeightConsensus::new(
         BlockNumber(0),
         false,
         *PROPOSER_ID,
-        VALIDATORS.to_vec(),
+        VALIDATORS.clone(),
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
     );
 
     context.expect_proposer().times(1).returning(move |_, _| *PROPOSER_ID);
@@ -354,14 +467,17 @@ async fn rebroadcast_votes() {
 #[tokio::test]
 async fn repropose() {
     let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
 
     let mut shc = SingleHeightConsensus::new(
         BlockNumber(0),
         false,
         *PROPOSER_ID,
-        VALIDATORS.to_vec(),
+        VALIDATORS.clone(),
         QuorumType::Byzantine,
         TIMEOUTS.clone(),
+        None,
+        EvidencePool::default(),
     );
 
     context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
@@ -436,3 +552,193 @@ async fn repropose() {
     assert_eq!(decision.block, BLOCK.id);
     assert!(decision.precommits.into_iter().all(|item| precommits.contains(&item)));
 }
+
+#[tokio::test]
+async fn restart_resumes_from_wal() {
+    let wal_dir = TempDir::new().unwrap();
+    let wal_path = wal_dir.path().join("wal");
+
+    let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
+    let mut shc = SingleHeightConsensus::new(
+        BlockNumber(0),
+        false,
+        *PROPOSER_ID,
+        VALIDATORS.clone(),
+        QuorumType::Byzantine,
+        TIMEOUTS.clone(),
+        Some(ConsensusWal::open(&wal_path, BlockNumber(0)).unwrap()),
+        EvidencePool::default(),
+    );
+    context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
+    context.expect_build_proposal().times(1).returning(move |_, _| {
+        let (block_sender, block_receiver) = oneshot::channel();
+        block_sender.send(BLOCK.id).unwrap();
+        block_receiver
+    });
+    context.expect_set_height_and_round().returning(move |_, _| ());
+    context.expect_broadcast().times(2).returning(move |_| Ok(()));
+    // Build a proposal, prevote and precommit on it.
+    shc.start(&mut context).await.unwrap();
+    shc.handle_event(
+        &mut context,
+        ShcEvent::BuildProposal(StateMachineEvent::GetProposal(Some(BLOCK.id), 0)),
+    )
+    .await
+    .unwrap();
+    shc.handle_vote(&mut context, prevote(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_1)).await.unwrap();
+    shc.handle_vote(&mut context, prevote(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_2)).await.unwrap();
+    drop(shc);
+
+    // Restart.
+    let (wal, entries) = ConsensusWal::open(&wal_path, BlockNumber(0)).unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            WalEntry::BuildProposal(0),
+            WalEntry::BuiltProposal(BLOCK.id, 0),
+            WalEntry::Vote(prevote(Some(BLOCK.id.0), 0, 0, *PROPOSER_ID)),
+            WalEntry::LockedValue(BLOCK.id, 0),
+            WalEntry::ValidValue(BLOCK.id, 0),
+            WalEntry::Vote(precommit(Some(BLOCK.id.0), 0, 0, *PROPOSER_ID)),
+        ]
+    );
+    let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
+    let mut shc = SingleHeightConsensus::new(
+        BlockNumber(0),
+        false,
+        *PROPOSER_ID,
+        VALIDATORS.clone(),
+        QuorumType::Byzantine,
+        TIMEOUTS.clone(),
+        Some((wal, entries)),
+        EvidencePool::default(),
+    );
+    context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
+    context.expect_set_height_and_round().returning(move |_, _| ());
+    // The node neither builds another proposal nor votes again for round 0, but does rebroadcast
+    // its votes.
+    context.expect_build_proposal().times(0);
+    context.expect_repropose().times(0);
+    assert_eq!(
+        shc.start(&mut context).await,
+        Ok(ShcReturn::Tasks(vec![
+            prevote_task(Some(BLOCK.id.0), 0),
+            precommit_task(Some(BLOCK.id.0), 0)
+        ]))
+    );
+    context
+        .expect_broadcast()
+        .times(1)
+        .withf(move |msg: &Vote| msg == &precommit(Some(BLOCK.id.0), 0, 0, *PROPOSER_ID))
+        .returning(move |_| Ok(()));
+    assert_eq!(
+        shc.handle_event(
+            &mut context,
+            ShcEvent::Precommit(StateMachineEvent::Precommit(Some(BLOCK.id), 0))
+        )
+        .await,
+        Ok(ShcReturn::Tasks(vec![precommit_task(Some(BLOCK.id.0), 0)]))
+    );
+
+    // Our own precommit from before the restart counts towards the timeout.
+    shc.handle_vote(&mut context, precommit(None, 0, 0, *VALIDATOR_ID_1)).await.unwrap();
+    assert_eq!(
+        shc.handle_vote(&mut context, precommit(None, 0, 0, *VALIDATOR_ID_2)).await,
+        Ok(ShcReturn::Tasks(vec![timeout_precommit_task(0)]))
+    );
+    // In round 1 the content of the valid value is lost, so it isn't reproposed.
+    assert_eq!(
+        shc.handle_event(
+            &mut context,
+            ShcEvent::TimeoutPrecommit(StateMachineEvent::TimeoutPrecommit(0)),
+        )
+        .await,
+        Ok(ShcReturn::Tasks(Vec::new()))
+    );
+    // Once the peers' prevotes for round 0, which weren't persisted, are rebroadcast, the node
+    // prevotes for the value it is locked on.
+    shc.handle_vote(&mut context, prevote(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_1)).await.unwrap();
+    context
+        .expect_broadcast()
+        .times(1)
+        .withf(move |msg: &Vote| msg == &prevote(Some(BLOCK.id.0), 0, 1, *PROPOSER_ID))
+        .returning(move |_| Ok(()));
+    assert_eq!(
+        shc.handle_vote(&mut context, prevote(Some(BLOCK.id.0), 0, 0, *VALIDATOR_ID_2)).await,
+        Ok(ShcReturn::Tasks(vec![prevote_task(Some(BLOCK.id.0), 1)]))
+    );
+}
+
+#[tokio::test]
+async fn restart_after_building_resumes_with_the_built_proposal() {
+    let wal_dir = TempDir::new().unwrap();
+    let wal_path = wal_dir.path().join("wal");
+
+    let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
+    context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
+    context.expect_set_height_and_round().returning(move |_, _| ());
+    context.expect_build_proposal().times(1).returning(move |_, _| {
+        let (block_sender, block_receiver) = oneshot::channel();
+        block_sender.send(BLOCK.id).unwrap();
+        block_receiver
+    });
+    let mut shc = SingleHeightConsensus::new(
+        BlockNumber(0),
+        false,
+        *PROPOSER_ID,
+        VALIDATORS.clone(),
+        QuorumType::Byzantine,
+        TIMEOUTS.clone(),
+        Some(ConsensusWal::open(&wal_path, BlockNumber(0)).unwrap()),
+        EvidencePool::default(),
+    );
+    shc.start(&mut context).await.unwrap();
+    drop(shc);
+    // Simulate a crash after the proposal was built, but before prevoting on it.
+    let mut wal = ConsensusWal::open(&wal_path, BlockNumber(0)).unwrap().0;
+    wal.append(WalEntry::BuiltProposal(BLOCK.id, 0)).unwrap();
+    drop(wal);
+
+    // Restart.
+    let (wal, entries) = ConsensusWal::open(&wal_path, BlockNumber(0)).unwrap();
+    assert_eq!(entries, vec![WalEntry::BuildProposal(0), WalEntry::BuiltProposal(BLOCK.id, 0)]);
+    let mut context = MockTestContext::new();
+    expect_vote_signing(&mut context);
+    context.expect_proposer().returning(move |_, _| *PROPOSER_ID);
+    context.expect_set_height_and_round().returning(move |_, _| ());
+    context.expect_build_proposal().times(0);
+    let mut shc = SingleHeightConsensus::new(
+        BlockNumber(0),
+        false,
+        *PROPOSER_ID,
+        VALIDATORS.clone(),
+        QuorumType::Byzantine,
+        TIMEOUTS.clone(),
+        Some((wal, entries)),
+        EvidencePool::default(),
+    );
+    let ShcReturn::Tasks(mut tasks) = shc.start(&mut context).await.unwrap() else {
+        panic!("Expected tasks");
+    };
+    assert_eq!(tasks.len(), 1);
+    let event = tasks.pop().unwrap().run().await;
+    assert!(matches!(
+        event,
+        ShcEvent::BuildProposal(StateMachineEvent::GetProposal(Some(proposal_id), 0))
+            if proposal_id == BLOCK.id
+    ));
+
+    // The node prevotes on the proposal it built before the restart.
+    context
+        .expect_broadcast()
+        .times(1)
+        .withf(move |msg: &Vote| msg == &prevote(Some(BLOCK.id.0), 0, 0, *PROPOSER_ID))
+        .returning(move |_| Ok(()));
+    assert_eq!(
+        shc.handle_event(&mut context, event).await,
+        Ok(ShcReturn::Tasks(vec![prevote_task(Some(BLOCK.id.0), 0)]))
+    );
+}
@@ -21,7 +21,14 @@ use crate::metrics::{
     CONSENSUS_TIMEOUTS,
     LABEL_NAME_TIMEOUT_REASON,
 };
-use crate::types::{ProposalCommitment, Round, ValidatorId};
+use crate::types::{
+    ConsensusError,
+    ProposalCommitment,
+    Round,
+    ValidatorId,
+    ValidatorSet,
+    VotingWeight,
+};
 use crate::votes_threshold::{QuorumType, VotesThreshold, ROUND_SKIP_THRESHOLD};
 
 /// Events which the state machine sends/receives.
@@ -70,17 +77,20 @@ pub struct StateMachine {
     step: Step,
     quorum: VotesThreshold,
     round_skip_threshold: VotesThreshold,
-    total_weight: u64,
+    total_weight: VotingWeight,
+    // The voting weight of this node, used when counting its own votes.
+    own_weight: VotingWeight,
     is_observer: bool,
     // {round: (proposal_id, valid_round)}
     proposals: HashMap<Round, (Option<ProposalCommitment>, Option<Round>)>,
-    // {round: {proposal_id: vote_count}
-    prevotes: HashMap<Round, HashMap<Option<ProposalCommitment>, u32>>,
-    precommits: HashMap<Round, HashMap<Option<ProposalCommitment>, u32>>,
+    // {round: {proposal_id: vote_weight}
+    prevotes: HashMap<Round, HashMap<Option<ProposalCommitment>, VotingWeight>>,
+    precommits: HashMap<Round, HashMap<Option<ProposalCommitment>, VotingWeight>>,
     // When true, the state machine will wait for a GetProposal event, buffering all other input
     // events in `events_queue`.
     awaiting_get_proposal: bool,
-    events_queue: VecDeque<StateMachineEvent>,
+    // Input events along with the weight of the voter (relevant only for votes).
+    events_queue: VecDeque<(StateMachineEvent, VotingWeight)>,
     locked_value_round: Option<(ProposalCommitment, Round)>,
     valid_value_round: Option<(ProposalCommitment, Round)>,
     prevote_quorum: HashSet<Round>,
@@ -89,13 +99,19 @@ pub struct StateMachine {
 }
 
 impl StateMachine {
-    /// total_weight - the total voting weight of all validators for this height.
+    /// validators - the validators for this height, used to determine the total voting weight and
+    /// the weight of this node's own votes.
     pub fn new(
         id: ValidatorId,
-        total_weight: u64,
+        validators: &ValidatorSet,
         is_observer: bool,
         quorum_type: QuorumType,
     ) -> Self {
+        let total_weight = validators
+            .values()
+            .try_fold(0, |acc: VotingWeight, weight| acc.checked_add(*weight))
+            .expect("Total weight overflow.");
+        let own_weight = validators.get(&id).copied().unwrap_or(0);
         Self {
             id,
             round: 0,
@@ -105,6 +121,7 @@ impl StateMachine {
             // Skip round threshold is 1/3 of the total weight.
             round_skip_threshold: ROUND_SKIP_THRESHOLD,
             total_weight,
+            own_weight,
             is_observer,
             proposals: HashMap::new(),
             prevotes: HashMap::new(),
@@ -123,7 +140,7 @@ impl StateMachine {
         self.round
     }
 
-    pub fn total_weight(&self) -> u64 {
+    pub fn total_weight(&self) -> VotingWeight {
         self.total_weight
     }
 
@@ -131,29 +148,127 @@ impl StateMachine {
         &self.quorum
     }
 
+    pub fn locked_value_round(&self) -> Option<(ProposalCommitment, Round)> {
+        self.locked_value_round
+    }
+
+    pub fn valid_value_round(&self) -> Option<(ProposalCommitment, Round)> {
+        self.valid_value_round
+    }
+
+    /// Restores the state this node persisted before restarting mid-height. Must be called before
+    /// `start`.
+    ///
+    /// - `round`: the round to resume from. Must not be lower than the round of any own vote.
+    /// - `own_votes`: the votes this node already sent. They are counted, and if any were sent in
+    ///   `round`, the state machine resumes from the step following the latest of them, so that it
+    ///   never votes twice in the same step.
+    /// - `locked_value_round`, `valid_value_round`: as they were before the restart.
+    pub fn restore(
+        &mut self,
+        round: Round,
+        own_votes: &[StateMachineEvent],
+        locked_value_round: Option<(ProposalCommitment, Round)>,
+        valid_value_round: Option<(ProposalCommitment, Round)>,
+    ) {
+        assert_eq!(self.step, Step::Propose, "Restore must be called before start.");
+        assert_eq!(self.round, 0, "Restore must be called before start.");
+        assert!(!self.is_observer, "Observers don't vote, so have nothing to restore.");
+        let mut step = Step::Propose;
+        for vote in own_votes {
+            let (votes, vote_round, proposal_id, vote_step) = match vote {
+                StateMachineEvent::Prevote(proposal_id, vote_round) => {
+                    (&mut self.prevotes, *vote_round, proposal_id, Step::Prevote)
+                }
+                StateMachineEvent::Precommit(proposal_id, vote_round) => {
+                    (&mut self.precommits, *vote_round, proposal_id, Step::Precommit)
+                }
+                _ => panic!("Expected a vote: {vote:?}"),
+            };
+            assert!(vote_round <= round, "Cannot resume from round {round}, before {vote:?}.");
+            let weight = votes.entry(vote_round).or_default().entry(*proposal_id).or_insert(0);
+            *weight = weight.checked_add(self.own_weight).expect("Vote weight overflow.");
+            if vote_round == round && (vote_step == Step::Precommit || step == Step::Propose) {
+                step = vote_step;
+            }
+        }
+        self.round = round;
+        self.step = step;
+        self.locked_value_round = locked_value_round;
+        self.valid_value_round = valid_value_round;
+    }
+
     /// Starts the state machine, effectively calling `StartRound(0)` from the paper. This is
     /// needed to trigger the first leader to propose.
     /// See [`GetProposal`](StateMachineEvent::GetProposal)
+    ///
+    /// If the state was [restored](Self::restore), starts from the restored round instead. If this
+    /// node already voted in that round, no new round is started; the state machine waits for
+    /// votes from its peers.
     pub fn start<LeaderFn>(&mut self, leader_fn: &LeaderFn) -> VecDeque<StateMachineEvent>
     where
         LeaderFn: Fn(Round) -> ValidatorId,
     {
-        self.advance_to_round(0, leader_fn)
+        if self.step != Step::Propose {
+            info!("Resuming round {} from step {:?}", self.round, self.step);
+            CONSENSUS_ROUND.set(self.round);
+            return VecDeque::new();
+        }
+        self.advance_to_round(self.round, leader_fn)
     }
 
-    /// Process the incoming event.
+    /// Process the incoming event. Votes must be passed via [`handle_vote`](Self::handle_vote)
+    /// instead, since they are counted according to the voter's weight.
     ///
     /// If we are waiting for a response to [`GetProposal`](`StateMachineEvent::GetProposal`) all
     /// other incoming events are buffered until that response arrives.
     ///
     /// Returns a set of events for the caller to handle. The caller should not mirror the output
     /// events back to the state machine, as it makes sure to handle them before returning.
+    /// Returns an error, without handling the event, if it's a vote.
     // This means that the StateMachine handles events the same regardless of whether it was sent by
     // self or a peer. This is in line with the Algorithm 1 in the paper and keeps the code simpler.
     pub fn handle_event<LeaderFn>(
         &mut self,
         event: StateMachineEvent,
         leader_fn: &LeaderFn,
+    ) -> Result<VecDeque<StateMachineEvent>, ConsensusError>
+    where
+        LeaderFn: Fn(Round) -> ValidatorId,
+    {
+        if matches!(event, StateMachineEvent::Prevote(_, _) | StateMachineEvent::Precommit(_, _)) {
+            return Err(ConsensusError::InternalInconsistency(format!(
+                "Votes must be passed along with the voter's weight: {event:?}"
+            )));
+        }
+        Ok(self.enqueue_and_handle(event, 0, leader_fn))
+    }
+
+    /// Process a vote (prevote or precommit) from a peer, where `weight` is the voting weight of
+    /// the voter. See [`handle_event`](Self::handle_event). Returns an error, without handling the
+    /// event, if it's not a vote.
+    pub fn handle_vote<LeaderFn>(
+        &mut self,
+        vote: StateMachineEvent,
+        weight: VotingWeight,
+        leader_fn: &LeaderFn,
+    ) -> Result<VecDeque<StateMachineEvent>, ConsensusError>
+    where
+        LeaderFn: Fn(Round) -> ValidatorId,
+    {
+        if !matches!(vote, StateMachineEvent::Prevote(_, _) | StateMachineEvent::Precommit(_, _)) {
+            return Err(ConsensusError::InternalInconsistency(format!(
+                "Expected a vote: {vote:?}"
+            )));
+        }
+        Ok(self.enqueue_and_handle(vote, weight, leader_fn))
+    }
+
+    fn enqueue_and_handle<LeaderFn>(
+        &mut self,
+        event: StateMachineEvent,
+        weight: VotingWeight,
+        leader_fn: &LeaderFn,
     ) -> VecDeque<StateMachineEvent>
     where
         LeaderFn: Fn(Round) -> ValidatorId,
@@ -163,15 +278,15 @@ impl StateMachine {
         if self.awaiting_get_proposal {
             match event {
                 StateMachineEvent::GetProposal(_, round) if round == self.round => {
-                    self.events_queue.push_front(event);
+                    self.events_queue.push_front((event, weight));
                 }
                 _ => {
-                    self.events_queue.push_back(event);
+                    self.events_queue.push_back((event, weight));
                     return VecDeque::new();
                 }
             }
         } else {
-            self.events_queue.push_back(event);
+            self.events_queue.push_back((event, weight));
         }
 
         self.handle_enqueued_events(leader_fn)
@@ -185,10 +300,10 @@ impl StateMachine {
         LeaderFn: Fn(Round) -> ValidatorId,
     {
         let mut output_events = VecDeque::new();
-        while let Some(event) = self.events_queue.pop_front() {
+        while let Some((event, weight)) = self.events_queue.pop_front() {
             // Handle a specific event and then decide which of the output events should also be
             // sent to self.
-            let mut resultant_events = self.handle_event_internal(event, leader_fn);
+            let mut resultant_events = self.handle_event_internal(event, weight, leader_fn);
             while let Some(e) = resultant_events.pop_front() {
                 match e {
                     StateMachineEvent::Proposal(_, _, _)
@@ -197,7 +312,7 @@ impl StateMachine {
                         if self.is_observer {
                             continue;
                         }
-                        self.events_queue.push_back(e.clone());
+                        self.events_queue.push_back((e.clone(), self.own_weight));
                     }
                     StateMachineEvent::Decision(_, _) => {
                         output_events.push_back(e);
@@ -221,6 +336,7 @@ impl StateMachine {
     fn handle_event_internal<LeaderFn>(
         &mut self,
         event: StateMachineEvent,
+        weight: VotingWeight,
         leader_fn: &LeaderFn,
     ) -> VecDeque<StateMachineEvent>
     where
@@ -239,10 +355,10 @@ impl StateMachine {
                 self.handle_proposal(proposal_id, round, valid_round, leader_fn)
             }
             StateMachineEvent::Prevote(proposal_id, round) => {
-                self.handle_prevote(proposal_id, round, leader_fn)
+                self.handle_prevote(proposal_id, round, weight, leader_fn)
             }
             StateMachineEvent::Precommit(proposal_id, round) => {
-                self.handle_precommit(proposal_id, round, leader_fn)
+                self.handle_precommit(proposal_id, round, weight, leader_fn)
             }
             StateMachineEvent::Decision(_, _) => {
                 unimplemented!(
@@ -305,14 +421,15 @@ impl StateMachine {
         &mut self,
         proposal_id: Option<ProposalCommitment>,
         round: u32,
+        weight: VotingWeight,
         leader_fn: &LeaderFn,
     ) -> VecDeque<StateMachineEvent>
     where
         LeaderFn: Fn(Round) -> ValidatorId,
     {
-        let prevote_count = self.prevotes.entry(round).or_default().entry(proposal_id).or_insert(0);
-        // TODO(matan): Use variable weight.
-        *prevote_count += 1;
+        let prevote_weight =
+            self.prevotes.entry(round).or_default().entry(proposal_id).or_insert(0);
+        *prevote_weight = prevote_weight.checked_add(weight).expect("Vote weight overflow.");
         self.map_round_to_upons(round, leader_fn)
     }
 
@@ -333,15 +450,15 @@ impl StateMachine {
         &mut self,
         proposal_id: Option<ProposalCommitment>,
         round: u32,
+        weight: VotingWeight,
         leader_fn: &LeaderFn,
     ) -> VecDeque<StateMachineEvent>
     where
         LeaderFn: Fn(Round) -> ValidatorId,
     {
-        let precommit_count =
+        let precommit_weight =
             self.precommits.entry(round).or_default().entry(proposal_id).or_insert(0);
-        // TODO(matan): Use variable weight.
-        *precommit_count += 1;
+        *precommit_weight = precommit_weight.checked_add(weight).expect("Vote weight overflow.");
         self.map_round_to_upons(round, leader_fn)
     }
 
@@ -606,24 +723,21 @@ impl StateMachine {
 
     fn round_has_enough_votes(
         &self,
-        votes: &HashMap<u32, HashMap<Option<ProposalCommitment>, u32>>,
+        votes: &HashMap<u32, HashMap<Option<ProposalCommitment>, VotingWeight>>,
         round: u32,
         threshold: &VotesThreshold,
     ) -> bool {
-        threshold
-            .is_met(votes.get(&round).map_or(0, |v| v.values().sum()).into(), self.total_weight)
+        threshold.is_met(votes.get(&round).map_or(0, |v| v.values().sum()), self.total_weight)
     }
 
     fn value_has_enough_votes(
         &self,
-        votes: &HashMap<u32, HashMap<Option<ProposalCommitment>, u32>>,
+        votes: &HashMap<u32, HashMap<Option<ProposalCommitment>, VotingWeight>>,
         round: u32,
         value: &Option<ProposalCommitment>,
         threshold: &VotesThreshold,
     ) -> bool {
-        threshold.is_met(
-            votes.get(&round).map_or(0, |v| *v.get(value).unwrap_or(&0)).into(),
-            self.total_weight,
-        )
+        threshold
+            .is_met(votes.get(&round).map_or(0, |v| *v.get(value).unwrap_or(&0)), self.total_weight)
     }
 }
@@ -8,7 +8,7 @@ use test_case::test_case;
 
 use super::Round;
 use crate::state_machine::{StateMachine, StateMachineEvent};
-use crate::types::{ProposalCommitment, ValidatorId};
+use crate::types::{ConsensusError, ProposalCommitment, ValidatorId, ValidatorSet, VotingWeight};
 use crate::votes_threshold::QuorumType;
 
 lazy_static! {
@@ -26,15 +26,32 @@ struct TestWrapper<LeaderFn: Fn(Round) -> ValidatorId> {
 }
 
 impl<LeaderFn: Fn(Round) -> ValidatorId> TestWrapper<LeaderFn> {
+    /// Creates a state machine with `total_weight` validators of weight 1, including `id`.
     pub fn new(
         id: ValidatorId,
         total_weight: u64,
         leader_fn: LeaderFn,
         is_observer: bool,
         quorum_type: QuorumType,
+    ) -> Self {
+        let mut validators = ValidatorSet::from([(id, 1)]);
+        let mut other_id = DEFAULT_VALIDATOR_ID;
+        while u64::try_from(validators.len()).unwrap() < total_weight {
+            validators.entry(other_id.into()).or_insert(1);
+            other_id += 1;
+        }
+        Self::with_validators(id, &validators, leader_fn, is_observer, quorum_type)
+    }
+
+    pub fn with_validators(
+        id: ValidatorId,
+        validators: &ValidatorSet,
+        leader_fn: LeaderFn,
+        is_observer: bool,
+        quorum_type: QuorumType,
     ) -> Self {
         Self {
-            state_machine: StateMachine::new(id, total_weight, is_observer, quorum_type),
+            state_machine: StateMachine::new(id, validators, is_observer, quorum_type),
             leader_fn,
             events: VecDeque::new(),
         }
@@ -57,11 +74,29 @@ impl<LeaderFn: Fn(Round) -> ValidatorId> TestWrapper<LeaderFn> {
     }
 
     pub fn send_prevote(&mut self, proposal_id: Option<ProposalCommitment>, round: Round) {
-        self.send_event(StateMachineEvent::Prevote(proposal_id, round))
+        self.send_weighted_prevote(proposal_id, round, 1)
     }
 
     pub fn send_precommit(&mut self, proposal_id: Option<ProposalCommitment>, round: Round) {
-        self.send_event(StateMachineEvent::Precommit(proposal_id, round))
+        self.send_weighted_precommit(proposal_id, round, 1)
+    }
+
+    pub fn send_weighted_prevote(
+        &mut self,
+        proposal_id: Option<ProposalCommitment>,
+        round: Round,
+        weight: VotingWeight,
+    ) {
+        self.send_vote(StateMachineEvent::Prevote(proposal_id, round), weight)
+    }
+
+    pub fn send_weighted_precommit(
+        &mut self,
+        proposal_id: Option<ProposalCommitment>,
+        round: Round,
+        weight: VotingWeight,
+    ) {
+        self.send_vote(StateMachineEvent::Precommit(proposal_id, round), weight)
     }
 
     pub fn send_timeout_propose(&mut self, round: Round) {
@@ -77,7 +112,12 @@ impl<LeaderFn: Fn(Round) -> ValidatorId> TestWrapper<LeaderFn> {
     }
 
     fn send_event(&mut self, event: StateMachineEvent) {
-        self.events.append(&mut self.state_machine.handle_event(event, &self.leader_fn));
+        self.events.append(&mut self.state_machine.handle_event(event, &self.leader_fn).unwrap());
+    }
+
+    fn send_vote(&mut self, vote: StateMachineEvent, weight: VotingWeight) {
+        self.events
+            .append(&mut self.state_machine.handle_vote(vote, weight, &self.leader_fn).unwrap());
     }
 }
 
@@ -491,3 +531,106 @@ fn number_of_required_votes(quorum_type: QuorumType) {
     );
     assert!(wrapper.next_event().is_none());
 }
+
+#[test]
+fn quorum_is_reached_by_voting_weight() {
+    // The proposer holds most of the stake, so its votes together with ours form a quorum, while
+    // the votes of all the other validators do not.
+    let validators = ValidatorSet::from([
+        (*PROPOSER_ID, 5),
+        (*VALIDATOR_ID, 1),
+        ((DEFAULT_VALIDATOR_ID + 2).into(), 1),
+        ((DEFAULT_VALIDATOR_ID + 3).into(), 1),
+    ]);
+    let mut wrapper = TestWrapper::with_validators(
+        *VALIDATOR_ID,
+        &validators,
+        |_: Round| *PROPOSER_ID,
+        false,
+        QuorumType::Byzantine,
+    );
+
+    wrapper.start();
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPropose(ROUND));
+    wrapper.send_proposal(PROPOSAL_ID, ROUND);
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::Prevote(PROPOSAL_ID, ROUND));
+    assert!(wrapper.next_event().is_none());
+
+    // 3 out of 8.
+    wrapper.send_weighted_prevote(PROPOSAL_ID, ROUND, 1);
+    wrapper.send_weighted_prevote(PROPOSAL_ID, ROUND, 1);
+    assert!(wrapper.next_event().is_none());
+
+    // 8 out of 8.
+    wrapper.send_weighted_prevote(PROPOSAL_ID, ROUND, 5);
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPrevote(ROUND));
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::Precommit(PROPOSAL_ID, ROUND));
+    assert!(wrapper.next_event().is_none());
+
+    // 6 out of 8.
+    wrapper.send_weighted_precommit(PROPOSAL_ID, ROUND, 5);
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPrecommit(ROUND));
+    assert_eq!(
+        wrapper.next_event().unwrap(),
+        StateMachineEvent::Decision(PROPOSAL_ID.unwrap(), ROUND)
+    );
+}
+
+#[test]
+fn restore_resumes_without_voting_again() {
+    let mut wrapper =
+        TestWrapper::new(*VALIDATOR_ID, 4, |_: Round| *PROPOSER_ID, false, QuorumType::Byzantine);
+
+    // Before the restart the node locked on the proposal in round 0 and prevoted for it in round 1.
+    wrapper.state_machine.restore(
+        ROUND + 1,
+        &[StateMachineEvent::Prevote(PROPOSAL_ID, ROUND + 1)],
+        Some((PROPOSAL_ID.unwrap(), ROUND)),
+        Some((PROPOSAL_ID.unwrap(), ROUND)),
+    );
+    wrapper.start();
+    assert_eq!(wrapper.state_machine.round(), ROUND + 1);
+    assert_eq!(wrapper.state_machine.locked_value_round(), Some((PROPOSAL_ID.unwrap(), ROUND)));
+    assert!(wrapper.next_event().is_none());
+
+    // The restored prevote counts towards the quorum.
+    wrapper.send_prevote(None, ROUND + 1);
+    assert!(wrapper.next_event().is_none());
+    wrapper.send_prevote(None, ROUND + 1);
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPrevote(ROUND + 1));
+    assert!(wrapper.next_event().is_none());
+
+    // A proposal arriving after the restart doesn't cause a second prevote in the same round.
+    wrapper.send_proposal(PROPOSAL_ID, ROUND + 1);
+    assert!(wrapper.next_event().is_none());
+}
+
+#[test]
+fn unexpected_events_are_rejected() {
+    let mut wrapper =
+        TestWrapper::new(*VALIDATOR_ID, 4, |_: Round| *PROPOSER_ID, false, QuorumType::Byzantine);
+    wrapper.start();
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::TimeoutPropose(ROUND));
+
+    // Votes must carry the voter's weight.
+    assert!(matches!(
+        wrapper
+            .state_machine
+            .handle_event(StateMachineEvent::Prevote(PROPOSAL_ID, ROUND), &wrapper.leader_fn),
+        Err(ConsensusError::InternalInconsistency(_))
+    ));
+    // Only votes carry a weight.
+    assert!(matches!(
+        wrapper.state_machine.handle_vote(
+            StateMachineEvent::TimeoutPrevote(ROUND),
+            1,
+            &wrapper.leader_fn
+        ),
+        Err(ConsensusError::InternalInconsistency(_))
+    ));
+
+    // The rejected events leave the state machine untouched.
+    wrapper.send_proposal(PROPOSAL_ID, ROUND);
+    assert_eq!(wrapper.next_event().unwrap(), StateMachineEvent::Prevote(PROPOSAL_ID, ROUND));
+    assert!(wrapper.next_event().is_none());
+}
@@ -20,11 +20,13 @@ use tracing::{info, instrument, warn};
 
 use crate::config::StreamHandlerConfig;
 use crate::metrics::{
+    StreamEvictionReason,
     CONSENSUS_INBOUND_STREAM_EVICTED,
     CONSENSUS_INBOUND_STREAM_FINISHED,
     CONSENSUS_INBOUND_STREAM_STARTED,
     CONSENSUS_OUTBOUND_STREAM_FINISHED,
     CONSENSUS_OUTBOUND_STREAM_STARTED,
+    LABEL_NAME_EVICTION_REASON,
 };
 
 #[cfg(test)]
@@ -33,6 +35,7 @@ mod stream_handler_test;
 
 type PeerId = OpaquePeerId;
 type MessageId = u64;
+type StreamKey<StreamId> = (PeerId, StreamId);
 
 /// Errors which cause the stream handler to stop functioning.
 #[derive(thiserror::Error, PartialEq, Debug)]
@@ -98,8 +101,12 @@ struct StreamData<StreamContent: StreamContentTrait, StreamId: StreamIdTrait> {
     // Keep the receiver until it is time to send it to the application.
     receiver: Option<mpsc::Receiver<StreamContent>>,
     sender: mpsc::Sender<StreamContent>,
-    // A buffer for messages that were received out of order.
-    message_buffer: HashMap<MessageId, StreamMessage<StreamContent, StreamId>>,
+    // A buffer for messages that were received out of order, along with their size in bytes.
+    message_buffer: HashMap<MessageId, (StreamMessage<StreamContent, StreamId>, usize)>,
+    // The total size of the messages in `message_buffer`, in bytes.
+    buffered_bytes: usize,
+    // The total size of the messages received on this stream, in bytes.
+    received_bytes: usize,
 }
 
 impl<StreamContent: StreamContentTrait, StreamId: StreamIdTrait>
@@ -114,6 +121,8 @@ impl<StreamContent: StreamContentTrait, StreamId: StreamIdTrait>
             sender,
             receiver: Some(receiver),
             message_buffer: HashMap::new(),
+            buffered_bytes: 0,
+            received_bytes: 0,
         }
     }
 }
@@ -121,6 +130,10 @@ impl<StreamContent: StreamContentTrait, StreamId: StreamIdTrait>
 /// A StreamHandler is responsible for:
 /// - Buffering inbound messages and reporting them to the application in order.
 /// - Sending outbound messages to the network, wrapped in StreamMessage.
+///
+/// The memory used for buffering inbound messages is bounded by the `StreamHandlerConfig`. Streams
+/// which exceed the limits are evicted, and when the limit was exceeded by a single peer, the peer
+/// is reported.
 pub struct StreamHandler<StreamContent, StreamId, InboundReceiverT, OutboundSenderT>
 where
     StreamContent: StreamContentTrait,
@@ -138,7 +151,11 @@ where
     // An LRU cache mapping (peer_id, stream_id) to a struct that contains all the information
     // about the stream. This includes both the message buffer and some metadata
     // (like the latest message ID).
-    inbound_stream_data: LruCache<(PeerId, StreamId), StreamData<StreamContent, StreamId>>,
+    inbound_stream_data: LruCache<StreamKey<StreamId>, StreamData<StreamContent, StreamId>>,
+    // The size of the out of order inbound messages buffered for each peer, in bytes.
+    buffered_bytes_per_peer: HashMap<PeerId, usize>,
+    // The size of the out of order inbound messages buffered for all peers, in bytes.
+    buffered_bytes: usize,
     // Whenever application wants to start a new stream, it must send out a
     // (stream_id, Receiver) pair. Each receiver gets messages that should
     // be sent out to the network.
@@ -177,6 +194,8 @@ where
             inbound_channel_sender,
             inbound_receiver,
             inbound_stream_data: cache,
+            buffered_bytes_per_peer: HashMap::new(),
+            buffered_bytes: 0,
             outbound_channel_receiver,
             outbound_sender,
             outbound_stream_receivers: StreamMap::new(BTreeMap::new()),
@@ -210,7 +229,7 @@ where
             }
             // New inbound message from the network.
             message = self.inbound_receiver.next() => {
-                self.handle_inbound_message(message)
+                self.handle_inbound_message(message).await
             }
         )
     }
@@ -324,7 +343,7 @@ where
     // Handle a message that was received from the network.
     #[instrument(skip_all, level = "warn")]
     #[allow(clippy::type_complexity)]
-    fn handle_inbound_message(
+    async fn handle_inbound_message(
         &mut self,
         message: Option<(
             Result<StreamMessage<StreamContent, StreamId>, ProtobufConversionError>,
@@ -346,7 +365,7 @@ where
         let key = (peer_id.clone(), stream_id.clone());
 
         // Try to get the stream data from the cache.
-        let data = match self.inbound_stream_data.pop(&key) {
+        let mut data = match self.inbound_stream_data.pop(&key) {
             Some(data) => data,
             None => {
                 info!(?peer_id, ?stream_id, "Inbound stream started");
@@ -354,27 +373,52 @@ where
                 StreamData::new(self.config.channel_buffer_capacity)
             }
         };
-        if let Some(data) = self.handle_message_inner(message, metadata, data) {
-            if let Some((evicted_key, _)) = self.inbound_stream_data.push(key, data) {
-                CONSENSUS_INBOUND_STREAM_EVICTED.increment(1);
-                warn!(?evicted_key, "Evicted inbound stream due to capacity");
+        // The stream's buffered messages remain accounted for while the message is handled.
+        let buffered_bytes_before = data.buffered_bytes;
+        match self.handle_message_inner(message, &metadata, &mut data) {
+            Ok(true) => {
+                self.update_buffered_bytes(&peer_id, buffered_bytes_before, data.buffered_bytes);
+                if let Some((evicted_key, evicted_data)) = self.inbound_stream_data.push(key, data)
+                {
+                    self.evict(evicted_key, evicted_data, StreamEvictionReason::MaxStreams);
+                }
+            }
+            Ok(false) => self.update_buffered_bytes(&peer_id, buffered_bytes_before, 0),
+            Err(reason) => {
+                self.evict(key, data, reason);
+                if reason.is_peer_fault() {
+                    if let Err(e) = self.outbound_sender.report_peer(metadata).await {
+                        warn!(?peer_id, "Failed to report peer: {e:?}");
+                    }
+                }
             }
         }
         Ok(())
     }
 
-    /// Returns the StreamData struct if it should be put back into the LRU cache. None if the data
-    /// should be dropped.
+    /// Returns whether the stream should be put back into the LRU cache, or the reason to evict it
+    /// if it exceeded the limits. Evicted streams are left unchanged.
     fn handle_message_inner(
         &mut self,
         message: StreamMessage<StreamContent, StreamId>,
-        metadata: BroadcastedMessageMetadata,
-        mut data: StreamData<StreamContent, StreamId>,
-    ) -> Option<StreamData<StreamContent, StreamId>> {
-        let peer_id = metadata.originator_id;
+        metadata: &BroadcastedMessageMetadata,
+        data: &mut StreamData<StreamContent, StreamId>,
+    ) -> Result<bool, StreamEvictionReason> {
+        let peer_id = metadata.originator_id.clone();
         let stream_id = message.stream_id.clone();
         let key = (peer_id.clone(), stream_id.clone());
         let message_id = message.message_id;
+        let message_size = metadata.encoded_message_length;
+
+        let received_bytes = data.received_bytes.saturating_add(message_size);
+        if received_bytes > self.config.max_stream_size {
+            warn!(?key, received_bytes, "Inbound stream exceeded the maximum stream size.");
+            return Err(StreamEvictionReason::StreamSize);
+        }
+        if message_id > data.next_message_id {
+            self.check_buffer_limits(&key, message_id - data.next_message_id, message_size)?;
+        }
+        data.received_bytes = received_bytes;
 
         if data.max_message_id_received < message_id {
             data.max_message_id_received = message_id;
@@ -394,7 +438,7 @@ where
                         message_id,
                         data.max_message_id_received
                     );
-                    return None;
+                    return Ok(false);
                 }
             }
         }
@@ -408,15 +452,15 @@ where
                 message_id,
                 data.fin_message_id.unwrap_or(u64::MAX)
             );
-            return None;
+            return Ok(false);
         }
 
         // This means we can just send the message without buffering it.
         match message_id.cmp(&data.next_message_id) {
             Ordering::Equal => {
-                let mut receiver_dropped = self.inbound_send(&mut data, message);
+                let mut receiver_dropped = self.inbound_send(data, message);
                 if !receiver_dropped {
-                    receiver_dropped = self.process_buffer(&mut data);
+                    receiver_dropped = self.process_buffer(data);
                 }
 
                 if data.message_buffer.is_empty() && data.fin_message_id.is_some()
@@ -425,11 +469,11 @@ where
                     data.sender.close_channel();
                     CONSENSUS_INBOUND_STREAM_FINISHED.increment(1);
                     info!(?peer_id, ?stream_id, "Inbound stream finished.");
-                    return None;
+                    return Ok(false);
                 }
             }
             Ordering::Greater => {
-                Self::store(&mut data, key.clone(), message);
+                Self::store(data, key.clone(), message, message_size);
             }
             Ordering::Less => {
                 // TODO(guyn): replace warnings with more graceful error handling
@@ -440,23 +484,86 @@ where
                     message_id,
                     data.next_message_id
                 );
-                return None;
+                return Ok(false);
             }
         }
-        Some(data)
+        Ok(true)
+    }
+
+    // Checks whether a message which is `gap` messages ahead of the next expected message in the
+    // stream, and is `message_size` bytes long, can be buffered. If all peers together buffer too
+    // much, makes room by evicting the least recently used streams of other peers.
+    fn check_buffer_limits(
+        &mut self,
+        key: &StreamKey<StreamId>,
+        gap: u64,
+        message_size: usize,
+    ) -> Result<(), StreamEvictionReason> {
+        if gap > self.config.max_message_gap {
+            warn!(?key, gap, "Inbound message is too far ahead of the next expected message.");
+            return Err(StreamEvictionReason::MessageGap);
+        }
+        let peer_buffered_bytes = self.buffered_bytes_per_peer.get(&key.0).copied().unwrap_or(0);
+        if peer_buffered_bytes.saturating_add(message_size)
+            > self.config.max_buffered_bytes_per_peer
+        {
+            warn!(?key, peer_buffered_bytes, "Peer exceeded the maximum buffered bytes.");
+            return Err(StreamEvictionReason::PeerBufferSize);
+        }
+        while self.buffered_bytes.saturating_add(message_size) > self.config.max_buffered_bytes {
+            let Some(lru_key) = self
+                .inbound_stream_data
+                .iter()
+                .rev()
+                .find(|(other_key, data)| other_key.0 != key.0 && data.buffered_bytes > 0)
+                .map(|(other_key, _)| other_key.clone())
+            else {
+                warn!(?key, "Not enough room to buffer the inbound message.");
+                return Err(StreamEvictionReason::TotalBufferSize);
+            };
+            let lru_data =
+                self.inbound_stream_data.pop(&lru_key).expect("The stream was just found.");
+            self.evict(lru_key, lru_data, StreamEvictionReason::TotalBufferSize);
+        }
+        Ok(())
+    }
+
+    fn update_buffered_bytes(&mut self, peer_id: &PeerId, before: usize, after: usize) {
+        self.buffered_bytes = self.buffered_bytes - before + after;
+        let peer_buffered_bytes = self.buffered_bytes_per_peer.entry(peer_id.clone()).or_default();
+        *peer_buffered_bytes = *peer_buffered_bytes - before + after;
+        if *peer_buffered_bytes == 0 {
+            self.buffered_bytes_per_peer.remove(peer_id);
+        }
+    }
+
+    // Drops a stream which was taken out of the LRU cache. If the application already got the
+    // stream's receiver, it is closed, and the application sees the stream end early.
+    fn evict(
+        &mut self,
+        key: StreamKey<StreamId>,
+        data: StreamData<StreamContent, StreamId>,
+        reason: StreamEvictionReason,
+    ) {
+        self.update_buffered_bytes(&key.0, data.buffered_bytes, 0);
+        CONSENSUS_INBOUND_STREAM_EVICTED
+            .increment(1, &[(LABEL_NAME_EVICTION_REASON, reason.into())]);
+        warn!(?key, ?reason, "Evicted inbound stream.");
     }
 
     // Store an inbound message in the buffer.
     fn store(
         data: &mut StreamData<StreamContent, StreamId>,
-        key: (PeerId, StreamId),
+        key: StreamKey<StreamId>,
         message: StreamMessage<StreamContent, StreamId>,
+        message_size: usize,
     ) {
         let message_id = message.message_id;
 
         match data.message_buffer.entry(message_id) {
             Vacant(e) => {
-                e.insert(message);
+                e.insert((message, message_size));
+                data.buffered_bytes += message_size;
             }
             Occupied(_) => {
                 // TODO(guyn): replace warnings with more graceful error handling
@@ -472,7 +579,9 @@ where
     // DOES NOT guarantee that the buffer will be empty after calling this function.
     // Returns true if the receiver for this stream is dropped.
     fn process_buffer(&mut self, data: &mut StreamData<StreamContent, StreamId>) -> bool {
-        while let Some(message) = data.message_buffer.remove(&data.next_message_id) {
+        while let Some((message, message_size)) = data.message_buffer.remove(&data.next_message_id)
+        {
+            data.buffered_bytes -= message_size;
             if self.inbound_send(data, message) {
                 return true;
             }
@@ -2,10 +2,10 @@ use std::collections::BTreeSet;
 use std::fmt::Display;
 
 use apollo_network::network_manager::{BroadcastTopicClientTrait, ReceivedBroadcastedMessage};
-use apollo_network_types::network_types::BroadcastedMessageMetadata;
+use apollo_network_types::network_types::{BroadcastedMessageMetadata, OpaquePeerId};
+use apollo_network_types::test_utils::get_peer_id;
 use apollo_protobuf::consensus::{ProposalInit, ProposalPart, StreamMessageBody};
 use apollo_protobuf::converters::ProtobufConversionError;
-use apollo_test_utils::{get_rng, GetTestInstance};
 use futures::channel::mpsc::{self, Receiver, SendError, Sender};
 use futures::{FutureExt, SinkExt, StreamExt};
 use prost::DecodeError;
@@ -14,6 +14,12 @@ use crate::config::StreamHandlerConfig;
 use crate::stream_handler::StreamHandler;
 const CHANNEL_CAPACITY: usize = 100;
 const MAX_STREAMS: usize = 10;
+const MESSAGE_SIZE: usize = 10;
+const MAX_MESSAGE_GAP: u64 = 20;
+// Limits in units of MESSAGE_SIZE.
+const MAX_STREAM_MESSAGES: usize = 50;
+const MAX_BUFFERED_MESSAGES_PER_PEER: usize = 30;
+const MAX_BUFFERED_MESSAGES: usize = 40;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct TestStreamId(u64);
@@ -58,6 +64,7 @@ type StreamMessage = apollo_protobuf::consensus::StreamMessage<ProposalPart, Tes
 
 struct FakeBroadcastClient {
     sender: Sender<StreamMessage>,
+    reported_peers: Vec<OpaquePeerId>,
 }
 
 #[async_trait::async_trait]
@@ -66,8 +73,9 @@ impl BroadcastTopicClientTrait<StreamMessage> for FakeBroadcastClient {
         self.sender.send(message).await
     }
 
-    async fn report_peer(&mut self, _: BroadcastedMessageMetadata) -> Result<(), SendError> {
-        todo!()
+    async fn report_peer(&mut self, metadata: BroadcastedMessageMetadata) -> Result<(), SendError> {
+        self.reported_peers.push(metadata.originator_id);
+        Ok(())
     }
 
     async fn continue_propagation(
@@ -97,9 +105,16 @@ fn setup() -> (
         mpsc::channel(CHANNEL_CAPACITY);
     let (outbound_internal_sender, outbound_internal_receiver) = mpsc::channel(CHANNEL_CAPACITY);
     let (outbound_network_sender, outbound_network_receiver) = mpsc::channel(CHANNEL_CAPACITY);
-    let outbound_network_sender = FakeBroadcastClient { sender: outbound_network_sender };
-    let config =
-        StreamHandlerConfig { channel_buffer_capacity: CHANNEL_CAPACITY, max_streams: MAX_STREAMS };
+    let outbound_network_sender =
+        FakeBroadcastClient { sender: outbound_network_sender, reported_peers: Vec::new() };
+    let config = StreamHandlerConfig {
+        channel_buffer_capacity: CHANNEL_CAPACITY,
+        max_streams: MAX_STREAMS,
+        max_message_gap: MAX_MESSAGE_GAP,
+        max_stream_size: MAX_STREAM_MESSAGES * MESSAGE_SIZE,
+        max_buffered_bytes_per_peer: MAX_BUFFERED_MESSAGES_PER_PEER * MESSAGE_SIZE,
+        max_buffered_bytes: MAX_BUFFERED_MESSAGES * MESSAGE_SIZE,
+    };
     let stream_handler = StreamHandler::new(
         config,
         inbound_internal_sender,
@@ -117,6 +132,14 @@ fn setup() -> (
     )
 }
 
+// Metadata of a message of MESSAGE_SIZE bytes, sent by the peer with the given index.
+fn metadata(peer_index: u8) -> BroadcastedMessageMetadata {
+    BroadcastedMessageMetadata {
+        originator_id: OpaquePeerId::private_new(get_peer_id(peer_index)),
+        encoded_message_length: MESSAGE_SIZE,
+    }
+}
+
 fn build_init_message(round: u32, stream_id: u64, message_id: u32) -> StreamMessage {
     StreamMessage {
         message: StreamMessageBody::Content(ProposalPart::Init(ProposalInit {
@@ -252,7 +275,7 @@ async fn inbound_in_order() {
         _client_to_streamhandler_sender,
         _streamhandler_to_network_receiver,
     ) = setup();
-    let metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let metadata = metadata(0);
 
     // Send all messages in order.
     for i in 0..num_messages {
@@ -284,7 +307,7 @@ async fn lru_cache_for_inbound_streams() {
         _streamhandler_to_network_receiver,
     ) = setup();
 
-    let metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let metadata = metadata(0);
     for i in 0..num_streams {
         let message = build_fin_message(i.try_into().unwrap(), 1);
         network_to_streamhandler_sender.send((Ok(message), metadata.clone())).await.unwrap();
@@ -326,7 +349,7 @@ async fn inbound_multiple() {
         _client_to_streamhandler_sender,
         _streamhandler_to_network_receiver,
     ) = setup();
-    let metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let metadata = metadata(0);
 
     // Send all messages to all streams, each stream's messages in order.
     for sid in 0..num_streams {
@@ -370,7 +393,7 @@ async fn inbound_delayed_first() {
         _client_to_streamhandler_sender,
         _streamhandler_to_network_receiver,
     ) = setup();
-    let metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let metadata = metadata(0);
 
     // Send all messages besides first one.
     for i in 1..num_messages {
@@ -414,7 +437,7 @@ async fn inbound_delayed_middle() {
         _client_to_streamhandler_sender,
         _streamhandler_to_network_receiver,
     ) = setup();
-    let metadata = BroadcastedMessageMetadata::get_test_instance(&mut get_rng());
+    let metadata = metadata(0);
 
     // Send all messages besides one in the middle of the stream.
     for i in 0..num_messages {
@@ -451,3 +474,167 @@ async fn inbound_delayed_middle() {
     // Check that the receiver was closed:
     assert!(matches!(receiver.try_next(), Ok(None)));
 }
+
+#[tokio::test]
+async fn inbound_message_too_far_ahead_evicts_stream() {
+    let stream_id = 127;
+    let (
+        mut stream_handler,
+        mut network_to_streamhandler_sender,
+        mut streamhandler_to_client_receiver,
+        _client_to_streamhandler_sender,
+        _streamhandler_to_network_receiver,
+    ) = setup();
+
+    // A message exactly MAX_MESSAGE_GAP ahead is buffered.
+    let message = build_init_message(0, stream_id, u32::try_from(MAX_MESSAGE_GAP).unwrap());
+    network_to_streamhandler_sender.send((Ok(message), metadata(0))).await.unwrap();
+    stream_handler.handle_next_msg().await.unwrap();
+    assert_eq!(stream_handler.buffered_bytes, MESSAGE_SIZE);
+    assert!(stream_handler.outbound_sender.reported_peers.is_empty());
+
+    // A message further ahead evicts the stream.
+    let message = build_init_message(0, stream_id, u32::try_from(MAX_MESSAGE_GAP + 1).unwrap());
+    network_to_streamhandler_sender.send((Ok(message), metadata(0))).await.unwrap();
+    stream_handler.handle_next_msg().await.unwrap();
+    assert_eq!(stream_handler.buffered_bytes, 0);
+    assert_eq!(stream_handler.outbound_sender.reported_peers, vec![metadata(0).originator_id]);
+
+    // The buffered message was dropped along with the stream.
+    network_to_streamhandler_sender
+        .send((Ok(build_init_message(0, stream_id, 0)), metadata(0)))
+        .await
+        .unwrap();
+    stream_handler.handle_next_msg().await.unwrap();
+    let mut receiver = streamhandler_to_client_receiver.next().now_or_never().unwrap().unwrap();
+    assert_eq!(
+        receiver.next().await.unwrap(),
+        ProposalPart::Init(ProposalInit { round: 0, ..Default::default() })
+    );
+    assert!(receiver.try_next().is_err());
+}
+
+#[tokio::test]
+async fn inbound_stream_too_large_is_evicted() {
+    let stream_id = 127;
+    let (
+        mut stream_handler,
+        mut network_to_streamhandler_sender,
+        mut streamhandler_to_client_receiver,
+        _client_to_streamhandler_sender,
+        _streamhandler_to_network_receiver,
+    ) = setup();
+
+    let num_messages = u32::try_from(MAX_STREAM_MESSAGES).unwrap();
+    for i in 0..=num_messages {
+        let message = build_init_message(i, stream_id, i);
+        network_to_streamhandler_sender.send((Ok(message), metadata(0))).await.unwrap();
+        stream_handler.handle_next_msg().await.unwrap();
+    }
+
+    // Only the messages within the size limit are delivered, then the stream is closed.
+    let mut receiver = streamhandler_to_client_receiver.next().now_or_never().unwrap().unwrap();
+    for i in 0..num_messages {
+        let message = receiver.next().await.unwrap();
+        assert_eq!(message, ProposalPart::Init(ProposalInit { round: i, ..Default::default() }));
+    }
+    assert!(matches!(receiver.try_next(), Ok(None)));
+    assert_eq!(stream_handler.outbound_sender.reported_peers, vec![metadata(0).originator_id]);
+}
+
+#[tokio::test]
+async fn peer_exceeding_buffer_limit_is_evicted() {
+    let (
+        mut stream_handler,
+        mut network_to_streamhandler_sender,
+        mut streamhandler_to_client_receiver,
+        _client_to_streamhandler_sender,
+        _streamhandler_to_network_receiver,
+    ) = setup();
+
+    // The peer fills its buffer limit with out of order messages on two streams.
+    let first_stream_messages = u32::try_from(MAX_BUFFERED_MESSAGES_PER_PEER / 2).unwrap();
+    let second_stream_messages =
+        u32::try_from(MAX_BUFFERED_MESSAGES_PER_PEER).unwrap() - first_stream_messages;
+    for (stream_id, num_messages) in [(1, first_stream_messages), (2, second_stream_messages)] {
+        for i in 1..=num_messages {
+            let message = build_init_message(i, stream_id, i);
+            network_to_streamhandler_sender.send((Ok(message), metadata(0))).await.unwrap();
+            stream_handler.handle_next_msg().await.unwrap();
+        }
+    }
+    assert!(stream_handler.outbound_sender.reported_peers.is_empty());
+
+    // Buffering another message evicts the stream it was sent on.
+    let message = build_init_message(0, 2, second_stream_messages + 1);
+    network_to_streamhandler_sender.send((Ok(message), metadata(0))).await.unwrap();
+    stream_handler.handle_next_msg().await.unwrap();
+    assert_eq!(stream_handler.outbound_sender.reported_peers, vec![metadata(0).originator_id]);
+    assert_eq!(
+        stream_handler.buffered_bytes,
+        usize::try_from(first_stream_messages).unwrap() * MESSAGE_SIZE
+    );
+
+    // The other stream is intact.
+    network_to_streamhandler_sender
+        .send((Ok(build_init_message(0, 1, 0)), metadata(0)))
+        .await
+        .unwrap();
+    stream_handler.handle_next_msg().await.unwrap();
+    let mut receiver = streamhandler_to_client_receiver.next().now_or_never().unwrap().unwrap();
+    for i in 0..=first_stream_messages {
+        let message = receiver.next().await.unwrap();
+        assert_eq!(message, ProposalPart::Init(ProposalInit { round: i, ..Default::default() }));
+    }
+    assert_eq!(stream_handler.buffered_bytes, 0);
+}
+
+#[tokio::test]
+async fn total_buffer_limit_evicts_least_recently_used_stream() {
+    let (
+        mut stream_handler,
+        mut network_to_streamhandler_sender,
+        mut streamhandler_to_client_receiver,
+        _client_to_streamhandler_sender,
+        _streamhandler_to_network_receiver,
+    ) = setup();
+
+    // Two peers fill the total buffer limit with out of order messages.
+    let num_messages = u32::try_from(MAX_BUFFERED_MESSAGES / 2).unwrap();
+    for peer in 0..2 {
+        for i in 1..=num_messages {
+            let message = build_init_message(i, u64::from(peer), i);
+            network_to_streamhandler_sender.send((Ok(message), metadata(peer))).await.unwrap();
+            stream_handler.handle_next_msg().await.unwrap();
+        }
+    }
+
+    // A third peer buffering a message evicts the least recently used stream, of the first peer.
+    let message = build_init_message(1, 2, 1);
+    network_to_streamhandler_sender.send((Ok(message), metadata(2))).await.unwrap();
+    stream_handler.handle_next_msg().await.unwrap();
+    // No single peer exceeded its limit.
+    assert!(stream_handler.outbound_sender.reported_peers.is_empty());
+    assert_eq!(
+        stream_handler.buffered_bytes,
+        (usize::try_from(num_messages).unwrap() + 1) * MESSAGE_SIZE
+    );
+
+    // The second peer's stream is intact, while the first peer's stream starts over.
+    for peer in [1, 0] {
+        let message = build_init_message(0, u64::from(peer), 0);
+        network_to_streamhandler_sender.send((Ok(message), metadata(peer))).await.unwrap();
+        stream_handler.handle_next_msg().await.unwrap();
+    }
+    let mut receiver = streamhandler_to_client_receiver.next().now_or_never().unwrap().unwrap();
+    for i in 0..=num_messages {
+        let message = receiver.next().await.unwrap();
+        assert_eq!(message, ProposalPart::Init(ProposalInit { round: i, ..Default::default() }));
+    }
+    let mut receiver = streamhandler_to_client_receiver.next().now_or_never().unwrap().unwrap();
+    assert_eq!(
+        receiver.next().await.unwrap(),
+        ProposalPart::Init(ProposalInit { round: 0, ..Default::default() })
+    );
+    assert!(receiver.try_next().is_err());
+}
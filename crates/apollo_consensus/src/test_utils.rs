@@ -8,7 +8,15 @@ use mockall::mock;
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_types_core::felt::Felt;
 
-use crate::types::{ConsensusContext, ConsensusError, ProposalCommitment, Round, ValidatorId};
+use crate::evidence::Evidence;
+use crate::types::{
+    ConsensusContext,
+    ConsensusError,
+    ProposalCommitment,
+    Round,
+    ValidatorId,
+    ValidatorSet,
+};
 
 /// Define a consensus block which can be used to enable auto mocking Context.
 #[derive(Debug, PartialEq, Clone)]
@@ -78,12 +86,16 @@ mock! {
             init: ProposalInit,
         );
 
-        async fn validators(&self, height: BlockNumber) -> Vec<ValidatorId>;
+        async fn validators(&self, height: BlockNumber) -> Result<ValidatorSet, ConsensusError>;
 
         fn proposer(&self, height: BlockNumber, round: Round) -> ValidatorId;
 
         async fn broadcast(&mut self, message: Vote) -> Result<(), ConsensusError>;
 
+        async fn sign_vote(&self, vote: Vote) -> Result<Vote, ConsensusError>;
+
+        fn verify_vote(&self, vote: &Vote) -> bool;
+
         async fn decision_reached(
             &mut self,
             block: ProposalCommitment,
@@ -93,17 +105,26 @@ mock! {
         async fn try_sync(&mut self, height: BlockNumber) -> bool;
 
         async fn set_height_and_round(&mut self, height: BlockNumber, round: Round);
+
+        async fn report_evidence(&mut self, evidence: Evidence);
     }
 }
 
+/// Sets up the context to sign votes by returning them as is, and to accept all received votes as
+/// validly signed.
+pub fn expect_vote_signing(context: &mut MockTestContext) {
+    context.expect_sign_vote().returning(Ok);
+    context.expect_verify_vote().returning(|_| true);
+}
+
 pub fn prevote(block_felt: Option<Felt>, height: u64, round: u32, voter: ValidatorId) -> Vote {
     let block_hash = block_felt.map(BlockHash);
-    Vote { vote_type: VoteType::Prevote, height, round, block_hash, voter }
+    Vote { vote_type: VoteType::Prevote, height, round, block_hash, voter, signature: None }
 }
 
 pub fn precommit(block_felt: Option<Felt>, height: u64, round: u32, voter: ValidatorId) -> Vote {
     let block_hash = block_felt.map(BlockHash);
-    Vote { vote_type: VoteType::Precommit, height, round, block_hash, voter }
+    Vote { vote_type: VoteType::Precommit, height, round, block_hash, voter, signature: None }
 }
 pub fn proposal_init(height: u64, round: u32, proposer: ValidatorId) -> ProposalInit {
     ProposalInit { height: BlockNumber(height), round, proposer, ..Default::default() }
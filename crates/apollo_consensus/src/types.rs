@@ -1,4 +1,5 @@
 //! Types for interfacing between consensus and the node.
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::time::Duration;
 
@@ -15,6 +16,8 @@ use futures::channel::{mpsc, oneshot};
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::core::ContractAddress;
 
+use crate::evidence::Evidence;
+
 /// Used to identify the node by consensus.
 /// 1. This ID is derived from the id registered with Starknet's L2 staking contract.
 /// 2. We must be able to derive the public key associated with this ID for the sake of validating
@@ -23,6 +26,11 @@ use starknet_api::core::ContractAddress;
 pub type ValidatorId = ContractAddress;
 pub type Round = u32;
 pub type ProposalCommitment = BlockHash;
+/// The voting power of a validator, derived from its stake.
+pub type VotingWeight = u128;
+/// The validators for a given height, mapped to their voting weight. Ordered so that proposer
+/// selection based on it is deterministic across nodes.
+pub type ValidatorSet = BTreeMap<ValidatorId, VotingWeight>;
 
 /// Interface for consensus to call out to the node.
 ///
@@ -89,23 +97,31 @@ pub trait ConsensusContext {
     /// - `init`: The `ProposalInit` that is broadcast to the network.
     async fn repropose(&mut self, id: ProposalCommitment, init: ProposalInit);
 
-    /// Get the set of validators for a given height. These are the nodes that can propose and vote
-    /// on blocks.
-    // TODO(matan): We expect this to change in the future to BTreeMap. Why?
-    // 1. Map - The nodes will have associated information (e.g. voting weight).
-    // 2. BTreeMap - We want a stable ordering of the nodes for deterministic leader selection.
-    async fn validators(&self, height: BlockNumber) -> Vec<ValidatorId>;
+    /// Get the set of validators for a given height, along with their voting weights. These are the
+    /// nodes that can propose and vote on blocks. Consensus can't run the height without them, so
+    /// an error stops it.
+    async fn validators(&self, height: BlockNumber) -> Result<ValidatorSet, ConsensusError>;
 
-    /// Calculates the ID of the Proposer based on the inputs.
+    /// Calculates the ID of the Proposer based on the inputs. Must be deterministic, and is
+    /// expected to select validators in proportion to their voting weight. Only called for heights
+    /// whose validators were fetched.
     // TODO(matan): Consider passing the validator set in order to keep this sync.
     fn proposer(&self, height: BlockNumber, round: Round) -> ValidatorId;
 
     async fn broadcast(&mut self, message: Vote) -> Result<(), ConsensusError>;
 
+    /// Sign a vote which this node is about to send. Returns the same vote with its signature set.
+    async fn sign_vote(&self, vote: Vote) -> Result<Vote, ConsensusError>;
+
+    /// Verify that a vote received from the network is signed by its voter. Returns false if the
+    /// signature is missing, invalid, or the voter's public key is unknown.
+    fn verify_vote(&self, vote: &Vote) -> bool;
+
     /// Update the context that a decision has been reached for a given height.
     /// - `block` identifies the decision.
     /// - `precommits` - All precommits must be for the same `(block, height, round)` and form a
-    ///   quorum (>2/3 of the voting power) for this height.
+    ///   quorum (>2/3 of the voting power) for this height. Each precommit is signed by its voter,
+    ///   so together they serve as a verifiable commit certificate.
     async fn decision_reached(
         &mut self,
         block: ProposalCommitment,
@@ -119,6 +135,10 @@ pub trait ConsensusContext {
     /// Update the context with the current height and round.
     /// Must be called at the beginning of each height.
     async fn set_height_and_round(&mut self, height: BlockNumber, round: Round);
+
+    /// Report that a validator equivocated. Called once per piece of evidence added to the
+    /// [`EvidencePool`](crate::evidence::EvidencePool).
+    async fn report_evidence(&mut self, evidence: Evidence);
 }
 
 #[derive(PartialEq, Debug)]
@@ -163,6 +183,9 @@ pub enum ConsensusError {
     InternalInconsistency(String),
     #[error("Block info conversion error: {0}")]
     BlockInfoConversion(#[from] starknet_api::StarknetApiError),
+    // Failed to read or write the consensus write-ahead log.
+    #[error("{0}")]
+    WalError(String),
     #[error("{0}")]
     Other(String),
 }
@@ -1,17 +1,19 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::VotingWeight;
+
 #[cfg(test)]
 #[path = "votes_threshold_test.rs"]
 mod votes_threshold_test;
 
-/// Represents a threshold for the number of votes (out of total votes) required to meet a quorum.
-/// For example, a threshold of 2/3 means that more than 2/3 of the total votes must be in favor.
-/// Note that if the number of votes is exactly equal to the denominator, the threshold is not met.
-/// If the total number of votes is zero, the threshold is not met.
+/// Represents a threshold for the voting weight (out of the total weight) required to meet a
+/// quorum. For example, a threshold of 2/3 means that more than 2/3 of the total weight must be in
+/// favor. Note that if the weight is exactly equal to the threshold, the threshold is not met.
+/// If the total weight is zero, the threshold is not met.
 #[derive(Serialize, Deserialize)]
 pub struct VotesThreshold {
-    numerator: u64,
-    denominator: u64,
+    numerator: VotingWeight,
+    denominator: VotingWeight,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -29,7 +31,7 @@ pub const ROUND_SKIP_THRESHOLD: VotesThreshold = VotesThreshold::new(1, 3);
 pub const HONEST_QUORUM: VotesThreshold = VotesThreshold::new(1, 2);
 
 impl VotesThreshold {
-    const fn new(numerator: u64, denominator: u64) -> Self {
+    const fn new(numerator: VotingWeight, denominator: VotingWeight) -> Self {
         assert!(denominator > 0, "Denominator must be greater than zero");
         assert!(denominator >= numerator, "Denominator must be greater than or equal to numerator");
         Self { numerator, denominator }
@@ -42,7 +44,7 @@ impl VotesThreshold {
         }
     }
 
-    pub fn is_met(&self, amount: u64, total: u64) -> bool {
+    pub fn is_met(&self, amount: VotingWeight, total: VotingWeight) -> bool {
         amount.checked_mul(self.denominator).expect("Numeric overflow")
             > total.checked_mul(self.numerator).expect("Numeric overflow")
     }
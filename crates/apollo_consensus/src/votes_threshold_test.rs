@@ -26,3 +26,12 @@ fn votes_threshold_is_met() {
     assert!(!threshold.is_met(2, 3)); // 2 out of 3 votes (not enough, must be above threshold)
     assert!(!threshold.is_met(2, 5)); // 2 out of 5 votes
 }
+
+#[test]
+fn votes_threshold_is_met_with_staking_weights() {
+    // Staking weights are denominated in the token's smallest unit, so they are large.
+    let total = 3_000_000_000_000_000_000_000_000;
+    let threshold = VotesThreshold::new(2, 3);
+    assert!(threshold.is_met(total / 3 * 2 + 1, total));
+    assert!(!threshold.is_met(total / 3 * 2, total));
+}
@@ -0,0 +1,95 @@
+//! Write-ahead log (WAL) for consensus.
+//!
+//! Records the commitments this node makes while running a height: the votes it sends, the values
+//! it locks on (or considers valid) and the proposals it builds. Entries are written (and synced to
+//! disk) before the matching action is taken, so that a node which restarts mid-height can resume
+//! with the same commitments instead of equivocating.
+//!
+//! Opening the WAL for a height discards the entries of older heights. Entries of later heights
+//! (e.g. if the node restarts from an earlier height than it reached) are kept, and returned once
+//! that height is opened.
+
+#[cfg(test)]
+#[path = "wal_test.rs"]
+mod wal_test;
+
+use std::fs::File;
+use std::path::Path;
+
+use apollo_infra_utils::json_lines::{
+    read_records,
+    rewrite_records,
+    write_record,
+    JsonLinesError,
+    JsonLinesResult,
+};
+use apollo_protobuf::consensus::Vote;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+
+use crate::types::{ConsensusError, ProposalCommitment, Round};
+
+/// A commitment made by this node, which must survive a restart.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WalEntry {
+    /// A vote which this node signed and is about to broadcast.
+    Vote(Vote),
+    /// This node started building a proposal for the given round.
+    BuildProposal(Round),
+    /// This node finished building the proposal for the given round, with the given commitment.
+    BuiltProposal(ProposalCommitment, Round),
+    /// The state machine locked on a value in the given round.
+    LockedValue(ProposalCommitment, Round),
+    /// The state machine considers the value as valid, as of the given round.
+    ValidValue(ProposalCommitment, Round),
+}
+
+pub type WalError = JsonLinesError;
+
+pub type WalResult<T> = JsonLinesResult<T>;
+
+impl From<WalError> for ConsensusError {
+    fn from(e: WalError) -> Self {
+        ConsensusError::WalError(e.to_string())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WalRecord {
+    height: BlockNumber,
+    entry: WalEntry,
+}
+
+/// The WAL of a single height. Records are stored one per line, as JSON.
+#[derive(Debug)]
+pub struct ConsensusWal {
+    height: BlockNumber,
+    file: File,
+}
+
+impl ConsensusWal {
+    /// Opens the WAL at `path` for running `height`, creating it if needed.
+    ///
+    /// Returns the entries previously written for `height`, in the order they were written. The
+    /// entries of older heights are discarded, while those of later heights are kept.
+    pub fn open(path: &Path, height: BlockNumber) -> WalResult<(Self, Vec<WalEntry>)> {
+        let records: Vec<WalRecord> = read_records::<WalRecord>(path)?
+            .into_iter()
+            .filter(|record| record.height >= height)
+            .collect();
+        let file = rewrite_records(path, &records)?;
+        let entries = records
+            .into_iter()
+            .filter(|record| record.height == height)
+            .map(|record| record.entry)
+            .collect();
+        Ok((Self { height, file }, entries))
+    }
+
+    /// Appends an entry to the WAL. Returns only once the entry is persisted.
+    pub fn append(&mut self, entry: WalEntry) -> WalResult<()> {
+        write_record(&mut self.file, &WalRecord { height: self.height, entry })?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use apollo_protobuf::consensus::{Vote, VoteType, DEFAULT_VALIDATOR_ID};
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_types_core::felt::Felt;
+use tempfile::TempDir;
+
+use crate::wal::{ConsensusWal, WalEntry};
+
+const BLOCK_HASH: BlockHash = BlockHash(Felt::ONE);
+
+fn vote(height: u64, round: u32) -> WalEntry {
+    WalEntry::Vote(Vote {
+        vote_type: VoteType::Prevote,
+        height,
+        round,
+        block_hash: Some(BLOCK_HASH),
+        voter: DEFAULT_VALIDATOR_ID.into(),
+        signature: None,
+    })
+}
+
+#[test]
+fn entries_survive_reopening() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("wal");
+
+    let (mut wal, entries) = ConsensusWal::open(&path, BlockNumber(1)).unwrap();
+    assert!(entries.is_empty());
+    let written = vec![
+        WalEntry::BuildProposal(0),
+        WalEntry::BuiltProposal(BLOCK_HASH, 0),
+        vote(1, 0),
+        WalEntry::ValidValue(BLOCK_HASH, 0),
+        WalEntry::LockedValue(BLOCK_HASH, 0),
+    ];
+    for entry in written.clone() {
+        wal.append(entry).unwrap();
+    }
+    drop(wal);
+
+    let (_, entries) = ConsensusWal::open(&path, BlockNumber(1)).unwrap();
+    assert_eq!(entries, written);
+}
+
+#[test]
+fn opening_a_new_height_discards_old_entries() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("wal");
+
+    let (mut wal, _) = ConsensusWal::open(&path, BlockNumber(1)).unwrap();
+    wal.append(vote(1, 0)).unwrap();
+    drop(wal);
+
+    let (mut wal, entries) = ConsensusWal::open(&path, BlockNumber(2)).unwrap();
+    assert!(entries.is_empty());
+    wal.append(vote(2, 3)).unwrap();
+    drop(wal);
+
+    let (_, entries) = ConsensusWal::open(&path, BlockNumber(2)).unwrap();
+    assert_eq!(entries, vec![vote(2, 3)]);
+    let (_, entries) = ConsensusWal::open(&path, BlockNumber(1)).unwrap();
+    assert!(entries.is_empty());
+}
+
+#[test]
+fn entries_of_later_heights_are_kept() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("wal");
+
+    let (mut wal, _) = ConsensusWal::open(&path, BlockNumber(3)).unwrap();
+    wal.append(vote(3, 0)).unwrap();
+    drop(wal);
+
+    // Restarting from an earlier height doesn't discard the entries of the later one.
+    let (mut wal, entries) = ConsensusWal::open(&path, BlockNumber(2)).unwrap();
+    assert!(entries.is_empty());
+    wal.append(vote(2, 1)).unwrap();
+    drop(wal);
+
+    let (_, entries) = ConsensusWal::open(&path, BlockNumber(3)).unwrap();
+    assert_eq!(entries, vec![vote(3, 0)]);
+}
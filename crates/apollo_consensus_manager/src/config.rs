@@ -16,6 +16,7 @@ use validator::Validate;
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct ConsensusManagerConfig {
     pub consensus_manager_config: ConsensusConfig,
+    #[validate]
     pub context_config: ContextConfig,
     pub eth_to_strk_oracle_config: EthToStrkOracleConfig,
     pub stream_handler_config: StreamHandlerConfig,
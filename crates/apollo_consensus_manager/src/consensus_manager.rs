@@ -9,6 +9,7 @@ use apollo_batcher_types::batcher_types::RevertBlockInput;
 use apollo_batcher_types::communication::SharedBatcherClient;
 use apollo_class_manager_types::transaction_converter::TransactionConverter;
 use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_consensus::evidence::EvidencePool;
 use apollo_consensus::stream_handler::StreamHandler;
 use apollo_consensus::types::ConsensusError;
 use apollo_consensus::votes_threshold::QuorumType;
@@ -52,6 +53,7 @@ pub struct ConsensusManager {
     pub class_manager_client: SharedClassManagerClient,
     pub signature_manager_client: SharedSignatureManagerClient,
     l1_gas_price_provider: Arc<dyn L1GasPriceProviderClient>,
+    evidence_pool: EvidencePool,
 }
 
 impl ConsensusManager {
@@ -63,6 +65,12 @@ impl ConsensusManager {
         signature_manager_client: SharedSignatureManagerClient,
         l1_gas_price_provider: Arc<dyn L1GasPriceProviderClient>,
     ) -> Self {
+        let max_evidence = config.consensus_manager_config.max_evidence;
+        let evidence_pool = match &config.consensus_manager_config.evidence_path {
+            Some(evidence_path) => EvidencePool::open(evidence_path, max_evidence)
+                .unwrap_or_else(|e| panic!("Failed to open the consensus evidence file: {e}")),
+            None => EvidencePool::new(max_evidence),
+        };
         Self {
             config,
             batcher_client,
@@ -70,9 +78,15 @@ impl ConsensusManager {
             class_manager_client,
             signature_manager_client,
             l1_gas_price_provider,
+            evidence_pool,
         }
     }
 
+    /// Evidence of validators equivocating, collected by consensus.
+    pub fn evidence_pool(&self) -> EvidencePool {
+        self.evidence_pool.clone()
+    }
+
     pub async fn run(&self) -> Result<(), ConsensusError> {
         if self.config.revert_config.should_revert {
             self.revert_batcher_blocks(self.config.revert_config.revert_up_to_and_including).await;
@@ -157,6 +171,7 @@ impl ConsensusManager {
                     self.config.context_config.chain_id.clone(),
                 )),
                 state_sync_client: Arc::clone(&self.state_sync_client),
+                class_manager_client: Arc::clone(&self.class_manager_client),
                 batcher: Arc::clone(&self.batcher_client),
                 cende_ambassador: Arc::new(CendeAmbassador::new(
                     self.config.cende_config.clone(),
@@ -169,6 +184,7 @@ impl ConsensusManager {
                 clock: Arc::new(DefaultClock),
                 outbound_proposal_sender: outbound_internal_sender,
                 vote_broadcast_client: votes_broadcast_channels.broadcast_topic_client.clone(),
+                signature_manager_client: Arc::clone(&self.signature_manager_client),
             },
         );
 
@@ -188,6 +204,9 @@ impl ConsensusManager {
             timeouts: self.config.consensus_manager_config.timeouts.clone(),
             sync_retry_interval: self.config.consensus_manager_config.sync_retry_interval,
             quorum_type,
+            wal_path: self.config.consensus_manager_config.wal_path.clone(),
+            evidence_pool: self.evidence_pool.clone(),
+            clock: Arc::new(DefaultClock),
         };
         let consensus_fut = apollo_consensus::run_consensus(
             run_consensus_args,
@@ -2,23 +2,41 @@ use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::time::Duration;
 
-use apollo_config::converters::deserialize_milliseconds_to_duration;
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::converters::{
+    deserialize_milliseconds_to_duration,
+    deserialize_vec,
+    serialize_slice,
+};
+use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::{ChainId, ContractAddress};
-use validator::Validate;
+use starknet_types_core::felt::Felt;
+use validator::{Validate, ValidationError};
 
 const GWEI_FACTOR: u128 = u128::pow(10, 9);
 const ETH_FACTOR: u128 = u128::pow(10, 18);
 
 /// Configuration for the Context struct.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Validate)]
+#[validate(schema(function = "validate_context_config"))]
 pub struct ContextConfig {
     /// Buffer size for streaming outbound proposals.
     pub proposal_buffer_size: usize,
     /// The number of validators.
     pub num_validators: u64,
+    /// The public keys of the validators, ordered by their ids, used to verify their votes. Unused
+    /// if the committees are read from the staking contract.
+    #[serde(deserialize_with = "deserialize_vec")]
+    pub validator_public_keys: Vec<Felt>,
+    /// The address of the staking contract from which the committee of each epoch is read. If
+    /// unset, the configured validators form the committee, with equal weights.
+    pub staking_contract_address: Option<ContractAddress>,
+    /// The maximal number of stakers in a committee read from the staking contract.
+    pub committee_size: usize,
+    /// How many heights back is the block whose hash seeds the proposer selection. Should be at
+    /// least the number of blocks the block hash of which is unknown to the execution.
+    pub proposer_prediction_window_in_heights: u64,
     /// The chain id of the Starknet chain.
     pub chain_id: ChainId,
     /// Maximum allowed deviation (seconds) of a proposed block's timestamp from the current time.
@@ -58,7 +76,7 @@ pub struct ContextConfig {
 
 impl SerializeConfig for ContextConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let mut dump = BTreeMap::from_iter([
             ser_param(
                 "proposal_buffer_size",
                 &self.proposal_buffer_size,
@@ -71,6 +89,27 @@ impl SerializeConfig for ContextConfig {
                 "The number of validators.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "validator_public_keys",
+                &serialize_slice(
+                    &self.validator_public_keys.iter().map(Felt::to_hex_string).collect::<Vec<_>>(),
+                ),
+                "Space separated public keys of the validators, ordered by their ids, used to \
+                 verify their votes.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "committee_size",
+                &self.committee_size,
+                "The maximal number of stakers in a committee read from the staking contract.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "proposer_prediction_window_in_heights",
+                &self.proposer_prediction_window_in_heights,
+                "How many heights back is the block whose hash seeds the proposer selection.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "chain_id",
                 &self.chain_id,
@@ -153,7 +192,16 @@ impl SerializeConfig for ContextConfig {
                 "If true, sets STRK gas price to its minimum price from the versioned constants.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+        ]);
+        dump.extend(ser_optional_param(
+            &self.staking_contract_address,
+            ContractAddress::default(),
+            "staking_contract_address",
+            "The address of the staking contract from which the committee of each epoch is read. \
+             If unset, the configured validators form the committee, with equal weights.",
+            ParamPrivacyInput::Public,
+        ));
+        dump
     }
 }
 
@@ -162,6 +210,11 @@ impl Default for ContextConfig {
         Self {
             proposal_buffer_size: 100,
             num_validators: 1,
+            // Zero means unset, like the default private key of the signature manager.
+            validator_public_keys: vec![Felt::ZERO],
+            staking_contract_address: None,
+            committee_size: 100,
+            proposer_prediction_window_in_heights: 10,
             chain_id: ChainId::Mainnet,
             block_timestamp_window_seconds: 1,
             l1_da_mode: true,
@@ -178,3 +231,16 @@ impl Default for ContextConfig {
         }
     }
 }
+
+fn validate_context_config(context_config: &ContextConfig) -> Result<(), ValidationError> {
+    // The committees read from the staking contract carry their own public keys.
+    if context_config.staking_contract_address.is_some() {
+        return Ok(());
+    }
+    let num_public_keys = u64::try_from(context_config.validator_public_keys.len())
+        .expect("Number of public keys should fit in u64.");
+    if num_public_keys != context_config.num_validators {
+        return Err(ValidationError::new("validator_public_keys must have num_validators keys"));
+    }
+    Ok(())
+}
@@ -18,12 +18,15 @@ use apollo_batcher_types::batcher_types::{
 };
 use apollo_batcher_types::communication::{BatcherClient, BatcherClientError};
 use apollo_class_manager_types::transaction_converter::TransactionConverterTrait;
+use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_consensus::evidence::Evidence;
 use apollo_consensus::types::{
     ConsensusContext,
     ConsensusError,
     ProposalCommitment,
     Round,
     ValidatorId,
+    ValidatorSet,
 };
 use apollo_l1_gas_price_types::{
     EthToStrkOracleClientTrait,
@@ -41,11 +44,28 @@ use apollo_protobuf::consensus::{
     Vote,
     DEFAULT_VALIDATOR_ID,
 };
+use apollo_signature_manager::signature_manager::verify_vote_signature;
+use apollo_signature_manager_types::SignatureManagerClient;
+use apollo_staking::committee_provider::{
+    Committee,
+    CommitteeProvider,
+    CommitteeProviderResult,
+    ExecutionContext,
+    HeightCommittee,
+    Staker,
+};
+use apollo_staking::staking_manager::{StakingManager, StakingManagerConfig};
+use apollo_staking::static_committee_provider::StaticCommitteeProvider;
+use apollo_staking::utils::BlockPseudorandomGenerator;
+use apollo_state_reader::sync_state_reader::SyncStateReader;
 use apollo_state_sync_types::communication::{StateSyncClient, StateSyncClientError};
 use apollo_state_sync_types::errors::StateSyncError;
 use apollo_state_sync_types::state_sync_types::SyncBlock;
 use apollo_time::time::Clock;
 use async_trait::async_trait;
+use blockifier::blockifier_versioned_constants::VersionedConstants as BlockifierVersionedConstants;
+use blockifier::bouncer::BouncerConfig;
+use blockifier::context::{BlockContext, ChainInfo};
 use futures::channel::{mpsc, oneshot};
 use futures::SinkExt;
 use num_rational::Ratio;
@@ -59,7 +79,9 @@ use starknet_api::block::{
 };
 use starknet_api::consensus_transaction::InternalConsensusTransaction;
 use starknet_api::core::SequencerContractAddress;
+use starknet_api::crypto::utils::{PublicKey, Signature};
 use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::staking::StakingWeight;
 use starknet_api::transaction::TransactionHash;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
@@ -82,6 +104,13 @@ use crate::validate_proposal::{
 
 type ValidationParams = (BlockNumber, ValidatorId, Duration, mpsc::Receiver<ProposalPart>);
 
+// The number of epochs whose committees read from the staking contract are cached.
+const MAX_CACHED_EPOCHS: usize = 2;
+// How long to wait before retrying to fetch the committee of a height.
+const COMMITTEE_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+// The number of attempts to fetch the committee of a height before consensus stops.
+const COMMITTEE_MAX_ATTEMPTS: usize = 20;
+
 type HeightToIdToContent = BTreeMap<
     BlockNumber,
     BTreeMap<
@@ -140,7 +169,14 @@ impl BuiltProposals {
 pub struct SequencerConsensusContext {
     config: ContextConfig,
     deps: SequencerConsensusContextDeps,
-    validators: Vec<ValidatorId>,
+    // Provides the weighted committee of each epoch, used as the validator set and for proposer
+    // selection. The committees are read from the staking contract if it's configured, and
+    // otherwise consist of the configured validators.
+    static_committee_provider: StaticCommitteeProvider,
+    staking_manager: Option<Arc<Mutex<StakingManager>>>,
+    // The committees of the current height and onwards, fetched when each height starts so that
+    // proposer selection and vote verification don't need to query the state.
+    height_committees: Mutex<BTreeMap<BlockNumber, HeightCommittee>>,
     // Proposal building/validating returns immediately, leaving the actual processing to a spawned
     // task. The spawned task processes the proposal asynchronously and updates the
     // valid_proposals map upon completion, ensuring consistency across tasks.
@@ -167,6 +203,8 @@ pub struct SequencerConsensusContext {
 pub struct SequencerConsensusContextDeps {
     pub transaction_converter: Arc<dyn TransactionConverterTrait>,
     pub state_sync_client: Arc<dyn StateSyncClient>,
+    // Used to read the staking contract's class when reading the committees.
+    pub class_manager_client: SharedClassManagerClient,
     pub batcher: Arc<dyn BatcherClient>,
     pub cende_ambassador: Arc<dyn CendeContext>,
     pub eth_to_strk_oracle_client: Arc<dyn EthToStrkOracleClientTrait>,
@@ -177,6 +215,8 @@ pub struct SequencerConsensusContextDeps {
     pub outbound_proposal_sender: mpsc::Sender<(HeightAndRound, mpsc::Receiver<ProposalPart>)>,
     // Used to broadcast votes to other consensus nodes.
     pub vote_broadcast_client: BroadcastTopicClient<Vote>,
+    // Used to sign the votes sent by this node.
+    pub signature_manager_client: Arc<dyn SignatureManagerClient>,
 }
 
 impl SequencerConsensusContext {
@@ -188,13 +228,38 @@ impl SequencerConsensusContext {
         } else {
             L1DataAvailabilityMode::Calldata
         };
+        // Until the staking contract is configured, all validators have the same weight. The config
+        // validation ensures every validator has a public key in that case.
+        let committee: Committee = (0..num_validators)
+            .zip(&config.validator_public_keys)
+            .map(|(i, public_key)| Staker {
+                address: ValidatorId::from(DEFAULT_VALIDATOR_ID + i),
+                weight: StakingWeight(1),
+                public_key: *public_key,
+            })
+            .collect();
+        let static_committee_provider = StaticCommitteeProvider::new(
+            vec![committee],
+            config.proposer_prediction_window_in_heights,
+        );
+        let staking_manager = config.staking_contract_address.map(|staking_contract_address| {
+            Arc::new(Mutex::new(StakingManager::new(
+                Box::new(BlockPseudorandomGenerator),
+                StakingManagerConfig {
+                    staking_contract_address,
+                    max_cached_epochs: MAX_CACHED_EPOCHS,
+                    committee_size: config.committee_size,
+                    proposer_prediction_window_in_heights: config
+                        .proposer_prediction_window_in_heights,
+                },
+            )))
+        });
         Self {
             config,
             deps,
-            // TODO(Matan): Set the actual validator IDs (contract addresses).
-            validators: (0..num_validators)
-                .map(|i| ValidatorId::from(DEFAULT_VALIDATOR_ID + i))
-                .collect(),
+            static_committee_provider,
+            staking_manager,
+            height_committees: Mutex::new(BTreeMap::new()),
             valid_proposals: Arc::new(Mutex::new(BuiltProposals::new())),
             proposal_id: 0,
             current_height: None,
@@ -207,6 +272,104 @@ impl SequencerConsensusContext {
         }
     }
 
+    /// Returns the committee of the given height, fetching it if it's not known yet. Retries up to
+    /// `COMMITTEE_MAX_ATTEMPTS` times, since consensus can't run the height without it.
+    async fn height_committee(
+        &self,
+        height: BlockNumber,
+    ) -> Result<HeightCommittee, ConsensusError> {
+        if let Some(height_committee) = self.cached_height_committee(height) {
+            return Ok(height_committee);
+        }
+        let mut attempt = 1;
+        let height_committee = loop {
+            match self.fetch_height_committee(height).await {
+                Ok(height_committee) => break height_committee,
+                Err(e) if attempt == COMMITTEE_MAX_ATTEMPTS => {
+                    return Err(ConsensusError::Other(format!(
+                        "Failed to fetch the committee of height {height} after {attempt} \
+                         attempts: {e}"
+                    )));
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to fetch the committee of height {height} (attempt \
+                         {attempt}/{COMMITTEE_MAX_ATTEMPTS}): {e}. Retrying."
+                    );
+                    attempt += 1;
+                    tokio::time::sleep(COMMITTEE_RETRY_INTERVAL).await;
+                }
+            }
+        };
+        let mut height_committees =
+            self.height_committees.lock().expect("Lock on committees was poisoned");
+        // Consensus doesn't return to past heights.
+        height_committees.retain(|&cached_height, _| cached_height >= height);
+        height_committees.insert(height, height_committee.clone());
+        Ok(height_committee)
+    }
+
+    fn cached_height_committee(&self, height: BlockNumber) -> Option<HeightCommittee> {
+        self.height_committees
+            .lock()
+            .expect("Lock on committees was poisoned")
+            .get(&height)
+            .cloned()
+    }
+
+    async fn fetch_height_committee(
+        &self,
+        height: BlockNumber,
+    ) -> CommitteeProviderResult<HeightCommittee> {
+        // The staking contract is read at the state preceding the height. Until there is such a
+        // state, the configured validators form the committee.
+        let (Some(staking_manager), Some(state_block_number)) =
+            (&self.staking_manager, height.prev())
+        else {
+            return self
+                .static_committee_provider
+                .height_committee(height, self.deps.state_sync_client.clone())
+                .await;
+        };
+
+        let staking_manager = Arc::clone(staking_manager);
+        let state_sync_client = self.deps.state_sync_client.clone();
+        let class_manager_client = self.deps.class_manager_client.clone();
+        let chain_info = ChainInfo { chain_id: self.config.chain_id.clone(), ..Default::default() };
+        let runtime = tokio::runtime::Handle::current();
+        // Reading the state and executing the staking contract are blocking, so they run on a
+        // dedicated thread.
+        tokio::task::spawn_blocking(move || {
+            let state_reader = SyncStateReader::from_number(
+                state_sync_client.clone(),
+                class_manager_client,
+                state_block_number,
+                runtime,
+            );
+            let mut block_info = state_reader.get_block_info()?;
+            block_info.block_number = height;
+            let block_context = BlockContext::new(
+                block_info,
+                chain_info,
+                BlockifierVersionedConstants::latest_constants().clone(),
+                BouncerConfig::max(),
+            );
+            let execution_context = ExecutionContext {
+                state_reader,
+                block_context: Arc::new(block_context),
+                state_sync_client,
+            };
+            futures::executor::block_on(
+                staking_manager
+                    .lock()
+                    .expect("Lock on the staking manager was poisoned")
+                    .get_height_committee(height, execution_context),
+            )
+        })
+        .await
+        .expect("Fetching the committee panicked")
+    }
+
     async fn start_stream(&mut self, stream_id: HeightAndRound) -> StreamSender {
         let (proposal_sender, proposal_receiver) = mpsc::channel(self.config.proposal_buffer_size);
         self.deps
@@ -414,17 +577,30 @@ impl ConsensusContext for SequencerConsensusContext {
         );
     }
 
-    async fn validators(&self, _height: BlockNumber) -> Vec<ValidatorId> {
-        self.validators.clone()
+    async fn validators(&self, height: BlockNumber) -> Result<ValidatorSet, ConsensusError> {
+        Ok(self
+            .height_committee(height)
+            .await?
+            .committee()
+            .iter()
+            .map(|staker| (staker.address, staker.weight.0))
+            .collect())
     }
 
+    // The proposer is selected from the committee cached when `validators` fetched it.
     fn proposer(&self, height: BlockNumber, round: Round) -> ValidatorId {
-        let height: usize = height.0.try_into().expect("Cannot convert to usize");
-        let round: usize = round.try_into().expect("Cannot convert to usize");
-        *self
-            .validators
-            .get((height + round) % self.validators.len())
-            .expect("There should be at least one validator")
+        let proposer = self
+            .cached_height_committee(height)
+            .ok_or_else(|| format!("the committee of height {height} wasn't fetched"))
+            .and_then(|height_committee| {
+                height_committee.proposer(round).map_err(|e| e.to_string())
+            });
+        proposer.unwrap_or_else(|e| {
+            // No validator has the default address, so proposals of the round are rejected and
+            // this node doesn't propose, until the round times out.
+            error!("No proposer for height {height} and round {round}: {e}");
+            ValidatorId::default()
+        })
     }
 
     async fn broadcast(&mut self, message: Vote) -> Result<(), ConsensusError> {
@@ -433,6 +609,37 @@ impl ConsensusContext for SequencerConsensusContext {
         Ok(())
     }
 
+    async fn sign_vote(&self, vote: Vote) -> Result<Vote, ConsensusError> {
+        let signature = self
+            .deps
+            .signature_manager_client
+            .sign_vote(vote.clone())
+            .await
+            .map_err(|e| ConsensusError::Other(format!("Failed to sign {vote:?}: {e}")))?;
+        let signature = Signature::try_from(signature)
+            .map_err(|e| ConsensusError::Other(format!("Invalid signature for {vote:?}: {e}")))?;
+        Ok(Vote { signature: Some(signature), ..vote })
+    }
+
+    fn verify_vote(&self, vote: &Vote) -> bool {
+        let Some(signature) = vote.signature else {
+            return false;
+        };
+        let Some(height_committee) = self.cached_height_committee(BlockNumber(vote.height)) else {
+            return false;
+        };
+        let Some(voter) =
+            height_committee.committee().iter().find(|staker| staker.address == vote.voter)
+        else {
+            return false;
+        };
+        let public_key = PublicKey(voter.public_key);
+        verify_vote_signature(vote, signature.into(), public_key).unwrap_or_else(|e| {
+            warn!("Failed to verify the signature of {vote:?}: {e}");
+            false
+        })
+    }
+
     async fn decision_reached(
         &mut self,
         block: ProposalCommitment,
@@ -456,7 +663,7 @@ impl ConsensusContext for SequencerConsensusContext {
 
         // TODO(dvir): return from the batcher's 'decision_reached' function the relevant data to
         // build a blob.
-        let DecisionReachedResponse { state_diff, l2_gas_used, central_objects } =
+        let DecisionReachedResponse { state_diff, state_root, l2_gas_used, central_objects } =
             self.batcher_decision_reached(proposal_id).await;
 
         // Remove transactions that were not accepted by the Batcher, so `transactions` and
@@ -505,6 +712,10 @@ impl ConsensusContext for SequencerConsensusContext {
             sequencer,
             timestamp: BlockTimestamp(block_info.timestamp),
             l1_da_mode: block_info.l1_da_mode,
+            // The batcher fails the decision if its committer can't compute the state root, so
+            // it's only unset if no committer is configured, in which case no state roots are
+            // computed.
+            state_root: state_root.unwrap_or_default(),
             // TODO(guy.f): Figure out where/if to get the values below from and fill them.
             ..Default::default()
         };
@@ -680,6 +891,16 @@ impl ConsensusContext for SequencerConsensusContext {
         )
         .await;
     }
+
+    async fn report_evidence(&mut self, evidence: Evidence) {
+        // Slashing isn't supported yet, so the evidence is only recorded by consensus.
+        warn!(
+            "Validator {} equivocated at height {} round {}: {evidence:?}",
+            evidence.voter(),
+            evidence.height(),
+            evidence.round()
+        );
+    }
 }
 
 impl SequencerConsensusContext {
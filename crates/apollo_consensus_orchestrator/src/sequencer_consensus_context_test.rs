@@ -5,7 +5,8 @@ use std::vec;
 use apollo_batcher_types::batcher_types::{CentralObjects, DecisionReachedResponse};
 use apollo_batcher_types::communication::BatcherClientError;
 use apollo_batcher_types::errors::BatcherError;
-use apollo_consensus::types::{ConsensusContext, Round};
+use apollo_consensus::types::{ConsensusContext, Round, ValidatorSet};
+use apollo_infra::component_client::ClientError;
 use apollo_l1_gas_price_types::errors::{
     EthToStrkOracleClientError,
     L1GasPriceClientError,
@@ -17,7 +18,17 @@ use apollo_l1_gas_price_types::{
     PriceInfo,
     DEFAULT_ETH_TO_FRI_RATE,
 };
-use apollo_protobuf::consensus::{ProposalFin, ProposalInit, ProposalPart, TransactionBatch, Vote};
+use apollo_protobuf::consensus::{
+    ProposalFin,
+    ProposalInit,
+    ProposalPart,
+    TransactionBatch,
+    Vote,
+    VoteType,
+    DEFAULT_VALIDATOR_ID,
+};
+use apollo_signature_manager::signature_manager::{LocalKeyStore, SignatureManager};
+use apollo_state_sync_types::communication::StateSyncClientError;
 use apollo_time::time::MockClock;
 use chrono::{TimeZone, Utc};
 use futures::channel::mpsc;
@@ -33,18 +44,23 @@ use starknet_api::block::{
     TEMP_ETH_BLOB_GAS_FEE_IN_WEI,
     TEMP_ETH_GAS_FEE_IN_WEI,
 };
+use starknet_api::core::ContractAddress;
+use starknet_api::crypto::utils::Signature;
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::state::ThinStateDiff;
+use starknet_types_core::felt::Felt;
 
 use crate::cende::MockCendeContext;
 use crate::config::ContextConfig;
 use crate::metrics::CONSENSUS_L2_GAS_PRICE;
 use crate::orchestrator_versioned_constants::VersionedConstants;
+use crate::sequencer_consensus_context::COMMITTEE_MAX_ATTEMPTS;
 use crate::test_utils::{
     block_info,
     create_test_and_network_deps,
     ETH_TO_FRI_RATE,
     INTERNAL_TX_BATCH,
+    NUM_VALIDATORS,
     STATE_DIFF_COMMITMENT,
     TIMEOUT,
     TX_BATCH,
@@ -595,7 +611,7 @@ async fn oracle_fails_on_startup(#[case] l1_oracle_failure: bool) {
         deps.l1_gas_price_provider = l1_prices_oracle_client;
     } else {
         let mut eth_to_strk_oracle_client = MockEthToStrkOracleClientTrait::new();
-        eth_to_strk_oracle_client.expect_eth_to_fri_rate().times(1).return_once(|_| {
+        eth_to_strk_oracle_client.expect_eth_to_fri_rate().times(1).return_once(|_, _| {
             Err(EthToStrkOracleClientError::MissingFieldError("", "".to_string()))
         });
         deps.eth_to_strk_oracle_client = eth_to_strk_oracle_client;
@@ -655,6 +671,7 @@ async fn oracle_fails_on_second_block(#[case] l1_oracle_failure: bool) {
     deps.batcher.expect_decision_reached().times(1).return_once(|_| {
         Ok(DecisionReachedResponse {
             state_diff: ThinStateDiff::default(),
+            state_root: None,
             l2_gas_used: GasAmount::default(),
             central_objects: CentralObjects::default(),
         })
@@ -683,8 +700,8 @@ async fn oracle_fails_on_second_block(#[case] l1_oracle_failure: bool) {
         eth_to_strk_oracle_client
             .expect_eth_to_fri_rate()
             .times(1)
-            .return_once(|_| Ok(ETH_TO_FRI_RATE));
-        eth_to_strk_oracle_client.expect_eth_to_fri_rate().times(1).return_once(|_| {
+            .return_once(|_, _| Ok(ETH_TO_FRI_RATE));
+        eth_to_strk_oracle_client.expect_eth_to_fri_rate().times(1).return_once(|_, _| {
             Err(EthToStrkOracleClientError::MissingFieldError("", "".to_string()))
         });
         deps.eth_to_strk_oracle_client = eth_to_strk_oracle_client;
@@ -788,6 +805,7 @@ async fn constant_l2_gas_price_behavior(
     deps.batcher.expect_decision_reached().times(1).return_once(move |_| {
         Ok(DecisionReachedResponse {
             state_diff: ThinStateDiff::default(),
+            state_root: None,
             l2_gas_used: mock_l2_gas_used,
             central_objects: CentralObjects::default(),
         })
@@ -823,3 +841,80 @@ async fn constant_l2_gas_price_behavior(
         );
     }
 }
+
+#[tokio::test]
+async fn sign_and_verify_vote() {
+    let (mut deps, _network) = create_test_and_network_deps();
+    deps.signature_manager_client.expect_sign_vote().times(1).returning(|vote| {
+        let signature_manager = SignatureManager::new(LocalKeyStore::new_for_testing());
+        Ok(futures::executor::block_on(signature_manager.sign_vote(&vote))?)
+    });
+    let context = deps.build_context();
+
+    let vote = Vote {
+        vote_type: VoteType::Precommit,
+        height: 1,
+        round: 0,
+        block_hash: Some(BlockHash(STATE_DIFF_COMMITMENT.0.0)),
+        voter: ContractAddress::from(DEFAULT_VALIDATOR_ID),
+        signature: None,
+    };
+    // Votes are verified against the committee of their height.
+    context.validators(BlockNumber(vote.height)).await.unwrap();
+    assert!(!context.verify_vote(&vote), "Unsigned votes must be rejected");
+
+    let signed_vote = context.sign_vote(vote.clone()).await.unwrap();
+    assert!(signed_vote.signature.is_some());
+    assert!(context.verify_vote(&signed_vote));
+
+    // The signature doesn't cover a different round.
+    assert!(!context.verify_vote(&Vote { round: 1, ..signed_vote.clone() }));
+    // A tampered signature is rejected.
+    let tampered_signature = Signature { r: signed_vote.signature.unwrap().s, s: Felt::ONE };
+    assert!(!context.verify_vote(&Vote { signature: Some(tampered_signature), ..signed_vote }));
+    // Votes from non validators are rejected, even if correctly signed.
+    let vote = Vote { voter: ContractAddress::from(DEFAULT_VALIDATOR_ID + NUM_VALIDATORS), ..vote };
+    let signature = SignatureManager::new(LocalKeyStore::new_for_testing())
+        .sign_vote(&vote)
+        .await
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(!context.verify_vote(&Vote { signature: Some(signature), ..vote }));
+}
+
+#[tokio::test]
+async fn weighted_validators_and_proposer() {
+    let (deps, _network) = create_test_and_network_deps();
+    let context = deps.build_context();
+
+    let validators = context.validators(BlockNumber(0)).await.unwrap();
+    let expected_validators: ValidatorSet =
+        (0..NUM_VALIDATORS).map(|i| (ContractAddress::from(DEFAULT_VALIDATOR_ID + i), 1)).collect();
+    assert_eq!(validators, expected_validators);
+
+    // The proposer is a validator, and all nodes agree on it.
+    let other_context = create_test_and_network_deps().0.build_context();
+    assert_eq!(context.validators(BlockNumber(1)).await.unwrap(), validators);
+    assert_eq!(other_context.validators(BlockNumber(1)).await.unwrap(), validators);
+    for round in 0..10 {
+        let proposer = context.proposer(BlockNumber(1), round);
+        assert!(validators.contains_key(&proposer));
+        assert_eq!(proposer, other_context.proposer(BlockNumber(1), round));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn committee_fetch_failures_stop_consensus() {
+    let (mut deps, _network) = create_test_and_network_deps();
+    // The proposer of this height is seeded with the hash of a past block, which can't be read.
+    let height = BlockNumber(ContextConfig::default().proposer_prediction_window_in_heights);
+    deps.state_sync_client.expect_get_block_hash().times(COMMITTEE_MAX_ATTEMPTS).returning(|_| {
+        Err(StateSyncClientError::ClientError(ClientError::CommunicationFailure("".to_string())))
+    });
+    let context = deps.build_context();
+
+    assert!(context.validators(height).await.is_err());
+    // Without the committee there's no proposer, but consensus isn't expected to ask for one.
+    assert_eq!(context.proposer(height, 0), ContractAddress::default());
+}
@@ -32,6 +32,8 @@ use apollo_network::network_manager::test_utils::{
 };
 use apollo_network::network_manager::{BroadcastTopicChannels, BroadcastTopicClient};
 use apollo_protobuf::consensus::{ConsensusBlockInfo, HeightAndRound, ProposalPart, Vote};
+use apollo_signature_manager::signature_manager::LocalKeyStore;
+use apollo_signature_manager_types::MockSignatureManagerClient;
 use apollo_state_sync_types::communication::MockStateSyncClient;
 use apollo_time::time::{Clock, DefaultClock};
 use futures::channel::mpsc;
@@ -98,6 +100,7 @@ pub(crate) struct TestDeps {
     pub clock: Arc<dyn Clock>,
     pub outbound_proposal_sender: mpsc::Sender<(HeightAndRound, mpsc::Receiver<ProposalPart>)>,
     pub vote_broadcast_client: BroadcastTopicClient<Vote>,
+    pub signature_manager_client: MockSignatureManagerClient,
 }
 
 impl From<TestDeps> for SequencerConsensusContextDeps {
@@ -105,6 +108,7 @@ impl From<TestDeps> for SequencerConsensusContextDeps {
         SequencerConsensusContextDeps {
             transaction_converter: Arc::new(deps.transaction_converter),
             state_sync_client: Arc::new(deps.state_sync_client),
+            class_manager_client: Arc::new(EmptyClassManagerClient),
             batcher: Arc::new(deps.batcher),
             cende_ambassador: Arc::new(deps.cende_ambassador),
             eth_to_strk_oracle_client: Arc::new(deps.eth_to_strk_oracle_client),
@@ -112,6 +116,7 @@ impl From<TestDeps> for SequencerConsensusContextDeps {
             clock: deps.clock,
             outbound_proposal_sender: deps.outbound_proposal_sender,
             vote_broadcast_client: deps.vote_broadcast_client,
+            signature_manager_client: Arc::new(deps.signature_manager_client),
         }
     }
 }
@@ -232,7 +237,9 @@ impl TestDeps {
     }
 
     pub(crate) fn setup_default_eth_to_strk_oracle_client(&mut self) {
-        self.eth_to_strk_oracle_client.expect_eth_to_fri_rate().returning(|_| Ok(ETH_TO_FRI_RATE));
+        self.eth_to_strk_oracle_client
+            .expect_eth_to_fri_rate()
+            .returning(|_, _| Ok(ETH_TO_FRI_RATE));
     }
 
     pub(crate) fn build_context(self) -> SequencerConsensusContext {
@@ -240,6 +247,11 @@ impl TestDeps {
             ContextConfig {
                 proposal_buffer_size: CHANNEL_SIZE,
                 num_validators: NUM_VALIDATORS,
+                // All validators sign with the same testing key.
+                validator_public_keys: vec![
+                    LocalKeyStore::new_for_testing().public_key.0;
+                    usize::try_from(NUM_VALIDATORS).unwrap()
+                ],
                 chain_id: CHAIN_ID,
                 ..Default::default()
             },
@@ -263,6 +275,7 @@ pub(crate) fn create_test_and_network_deps() -> (TestDeps, NetworkDependencies)
     let cende_ambassador = MockCendeContext::new();
     let eth_to_strk_oracle_client = MockEthToStrkOracleClientTrait::new();
     let l1_gas_price_provider = MockL1GasPriceProviderClient::new();
+    let signature_manager_client = MockSignatureManagerClient::new();
     let clock = Arc::new(DefaultClock);
 
     let test_deps = TestDeps {
@@ -275,6 +288,7 @@ pub(crate) fn create_test_and_network_deps() -> (TestDeps, NetworkDependencies)
         clock,
         outbound_proposal_sender,
         vote_broadcast_client: votes_topic_client,
+        signature_manager_client,
     };
 
     let network_deps =
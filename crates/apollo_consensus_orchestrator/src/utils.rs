@@ -3,6 +3,7 @@ use std::sync::Arc;
 use apollo_l1_gas_price_types::{
     EthToStrkOracleClientTrait,
     L1GasPriceProviderClient,
+    PreviousEthToFriRate,
     PriceInfo,
     DEFAULT_ETH_TO_FRI_RATE,
 };
@@ -85,7 +86,13 @@ pub(crate) async fn get_oracle_rate_and_prices(
     gas_price_params: &GasPriceParams,
 ) -> (u128, PriceInfo) {
     let (eth_to_strk_rate, price_info) = tokio::join!(
-        eth_to_strk_oracle_client.eth_to_fri_rate(timestamp),
+        eth_to_strk_oracle_client.eth_to_fri_rate(
+            timestamp,
+            previous_block_info.map(|previous_block_info| PreviousEthToFriRate {
+                timestamp: previous_block_info.timestamp,
+                eth_to_fri_rate: previous_block_info.eth_to_fri_rate,
+            }),
+        ),
         l1_gas_price_provider_client.get_price_info(BlockTimestamp(timestamp))
     );
     if price_info.is_err() {
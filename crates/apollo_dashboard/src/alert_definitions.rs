@@ -178,7 +178,7 @@ fn get_consensus_inbound_stream_evicted_alert() -> Alert {
         "consensus_inbound_stream_evicted",
         "Consensus inbound stream evicted",
         AlertGroup::Consensus,
-        format!("increase({}[1h])", CONSENSUS_INBOUND_STREAM_EVICTED.get_name_with_filter()),
+        format!("sum(increase({}[1h]))", CONSENSUS_INBOUND_STREAM_EVICTED.get_name_with_filter()),
         vec![AlertCondition {
             comparison_op: AlertComparisonOp::GreaterThan,
             comparison_value: 5.0,
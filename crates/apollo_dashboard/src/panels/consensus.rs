@@ -22,6 +22,7 @@ use apollo_consensus::metrics::{
     CONSENSUS_ROUND,
     CONSENSUS_ROUND_ABOVE_ZERO,
     CONSENSUS_TIMEOUTS,
+    LABEL_NAME_EVICTION_REASON,
     LABEL_NAME_TIMEOUT_REASON,
 };
 use apollo_consensus_manager::metrics::{
@@ -80,7 +81,16 @@ fn get_panel_consensus_inbound_stream_started() -> Panel {
     Panel::from_counter(CONSENSUS_INBOUND_STREAM_STARTED, PanelType::TimeSeries)
 }
 fn get_panel_consensus_inbound_stream_evicted() -> Panel {
-    Panel::from_counter(CONSENSUS_INBOUND_STREAM_EVICTED, PanelType::TimeSeries)
+    Panel::new(
+        CONSENSUS_INBOUND_STREAM_EVICTED.get_name(),
+        CONSENSUS_INBOUND_STREAM_EVICTED.get_description(),
+        vec![format!(
+            "sum  by ({}) ({})",
+            LABEL_NAME_EVICTION_REASON,
+            CONSENSUS_INBOUND_STREAM_EVICTED.get_name_with_filter()
+        )],
+        PanelType::TimeSeries,
+    )
 }
 fn get_panel_consensus_inbound_stream_finished() -> Panel {
     Panel::from_counter(CONSENSUS_INBOUND_STREAM_FINISHED, PanelType::TimeSeries)
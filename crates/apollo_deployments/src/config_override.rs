@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use apollo_config::converters::serialize_slice;
 use apollo_infra_utils::dumping::serialize_to_file;
 #[cfg(test)]
 use apollo_infra_utils::dumping::serialize_to_file_test;
@@ -126,6 +127,8 @@ pub struct DeploymentConfigOverride {
     l1_provider_config_provider_startup_height_override_is_none: bool,
     #[serde(rename = "consensus_manager_config.context_config.num_validators")]
     consensus_manager_config_context_config_num_validators: usize,
+    #[serde(rename = "consensus_manager_config.context_config.validator_public_keys")]
+    consensus_manager_config_context_config_validator_public_keys: String,
     #[serde(flatten)]
     state_sync_config: StateSyncConfig,
 }
@@ -140,6 +143,7 @@ impl DeploymentConfigOverride {
         strk_fee_token_address: impl ToString,
         l1_startup_height_override: Option<BlockNumber>,
         consensus_manager_config_context_config_num_validators: usize,
+        validator_public_keys: &[impl AsRef<str>],
         state_sync_type: StateSyncType,
     ) -> Self {
         let (
@@ -159,6 +163,9 @@ impl DeploymentConfigOverride {
             l1_provider_config_provider_startup_height_override,
             l1_provider_config_provider_startup_height_override_is_none,
             consensus_manager_config_context_config_num_validators,
+            consensus_manager_config_context_config_validator_public_keys: serialize_slice(
+                validator_public_keys,
+            ),
             state_sync_config: state_sync_type.get_state_sync_config(),
         }
     }
@@ -39,6 +39,10 @@ const SIERRA_COMPILER_PORT: u16 = 55007;
 const SIGNATURE_MANAGER_PORT: u16 = 55008;
 const STATE_SYNC_PORT: u16 = 55009;
 
+// The public key of the validator private key in the testing secrets.
+const TESTING_VALIDATOR_PUBLIC_KEY: &str =
+    "0x1ef15c18599971b7beced415a40f0c7deacfd9b0d1819e03d723d8bc943cfca";
+
 pub const DEPLOYMENTS: &[DeploymentFn] = &[
     || load_and_create_hybrid_deployments(POTC2_DEPLOYMENT_INPUTS_PATH),
     || load_and_create_hybrid_deployments(MAINNET_DEPLOYMENT_INPUTS_PATH),
@@ -68,6 +72,8 @@ const STRESS_TEST_DEPLOYMENT_INPUTS_PATH: &str =
 #[derive(Debug, Deserialize)]
 pub struct DeploymentInputs {
     pub node_ids: Vec<usize>,
+    // The public keys of the nodes' validators, ordered by the node ids.
+    pub validator_public_keys: Vec<String>,
     pub http_server_ingress_alternative_name: String,
     pub ingress_domain: String,
     pub secret_name_format: Template,
@@ -8,7 +8,7 @@ use crate::config_override::{
     NetworkConfigOverride,
 };
 use crate::deployment::Deployment;
-use crate::deployment_definitions::{Environment, StateSyncType};
+use crate::deployment_definitions::{Environment, StateSyncType, TESTING_VALIDATOR_PUBLIC_KEY};
 use crate::k8s::IngressParams;
 use crate::service::NodeType;
 
@@ -32,6 +32,7 @@ fn testing_deployment_config_override() -> DeploymentConfigOverride {
         "0x1002",
         Some(BlockNumber(1)),
         TESTING_NODE_IDS.len(),
+        &[TESTING_VALIDATOR_PUBLIC_KEY; TESTING_NODE_IDS.len()],
         StateSyncType::P2P,
     )
 }
@@ -4,7 +4,12 @@ use url::Url;
 
 use crate::config_override::DeploymentConfigOverride;
 use crate::deployment::{Deployment, P2PCommunicationType};
-use crate::deployment_definitions::{CloudK8sEnvironment, Environment, StateSyncType};
+use crate::deployment_definitions::{
+    CloudK8sEnvironment,
+    Environment,
+    StateSyncType,
+    TESTING_VALIDATOR_PUBLIC_KEY,
+};
 use crate::deployments::hybrid::{hybrid_deployment, INSTANCE_NAME_FORMAT};
 use crate::k8s::K8sServiceConfigParams;
 
@@ -44,6 +49,7 @@ pub(crate) fn upgrade_test_hybrid_deployments() -> Vec<Deployment> {
                     STRK_FEE_TOKEN_ADDRESS,
                     L1_STARTUP_HEIGHT_OVERRIDE,
                     NODE_IDS.len(),
+                    &[TESTING_VALIDATOR_PUBLIC_KEY; NODE_IDS.len()],
                     STATE_SYNC_TYPE,
                 ),
                 &Template::new(NODE_NAMESPACE_FORMAT),
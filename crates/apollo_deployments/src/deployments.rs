@@ -1,5 +1,10 @@
+use std::time::Duration;
+
 pub mod consolidated;
 pub mod distributed;
 pub mod hybrid;
 
 pub(crate) const IDLE_CONNECTIONS_FOR_AUTOSCALED_SERVICES: usize = 0;
+// The deadline of requests to the batcher, which may wait for a block to be committed, e.g. when a
+// decision is reached.
+pub(crate) const BATCHER_REQUEST_TIMEOUT: Duration = Duration::from_secs(900);
@@ -18,7 +18,7 @@ use crate::deployment_definitions::{
     InfraServicePort,
     ServicePort,
 };
-use crate::deployments::IDLE_CONNECTIONS_FOR_AUTOSCALED_SERVICES;
+use crate::deployments::{BATCHER_REQUEST_TIMEOUT, IDLE_CONNECTIONS_FOR_AUTOSCALED_SERVICES};
 use crate::k8s::{
     get_environment_ingress_internal,
     get_ingress,
@@ -67,7 +67,8 @@ impl GetComponentConfigs for DistributedNodeServiceName {
     fn get_component_configs(ports: Option<Vec<u16>>) -> IndexMap<NodeService, ComponentConfig> {
         let ports = determine_port_numbers(ports, DISTRIBUTED_NODE_REQUIRED_PORTS_NUM, BASE_PORT);
 
-        let batcher = DistributedNodeServiceName::Batcher.component_config_pair(ports[0]);
+        let mut batcher = DistributedNodeServiceName::Batcher.component_config_pair(ports[0]);
+        batcher.remote.remote_client_config.request_timeout_millis = BATCHER_REQUEST_TIMEOUT;
         let class_manager =
             DistributedNodeServiceName::ClassManager.component_config_pair(ports[1]);
         let gateway = DistributedNodeServiceName::Gateway.component_config_pair(ports[2]);
@@ -30,7 +30,7 @@ use crate::deployment_definitions::{
     InfraServicePort,
     ServicePort,
 };
-use crate::deployments::IDLE_CONNECTIONS_FOR_AUTOSCALED_SERVICES;
+use crate::deployments::{BATCHER_REQUEST_TIMEOUT, IDLE_CONNECTIONS_FOR_AUTOSCALED_SERVICES};
 use crate::k8s::{
     get_environment_ingress_internal,
     get_ingress,
@@ -78,7 +78,8 @@ impl GetComponentConfigs for HybridNodeServiceName {
 
         let ports = determine_port_numbers(ports, HYBRID_NODE_REQUIRED_PORTS_NUM, BASE_PORT);
 
-        let batcher = HybridNodeServiceName::Core.component_config_pair(ports[0]);
+        let mut batcher = HybridNodeServiceName::Core.component_config_pair(ports[0]);
+        batcher.remote.remote_client_config.request_timeout_millis = BATCHER_REQUEST_TIMEOUT;
         let class_manager = HybridNodeServiceName::Core.component_config_pair(ports[1]);
         let gateway = HybridNodeServiceName::Gateway.component_config_pair(ports[2]);
         let l1_gas_price_provider = HybridNodeServiceName::L1.component_config_pair(ports[3]);
@@ -842,6 +843,7 @@ fn hybrid_deployments(inputs: &DeploymentInputs) -> Vec<Deployment> {
                     &inputs.strk_fee_token_address,
                     inputs.l1_startup_height_override,
                     inputs.node_ids.len(),
+                    &inputs.validator_public_keys,
                     inputs.state_sync_type.clone(),
                 ),
                 &inputs.node_namespace_format,
@@ -417,6 +417,8 @@ impl ComponentConfigsSerializationWrapper {
 impl SerializeConfig for ComponentConfigsSerializationWrapper {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
         let mut map = prepend_sub_config_name(self.component_config.dump(), "components");
+        // Private params are set through their pointer targets in the secrets config.
+        map.retain(|_, serialized_param| !serialized_param.is_private());
         for component_config_in_service in ComponentConfigInService::iter() {
             if component_config_in_service == ComponentConfigInService::General {
                 // General configs are not toggle-able, i.e., no need to add their existence to the
@@ -5,6 +5,8 @@ use apollo_config::converters::{
     UrlAndHeaders,
 };
 use serde::{Serialize, Serializer};
+use starknet_api::crypto::utils::PrivateKey;
+use starknet_api::felt;
 use url::Url;
 
 pub(crate) const FIX_BINARY_NAME: &str = "deployment_generator";
@@ -34,6 +36,14 @@ pub struct SecretsConfigOverride {
     )]
     mempool_p2p_config_network_config_secret_key: Option<Vec<u8>>,
     recorder_url: Url,
+    #[serde(rename = "remote_component_tls_config.ca_cert_path")]
+    remote_component_tls_config_ca_cert_path: String,
+    #[serde(rename = "remote_component_tls_config.cert_path")]
+    remote_component_tls_config_cert_path: String,
+    #[serde(rename = "remote_component_tls_config.key_path")]
+    remote_component_tls_config_key_path: String,
+    #[serde(rename = "signature_manager_config.private_key")]
+    signature_manager_config_private_key: PrivateKey,
     #[serde(
         rename = "state_sync_config.central_sync_client_config.central_source_config.http_headers"
     )]
@@ -62,6 +72,10 @@ impl Default for SecretsConfigOverride {
             ],
             mempool_p2p_config_network_config_secret_key: None,
             recorder_url: Url::parse("https://arbitrary.recorder.url").unwrap(),
+            remote_component_tls_config_ca_cert_path: "".to_string(),
+            remote_component_tls_config_cert_path: "".to_string(),
+            remote_component_tls_config_key_path: "".to_string(),
+            signature_manager_config_private_key: PrivateKey(felt!(1_u8)),
             state_sync_config_central_sync_client_config_central_source_config_http_headers: ""
                 .to_string(),
             state_sync_config_network_config_secret_key: None,
@@ -196,11 +196,18 @@ pub fn mempool_client_result_to_gw_spec_result(
                 | MempoolError::NonceTooOld { .. } => {
                     Err(GatewaySpecError::InvalidTransactionNonce)
                 }
+                MempoolError::NonceTooFarAhead { .. } => Err(GatewaySpecError::NonceTooFarAhead),
                 MempoolError::DuplicateTransaction { .. } => Err(GatewaySpecError::DuplicateTx),
                 // TODO(Dafna): change to a more appropriate error, once we have it.
                 MempoolError::MempoolFull => {
                     Err(GatewaySpecError::UnexpectedError { data: "Mempool full".to_owned() })
                 }
+                MempoolError::AccountTransactionLimitExceeded { .. } => {
+                    Err(GatewaySpecError::AccountTransactionLimitExceeded)
+                }
+                MempoolError::AccountCapacityExceeded { .. } => {
+                    Err(GatewaySpecError::AccountCapacityExceeded)
+                }
                 MempoolError::P2pPropagatorClientError { .. } => {
                     // Not an error from the gateway's perspective.
                     warn!("P2p propagator client error: {}", mempool_error);
@@ -245,6 +252,19 @@ pub fn mempool_client_err_to_deprecated_gw_err(err: MempoolClientError) -> Stark
                 MempoolError::MempoolFull => StarknetErrorCode::KnownErrorCode(
                     KnownStarknetErrorCode::TransactionLimitExceeded,
                 ),
+                MempoolError::AccountTransactionLimitExceeded { .. } => {
+                    StarknetErrorCode::UnknownErrorCode(
+                        "StarknetErrorCode.ACCOUNT_TRANSACTION_LIMIT_EXCEEDED".to_string(),
+                    )
+                }
+                MempoolError::AccountCapacityExceeded { .. } => {
+                    StarknetErrorCode::UnknownErrorCode(
+                        "StarknetErrorCode.ACCOUNT_CAPACITY_EXCEEDED".to_string(),
+                    )
+                }
+                MempoolError::NonceTooFarAhead { .. } => StarknetErrorCode::UnknownErrorCode(
+                    "StarknetErrorCode.NONCE_TOO_FAR_AHEAD".to_string(),
+                ),
                 MempoolError::P2pPropagatorClientError { .. } => {
                     // Not an error from the gateway's perspective.
                     return StarknetError::internal(&message);
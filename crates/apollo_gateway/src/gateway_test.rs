@@ -336,6 +336,18 @@ async fn run_add_tx_and_extract_metrics(
     Err(MempoolClientError::MempoolError(MempoolError::NonceTooLarge(Nonce::default()))),
     StarknetErrorCode::UnknownErrorCode("StarknetErrorCode.NONCE_TOO_LARGE".to_string())
 )]
+#[case::tx_with_nonce_too_far_ahead(
+    Err(MempoolClientError::MempoolError(MempoolError::NonceTooFarAhead { address: ContractAddress::default(), tx_nonce: nonce!(300), account_nonce: Nonce::default(), max_future_nonces: 200 })),
+    StarknetErrorCode::UnknownErrorCode("StarknetErrorCode.NONCE_TOO_FAR_AHEAD".to_string())
+)]
+#[case::account_transaction_limit_exceeded(
+    Err(MempoolClientError::MempoolError(MempoolError::AccountTransactionLimitExceeded { address: ContractAddress::default(), max_txs: 200 })),
+    StarknetErrorCode::UnknownErrorCode("StarknetErrorCode.ACCOUNT_TRANSACTION_LIMIT_EXCEEDED".to_string())
+)]
+#[case::account_capacity_exceeded(
+    Err(MempoolClientError::MempoolError(MempoolError::AccountCapacityExceeded { address: ContractAddress::default(), max_bytes: 1 << 24 })),
+    StarknetErrorCode::UnknownErrorCode("StarknetErrorCode.ACCOUNT_CAPACITY_EXCEEDED".to_string())
+)]
 #[tokio::test]
 async fn test_add_tx_negative(
     mut mock_dependencies: MockDependencies,
@@ -15,6 +15,4 @@ mod stateful_transaction_validator;
 mod stateless_transaction_validator;
 mod sync_state_reader;
 #[cfg(test)]
-mod sync_state_reader_test;
-#[cfg(test)]
 mod test_utils;
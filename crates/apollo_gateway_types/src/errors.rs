@@ -3,6 +3,8 @@ use apollo_rpc::error::{
     unexpected_error,
     validation_failure,
     JsonRpcError,
+    ACCOUNT_CAPACITY_EXCEEDED,
+    ACCOUNT_TRANSACTION_LIMIT_EXCEEDED,
     CLASS_ALREADY_DECLARED,
     CLASS_HASH_NOT_FOUND,
     COMPILATION_FAILED,
@@ -12,6 +14,7 @@ use apollo_rpc::error::{
     INSUFFICIENT_ACCOUNT_BALANCE,
     INSUFFICIENT_MAX_FEE,
     INVALID_TRANSACTION_NONCE,
+    NONCE_TOO_FAR_AHEAD,
     NON_ACCOUNT,
     UNSUPPORTED_CONTRACT_CLASS_VERSION,
     UNSUPPORTED_TX_VERSION,
@@ -29,6 +32,10 @@ use crate::deprecated_gateway_error::StarknetError;
 #[derive(Debug, Clone, Eq, PartialEq, Assoc, Error, Serialize, Deserialize)]
 #[func(pub fn into_rpc(self) -> JsonRpcError<String>)]
 pub enum GatewaySpecError {
+    #[assoc(into_rpc = ACCOUNT_CAPACITY_EXCEEDED)]
+    AccountCapacityExceeded,
+    #[assoc(into_rpc = ACCOUNT_TRANSACTION_LIMIT_EXCEEDED)]
+    AccountTransactionLimitExceeded,
     #[assoc(into_rpc = CLASS_ALREADY_DECLARED)]
     ClassAlreadyDeclared,
     #[assoc(into_rpc = CLASS_HASH_NOT_FOUND)]
@@ -47,6 +54,8 @@ pub enum GatewaySpecError {
     InsufficientMaxFee,
     #[assoc(into_rpc = INVALID_TRANSACTION_NONCE)]
     InvalidTransactionNonce,
+    #[assoc(into_rpc = NONCE_TOO_FAR_AHEAD)]
+    NonceTooFarAhead,
     #[assoc(into_rpc = NON_ACCOUNT)]
     NonAccount,
     #[assoc(into_rpc = unexpected_error(_data))]
@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+#[cfg(test)]
+#[path = "circuit_breaker_test.rs"]
+mod circuit_breaker_test;
+
+/// Stops sending requests to a server after a number of consecutive failed requests. While the
+/// circuit breaker is open, requests are rejected without being sent. Once the open duration
+/// passes, a single trial request is let through: if it succeeds the circuit breaker closes, and
+/// otherwise it stays open for another open duration. A failure threshold of 0 disables the
+/// circuit breaker.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    failure_threshold: usize,
+    open_duration: Duration,
+    state: Mutex<CircuitBreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreakerState {
+    consecutive_failures: usize,
+    // Set while the circuit breaker is open.
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: usize, open_duration: Duration) -> Self {
+        Self { failure_threshold, open_duration, state: Mutex::new(CircuitBreakerState::default()) }
+    }
+
+    /// Returns whether a request may be sent.
+    pub(crate) fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().expect("Circuit breaker lock should not be poisoned");
+        let Some(open_until) = state.open_until else {
+            return true;
+        };
+        let now = Instant::now();
+        if now < open_until {
+            return false;
+        }
+        // Let a single trial request through. If the trial request is never completed, another one
+        // is let through after the open duration.
+        state.open_until = Some(now + self.open_duration);
+        true
+    }
+
+    pub(crate) fn record_success(&self) {
+        let mut state = self.state.lock().expect("Circuit breaker lock should not be poisoned");
+        *state = CircuitBreakerState::default();
+    }
+
+    pub(crate) fn record_failure(&self) {
+        if self.failure_threshold == 0 {
+            return;
+        }
+        let mut state = self.state.lock().expect("Circuit breaker lock should not be poisoned");
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.failure_threshold {
+            warn!(
+                "Opening the circuit breaker for {:?} after {} consecutive failed requests.",
+                self.open_duration, state.consecutive_failures
+            );
+            state.open_until = Some(Instant::now() + self.open_duration);
+        }
+    }
+}
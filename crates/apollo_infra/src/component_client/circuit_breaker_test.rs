@@ -0,0 +1,54 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::component_client::circuit_breaker::CircuitBreaker;
+
+const OPEN_DURATION: Duration = Duration::from_millis(100);
+
+#[test]
+fn opens_after_consecutive_failures() {
+    let circuit_breaker = CircuitBreaker::new(2, OPEN_DURATION);
+    circuit_breaker.record_failure();
+    assert!(circuit_breaker.try_acquire());
+
+    // A success resets the consecutive failures count.
+    circuit_breaker.record_success();
+    circuit_breaker.record_failure();
+    assert!(circuit_breaker.try_acquire());
+
+    circuit_breaker.record_failure();
+    assert!(!circuit_breaker.try_acquire());
+}
+
+#[test]
+fn trial_request() {
+    let circuit_breaker = CircuitBreaker::new(1, OPEN_DURATION);
+    circuit_breaker.record_failure();
+    assert!(!circuit_breaker.try_acquire());
+
+    // Only a single request is let through after the open duration.
+    sleep(OPEN_DURATION);
+    assert!(circuit_breaker.try_acquire());
+    assert!(!circuit_breaker.try_acquire());
+
+    // A failed trial request keeps the circuit breaker open.
+    circuit_breaker.record_failure();
+    sleep(OPEN_DURATION / 2);
+    assert!(!circuit_breaker.try_acquire());
+
+    // A successful trial request closes the circuit breaker.
+    sleep(OPEN_DURATION);
+    assert!(circuit_breaker.try_acquire());
+    circuit_breaker.record_success();
+    assert!(circuit_breaker.try_acquire());
+    assert!(circuit_breaker.try_acquire());
+}
+
+#[test]
+fn disabled() {
+    let circuit_breaker = CircuitBreaker::new(0, OPEN_DURATION);
+    for _ in 0..10 {
+        circuit_breaker.record_failure();
+        assert!(circuit_breaker.try_acquire());
+    }
+}
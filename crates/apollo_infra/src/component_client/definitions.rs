@@ -10,6 +10,8 @@ use crate::component_definitions::ServerError;
 pub enum ClientError {
     #[error("Communication error: {0}")]
     CommunicationFailure(String),
+    #[error("Could not connect to the server: {0}")]
+    ConnectionFailure(String),
     #[error("Could not deserialize server response: {0}")]
     ResponseDeserializationFailure(String),
     #[error("Could not parse the response: {0}")]
@@ -18,6 +20,10 @@ pub enum ClientError {
     ResponseError(StatusCode, ServerError),
     #[error("Got an unexpected response type: {0}")]
     UnexpectedResponse(String),
+    #[error("The request deadline was exceeded.")]
+    DeadlineExceeded,
+    #[error("The circuit breaker is open after consecutive failed requests.")]
+    CircuitBreakerOpen,
 }
 
 pub type ClientResult<T> = Result<T, ClientError>;
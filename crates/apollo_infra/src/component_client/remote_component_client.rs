@@ -1,24 +1,42 @@
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
 
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::converters::deserialize_milliseconds_to_duration;
+use apollo_config::dumping::{
+    prepend_sub_config_name,
+    ser_optional_sub_config,
+    ser_param,
+    SerializeConfig,
+};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use async_trait::async_trait;
 use hyper::body::to_bytes;
+use hyper::client::HttpConnector;
 use hyper::header::CONTENT_TYPE;
 use hyper::{Body, Client, Request as HyperRequest, Response as HyperResponse, StatusCode, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use rand::Rng;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tracing::{debug, trace, warn};
 use validator::Validate;
 
+use super::circuit_breaker::CircuitBreaker;
 use super::definitions::{ClientError, ClientResult};
-use crate::component_definitions::{ComponentClient, ServerError, APPLICATION_OCTET_STREAM};
+use crate::component_definitions::{
+    ComponentClient,
+    ServerError,
+    APPLICATION_OCTET_STREAM,
+    REQUEST_TIMEOUT_HEADER,
+};
 use crate::metrics::RemoteClientMetrics;
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{deserialize_payload, is_framed_payload, serialize_payload, CodecConfig};
+use crate::tls_utils::{client_tls_config, TlsConfig};
 
 // TODO(Tsabary): rename all constants to better describe their purpose.
 const DEFAULT_RETRIES: usize = 150;
@@ -26,8 +44,14 @@ const DEFAULT_IDLE_CONNECTIONS: usize = 10;
 // TODO(Tsabary): add `_SECS` suffix to the constant names and the config fields.
 const DEFAULT_IDLE_TIMEOUT: u64 = 30;
 const DEFAULT_RETRY_INTERVAL: u64 = 1;
-
-// TODO(Tsabary): consider retry delay mechanisms, e.g., exponential backoff, jitter, etc.
+const DEFAULT_MAX_RETRY_INTERVAL: u64 = 10;
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(180);
+const DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD: usize = 5;
+const DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION: Duration = Duration::from_secs(10);
+// The duration to send requests without a format header for, after the server rejected the format
+// of a request. The configured format is tried again once it passes, e.g. after the server
+// upgrades.
+const LEGACY_FORMAT_DURATION: Duration = Duration::from_secs(60);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct RemoteClientConfig {
@@ -35,6 +59,14 @@ pub struct RemoteClientConfig {
     pub idle_connections: usize,
     pub idle_timeout: u64,
     pub retry_interval: u64,
+    pub max_retry_interval: u64,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub request_timeout_millis: Duration,
+    pub circuit_breaker_failure_threshold: usize,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub circuit_breaker_open_duration_millis: Duration,
+    pub codec_config: CodecConfig,
+    pub tls_config: Option<TlsConfig>,
 }
 
 impl Default for RemoteClientConfig {
@@ -44,17 +76,24 @@ impl Default for RemoteClientConfig {
             idle_connections: DEFAULT_IDLE_CONNECTIONS,
             idle_timeout: DEFAULT_IDLE_TIMEOUT,
             retry_interval: DEFAULT_RETRY_INTERVAL,
+            max_retry_interval: DEFAULT_MAX_RETRY_INTERVAL,
+            request_timeout_millis: DEFAULT_REQUEST_TIMEOUT,
+            circuit_breaker_failure_threshold: DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+            circuit_breaker_open_duration_millis: DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION,
+            codec_config: CodecConfig::default(),
+            tls_config: None,
         }
     }
 }
 
 impl SerializeConfig for RemoteClientConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let members = BTreeMap::from_iter([
             ser_param(
                 "retries",
                 &self.retries,
-                "The max number of retries for sending a message.",
+                "The max number of retries for sending a message that failed to connect to the \
+                 server.",
                 ParamPrivacyInput::Public,
             ),
             ser_param(
@@ -72,10 +111,48 @@ impl SerializeConfig for RemoteClientConfig {
             ser_param(
                 "retry_interval",
                 &self.retry_interval,
-                "The duration in seconds to wait between remote connection retries.",
+                "The initial duration in seconds to wait between remote connection retries. The \
+                 duration doubles after every retry, and a random jitter of up to half of it is \
+                 subtracted.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+            ser_param(
+                "max_retry_interval",
+                &self.max_retry_interval,
+                "The maximal duration in seconds to wait between remote connection retries.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "request_timeout_millis",
+                &self.request_timeout_millis.as_millis(),
+                "The deadline in milliseconds of a request, including its retries. The server \
+                 stops waiting for the component to handle the request once it is exceeded.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "circuit_breaker_failure_threshold",
+                &self.circuit_breaker_failure_threshold,
+                "The number of consecutive requests that fail due to an unavailable server, after \
+                 which requests fail without being sent until the circuit breaker open duration \
+                 passes. 0 disables the circuit breaker.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "circuit_breaker_open_duration_millis",
+                &self.circuit_breaker_open_duration_millis.as_millis(),
+                "The duration in milliseconds to reject requests for once the circuit breaker \
+                 opens.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+        vec![
+            members,
+            prepend_sub_config_name(self.codec_config.dump(), "codec_config"),
+            ser_optional_sub_config(&self.tls_config, "tls_config"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
     }
 }
 
@@ -90,7 +167,12 @@ impl SerializeConfig for RemoteClientConfig {
 /// # Fields
 /// - `uri`: URI address of the server.
 /// - `client`: The inner HTTP client that initiates the connection to the server and manages it.
-/// - `config`: Client configuration.
+/// - `config`: Client configuration. If it has a TLS configuration, the client connects to the
+///   server over TLS, authenticates the server, and presents its own certificate to the server.
+/// - `legacy_format_until`: Set when the server rejected the payload format of a request as
+///   unsupported. Until then, requests are sent as JSON without a format header.
+/// - `circuit_breaker`: Rejects requests without sending them after consecutive requests failed due
+///   to an unavailable server.
 ///
 /// # Example
 /// ```rust
@@ -102,6 +184,7 @@ impl SerializeConfig for RemoteClientConfig {
 ///
 /// use crate::apollo_infra::component_client::{RemoteClientConfig, RemoteComponentClient};
 /// use crate::apollo_infra::component_definitions::ComponentClient;
+/// use crate::apollo_infra::serde_utils::{CodecConfig, SerdeCodec};
 ///
 /// // Define your request and response types
 /// #[derive(Serialize, Deserialize, Debug)]
@@ -125,6 +208,8 @@ impl SerializeConfig for RemoteClientConfig {
 ///         idle_connections: usize::MAX,
 ///         idle_timeout: 90,
 ///         retry_interval: 3,
+///         codec_config: CodecConfig { codec: SerdeCodec::Cbor, ..Default::default() },
+///         ..Default::default()
 ///     };
 ///
 ///     const EXAMPLE_HISTOGRAM_METRIC: MetricHistogram = MetricHistogram::new(
@@ -157,9 +242,11 @@ where
     Response: DeserializeOwned,
 {
     uri: Uri,
-    client: Client<hyper::client::HttpConnector>,
+    client: Client<HttpsConnector<HttpConnector>>,
     config: RemoteClientConfig,
     metrics: RemoteClientMetrics,
+    legacy_format_until: Arc<StdMutex<Option<Instant>>>,
+    circuit_breaker: Arc<CircuitBreaker>,
     // [`RemoteComponentClient<Request,Response>`] should be [`Send + Sync`] while [`Request`] and
     // [`Response`] are only [`Send`]. [`Phantom<T>`] is [`Send + Sync`] only if [`T`] is, despite
     // this bound making no sense as the phantom data field is unused. As such, we wrap it as
@@ -181,48 +268,179 @@ where
         port: u16,
         metrics: RemoteClientMetrics,
     ) -> Self {
-        let uri = format!("http://{url}:{port}/").parse().unwrap();
+        let tls_config = client_tls_config(config.tls_config.as_ref())
+            .unwrap_or_else(|err| panic!("Failed to load the TLS configuration: {err}"));
+        let connector_builder = HttpsConnectorBuilder::new().with_tls_config(tls_config);
+        let (scheme, connector) = match config.tls_config {
+            Some(_) => ("https", connector_builder.https_only().enable_http2().build()),
+            None => ("http", connector_builder.https_or_http().enable_http2().build()),
+        };
+        let uri = format!("{scheme}://{url}:{port}/").parse().unwrap();
         let client = Client::builder()
             .http2_only(true)
             .pool_max_idle_per_host(config.idle_connections)
             .pool_idle_timeout(Duration::from_secs(config.idle_timeout))
-            .build_http();
+            .build(connector);
         debug!("RemoteComponentClient created with URI: {:?}", uri);
-        Self { uri, client, config, metrics, _req: PhantomData, _res: PhantomData }
+        let circuit_breaker = Arc::new(CircuitBreaker::new(
+            config.circuit_breaker_failure_threshold,
+            config.circuit_breaker_open_duration_millis,
+        ));
+        Self {
+            uri,
+            client,
+            config,
+            metrics,
+            legacy_format_until: Arc::new(StdMutex::new(None)),
+            circuit_breaker,
+            _req: PhantomData,
+            _res: PhantomData,
+        }
     }
 
-    fn construct_http_request(&self, serialized_request: Vec<u8>) -> HyperRequest<Body> {
+    fn serialize_request(&self, component_request: &Request) -> Vec<u8> {
+        let legacy_format_until =
+            *self.legacy_format_until.lock().expect("Lock should not be poisoned");
+        let codec_config = if legacy_format_until.is_some_and(|until| Instant::now() < until) {
+            CodecConfig::default()
+        } else {
+            self.config.codec_config.clone()
+        };
+        serialize_payload(component_request, &codec_config)
+            .expect("Request serialization should succeed")
+    }
+
+    // Servers reject requests whose payload format they don't support with an explicit error.
+    // Returns whether the request should be resent without a format header, which any server
+    // supports.
+    fn should_fall_back_to_legacy(
+        &self,
+        res: &ClientResult<Response>,
+        serialized_request: &[u8],
+    ) -> bool {
+        let rejected = matches!(
+            res,
+            Err(ClientError::ResponseError(
+                StatusCode::BAD_REQUEST,
+                ServerError::UnsupportedPayloadFormat(_)
+            ))
+        );
+        if !rejected || !is_framed_payload(serialized_request) {
+            return false;
+        }
+        warn!(
+            "Server at {} doesn't support the payload format of the request, sending JSON \
+             requests without a format header for {LEGACY_FORMAT_DURATION:?}.",
+            self.uri
+        );
+        *self.legacy_format_until.lock().expect("Lock should not be poisoned") =
+            Some(Instant::now() + LEGACY_FORMAT_DURATION);
+        true
+    }
+
+    fn construct_http_request(
+        &self,
+        serialized_request: Vec<u8>,
+        timeout: Duration,
+    ) -> HyperRequest<Body> {
         trace!("Constructing remote request");
         HyperRequest::post(self.uri.clone())
             .header(CONTENT_TYPE, APPLICATION_OCTET_STREAM)
+            .header(REQUEST_TIMEOUT_HEADER, timeout.as_millis().to_string())
             .body(Body::from(serialized_request))
             .expect("Request building should succeed")
     }
 
+    async fn send_with_retries(&self, component_request: Request) -> ClientResult<Response> {
+        let deadline = Instant::now() + self.config.request_timeout_millis;
+        // Serialize the request.
+        let mut serialized_request = self.serialize_request(&component_request);
+
+        // Construct the request, and send it up to 'max_retries + 1' times, as long as the deadline
+        // allows. Only requests that failed to connect to the server are resent, as handling a
+        // request isn't necessarily idempotent. Return if received a successful response, or the
+        // last response if all attempts failed.
+        let max_attempts = self.config.retries + 1;
+        let mut retry_interval = Duration::from_secs(self.config.retry_interval);
+        trace!("Starting retry loop: max_attempts = {:?}", max_attempts);
+        for attempt in 1..max_attempts + 1 {
+            trace!("Attempt {} of {:?}", attempt, max_attempts);
+            let mut res = self.try_send_until(serialized_request.clone(), deadline).await;
+            // Resending the request in the legacy format is part of the same attempt, so it's
+            // resent even on the last attempt.
+            if self.should_fall_back_to_legacy(&res, &serialized_request) {
+                serialized_request = self.serialize_request(&component_request);
+                res = self.try_send_until(serialized_request.clone(), deadline).await;
+            }
+            if res.is_ok() {
+                trace!("Request successful on attempt {}/{}", attempt, max_attempts);
+                self.metrics.record_attempt(attempt);
+                return res;
+            }
+            warn!("Request failed on attempt {}/{}: {:?}", attempt, max_attempts, res);
+            let retry_delay = apply_jitter(retry_interval);
+            if !matches!(res, Err(ClientError::ConnectionFailure(_)))
+                || attempt == max_attempts
+                || Instant::now() + retry_delay >= deadline
+            {
+                self.metrics.record_attempt(attempt);
+                return res;
+            }
+            tokio::time::sleep(retry_delay).await;
+            retry_interval =
+                (retry_interval * 2).min(Duration::from_secs(self.config.max_retry_interval));
+        }
+        unreachable!("Guaranteed to return a response before reaching this point.");
+    }
+
+    async fn try_send_until(
+        &self,
+        serialized_request: Vec<u8>,
+        deadline: Instant,
+    ) -> ClientResult<Response> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let http_request = self.construct_http_request(serialized_request, timeout);
+        // Dropping the request future on timeout cancels the request.
+        tokio::time::timeout(timeout, self.try_send(http_request))
+            .await
+            .unwrap_or(Err(ClientError::DeadlineExceeded))
+    }
+
     async fn try_send(&self, http_request: HyperRequest<Body>) -> ClientResult<Response> {
         trace!("Sending HTTP request");
         let http_response = self.client.request(http_request).await.map_err(|err| {
             warn!("HTTP request failed with error: {:?}", err);
-            ClientError::CommunicationFailure(err.to_string())
+            // Only a connection failure guarantees that the request didn't reach the server.
+            if err.is_connect() {
+                ClientError::ConnectionFailure(err.to_string())
+            } else {
+                ClientError::CommunicationFailure(err.to_string())
+            }
         })?;
 
         match http_response.status() {
             StatusCode::OK => {
-                let response_body = get_response_body(http_response).await;
+                let response_body = get_response_body(
+                    http_response,
+                    self.config.codec_config.max_decompressed_size,
+                )
+                .await;
                 trace!("Successfully deserialized response");
                 response_body
             }
             status_code => {
-                warn!(
-                    "Unexpected response status: {:?}. Unable to deserialize response.",
-                    status_code
-                );
-                Err(ClientError::ResponseError(
-                    status_code,
+                warn!("Unexpected response status: {:?}.", status_code);
+                let server_error = get_response_body::<ServerError>(
+                    http_response,
+                    self.config.codec_config.max_decompressed_size,
+                )
+                .await
+                .unwrap_or_else(|_| {
                     ServerError::RequestDeserializationFailure(
                         "Could not deserialize server response".to_string(),
-                    ),
-                ))
+                    )
+                });
+                Err(ClientError::ResponseError(status_code, server_error))
             }
         }
     }
@@ -236,36 +454,41 @@ where
     Response: Send + Serialize + DeserializeOwned + Debug,
 {
     async fn send(&self, component_request: Request) -> ClientResult<Response> {
-        // Serialize the request.
-        let serialized_request = SerdeWrapper::new(component_request)
-            .wrapper_serialize()
-            .expect("Request serialization should succeed");
-
-        // Construct the request, and send it up to 'max_retries + 1' times. Return if received a
-        // successful response, or the last response if all attempts failed.
-        let max_attempts = self.config.retries + 1;
-        trace!("Starting retry loop: max_attempts = {:?}", max_attempts);
-        for attempt in 1..max_attempts + 1 {
-            trace!("Attempt {} of {:?}", attempt, max_attempts);
-            let http_request = self.construct_http_request(serialized_request.clone());
-            let res = self.try_send(http_request).await;
-            if res.is_ok() {
-                trace!("Request successful on attempt {}/{}", attempt, max_attempts);
-                self.metrics.record_attempt(attempt);
-                return res;
-            }
-            warn!("Request failed on attempt {}/{}: {:?}", attempt, max_attempts, res);
-            if attempt == max_attempts {
-                self.metrics.record_attempt(attempt);
-                return res;
-            }
-            tokio::time::sleep(Duration::from_secs(self.config.retry_interval)).await;
+        if !self.circuit_breaker.try_acquire() {
+            warn!("Circuit breaker is open, not sending a request to {}.", self.uri);
+            return Err(ClientError::CircuitBreakerOpen);
         }
-        unreachable!("Guaranteed to return a response before reaching this point.");
+        let res = self.send_with_retries(component_request).await;
+        match &res {
+            Err(err) if is_server_unavailable(err) => self.circuit_breaker.record_failure(),
+            _ => self.circuit_breaker.record_success(),
+        }
+        res
+    }
+}
+
+// Returns whether the error indicates that the server is unavailable, as opposed to a server that
+// rejects the request.
+fn is_server_unavailable(err: &ClientError) -> bool {
+    match err {
+        ClientError::CommunicationFailure(_)
+        | ClientError::ConnectionFailure(_)
+        | ClientError::DeadlineExceeded => true,
+        ClientError::ResponseError(status_code, _) => status_code.is_server_error(),
+        _ => false,
     }
 }
 
-async fn get_response_body<Response>(response: HyperResponse<Body>) -> Result<Response, ClientError>
+// Subtracts a random jitter of up to half of the interval, to spread the retries of clients that
+// failed together.
+fn apply_jitter(interval: Duration) -> Duration {
+    rand::thread_rng().gen_range(interval / 2..=interval)
+}
+
+async fn get_response_body<Response>(
+    response: HyperResponse<Body>,
+    max_decompressed_size: usize,
+) -> Result<Response, ClientError>
 where
     Response: Serialize + DeserializeOwned + Debug,
 {
@@ -273,7 +496,8 @@ where
         .await
         .map_err(|err| ClientError::ResponseParsingFailure(err.to_string()))?;
 
-    SerdeWrapper::<Response>::wrapper_deserialize(&body_bytes)
+    deserialize_payload::<Response>(&body_bytes, max_decompressed_size)
+        .map(|(response, _format)| response)
         .map_err(|err| ClientError::ResponseDeserializationFailure(err.to_string()))
 }
 
@@ -290,6 +514,8 @@ where
             client: self.client.clone(),
             config: self.config.clone(),
             metrics: self.metrics.clone(),
+            legacy_format_until: self.legacy_format_until.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
             _req: PhantomData,
             _res: PhantomData,
         }
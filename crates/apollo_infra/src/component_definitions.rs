@@ -11,6 +11,8 @@ use tracing::{error, info};
 use crate::component_client::ClientResult;
 
 pub(crate) const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+// The remaining time in milliseconds until the deadline of a request.
+pub(crate) const REQUEST_TIMEOUT_HEADER: &str = "x-request-timeout-millis";
 
 #[async_trait]
 pub trait ComponentRequestHandler<Request, Response> {
@@ -90,4 +92,12 @@ where
 pub enum ServerError {
     #[error("Could not deserialize client request: {0}")]
     RequestDeserializationFailure(String),
+    #[error("The payload format of the client request is not supported: {0}")]
+    UnsupportedPayloadFormat(String),
+    #[error("Invalid request timeout: {0}")]
+    InvalidRequestTimeout(String),
+    #[error("The component failed handling the request: {0}")]
+    ComponentFailure(String),
+    #[error("The request deadline was exceeded before the component handled it.")]
+    DeadlineExceeded,
 }
@@ -1,24 +1,82 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::Duration;
 
+use apollo_config::converters::{deserialize_vec, serialize_slice};
+use apollo_config::dumping::{
+    prepend_sub_config_name,
+    ser_optional_sub_config,
+    ser_param,
+    SerializeConfig,
+};
+use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_infra_utils::type_name::short_type_name;
 use async_trait::async_trait;
 use hyper::body::to_bytes;
 use hyper::header::CONTENT_TYPE;
+use hyper::server::accept::Accept;
+use hyper::server::conn::AddrIncoming;
+use hyper::server::Builder;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request as HyperRequest, Response as HyperResponse, Server, StatusCode};
+use hyper_rustls::TlsAcceptor;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tower::limit::ConcurrencyLimitLayer;
 use tower::ServiceBuilder;
 use tracing::{debug, error, trace, warn};
+use validator::Validate;
 
-use crate::component_client::{ClientError, LocalComponentClient};
-use crate::component_definitions::{ComponentClient, ServerError, APPLICATION_OCTET_STREAM};
+use crate::component_client::LocalComponentClient;
+use crate::component_definitions::{
+    ComponentClient,
+    ServerError,
+    APPLICATION_OCTET_STREAM,
+    REQUEST_TIMEOUT_HEADER,
+};
 use crate::component_server::ComponentServerStarter;
 use crate::metrics::RemoteServerMetrics;
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{
+    deserialize_payload,
+    serialize_payload,
+    CodecConfig,
+    PayloadFormat,
+    SerdeWrapper,
+};
+use crate::tls_utils::{server_tls_config, TlsConfig};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate, PartialEq)]
+pub struct RemoteServerConfig {
+    pub codec_config: CodecConfig,
+    pub tls_config: Option<TlsConfig>,
+    #[serde(deserialize_with = "deserialize_vec")]
+    pub allowed_client_identities: Vec<String>,
+}
+
+impl SerializeConfig for RemoteServerConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        let members = BTreeMap::from_iter([ser_param(
+            "allowed_client_identities",
+            &serialize_slice(&self.allowed_client_identities),
+            "A space-separated list of the client identities, i.e., certificate common names or \
+             subject alternative names, that are allowed to connect to the server. If empty, any \
+             client with a certificate signed by the CA is allowed. Applies only if TLS is \
+             enabled.",
+            ParamPrivacyInput::Public,
+        )]);
+        vec![
+            members,
+            prepend_sub_config_name(self.codec_config.dump(), "codec_config"),
+            ser_optional_sub_config(&self.tls_config, "tls_config"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
 
 /// The `RemoteComponentServer` struct is a generic server that handles requests and responses for a
 /// specified component. It receives requests, processes them using the provided component, and
@@ -38,8 +96,15 @@ use crate::serde_utils::SerdeWrapper;
 /// # Fields
 ///
 /// - `component`: The component responsible for handling the requests and generating responses.
+/// - `config`: Server configuration. Responses to requests without a payload format header are sent
+///   as JSON without a format header, regardless of the configured codec. If it has a TLS
+///   configuration, the server accepts only TLS connections of clients with a certificate signed by
+///   the configured CA and, if the allowlist isn't empty, with an allowed identity.
 /// - `socket`: A socket address for the server to listen on.
 ///
+/// Requests that carry a deadline header are answered with a `DeadlineExceeded` error once their
+/// deadline passes, and failures of the component are answered with a `ComponentFailure` error.
+///
 /// # Example
 /// ```rust
 /// // Example usage of the RemoteComponentServer
@@ -50,7 +115,11 @@ use crate::serde_utils::SerdeWrapper;
 ///
 /// use crate::apollo_infra::component_client::LocalComponentClient;
 /// use crate::apollo_infra::component_definitions::{ComponentRequestHandler, ComponentStarter};
-/// use crate::apollo_infra::component_server::{ComponentServerStarter, RemoteComponentServer};
+/// use crate::apollo_infra::component_server::{
+///     ComponentServerStarter,
+///     RemoteComponentServer,
+///     RemoteServerConfig,
+/// };
 /// use crate::apollo_infra::metrics::RemoteServerMetrics;
 ///
 /// const REMOTE_MESSAGES_RECEIVED: MetricCounter = MetricCounter::new(
@@ -113,6 +182,7 @@ use crate::serde_utils::SerdeWrapper;
 ///     // Instantiate the server.
 ///     let mut server = RemoteComponentServer::<MyRequest, MyResponse>::new(
 ///         local_client,
+///         RemoteServerConfig::default(),
 ///         ip_address,
 ///         port,
 ///         max_concurrency,
@@ -136,6 +206,7 @@ where
 {
     socket: SocketAddr,
     local_client: LocalComponentClient<Request, Response>,
+    config: RemoteServerConfig,
     max_concurrency: usize,
     metrics: Arc<RemoteServerMetrics>,
 }
@@ -147,6 +218,7 @@ where
 {
     pub fn new(
         local_client: LocalComponentClient<Request, Response>,
+        config: RemoteServerConfig,
         ip: IpAddr,
         port: u16,
         max_concurrency: usize,
@@ -155,74 +227,178 @@ where
         metrics.register();
         Self {
             local_client,
+            config,
             socket: SocketAddr::new(ip, port),
             max_concurrency,
             metrics: Arc::new(metrics),
         }
     }
 
+    async fn serve<I>(&self, server_builder: Builder<I>)
+    where
+        I: Accept + Send,
+        I::Conn: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+        I::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let make_svc = make_service_fn(|_conn: &I::Conn| {
+            let local_client = self.local_client.clone();
+            let max_concurrency = self.max_concurrency;
+            debug!(
+                "Initializing service for new connection with max_concurrency: {:?}",
+                max_concurrency
+            );
+            let codec_config = self.config.codec_config.clone();
+            let metrics = self.metrics.clone();
+            async move {
+                let app_service = service_fn(move |req| {
+                    debug!("Received request: {:?}", req);
+                    Self::remote_component_server_handler(
+                        req,
+                        local_client.clone(),
+                        codec_config.clone(),
+                        metrics.clone(),
+                    )
+                });
+
+                // Apply the ConcurrencyLimitLayer middleware
+                let service = ServiceBuilder::new()
+                    .layer(ConcurrencyLimitLayer::new(max_concurrency))
+                    .service(app_service);
+
+                Ok::<_, hyper::Error>(service)
+            }
+        });
+        server_builder
+            .serve(make_svc)
+            .await
+            .unwrap_or_else(|e| panic!("HttpServerStartError: {e}"));
+    }
+
     async fn remote_component_server_handler(
         http_request: HyperRequest<Body>,
         local_client: LocalComponentClient<Request, Response>,
+        codec_config: CodecConfig,
         metrics: Arc<RemoteServerMetrics>,
     ) -> Result<HyperResponse<Body>, hyper::Error> {
         trace!("Received HTTP request: {:?}", http_request);
+        let timeout = match parse_request_timeout(&http_request) {
+            Ok(timeout) => timeout,
+            Err(server_error) => {
+                warn!("Rejecting request: {server_error}");
+                return Ok(server_error_response(StatusCode::BAD_REQUEST, server_error));
+            }
+        };
         let body_bytes = to_bytes(http_request.into_body()).await?;
         trace!("Extracted {} bytes from HTTP request body", body_bytes.len());
 
         metrics.increment_total_received();
 
-        let http_response = match SerdeWrapper::<Request>::wrapper_deserialize(&body_bytes)
-            .map_err(|err| ClientError::ResponseDeserializationFailure(err.to_string()))
-        {
-            Ok(request) => {
-                debug!("Successfully deserialized request: {:?}", request);
-                metrics.increment_valid_received();
+        let http_response =
+            match deserialize_payload::<Request>(&body_bytes, codec_config.max_decompressed_size) {
+                Ok((request, request_format)) => {
+                    debug!("Successfully deserialized request: {:?}", request);
+                    metrics.increment_valid_received();
 
-                // Wrap the send operation in a tokio::spawn as it is NOT a cancel-safe operation.
-                // Even if the current task is cancelled, the inner task will continue to run.
-                let response = tokio::spawn(async move { local_client.send(request).await })
-                    .await
-                    .expect("Should be able to extract value from the task");
+                    // Wrap the send operation in a tokio::spawn as it is NOT a cancel-safe
+                    // operation. Even if the current task is cancelled, or the
+                    // deadline of the request passes, the inner task will
+                    // continue to run.
+                    let task = tokio::spawn(async move { local_client.send(request).await });
+                    let response = match timeout {
+                        Some(timeout) => tokio::time::timeout(timeout, task).await,
+                        None => Ok(task.await),
+                    };
 
-                metrics.increment_processed();
+                    metrics.increment_processed();
 
-                match response {
-                    Ok(response) => {
-                        debug!("Local client processed request successfully: {:?}", response);
-                        HyperResponse::builder()
-                            .status(StatusCode::OK)
-                            .header(CONTENT_TYPE, APPLICATION_OCTET_STREAM)
-                            .body(Body::from(
-                                SerdeWrapper::new(response)
-                                    .wrapper_serialize()
-                                    .expect("Response serialization should succeed"),
-                            ))
-                    }
-                    Err(error) => {
-                        panic!(
-                            "Remote server failed sending with its local client. Error: {error:?}"
-                        );
+                    match response {
+                        Ok(Ok(Ok(response))) => {
+                            debug!("Local client processed request successfully: {:?}", response);
+                            // Clients that send requests without a format header don't support it
+                            // in responses.
+                            let codec_config = match request_format {
+                                PayloadFormat::Legacy => CodecConfig::default(),
+                                PayloadFormat::Framed { .. } => codec_config,
+                            };
+                            HyperResponse::builder()
+                                .status(StatusCode::OK)
+                                .header(CONTENT_TYPE, APPLICATION_OCTET_STREAM)
+                                .body(Body::from(
+                                    serialize_payload(&response, &codec_config)
+                                        .expect("Response serialization should succeed"),
+                                ))
+                                .expect("Response building should succeed")
+                        }
+                        Ok(Ok(Err(error))) => {
+                            error!("Remote server failed sending with its local client: {error:?}");
+                            server_error_response(
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                ServerError::ComponentFailure(error.to_string()),
+                            )
+                        }
+                        Ok(Err(join_error)) => {
+                            error!("Local client task failed: {join_error:?}");
+                            server_error_response(
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                ServerError::ComponentFailure(join_error.to_string()),
+                            )
+                        }
+                        Err(_) => {
+                            warn!("Request deadline of {timeout:?} exceeded.");
+                            server_error_response(
+                                StatusCode::GATEWAY_TIMEOUT,
+                                ServerError::DeadlineExceeded,
+                            )
+                        }
                     }
                 }
-            }
-            Err(error) => {
-                error!("Failed to deserialize request: {:?}", error);
-                let server_error = ServerError::RequestDeserializationFailure(error.to_string());
-                HyperResponse::builder().status(StatusCode::BAD_REQUEST).body(Body::from(
-                    SerdeWrapper::new(server_error)
-                        .wrapper_serialize()
-                        .expect("Server error serialization should succeed"),
-                ))
-            }
-        }
-        .expect("Response building should succeed");
+                Err(error) => {
+                    error!("Failed to deserialize request: {:?}", error);
+                    // Tells the client to resend the request in a supported format.
+                    let server_error = if error.is_unsupported_format() {
+                        ServerError::UnsupportedPayloadFormat(error.to_string())
+                    } else {
+                        ServerError::RequestDeserializationFailure(error.to_string())
+                    };
+                    server_error_response(StatusCode::BAD_REQUEST, server_error)
+                }
+            };
         trace!("Built HTTP response: {:?}", http_response);
 
         Ok(http_response)
     }
 }
 
+// Returns the timeout of the request, if its client set one.
+fn parse_request_timeout(
+    http_request: &HyperRequest<Body>,
+) -> Result<Option<Duration>, ServerError> {
+    let Some(header_value) = http_request.headers().get(REQUEST_TIMEOUT_HEADER) else {
+        return Ok(None);
+    };
+    header_value
+        .to_str()
+        .ok()
+        .and_then(|millis| millis.parse().ok())
+        .map(|millis| Some(Duration::from_millis(millis)))
+        .ok_or_else(|| ServerError::InvalidRequestTimeout(format!("{header_value:?}")))
+}
+
+// Server errors are sent as JSON, which every client can deserialize.
+fn server_error_response(
+    status_code: StatusCode,
+    server_error: ServerError,
+) -> HyperResponse<Body> {
+    HyperResponse::builder()
+        .status(status_code)
+        .body(Body::from(
+            SerdeWrapper::new(server_error)
+                .wrapper_serialize()
+                .expect("Server error serialization should succeed"),
+        ))
+        .expect("Response building should succeed")
+}
+
 #[async_trait]
 impl<Request, Response> ComponentServerStarter for RemoteComponentServer<Request, Response>
 where
@@ -231,37 +407,22 @@ where
 {
     async fn start(&mut self) {
         debug!("Starting server on socket: {:?}", self.socket);
-        let make_svc = make_service_fn(|_conn| {
-            let local_client = self.local_client.clone();
-            let max_concurrency = self.max_concurrency;
-            debug!(
-                "Initializing service for new connection with max_concurrency: {:?}",
-                max_concurrency
-            );
-            let metrics = self.metrics.clone();
-            async move {
-                let app_service = service_fn(move |req| {
-                    debug!("Received request: {:?}", req);
-                    Self::remote_component_server_handler(
-                        req,
-                        local_client.clone(),
-                        metrics.clone(),
-                    )
-                });
-
-                // Apply the ConcurrencyLimitLayer middleware
-                let service = ServiceBuilder::new()
-                    .layer(ConcurrencyLimitLayer::new(max_concurrency))
-                    .service(app_service);
-
-                Ok::<_, hyper::Error>(service)
-            }
-        });
         debug!("Binding server to socket: {:?}", self.socket);
-        Server::bind(&self.socket)
-            .serve(make_svc)
-            .await
+        let incoming = AddrIncoming::bind(&self.socket)
             .unwrap_or_else(|e| panic!("HttpServerStartError: {e}"));
+        match &self.config.tls_config {
+            Some(tls_config) => {
+                let tls_server_config =
+                    server_tls_config(tls_config, &self.config.allowed_client_identities)
+                        .unwrap_or_else(|e| panic!("Failed to load the TLS configuration: {e}"));
+                let acceptor = TlsAcceptor::builder()
+                    .with_tls_config(tls_server_config)
+                    .with_http2_alpn()
+                    .with_incoming(incoming);
+                self.serve(Server::builder(acceptor)).await;
+            }
+            None => self.serve(Server::builder(incoming)).await,
+        }
     }
 }
 
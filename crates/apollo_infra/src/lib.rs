@@ -1,8 +1,10 @@
 pub mod component_client;
 pub mod component_definitions;
 pub mod component_server;
+mod human_readable;
 pub mod metrics;
 pub mod serde_utils;
 #[cfg(test)]
 pub mod tests;
+pub mod tls_utils;
 pub mod trace_util;
@@ -8,6 +8,8 @@ define_metrics!(
         MetricCounter { BATCHER_LOCAL_MSGS_PROCESSED, "batcher_local_msgs_processed", "Counter of messages processed by batcher local server", init = 0 },
         MetricCounter { CLASS_MANAGER_LOCAL_MSGS_RECEIVED, "class_manager_local_msgs_received", "Counter of messages received by class manager local server", init = 0 },
         MetricCounter { CLASS_MANAGER_LOCAL_MSGS_PROCESSED, "class_manager_local_msgs_processed", "Counter of messages processed by class manager local server", init = 0 },
+        MetricCounter { COMMITTER_LOCAL_MSGS_RECEIVED, "committer_local_msgs_received", "Counter of messages received by committer local server", init = 0 },
+        MetricCounter { COMMITTER_LOCAL_MSGS_PROCESSED, "committer_local_msgs_processed", "Counter of messages processed by committer local server", init = 0 },
         MetricCounter { GATEWAY_LOCAL_MSGS_RECEIVED, "gateway_local_msgs_received", "Counter of messages received by gateway local server", init = 0 },
         MetricCounter { GATEWAY_LOCAL_MSGS_PROCESSED, "gateway_local_msgs_processed", "Counter of messages processed by gateway local server", init = 0 },
         MetricCounter { L1_ENDPOINT_MONITOR_LOCAL_MSGS_RECEIVED, "l1_endpoint_monitor_local_msgs_received", "Counter of messages received by L1 endpoint monitor local server", init = 0 },
@@ -33,6 +35,9 @@ define_metrics!(
         MetricCounter { CLASS_MANAGER_REMOTE_MSGS_RECEIVED, "class_manager_remote_msgs_received", "Counter of messages received by class manager remote server", init = 0 },
         MetricCounter { CLASS_MANAGER_REMOTE_VALID_MSGS_RECEIVED, "class_manager_remote_valid_msgs_received", "Counter of valid messages received by class manager remote server", init = 0 },
         MetricCounter { CLASS_MANAGER_REMOTE_MSGS_PROCESSED, "class_manager_remote_msgs_processed", "Counter of messages processed by class manager remote server", init = 0 },
+        MetricCounter { COMMITTER_REMOTE_MSGS_RECEIVED, "committer_remote_msgs_received", "Counter of messages received by committer remote server", init = 0 },
+        MetricCounter { COMMITTER_REMOTE_VALID_MSGS_RECEIVED, "committer_remote_valid_msgs_received", "Counter of valid messages received by committer remote server", init = 0 },
+        MetricCounter { COMMITTER_REMOTE_MSGS_PROCESSED, "committer_remote_msgs_processed", "Counter of messages processed by committer remote server", init = 0 },
         MetricCounter { GATEWAY_REMOTE_MSGS_RECEIVED, "gateway_remote_msgs_received", "Counter of messages received by gateway remote server", init = 0 },
         MetricCounter { GATEWAY_REMOTE_VALID_MSGS_RECEIVED, "gateway_remote_valid_msgs_received", "Counter of valid messages received by gateway remote server", init = 0 },
         MetricCounter { GATEWAY_REMOTE_MSGS_PROCESSED, "gateway_remote_msgs_processed", "Counter of messages processed by gateway remote server", init = 0 },
@@ -63,6 +68,7 @@ define_metrics!(
         // Local server queue depths
         MetricGauge { BATCHER_LOCAL_QUEUE_DEPTH, "batcher_local_queue_depth", "The depth of the batcher's local message queue" },
         MetricGauge { CLASS_MANAGER_LOCAL_QUEUE_DEPTH, "class_manager_local_queue_depth", "The depth of the class manager's local message queue" },
+        MetricGauge { COMMITTER_LOCAL_QUEUE_DEPTH, "committer_local_queue_depth", "The depth of the committer's local message queue" },
         MetricGauge { GATEWAY_LOCAL_QUEUE_DEPTH, "gateway_local_queue_depth", "The depth of the gateway's local message queue" },
         MetricGauge { L1_ENDPOINT_MONITOR_LOCAL_QUEUE_DEPTH, "l1_endpoint_monitor_local_queue_depth", "The depth of the L1 endpoint monitor's local message queue" },
         MetricGauge { L1_PROVIDER_LOCAL_QUEUE_DEPTH, "l1_provider_local_queue_depth", "The depth of the L1 provider's local message queue" },
@@ -75,6 +81,7 @@ define_metrics!(
         // Remote client metrics
         MetricHistogram { BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS, "batcher_remote_client_send_attempts", "Required number of remote connection attempts made by a batcher remote client"},
         MetricHistogram { CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS, "class_manager_remote_client_send_attempts", "Required number of remote connection attempts made by a class manager remote client"},
+        MetricHistogram { COMMITTER_REMOTE_CLIENT_SEND_ATTEMPTS, "committer_remote_client_send_attempts", "Required number of remote connection attempts made by a committer remote client"},
         MetricHistogram { GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS, "gateway_remote_client_send_attempts", "Required number of remote connection attempts made by a gateway remote client"},
         MetricHistogram { L1_ENDPOINT_MONITOR_SEND_ATTEMPTS, "l1_endpoint_monitor_remote_client_send_attempts", "Required number of remote connection attempts made by a L1 endpoint monitor remote client"},
         MetricHistogram { L1_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS, "l1_provider_remote_client_send_attempts", "Required number of remote connection attempts made by a L1 provider remote client"},
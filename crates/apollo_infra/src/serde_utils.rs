@@ -1,14 +1,36 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::io::Read;
 
+use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_slice, to_vec};
+use thiserror::Error;
+
+use crate::human_readable::HumanReadable;
 
 #[cfg(test)]
 #[path = "serde_utils_test.rs"]
 pub mod serde_utils_test;
 
-// A generic wrapper struct for binary serialization and deserialization, used for remote component
-// communication.
+// The first byte of a payload that starts with a format header. It is not a valid first byte of a
+// JSON payload, which allows telling apart payloads of peers that don't send a format header.
+const PAYLOAD_HEADER_MAGIC: u8 = 0xff;
+// The version of the format header. Bumped whenever the header layout or the meaning of its fields
+// changes.
+const PAYLOAD_HEADER_VERSION: u8 = 1;
+// Magic, version, codec and compression.
+const PAYLOAD_HEADER_LENGTH: usize = 4;
+
+// Use the default compression level of zstd.
+const ZSTD_COMPRESSION_LEVEL: i32 = 0;
+const DEFAULT_COMPRESSION_THRESHOLD: usize = 1 << 16;
+const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 1 << 28; // 256MB.
+
+// A generic wrapper struct for JSON serialization and deserialization, used for remote component
+// communication with peers that don't send a format header.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SerdeWrapper<T> {
     data: T,
@@ -30,3 +52,254 @@ where
         from_slice(bytes).map(|serde_wrapper: Self| serde_wrapper.data)
     }
 }
+
+// A borrowing counterpart of `SerdeWrapper` with the same serialization.
+#[derive(Serialize)]
+struct SerdeWrapperRef<'a, T> {
+    data: &'a T,
+}
+
+/// The codec used to encode the payloads of remote component communication.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SerdeCodec {
+    /// JSON. Uncompressed JSON payloads are sent without a format header, and are understood by
+    /// peers of any version.
+    #[default]
+    Json,
+    /// CBOR, a compact binary encoding. The types are encoded with their human readable
+    /// representation, as in JSON.
+    Cbor,
+}
+
+/// The compression applied to large payloads of remote component communication.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SerdeCompression {
+    #[default]
+    None,
+    Zstd,
+}
+
+/// The format of an encoded payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayloadFormat {
+    /// JSON without a format header, as sent by peers that don't support other formats.
+    Legacy,
+    /// A payload with a format header.
+    Framed { codec: SerdeCodec, compression: SerdeCompression },
+}
+
+#[derive(Debug, Error)]
+pub enum SerdeError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("CBOR serialization failed: {0}")]
+    CborSerialization(String),
+    #[error("CBOR deserialization failed: {0}")]
+    CborDeserialization(String),
+    #[error(transparent)]
+    Compression(#[from] std::io::Error),
+    #[error("The payload is shorter than its format header.")]
+    MissingHeader,
+    #[error("Unsupported payload format version {0}.")]
+    UnsupportedVersion(u8),
+    #[error("Unknown payload codec {0}.")]
+    UnknownCodec(u8),
+    #[error("Unknown payload compression {0}.")]
+    UnknownCompression(u8),
+    #[error("The decompressed payload exceeds the maximal size of {0} bytes.")]
+    DecompressedPayloadTooLarge(usize),
+}
+
+impl SerdeError {
+    /// Returns whether the payload has a format header that isn't supported, as opposed to a
+    /// malformed payload.
+    pub fn is_unsupported_format(&self) -> bool {
+        matches!(
+            self,
+            Self::UnsupportedVersion(_) | Self::UnknownCodec(_) | Self::UnknownCompression(_)
+        )
+    }
+}
+
+/// The configuration of the payload encoding of a remote component client or server.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CodecConfig {
+    pub codec: SerdeCodec,
+    pub compression: SerdeCompression,
+    pub compression_threshold: usize,
+    pub max_decompressed_size: usize,
+}
+
+impl Default for CodecConfig {
+    fn default() -> Self {
+        Self {
+            codec: SerdeCodec::default(),
+            compression: SerdeCompression::default(),
+            compression_threshold: DEFAULT_COMPRESSION_THRESHOLD,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+}
+
+impl SerializeConfig for CodecConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "codec",
+                &self.codec,
+                "The codec of the sent payloads. Payloads of any codec are accepted. A codec \
+                 other than Json requires the peer to support payload format headers.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "compression",
+                &self.compression,
+                "The compression of sent payloads that exceed the compression threshold. \
+                 Compressed payloads require the peer to support payload format headers.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "compression_threshold",
+                &self.compression_threshold,
+                "The minimal size in bytes of a sent payload to compress.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_decompressed_size",
+                &self.max_decompressed_size,
+                "The maximal size in bytes of a received compressed payload after it is \
+                 decompressed. Larger payloads are rejected.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// Encodes a payload according to the given configuration. Uncompressed JSON payloads are encoded
+/// without a format header.
+pub fn serialize_payload<T: Serialize>(
+    data: &T,
+    codec_config: &CodecConfig,
+) -> Result<Vec<u8>, SerdeError> {
+    let wrapper = SerdeWrapperRef { data };
+    let mut encoded = match codec_config.codec {
+        SerdeCodec::Json => to_vec(&wrapper)?,
+        SerdeCodec::Cbor => {
+            let mut encoded = Vec::new();
+            ciborium::into_writer(&HumanReadable(&wrapper), &mut encoded)
+                .map_err(|err| SerdeError::CborSerialization(err.to_string()))?;
+            encoded
+        }
+    };
+
+    let compression = if encoded.len() >= codec_config.compression_threshold {
+        codec_config.compression
+    } else {
+        SerdeCompression::None
+    };
+    if codec_config.codec == SerdeCodec::Json && compression == SerdeCompression::None {
+        return Ok(encoded);
+    }
+    if compression == SerdeCompression::Zstd {
+        encoded = zstd::encode_all(encoded.as_slice(), ZSTD_COMPRESSION_LEVEL)?;
+    }
+
+    let mut payload = Vec::with_capacity(PAYLOAD_HEADER_LENGTH + encoded.len());
+    payload.extend_from_slice(&[
+        PAYLOAD_HEADER_MAGIC,
+        PAYLOAD_HEADER_VERSION,
+        codec_to_byte(codec_config.codec),
+        compression_to_byte(compression),
+    ]);
+    payload.extend_from_slice(&encoded);
+    Ok(payload)
+}
+
+/// Decodes a payload of any supported format, and returns it with its format. Compressed payloads
+/// whose decompressed size exceeds `max_decompressed_size` are rejected.
+pub fn deserialize_payload<T: DeserializeOwned>(
+    bytes: &[u8],
+    max_decompressed_size: usize,
+) -> Result<(T, PayloadFormat), SerdeError> {
+    if !is_framed_payload(bytes) {
+        let wrapper: SerdeWrapper<T> = from_slice(bytes)?;
+        return Ok((wrapper.data, PayloadFormat::Legacy));
+    }
+
+    let Some((header, encoded)) = bytes.split_at_checked(PAYLOAD_HEADER_LENGTH) else {
+        return Err(SerdeError::MissingHeader);
+    };
+    if header[1] != PAYLOAD_HEADER_VERSION {
+        return Err(SerdeError::UnsupportedVersion(header[1]));
+    }
+    let codec = codec_from_byte(header[2])?;
+    let compression = compression_from_byte(header[3])?;
+
+    let decompressed;
+    let encoded = match compression {
+        SerdeCompression::None => encoded,
+        SerdeCompression::Zstd => {
+            decompressed = decompress(encoded, max_decompressed_size)?;
+            decompressed.as_slice()
+        }
+    };
+    let wrapper: SerdeWrapper<T> = match codec {
+        SerdeCodec::Json => from_slice(encoded)?,
+        SerdeCodec::Cbor => {
+            ciborium::from_reader::<HumanReadable<_>, _>(encoded)
+                .map_err(|err| SerdeError::CborDeserialization(err.to_string()))?
+                .0
+        }
+    };
+    Ok((wrapper.data, PayloadFormat::Framed { codec, compression }))
+}
+
+// Decompresses a zstd payload without decompressing more than the given size.
+fn decompress(encoded: &[u8], max_decompressed_size: usize) -> Result<Vec<u8>, SerdeError> {
+    // Reads one byte beyond the limit to tell whether the payload exceeds it.
+    let limit = u64::try_from(max_decompressed_size).expect("usize should fit in u64");
+    let mut decompressed = Vec::new();
+    zstd::stream::Decoder::new(encoded)?
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut decompressed)?;
+    if decompressed.len() > max_decompressed_size {
+        return Err(SerdeError::DecompressedPayloadTooLarge(max_decompressed_size));
+    }
+    Ok(decompressed)
+}
+
+/// Returns whether the payload starts with a format header.
+pub fn is_framed_payload(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&PAYLOAD_HEADER_MAGIC)
+}
+
+// The byte values are part of the format header and must not change.
+fn codec_to_byte(codec: SerdeCodec) -> u8 {
+    match codec {
+        SerdeCodec::Json => 0,
+        SerdeCodec::Cbor => 1,
+    }
+}
+
+fn codec_from_byte(byte: u8) -> Result<SerdeCodec, SerdeError> {
+    match byte {
+        0 => Ok(SerdeCodec::Json),
+        1 => Ok(SerdeCodec::Cbor),
+        _ => Err(SerdeError::UnknownCodec(byte)),
+    }
+}
+
+fn compression_to_byte(compression: SerdeCompression) -> u8 {
+    match compression {
+        SerdeCompression::None => 0,
+        SerdeCompression::Zstd => 1,
+    }
+}
+
+fn compression_from_byte(byte: u8) -> Result<SerdeCompression, SerdeError> {
+    match byte {
+        0 => Ok(SerdeCompression::None),
+        1 => Ok(SerdeCompression::Zstd),
+        _ => Err(SerdeError::UnknownCompression(byte)),
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::Debug;
 
+use assert_matches::assert_matches;
+use rstest::rstest;
 use serde::{Deserialize, Serialize};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::rpc_transaction::{
@@ -13,7 +15,45 @@ use starknet_api::rpc_transaction::{
 };
 use starknet_types_core::felt::Felt;
 
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{
+    deserialize_payload,
+    serialize_payload,
+    CodecConfig,
+    PayloadFormat,
+    SerdeCodec,
+    SerdeCompression,
+    SerdeError,
+    SerdeWrapper,
+};
+
+const MAX_DECOMPRESSED_SIZE: usize = 1 << 20;
+
+const CODEC_CONFIGS: [CodecConfig; 4] = [
+    CodecConfig {
+        codec: SerdeCodec::Json,
+        compression: SerdeCompression::None,
+        compression_threshold: 0,
+        max_decompressed_size: MAX_DECOMPRESSED_SIZE,
+    },
+    CodecConfig {
+        codec: SerdeCodec::Json,
+        compression: SerdeCompression::Zstd,
+        compression_threshold: 0,
+        max_decompressed_size: MAX_DECOMPRESSED_SIZE,
+    },
+    CodecConfig {
+        codec: SerdeCodec::Cbor,
+        compression: SerdeCompression::None,
+        compression_threshold: 0,
+        max_decompressed_size: MAX_DECOMPRESSED_SIZE,
+    },
+    CodecConfig {
+        codec: SerdeCodec::Cbor,
+        compression: SerdeCompression::Zstd,
+        compression_threshold: 0,
+        max_decompressed_size: MAX_DECOMPRESSED_SIZE,
+    },
+];
 
 fn test_generic_data_serde<T>(data: T)
 where
@@ -25,6 +65,13 @@ where
 
     // Assert that the data is the same after serialization and deserialization.
     assert_eq!(data, decoded);
+
+    // Repeat with every codec and compression.
+    for codec_config in CODEC_CONFIGS {
+        let encoded = serialize_payload(&data, &codec_config).unwrap();
+        let (decoded, _format) = deserialize_payload::<T>(&encoded, MAX_DECOMPRESSED_SIZE).unwrap();
+        assert_eq!(data, decoded, "Codec config: {codec_config:?}");
+    }
 }
 
 #[test]
@@ -108,3 +155,70 @@ fn serde_rpc_declare_tx() {
 
     test_generic_data_serde(RpcTransaction::Declare(rpc_declare_tx));
 }
+
+#[rstest]
+#[case::json(CODEC_CONFIGS[0].clone(), PayloadFormat::Legacy)]
+#[case::compressed_json(
+    CODEC_CONFIGS[1].clone(),
+    PayloadFormat::Framed { codec: SerdeCodec::Json, compression: SerdeCompression::Zstd }
+)]
+#[case::cbor(
+    CODEC_CONFIGS[2].clone(),
+    PayloadFormat::Framed { codec: SerdeCodec::Cbor, compression: SerdeCompression::None }
+)]
+#[case::small_payload_not_compressed(
+    CodecConfig { compression_threshold: 1 << 10, ..CODEC_CONFIGS[3].clone() },
+    PayloadFormat::Framed { codec: SerdeCodec::Cbor, compression: SerdeCompression::None }
+)]
+fn payload_format(#[case] codec_config: CodecConfig, #[case] expected_format: PayloadFormat) {
+    let encoded = serialize_payload(&Felt::ONE, &codec_config).unwrap();
+    let (_decoded, format) = deserialize_payload::<Felt>(&encoded, MAX_DECOMPRESSED_SIZE).unwrap();
+    assert_eq!(format, expected_format);
+}
+
+#[test]
+fn json_payload_is_legacy_compatible() {
+    let data = Felt::ONE;
+    let encoded = serialize_payload(&data, &CodecConfig::default()).unwrap();
+    assert_eq!(encoded, SerdeWrapper::new(data).wrapper_serialize().unwrap());
+}
+
+#[test]
+fn cbor_payload_is_smaller_than_json() {
+    // Felts are encoded as hex strings by both codecs, but numbers are encoded in binary by CBOR.
+    let data = vec![u64::MAX; 100];
+    let json = serialize_payload(&data, &CODEC_CONFIGS[0]).unwrap();
+    let cbor = serialize_payload(&data, &CODEC_CONFIGS[2]).unwrap();
+    assert!(cbor.len() * 3 < json.len() * 2, "CBOR: {}, JSON: {}", cbor.len(), json.len());
+}
+
+#[test]
+fn unsupported_payload_format() {
+    let mut encoded = serialize_payload(&Felt::ONE, &CODEC_CONFIGS[2]).unwrap();
+    encoded[1] += 1;
+    assert_matches!(
+        deserialize_payload::<Felt>(&encoded, MAX_DECOMPRESSED_SIZE),
+        Err(SerdeError::UnsupportedVersion(version)) if version == encoded[1]
+    );
+
+    assert_matches!(
+        deserialize_payload::<Felt>(&encoded[..2], MAX_DECOMPRESSED_SIZE),
+        Err(SerdeError::MissingHeader)
+    );
+}
+
+#[test]
+fn decompressed_payload_too_large() {
+    let data = vec![Felt::MAX; 100];
+    // The payload is compressed as is, after its format header.
+    let decompressed_size = serialize_payload(&data, &CODEC_CONFIGS[0]).unwrap().len();
+    let encoded = serialize_payload(&data, &CODEC_CONFIGS[1]).unwrap();
+    let (decoded, _format) = deserialize_payload::<Vec<Felt>>(&encoded, decompressed_size).unwrap();
+    assert_eq!(decoded, data);
+
+    assert_matches!(
+        deserialize_payload::<Vec<Felt>>(&encoded, decompressed_size - 1),
+        Err(SerdeError::DecompressedPayloadTooLarge(max_decompressed_size))
+        if max_decompressed_size == decompressed_size - 1
+    );
+}
@@ -25,6 +25,7 @@ use crate::component_server::{
     ComponentServerStarter,
     ConcurrentLocalComponentServer,
     RemoteComponentServer,
+    RemoteServerConfig,
 };
 use crate::tests::{
     AVAILABLE_PORTS,
@@ -148,6 +149,7 @@ async fn setup_concurrent_remote_test() -> RemoteConcurrentComponentClient {
     let max_concurrency = 10;
     let mut concurrent_remote_server = RemoteComponentServer::new(
         local_client.clone(),
+        RemoteServerConfig::default(),
         socket.ip(),
         socket.port(),
         max_concurrency,
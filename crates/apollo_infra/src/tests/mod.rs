@@ -3,12 +3,16 @@ mod local_component_client_server_test;
 mod remote_component_client_server_test;
 mod server_metrics_test;
 
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::Path;
 use std::sync::Arc;
 
 use apollo_infra_utils::test_utils::{AvailablePorts, TestIdentifier};
 use apollo_metrics::metrics::{MetricCounter, MetricGauge, MetricHistogram, MetricScope};
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, KeyPair};
 use serde::{Deserialize, Serialize};
 use starknet_types_core::felt::Felt;
 use tokio::sync::Mutex;
@@ -16,6 +20,7 @@ use tokio::sync::Mutex;
 use crate::component_client::ClientResult;
 use crate::component_definitions::{ComponentRequestHandler, ComponentStarter};
 use crate::metrics::{LocalServerMetrics, RemoteClientMetrics, RemoteServerMetrics};
+use crate::tls_utils::TlsConfig;
 
 pub(crate) type ValueA = Felt;
 pub(crate) type ValueB = Felt;
@@ -97,6 +102,34 @@ pub static AVAILABLE_PORTS: Lazy<Arc<Mutex<AvailablePorts>>> = Lazy::new(|| {
     Arc::new(Mutex::new(available_ports))
 });
 
+// Generates a CA and a certificate signed by it for each of the given identities in the directory,
+// and returns their TLS configurations. Each certificate has its identity as its common name and
+// the local host as its subject alternative name.
+pub(crate) fn generate_tls_configs(dir: &Path, identities: &[&str]) -> Vec<TlsConfig> {
+    let ca_key = KeyPair::generate().unwrap();
+    let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params.distinguished_name.push(DnType::CommonName, "test_ca");
+    let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+    let ca_cert_path = dir.join("ca_cert.pem");
+    fs::write(&ca_cert_path, ca_cert.pem()).unwrap();
+
+    identities
+        .iter()
+        .map(|identity| {
+            let key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(vec![Ipv4Addr::LOCALHOST.to_string()]).unwrap();
+            params.distinguished_name.push(DnType::CommonName, *identity);
+            let cert = params.signed_by(&key, &ca_cert, &ca_key).unwrap();
+            let cert_path = dir.join(format!("{identity}_cert.pem"));
+            let key_path = dir.join(format!("{identity}_key.pem"));
+            fs::write(&cert_path, cert.pem()).unwrap();
+            fs::write(&key_path, key.serialize_pem()).unwrap();
+            TlsConfig { ca_cert_path: ca_cert_path.clone(), cert_path, key_path }
+        })
+        .collect()
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ComponentARequest {
     AGetValue,
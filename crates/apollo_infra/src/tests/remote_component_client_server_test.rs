@@ -1,7 +1,9 @@
 use std::fmt::Debug;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
+use assert_matches::assert_matches;
 use async_trait::async_trait;
 use hyper::body::to_bytes;
 use hyper::header::CONTENT_TYPE;
@@ -11,6 +13,7 @@ use rstest::rstest;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use starknet_types_core::felt::Felt;
+use tempfile::tempdir;
 use tokio::sync::mpsc::channel;
 use tokio::sync::Mutex;
 use tokio::task;
@@ -27,14 +30,17 @@ use crate::component_definitions::{
     ComponentRequestAndResponseSender,
     ServerError,
     APPLICATION_OCTET_STREAM,
+    REQUEST_TIMEOUT_HEADER,
 };
 use crate::component_server::{
     ComponentServerStarter,
     LocalComponentServer,
     RemoteComponentServer,
+    RemoteServerConfig,
 };
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{CodecConfig, SerdeCodec, SerdeCompression, SerdeWrapper};
 use crate::tests::{
+    generate_tls_configs,
     test_a_b_functionality,
     ComponentA,
     ComponentAClientTrait,
@@ -154,9 +160,15 @@ where
     )
 }
 
-async fn setup_for_tests(setup_value: ValueB, a_socket: SocketAddr, b_socket: SocketAddr) {
-    let a_config = RemoteClientConfig::default();
-    let b_config = RemoteClientConfig::default();
+async fn setup_for_tests(
+    setup_value: ValueB,
+    a_socket: SocketAddr,
+    b_socket: SocketAddr,
+    server_config: RemoteServerConfig,
+    client_config: RemoteClientConfig,
+) {
+    let a_config = client_config.clone();
+    let b_config = client_config;
 
     let a_remote_client = ComponentAClient::new(
         a_config,
@@ -190,6 +202,7 @@ async fn setup_for_tests(setup_value: ValueB, a_socket: SocketAddr, b_socket: So
     let max_concurrency = 10;
     let mut component_a_remote_server = RemoteComponentServer::new(
         a_local_client,
+        server_config.clone(),
         a_socket.ip(),
         a_socket.port(),
         max_concurrency,
@@ -197,6 +210,7 @@ async fn setup_for_tests(setup_value: ValueB, a_socket: SocketAddr, b_socket: So
     );
     let mut component_b_remote_server = RemoteComponentServer::new(
         b_local_client,
+        server_config,
         b_socket.ip(),
         b_socket.port(),
         max_concurrency,
@@ -222,15 +236,52 @@ async fn setup_for_tests(setup_value: ValueB, a_socket: SocketAddr, b_socket: So
     task::yield_now().await;
 }
 
+// Clients and servers accept payloads of any codec, so the codecs may differ.
+#[rstest]
+#[case::json(CodecConfig::default(), CodecConfig::default())]
+#[case::cbor(
+    CodecConfig { codec: SerdeCodec::Cbor, ..Default::default() },
+    CodecConfig { codec: SerdeCodec::Cbor, ..Default::default() }
+)]
+#[case::compressed_cbor_client(
+    CodecConfig {
+        codec: SerdeCodec::Cbor,
+        compression: SerdeCompression::Zstd,
+        compression_threshold: 0,
+        ..Default::default()
+    },
+    CodecConfig::default()
+)]
+#[case::compressed_json_server(
+    CodecConfig::default(),
+    CodecConfig {
+        codec: SerdeCodec::Json,
+        compression: SerdeCompression::Zstd,
+        compression_threshold: 0,
+        ..Default::default()
+    }
+)]
 #[tokio::test]
-async fn proper_setup() {
+async fn proper_setup(
+    #[case] client_codec_config: CodecConfig,
+    #[case] server_codec_config: CodecConfig,
+) {
     let setup_value: ValueB = Felt::from(90);
     let a_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
     let b_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
 
-    setup_for_tests(setup_value, a_socket, b_socket).await;
-    let a_client_config = RemoteClientConfig::default();
-    let b_client_config = RemoteClientConfig::default();
+    setup_for_tests(
+        setup_value,
+        a_socket,
+        b_socket,
+        RemoteServerConfig { codec_config: server_codec_config, ..Default::default() },
+        RemoteClientConfig::default(),
+    )
+    .await;
+    let a_client_config =
+        RemoteClientConfig { codec_config: client_codec_config.clone(), ..Default::default() };
+    let b_client_config =
+        RemoteClientConfig { codec_config: client_codec_config, ..Default::default() };
 
     let a_remote_client = ComponentAClient::new(
         a_client_config,
@@ -254,7 +305,14 @@ async fn faulty_client_setup() {
     let b_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
     // Todo(uriel): Find a better way to pass expected value to the setup
     // 123 is some arbitrary value, we don't check it anyway.
-    setup_for_tests(Felt::from(123), a_socket, b_socket).await;
+    setup_for_tests(
+        Felt::from(123),
+        a_socket,
+        b_socket,
+        RemoteServerConfig::default(),
+        RemoteClientConfig::default(),
+    )
+    .await;
 
     struct FaultyAClient {
         socket: SocketAddr,
@@ -318,7 +376,7 @@ async fn faulty_server(
 }
 
 #[tokio::test]
-async fn retry_request() {
+async fn no_retry_after_server_response() {
     let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
     // Spawn a server that responses with OK every other request.
     task::spawn(async move {
@@ -358,36 +416,341 @@ async fn retry_request() {
     // Ensure the server starts running.
     task::yield_now().await;
 
-    // The initial server state is 'false', hence the first attempt returns an error and
-    // sets the server state to 'true'. The second attempt (first retry) therefore returns a
-    // 'success', while setting the server state to 'false' yet again.
-    let retry_config = RemoteClientConfig {
+    // The initial server state is 'false', hence the first request returns an error and sets the
+    // server state to 'true'. The request isn't resent as it reached the server, so the second
+    // request returns a 'success'.
+    let config = RemoteClientConfig {
         retries: 1,
         idle_connections: MAX_IDLE_CONNECTION,
         idle_timeout: IDLE_TIMEOUT,
         ..Default::default()
     };
-    let a_client_retry = ComponentAClient::new(
-        retry_config,
+    let a_client = ComponentAClient::new(
+        config,
+        &socket.ip().to_string(),
+        socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    let expected_error_contained_keywords = [StatusCode::IM_A_TEAPOT.as_str()];
+    verify_error(a_client.clone(), &expected_error_contained_keywords).await;
+    assert_eq!(a_client.a_get_value().await.unwrap(), VALID_VALUE_A);
+}
+
+#[tokio::test]
+async fn retry_on_connection_failure() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let config = RemoteClientConfig { retries: 3, retry_interval: 1, ..Default::default() };
+    let client = ComponentAClient::new(
+        config,
+        &socket.ip().to_string(),
+        socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    // The first attempt fails to connect, and the request is resent once the server is up.
+    task::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        start_json_only_server(socket, ServerError::UnsupportedPayloadFormat).await;
+    });
+    assert_eq!(client.a_get_value().await.unwrap(), VALID_VALUE_A);
+}
+
+// Starts a server that only supports JSON requests without a format header, and rejects other
+// requests with the given error.
+async fn start_json_only_server(socket: SocketAddr, rejection: fn(String) -> ServerError) {
+    task::spawn(async move {
+        async fn handler(
+            http_request: Request<Body>,
+            rejection: fn(String) -> ServerError,
+        ) -> Result<Response<Body>, hyper::Error> {
+            let body_bytes = to_bytes(http_request.into_body()).await?;
+            let ret = match SerdeWrapper::<ComponentARequest>::wrapper_deserialize(&body_bytes) {
+                Ok(_) => {
+                    let body = ComponentAResponse::AGetValue(VALID_VALUE_A);
+                    Response::builder()
+                        .status(StatusCode::OK)
+                        .body(Body::from(SerdeWrapper::new(body).wrapper_serialize().unwrap()))
+                        .unwrap()
+                }
+                Err(error) => {
+                    let body = rejection(error.to_string());
+                    Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(Body::from(SerdeWrapper::new(body).wrapper_serialize().unwrap()))
+                        .unwrap()
+                }
+            };
+            Ok(ret)
+        }
+
+        let make_svc = make_service_fn(move |_conn| async move {
+            Ok::<_, hyper::Error>(service_fn(move |req| handler(req, rejection)))
+        });
+        Server::bind(&socket).serve(make_svc).await.unwrap();
+    });
+    // Todo(uriel): Get rid of this
+    // Ensure the server starts running.
+    task::yield_now().await;
+}
+
+#[rstest]
+#[case::unsupported_format(ServerError::UnsupportedPayloadFormat, true)]
+#[case::deserialization_failure(ServerError::RequestDeserializationFailure, false)]
+#[tokio::test]
+async fn fall_back_to_legacy_format(
+    #[case] rejection: fn(String) -> ServerError,
+    #[case] expected_fall_back: bool,
+    // The legacy resend doesn't count as an attempt, so it's sent even without retries.
+    #[values(0, 1)] retries: usize,
+) {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    start_json_only_server(socket, rejection).await;
+    let config = RemoteClientConfig {
+        retries,
+        retry_interval: 0,
+        codec_config: CodecConfig { codec: SerdeCodec::Cbor, ..Default::default() },
+        ..Default::default()
+    };
+    let client = ComponentAClient::new(
+        config,
         &socket.ip().to_string(),
         socket.port(),
         TEST_REMOTE_CLIENT_METRICS,
     );
-    assert_eq!(a_client_retry.a_get_value().await.unwrap(), VALID_VALUE_A);
+    // Only an explicit rejection of the payload format makes the client resend the request
+    // without a format header.
+    let result = client.a_get_value().await;
+    if expected_fall_back {
+        assert_eq!(result.unwrap(), VALID_VALUE_A);
+    } else {
+        assert_error_contains_keywords(
+            result.unwrap_err().to_string(),
+            &[StatusCode::BAD_REQUEST.as_str()],
+        );
+    }
+}
+
+#[tokio::test]
+async fn mutual_tls() {
+    let dir = tempdir().unwrap();
+    let [server_tls_config, client_tls_config] =
+        generate_tls_configs(dir.path(), &["server", "client"]).try_into().unwrap();
+    let setup_value: ValueB = Felt::from(90);
+    let a_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let b_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    // The components are also clients of each other.
+    let server_config = RemoteServerConfig {
+        tls_config: Some(server_tls_config),
+        allowed_client_identities: vec!["client".to_owned(), "server".to_owned()],
+        ..Default::default()
+    };
+    let client_config =
+        RemoteClientConfig { tls_config: Some(client_tls_config), ..Default::default() };
+    setup_for_tests(setup_value, a_socket, b_socket, server_config, client_config.clone()).await;
+
+    let a_remote_client = ComponentAClient::new(
+        client_config.clone(),
+        &a_socket.ip().to_string(),
+        a_socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    let b_remote_client = ComponentBClient::new(
+        client_config,
+        &b_socket.ip().to_string(),
+        b_socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    test_a_b_functionality(a_remote_client, b_remote_client, setup_value).await;
+}
 
-    // The current server state is 'false', hence the first and only attempt returns an error.
-    let no_retry_config = RemoteClientConfig {
+#[rstest]
+#[case::plain_client(None)]
+#[case::client_not_in_allowlist(Some(("intruder", false)))]
+#[case::client_of_untrusted_ca(Some(("client", true)))]
+#[tokio::test]
+async fn mutual_tls_rejects_client(
+    // The identity of the rejected client, and whether its certificate is signed by another CA.
+    #[case] rejected_client: Option<(&str, bool)>,
+) {
+    let dir = tempdir().unwrap();
+    let [server_tls_config, client_tls_config, intruder_tls_config] =
+        generate_tls_configs(dir.path(), &["server", "client", "intruder"]).try_into().unwrap();
+    let untrusted_ca_dir = tempdir().unwrap();
+    let rejected_tls_config = rejected_client.map(|(identity, is_untrusted_ca)| {
+        if is_untrusted_ca {
+            generate_tls_configs(untrusted_ca_dir.path(), &[identity]).pop().unwrap()
+        } else {
+            intruder_tls_config
+        }
+    });
+
+    let a_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let b_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let server_config = RemoteServerConfig {
+        tls_config: Some(server_tls_config),
+        allowed_client_identities: vec!["client".to_owned()],
+        ..Default::default()
+    };
+    let client_config =
+        RemoteClientConfig { tls_config: Some(client_tls_config), ..Default::default() };
+    let setup_value: ValueB = Felt::from(90);
+    setup_for_tests(setup_value, a_socket, b_socket, server_config, client_config.clone()).await;
+
+    // Wait for the server to start by sending a request with an allowed client.
+    let allowed_client = ComponentAClient::new(
+        client_config,
+        &a_socket.ip().to_string(),
+        a_socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    assert_eq!(allowed_client.a_get_value().await.unwrap(), setup_value);
+
+    let rejected_client = ComponentAClient::new(
+        RemoteClientConfig { retries: 0, tls_config: rejected_tls_config, ..Default::default() },
+        &a_socket.ip().to_string(),
+        a_socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    // The handshake fails on the client side if it doesn't trust the server, and on the server side
+    // otherwise.
+    assert_matches!(
+        rejected_client.a_get_value().await,
+        Err(ClientError::CommunicationFailure(_) | ClientError::ConnectionFailure(_))
+    );
+}
+
+// Starts a remote server of component A whose local component never handles requests: the
+// receiver is either kept alive without being served, or dropped.
+async fn start_remote_server_without_component(socket: SocketAddr, keep_receiver_alive: bool) {
+    let (tx, rx) =
+        channel::<ComponentRequestAndResponseSender<ComponentARequest, ComponentAResponse>>(32);
+    let local_client = LocalComponentClient::<ComponentARequest, ComponentAResponse>::new(tx);
+    let mut remote_server = RemoteComponentServer::new(
+        local_client,
+        RemoteServerConfig::default(),
+        socket.ip(),
+        socket.port(),
+        10,
+        TEST_REMOTE_SERVER_METRICS,
+    );
+    task::spawn(async move {
+        let _receiver = keep_receiver_alive.then_some(rx);
+        let _ = remote_server.start().await;
+    });
+    // Todo(uriel): Get rid of this
+    // Ensure the server starts running.
+    task::yield_now().await;
+}
+
+async fn send_raw_request(
+    socket: SocketAddr,
+    request_timeout_header: Option<&str>,
+) -> (StatusCode, ServerError) {
+    let uri: Uri = format!("http://{socket}/").parse().unwrap();
+    let mut http_request = Request::post(uri).header(CONTENT_TYPE, APPLICATION_OCTET_STREAM);
+    if let Some(request_timeout) = request_timeout_header {
+        http_request = http_request.header(REQUEST_TIMEOUT_HEADER, request_timeout);
+    }
+    let http_request = http_request
+        .body(Body::from(
+            SerdeWrapper::new(ComponentARequest::AGetValue).wrapper_serialize().unwrap(),
+        ))
+        .unwrap();
+    let http_response = Client::new().request(http_request).await.unwrap();
+    let status_code = http_response.status();
+    let body_bytes = to_bytes(http_response.into_body()).await.unwrap();
+    (status_code, SerdeWrapper::<ServerError>::wrapper_deserialize(&body_bytes).unwrap())
+}
+
+#[tokio::test]
+async fn client_request_deadline() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let received_request_timeout = Arc::new(Mutex::new(None));
+    let server_received_request_timeout = received_request_timeout.clone();
+    // Spawn a server that never responds.
+    task::spawn(async move {
+        let make_svc = make_service_fn(move |_conn| {
+            let received_request_timeout = server_received_request_timeout.clone();
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
+                    let received_request_timeout = received_request_timeout.clone();
+                    async move {
+                        let request_timeout = req.headers().get(REQUEST_TIMEOUT_HEADER).cloned();
+                        *received_request_timeout.lock().await = request_timeout;
+                        std::future::pending::<Result<Response<Body>, hyper::Error>>().await
+                    }
+                }))
+            }
+        });
+        Server::bind(&socket).serve(make_svc).await.unwrap();
+    });
+    // Todo(uriel): Get rid of this
+    // Ensure the server starts running.
+    task::yield_now().await;
+
+    let config = RemoteClientConfig {
+        retries: 3,
+        request_timeout_millis: Duration::from_millis(200),
+        ..Default::default()
+    };
+    let client = ComponentAClient::new(
+        config,
+        &socket.ip().to_string(),
+        socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    assert_matches!(client.a_get_value().await, Err(ClientError::DeadlineExceeded));
+
+    let request_timeout = received_request_timeout.lock().await.clone().unwrap();
+    let request_timeout: u64 = request_timeout.to_str().unwrap().parse().unwrap();
+    assert!(request_timeout <= 200);
+}
+
+#[tokio::test]
+async fn server_request_deadline() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    start_remote_server_without_component(socket, true).await;
+
+    assert_eq!(
+        send_raw_request(socket, Some("100")).await,
+        (StatusCode::GATEWAY_TIMEOUT, ServerError::DeadlineExceeded)
+    );
+    let (status_code, server_error) = send_raw_request(socket, Some("soon")).await;
+    assert_eq!(status_code, StatusCode::BAD_REQUEST);
+    assert_matches!(server_error, ServerError::InvalidRequestTimeout(_));
+}
+
+#[tokio::test]
+async fn component_failure() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    start_remote_server_without_component(socket, false).await;
+
+    let (status_code, server_error) = send_raw_request(socket, None).await;
+    assert_eq!(status_code, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_matches!(server_error, ServerError::ComponentFailure(_));
+}
+
+#[tokio::test]
+async fn circuit_breaker() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let config = RemoteClientConfig {
         retries: 0,
-        idle_connections: MAX_IDLE_CONNECTION,
-        idle_timeout: IDLE_TIMEOUT,
+        circuit_breaker_failure_threshold: 2,
+        circuit_breaker_open_duration_millis: Duration::from_millis(100),
         ..Default::default()
     };
-    let a_client_no_retry = ComponentAClient::new(
-        no_retry_config,
+    let client = ComponentAClient::new(
+        config,
         &socket.ip().to_string(),
         socket.port(),
         TEST_REMOTE_CLIENT_METRICS,
     );
-    let expected_error_contained_keywords = [StatusCode::IM_A_TEAPOT.as_str()];
-    verify_error(a_client_no_retry.clone(), &expected_error_contained_keywords).await;
+    for _ in 0..2 {
+        assert_matches!(client.a_get_value().await, Err(ClientError::ConnectionFailure(_)));
+    }
+    assert_matches!(client.a_get_value().await, Err(ClientError::CircuitBreakerOpen));
+
+    // Once the open duration passes, a trial request is sent.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_matches!(client.a_get_value().await, Err(ClientError::ConnectionFailure(_)));
+    assert_matches!(client.a_get_value().await, Err(ClientError::CircuitBreakerOpen));
 }
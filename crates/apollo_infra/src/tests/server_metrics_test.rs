@@ -28,6 +28,7 @@ use crate::component_server::{
     ConcurrentLocalComponentServer,
     LocalComponentServer,
     RemoteComponentServer,
+    RemoteServerConfig,
 };
 use crate::tests::{
     AVAILABLE_PORTS,
@@ -160,6 +161,7 @@ async fn setup_remote_server_test(
 
     let mut remote_server = RemoteComponentServer::new(
         local_client.clone(),
+        RemoteServerConfig::default(),
         socket.ip(),
         socket.port(),
         max_concurrency,
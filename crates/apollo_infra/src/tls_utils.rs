@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use rustls::server::{AllowAnyAuthenticatedClient, ClientCertVerified, ClientCertVerifier};
+use rustls::{
+    Certificate,
+    CertificateError,
+    ClientConfig,
+    DistinguishedName,
+    PrivateKey,
+    RootCertStore,
+    ServerConfig,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+use validator::Validate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::parse_x509_certificate;
+
+#[cfg(test)]
+#[path = "tls_utils_test.rs"]
+pub mod tls_utils_test;
+
+/// The TLS configuration of a remote component client or server. The same files serve both
+/// directions: the certificate authenticates the node to its peers, and the CA certificate is used
+/// to authenticate the peers.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate, PartialEq)]
+pub struct TlsConfig {
+    pub ca_cert_path: PathBuf,
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl SerializeConfig for TlsConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "ca_cert_path",
+                &self.ca_cert_path,
+                "The path of the PEM encoded certificate of the CA that signs the certificates of \
+                 the peers.",
+                ParamPrivacyInput::Private,
+            ),
+            ser_param(
+                "cert_path",
+                &self.cert_path,
+                "The path of the PEM encoded certificate chain presented to the peers.",
+                ParamPrivacyInput::Private,
+            ),
+            ser_param(
+                "key_path",
+                &self.key_path,
+                "The path of the PEM encoded private key of the certificate.",
+                ParamPrivacyInput::Private,
+            ),
+        ])
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("Failed to read {path:?}: {error}.")]
+    Io { path: PathBuf, error: std::io::Error },
+    #[error("Invalid CA certificate in {path:?}: {error}.")]
+    InvalidCaCertificate { path: PathBuf, error: rustls::Error },
+    #[error("No certificates found in {0:?}.")]
+    MissingCertificates(PathBuf),
+    #[error("No private key found in {0:?}.")]
+    MissingPrivateKey(PathBuf),
+    #[error(transparent)]
+    Rustls(#[from] rustls::Error),
+}
+
+/// Builds the TLS configuration of a remote component server. Only clients that present a
+/// certificate signed by the configured CA are accepted. If `allowed_client_identities` is not
+/// empty, the client certificate must also have a common name or a subject alternative name in it.
+pub fn server_tls_config(
+    tls_config: &TlsConfig,
+    allowed_client_identities: &[String],
+) -> Result<ServerConfig, TlsError> {
+    let verifier = AllowlistClientCertVerifier {
+        inner: AllowAnyAuthenticatedClient::new(load_root_cert_store(&tls_config.ca_cert_path)?),
+        allowed_identities: allowed_client_identities.iter().cloned().collect(),
+    };
+    let certificates = load_certificates(&tls_config.cert_path)?;
+    let private_key = load_private_key(&tls_config.key_path)?;
+    Ok(ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(Arc::new(verifier))
+        .with_single_cert(certificates, private_key)?)
+}
+
+/// Builds the TLS configuration of a remote component client, which authenticates both the server
+/// and itself. Without a TLS configuration, no server is trusted, which leaves the client with
+/// plain connections only.
+pub fn client_tls_config(tls_config: Option<&TlsConfig>) -> Result<ClientConfig, TlsError> {
+    let builder = ClientConfig::builder().with_safe_defaults();
+    let Some(tls_config) = tls_config else {
+        return Ok(builder.with_root_certificates(RootCertStore::empty()).with_no_client_auth());
+    };
+    Ok(builder
+        .with_root_certificates(load_root_cert_store(&tls_config.ca_cert_path)?)
+        .with_client_auth_cert(
+            load_certificates(&tls_config.cert_path)?,
+            load_private_key(&tls_config.key_path)?,
+        )?)
+}
+
+/// Returns the identities of a DER encoded certificate: the common names of its subject and its
+/// DNS, URI and IP address subject alternative names.
+pub fn certificate_identities(der: &[u8]) -> Result<BTreeSet<String>, CertificateError> {
+    let (_, certificate) =
+        parse_x509_certificate(der).map_err(|_| CertificateError::BadEncoding)?;
+    let mut identities: BTreeSet<String> = certificate
+        .subject()
+        .iter_common_name()
+        .filter_map(|common_name| common_name.as_str().ok())
+        .map(str::to_owned)
+        .collect();
+    let subject_alternative_name =
+        certificate.subject_alternative_name().map_err(|_| CertificateError::BadEncoding)?;
+    for name in subject_alternative_name.iter().flat_map(|san| &san.value.general_names) {
+        match name {
+            GeneralName::DNSName(name) | GeneralName::URI(name) => {
+                identities.insert((*name).to_owned());
+            }
+            GeneralName::IPAddress(octets) => {
+                let ip = match octets.len() {
+                    4 => <[u8; 4]>::try_from(*octets).map(std::net::IpAddr::from).ok(),
+                    16 => <[u8; 16]>::try_from(*octets).map(std::net::IpAddr::from).ok(),
+                    _ => None,
+                };
+                identities.extend(ip.map(|ip| ip.to_string()));
+            }
+            _ => {}
+        }
+    }
+    Ok(identities)
+}
+
+// Accepts client certificates that are signed by the trusted CA and, unless the allowlist is
+// empty, have an allowed identity.
+struct AllowlistClientCertVerifier {
+    inner: AllowAnyAuthenticatedClient,
+    allowed_identities: BTreeSet<String>,
+}
+
+impl ClientCertVerifier for AllowlistClientCertVerifier {
+    fn client_auth_root_subjects(&self) -> &[DistinguishedName] {
+        self.inner.client_auth_root_subjects()
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        now: SystemTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        let verified = self.inner.verify_client_cert(end_entity, intermediates, now)?;
+        if self.allowed_identities.is_empty() {
+            return Ok(verified);
+        }
+        let identities = certificate_identities(&end_entity.0)?;
+        if identities.is_disjoint(&self.allowed_identities) {
+            warn!("Rejecting a client certificate with identities {identities:?}.");
+            return Err(CertificateError::ApplicationVerificationFailure.into());
+        }
+        Ok(verified)
+    }
+}
+
+fn open_file(path: &Path) -> Result<BufReader<File>, TlsError> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|error| TlsError::Io { path: path.to_owned(), error })
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>, TlsError> {
+    let certificates = rustls_pemfile::certs(&mut open_file(path)?)
+        .map_err(|error| TlsError::Io { path: path.to_owned(), error })?;
+    if certificates.is_empty() {
+        return Err(TlsError::MissingCertificates(path.to_owned()));
+    }
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKey, TlsError> {
+    let mut reader = open_file(path)?;
+    loop {
+        let item = rustls_pemfile::read_one(&mut reader)
+            .map_err(|error| TlsError::Io { path: path.to_owned(), error })?;
+        match item {
+            Some(
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key),
+            ) => return Ok(PrivateKey(key)),
+            Some(_) => continue,
+            None => return Err(TlsError::MissingPrivateKey(path.to_owned())),
+        }
+    }
+}
+
+fn load_root_cert_store(path: &Path) -> Result<RootCertStore, TlsError> {
+    let mut root_cert_store = RootCertStore::empty();
+    for certificate in load_certificates(path)? {
+        root_cert_store
+            .add(&certificate)
+            .map_err(|error| TlsError::InvalidCaCertificate { path: path.to_owned(), error })?;
+    }
+    Ok(root_cert_store)
+}
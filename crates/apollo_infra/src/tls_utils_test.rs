@@ -0,0 +1,62 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::Ipv4Addr;
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+use crate::tests::generate_tls_configs;
+use crate::tls_utils::{
+    certificate_identities,
+    client_tls_config,
+    server_tls_config,
+    TlsConfig,
+    TlsError,
+};
+
+#[test]
+fn identities_of_certificate() {
+    let dir = tempdir().unwrap();
+    let [tls_config] = generate_tls_configs(dir.path(), &["client"]).try_into().unwrap();
+    let certificates =
+        rustls_pemfile::certs(&mut BufReader::new(File::open(tls_config.cert_path).unwrap()))
+            .unwrap();
+    assert_eq!(
+        certificate_identities(&certificates[0]).unwrap(),
+        BTreeSet::from(["client".to_owned(), Ipv4Addr::LOCALHOST.to_string()])
+    );
+}
+
+#[test]
+fn load_tls_configs() {
+    let dir = tempdir().unwrap();
+    let [tls_config] = generate_tls_configs(dir.path(), &["node"]).try_into().unwrap();
+    server_tls_config(&tls_config, &[]).unwrap();
+    server_tls_config(&tls_config, &["client".to_owned()]).unwrap();
+    client_tls_config(Some(&tls_config)).unwrap();
+    client_tls_config(None).unwrap();
+}
+
+#[test]
+fn load_invalid_tls_configs() {
+    let dir = tempdir().unwrap();
+    let [tls_config] = generate_tls_configs(dir.path(), &["node"]).try_into().unwrap();
+
+    let missing_file =
+        TlsConfig { ca_cert_path: dir.path().join("missing.pem"), ..tls_config.clone() };
+    assert_matches!(client_tls_config(Some(&missing_file)), Err(TlsError::Io { .. }));
+
+    let key_as_cert = TlsConfig { cert_path: tls_config.key_path.clone(), ..tls_config.clone() };
+    assert_matches!(
+        server_tls_config(&key_as_cert, &[]),
+        Err(TlsError::MissingCertificates(path)) if path == tls_config.key_path
+    );
+
+    let cert_as_key = TlsConfig { key_path: tls_config.cert_path.clone(), ..tls_config.clone() };
+    assert_matches!(
+        server_tls_config(&cert_as_key, &[]),
+        Err(TlsError::MissingPrivateKey(path)) if path == tls_config.cert_path
+    );
+}
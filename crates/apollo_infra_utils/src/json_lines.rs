@@ -0,0 +1,80 @@
+//! Files holding records one per line, as JSON. Used by components which persist their state
+//! across restarts (e.g. write-ahead logs and journals), by appending records as they are created
+//! and occasionally rewriting the whole file.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::warn;
+
+#[cfg(test)]
+#[path = "json_lines_test.rs"]
+mod json_lines_test;
+
+#[derive(Debug, thiserror::Error)]
+pub enum JsonLinesError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize a record: {0}")]
+    Serde(#[from] serde_json::Error),
+}
+
+pub type JsonLinesResult<T> = Result<T, JsonLinesError>;
+
+/// Reads the records in the file at `path`. A missing file holds no records.
+///
+/// A crash while appending can leave the last record partially written; such a record is ignored.
+/// Callers must therefore only act on a record once it has been appended.
+pub fn read_records<T: DeserializeOwned>(path: &Path) -> JsonLinesResult<Vec<T>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let lines = BufReader::new(file).lines().collect::<Result<Vec<_>, _>>()?;
+    let n_lines = lines.len();
+    let mut records = Vec::with_capacity(n_lines);
+    for (i, line) in lines.into_iter().enumerate() {
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) if i + 1 == n_lines => {
+                warn!("Ignoring a partially written record at the end of {path:?}: {e}");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(records)
+}
+
+/// Writes a record at the end of `file`. The caller is responsible for syncing the file.
+pub fn write_record<T: Serialize>(file: &mut File, record: &T) -> JsonLinesResult<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)?;
+    Ok(())
+}
+
+/// Replaces the contents of the file at `path` with `records`, and returns the file opened for
+/// appending. Writes to a temporary file and renames it, so a crash can't leave a partially written
+/// file.
+pub fn rewrite_records<T: Serialize>(path: &Path, records: &[T]) -> JsonLinesResult<File> {
+    let tmp_path = tmp_path(path);
+    {
+        let mut tmp_file = File::create(&tmp_path)?;
+        for record in records {
+            write_record(&mut tmp_file, record)?;
+        }
+        tmp_file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(OpenOptions::new().append(true).open(path)?)
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    tmp_path.into()
+}
@@ -0,0 +1,41 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use tempfile::TempDir;
+
+use crate::json_lines::{read_records, rewrite_records, write_record};
+
+#[test]
+fn records_survive_reopening() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("records");
+    assert!(read_records::<u32>(&path).unwrap().is_empty());
+
+    let mut file = rewrite_records(&path, &[1_u32, 2]).unwrap();
+    write_record(&mut file, &3_u32).unwrap();
+    drop(file);
+    assert_eq!(read_records::<u32>(&path).unwrap(), vec![1, 2, 3]);
+
+    rewrite_records(&path, &[4_u32]).unwrap();
+    assert_eq!(read_records::<u32>(&path).unwrap(), vec![4]);
+}
+
+#[test]
+fn partially_written_last_record_is_ignored() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("records");
+    rewrite_records(&path, &["first".to_owned()]).unwrap();
+    // Simulate a crash in the middle of appending a record.
+    OpenOptions::new().append(true).open(&path).unwrap().write_all(b"\"sec").unwrap();
+
+    assert_eq!(read_records::<String>(&path).unwrap(), vec!["first".to_owned()]);
+}
+
+#[test]
+fn corrupted_record_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("records");
+    fs::write(&path, "not a record\n1\n").unwrap();
+
+    assert!(read_records::<u32>(&path).is_err());
+}
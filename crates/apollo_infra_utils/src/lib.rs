@@ -3,6 +3,7 @@ pub mod cairo_compiler_version;
 pub mod command;
 pub mod dumping;
 pub mod global_allocator;
+pub mod json_lines;
 pub mod path;
 pub mod run_until;
 pub mod tasks;
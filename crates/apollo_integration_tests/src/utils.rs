@@ -11,6 +11,7 @@ use apollo_class_manager::config::{
     FsClassManagerConfig,
     FsClassStorageConfig,
 };
+use apollo_committer::committer::CommitterConfig;
 use apollo_config::converters::UrlAndHeaders;
 use apollo_consensus::config::{ConsensusConfig, TimeoutsConfig};
 use apollo_consensus::types::ValidatorId;
@@ -38,7 +39,10 @@ use apollo_network::NetworkConfig;
 use apollo_node::config::component_config::ComponentConfig;
 use apollo_node::config::definitions::ConfigPointersMap;
 use apollo_node::config::node_config::{SequencerNodeConfig, CONFIG_POINTERS};
+use apollo_protobuf::consensus::DEFAULT_VALIDATOR_ID;
 use apollo_rpc::RpcConfig;
+use apollo_signature_manager::config::SignatureManagerConfig;
+use apollo_signature_manager::signature_manager::LocalKeyStore;
 use apollo_state_sync::config::StateSyncConfig;
 use apollo_storage::StorageConfig;
 use axum::extract::Query;
@@ -58,6 +62,7 @@ use serde::Deserialize;
 use serde_json::{json, to_value};
 use starknet_api::block::BlockNumber;
 use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::crypto::utils::PrivateKey;
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::rpc_transaction::RpcTransaction;
 use starknet_api::transaction::fields::ContractAddressSalt;
@@ -78,6 +83,7 @@ pub const UNDEPLOYED_ACCOUNT_ID: AccountId = 2;
 // with the set [TimeoutsConfig] .
 pub const TPS: u64 = 3;
 pub const N_TXS_IN_FIRST_BLOCK: usize = 2;
+const COMMITTER_STORAGE_FILE_NAME: &str = "committer_state_tries";
 
 pub type CreateRpcTxsFn = fn(&mut MultiAccountTransactionGenerator) -> Vec<RpcTransaction>;
 pub type CreateL1ToL2MessagesArgsFn =
@@ -175,11 +181,21 @@ pub fn create_node_config(
 ) -> (SequencerNodeConfig, ConfigPointersMap) {
     let recorder_url = consensus_manager_config.cende_config.recorder_url.clone();
     let fee_token_addresses = chain_info.fee_token_addresses.clone();
+    // The committer's storage is kept next to the batcher's, so that it is removed along with it.
+    let committer_config = CommitterConfig {
+        storage_path: storage_config
+            .batcher_storage_config
+            .db_config
+            .path_prefix
+            .join(COMMITTER_STORAGE_FILE_NAME),
+    };
     let batcher_config = create_batcher_config(
         storage_config.batcher_storage_config,
         chain_info.clone(),
         block_max_capacity_sierra_gas,
     );
+    let signature_manager_config =
+        SignatureManagerConfig { private_key: validator_private_key(validator_id) };
     let validate_non_zero_resource_bounds = !allow_bootstrap_txs;
     let gateway_config =
         create_gateway_config(chain_info.clone(), validate_non_zero_resource_bounds);
@@ -250,12 +266,14 @@ pub fn create_node_config(
             base_layer_config: Some(base_layer_config),
             batcher_config: Some(batcher_config),
             class_manager_config: Some(class_manager_config),
+            committer_config: Some(committer_config),
             consensus_manager_config: Some(consensus_manager_config),
             gateway_config: Some(gateway_config),
             http_server_config: Some(http_server_config),
             mempool_config: Some(mempool_config),
             mempool_p2p_config: Some(mempool_p2p_config),
             monitoring_endpoint_config: Some(monitoring_endpoint_config),
+            signature_manager_config: Some(signature_manager_config),
             state_sync_config: Some(state_sync_config),
             components: component_config,
             l1_scraper_config: Some(l1_scraper_config),
@@ -280,6 +298,12 @@ pub(crate) fn create_consensus_manager_configs_from_network_configs(
     timeouts.proposal_timeout *= 3;
 
     let num_validators = u64::try_from(n_composed_nodes).unwrap();
+    let validator_public_keys: Vec<Felt> = (0..num_validators)
+        .map(|i| {
+            let private_key = validator_private_key(ValidatorId::from(DEFAULT_VALIDATOR_ID + i));
+            LocalKeyStore::new(private_key).public_key.0
+        })
+        .collect();
 
     network_configs
         .into_iter()
@@ -295,6 +319,7 @@ pub(crate) fn create_consensus_manager_configs_from_network_configs(
             },
             context_config: ContextConfig {
                 num_validators,
+                validator_public_keys: validator_public_keys.clone(),
                 chain_id: chain_id.clone(),
                 builder_address: ContractAddress::from(4_u128),
                 ..Default::default()
@@ -620,6 +645,11 @@ pub fn create_class_manager_config(
     FsClassManagerConfig { class_manager_config, class_storage_config }
 }
 
+/// The private key with which the validator signs in tests, derived from its id.
+pub fn validator_private_key(validator_id: ValidatorId) -> PrivateKey {
+    PrivateKey(Felt::from(validator_id))
+}
+
 pub fn set_validator_id(
     consensus_manager_config: &mut ConsensusManagerConfig,
     node_index: usize,
@@ -9,25 +9,35 @@ use apollo_config::converters::{
     serialize_optional_list_with_url_and_headers,
     UrlAndHeaders,
 };
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::dumping::{ser_optional_sub_config, ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_l1_gas_price_types::errors::EthToStrkOracleClientError;
-use apollo_l1_gas_price_types::EthToStrkOracleClientTrait;
+use apollo_l1_gas_price_types::{EthToStrkOracleClientTrait, PreviousEthToFriRate};
 use async_trait::async_trait;
+use futures::future::join_all;
 use futures::FutureExt;
 use lru::LruCache;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
 use serde_json;
+use strum::VariantNames;
 use tokio_util::task::AbortOnDropHandle;
 use tracing::{debug, info, instrument, warn};
 use url::Url;
 
 use crate::metrics::{
+    oracle_source_label,
     register_eth_to_strk_metrics,
+    OracleSourceLabelValue,
     ETH_TO_STRK_ERROR_COUNT,
+    ETH_TO_STRK_QUORUM_NOT_REACHED_COUNT,
     ETH_TO_STRK_RATE,
+    ETH_TO_STRK_RATE_CHANGE_CAPPED_COUNT,
+    ETH_TO_STRK_SOURCE_DEVIATION_BPS,
+    ETH_TO_STRK_SOURCE_ERROR_COUNT,
+    ETH_TO_STRK_SOURCE_OUTLIER_COUNT,
+    ETH_TO_STRK_SOURCE_SUCCESS_COUNT,
     ETH_TO_STRK_SUCCESS_COUNT,
 };
 
@@ -36,6 +46,7 @@ use crate::metrics::{
 pub mod eth_to_strk_oracle_test;
 
 pub const ETH_TO_STRK_QUANTIZATION: u64 = 18;
+const BASIS_POINTS: u128 = 10_000;
 
 fn btreemap_to_headermap(hash_map: BTreeMap<String, String>) -> HeaderMap {
     let mut header_map = HeaderMap::new();
@@ -55,11 +66,14 @@ pub struct EthToStrkOracleConfig {
     pub lag_interval_seconds: u64,
     pub max_cache_size: usize,
     pub query_timeout_sec: u64,
+    /// If set, all of the URLs are queried concurrently and their rates aggregated, instead of
+    /// trusting the first URL that answers.
+    pub quorum_config: Option<EthToStrkOracleQuorumConfig>,
 }
 
 impl SerializeConfig for EthToStrkOracleConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let mut config = BTreeMap::from_iter([
             ser_param(
                 "url_header_list",
                 &serialize_optional_list_with_url_and_headers(&self.url_header_list),
@@ -92,7 +106,9 @@ impl SerializeConfig for EthToStrkOracleConfig {
                 "The timeout (seconds) for the query to the eth to strk oracle.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+        ]);
+        config.extend(ser_optional_sub_config(&self.quorum_config, "quorum_config"));
+        config
     }
 }
 
@@ -106,6 +122,55 @@ impl Default for EthToStrkOracleConfig {
             lag_interval_seconds: 1,
             max_cache_size: 100,
             query_timeout_sec: 3,
+            quorum_config: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EthToStrkOracleQuorumConfig {
+    /// The minimal number of URLs whose rates must agree for a bucket's rate to be accepted.
+    pub min_agreeing_sources: usize,
+    /// Rates that deviate from the median rate by more than this are rejected as outliers.
+    pub max_deviation_from_median_bps: u64,
+    /// The maximal change of the rate per bucket, relative to the rate of the previous block.
+    pub max_rate_change_per_bucket_bps: u64,
+}
+
+impl SerializeConfig for EthToStrkOracleQuorumConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "min_agreeing_sources",
+                &self.min_agreeing_sources,
+                "The minimal number of URLs in `url_header_list` whose rates must agree, i.e., \
+                 not be outliers, for the rate of a bucket to be accepted.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_deviation_from_median_bps",
+                &self.max_deviation_from_median_bps,
+                "The maximal deviation (basis points) of a URL's rate from the median of all \
+                 rates of the bucket. Rates deviating by more are rejected as outliers.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_rate_change_per_bucket_bps",
+                &self.max_rate_change_per_bucket_bps,
+                "The maximal change (basis points) of the rate per bucket, relative to the rate \
+                 of the previous block. Larger changes are capped.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+impl Default for EthToStrkOracleQuorumConfig {
+    fn default() -> Self {
+        Self {
+            min_agreeing_sources: 2,
+            max_deviation_from_median_bps: 200,
+            max_rate_change_per_bucket_bps: 500,
         }
     }
 }
@@ -151,6 +216,18 @@ impl EthToStrkOracleClient {
                 headers: btreemap_to_headermap(uh.headers.clone()),
             })
             .collect::<Vec<_>>();
+        if let Some(quorum_config) = &config.quorum_config {
+            assert!(
+                (1..=url_header_list.len()).contains(&quorum_config.min_agreeing_sources),
+                "min_agreeing_sources must be between 1 and the number of URLs, got {}.",
+                quorum_config.min_agreeing_sources
+            );
+            assert!(
+                url_header_list.len() <= OracleSourceLabelValue::VARIANTS.len(),
+                "At most {} URLs are supported in quorum mode.",
+                OracleSourceLabelValue::VARIANTS.len()
+            );
+        }
         Self {
             config: config.clone(),
             index: Arc::new(AtomicUsize::new(0)),
@@ -175,36 +252,49 @@ impl EthToStrkOracleClient {
         let index_clone = self.index.clone();
         let url_header_list = self.url_header_list.clone();
         let list_len = url_header_list.len();
+        if let Some(quorum_config) = self.config.quorum_config.clone() {
+            let future = async move {
+                let rates = join_all(url_header_list.iter().map(|url_and_headers| {
+                    query_url(&client, url_and_headers, adjusted_timestamp, query_timeout_sec)
+                }))
+                .await;
+                let mut source_rates = Vec::with_capacity(rates.len());
+                for (index, rate) in rates.into_iter().enumerate() {
+                    match rate {
+                        Some(rate) => {
+                            ETH_TO_STRK_SOURCE_SUCCESS_COUNT
+                                .increment(1, &oracle_source_label(index));
+                            source_rates.push((index, rate));
+                        }
+                        None => {
+                            ETH_TO_STRK_SOURCE_ERROR_COUNT
+                                .increment(1, &oracle_source_label(index));
+                            ETH_TO_STRK_ERROR_COUNT.increment(1);
+                        }
+                    }
+                }
+                let rate = aggregate_rates(adjusted_timestamp, &source_rates, &quorum_config)?;
+                ETH_TO_STRK_SUCCESS_COUNT.increment(1);
+                Ok(rate)
+            };
+            return AbortOnDropHandle::new(tokio::spawn(future));
+        }
+
         let future = async move {
             let initial_index = index_clone.load(Ordering::SeqCst);
             for (i, url_and_headers) in
                 url_header_list.iter().cycle().skip(initial_index).take(list_len).enumerate()
             {
-                let UrlAndHeaderMap { url, headers } = url_and_headers;
-                let mut url = url.clone();
-                url.query_pairs_mut().append_pair("timestamp", &adjusted_timestamp.to_string());
-                let result = tokio::time::timeout(Duration::from_secs(query_timeout_sec), async {
-                    let response = client.get(url.clone()).headers(headers.clone()).send().await?;
-                    let body = response.text().await?;
-                    let rate = resolve_query(body)?;
-                    Ok::<_, EthToStrkOracleClientError>(rate)
-                })
-                .await;
-
-                match result {
-                    Ok(Ok(rate)) => {
-                        let idx = (i + initial_index) % list_len;
-                        index_clone.store(idx, Ordering::SeqCst);
-                        debug!("Resolved query to {url} with rate {rate}");
-                        return Ok(rate);
-                    }
-                    Ok(Err(e)) => {
-                        warn!("Failed to resolve query to {url}: {e:?}");
-                    }
-                    Err(_) => {
-                        warn!("Timeout when resolving query to {url}");
-                    }
-                };
+                let rate =
+                    query_url(&client, url_and_headers, adjusted_timestamp, query_timeout_sec)
+                        .await;
+                if let Some(rate) = rate {
+                    let idx = (i + initial_index) % list_len;
+                    index_clone.store(idx, Ordering::SeqCst);
+                    ETH_TO_STRK_SUCCESS_COUNT.increment(1);
+                    ETH_TO_STRK_RATE.set_lossy(rate);
+                    return Ok(rate);
+                }
                 ETH_TO_STRK_ERROR_COUNT.increment(1);
             }
             warn!("All {list_len} URLs in the list failed for timestamp {adjusted_timestamp}");
@@ -212,52 +302,54 @@ impl EthToStrkOracleClient {
         };
         AbortOnDropHandle::new(tokio::spawn(future))
     }
-}
 
-fn resolve_query(body: String) -> Result<u128, EthToStrkOracleClientError> {
-    let Ok(json): Result<serde_json::Value, _> = serde_json::from_str(&body) else {
-        return Err(EthToStrkOracleClientError::ParseError(serde_json::Error::custom(format!(
-            "Failed to parse JSON: {body}"
-        ))));
-    };
-    // Extract price from API response. Also returns MissingFieldError if value is not a string.
-    let price = match json.get("price").and_then(|v| v.as_str()) {
-        Some(price) => price,
-        None => {
-            return Err(EthToStrkOracleClientError::MissingFieldError("price", body));
-        }
-    };
-    let rate = u128::from_str_radix(price.trim_start_matches("0x"), 16)
-        .expect("Failed to parse price as u128");
-    // Extract decimals from API response. Also returns MissingFieldError if value is not a number.
-    let decimals = match json.get("decimals").and_then(|v| v.as_u64()) {
-        Some(decimals) => decimals,
-        None => {
-            return Err(EthToStrkOracleClientError::MissingFieldError("decimals", body));
-        }
-    };
-    if decimals != ETH_TO_STRK_QUANTIZATION {
-        return Err(EthToStrkOracleClientError::InvalidDecimalsError(
-            ETH_TO_STRK_QUANTIZATION,
-            decimals,
-        ));
+    fn quantize(&self, timestamp: u64) -> u64 {
+        timestamp
+            .saturating_sub(self.config.lag_interval_seconds)
+            .checked_div(self.config.lag_interval_seconds)
+            .expect("lag_interval_seconds should be non-zero")
     }
-    ETH_TO_STRK_SUCCESS_COUNT.increment(1);
-    ETH_TO_STRK_RATE.set_lossy(rate);
-    Ok(rate)
-}
 
-#[async_trait]
-impl EthToStrkOracleClientTrait for EthToStrkOracleClient {
-    /// The HTTP response must include the following fields:
-    /// - `price`: a hexadecimal string representing the price.
-    /// - `decimals`: a `u64` value, must be equal to `ETH_TO_STRK_QUANTIZATION`.
-    #[instrument(skip(self))]
-    async fn eth_to_fri_rate(&self, timestamp: u64) -> Result<u128, EthToStrkOracleClientError> {
-        let quantized_timestamp = (timestamp - self.config.lag_interval_seconds)
-            .checked_div(self.config.lag_interval_seconds)
-            .expect("lag_interval_seconds should be non-zero");
+    /// Caps the change of `rate` relative to the rate of the previous block, proportionally to the
+    /// number of buckets between them. Depends only on the bucket and the previous block, so every
+    /// node building on the same previous block gets the same rate.
+    fn cap_rate_change(
+        &self,
+        quantized_timestamp: u64,
+        rate: u128,
+        previous_rate: PreviousEthToFriRate,
+        quorum_config: &EthToStrkOracleQuorumConfig,
+    ) -> u128 {
+        let PreviousEthToFriRate { timestamp: previous_timestamp, eth_to_fri_rate: previous_rate } =
+            previous_rate;
+        // The rate can't change within the bucket of the previous block.
+        let buckets =
+            u128::from(quantized_timestamp.saturating_sub(self.quantize(previous_timestamp)));
+        let max_change = previous_rate
+            .saturating_mul(u128::from(quorum_config.max_rate_change_per_bucket_bps))
+            .saturating_mul(buckets)
+            / BASIS_POINTS;
+        let capped_rate = rate.clamp(
+            previous_rate.saturating_sub(max_change),
+            previous_rate.saturating_add(max_change),
+        );
+        if capped_rate != rate {
+            warn!(
+                "Capping the change of the eth to strk rate from {previous_rate} to {rate}, over \
+                 {buckets} buckets, at {capped_rate}."
+            );
+            ETH_TO_STRK_RATE_CHANGE_CAPPED_COUNT.increment(1);
+        }
+        capped_rate
+    }
 
+    /// Returns the rate of the bucket, either cached or from its query, which is started if
+    /// needed.
+    fn bucket_rate(
+        &self,
+        timestamp: u64,
+        quantized_timestamp: u64,
+    ) -> Result<u128, EthToStrkOracleClientError> {
         let mut cache = self.cached_prices.lock().unwrap();
 
         if let Some(rate) = cache.get(&quantized_timestamp) {
@@ -300,3 +392,174 @@ impl EthToStrkOracleClientTrait for EthToStrkOracleClient {
         Ok(rate)
     }
 }
+
+/// Queries a single URL for the rate at `adjusted_timestamp`. Failures are logged and yield `None`.
+async fn query_url(
+    client: &reqwest::Client,
+    UrlAndHeaderMap { url, headers }: &UrlAndHeaderMap,
+    adjusted_timestamp: u64,
+    query_timeout_sec: u64,
+) -> Option<u128> {
+    let mut url = url.clone();
+    url.query_pairs_mut().append_pair("timestamp", &adjusted_timestamp.to_string());
+    let result = tokio::time::timeout(Duration::from_secs(query_timeout_sec), async {
+        let response = client.get(url.clone()).headers(headers.clone()).send().await?;
+        let body = response.text().await?;
+        let rate = resolve_query(body)?;
+        Ok::<_, EthToStrkOracleClientError>(rate)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(rate)) => {
+            debug!("Resolved query to {url} with rate {rate}");
+            Some(rate)
+        }
+        Ok(Err(e)) => {
+            warn!("Failed to resolve query to {url}: {e:?}");
+            None
+        }
+        Err(_) => {
+            warn!("Timeout when resolving query to {url}");
+            None
+        }
+    }
+}
+
+/// Aggregates the rates answered by the sources, given along with their index in the URL list.
+/// Rates that deviate from the median by too much are rejected as outliers, and the median of the
+/// remaining rates is returned, if enough sources agree on it.
+pub(crate) fn aggregate_rates(
+    adjusted_timestamp: u64,
+    source_rates: &[(usize, u128)],
+    quorum_config: &EthToStrkOracleQuorumConfig,
+) -> Result<u128, EthToStrkOracleClientError> {
+    let all_rates: Vec<u128> = source_rates.iter().map(|&(_, rate)| rate).collect();
+    let Some(median_rate) = median(all_rates) else {
+        return Err(quorum_not_reached(adjusted_timestamp, 0, quorum_config));
+    };
+
+    let mut agreeing_rates = Vec::with_capacity(source_rates.len());
+    for &(index, rate) in source_rates {
+        let deviation_bps = deviation_bps(rate, median_rate);
+        ETH_TO_STRK_SOURCE_DEVIATION_BPS
+            .set(u32::try_from(deviation_bps).unwrap_or(u32::MAX), &oracle_source_label(index));
+        if deviation_bps > u128::from(quorum_config.max_deviation_from_median_bps) {
+            warn!(
+                "Rejecting the rate {rate} of URL #{index} for timestamp {adjusted_timestamp}, \
+                 deviating by {deviation_bps} basis points from the median {median_rate}."
+            );
+            ETH_TO_STRK_SOURCE_OUTLIER_COUNT.increment(1, &oracle_source_label(index));
+            continue;
+        }
+        agreeing_rates.push(rate);
+    }
+
+    let num_agreeing = agreeing_rates.len();
+    if num_agreeing < quorum_config.min_agreeing_sources {
+        return Err(quorum_not_reached(adjusted_timestamp, num_agreeing, quorum_config));
+    }
+    let rate = median(agreeing_rates).expect("Quorum is at least one rate.");
+    debug!("Aggregated {num_agreeing} rates for timestamp {adjusted_timestamp} into {rate}");
+    Ok(rate)
+}
+
+fn quorum_not_reached(
+    adjusted_timestamp: u64,
+    num_agreeing: usize,
+    quorum_config: &EthToStrkOracleQuorumConfig,
+) -> EthToStrkOracleClientError {
+    warn!(
+        "Only {num_agreeing} URLs agree on the rate for timestamp {adjusted_timestamp}, at least \
+         {} are required.",
+        quorum_config.min_agreeing_sources
+    );
+    ETH_TO_STRK_QUORUM_NOT_REACHED_COUNT.increment(1);
+    EthToStrkOracleClientError::QuorumNotReachedError(
+        adjusted_timestamp,
+        num_agreeing,
+        quorum_config.min_agreeing_sources,
+    )
+}
+
+/// The median of the given rates. For an even number of rates, the mean of the two middle ones,
+/// rounded up.
+fn median(mut rates: Vec<u128>) -> Option<u128> {
+    rates.sort_unstable();
+    let upper_middle = *rates.get(rates.len() / 2)?;
+    if rates.len() % 2 == 1 {
+        return Some(upper_middle);
+    }
+    let lower_middle = rates[rates.len() / 2 - 1];
+    Some(lower_middle + (upper_middle - lower_middle).div_ceil(2))
+}
+
+/// The deviation of `rate` from `median_rate`, in basis points of the latter.
+fn deviation_bps(rate: u128, median_rate: u128) -> u128 {
+    let deviation = rate.abs_diff(median_rate).saturating_mul(BASIS_POINTS);
+    match median_rate {
+        0 if deviation == 0 => 0,
+        0 => u128::MAX,
+        _ => deviation / median_rate,
+    }
+}
+
+fn resolve_query(body: String) -> Result<u128, EthToStrkOracleClientError> {
+    let Ok(json): Result<serde_json::Value, _> = serde_json::from_str(&body) else {
+        return Err(EthToStrkOracleClientError::ParseError(serde_json::Error::custom(format!(
+            "Failed to parse JSON: {body}"
+        ))));
+    };
+    // Extract price from API response. Also returns MissingFieldError if value is not a string.
+    let price = match json.get("price").and_then(|v| v.as_str()) {
+        Some(price) => price,
+        None => {
+            return Err(EthToStrkOracleClientError::MissingFieldError("price", body));
+        }
+    };
+    let rate = u128::from_str_radix(price.trim_start_matches("0x"), 16)
+        .expect("Failed to parse price as u128");
+    // Extract decimals from API response. Also returns MissingFieldError if value is not a number.
+    let decimals = match json.get("decimals").and_then(|v| v.as_u64()) {
+        Some(decimals) => decimals,
+        None => {
+            return Err(EthToStrkOracleClientError::MissingFieldError("decimals", body));
+        }
+    };
+    if decimals != ETH_TO_STRK_QUANTIZATION {
+        return Err(EthToStrkOracleClientError::InvalidDecimalsError(
+            ETH_TO_STRK_QUANTIZATION,
+            decimals,
+        ));
+    }
+    Ok(rate)
+}
+
+#[async_trait]
+impl EthToStrkOracleClientTrait for EthToStrkOracleClient {
+    /// The HTTP response must include the following fields:
+    /// - `price`: a hexadecimal string representing the price.
+    /// - `decimals`: a `u64` value, must be equal to `ETH_TO_STRK_QUANTIZATION`.
+    #[instrument(skip(self))]
+    async fn eth_to_fri_rate(
+        &self,
+        timestamp: u64,
+        previous_rate: Option<PreviousEthToFriRate>,
+    ) -> Result<u128, EthToStrkOracleClientError> {
+        let quantized_timestamp = self.quantize(timestamp);
+        // Cached uncapped, since the cap depends on the previous block.
+        let rate = self.bucket_rate(timestamp, quantized_timestamp)?;
+        let Some(quorum_config) = &self.config.quorum_config else {
+            return Ok(rate);
+        };
+
+        let rate = match previous_rate {
+            Some(previous_rate) => {
+                self.cap_rate_change(quantized_timestamp, rate, previous_rate, quorum_config)
+            }
+            None => rate,
+        };
+        ETH_TO_STRK_RATE.set_lossy(rate);
+        Ok(rate)
+    }
+}
@@ -1,13 +1,20 @@
 use std::collections::BTreeMap;
 
 use apollo_l1_gas_price_types::errors::EthToStrkOracleClientError;
-use apollo_l1_gas_price_types::EthToStrkOracleClientTrait;
+use apollo_l1_gas_price_types::{EthToStrkOracleClientTrait, PreviousEthToFriRate};
+use assert_matches::assert_matches;
 use mockito::{Mock, ServerGuard};
 use serde_json::json;
 use tokio::{self};
 use url::Url;
 
-use crate::eth_to_strk_oracle::{EthToStrkOracleClient, EthToStrkOracleConfig, UrlAndHeaders};
+use crate::eth_to_strk_oracle::{
+    aggregate_rates,
+    EthToStrkOracleClient,
+    EthToStrkOracleConfig,
+    EthToStrkOracleQuorumConfig,
+    UrlAndHeaders,
+};
 
 async fn make_server(server: &mut ServerGuard, body: serde_json::Value) -> Mock {
     server
@@ -53,14 +60,14 @@ async fn eth_to_fri_rate_uses_cache_on_quantized_hit() {
     let client = EthToStrkOracleClient::new(config.clone());
 
     // First request should fail because the cache is empty.
-    assert!(client.eth_to_fri_rate(timestamp1).await.is_err());
+    assert!(client.eth_to_fri_rate(timestamp1, None).await.is_err());
     // Wait for the query to resolve.
-    while client.eth_to_fri_rate(timestamp1).await.is_err() {
+    while client.eth_to_fri_rate(timestamp1, None).await.is_err() {
         tokio::task::yield_now().await; // Don't block the executor.
     }
-    let rate1 = client.eth_to_fri_rate(timestamp1).await.unwrap();
+    let rate1 = client.eth_to_fri_rate(timestamp1, None).await.unwrap();
     let rate2 = client
-        .eth_to_fri_rate(timestamp2)
+        .eth_to_fri_rate(timestamp2, None)
         .await
         .expect("Should resolve immediately due to the cache");
     assert_eq!(rate1, rate2);
@@ -95,21 +102,21 @@ async fn eth_to_fri_rate_two_urls() {
         EthToStrkOracleConfig { url_header_list, lag_interval_seconds, ..Default::default() };
     let client = EthToStrkOracleClient::new(config.clone());
     // First request should fail because the cache is empty.
-    assert!(client.eth_to_fri_rate(timestamp1).await.is_err());
+    assert!(client.eth_to_fri_rate(timestamp1, None).await.is_err());
     // Wait for the query to resolve.
-    while client.eth_to_fri_rate(timestamp1).await.is_err() {
+    while client.eth_to_fri_rate(timestamp1, None).await.is_err() {
         tokio::task::yield_now().await; // Don't block the executor.
     }
-    let rate1 = client.eth_to_fri_rate(timestamp1).await.unwrap();
+    let rate1 = client.eth_to_fri_rate(timestamp1, None).await.unwrap();
     assert_eq!(rate1, expected_rate);
 
     // Note this server fails on missing "decimals", not "price".
     let _m3 = make_server(&mut server2, json!({"price": &expected_rate_hex, "bar": 18})).await;
     // First request should fail because the cache is empty.
-    assert!(client.eth_to_fri_rate(timestamp2).await.is_err());
+    assert!(client.eth_to_fri_rate(timestamp2, None).await.is_err());
     // Wait for the query to resolve.
     loop {
-        match client.eth_to_fri_rate(timestamp2).await {
+        match client.eth_to_fri_rate(timestamp2, None).await {
             Ok(_) => panic!("Both servers should be returning bad JSON!"),
             Err(EthToStrkOracleClientError::QueryNotReadyError(_)) => {}
             Err(EthToStrkOracleClientError::AllUrlsFailedError(_, index)) => {
@@ -121,3 +128,145 @@ async fn eth_to_fri_rate_two_urls() {
         tokio::task::yield_now().await; // Don't block the executor.
     }
 }
+
+fn rate_body(rate: u128) -> serde_json::Value {
+    json!({"price": format!("0x{rate:x}"), "decimals": 18})
+}
+
+async fn quorum_client(
+    servers: &[ServerGuard],
+    lag_interval_seconds: u64,
+    quorum_config: EthToStrkOracleQuorumConfig,
+) -> EthToStrkOracleClient {
+    let url_header_list = Some(
+        servers
+            .iter()
+            .map(|server| UrlAndHeaders {
+                url: Url::parse(&server.url()).unwrap(),
+                headers: BTreeMap::new(),
+            })
+            .collect(),
+    );
+    EthToStrkOracleClient::new(EthToStrkOracleConfig {
+        url_header_list,
+        lag_interval_seconds,
+        quorum_config: Some(quorum_config),
+        ..Default::default()
+    })
+}
+
+async fn resolve_rate(
+    client: &EthToStrkOracleClient,
+    timestamp: u64,
+    previous_rate: Option<PreviousEthToFriRate>,
+) -> Result<u128, EthToStrkOracleClientError> {
+    loop {
+        match client.eth_to_fri_rate(timestamp, previous_rate).await {
+            Err(EthToStrkOracleClientError::QueryNotReadyError(_)) => {
+                tokio::task::yield_now().await; // Don't block the executor.
+            }
+            result => return result,
+        }
+    }
+}
+
+#[test]
+fn aggregate_rates_rejects_outliers() {
+    let quorum_config = EthToStrkOracleQuorumConfig {
+        min_agreeing_sources: 2,
+        max_deviation_from_median_bps: 100,
+        ..Default::default()
+    };
+
+    // The median of all rates is 1008, from which 2000 deviates by more than 1%.
+    let rates = [(0, 1000), (1, 2000), (2, 1010), (3, 1005)];
+    assert_eq!(aggregate_rates(0, &rates, &quorum_config).unwrap(), 1005);
+
+    // Only one rate is within 1% of the median 1500.
+    let rates = [(0, 1000), (1, 2000), (2, 1500)];
+    assert_matches!(
+        aggregate_rates(0, &rates, &quorum_config),
+        Err(EthToStrkOracleClientError::QuorumNotReachedError(0, 1, 2))
+    );
+    assert_matches!(
+        aggregate_rates(0, &[], &quorum_config),
+        Err(EthToStrkOracleClientError::QuorumNotReachedError(0, 0, 2))
+    );
+}
+
+#[tokio::test]
+async fn eth_to_fri_rate_quorum_ignores_failing_and_outlier_sources() {
+    let mut servers = Vec::new();
+    for _ in 0..4 {
+        servers.push(mockito::Server::new_async().await);
+    }
+    let _m0 = make_server(&mut servers[0], rate_body(1000)).await;
+    let _m1 = make_server(&mut servers[1], rate_body(1_000_000)).await; // A compromised source.
+    let _m2 = make_server(&mut servers[2], json!({"foo": "0x0", "bar": 18})).await;
+    let _m3 = make_server(&mut servers[3], rate_body(1002)).await;
+    let quorum_config = EthToStrkOracleQuorumConfig {
+        min_agreeing_sources: 2,
+        max_deviation_from_median_bps: 100,
+        ..Default::default()
+    };
+    let client = quorum_client(&servers, 60, quorum_config).await;
+
+    assert_eq!(resolve_rate(&client, 1234567890, None).await.unwrap(), 1001);
+}
+
+#[tokio::test]
+async fn eth_to_fri_rate_quorum_not_reached() {
+    let mut servers = Vec::new();
+    for _ in 0..3 {
+        servers.push(mockito::Server::new_async().await);
+    }
+    let _m0 = make_server(&mut servers[0], rate_body(1000)).await;
+    let _m1 = make_server(&mut servers[1], rate_body(2000)).await;
+    let _m2 = make_server(&mut servers[2], json!({"foo": "0x0", "bar": 18})).await;
+    let quorum_config = EthToStrkOracleQuorumConfig {
+        min_agreeing_sources: 2,
+        max_deviation_from_median_bps: 100,
+        ..Default::default()
+    };
+    let client = quorum_client(&servers, 60, quorum_config).await;
+
+    assert_matches!(
+        resolve_rate(&client, 1234567890, None).await,
+        Err(EthToStrkOracleClientError::QuorumNotReachedError(_, 0, 2))
+    );
+}
+
+#[tokio::test]
+async fn eth_to_fri_rate_quorum_caps_rate_change_relative_to_previous_block() {
+    let lag_interval_seconds = 60;
+    let timestamp = 1234567890;
+    let mut server = mockito::Server::new_async().await;
+    let quorum_config = EthToStrkOracleQuorumConfig {
+        min_agreeing_sources: 1,
+        max_rate_change_per_bucket_bps: 1000,
+        ..Default::default()
+    };
+    let _m = make_server(&mut server, rate_body(2000)).await;
+    let client =
+        quorum_client(std::slice::from_ref(&server), lag_interval_seconds, quorum_config).await;
+
+    // Without a previous block, the rate isn't capped.
+    assert_eq!(resolve_rate(&client, timestamp, None).await.unwrap(), 2000);
+
+    // A block in the next bucket may change the rate by at most 10%, on every call.
+    let previous_rate =
+        PreviousEthToFriRate { timestamp: timestamp - lag_interval_seconds, eth_to_fri_rate: 1000 };
+    assert_eq!(resolve_rate(&client, timestamp, Some(previous_rate)).await.unwrap(), 1100);
+    assert_eq!(resolve_rate(&client, timestamp, Some(previous_rate)).await.unwrap(), 1100);
+
+    // Two buckets later, by at most 20%.
+    let previous_rate = PreviousEthToFriRate {
+        timestamp: timestamp - 2 * lag_interval_seconds,
+        eth_to_fri_rate: 1000,
+    };
+    assert_eq!(resolve_rate(&client, timestamp, Some(previous_rate)).await.unwrap(), 1200);
+
+    // The rate can't change within the bucket of the previous block.
+    let previous_rate = PreviousEthToFriRate { timestamp, eth_to_fri_rate: 1000 };
+    assert_eq!(resolve_rate(&client, timestamp, Some(previous_rate)).await.unwrap(), 1000);
+}
@@ -2,6 +2,7 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
 use apollo_l1_gas_price_types::{GasPriceData, MockL1GasPriceProviderClient};
+use papyrus_base_layer::simulated_base_layer::SimulatedBaseLayer;
 use papyrus_base_layer::{L1BlockHash, L1BlockHeader, MockBaseLayerContract};
 use rstest::rstest;
 use starknet_api::block::GasPrice;
@@ -279,4 +280,26 @@ async fn l1_short_reorg_gas_price_scraper_is_fine(#[case] finality: u64) {
     }
 }
 
+#[tokio::test]
+async fn l1_reorg_on_simulated_base_layer_is_detected() {
+    let base_layer = SimulatedBaseLayer::default();
+    base_layer.mine_blocks(3);
+    let mut mock_provider = MockL1GasPriceProviderClient::new();
+    mock_provider.expect_add_price_info().times(4).returning(|_| Ok(()));
+    let mut scraper = L1GasPriceScraper::new(
+        L1GasPriceScraperConfig::default(),
+        Arc::new(mock_provider),
+        base_layer.clone(),
+    );
+
+    let mut block_number = 0;
+    scraper.update_prices(&mut block_number).await.unwrap();
+    assert_eq!(block_number, 4);
+
+    base_layer.reorg(1);
+    base_layer.mine_blocks(2);
+    let result = scraper.update_prices(&mut block_number).await;
+    assert!(matches!(result, Err(L1GasPriceScraperError::L1ReorgDetected { .. })));
+}
+
 // TODO(guyn): test scraper with a provider timeout
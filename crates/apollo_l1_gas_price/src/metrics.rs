@@ -1,4 +1,13 @@
-use apollo_metrics::define_metrics;
+use apollo_metrics::{define_metrics, generate_permutation_labels};
+use strum::VariantNames;
+use strum_macros::EnumVariantNames;
+
+pub const LABEL_NAME_ORACLE_SOURCE: &str = "oracle_source";
+
+generate_permutation_labels! {
+    ORACLE_SOURCE_LABELS,
+    (LABEL_NAME_ORACLE_SOURCE, OracleSourceLabelValue),
+}
 
 define_metrics!(
     L1GasPrice => {
@@ -8,6 +17,12 @@ define_metrics!(
         MetricCounter { L1_GAS_PRICE_SCRAPER_REORG_DETECTED, "l1_gas_price_scraper_reorg_detected", "Number of times the L1 gas price scraper detected a reorganization in the base layer", init=0 },
         MetricCounter { ETH_TO_STRK_ERROR_COUNT, "eth_to_strk_error_count", "Number of times the query to the Eth to Strk oracle failed due to an error or timeout", init=0 },
         MetricCounter { ETH_TO_STRK_SUCCESS_COUNT, "eth_to_strk_success_count", "Number of times the query to the Eth to Strk oracle succeeded", init=0 },
+        MetricCounter { ETH_TO_STRK_QUORUM_NOT_REACHED_COUNT, "eth_to_strk_quorum_not_reached_count", "Number of times too few Eth to Strk oracle sources agreed on a rate", init=0 },
+        MetricCounter { ETH_TO_STRK_RATE_CHANGE_CAPPED_COUNT, "eth_to_strk_rate_change_capped_count", "Number of times the change of the Eth to Strk rate between buckets was capped", init=0 },
+        LabeledMetricCounter { ETH_TO_STRK_SOURCE_SUCCESS_COUNT, "eth_to_strk_source_success_count", "Number of times an Eth to Strk oracle source answered a quorum query", init=0, labels = ORACLE_SOURCE_LABELS },
+        LabeledMetricCounter { ETH_TO_STRK_SOURCE_ERROR_COUNT, "eth_to_strk_source_error_count", "Number of times an Eth to Strk oracle source failed or timed out on a quorum query", init=0, labels = ORACLE_SOURCE_LABELS },
+        LabeledMetricCounter { ETH_TO_STRK_SOURCE_OUTLIER_COUNT, "eth_to_strk_source_outlier_count", "Number of times the rate of an Eth to Strk oracle source was rejected as an outlier", init=0, labels = ORACLE_SOURCE_LABELS },
+        LabeledMetricGauge { ETH_TO_STRK_SOURCE_DEVIATION_BPS, "eth_to_strk_source_deviation_bps", "The deviation (basis points) of the last rate of an Eth to Strk oracle source from the median rate", labels = ORACLE_SOURCE_LABELS },
         MetricGauge { L1_GAS_PRICE_SCRAPER_LATEST_SCRAPED_BLOCK, "l1_gas_price_scraper_latest_scraped_block", "The latest block number that the L1 gas price scraper has scraped" },
         MetricGauge { ETH_TO_STRK_RATE, "eth_to_strk_rate", "The current rate of ETH to STRK conversion" },
         MetricGauge { L1_GAS_PRICE_LATEST_MEAN_VALUE, "l1_gas_price_latest_mean_value", "The latest L1 gas price, calculated as an average by the provider client" },
@@ -15,6 +30,33 @@ define_metrics!(
     }
 );
 
+/// Oracle sources are labeled by their position in the oracle's URL list, since the URLs are
+/// private. This bounds the number of sources in quorum mode.
+#[derive(Clone, Copy, Debug, EnumVariantNames)]
+pub enum OracleSourceLabelValue {
+    #[strum(serialize = "0")]
+    Source0,
+    #[strum(serialize = "1")]
+    Source1,
+    #[strum(serialize = "2")]
+    Source2,
+    #[strum(serialize = "3")]
+    Source3,
+    #[strum(serialize = "4")]
+    Source4,
+    #[strum(serialize = "5")]
+    Source5,
+    #[strum(serialize = "6")]
+    Source6,
+    #[strum(serialize = "7")]
+    Source7,
+}
+
+/// The label of the oracle source at position `index` in the oracle's URL list.
+pub(crate) fn oracle_source_label(index: usize) -> [(&'static str, &'static str); 1] {
+    [(LABEL_NAME_ORACLE_SOURCE, OracleSourceLabelValue::VARIANTS[index])]
+}
+
 pub(crate) fn register_provider_metrics() {
     L1_GAS_PRICE_PROVIDER_INSUFFICIENT_HISTORY.register();
     L1_GAS_PRICE_LATEST_MEAN_VALUE.register();
@@ -32,4 +74,10 @@ pub(crate) fn register_eth_to_strk_metrics() {
     ETH_TO_STRK_ERROR_COUNT.register();
     ETH_TO_STRK_SUCCESS_COUNT.register();
     ETH_TO_STRK_RATE.register();
+    ETH_TO_STRK_QUORUM_NOT_REACHED_COUNT.register();
+    ETH_TO_STRK_RATE_CHANGE_CAPPED_COUNT.register();
+    ETH_TO_STRK_SOURCE_SUCCESS_COUNT.register();
+    ETH_TO_STRK_SOURCE_ERROR_COUNT.register();
+    ETH_TO_STRK_SOURCE_OUTLIER_COUNT.register();
+    ETH_TO_STRK_SOURCE_DEVIATION_BPS.register();
 }
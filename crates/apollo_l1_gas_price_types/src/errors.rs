@@ -43,4 +43,8 @@ pub enum EthToStrkOracleClientError {
     QueryNotReadyError(u64),
     #[error("All URLs in the list failed for timestamp {0}, starting with index {1}")]
     AllUrlsFailedError(u64, usize),
+    #[error(
+        "Only {1} oracle sources agree on the rate for timestamp {0}, at least {2} are required"
+    )]
+    QuorumNotReachedError(u64, usize, usize),
 }
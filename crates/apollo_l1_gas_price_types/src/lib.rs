@@ -87,11 +87,26 @@ pub trait L1GasPriceProviderClient: Send + Sync {
     ) -> L1GasPriceProviderClientResult<PriceInfo>;
 }
 
+/// The eth to fri rate of the previous block, relative to which the change of the rate may be
+/// capped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreviousEthToFriRate {
+    /// The timestamp of the previous block.
+    pub timestamp: u64,
+    pub eth_to_fri_rate: u128,
+}
+
 #[cfg_attr(any(feature = "testing", test), automock)]
 #[async_trait]
 pub trait EthToStrkOracleClientTrait: Send + Sync {
-    /// Fetches the eth to fri rate for a given timestamp.
-    async fn eth_to_fri_rate(&self, timestamp: u64) -> Result<u128, EthToStrkOracleClientError>;
+    /// Fetches the eth to fri rate for a given timestamp. The change of the rate relative to the
+    /// previous block's rate, if given, may be capped, so the result is the same for every node
+    /// building on the same previous block.
+    async fn eth_to_fri_rate(
+        &self,
+        timestamp: u64,
+        previous_rate: Option<PreviousEthToFriRate>,
+    ) -> Result<u128, EthToStrkOracleClientError>;
 }
 
 #[async_trait]
@@ -49,6 +49,15 @@ impl ComponentRequestHandler<L1ProviderRequest, L1ProviderResponse> for L1Provid
             L1ProviderRequest::Initialize(events) => {
                 L1ProviderResponse::Initialize(self.initialize(events).await)
             }
+            L1ProviderRequest::RetractEvents(events) => {
+                L1ProviderResponse::RetractEvents(self.retract_events(events))
+            }
+            L1ProviderRequest::SetScrapedL1Block(l1_block) => {
+                L1ProviderResponse::SetScrapedL1Block(self.set_scraped_l1_block(l1_block))
+            }
+            L1ProviderRequest::GetScrapedL1Block => {
+                L1ProviderResponse::GetScrapedL1Block(self.get_scraped_l1_block())
+            }
             L1ProviderRequest::GetL1ProviderSnapshot => {
                 L1ProviderResponse::GetL1ProviderSnapshot(self.get_l1_provider_snapshot())
             }
@@ -16,12 +16,14 @@ use apollo_l1_provider_types::{
 use apollo_state_sync_types::communication::SharedStateSyncClient;
 use apollo_time::time::{Clock, DefaultClock};
 use indexmap::IndexSet;
+use papyrus_base_layer::L1BlockReference;
 use starknet_api::block::BlockNumber;
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::bootstrapper::Bootstrapper;
+use crate::persistence::L1ProviderStore;
 use crate::transaction_manager::TransactionManager;
 use crate::{L1ProviderConfig, ProviderState};
 
@@ -42,6 +44,11 @@ pub struct L1Provider {
     pub state: ProviderState,
     pub clock: Arc<dyn Clock>,
     pub start_height: BlockNumber,
+    /// Persists the records and the current height across restarts, if configured.
+    pub(crate) store: Option<L1ProviderStore>,
+    /// The last L1 block whose events the provider has, as reported by the scraper, or restored
+    /// on startup.
+    pub l1_block: Option<L1BlockReference>,
 }
 
 impl L1Provider {
@@ -201,9 +208,85 @@ impl L1Provider {
                 _ => return Err(L1ProviderError::unsupported_l1_event(event)),
             }
         }
+        self.persist();
         Ok(())
     }
 
+    /// Undoes events of L1 blocks that were reorged out: L1 handler transactions are removed and
+    /// cancellation requests are dropped, except for transactions that were already committed on
+    /// L2, which are kept as is. Transactions staged in the current block are removed once the
+    /// block ends, unless they are committed in it.
+    #[instrument(skip_all, err)]
+    pub fn retract_events(&mut self, events: Vec<Event>) -> L1ProviderResult<()> {
+        if self.state.uninitialized() {
+            return Err(L1ProviderError::Uninitialized);
+        }
+
+        info!("Retracting {} l1 events", events.len());
+        trace!("Retracting events: {events:?}");
+
+        // In reverse order, so that a cancellation request is retracted before its transaction.
+        for event in events.into_iter().rev() {
+            match event {
+                Event::L1HandlerTransaction { l1_handler_tx, .. } => {
+                    let tx_hash = l1_handler_tx.tx_hash;
+                    if self.tx_manager.is_committed(tx_hash) {
+                        warn!(
+                            "L1 handler transaction {tx_hash} was reorged out of L1, but is \
+                             already committed on L2, keeping it."
+                        );
+                    } else if self.tx_manager.is_staged(tx_hash) {
+                        info!(
+                            "L1 handler transaction {tx_hash} was reorged out of L1 while staged \
+                             in the current block, retracting it once the block ends."
+                        );
+                        self.tx_manager.defer_retraction(tx_hash);
+                    } else if self.tx_manager.retract_tx(tx_hash) {
+                        info!("Retracted L1 handler transaction {tx_hash}.");
+                    } else {
+                        debug!("Unknown reorged out L1 handler transaction {tx_hash}, skipping.");
+                    }
+                }
+                Event::TransactionCancellationStarted {
+                    tx_hash,
+                    cancellation_request_timestamp,
+                } => {
+                    let retracted = self
+                        .tx_manager
+                        .retract_cancellation_request(tx_hash, cancellation_request_timestamp);
+                    if !retracted {
+                        debug!(
+                            "Reorged out cancellation request for {tx_hash} at \
+                             {cancellation_request_timestamp} was not recorded, skipping."
+                        );
+                    }
+                }
+                _ => return Err(L1ProviderError::unsupported_l1_event(event)),
+            }
+        }
+        self.persist();
+        Ok(())
+    }
+
+    /// Records the last L1 block whose events were added or retracted, so that the scraper can
+    /// resume from it after a restart.
+    pub fn set_scraped_l1_block(&mut self, l1_block: L1BlockReference) -> L1ProviderResult<()> {
+        if self.state.uninitialized() {
+            return Err(L1ProviderError::Uninitialized);
+        }
+
+        debug!("Setting the last scraped L1 block to {l1_block:?}");
+        self.l1_block = Some(l1_block);
+        self.persist();
+        Ok(())
+    }
+
+    /// Returns the last L1 block whose events the provider has, if known. The scraper only
+    /// resumes from its own persisted state if it ends at this block.
+    pub fn get_scraped_l1_block(&self) -> L1ProviderResult<Option<L1BlockReference>> {
+        Ok(self.l1_block)
+    }
+
     pub fn get_l1_provider_snapshot(&self) -> L1ProviderResult<L1ProviderSnapshot> {
         let txs_snapshot = self.tx_manager.snapshot();
         Ok(L1ProviderSnapshot {
@@ -235,9 +318,36 @@ impl L1Provider {
         debug!("Applying commit_block to height: {}", self.current_height);
         let (rejected_and_consumed, committed_txs): (Vec<_>, Vec<_>) =
             consumed_txs.iter().copied().partition(|tx| rejected_txs.contains(tx));
-        self.tx_manager.commit_txs(&committed_txs, &rejected_and_consumed);
+        self.tx_manager.commit_txs(&committed_txs, &rejected_and_consumed, self.current_height);
 
         self.current_height = self.current_height.unchecked_next();
+        if let Some(prune_height) =
+            self.current_height.0.checked_sub(self.config.committed_txs_retention_blocks)
+        {
+            let n_pruned = self.tx_manager.prune_committed(BlockNumber(prune_height));
+            if n_pruned > 0 {
+                debug!("Pruned {n_pruned} records of transactions committed below {prune_height}.");
+            }
+        }
+        self.persist();
+    }
+
+    /// Persists the records and the current height, if persistence is configured. Failures are
+    /// logged, since the in-memory state remains valid.
+    fn persist(&mut self) {
+        let Some(store) = &mut self.store else {
+            return;
+        };
+        let changed_txs = self.tx_manager.take_changed_txs();
+        if let Err(err) = store.store(
+            self.current_height,
+            self.l1_block,
+            &self.tx_manager.records,
+            changed_txs,
+            self.tx_manager.deferred_retractions(),
+        ) {
+            error!("Failed to persist the L1 provider state to {:?}: {err}", store.path());
+        }
     }
 
     /// Try to apply commit_block backlog, and if all caught up, drop bootstrapping state.
@@ -401,6 +511,28 @@ impl L1ProviderBuilder {
     }
 
     pub fn build(self) -> L1Provider {
+        let (mut store, mut persisted_state) = match &self.config.persistence_dir {
+            Some(persistence_dir) => {
+                let (store, persisted_state) = L1ProviderStore::open(persistence_dir)
+                    .unwrap_or_else(|err| {
+                        panic!("Failed to open the L1 provider store at {persistence_dir:?}: {err}")
+                    });
+                (Some(store), persisted_state)
+            }
+            None => (None, None),
+        };
+        if persisted_state.is_some() && self.config.provider_startup_height_override.is_some() {
+            warn!(
+                "Ignoring the persisted L1 provider state, since the startup height is overridden."
+            );
+            persisted_state = None;
+            if let Some(store) = &mut store {
+                store.clear().unwrap_or_else(|err| {
+                    panic!("Failed to clear the L1 provider store at {:?}: {err}", store.path())
+                });
+            }
+        }
+
         let l1_provider_startup_height = self
             .config
             .provider_startup_height_override
@@ -412,6 +544,7 @@ impl L1ProviderBuilder {
                      See docstring."
                 );
             })
+            .or(persisted_state.as_ref().map(|persisted_state| persisted_state.current_height))
             .or(self.startup_height)
             // TODO(Gilad): remove expect message below once we support LogStateUpdate in Anvil.
             .expect(
@@ -443,17 +576,31 @@ impl L1ProviderBuilder {
             catchup_height,
         );
 
+        let mut tx_manager = TransactionManager::new(
+            self.config.new_l1_handler_cooldown_seconds,
+            self.config.l1_handler_cancellation_timelock_seconds,
+        );
+        let mut l1_block = None;
+        if let Some(persisted_state) = persisted_state {
+            info!(
+                "Restoring {} persisted L1 handler transaction records, up to L1 block {:?}.",
+                persisted_state.records.len(),
+                persisted_state.l1_block
+            );
+            tx_manager.restore_records(persisted_state.records);
+            l1_block = persisted_state.l1_block;
+        }
+
         info!("Starting L1 provider at height: {l1_provider_startup_height}");
         L1Provider {
             start_height: l1_provider_startup_height,
             current_height: l1_provider_startup_height,
-            tx_manager: TransactionManager::new(
-                self.config.new_l1_handler_cooldown_seconds,
-                self.config.l1_handler_cancellation_timelock_seconds,
-            ),
+            tx_manager,
             state: ProviderState::Bootstrap(bootstrapper),
             config: self.config,
             clock: self.clock.unwrap_or_else(|| Arc::new(DefaultClock)),
+            store,
+            l1_block,
         }
     }
 }
@@ -13,6 +13,7 @@ use apollo_state_sync_types::communication::MockStateSyncClient;
 use apollo_time::test_utils::FakeClock;
 use assert_matches::assert_matches;
 use itertools::Itertools;
+use papyrus_base_layer::L1BlockReference;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
@@ -21,7 +22,7 @@ use starknet_api::transaction::TransactionHash;
 use starknet_api::tx_hash;
 
 use crate::bootstrapper::{Bootstrapper, CommitBlockBacklog, SyncTaskHandle};
-use crate::l1_provider::L1Provider;
+use crate::l1_provider::{L1Provider, L1ProviderBuilder};
 use crate::test_utils::{l1_handler, FakeL1ProviderClient, L1ProviderContentBuilder};
 use crate::{L1ProviderConfig, ProviderState};
 
@@ -996,3 +997,252 @@ fn commit_block_historical_height_short_circuits_bootstrap() {
     let expected_unchanged = l1_provider_builder.build();
     expected_unchanged.assert_eq(&l1_provider);
 }
+
+#[test]
+fn retract_events_keeps_committed_txs() {
+    // Setup.
+    let tx_uncommitted = l1_handler(1);
+    let tx_cancel_requested = l1_handler(2);
+    let tx_committed = l1_handler(3);
+    let cancellation_request_timestamp = 1;
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([tx_uncommitted.clone()])
+        .with_timed_cancel_requested_txs([(
+            tx_cancel_requested.clone(),
+            cancellation_request_timestamp,
+        )])
+        .with_committed([tx_committed.clone()])
+        .with_state(ProviderState::Pending)
+        .build_into_l1_provider();
+
+    // Test.
+    l1_provider
+        .retract_events(vec![
+            l1_handler_event(tx_uncommitted.tx_hash),
+            cancellation_event(tx_cancel_requested.tx_hash, cancellation_request_timestamp.into()),
+            l1_handler_event(tx_committed.tx_hash),
+        ])
+        .unwrap();
+
+    // The transaction whose cancellation request was reorged out is proposable again.
+    let expected = L1ProviderContentBuilder::new()
+        .with_txs([tx_cancel_requested])
+        .with_timed_cancel_requested_txs([])
+        .with_committed([tx_committed])
+        .build();
+    expected.assert_eq(&l1_provider);
+}
+
+#[test]
+fn retract_events_defers_staged_txs() {
+    // Setup.
+    let [tx_committed, tx_rejected, tx_unstaged] = [l1_handler(1), l1_handler(2), l1_handler(3)];
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([tx_committed.clone(), tx_rejected.clone(), tx_unstaged.clone()])
+        .with_state(ProviderState::Propose)
+        .build_into_l1_provider();
+    assert_eq!(
+        l1_provider.get_txs(2, BlockNumber(0)).unwrap(),
+        [tx_committed.clone(), tx_rejected.clone()]
+    );
+
+    // Test.
+    l1_provider
+        .retract_events(vec![
+            l1_handler_event(tx_committed.tx_hash),
+            l1_handler_event(tx_rejected.tx_hash),
+            l1_handler_event(tx_unstaged.tx_hash),
+        ])
+        .unwrap();
+
+    // Staged transactions are kept until the block ends.
+    let expected = L1ProviderContentBuilder::new()
+        .with_txs([tx_committed.clone(), tx_rejected.clone()])
+        .build();
+    expected.assert_eq(&l1_provider);
+
+    l1_provider
+        .commit_block(
+            [tx_committed.tx_hash, tx_rejected.tx_hash].into(),
+            [tx_rejected.tx_hash].into(),
+            BlockNumber(0),
+        )
+        .unwrap();
+
+    // Only the transaction committed in the block is kept.
+    let expected = L1ProviderContentBuilder::new()
+        .with_txs([])
+        .with_rejected([])
+        .with_committed([tx_committed])
+        .build();
+    expected.assert_eq(&l1_provider);
+}
+
+#[test]
+fn retract_events_keeps_staged_txs_re_added_by_reorg() {
+    // Setup.
+    let [tx_re_added, tx_reorged_out] = [l1_handler(1), l1_handler(2)];
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([tx_re_added.clone(), tx_reorged_out.clone()])
+        .with_state(ProviderState::Propose)
+        .build_into_l1_provider();
+    assert_eq!(
+        l1_provider.get_txs(2, BlockNumber(0)).unwrap(),
+        [tx_re_added.clone(), tx_reorged_out.clone()]
+    );
+
+    // Test.
+    l1_provider
+        .retract_events(vec![
+            l1_handler_event(tx_re_added.tx_hash),
+            l1_handler_event(tx_reorged_out.tx_hash),
+        ])
+        .unwrap();
+    // The new L1 chain includes the first transaction as well.
+    l1_provider.add_events(vec![l1_handler_event(tx_re_added.tx_hash)]).unwrap();
+    commit_block_no_rejected(&mut l1_provider, &[], BlockNumber(0));
+
+    // Only the transaction missing from the new L1 chain is retracted when the block ends.
+    let expected = L1ProviderContentBuilder::new().with_txs([tx_re_added]).build();
+    expected.assert_eq(&l1_provider);
+}
+
+fn l1_provider_with_persistence(config: L1ProviderConfig) -> L1Provider {
+    L1ProviderBuilder::new(
+        config,
+        Arc::new(FakeL1ProviderClient::default()),
+        Arc::new(MockBatcherClient::default()),
+        Arc::new(MockStateSyncClient::default()),
+    )
+    .startup_height(BlockNumber(0))
+    .build()
+}
+
+#[test]
+fn persisted_state_is_restored_on_build() {
+    // Setup.
+    let persistence_dir = tempfile::tempdir().unwrap();
+    let config = L1ProviderConfig {
+        persistence_dir: Some(persistence_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut l1_provider = l1_provider_with_persistence(config.clone());
+    l1_provider.state = ProviderState::Pending;
+    l1_provider
+        .add_events(vec![l1_handler_event(tx_hash!(1)), l1_handler_event(tx_hash!(2))])
+        .unwrap();
+    commit_block_no_rejected(&mut l1_provider, &[tx_hash!(1)], BlockNumber(0));
+
+    // Test.
+    let restored_l1_provider = l1_provider_with_persistence(config);
+
+    // The persisted height takes precedence over the startup height given to the builder.
+    let expected = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(2)])
+        .with_committed([l1_handler(1)])
+        .with_height(BlockNumber(1))
+        .build();
+    expected.assert_eq(&restored_l1_provider);
+}
+
+#[test]
+fn persisted_state_is_ignored_when_startup_height_is_overridden() {
+    // Setup.
+    let persistence_dir = tempfile::tempdir().unwrap();
+    let config = L1ProviderConfig {
+        persistence_dir: Some(persistence_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut l1_provider = l1_provider_with_persistence(config.clone());
+    l1_provider.state = ProviderState::Pending;
+    l1_provider.add_events(vec![l1_handler_event(tx_hash!(1))]).unwrap();
+
+    // Test.
+    let startup_height_override = BlockNumber(5);
+    let restored_l1_provider = l1_provider_with_persistence(L1ProviderConfig {
+        provider_startup_height_override: Some(startup_height_override),
+        ..config.clone()
+    });
+
+    let expected =
+        L1ProviderContentBuilder::new().with_txs([]).with_height(startup_height_override).build();
+    expected.assert_eq(&restored_l1_provider);
+
+    // The ignored state is dropped, so later restarts don't restore it either.
+    let restored_l1_provider = l1_provider_with_persistence(config);
+    let expected = L1ProviderContentBuilder::new().with_txs([]).with_height(BlockNumber(0)).build();
+    expected.assert_eq(&restored_l1_provider);
+}
+
+#[test]
+fn scraped_l1_block_is_restored_on_build() {
+    // Setup.
+    let persistence_dir = tempfile::tempdir().unwrap();
+    let config = L1ProviderConfig {
+        persistence_dir: Some(persistence_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut l1_provider = l1_provider_with_persistence(config.clone());
+    assert_eq!(l1_provider.get_scraped_l1_block().unwrap(), None);
+    l1_provider.state = ProviderState::Pending;
+    l1_provider.add_events(vec![l1_handler_event(tx_hash!(1))]).unwrap();
+    let scraped_l1_block = L1BlockReference { number: 7, hash: [7; 32] };
+    l1_provider.set_scraped_l1_block(scraped_l1_block).unwrap();
+
+    // Test.
+    let restored_l1_provider = l1_provider_with_persistence(config);
+
+    assert_eq!(restored_l1_provider.get_scraped_l1_block().unwrap(), Some(scraped_l1_block));
+}
+
+#[test]
+fn deferred_retractions_are_applied_to_persisted_state() {
+    // Setup.
+    let persistence_dir = tempfile::tempdir().unwrap();
+    let config = L1ProviderConfig {
+        persistence_dir: Some(persistence_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut l1_provider = l1_provider_with_persistence(config.clone());
+    l1_provider.state = ProviderState::Pending;
+    l1_provider
+        .add_events(vec![l1_handler_event(tx_hash!(1)), l1_handler_event(tx_hash!(2))])
+        .unwrap();
+    l1_provider.state = ProviderState::Propose;
+    assert_eq!(l1_provider.get_txs(1, BlockNumber(0)).unwrap(), [l1_handler(1)]);
+
+    // Test.
+    l1_provider.retract_events(vec![l1_handler_event(tx_hash!(1))]).unwrap();
+    let restored_l1_provider = l1_provider_with_persistence(config);
+
+    // Nothing is staged after the restart, so the staged transaction is retracted.
+    let expected = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(2)])
+        .with_height(BlockNumber(0))
+        .build();
+    expected.assert_eq(&restored_l1_provider);
+}
+
+#[test]
+fn committed_txs_are_pruned_after_retention() {
+    // Setup.
+    let config = L1ProviderConfig { committed_txs_retention_blocks: 1, ..Default::default() };
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_config(config)
+        .with_txs([l1_handler(1), l1_handler(2)])
+        .with_height(BlockNumber(0))
+        .with_state(ProviderState::Pending)
+        .build_into_l1_provider();
+
+    // Test.
+    commit_block_no_rejected(&mut l1_provider, &[tx_hash!(1)], BlockNumber(0));
+    commit_block_no_rejected(&mut l1_provider, &[tx_hash!(2)], BlockNumber(1));
+
+    // Only the transaction committed in the last block is kept.
+    let expected = L1ProviderContentBuilder::new()
+        .with_txs([])
+        .with_committed([l1_handler(2)])
+        .with_height(BlockNumber(2))
+        .build();
+    expected.assert_eq(&l1_provider);
+}
@@ -1,9 +1,10 @@
 use std::any::type_name;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
 use std::time::Duration;
 
 use apollo_config::converters::deserialize_float_seconds_to_duration;
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
 use apollo_config::validators::validate_ascii;
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_infra::component_client::ClientError;
@@ -26,9 +27,11 @@ use validator::Validate;
 use crate::metrics::{
     register_scraper_metrics,
     L1_MESSAGE_SCRAPER_BASELAYER_ERROR_COUNT,
+    L1_MESSAGE_SCRAPER_REORG_DEPTH,
     L1_MESSAGE_SCRAPER_REORG_DETECTED,
     L1_MESSAGE_SCRAPER_SUCCESS_COUNT,
 };
+use crate::persistence::{PersistedL1Scraper, SnapshotFile, L1_SCRAPER_SNAPSHOT_FILE_NAME};
 
 #[cfg(test)]
 #[path = "l1_scraper_tests.rs"]
@@ -45,6 +48,15 @@ pub struct L1Scraper<B: BaseLayerContract> {
     pub last_l1_block_processed: L1BlockReference,
     pub l1_provider_client: SharedL1ProviderClient,
     tracked_event_identifiers: Vec<EventIdentifier>,
+    /// The last L1 block of each recent scraping round, with the events scraped in that round,
+    /// oldest first. Used to find the common ancestor of an L1 reorg and the events to retract.
+    /// The front is the newest round at least `max_l1_reorg_depth` blocks deep.
+    scraped_history: VecDeque<(L1BlockReference, Vec<Event>)>,
+    /// Persists the scraped history across restarts, if configured.
+    store: Option<SnapshotFile<PersistedL1Scraper>>,
+    /// The scraped history restored from the store on startup. Resumed from only if the provider
+    /// has the events up to the same L1 block.
+    persisted_history: Option<Vec<(L1BlockReference, Vec<Event>)>>,
 }
 
 impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
@@ -55,30 +67,75 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         events_identifiers_to_track: &[EventIdentifier],
         l1_start_block: L1BlockReference,
     ) -> L1ScraperResult<Self, B> {
+        let (store, persisted_history) = match &config.persistence_dir {
+            Some(persistence_dir) => {
+                let store = SnapshotFile::new(persistence_dir, L1_SCRAPER_SNAPSHOT_FILE_NAME)
+                    .unwrap_or_else(|err| {
+                        panic!("Failed to open the L1 scraper store at {persistence_dir:?}: {err}")
+                    });
+                let persisted_state = store.load().unwrap_or_else(|err| {
+                    panic!("Failed to load the persisted L1 scraper state: {err}")
+                });
+                let persisted_history =
+                    persisted_state.map(|PersistedL1Scraper { scraped_history }| scraped_history);
+                (Some(store), persisted_history)
+            }
+            None => (None, None),
+        };
+
         Ok(Self {
             l1_provider_client,
             base_layer,
             last_l1_block_processed: l1_start_block,
             config,
             tracked_event_identifiers: events_identifiers_to_track.to_vec(),
+            scraped_history: VecDeque::from([(l1_start_block, vec![])]),
+            store,
+            persisted_history,
         })
     }
 
     #[instrument(skip(self), err)]
     async fn initialize(&mut self) -> L1ScraperResult<(), B> {
+        if let Some(persisted_history) = self.persisted_history.take() {
+            let (persisted_l1_block, _) =
+                persisted_history.last().expect("The persisted scraped history is never empty.");
+            let persisted_l1_block = *persisted_l1_block;
+            let provider_l1_block_result = self.l1_provider_client.get_scraped_l1_block().await;
+            let provider_l1_block = handle_client_error(provider_l1_block_result)?;
+            if provider_l1_block == Some(persisted_l1_block) {
+                info!("Resuming L1 scraping after the persisted L1 block {persisted_l1_block:?}.");
+                // The provider has the events up to the last processed L1 block, which may
+                // have been reorged out since. So only initialize it, and let the regular
+                // scraping recover from reorgs and scan the L1 blocks since.
+                let initialize_result = self.l1_provider_client.initialize(vec![]).await;
+                handle_client_error(initialize_result)?;
+                self.last_l1_block_processed = persisted_l1_block;
+                self.scraped_history = persisted_history.into();
+                return Ok(());
+            }
+            warn!(
+                "Discarding the persisted L1 scraper state up to L1 block {persisted_l1_block:?}, \
+                 since the L1 provider has the events up to L1 block {provider_l1_block:?}. \
+                 Scanning from the start block {:?}.",
+                self.last_l1_block_processed
+            );
+        }
+
         let (latest_l1_block, events) = self.fetch_events().await?;
 
         // If this gets too high, send in batches.
-        let initialize_result = self.l1_provider_client.initialize(events).await;
+        let initialize_result = self.l1_provider_client.initialize(events.clone()).await;
         handle_client_error(initialize_result)?;
 
-        self.last_l1_block_processed = latest_l1_block;
+        self.set_scraped_l1_block(latest_l1_block).await?;
+        self.record_scraped_events(latest_l1_block, events);
 
         Ok(())
     }
 
     pub async fn send_events_to_l1_provider(&mut self) -> L1ScraperResult<(), B> {
-        self.assert_no_l1_reorgs().await?;
+        self.recover_from_l1_reorgs().await?;
 
         let (latest_l1_block, events) = self.fetch_events().await?;
         trace!("scraped up to {latest_l1_block:?}");
@@ -87,14 +144,60 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         // Sending even if there are no events, to keep the flow as simple/debuggable as possible.
         // Perf hit is minimal, since the scraper is on the same machine as the provider (no net).
         // If this gets spammy, short-circuit on events.empty().
-        let add_events_result = self.l1_provider_client.add_events(events).await;
+        let add_events_result = self.l1_provider_client.add_events(events.clone()).await;
         handle_client_error(add_events_result)?;
 
-        self.last_l1_block_processed = latest_l1_block;
+        self.set_scraped_l1_block(latest_l1_block).await?;
+        self.record_scraped_events(latest_l1_block, events);
 
         Ok(())
     }
 
+    /// Reports the last L1 block whose events the provider has, so that it persists it along with
+    /// its state, if persistence is configured. Must precede persisting the scraper's state, so the
+    /// provider's state never lags behind it.
+    async fn set_scraped_l1_block(&self, l1_block: L1BlockReference) -> L1ScraperResult<(), B> {
+        if self.store.is_none() {
+            return Ok(());
+        }
+        let set_scraped_l1_block_result =
+            self.l1_provider_client.set_scraped_l1_block(l1_block).await;
+        handle_client_error(set_scraped_l1_block_result)
+    }
+
+    fn record_scraped_events(&mut self, latest_l1_block: L1BlockReference, events: Vec<Event>) {
+        self.last_l1_block_processed = latest_l1_block;
+        let is_new_block = self
+            .scraped_history
+            .back()
+            .is_none_or(|(last_l1_block, _)| *last_l1_block != latest_l1_block);
+        if is_new_block {
+            self.scraped_history.push_back((latest_l1_block, events));
+        }
+
+        // Keep a single round that is deep enough to serve as the common ancestor of any reorg.
+        let max_l1_reorg_depth = self.config.max_l1_reorg_depth;
+        while self.scraped_history.get(1).is_some_and(|(l1_block, _)| {
+            l1_block.number.saturating_add(max_l1_reorg_depth) <= latest_l1_block.number
+        }) {
+            self.scraped_history.pop_front();
+        }
+        self.persist();
+    }
+
+    /// Persists the scraped history, if persistence is configured. Failures are logged, since the
+    /// in-memory state remains valid.
+    fn persist(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let snapshot =
+            PersistedL1Scraper { scraped_history: self.scraped_history.iter().cloned().collect() };
+        if let Err(err) = store.store(&snapshot) {
+            error!("Failed to persist the L1 scraper state to {:?}: {err}", store.path());
+        }
+    }
+
     async fn fetch_events(&self) -> L1ScraperResult<(L1BlockReference, Vec<Event>), B> {
         let latest_l1_block = self
             .base_layer
@@ -185,39 +288,85 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         }
     }
 
-    async fn assert_no_l1_reorgs(&self) -> L1ScraperResult<(), B> {
+    /// If the last processed L1 block was reorged out, walks back to the newest scraped block that
+    /// is still on the canonical chain, retracts the events scraped after it from the provider and
+    /// resumes scraping from it. Fails if the reorg is deeper than the scraped history.
+    async fn recover_from_l1_reorgs(&mut self) -> L1ScraperResult<(), B> {
         let last_processed_l1_block_number = self.last_l1_block_processed.number;
         let last_block_processed_fresh = self
             .base_layer
             .l1_block_at(last_processed_l1_block_number)
             .await
             .map_err(L1ScraperError::BaseLayerError)?;
+        if last_block_processed_fresh == Some(self.last_l1_block_processed) {
+            return Ok(());
+        }
 
-        let Some(last_block_processed_fresh) = last_block_processed_fresh else {
-            L1_MESSAGE_SCRAPER_REORG_DETECTED.increment(1);
-            return Err(L1ScraperError::L1ReorgDetected {
-                reason: format!(
-                    "Last processed L1 block with number {last_processed_l1_block_number} no \
-                     longer exists."
-                ),
-            });
+        L1_MESSAGE_SCRAPER_REORG_DETECTED.increment(1);
+        let reason = match last_block_processed_fresh {
+            None => format!(
+                "Last processed L1 block with number {last_processed_l1_block_number} no longer \
+                 exists."
+            ),
+            Some(fresh) => format!(
+                "Last processed L1 block hash, {}, for block number {}, is different from the \
+                 hash stored, {}",
+                hex::encode(fresh.hash),
+                last_processed_l1_block_number,
+                hex::encode(self.last_l1_block_processed.hash),
+            ),
         };
+        warn!("L1 reorg detected: {reason}. Looking for the common ancestor.");
 
-        if last_block_processed_fresh.hash != self.last_l1_block_processed.hash {
-            L1_MESSAGE_SCRAPER_REORG_DETECTED.increment(1);
+        let Some(common_ancestor_index) = self.find_common_ancestor().await? else {
             return Err(L1ScraperError::L1ReorgDetected {
-                reason: format!(
-                    "Last processed L1 block hash, {}, for block number {}, is different from the \
-                     hash stored, {}",
-                    hex::encode(last_block_processed_fresh.hash),
-                    last_processed_l1_block_number,
-                    hex::encode(self.last_l1_block_processed.hash),
-                ),
+                reason: format!("{reason}. The reorg is deeper than the scraped history"),
             });
-        }
+        };
+        let (common_ancestor, _) = self.scraped_history[common_ancestor_index];
+        let reorged_out_events: Vec<Event> = self
+            .scraped_history
+            .range(common_ancestor_index + 1..)
+            .flat_map(|(_, events)| events.iter().cloned())
+            .collect();
+
+        let reorg_depth = last_processed_l1_block_number - common_ancestor.number;
+        L1_MESSAGE_SCRAPER_REORG_DEPTH.record_lossy(reorg_depth);
+        info!(
+            "Recovering from an L1 reorg of depth {reorg_depth}: retracting {} events scraped \
+             after the common ancestor {common_ancestor:?}.",
+            reorged_out_events.len()
+        );
+
+        // Events of blocks between the common ancestor and the fork point are retracted as well,
+        // and added back by the rescan.
+        let retract_events_result =
+            self.l1_provider_client.retract_events(reorged_out_events).await;
+        handle_client_error(retract_events_result)?;
+        self.set_scraped_l1_block(common_ancestor).await?;
+        self.scraped_history.truncate(common_ancestor_index + 1);
+        self.last_l1_block_processed = common_ancestor;
+        self.persist();
 
         Ok(())
     }
+
+    /// Returns the index in the scraped history of the newest block that is still on the canonical
+    /// chain, if any.
+    async fn find_common_ancestor(&self) -> L1ScraperResult<Option<usize>, B> {
+        // The newest block is known to be reorged out.
+        for (index, (l1_block, _)) in self.scraped_history.iter().enumerate().rev().skip(1) {
+            let l1_block_fresh = self
+                .base_layer
+                .l1_block_at(l1_block.number)
+                .await
+                .map_err(L1ScraperError::BaseLayerError)?;
+            if l1_block_fresh == Some(*l1_block) {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
 }
 
 pub async fn fetch_start_block<B: BaseLayerContract + Send + Sync>(
@@ -271,6 +420,10 @@ pub struct L1ScraperConfig {
     pub finality: u64,
     #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
     pub polling_interval_seconds: Duration,
+    pub max_l1_reorg_depth: u64,
+    /// Directory in which the scraper persists its state across restarts. If None, it starts
+    /// from the start block on every restart.
+    pub persistence_dir: Option<PathBuf>,
 }
 
 impl Default for L1ScraperConfig {
@@ -280,13 +433,15 @@ impl Default for L1ScraperConfig {
             chain_id: ChainId::Mainnet,
             finality: 0,
             polling_interval_seconds: Duration::from_secs(120),
+            max_l1_reorg_depth: 64,
+            persistence_dir: None,
         }
     }
 }
 
 impl SerializeConfig for L1ScraperConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from([
+        let mut dump = BTreeMap::from([
             ser_param(
                 "startup_rewind_time_seconds",
                 &self.startup_rewind_time_seconds.as_secs(),
@@ -305,13 +460,32 @@ impl SerializeConfig for L1ScraperConfig {
                 "Interval in Seconds between each scraping attempt of L1.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_l1_reorg_depth",
+                &self.max_l1_reorg_depth,
+                "The maximal depth in blocks of L1 reorgs that the scraper recovers from, by \
+                 retracting the events of the reorged out blocks and rescanning. Deeper reorgs \
+                 require a restart.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "chain_id",
                 &self.chain_id,
                 "The chain to follow. For more details see https://docs.starknet.io/documentation/architecture_and_concepts/Blocks/transactions/#chain-id.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+        ]);
+
+        dump.extend(ser_optional_param(
+            &self.persistence_dir,
+            "".into(),
+            "persistence_dir",
+            "Directory in which the L1 scraper persists its state across restarts, so that only \
+             the L1 blocks since the last shutdown are scanned on startup, provided that the L1 \
+             provider has the events up to the same L1 block.",
+            ParamPrivacyInput::Public,
+        ));
+        dump
     }
 }
 
@@ -366,11 +540,12 @@ impl<B: BaseLayerContract + Send + Sync> L1ScraperError<B> {
     }
 }
 
-fn handle_client_error<B: BaseLayerContract + Send + Sync>(
-    client_result: Result<(), L1ProviderClientError>,
-) -> Result<(), L1ScraperError<B>> {
-    let Err(error) = client_result else {
-        return Ok(());
+fn handle_client_error<B: BaseLayerContract + Send + Sync, T>(
+    client_result: Result<T, L1ProviderClientError>,
+) -> Result<T, L1ScraperError<B>> {
+    let error = match client_result {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
     };
     match error {
         L1ProviderClientError::ClientError(client_error) => {
@@ -6,16 +6,18 @@ use apollo_batcher_types::batcher_types::GetHeightResponse;
 use apollo_batcher_types::communication::MockBatcherClient;
 use apollo_infra::trace_util::configure_tracing;
 use apollo_l1_provider_types::errors::L1ProviderError;
-use apollo_l1_provider_types::{L1ProviderClient, MockL1ProviderClient};
+use apollo_l1_provider_types::{Event, L1ProviderClient, MockL1ProviderClient};
 use apollo_state_sync_types::communication::MockStateSyncClient;
 use apollo_state_sync_types::errors::StateSyncError;
 use apollo_state_sync_types::state_sync_types::SyncBlock;
 use assert_matches::assert_matches;
 use indexmap::IndexSet;
 use itertools::Itertools;
-use papyrus_base_layer::{L1BlockReference, MockBaseLayerContract};
+use papyrus_base_layer::{L1BlockReference, L1Event, MockBaseLayerContract};
 use rstest::{fixture, rstest};
-use starknet_api::block::BlockNumber;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{ChainId, Nonce};
+use starknet_api::transaction::fields::Fee;
 use starknet_api::transaction::TransactionHash;
 
 use crate::bootstrapper::Bootstrapper;
@@ -94,7 +96,7 @@ async fn bootstrap_e2e() {
         ..Default::default()
     };
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -207,7 +209,7 @@ async fn bootstrap_delayed_batcher_and_sync_state_with_trivial_catch_up() {
         ..Default::default()
     };
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -288,7 +290,7 @@ async fn bootstrap_delayed_sync_state_with_sync_behind_batcher() {
         ..Default::default()
     };
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -360,7 +362,7 @@ async fn test_stuck_sync() {
         ..Default::default()
     };
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -490,6 +492,230 @@ async fn l1_reorg_block_number(mut dummy_base_layer: MockBaseLayerContract) {
     );
 }
 
+type L1Chain = Arc<Mutex<Vec<(L1BlockReference, Vec<L1Event>)>>>;
+
+fn l1_block(number: u64, hash_byte: u8) -> L1BlockReference {
+    L1BlockReference { number, hash: [hash_byte; 32] }
+}
+
+fn log_message_to_l2(nonce: u64) -> L1Event {
+    L1Event::LogMessageToL2 {
+        tx: starknet_api::transaction::L1HandlerTransaction {
+            nonce: Nonce(nonce.into()),
+            ..Default::default()
+        },
+        fee: Fee(1),
+        l1_tx_hash: None,
+        timestamp: BlockTimestamp(nonce),
+    }
+}
+
+fn as_event(l1_event: L1Event) -> Event {
+    Event::from_l1_event(&ChainId::Mainnet, l1_event).unwrap()
+}
+
+/// A base layer that serves the blocks and events of the given chain, the index of each block in
+/// the chain being its number.
+fn base_layer_of_chain(l1_chain: &L1Chain) -> MockBaseLayerContract {
+    let mut base_layer = MockBaseLayerContract::new();
+    let chain = l1_chain.clone();
+    base_layer
+        .expect_latest_l1_block()
+        .returning(move |_| Ok(chain.lock().unwrap().last().map(|(block, _)| *block)));
+    let chain = l1_chain.clone();
+    base_layer.expect_l1_block_at().returning(move |number| {
+        Ok(chain.lock().unwrap().get(usize::try_from(number).unwrap()).map(|(block, _)| *block))
+    });
+    let chain = l1_chain.clone();
+    base_layer.expect_events().returning(move |block_range, _| {
+        let chain = chain.lock().unwrap();
+        Ok(block_range
+            .filter_map(|number| chain.get(usize::try_from(number).unwrap()))
+            .flat_map(|(_, events)| events.clone())
+            .collect())
+    });
+    base_layer
+}
+
+#[tokio::test]
+async fn l1_reorg_recovery() {
+    // Setup.
+    let l1_chain: L1Chain = Arc::new(Mutex::new(vec![
+        (l1_block(0, 0), vec![]),
+        (l1_block(1, 1), vec![log_message_to_l2(1)]),
+    ]));
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let mut scraper = L1Scraper::new(
+        L1ScraperConfig::default(),
+        l1_provider_client.clone(),
+        base_layer_of_chain(&l1_chain),
+        event_identifiers_to_track(),
+        l1_block(0, 0),
+    )
+    .await
+    .unwrap();
+
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_add_events_received_with(&[as_event(log_message_to_l2(1))]);
+    l1_chain.lock().unwrap().push((l1_block(2, 2), vec![log_message_to_l2(2)]));
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_add_events_received_with(&[as_event(log_message_to_l2(2))]);
+
+    // Test.
+    // Reorg out block 2, whose message is not included in the new chain.
+    {
+        let mut l1_chain = l1_chain.lock().unwrap();
+        l1_chain[2] = (l1_block(2, 22), vec![log_message_to_l2(3)]);
+        l1_chain.push((l1_block(3, 3), vec![]));
+    }
+    scraper.send_events_to_l1_provider().await.unwrap();
+
+    // The events of the reorged out block are retracted, and the new chain is scraped from the
+    // common ancestor.
+    l1_provider_client.assert_retract_events_received_with(&[as_event(log_message_to_l2(2))]);
+    l1_provider_client.assert_add_events_received_with(&[as_event(log_message_to_l2(3))]);
+    assert_eq!(scraper.last_l1_block_processed, l1_block(3, 3));
+}
+
+#[tokio::test]
+async fn l1_reorg_deeper_than_max_depth() {
+    // Setup.
+    let l1_chain: L1Chain = Arc::new(Mutex::new(vec![(l1_block(0, 0), vec![])]));
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let config = L1ScraperConfig { max_l1_reorg_depth: 1, ..Default::default() };
+    let mut scraper = L1Scraper::new(
+        config,
+        l1_provider_client.clone(),
+        base_layer_of_chain(&l1_chain),
+        event_identifiers_to_track(),
+        l1_block(0, 0),
+    )
+    .await
+    .unwrap();
+    for number in 1..=2 {
+        l1_chain.lock().unwrap().push((l1_block(number, number.try_into().unwrap()), vec![]));
+        scraper.send_events_to_l1_provider().await.unwrap();
+    }
+
+    // Test.
+    // Only the blocks that are at most 1 block deep are kept, so reorging out block 1 is
+    // unrecoverable.
+    {
+        let mut l1_chain = l1_chain.lock().unwrap();
+        l1_chain[1] = (l1_block(1, 11), vec![]);
+        l1_chain[2] = (l1_block(2, 22), vec![]);
+    }
+    assert_matches!(
+        scraper.send_events_to_l1_provider().await,
+        Err(L1ScraperError::L1ReorgDetected { .. })
+    );
+    l1_provider_client.assert_retract_events_received_with(&[]);
+}
+
+#[tokio::test]
+async fn resume_from_persisted_state_recovers_from_l1_reorg_during_downtime() {
+    // Setup.
+    let persistence_dir = tempfile::tempdir().unwrap();
+    let config = L1ScraperConfig {
+        persistence_dir: Some(persistence_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let l1_chain: L1Chain = Arc::new(Mutex::new(vec![
+        (l1_block(0, 0), vec![]),
+        (l1_block(1, 1), vec![log_message_to_l2(1)]),
+    ]));
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let mut scraper = L1Scraper::new(
+        config.clone(),
+        l1_provider_client.clone(),
+        base_layer_of_chain(&l1_chain),
+        event_identifiers_to_track(),
+        l1_block(0, 0),
+    )
+    .await
+    .unwrap();
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_add_events_received_with(&[as_event(log_message_to_l2(1))]);
+    l1_chain.lock().unwrap().push((l1_block(2, 2), vec![log_message_to_l2(2)]));
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_add_events_received_with(&[as_event(log_message_to_l2(2))]);
+    drop(scraper);
+
+    // Block 2 is reorged out while the scraper is down.
+    {
+        let mut l1_chain = l1_chain.lock().unwrap();
+        l1_chain[2] = (l1_block(2, 22), vec![log_message_to_l2(3)]);
+        l1_chain.push((l1_block(3, 3), vec![]));
+    }
+
+    // Test.
+    // The fake provider still has the events up to the last scraped L1 block.
+    let mut scraper = L1Scraper::new(
+        config,
+        l1_provider_client.clone(),
+        base_layer_of_chain(&l1_chain),
+        event_identifiers_to_track(),
+        l1_block(0, 0),
+    )
+    .await
+    .unwrap();
+    scraper.initialize().await.unwrap();
+    assert_eq!(*l1_provider_client.initialize_events_received.lock().unwrap(), Some(vec![]));
+    assert_eq!(scraper.last_l1_block_processed, l1_block(2, 2));
+    scraper.send_events_to_l1_provider().await.unwrap();
+
+    // Only the events since the last shutdown are sent, after retracting the reorged out ones.
+    l1_provider_client.assert_retract_events_received_with(&[as_event(log_message_to_l2(2))]);
+    l1_provider_client.assert_add_events_received_with(&[as_event(log_message_to_l2(3))]);
+    assert_eq!(scraper.last_l1_block_processed, l1_block(3, 3));
+}
+
+#[tokio::test]
+async fn persisted_state_is_discarded_when_provider_did_not_restore_it() {
+    // Setup.
+    let persistence_dir = tempfile::tempdir().unwrap();
+    let config = L1ScraperConfig {
+        persistence_dir: Some(persistence_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let l1_chain: L1Chain = Arc::new(Mutex::new(vec![
+        (l1_block(0, 0), vec![]),
+        (l1_block(1, 1), vec![log_message_to_l2(1)]),
+    ]));
+    let mut scraper = L1Scraper::new(
+        config.clone(),
+        Arc::new(FakeL1ProviderClient::default()),
+        base_layer_of_chain(&l1_chain),
+        event_identifiers_to_track(),
+        l1_block(0, 0),
+    )
+    .await
+    .unwrap();
+    scraper.send_events_to_l1_provider().await.unwrap();
+    drop(scraper);
+
+    // Test.
+    // The provider restarted without restoring its state.
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let mut scraper = L1Scraper::new(
+        config,
+        l1_provider_client.clone(),
+        base_layer_of_chain(&l1_chain),
+        event_identifiers_to_track(),
+        l1_block(0, 0),
+    )
+    .await
+    .unwrap();
+    scraper.initialize().await.unwrap();
+
+    // The L1 blocks since the start block are scanned again.
+    assert_eq!(
+        *l1_provider_client.initialize_events_received.lock().unwrap(),
+        Some(vec![as_event(log_message_to_l2(1))])
+    );
+    assert_eq!(scraper.last_l1_block_processed, l1_block(1, 1));
+}
+
 #[test]
 #[ignore = "similar to backlog_happy_flow, only shorter, and sprinkle some start_block/get_txs \
             attempts while its bootstrapping (and assert failure on height), then assert that they \
@@ -6,6 +6,7 @@ pub mod l1_provider;
 pub mod l1_scraper;
 pub mod metrics;
 
+pub(crate) mod persistence;
 pub(crate) mod transaction_manager;
 pub(crate) mod transaction_record;
 
@@ -13,6 +14,7 @@ pub(crate) mod transaction_record;
 pub mod test_utils;
 
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
@@ -96,7 +98,7 @@ impl std::fmt::Display for ProviderState {
     }
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, Validate, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq, Eq)]
 pub struct L1ProviderConfig {
     /// In most cases this can remain None: the provider defaults to using the
     /// LastStateUpdate height at the L1 Height that the L1Scraper is initialized on.
@@ -114,6 +116,13 @@ pub struct L1ProviderConfig {
     pub new_l1_handler_cooldown_seconds: Duration,
     /// When true, the L1 provider operates in dummy mode.
     pub dummy_mode: bool,
+    /// Directory in which the provider persists its state across restarts. If None, it starts
+    /// from scratch on every restart.
+    pub persistence_dir: Option<PathBuf>,
+    /// Number of L2 blocks for which the records of committed transactions are kept. Must cover
+    /// the L2 blocks committed since the L1 blocks the scraper may rescan, otherwise rescanned
+    /// transactions are considered new.
+    pub committed_txs_retention_blocks: u64,
 }
 
 impl Default for L1ProviderConfig {
@@ -125,6 +134,8 @@ impl Default for L1ProviderConfig {
             l1_handler_cancellation_timelock_seconds: Duration::from_secs(5 * 60),
             new_l1_handler_cooldown_seconds: Duration::from_secs(4 * 60 + 5),
             dummy_mode: false,
+            persistence_dir: None,
+            committed_txs_retention_blocks: 20_000,
         }
     }
 }
@@ -169,6 +180,14 @@ impl SerializeConfig for L1ProviderConfig {
                  trivial truthy responses without connecting to actual L1.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "committed_txs_retention_blocks",
+                &self.committed_txs_retention_blocks,
+                "Number of L2 blocks for which the records of committed transactions are kept. \
+                 Must cover the L2 blocks committed since the L1 blocks the scraper may rescan, \
+                 otherwise rescanned transactions are considered new.",
+                ParamPrivacyInput::Public,
+            ),
         ]);
 
         dump.extend(ser_optional_param(
@@ -185,6 +204,14 @@ impl SerializeConfig for L1ProviderConfig {
             "Override height at which the provider should catch up to the bootstrapper.",
             ParamPrivacyInput::Public,
         ));
+        dump.extend(ser_optional_param(
+            &self.persistence_dir,
+            "".into(),
+            "persistence_dir",
+            "Directory in which the L1 provider persists its state across restarts, so that only \
+             the L2 blocks since the last shutdown are synced on startup.",
+            ParamPrivacyInput::Public,
+        ));
         dump
     }
 }
@@ -0,0 +1,249 @@
+//! Persistence of the L1 provider and the L1 scraper across restarts.
+//!
+//! The provider persists its transaction records along with the L2 height it reached and the last
+//! L1 block whose events they reflect. Each change is appended to a log, which is rewritten as a
+//! single entry holding the whole state every `LOG_ENTRIES_PER_COMPACTION` entries and on startup.
+//! The scraper persists the L1 blocks it recently scraped along with their events in a snapshot
+//! file, rewritten only after the provider acknowledged the scraped events, so the provider's state
+//! never lags behind the scraper's.
+//!
+//! On startup, the provider resumes from its persisted height, so the bootstrapper only syncs the
+//! L2 blocks committed since. The scraper resumes from its last scraped L1 block only if the
+//! provider restored the state of that same block, and otherwise rescans from its start block.
+
+#[cfg(test)]
+#[path = "persistence_test.rs"]
+mod persistence_test;
+
+use std::fs::{self, OpenOptions};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::slice;
+
+use apollo_infra_utils::json_lines::{
+    read_records,
+    rewrite_records,
+    write_record,
+    JsonLinesError,
+    JsonLinesResult,
+};
+use apollo_l1_provider_types::Event;
+use indexmap::IndexMap;
+use papyrus_base_layer::L1BlockReference;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionHash;
+
+use crate::transaction_record::TransactionRecord;
+
+pub const L1_PROVIDER_LOG_FILE_NAME: &str = "l1_provider.log";
+pub const L1_SCRAPER_SNAPSHOT_FILE_NAME: &str = "l1_scraper.json";
+/// Number of log entries after which the log is compacted.
+pub const LOG_ENTRIES_PER_COMPACTION: usize = 1000;
+
+pub type PersistenceError = JsonLinesError;
+pub type PersistenceResult<T> = JsonLinesResult<T>;
+
+/// The persisted state of the L1 provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PersistedL1Provider {
+    /// The L2 height the provider was at, all of the blocks below it are reflected in `records`.
+    pub current_height: BlockNumber,
+    /// The last L1 block whose events are reflected in `records`, as reported by the scraper.
+    pub l1_block: Option<L1BlockReference>,
+    /// In order of arrival.
+    pub records: Vec<TransactionRecord>,
+}
+
+/// The persisted state of the L1 scraper.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedL1Scraper {
+    /// The last L1 block of each recent scraping round, with the events scraped in that round,
+    /// oldest first. The last one is the last L1 block processed.
+    pub scraped_history: Vec<(L1BlockReference, Vec<Event>)>,
+}
+
+/// A snapshot stored as JSON in a single file.
+#[derive(Clone, Debug)]
+pub struct SnapshotFile<T> {
+    path: PathBuf,
+    _snapshot: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> SnapshotFile<T> {
+    /// Creates the persistence directory if needed. The file itself is only created on the first
+    /// `store`.
+    pub fn new(persistence_dir: &Path, file_name: &str) -> PersistenceResult<Self> {
+        fs::create_dir_all(persistence_dir)?;
+        Ok(Self { path: persistence_dir.join(file_name), _snapshot: PhantomData })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the stored snapshot, or `None` if nothing was stored yet.
+    pub fn load(&self) -> PersistenceResult<Option<T>> {
+        Ok(read_records(&self.path)?.pop())
+    }
+
+    /// Replaces the stored snapshot.
+    pub fn store(&self, snapshot: &T) -> PersistenceResult<()> {
+        rewrite_records(&self.path, slice::from_ref(snapshot))?;
+        Ok(())
+    }
+}
+
+/// A change to the persisted state of the L1 provider.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct L1ProviderLogEntry {
+    current_height: BlockNumber,
+    l1_block: Option<L1BlockReference>,
+    /// Records that were added or changed, in order of arrival.
+    upserted: Vec<TransactionRecord>,
+    removed: Vec<TransactionHash>,
+}
+
+/// Persists the state of the L1 provider incrementally: each change is appended to a log, and the
+/// log is periodically compacted.
+#[derive(Clone, Debug)]
+pub struct L1ProviderStore {
+    log_path: PathBuf,
+    /// The persisted state, used to compute the changes to append to the log.
+    current_height: Option<BlockNumber>,
+    l1_block: Option<L1BlockReference>,
+    records: IndexMap<TransactionHash, TransactionRecord>,
+    n_log_entries: usize,
+    /// Set when the log may end with a partially written entry, in which case the next change
+    /// rewrites the log instead of being appended to it.
+    rewrite_log: bool,
+}
+
+impl L1ProviderStore {
+    /// Opens the store in the given directory, creating it if needed, and returns the persisted
+    /// state, if any. The log is compacted.
+    pub fn open(persistence_dir: &Path) -> PersistenceResult<(Self, Option<PersistedL1Provider>)> {
+        fs::create_dir_all(persistence_dir)?;
+        let mut store = Self {
+            log_path: persistence_dir.join(L1_PROVIDER_LOG_FILE_NAME),
+            current_height: None,
+            l1_block: None,
+            records: IndexMap::new(),
+            n_log_entries: 0,
+            rewrite_log: true,
+        };
+
+        for entry in read_records(&store.log_path)? {
+            store.apply(entry);
+        }
+        // Also drops a partially written last entry, which must not be appended to.
+        store.compact()?;
+
+        let persisted_state = store.current_height.map(|current_height| PersistedL1Provider {
+            current_height,
+            l1_block: store.l1_block,
+            records: store.records.values().cloned().collect(),
+        });
+        Ok((store, persisted_state))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.log_path
+    }
+
+    /// Drops the persisted state.
+    pub fn clear(&mut self) -> PersistenceResult<()> {
+        self.current_height = None;
+        self.l1_block = None;
+        self.records.clear();
+        self.compact()
+    }
+
+    /// Persists the given state, by appending the changes to the records of `changed_txs` to the
+    /// log. Records are given in order of arrival. The records of `retracted_txs`, whose L1 block
+    /// was reorged out but are kept until the current block attempt ends, are persisted as
+    /// removed: nothing is staged after a restart, so they would be retracted right away.
+    pub fn store(
+        &mut self,
+        current_height: BlockNumber,
+        l1_block: Option<L1BlockReference>,
+        records: &IndexMap<TransactionHash, TransactionRecord>,
+        changed_txs: impl IntoIterator<Item = TransactionHash>,
+        retracted_txs: &[TransactionHash],
+    ) -> PersistenceResult<()> {
+        let mut upserted = Vec::new();
+        let mut removed = Vec::new();
+        for tx_hash in changed_txs {
+            match records.get_full(&tx_hash) {
+                Some((index, _, record)) if !retracted_txs.contains(&tx_hash) => {
+                    let is_persisted = self
+                        .records
+                        .get(&tx_hash)
+                        .is_some_and(|persisted| persisted.eq_persisted(record));
+                    if !is_persisted {
+                        upserted.push((index, record.clone()));
+                    }
+                }
+                _ if self.records.contains_key(&tx_hash) => removed.push(tx_hash),
+                _ => {}
+            }
+        }
+        upserted.sort_by_key(|(index, _)| *index);
+        let upserted = upserted.into_iter().map(|(_, record)| record).collect();
+        let entry = L1ProviderLogEntry { current_height, l1_block, upserted, removed };
+
+        let unchanged = self.current_height == Some(current_height)
+            && self.l1_block == l1_block
+            && entry.upserted.is_empty()
+            && entry.removed.is_empty();
+        if unchanged {
+            return Ok(());
+        }
+
+        self.apply(entry.clone());
+        if self.rewrite_log || self.n_log_entries >= LOG_ENTRIES_PER_COMPACTION {
+            return self.compact();
+        }
+        self.append(&entry).inspect_err(|_| self.rewrite_log = true)
+    }
+
+    fn append(&mut self, entry: &L1ProviderLogEntry) -> PersistenceResult<()> {
+        let mut log_file = OpenOptions::new().create(true).append(true).open(&self.log_path)?;
+        write_record(&mut log_file, entry)?;
+        log_file.sync_data()?;
+        self.n_log_entries += 1;
+        Ok(())
+    }
+
+    /// Applies a log entry to the persisted state.
+    fn apply(&mut self, entry: L1ProviderLogEntry) {
+        self.current_height = Some(entry.current_height);
+        self.l1_block = entry.l1_block;
+        for record in entry.upserted {
+            self.records.insert(record.tx.tx_hash(), record);
+        }
+        for tx_hash in entry.removed {
+            self.records.shift_remove(&tx_hash);
+        }
+    }
+
+    /// Rewrites the log as a single entry holding the persisted state, if any.
+    fn compact(&mut self) -> PersistenceResult<()> {
+        self.rewrite_log = true;
+        let entries: Vec<_> = self
+            .current_height
+            .map(|current_height| L1ProviderLogEntry {
+                current_height,
+                l1_block: self.l1_block,
+                upserted: self.records.values().cloned().collect(),
+                removed: Vec::new(),
+            })
+            .into_iter()
+            .collect();
+        rewrite_records(&self.log_path, &entries)?;
+        self.n_log_entries = entries.len();
+        self.rewrite_log = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use std::fs;
+
+use apollo_l1_provider_types::Event;
+use indexmap::IndexMap;
+use papyrus_base_layer::L1BlockReference;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::transaction::TransactionHash;
+use starknet_api::tx_hash;
+
+use crate::persistence::{
+    L1ProviderStore,
+    PersistedL1Provider,
+    PersistedL1Scraper,
+    SnapshotFile,
+    L1_PROVIDER_LOG_FILE_NAME,
+    L1_SCRAPER_SNAPSHOT_FILE_NAME,
+};
+use crate::test_utils::l1_handler;
+use crate::transaction_record::{TransactionPayload, TransactionRecord};
+
+fn records_by_hash(records: &[TransactionRecord]) -> IndexMap<TransactionHash, TransactionRecord> {
+    records.iter().map(|record| (record.tx.tx_hash(), record.clone())).collect()
+}
+
+fn tx_hashes(records: &[TransactionRecord]) -> Vec<TransactionHash> {
+    records.iter().map(|record| record.tx.tx_hash()).collect()
+}
+
+#[test]
+fn provider_store_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let persistence_dir = dir.path().join("nested");
+    let (mut store, persisted_state) = L1ProviderStore::open(&persistence_dir).unwrap();
+    assert_eq!(persisted_state, None);
+
+    let mut committed = TransactionRecord::new(tx_hash!(1).into());
+    committed.mark_committed(BlockNumber(0));
+    let uncommitted = TransactionRecord::new(TransactionPayload::Full {
+        tx: l1_handler(2),
+        created_at_block_timestamp: BlockTimestamp(3),
+    });
+    let l1_block = Some(L1BlockReference { number: 7, hash: [7; 32] });
+    let records = [committed, uncommitted.clone()];
+    store
+        .store(BlockNumber(4), l1_block, &records_by_hash(&records), tx_hashes(&records), &[])
+        .unwrap();
+
+    // Later changes are appended to the log.
+    let mut rejected = uncommitted;
+    rejected.mark_rejected();
+    let added = TransactionRecord::new(tx_hash!(3).into());
+    let records = [rejected, added];
+    let changed_txs = [vec![tx_hash!(1)], tx_hashes(&records)].concat();
+    store.store(BlockNumber(5), l1_block, &records_by_hash(&records), changed_txs, &[]).unwrap();
+    let log_path = persistence_dir.join(L1_PROVIDER_LOG_FILE_NAME);
+    assert_eq!(fs::read_to_string(&log_path).unwrap().lines().count(), 2);
+
+    let expected_state =
+        PersistedL1Provider { current_height: BlockNumber(5), l1_block, records: records.to_vec() };
+    let (_store, persisted_state) = L1ProviderStore::open(&persistence_dir).unwrap();
+    assert_eq!(persisted_state, Some(expected_state.clone()));
+
+    // The log was compacted on open.
+    assert_eq!(fs::read_to_string(&log_path).unwrap().lines().count(), 1);
+    let (_store, persisted_state) = L1ProviderStore::open(&persistence_dir).unwrap();
+    assert_eq!(persisted_state, Some(expected_state));
+}
+
+#[test]
+fn provider_store_only_persists_changed_txs() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut store, _) = L1ProviderStore::open(dir.path()).unwrap();
+    let records = [TransactionRecord::new(tx_hash!(1).into())];
+    store.store(BlockNumber(1), None, &records_by_hash(&records), [], &[]).unwrap();
+
+    let (_store, persisted_state) = L1ProviderStore::open(dir.path()).unwrap();
+    let expected_state =
+        PersistedL1Provider { current_height: BlockNumber(1), l1_block: None, records: vec![] };
+    assert_eq!(persisted_state, Some(expected_state));
+}
+
+#[test]
+fn scraper_snapshot_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let store =
+        SnapshotFile::<PersistedL1Scraper>::new(dir.path(), L1_SCRAPER_SNAPSHOT_FILE_NAME).unwrap();
+    assert_eq!(store.load().unwrap(), None);
+
+    let l1_block = L1BlockReference { number: 7, hash: [7; 32] };
+    let events = vec![
+        Event::L1HandlerTransaction { l1_handler_tx: l1_handler(1), timestamp: BlockTimestamp(1) },
+        Event::TransactionCancellationStarted {
+            tx_hash: tx_hash!(1),
+            cancellation_request_timestamp: BlockTimestamp(2),
+        },
+        Event::TransactionConsumed(tx_hash!(1)),
+    ];
+    let snapshot = PersistedL1Scraper { scraped_history: vec![(l1_block, events)] };
+    store.store(&snapshot).unwrap();
+    assert_eq!(store.load().unwrap(), Some(snapshot));
+}
@@ -16,6 +16,7 @@ use apollo_time::time::{Clock, DefaultClock};
 use async_trait::async_trait;
 use indexmap::{IndexMap, IndexSet};
 use itertools::{chain, Itertools};
+use papyrus_base_layer::L1BlockReference;
 use pretty_assertions::assert_eq;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::executable_transaction::{
@@ -76,6 +77,8 @@ impl From<L1ProviderContent> for L1Provider {
             current_height: content.current_height.unwrap_or_default(),
             start_height: content.current_height.unwrap_or_default(),
             clock: content.clock.unwrap_or_else(|| Arc::new(DefaultClock)),
+            store: None,
+            l1_block: None,
         }
     }
 }
@@ -191,7 +194,7 @@ impl L1ProviderContentBuilder {
 
         let now = self.clock.as_ref().unwrap().unix_now();
         let cancellation_timelock =
-            self.config.unwrap().l1_handler_cancellation_timelock_seconds.as_secs();
+            self.config.as_ref().unwrap().l1_handler_cancellation_timelock_seconds.as_secs();
         // If a tx's timestamp is OLDER than the timelock, then it's timeout is expired and it's
         // considered fully cancelled on L2.
         let cancellation_expired = now - (cancellation_timelock + 1);
@@ -223,9 +226,9 @@ impl L1ProviderContentBuilder {
     }
 
     pub fn build(mut self) -> L1ProviderContent {
-        if let Some(config) = self.config {
+        if let Some(config) = &self.config {
             self.tx_manager_content_builder =
-                self.tx_manager_content_builder.with_config(config.into());
+                self.tx_manager_content_builder.with_config(config.clone().into());
         }
 
         L1ProviderContent {
@@ -246,7 +249,7 @@ impl L1ProviderContentBuilder {
         self.clock = self.clock.take().or_else(|| Some(Arc::new(FakeClock::new(base_timestamp))));
 
         let nonzero_timelock = Duration::from_secs(1);
-        let config = self.config.unwrap_or_default();
+        let config = self.config.take().unwrap_or_default();
         self.with_config(L1ProviderConfig {
             new_l1_handler_cooldown_seconds: nonzero_timelock,
             l1_handler_cancellation_timelock_seconds: nonzero_timelock,
@@ -334,7 +337,7 @@ impl From<TransactionManagerContent> for TransactionManager {
 
         for (tx_hash, committed_tx) in committed {
             let mut record = TransactionRecord::from(committed_tx);
-            record.mark_committed();
+            record.mark_committed(BlockNumber(0));
             assert_eq!(records.insert(tx_hash, record), None);
         }
 
@@ -464,7 +467,11 @@ pub struct FakeL1ProviderClient {
     // Interior mutability needed since this is modifying during client API calls, which are all
     // immutable.
     pub events_received: Mutex<Vec<Event>>,
+    pub events_retracted: Mutex<Vec<Event>>,
     pub commit_blocks_received: Mutex<Vec<CommitBlockBacklog>>,
+    pub initialize_events_received: Mutex<Option<Vec<Event>>>,
+    /// Set by the scraper, and returned to it after its restart, as if the provider restored it.
+    pub scraped_l1_block: Mutex<Option<L1BlockReference>>,
 }
 
 impl FakeL1ProviderClient {
@@ -483,6 +490,12 @@ impl FakeL1ProviderClient {
         let events_received = mem::take(&mut *self.events_received.lock().unwrap());
         assert_eq!(events_received, expected);
     }
+
+    #[track_caller]
+    pub fn assert_retract_events_received_with(&self, expected: &[Event]) {
+        let events_retracted = mem::take(&mut *self.events_retracted.lock().unwrap());
+        assert_eq!(events_retracted, expected);
+    }
 }
 
 #[async_trait]
@@ -508,6 +521,11 @@ impl L1ProviderClient for FakeL1ProviderClient {
         Ok(())
     }
 
+    async fn retract_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
+        self.events_retracted.lock().unwrap().extend(events);
+        Ok(())
+    }
+
     async fn commit_block(
         &self,
         l1_handler_tx_hashes: IndexSet<TransactionHash>,
@@ -529,8 +547,18 @@ impl L1ProviderClient for FakeL1ProviderClient {
         todo!()
     }
 
-    async fn initialize(&self, _events: Vec<Event>) -> L1ProviderClientResult<()> {
-        todo!()
+    async fn initialize(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
+        *self.initialize_events_received.lock().unwrap() = Some(events);
+        Ok(())
+    }
+
+    async fn set_scraped_l1_block(&self, l1_block: L1BlockReference) -> L1ProviderClientResult<()> {
+        *self.scraped_l1_block.lock().unwrap() = Some(l1_block);
+        Ok(())
+    }
+
+    async fn get_scraped_l1_block(&self) -> L1ProviderClientResult<Option<L1BlockReference>> {
+        Ok(*self.scraped_l1_block.lock().unwrap())
     }
 
     async fn get_l1_provider_snapshot(&self) -> L1ProviderClientResult<L1ProviderSnapshot> {
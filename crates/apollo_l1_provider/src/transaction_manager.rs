@@ -1,10 +1,12 @@
 use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
+use std::mem;
 use std::ops::{Deref, Sub};
 use std::time::Duration;
 
 use apollo_l1_provider_types::{InvalidValidationStatus, ValidationStatus};
-use starknet_api::block::BlockTimestamp;
+use indexmap::IndexSet;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
 
@@ -16,7 +18,7 @@ use crate::transaction_record::{
     TransactionState,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct TransactionManager {
     /// Storage of all l1 handler transactions --- keeps transactions until they can be safely
     /// removed, like when they are consumed on L1, or fully cancelled on L1.
@@ -26,7 +28,8 @@ pub struct TransactionManager {
     /// identical timestamps, also at any point the staged transactions are a prefix of the
     /// structure under this order.
     /// Invariant: contains all hashes of transactions that are proposable, and only them.
-    /// Invarariant 2: Once removed from this index, a transaction will never be proposed again.
+    /// Invarariant 2: Once removed from this index, a transaction will never be proposed again,
+    /// unless the cancellation request that removed it is retracted due to an L1 reorg.
     proposable_index: BTreeMap<BlockTimestamp, Vec<TransactionHash>>,
     /// Generation counter used to prevent double usage of an l1 handler transaction in a single
     /// block.
@@ -36,6 +39,12 @@ pub struct TransactionManager {
     /// all tagged transactions from the previous block attempt.
     // TODO(Gilad): remove "for rejected" from name when uncommitted is migrated to records DS.
     current_staging_epoch: StagingEpoch,
+    /// Transactions whose L1 block was reorged out while they were staged. They are retracted
+    /// once the current block attempt ends, unless they are committed in it.
+    deferred_retractions: Vec<TransactionHash>,
+    /// Transactions whose records were added, changed, removed or (un)deferred for retraction
+    /// since the last `take_changed_txs`, so that only they are persisted.
+    changed_txs: IndexSet<TransactionHash>,
 }
 
 impl TransactionManager {
@@ -51,11 +60,14 @@ impl TransactionManager {
             records: Default::default(),
             proposable_index: Default::default(),
             current_staging_epoch: StagingEpoch::new(),
+            deferred_retractions: Default::default(),
+            changed_txs: Default::default(),
         }
     }
 
     pub fn start_block(&mut self) {
         self.rollback_staging();
+        self.apply_deferred_retractions();
     }
 
     pub fn get_txs(&mut self, n_txs: usize, now: u64) -> Vec<L1HandlerTransaction> {
@@ -126,12 +138,13 @@ impl TransactionManager {
         &mut self,
         committed_txs: &[TransactionHash],
         rejected_txs: &[TransactionHash],
+        height: BlockNumber,
     ) {
         self.rollback_staging();
 
         for &tx_hash in committed_txs {
             self.create_record_if_not_exist(tx_hash);
-            self.with_record(tx_hash, |r| r.mark_committed()).unwrap();
+            self.with_record(tx_hash, |r| r.mark_committed(height)).unwrap();
         }
         for &tx_hash in rejected_txs {
             self.with_record(tx_hash, |r| r.mark_rejected()).expect(
@@ -139,6 +152,7 @@ impl TransactionManager {
                  unexpectedly.",
             );
         }
+        self.apply_deferred_retractions();
     }
 
     /// Adds a transaction to the transaction manager, return true if the transaction was
@@ -148,6 +162,9 @@ impl TransactionManager {
     // committed txs storage, to account for commit-before-add tx scenario.
     pub fn add_tx(&mut self, tx: L1HandlerTransaction, block_timestamp: BlockTimestamp) -> bool {
         let tx_hash = tx.tx_hash;
+        // A staged transaction whose L1 block was reorged out may be re-added by the rescan of the
+        // new L1 chain, in which case it must no longer be retracted when the block ends.
+        self.deferred_retractions.retain(|&deferred_tx_hash| deferred_tx_hash != tx_hash);
         let is_new_record = self.create_record_if_not_exist(tx_hash);
         self.with_record(tx_hash, move |record| {
             record.tx.set(tx, block_timestamp);
@@ -166,6 +183,77 @@ impl TransactionManager {
         )
     }
 
+    /// Removes a transaction whose L1 block was reorged out, unless it was already committed on L2.
+    /// Returns true if the transaction was removed.
+    pub fn retract_tx(&mut self, tx_hash: TransactionHash) -> bool {
+        if !self.exists(tx_hash) || self.is_committed(tx_hash) {
+            return false;
+        }
+        assert!(!self.is_staged(tx_hash), "Staged transaction {tx_hash} can't be retracted.");
+        self.remove_from_index(tx_hash);
+        self.records.remove(tx_hash);
+        self.changed_txs.insert(tx_hash);
+        true
+    }
+
+    /// Retracts a staged transaction whose L1 block was reorged out once the current block attempt
+    /// ends, as the block may still be committed with it.
+    pub fn defer_retraction(&mut self, tx_hash: TransactionHash) {
+        self.deferred_retractions.push(tx_hash);
+        self.changed_txs.insert(tx_hash);
+    }
+
+    /// Returns the staged transactions that are retracted once the current block attempt ends.
+    pub fn deferred_retractions(&self) -> &[TransactionHash] {
+        &self.deferred_retractions
+    }
+
+    /// Undoes a cancellation request whose L1 block was reorged out, returns true if the
+    /// transaction had a cancellation request with the given timestamp.
+    pub fn retract_cancellation_request(
+        &mut self,
+        tx_hash: TransactionHash,
+        block_timestamp: BlockTimestamp,
+    ) -> bool {
+        self.with_record(tx_hash, |r| r.retract_cancellation_request(block_timestamp))
+            .unwrap_or(false)
+    }
+
+    /// Removes the records of transactions committed below the given height, returns the number
+    /// of removed records.
+    pub fn prune_committed(&mut self, height: BlockNumber) -> usize {
+        let n_records = self.records.len();
+        // Committed transactions are not proposable, so they are not indexed.
+        self.records.retain(|record| {
+            let keep = !record.is_committed_below(height);
+            if !keep {
+                self.changed_txs.insert(record.tx.tx_hash());
+            }
+            keep
+        });
+        n_records - self.records.len()
+    }
+
+    /// Restores persisted records, given in order of arrival, and indexes the proposable ones.
+    pub fn restore_records(&mut self, records: Vec<TransactionRecord>) {
+        for record in records {
+            let tx_hash = record.tx.tx_hash();
+            assert!(self.records.insert(tx_hash, record), "Duplicate persisted record {tx_hash}.");
+            self.maintain_index(tx_hash);
+        }
+    }
+
+    /// Returns the transactions whose records changed since the last call, see `changed_txs`.
+    pub fn take_changed_txs(&mut self) -> IndexSet<TransactionHash> {
+        mem::take(&mut self.changed_txs)
+    }
+
+    pub fn is_staged(&self, tx_hash: TransactionHash) -> bool {
+        self.records
+            .get(&tx_hash)
+            .is_some_and(|record| record.is_staged(self.current_staging_epoch))
+    }
+
     pub fn is_committed(&self, tx_hash: TransactionHash) -> bool {
         self.records.get(&tx_hash).is_some_and(|record| record.is_committed())
     }
@@ -212,24 +300,27 @@ impl TransactionManager {
     {
         let record = self.records.get_mut_unchecked(hash)?;
         let result = f(record);
+        self.changed_txs.insert(hash);
         self.maintain_index(hash);
         Some(result)
     }
 
     fn create_record_if_not_exist(&mut self, hash: TransactionHash) -> bool {
+        self.changed_txs.insert(hash);
         self.records.insert(hash, TransactionRecord::new(hash.into()))
     }
 
-    fn is_staged(&self, tx_hash: TransactionHash) -> bool {
-        self.records
-            .get(&tx_hash)
-            .is_some_and(|record| record.is_staged(self.current_staging_epoch))
-    }
-
     fn rollback_staging(&mut self) {
         self.current_staging_epoch = self.current_staging_epoch.increment();
     }
 
+    // Must be called after staging is rolled back, so that the transactions are no longer staged.
+    fn apply_deferred_retractions(&mut self) {
+        for tx_hash in mem::take(&mut self.deferred_retractions) {
+            self.retract_tx(tx_hash);
+        }
+    }
+
     fn maintain_index(&mut self, hash: TransactionHash) {
         if let Some(record) = self.records.get(&hash) {
             let TransactionPayload::Full { created_at_block_timestamp: created_at, .. } = record.tx
@@ -247,20 +338,31 @@ impl TransactionManager {
                     tx_hashes.push(tx_hash);
                 }
             } else {
-                // Remove from the vec for this timestamp, and drop the entry if it becomes empty.
-                match self.proposable_index.entry(created_at) {
-                    Entry::Occupied(mut entry) => {
-                        let tx_hashes = entry.get_mut();
-                        if let Some(index_in_vec) = tx_hashes.iter().position(|&h| h == tx_hash) {
-                            tx_hashes.remove(index_in_vec);
-                            if tx_hashes.is_empty() {
-                                entry.remove();
-                            }
-                        }
+                self.remove_from_index(tx_hash);
+            }
+        }
+    }
+
+    fn remove_from_index(&mut self, tx_hash: TransactionHash) {
+        let Some(TransactionPayload::Full { created_at_block_timestamp: created_at, .. }) =
+            self.records.get(&tx_hash).map(|record| &record.tx)
+        else {
+            // Not scraped, so it isn't indexed.
+            return;
+        };
+
+        // Remove from the vec for this timestamp, and drop the entry if it becomes empty.
+        match self.proposable_index.entry(*created_at) {
+            Entry::Occupied(mut entry) => {
+                let tx_hashes = entry.get_mut();
+                if let Some(index_in_vec) = tx_hashes.iter().position(|&h| h == tx_hash) {
+                    tx_hashes.remove(index_in_vec);
+                    if tx_hashes.is_empty() {
+                        entry.remove();
                     }
-                    Entry::Vacant(_) => {}
                 }
             }
+            Entry::Vacant(_) => {}
         }
     }
 
@@ -271,10 +373,30 @@ impl TransactionManager {
         current_epoch: StagingEpoch,
         config: TransactionManagerConfig,
     ) -> Self {
-        Self { records, proposable_index, current_staging_epoch: current_epoch, config }
+        Self {
+            records,
+            proposable_index,
+            current_staging_epoch: current_epoch,
+            config,
+            deferred_retractions: Default::default(),
+            changed_txs: Default::default(),
+        }
     }
 }
 
+// The changed transactions are bookkeeping for persistence, not part of the state.
+impl PartialEq for TransactionManager {
+    fn eq(&self, other: &Self) -> bool {
+        self.records == other.records
+            && self.config == other.config
+            && self.proposable_index == other.proposable_index
+            && self.current_staging_epoch == other.current_staging_epoch
+            && self.deferred_retractions == other.deferred_retractions
+    }
+}
+
+impl Eq for TransactionManager {}
+
 impl Default for TransactionManager {
     // Note that new will init the epoch at 1, not 0, this is because a 0 epoch in the transaction
     // manager will make new transactions automatically staged by default in the first block.
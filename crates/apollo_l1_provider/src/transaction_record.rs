@@ -3,7 +3,8 @@ use std::time::Duration;
 
 use indexmap::map::Entry;
 use indexmap::IndexMap;
-use starknet_api::block::BlockTimestamp;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
 use tracing::{info, warn};
@@ -12,7 +13,7 @@ use crate::transaction_manager::StagingEpoch;
 
 /// An entity that wraps a committed L1 handler transaction and all information and decisions made
 /// on it ("Domain Entity"). Uses lifecycle metadata to maintain the state of the transaction.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TransactionRecord {
     pub tx: TransactionPayload,
 
@@ -26,7 +27,12 @@ pub struct TransactionRecord {
     committed: bool,
     rejected: bool,
     cancellation_requested_at: Option<BlockTimestamp>,
+    /// The L2 height of the block the transaction was committed in, used to prune the records of
+    /// transactions committed long ago.
+    committed_at_height: Option<BlockNumber>,
     /// A record is staged iff its epoch equals the record owner's (tx manager) epoch counter.
+    // Not persisted: staging is reset on restart, along with the owner's epoch counter.
+    #[serde(skip)]
     staged_epoch: StagingEpoch,
 }
 
@@ -46,7 +52,7 @@ impl TransactionRecord {
         }
     }
 
-    pub fn mark_committed(&mut self) {
+    pub fn mark_committed(&mut self, height: BlockNumber) {
         // Can't return error because committing only part of a block leaves the provider in an
         // undetermined state.
         assert!(
@@ -56,6 +62,31 @@ impl TransactionRecord {
         );
         self.state = TransactionState::Committed;
         self.committed = true;
+        self.committed_at_height = Some(height);
+    }
+
+    /// Returns whether the transaction was committed in a block below the given height.
+    pub fn is_committed_below(&self, height: BlockNumber) -> bool {
+        self.committed_at_height.is_some_and(|committed_at_height| committed_at_height < height)
+    }
+
+    /// Compares the persisted fields of the records, ignoring whether they are staged.
+    pub fn eq_persisted(&self, other: &Self) -> bool {
+        let Self {
+            tx,
+            state,
+            committed,
+            rejected,
+            cancellation_requested_at,
+            committed_at_height,
+            staged_epoch: _,
+        } = self;
+        *tx == other.tx
+            && *state == other.state
+            && *committed == other.committed
+            && *rejected == other.rejected
+            && *cancellation_requested_at == other.cancellation_requested_at
+            && *committed_at_height == other.committed_at_height
     }
 
     // Note: double reject not currently checked.
@@ -99,6 +130,26 @@ impl TransactionRecord {
         }
     }
 
+    /// Undo a cancellation request whose L1 block was reorged out, given the block timestamp it
+    /// was requested at. Returns whether the cancellation request was retracted.
+    pub fn retract_cancellation_request(&mut self, timestamp: BlockTimestamp) -> bool {
+        if self.cancellation_requested_at != Some(timestamp) {
+            // Only the first cancellation request is recorded.
+            return false;
+        }
+        self.cancellation_requested_at = None;
+        // Committed transactions are not affected by cancellation requests.
+        if !self.is_committed() {
+            info!(
+                "Retracting the cancellation request of L1 handler transaction {}.",
+                self.tx.tx_hash()
+            );
+            self.state =
+                if self.rejected { TransactionState::Rejected } else { TransactionState::Pending };
+        }
+        true
+    }
+
     /// Try to stage an l1 handler transaction, which means that we allow to include it in the
     /// current proposed or validated block. If already included in a block, this test will return
     /// false, thus preventing double-inclusion in the block. Staging is reset at the start of every
@@ -167,7 +218,7 @@ impl From<TransactionPayload> for TransactionRecord {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionPayload {
     HashOnly(TransactionHash),
     Full { tx: L1HandlerTransaction, created_at_block_timestamp: BlockTimestamp },
@@ -198,7 +249,7 @@ impl From<TransactionHash> for TransactionPayload {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TransactionState {
     CancellationStartedOnL2,
     CancelledOnL2,
@@ -218,6 +269,17 @@ impl Records {
         self.0.get_mut(&hash)
     }
 
+    /// Removes a record while preserving the arrival order of the rest.
+    pub fn remove(&mut self, hash: TransactionHash) -> Option<TransactionRecord> {
+        self.0.shift_remove(&hash)
+    }
+
+    /// Removes the records that don't satisfy the predicate, while preserving the arrival order of
+    /// the rest.
+    pub fn retain(&mut self, mut keep: impl FnMut(&TransactionRecord) -> bool) {
+        self.0.retain(|_, record| keep(record));
+    }
+
     pub fn insert(&mut self, hash: TransactionHash, record: TransactionRecord) -> bool {
         match self.0.entry(hash) {
             Entry::Occupied(_) => false,
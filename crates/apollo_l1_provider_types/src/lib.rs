@@ -11,7 +11,7 @@ use async_trait::async_trait;
 use indexmap::IndexSet;
 #[cfg(any(feature = "testing", test))]
 use mockall::automock;
-use papyrus_base_layer::{EventData, L1Event};
+use papyrus_base_layer::{EventData, L1BlockReference, L1Event};
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::ChainId;
@@ -63,6 +63,9 @@ pub enum L1ProviderRequest {
         height: BlockNumber,
     },
     Initialize(Vec<Event>),
+    RetractEvents(Vec<Event>),
+    SetScrapedL1Block(L1BlockReference),
+    GetScrapedL1Block,
     StartBlock {
         state: SessionState,
         height: BlockNumber,
@@ -81,6 +84,9 @@ pub enum L1ProviderResponse {
     CommitBlock(L1ProviderResult<()>),
     GetTransactions(L1ProviderResult<Vec<L1HandlerTransaction>>),
     Initialize(L1ProviderResult<()>),
+    RetractEvents(L1ProviderResult<()>),
+    SetScrapedL1Block(L1ProviderResult<()>),
+    GetScrapedL1Block(L1ProviderResult<Option<L1BlockReference>>),
     StartBlock(L1ProviderResult<()>),
     Validate(L1ProviderResult<ValidationStatus>),
     GetL1ProviderSnapshot(L1ProviderResult<L1ProviderSnapshot>),
@@ -118,7 +124,15 @@ pub trait L1ProviderClient: Send + Sync {
     ) -> L1ProviderClientResult<()>;
 
     async fn add_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
+    /// Undoes events that were added from L1 blocks that have since been reorged out.
+    async fn retract_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
     async fn initialize(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
+    /// Records that the events of all L1 blocks up to the given one were added, so that the
+    /// provider persists it along with its state.
+    async fn set_scraped_l1_block(&self, l1_block: L1BlockReference) -> L1ProviderClientResult<()>;
+    /// Returns the last scraped L1 block whose events the provider has, either restored on startup
+    /// or set since.
+    async fn get_scraped_l1_block(&self) -> L1ProviderClientResult<Option<L1BlockReference>>;
     async fn get_l1_provider_snapshot(&self) -> L1ProviderClientResult<L1ProviderSnapshot>;
 }
 
@@ -203,6 +217,18 @@ where
         )
     }
 
+    #[instrument(skip(self))]
+    async fn retract_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
+        let request = L1ProviderRequest::RetractEvents(events);
+        handle_all_response_variants!(
+            L1ProviderResponse,
+            RetractEvents,
+            L1ProviderClientError,
+            L1ProviderError,
+            Direct
+        )
+    }
+
     async fn initialize(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
         let request = L1ProviderRequest::Initialize(events);
         handle_all_response_variants!(
@@ -214,6 +240,29 @@ where
         )
     }
 
+    #[instrument(skip(self))]
+    async fn set_scraped_l1_block(&self, l1_block: L1BlockReference) -> L1ProviderClientResult<()> {
+        let request = L1ProviderRequest::SetScrapedL1Block(l1_block);
+        handle_all_response_variants!(
+            L1ProviderResponse,
+            SetScrapedL1Block,
+            L1ProviderClientError,
+            L1ProviderError,
+            Direct
+        )
+    }
+
+    async fn get_scraped_l1_block(&self) -> L1ProviderClientResult<Option<L1BlockReference>> {
+        let request = L1ProviderRequest::GetScrapedL1Block;
+        handle_all_response_variants!(
+            L1ProviderResponse,
+            GetScrapedL1Block,
+            L1ProviderClientError,
+            L1ProviderError,
+            Direct
+        )
+    }
+
     async fn get_l1_provider_snapshot(&self) -> L1ProviderClientResult<L1ProviderSnapshot> {
         let request = L1ProviderRequest::GetL1ProviderSnapshot;
         handle_all_response_variants!(
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use apollo_infra::component_definitions::{ComponentRequestHandler, ComponentStarter};
@@ -9,18 +10,25 @@ use apollo_mempool_types::communication::{
     MempoolResponse,
 };
 use apollo_mempool_types::errors::MempoolError;
-use apollo_mempool_types::mempool_types::{CommitBlockArgs, MempoolResult, MempoolSnapshot};
+use apollo_mempool_types::mempool_types::{
+    CommitBlockArgs,
+    MempoolResult,
+    MempoolSnapshot,
+    MempoolTransactionStatus,
+};
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_time::time::DefaultClock;
 use async_trait::async_trait;
 use starknet_api::block::GasPrice;
 use starknet_api::core::ContractAddress;
 use starknet_api::rpc_transaction::InternalRpcTransaction;
-use tracing::warn;
+use starknet_api::transaction::TransactionHash;
+use tracing::{info, warn};
 
 use crate::config::MempoolConfig;
 use crate::mempool::Mempool;
 use crate::metrics::register_metrics;
+use crate::persistence::{live_entries, JournalEntry, MempoolJournal, SharedAccountNonceReader};
 
 pub type LocalMempoolServer =
     LocalComponentServer<MempoolCommunicationWrapper, MempoolRequest, MempoolResponse>;
@@ -29,10 +37,12 @@ pub type RemoteMempoolServer = RemoteComponentServer<MempoolRequest, MempoolResp
 pub fn create_mempool(
     config: MempoolConfig,
     mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
+    account_nonce_reader: Option<SharedAccountNonceReader>,
 ) -> MempoolCommunicationWrapper {
     MempoolCommunicationWrapper::new(
         Mempool::new(config, Arc::new(DefaultClock)),
         mempool_p2p_propagator_client,
+        account_nonce_reader,
     )
 }
 
@@ -40,14 +50,53 @@ pub fn create_mempool(
 pub struct MempoolCommunicationWrapper {
     mempool: Mempool,
     mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
+    // Used to revalidate the transactions restored after a restart.
+    account_nonce_reader: Option<SharedAccountNonceReader>,
 }
 
 impl MempoolCommunicationWrapper {
     pub fn new(
         mempool: Mempool,
         mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
+        account_nonce_reader: Option<SharedAccountNonceReader>,
     ) -> Self {
-        MempoolCommunicationWrapper { mempool, mempool_p2p_propagator_client }
+        MempoolCommunicationWrapper { mempool, mempool_p2p_propagator_client, account_nonce_reader }
+    }
+
+    /// Restores the transactions persisted before the last shutdown, if persistence is configured.
+    pub(crate) async fn restore_persisted_txs(&mut self) {
+        let Some(path) = self.mempool.config().persistence_path.clone() else {
+            return;
+        };
+        let (journal, entries) = MempoolJournal::open(&path)
+            .unwrap_or_else(|err| panic!("Failed to open the mempool journal at {path:?}: {err}"));
+        info!("Restoring the mempool from {} journal entries at {path:?}.", entries.len());
+        // Skip the transactions removed before the shutdown, to avoid fetching their nonces.
+        let entries = live_entries(entries);
+
+        let mut account_nonces = HashMap::new();
+        if let Some(account_nonce_reader) = &self.account_nonce_reader {
+            for entry in &entries {
+                let JournalEntry::AddTransaction { args, .. } = entry else {
+                    continue;
+                };
+                let address = args.account_state.address;
+                if account_nonces.contains_key(&address) {
+                    continue;
+                }
+                match account_nonce_reader.get_account_nonce(address).await {
+                    Ok(nonce) => {
+                        account_nonces.insert(address, nonce);
+                    }
+                    Err(err) => warn!(
+                        "Failed to get the nonce of {address}, restoring its transactions with \
+                         their persisted nonce: {err}"
+                    ),
+                }
+            }
+        }
+
+        self.mempool.restore(journal, entries, &account_nonces);
     }
 
     async fn send_tx_to_p2p(
@@ -110,6 +159,21 @@ impl MempoolCommunicationWrapper {
     fn mempool_snapshot(&self) -> MempoolResult<MempoolSnapshot> {
         self.mempool.mempool_snapshot()
     }
+
+    fn get_tx_by_hash(&self, tx_hash: TransactionHash) -> MempoolResult<InternalRpcTransaction> {
+        self.mempool.get_tx_by_hash(tx_hash).cloned()
+    }
+
+    fn get_account_txs(
+        &self,
+        account_address: ContractAddress,
+    ) -> MempoolResult<Vec<InternalRpcTransaction>> {
+        Ok(self.mempool.get_account_txs(account_address).into_iter().cloned().collect())
+    }
+
+    fn get_tx_status(&self, tx_hash: TransactionHash) -> MempoolResult<MempoolTransactionStatus> {
+        self.mempool.get_tx_status(tx_hash)
+    }
 }
 
 #[async_trait]
@@ -136,6 +200,15 @@ impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommuni
             MempoolRequest::GetMempoolSnapshot() => {
                 MempoolResponse::GetMempoolSnapshot(self.mempool_snapshot())
             }
+            MempoolRequest::GetTransactionByHash(tx_hash) => {
+                MempoolResponse::GetTransactionByHash(self.get_tx_by_hash(tx_hash))
+            }
+            MempoolRequest::GetAccountTransactions(account_address) => {
+                MempoolResponse::GetAccountTransactions(self.get_account_txs(account_address))
+            }
+            MempoolRequest::GetTransactionStatus(tx_hash) => {
+                MempoolResponse::GetTransactionStatus(self.get_tx_status(tx_hash))
+            }
         }
     }
 }
@@ -144,5 +217,6 @@ impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommuni
 impl ComponentStarter for MempoolCommunicationWrapper {
     async fn start(&mut self) {
         register_metrics();
+        self.restore_persisted_txs().await;
     }
 }
@@ -1,8 +1,9 @@
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use apollo_config::converters::deserialize_seconds_to_duration;
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -29,6 +30,30 @@ pub struct MempoolConfig {
     pub committed_nonce_retention_block_count: usize,
     // The maximum size of the mempool, in bytes.
     pub capacity_in_bytes: u64,
+    // The maximum number of transactions of a single account held in the mempool. If None, not
+    // limited.
+    pub max_txs_per_account: Option<usize>,
+    // The maximum total size of the transactions of a single account held in the mempool, in
+    // bytes. If None, not limited.
+    pub max_bytes_per_account: Option<u64>,
+    // The maximum number of nonces a transaction may be ahead of its account nonce. If None, not
+    // limited.
+    pub max_future_nonces: Option<u64>,
+    // Determines which transactions are evicted to make space when the mempool is full.
+    pub eviction_policy: EvictionPolicy,
+    // Time-to-live for suspended transactions (i.e., of accounts with a nonce gap), in seconds,
+    // counted from the time they were suspended.
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub suspended_transaction_ttl: Duration,
+    // The maximum size of the suspended transactions, in bytes. Counts towards the capacity of the
+    // mempool.
+    pub suspended_capacity_in_bytes: u64,
+    // Number of latest transactions removed from the mempool before being included in a block
+    // (e.g., evicted or expired) whose status is retained, to be reported by status queries.
+    pub removed_tx_status_retention_count: usize,
+    // Path of the journal which persists the mempool's transactions across restarts. If None, they
+    // aren't persisted.
+    pub persistence_path: Option<PathBuf>,
 }
 
 impl Default for MempoolConfig {
@@ -41,13 +66,21 @@ impl Default for MempoolConfig {
             declare_delay: Duration::from_secs(1),
             committed_nonce_retention_block_count: 100,
             capacity_in_bytes: 1 << 30, // 1GB.
+            max_txs_per_account: None,
+            max_bytes_per_account: None,
+            max_future_nonces: None,
+            eviction_policy: EvictionPolicy::GapOnly,
+            suspended_transaction_ttl: Duration::from_secs(60), // 1 minute.
+            suspended_capacity_in_bytes: 1 << 28,               // 256MB.
+            removed_tx_status_retention_count: 10000,
+            persistence_path: None,
         }
     }
 }
 
 impl SerializeConfig for MempoolConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let mut config = BTreeMap::from_iter([
             ser_param(
                 "enable_fee_escalation",
                 &self.enable_fee_escalation,
@@ -93,6 +126,84 @@ impl SerializeConfig for MempoolConfig {
                 "Maximum size of the mempool, in bytes.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+            ser_param(
+                "eviction_policy",
+                &self.eviction_policy,
+                "Determines which transactions are evicted to make space when the mempool is \
+                 full. One of: GapOnly, LowestTip, OldestFirst, LargestAccount.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "suspended_transaction_ttl",
+                &self.suspended_transaction_ttl.as_secs(),
+                "Time-to-live for suspended transactions (i.e., of accounts with a nonce gap), in \
+                 seconds, counted from the time they were suspended.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "suspended_capacity_in_bytes",
+                &self.suspended_capacity_in_bytes,
+                "Maximum size of the suspended transactions, in bytes.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "removed_tx_status_retention_count",
+                &self.removed_tx_status_retention_count,
+                "Number of latest transactions removed from the mempool before being included in \
+                 a block whose status is retained, to be reported by status queries.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+        config.extend(ser_optional_param(
+            &self.max_txs_per_account,
+            200,
+            "max_txs_per_account",
+            "Maximum number of transactions of a single account held in the mempool.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(ser_optional_param(
+            &self.max_bytes_per_account,
+            1 << 24,
+            "max_bytes_per_account",
+            "Maximum total size of the transactions of a single account held in the mempool, in \
+             bytes.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(ser_optional_param(
+            &self.max_future_nonces,
+            200,
+            "max_future_nonces",
+            "Maximum number of nonces a transaction may be ahead of its account nonce.",
+            ParamPrivacyInput::Public,
+        ));
+        config.extend(ser_optional_param(
+            &self.persistence_path,
+            "".into(),
+            "persistence_path",
+            "Path of the journal which persists the mempool's transactions across restarts.",
+            ParamPrivacyInput::Public,
+        ));
+        config
     }
 }
+
+/// Determines which transactions are evicted to make space for an incoming transaction when the
+/// mempool is full.
+///
+/// Accounts with a nonce gap are always evicted first, since their transactions cannot be
+/// sequenced. Once none are left, the other policies evict the last (highest nonce) transaction of
+/// an account, so that no new gaps are created. Transactions already handed out for the block in
+/// progress, and those of the incoming transaction's account, are never evicted.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Only evict accounts with a nonce gap.
+    GapOnly,
+    /// Evict the transaction with the lowest tip, as long as it is lower than the incoming
+    /// transaction's tip.
+    LowestTip,
+    /// Evict the transaction that was submitted first.
+    OldestFirst,
+    /// Evict from the account occupying the most space in the mempool, so that every account gets
+    /// a fair share of it.
+    LargestAccount,
+}
@@ -6,11 +6,14 @@ use apollo_mempool_types::mempool_types::{
     AccountState,
     AddTransactionArgs,
     CommitBlockArgs,
+    EvictionReason,
     MempoolResult,
     MempoolSnapshot,
     MempoolStateSnapshot,
+    MempoolTransactionStatus,
 };
 use apollo_time::time::{Clock, DateTime};
+#[cfg(test)]
 use indexmap::IndexSet;
 use rand::{thread_rng, Rng};
 use starknet_api::block::GasPrice;
@@ -18,9 +21,10 @@ use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::rpc_transaction::{InternalRpcTransaction, InternalRpcTransactionWithoutTxHash};
 use starknet_api::transaction::fields::Tip;
 use starknet_api::transaction::TransactionHash;
-use tracing::{debug, info, instrument, trace};
+use starknet_types_core::felt::Felt;
+use tracing::{debug, error, info, instrument, trace};
 
-use crate::config::MempoolConfig;
+use crate::config::{EvictionPolicy, MempoolConfig};
 use crate::metrics::{
     metric_count_committed_txs,
     metric_count_expired_txs,
@@ -32,8 +36,13 @@ use crate::metrics::{
     MEMPOOL_PENDING_QUEUE_SIZE,
     MEMPOOL_POOL_SIZE,
     MEMPOOL_PRIORITY_QUEUE_SIZE,
+    MEMPOOL_SUSPENDED_POOL_SIZE,
+    MEMPOOL_SUSPENDED_PROMOTIONS_COUNT,
+    MEMPOOL_SUSPENDED_SIZE_BYTES,
     MEMPOOL_TOTAL_SIZE_BYTES,
 };
+use crate::persistence::{live_entries, submission_time_from_millis, JournalEntry, MempoolJournal};
+use crate::suspended_transaction_pool::SuspendedTransactionPool;
 use crate::transaction_pool::TransactionPool;
 use crate::transaction_queue::TransactionQueue;
 use crate::utils::try_increment_nonce;
@@ -46,7 +55,12 @@ pub mod mempool_test;
 #[path = "mempool_flow_tests.rs"]
 pub mod mempool_flow_tests;
 
+// The journal is never compacted below this many entries, to avoid rewriting it on every block
+// while the mempool is small.
+const JOURNAL_COMPACTION_MIN_ENTRIES: usize = 1000;
+
 type AddressToNonce = HashMap<ContractAddress, Nonce>;
+#[cfg(test)]
 type AccountsWithGap = IndexSet<ContractAddress>;
 
 #[derive(Debug)]
@@ -232,6 +246,41 @@ impl AddTransactionQueue {
     }
 }
 
+// The statuses of the latest transactions removed from the mempool before being included in a
+// block, retained for status queries.
+struct RemovedTransactions {
+    statuses: HashMap<TransactionHash, MempoolTransactionStatus>,
+    // Removal order, used to drop the oldest statuses once the capacity is reached.
+    order: VecDeque<TransactionHash>,
+    capacity: usize,
+}
+
+impl RemovedTransactions {
+    fn new(capacity: usize) -> Self {
+        RemovedTransactions { statuses: HashMap::new(), order: VecDeque::new(), capacity }
+    }
+
+    fn insert(&mut self, tx_hash: TransactionHash, status: MempoolTransactionStatus) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.statuses.insert(tx_hash, status).is_some() {
+            return;
+        }
+
+        self.order.push_back(tx_hash);
+        if self.order.len() > self.capacity {
+            let oldest_tx_hash =
+                self.order.pop_front().expect("Removal order should not be empty.");
+            self.statuses.remove(&oldest_tx_hash);
+        }
+    }
+
+    fn get(&self, tx_hash: TransactionHash) -> Option<MempoolTransactionStatus> {
+        self.statuses.get(&tx_hash).copied()
+    }
+}
+
 pub struct Mempool {
     config: MempoolConfig,
     // TODO(AlonH): add docstring explaining visibility and coupling of the fields.
@@ -241,11 +290,18 @@ pub struct Mempool {
     tx_pool: TransactionPool,
     // Transactions eligible for sequencing.
     tx_queue: TransactionQueue,
-    // Accounts whose lowest transaction nonce is greater than the account nonce, which are
-    // therefore candidates for eviction.
-    accounts_with_gap: AccountsWithGap,
+    // Transactions of accounts whose lowest transaction nonce is greater than the account nonce.
+    // They can't be sequenced until the gap is filled, and are the first candidates for eviction.
+    suspended_tx_pool: SuspendedTransactionPool,
+    // Transactions removed before being included in a block, with the reason of their removal.
+    removed_txs: RemovedTransactions,
     state: MempoolState,
     clock: Arc<dyn Clock>,
+    // Persists the mempool's transactions across restarts, if configured.
+    journal: Option<MempoolJournal>,
+    // Transactions removed since the journal was last written to. They are journaled along with
+    // the next entry, so that removing many transactions at once takes a single write.
+    journal_removals: Vec<TransactionHash>,
 }
 
 impl Mempool {
@@ -255,9 +311,189 @@ impl Mempool {
             delayed_declares: AddTransactionQueue::new(),
             tx_pool: TransactionPool::new(clock.clone()),
             tx_queue: TransactionQueue::default(),
-            accounts_with_gap: AccountsWithGap::new(),
+            suspended_tx_pool: SuspendedTransactionPool::new(config.suspended_capacity_in_bytes),
+            removed_txs: RemovedTransactions::new(config.removed_tx_status_retention_count),
             state: MempoolState::new(config.committed_nonce_retention_block_count),
             clock,
+            journal: None,
+            journal_removals: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &MempoolConfig {
+        &self.config
+    }
+
+    /// Restores the transactions persisted in `entries` before a restart, and from now on persists
+    /// the mempool's transactions to `journal`.
+    ///
+    /// Restored transactions keep their original submission times, so they expire as if no restart
+    /// occurred. Each transaction is revalidated against the nonce of its account, taken from
+    /// `account_nonces` (or from the persisted entry, if missing); transactions which are no
+    /// longer valid are dropped.
+    pub fn restore(
+        &mut self,
+        journal: MempoolJournal,
+        entries: Vec<JournalEntry>,
+        account_nonces: &AddressToNonce,
+    ) {
+        let submission_cutoff_time = self.clock.now() - self.config.transaction_ttl;
+        let (mut n_restored_txs, mut n_dropped_txs) = (0, 0);
+        let mut account_nonce_updates = AddressToNonce::new();
+        for entry in live_entries(entries) {
+            let (submission_time, mut args) = match entry {
+                JournalEntry::GasPriceThreshold(threshold) => {
+                    self.tx_queue.update_gas_price_threshold(threshold);
+                    continue;
+                }
+                JournalEntry::AddTransaction { submission_time, args } => {
+                    (submission_time_from_millis(submission_time), args)
+                }
+                JournalEntry::RemoveTransactions(_) => {
+                    unreachable!("Removals are applied when filtering the live entries.")
+                }
+            };
+            let tx_hash = args.tx.tx_hash;
+            if submission_time < submission_cutoff_time {
+                n_dropped_txs += 1;
+                debug!("Dropped expired persisted transaction {tx_hash}.");
+                continue;
+            }
+
+            let address = args.account_state.address;
+            if let Some(&account_nonce) = account_nonces.get(&address) {
+                args.account_state.nonce = account_nonce;
+            }
+            let account_nonce = args.account_state.nonce;
+            match self.restore_tx(submission_time, args) {
+                Ok(()) => {
+                    n_restored_txs += 1;
+                    account_nonce_updates
+                        .insert(address, self.state.resolve_nonce(address, account_nonce));
+                }
+                Err(err) => {
+                    n_dropped_txs += 1;
+                    debug!("Dropped persisted transaction {tx_hash}: {err}.");
+                }
+            }
+        }
+        info!(
+            "Restored {n_restored_txs} transactions, dropped {n_dropped_txs} expired or invalid \
+             ones."
+        );
+
+        self.journal = Some(journal);
+        self.compact_journal();
+        self.add_ready_declares();
+        self.update_suspended_txs(account_nonce_updates);
+    }
+
+    fn restore_tx(
+        &mut self,
+        submission_time: DateTime,
+        args: AddTransactionArgs,
+    ) -> MempoolResult<()> {
+        let tx_reference = TransactionReference::new(&args.tx);
+        self.validate_incoming_tx(tx_reference, args.account_state.nonce)?;
+        self.validate_account_limits(&args.tx, args.account_state.nonce)?;
+        self.handle_fee_escalation(&args.tx)?;
+        if self.exceeds_capacity(&args.tx) {
+            self.handle_capacity_overflow(&args.tx, args.account_state.nonce)?;
+        }
+        if self.exceeds_suspended_capacity(&args.tx, args.account_state.nonce) {
+            self.handle_suspended_capacity_overflow(&args.tx)?;
+        }
+
+        if let InternalRpcTransactionWithoutTxHash::Declare(_) = &args.tx.tx {
+            self.delayed_declares.push_back(submission_time, args);
+        } else {
+            self.add_tx_inner(submission_time, args);
+        }
+        Ok(())
+    }
+
+    fn persist(&mut self, entry: JournalEntry) {
+        self.persist_removals();
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        if let Err(err) = journal.append(&entry) {
+            error!("Failed to persist mempool journal entry: {err}");
+        }
+    }
+
+    // Marks the transaction as removed, to be journaled with the next journal entry.
+    fn journal_removal(&mut self, tx_hash: TransactionHash) {
+        if self.journal.is_some() {
+            self.journal_removals.push(tx_hash);
+        }
+    }
+
+    fn persist_removals(&mut self) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        if self.journal_removals.is_empty() {
+            return;
+        }
+        let entry = JournalEntry::RemoveTransactions(std::mem::take(&mut self.journal_removals));
+        if let Err(err) = journal.append(&entry) {
+            error!("Failed to persist mempool journal entry: {err}");
+        }
+    }
+
+    /// Compacts the journal once it holds many more entries than the mempool's content, so that the
+    /// cost of rewriting it is amortized over the entries appended since the last compaction.
+    fn maybe_compact_journal(&mut self) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        let n_content_entries = 1 + self.tx_pool.len() + self.delayed_declares.len();
+        if journal.n_entries() > JOURNAL_COMPACTION_MIN_ENTRIES.max(2 * n_content_entries) {
+            self.compact_journal();
+        }
+    }
+
+    /// Rewrites the journal with the mempool's current content, discarding the transactions that
+    /// were removed since it was last compacted.
+    fn compact_journal(&mut self) {
+        self.journal_removals.clear();
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+
+        let mut entries =
+            vec![JournalEntry::GasPriceThreshold(self.tx_queue.gas_price_threshold())];
+        // Oldest transactions first, as they were originally added.
+        for tx_hash in self.tx_pool.chronological_txs_hashes().into_iter().rev() {
+            let tx =
+                self.tx_pool.get_by_tx_hash(tx_hash).expect("Transaction must be in the pool.");
+            let submission_time = self
+                .tx_pool
+                .get_submission_time(tx_hash)
+                .expect("Transaction must be in the pool.");
+            let address = tx.contract_address();
+            // A queued transaction holds the account nonce; otherwise the account has a nonce gap,
+            // and only its committed nonce, if known, is below the lowest transaction nonce.
+            let nonce = self
+                .tx_queue
+                .get_nonce(address)
+                .or_else(|| self.state.committed.get(&address).copied())
+                .unwrap_or_default();
+            entries.push(JournalEntry::add_transaction(
+                submission_time,
+                AddTransactionArgs {
+                    tx: tx.clone(),
+                    account_state: AccountState { address, nonce },
+                },
+            ));
+        }
+        for (submission_time, args) in &self.delayed_declares.elements {
+            entries.push(JournalEntry::add_transaction(*submission_time, args.clone()));
+        }
+
+        if let Err(err) = journal.rewrite(&entries) {
+            error!("Failed to compact the mempool journal: {err}");
         }
     }
 
@@ -304,7 +540,8 @@ impl Mempool {
 
         metric_set_get_txs_size(n_returned_txs);
         self.update_state_metrics();
-        self.update_accounts_with_gap(account_nonce_updates);
+        self.update_suspended_txs(account_nonce_updates);
+        self.persist_removals();
 
         Ok(eligible_tx_references
             .iter()
@@ -339,11 +576,15 @@ impl Mempool {
 
         let tx_reference = TransactionReference::new(&args.tx);
         self.validate_incoming_tx(tx_reference, args.account_state.nonce)?;
+        self.validate_account_limits(&args.tx, args.account_state.nonce)?;
         self.handle_fee_escalation(&args.tx)?;
 
         if self.exceeds_capacity(&args.tx) {
             self.handle_capacity_overflow(&args.tx, args.account_state.nonce)?;
         }
+        if self.exceeds_suspended_capacity(&args.tx, args.account_state.nonce) {
+            self.handle_suspended_capacity_overflow(&args.tx)?;
+        }
 
         metric_handle.transaction_inserted();
 
@@ -354,14 +595,16 @@ impl Mempool {
             self.state.resolve_nonce(args.account_state.address, args.account_state.nonce),
         );
 
+        let submission_time = self.clock.now();
+        self.persist(JournalEntry::add_transaction(submission_time, args.clone()));
         if let InternalRpcTransactionWithoutTxHash::Declare(_) = &args.tx.tx {
-            self.delayed_declares.push_back(self.clock.now(), args);
+            self.delayed_declares.push_back(submission_time, args);
         } else {
-            self.add_tx_inner(args);
+            self.add_tx_inner(submission_time, args);
         }
 
         self.update_state_metrics();
-        self.update_accounts_with_gap(account_nonce_updates);
+        self.update_suspended_txs(account_nonce_updates);
         Ok(())
     }
 
@@ -369,7 +612,7 @@ impl Mempool {
         self.tx_queue.insert(tx_reference, self.config.validate_resource_bounds);
     }
 
-    fn add_tx_inner(&mut self, args: AddTransactionArgs) {
+    fn add_tx_inner(&mut self, submission_time: DateTime, args: AddTransactionArgs) {
         let AddTransactionArgs { tx, account_state } = args;
         info!("Adding transaction to mempool.");
         trace!("{tx:#?}");
@@ -377,7 +620,7 @@ impl Mempool {
         let tx_reference = TransactionReference::new(&tx);
 
         self.tx_pool
-            .insert(tx)
+            .insert_with_submission_time(tx, submission_time)
             .expect("Duplicate transactions should cause an error during the validation stage.");
 
         let AccountState { address, nonce: incoming_account_nonce } = account_state;
@@ -399,7 +642,7 @@ impl Mempool {
             }
             let (_submission_time, args) =
                 self.delayed_declares.pop_front().expect("Delay declare should exist.");
-            self.add_tx_inner(args);
+            self.add_tx_inner(now, args);
         }
         self.update_state_metrics();
     }
@@ -431,8 +674,11 @@ impl Mempool {
             }
 
             // Remove from pool.
-            let n_removed_txs = self.tx_pool.remove_up_to_nonce(address, next_nonce);
-            metric_count_committed_txs(n_removed_txs);
+            let removed_txs = self.tx_pool.remove_up_to_nonce(address, next_nonce);
+            metric_count_committed_txs(removed_txs.len());
+            for tx in removed_txs {
+                self.journal_removal(tx.tx_hash);
+            }
 
             // Maybe close nonce gap.
             if self.tx_queue.get_nonce(address).is_none() {
@@ -466,6 +712,11 @@ impl Mempool {
         let mut account_nonce_updates = AddressToNonce::new();
         for tx_hash in rejected_tx_hashes {
             if let Ok(tx) = self.tx_pool.remove(tx_hash) {
+                self.suspended_tx_pool.remove(&TransactionReference::new(&tx));
+                self.record_removal(
+                    tx_hash,
+                    MempoolTransactionStatus::Evicted(EvictionReason::Rejected),
+                );
                 self.tx_queue.remove(tx.contract_address());
                 account_nonce_updates
                     .entry(tx.contract_address())
@@ -483,7 +734,9 @@ impl Mempool {
         account_nonce_updates.extend(committed_nonce_updates);
 
         self.update_state_metrics();
-        self.update_accounts_with_gap(account_nonce_updates);
+        self.update_suspended_txs(account_nonce_updates);
+        self.persist_removals();
+        self.maybe_compact_journal();
     }
 
     pub fn account_tx_in_pool_or_recent_block(&self, account_address: ContractAddress) -> bool {
@@ -502,6 +755,57 @@ impl Mempool {
         self.state.validate_incoming_tx(tx_reference, incoming_account_nonce)
     }
 
+    /// Validates that adding the given transaction keeps its account within the per-account limits.
+    /// A transaction which would replace an existing one (see `handle_fee_escalation`) takes its
+    /// place in the limits.
+    fn validate_account_limits(
+        &self,
+        tx: &InternalRpcTransaction,
+        incoming_account_nonce: Nonce,
+    ) -> MempoolResult<()> {
+        let address = tx.contract_address();
+        let tx_nonce = tx.nonce();
+
+        // The nonce was already validated to not be lower than the account nonce.
+        let account_nonce = self.state.resolve_nonce(address, incoming_account_nonce);
+        if let Some(max_future_nonces) = self.config.max_future_nonces {
+            if tx_nonce.0 - account_nonce.0 > Felt::from(max_future_nonces) {
+                return Err(MempoolError::NonceTooFarAhead {
+                    address,
+                    tx_nonce,
+                    account_nonce,
+                    max_future_nonces,
+                });
+            }
+        }
+
+        let (mut n_txs, mut size_in_bytes) =
+            (self.tx_pool.account_len(address), self.tx_pool.account_size_in_bytes(address));
+        if let Some(existing_tx_reference) =
+            self.tx_pool.get_by_address_and_nonce(address, tx_nonce)
+        {
+            let existing_tx = self
+                .tx_pool
+                .get_by_tx_hash(existing_tx_reference.tx_hash)
+                .expect("Transaction from the account mapping must appear in pool.");
+            n_txs -= 1;
+            size_in_bytes -= existing_tx.total_bytes();
+        }
+
+        if let Some(max_txs) = self.config.max_txs_per_account {
+            if n_txs >= max_txs {
+                return Err(MempoolError::AccountTransactionLimitExceeded { address, max_txs });
+            }
+        }
+        if let Some(max_bytes) = self.config.max_bytes_per_account {
+            if size_in_bytes + tx.total_bytes() > max_bytes {
+                return Err(MempoolError::AccountCapacityExceeded { address, max_bytes });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Validates that the given transaction does not front run a delayed declare. This means in
     /// particular that no fee escalation can occur to a declare that is being delayed.
     fn validate_no_delayed_declare_front_run(
@@ -524,6 +828,7 @@ impl Mempool {
     /// Updates the gas price threshold for transactions that are eligible for sequencing.
     pub fn update_gas_price(&mut self, threshold: GasPrice) {
         self.tx_queue.update_gas_price_threshold(threshold);
+        self.persist(JournalEntry::GasPriceThreshold(threshold));
         self.update_state_metrics();
     }
 
@@ -577,9 +882,14 @@ impl Mempool {
         debug!("{existing_tx_reference} will be replaced by {incoming_tx_reference}.");
 
         self.tx_queue.remove_txs(&[existing_tx_reference]);
+        self.suspended_tx_pool.remove(&existing_tx_reference);
         self.tx_pool
             .remove(existing_tx_reference.tx_hash)
             .expect("Transaction hash from pool must exist.");
+        self.record_removal(
+            existing_tx_reference.tx_hash,
+            MempoolTransactionStatus::Evicted(EvictionReason::Replaced),
+        );
 
         Ok(())
     }
@@ -617,8 +927,24 @@ impl Mempool {
     }
 
     fn remove_expired_txs(&mut self) -> AddressToNonce {
-        let removed_txs =
+        let mut removed_txs =
             self.tx_pool.remove_txs_older_than(self.config.transaction_ttl, &self.state.staged);
+        for tx in &removed_txs {
+            self.suspended_tx_pool.remove(tx);
+        }
+
+        // Suspended transactions also expire once they have been suspended for too long.
+        let suspension_cutoff_time = self.clock.now() - self.config.suspended_transaction_ttl;
+        let expired_suspended_txs =
+            self.suspended_tx_pool.remove_txs_suspended_before(suspension_cutoff_time);
+        for tx in &expired_suspended_txs {
+            self.tx_pool.remove(tx.tx_hash).expect("Suspended transaction must exist in the pool.");
+        }
+        removed_txs.extend(expired_suspended_txs);
+        for tx in &removed_txs {
+            self.record_removal(tx.tx_hash, MempoolTransactionStatus::Expired);
+        }
+
         let queued_txs = self.tx_queue.remove_txs(&removed_txs);
 
         metric_count_expired_txs(removed_txs.len());
@@ -655,6 +981,7 @@ impl Mempool {
                 self.tx_pool
                     .remove(tx.tx_hash)
                     .expect("Transaction hash from queue must appear in pool.");
+                self.record_removal(tx.tx_hash, MempoolTransactionStatus::Expired);
                 (tx.address, self.state.resolve_nonce(tx.address, tx.nonce))
             })
             .collect();
@@ -671,11 +998,73 @@ impl Mempool {
                 .iter()
                 .map(|(_, args)| args.tx.tx_hash)
                 .collect(),
+            suspended_transactions: self.suspended_tx_pool.chronological_txs_hashes(),
             transaction_queue: self.tx_queue.queue_snapshot(),
             mempool_state: self.state.state_snapshot(),
         })
     }
 
+    /// Returns the transaction with the given hash, if held in the mempool.
+    pub fn get_tx_by_hash(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolResult<&InternalRpcTransaction> {
+        self.tx_pool.get_by_tx_hash(tx_hash).or_else(|err| {
+            self.delayed_declares
+                .elements
+                .iter()
+                .map(|(_, args)| &args.tx)
+                .find(|tx| tx.tx_hash == tx_hash)
+                .ok_or(err)
+        })
+    }
+
+    /// Returns the transactions of the given account held in the mempool, sorted by nonce.
+    pub fn get_account_txs(&self, address: ContractAddress) -> Vec<&InternalRpcTransaction> {
+        let mut account_txs: Vec<_> = self
+            .tx_pool
+            .account_txs_sorted_by_nonce(address)
+            .map(|tx_reference| {
+                self.tx_pool
+                    .get_by_tx_hash(tx_reference.tx_hash)
+                    .expect("Transaction from the account mapping must appear in pool.")
+            })
+            .chain(
+                self.delayed_declares
+                    .elements
+                    .iter()
+                    .map(|(_, args)| &args.tx)
+                    .filter(|tx| tx.contract_address() == address),
+            )
+            .collect();
+        account_txs.sort_by_key(|tx| tx.nonce());
+        account_txs
+    }
+
+    /// Returns the status of the transaction with the given hash, if held in the mempool or
+    /// recently removed from it.
+    pub fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolResult<MempoolTransactionStatus> {
+        if let Ok(tx) = self.tx_pool.get_by_tx_hash(tx_hash) {
+            let tx_reference = TransactionReference::new(tx);
+            if self.suspended_tx_pool.contains(&tx_reference) {
+                return Ok(MempoolTransactionStatus::Gapped);
+            }
+            if self.tx_queue.is_pending(&tx_reference) {
+                return Ok(MempoolTransactionStatus::PendingBelowThreshold);
+            }
+            return Ok(MempoolTransactionStatus::Queued);
+        }
+
+        if self.delayed_declares.elements.iter().any(|(_, args)| args.tx.tx_hash == tx_hash) {
+            return Ok(MempoolTransactionStatus::Queued);
+        }
+
+        self.removed_txs.get(tx_hash).ok_or(MempoolError::TransactionNotFound { tx_hash })
+    }
+
     fn size_in_bytes(&self) -> u64 {
         self.tx_pool.size_in_bytes() + self.delayed_declares.size_in_bytes()
     }
@@ -685,69 +1074,207 @@ impl Mempool {
         self.size_in_bytes() + tx.total_bytes() > self.config.capacity_in_bytes
     }
 
-    fn update_accounts_with_gap(&mut self, address_to_nonce: AddressToNonce) {
+    /// Suspends the transactions of the given accounts which have a nonce gap, and promotes those
+    /// of the accounts whose gap was filled.
+    fn update_suspended_txs(&mut self, address_to_nonce: AddressToNonce) {
         for (address, account_nonce) in address_to_nonce {
-            // Assumption: Future declares are not allowed — their nonce must match the account
-            // nonce, so they fill a gap if one exists.
-            if self.delayed_declares.contains(address, account_nonce) {
-                self.accounts_with_gap.swap_remove(&address);
-                continue;
+            if self.has_gap(address, account_nonce) {
+                self.suspend_account_txs(address);
+            } else {
+                self.promote_account_txs(address);
             }
+        }
+        self.update_state_metrics();
+    }
 
-            // Gap exists when lowest transaction nonce is higher than account nonce.
-            let gap_exists = match self.tx_pool.get_lowest_nonce(address) {
-                Some(lowest_nonce) => account_nonce < lowest_nonce,
-                None => false, // No transactions for the account, so no gap.
-            };
+    fn has_gap(&self, address: ContractAddress, account_nonce: Nonce) -> bool {
+        // Assumption: Future declares are not allowed — their nonce must match the account
+        // nonce, so they fill a gap if one exists.
+        if self.delayed_declares.contains(address, account_nonce) {
+            return false;
+        }
 
-            // Update the eviction tracking set accordingly.
-            if gap_exists {
-                self.accounts_with_gap.insert(address);
-            } else {
-                self.accounts_with_gap.swap_remove(&address);
+        // Gap exists when lowest transaction nonce is higher than account nonce.
+        match self.tx_pool.get_lowest_nonce(address) {
+            Some(lowest_nonce) => account_nonce < lowest_nonce,
+            None => false, // No transactions for the account, so no gap.
+        }
+    }
+
+    fn suspend_account_txs(&mut self, address: ContractAddress) {
+        // Drop transactions removed from the pool meanwhile (e.g., committed).
+        let removed_txs: Vec<_> = self
+            .suspended_tx_pool
+            .account_txs(address)
+            .filter(|tx_reference| self.tx_pool.get_by_tx_hash(tx_reference.tx_hash).is_err())
+            .copied()
+            .collect();
+        for tx_reference in &removed_txs {
+            self.suspended_tx_pool.remove(tx_reference);
+        }
+
+        let now = self.clock.now();
+        let account_txs: Vec<_> =
+            self.tx_pool.account_txs_sorted_by_nonce(address).copied().collect();
+        for (i, &tx_reference) in account_txs.iter().enumerate() {
+            let tx_size = self
+                .tx_pool
+                .get_by_tx_hash(tx_reference.tx_hash)
+                .expect("Transaction from the account mapping must appear in pool.")
+                .total_bytes();
+            if self.suspended_tx_pool.contains(&tx_reference) {
+                continue;
             }
+            if self.suspended_tx_pool.exceeds_capacity(tx_size) {
+                // The suspended transactions are full; the rest of the account's transactions,
+                // which depend on this one, are evicted.
+                for evicted_tx in &account_txs[i..] {
+                    self.tx_pool
+                        .remove(evicted_tx.tx_hash)
+                        .expect("Transaction must exist in the pool.");
+                    self.suspended_tx_pool.remove(evicted_tx);
+                    self.record_eviction(evicted_tx.tx_hash);
+                }
+                break;
+            }
+            self.suspended_tx_pool.insert(tx_reference, tx_size, now);
+            debug!("Suspended {tx_reference}.");
+        }
+    }
+
+    fn promote_account_txs(&mut self, address: ContractAddress) {
+        // Transactions removed from the pool meanwhile (e.g., committed) are not promoted.
+        let n_promoted_txs = self
+            .suspended_tx_pool
+            .remove_account(address)
+            .into_iter()
+            .filter(|tx_reference| self.tx_pool.get_by_tx_hash(tx_reference.tx_hash).is_ok())
+            .count();
+        if n_promoted_txs != 0 {
+            debug!("Promoted {n_promoted_txs} suspended transactions of account {address}.");
+            MEMPOOL_SUSPENDED_PROMOTIONS_COUNT
+                .increment(n_promoted_txs.try_into().expect("The number of txs should fit u64"));
         }
     }
 
     pub fn get_evictable_account(&self) -> Option<ContractAddress> {
-        let len = self.accounts_with_gap.len();
+        let len = self.suspended_tx_pool.n_accounts();
         if len == 0 {
             return None;
         }
         let random_index = thread_rng().gen_range(0..len);
-        self.accounts_with_gap.get_index(random_index).copied()
+        self.suspended_tx_pool.get_account_by_index(random_index)
     }
 
-    // Attempts to make space for a new transaction by evicting existing transactions.
+    // Attempts to make space for the incoming transaction by evicting existing transactions,
+    // according to the configured eviction policy.
     // Returns true if enough space was freed, false otherwise.
-    pub fn try_make_space(&mut self, required_space: u64) -> bool {
+    pub fn try_make_space(
+        &mut self,
+        incoming_tx: &TransactionReference,
+        required_space: u64,
+    ) -> bool {
         let mut total_space_freed = 0;
 
         while total_space_freed < required_space {
-            let Some(address) = self.get_evictable_account() else {
+            // Accounts with a gap are evicted first, regardless of the policy.
+            if let Some(address) = self.get_evictable_account() {
+                total_space_freed +=
+                    self.evict_suspended_account_txs(address, required_space - total_space_freed);
+                continue;
+            }
+
+            let Some(tx_reference) = self.select_eviction_candidate(incoming_tx) else {
                 return false;
             };
+            let tx = self
+                .tx_pool
+                .remove(tx_reference.tx_hash)
+                .expect("Transaction must exist in the pool.");
+            // Only the account's last transaction is evicted, so no gap is created; it may still be
+            // queued if it is the only one.
+            self.tx_queue.remove_txs(&[tx_reference]);
+            self.record_eviction(tx_reference.tx_hash);
+            total_space_freed += tx.total_bytes();
+        }
 
-            let txs: Vec<_> = self.tx_pool.account_txs_sorted_by_nonce(address).copied().collect();
-            for tx_ref in txs.iter().rev() {
-                let tx = self
-                    .tx_pool
-                    .remove(tx_ref.tx_hash)
-                    .expect("Transaction must exist in the pool.");
-                total_space_freed += tx.total_bytes();
-                MEMPOOL_EVICTIONS_COUNT.increment(1);
-                if total_space_freed >= required_space {
-                    break;
-                }
-            }
+        true
+    }
 
-            // Clean up if account is now empty.
-            if !self.tx_pool.contains_account(address) {
-                self.accounts_with_gap.swap_remove(&address);
+    // Evicts the suspended transactions of the given account, starting from the highest nonce,
+    // until the given space is freed among them or none are left. Returns the space freed in the
+    // pool, which is smaller if some were already removed from it (e.g., committed).
+    fn evict_suspended_account_txs(
+        &mut self,
+        address: ContractAddress,
+        required_space: u64,
+    ) -> u64 {
+        let (mut suspended_space_freed, mut space_freed) = (0, 0);
+
+        while suspended_space_freed < required_space {
+            let Some((tx_ref, tx_size)) = self.suspended_tx_pool.remove_last_account_tx(address)
+            else {
+                break;
+            };
+            suspended_space_freed += tx_size;
+            if let Ok(tx) = self.tx_pool.remove(tx_ref.tx_hash) {
+                self.record_eviction(tx_ref.tx_hash);
+                space_freed += tx.total_bytes();
             }
         }
 
-        true
+        space_freed
+    }
+
+    fn record_eviction(&mut self, tx_hash: TransactionHash) {
+        self.record_removal(
+            tx_hash,
+            MempoolTransactionStatus::Evicted(EvictionReason::CapacityExceeded),
+        );
+        MEMPOOL_EVICTIONS_COUNT.increment(1);
+    }
+
+    // Records the removal of a transaction before it was included in a block.
+    fn record_removal(&mut self, tx_hash: TransactionHash, status: MempoolTransactionStatus) {
+        self.removed_txs.insert(tx_hash, status);
+        self.journal_removal(tx_hash);
+    }
+
+    // Selects the next transaction to evict for the incoming transaction, among the last
+    // transactions of the accounts without a gap. Returns None if there is none the policy allows
+    // to evict.
+    fn select_eviction_candidate(
+        &self,
+        incoming_tx: &TransactionReference,
+    ) -> Option<TransactionReference> {
+        // Transactions handed out for the block in progress, and those the incoming transaction
+        // may depend on, must not be evicted.
+        let candidates = self.tx_pool.last_account_txs().copied().filter(|tx| {
+            tx.address != incoming_tx.address
+                && self.state.staged.get(&tx.address).is_none_or(|&nonce| tx.nonce >= nonce)
+        });
+
+        match self.config.eviction_policy {
+            EvictionPolicy::GapOnly => None,
+            EvictionPolicy::LowestTip => candidates
+                .filter(|tx| tx.tip < incoming_tx.tip)
+                .min_by_key(|tx| (tx.tip, tx.tx_hash)),
+            EvictionPolicy::OldestFirst => candidates.min_by_key(|tx| {
+                let submission_time = self
+                    .tx_pool
+                    .get_submission_time(tx.tx_hash)
+                    .expect("Transaction from the account mapping must appear in pool.");
+                (submission_time, tx.tx_hash)
+            }),
+            EvictionPolicy::LargestAccount => {
+                let incoming_account_size = self.tx_pool.account_size_in_bytes(incoming_tx.address);
+                candidates
+                    .map(|tx| (self.tx_pool.account_size_in_bytes(tx.address), tx))
+                    .filter(|(account_size, _)| *account_size > incoming_account_size)
+                    .max_by_key(|(account_size, tx)| (*account_size, tx.tx_hash))
+                    .map(|(_, tx)| tx)
+            }
+        }
     }
 
     fn handle_capacity_overflow(
@@ -757,18 +1284,69 @@ impl Mempool {
     ) -> Result<(), MempoolError> {
         let address = tx.contract_address();
 
-        let account_has_gap = self.accounts_with_gap.contains(&address);
+        let account_has_gap = self.suspended_tx_pool.contains_account(address);
         let account_has_txs = self.tx_pool.contains_account(address);
         let closing_gap = tx.nonce() == account_nonce;
         let creating_gap = (account_has_gap || !account_has_txs) && !closing_gap;
 
-        if !creating_gap && self.try_make_space(tx.total_bytes()) {
+        if !creating_gap && self.try_make_space(&TransactionReference::new(tx), tx.total_bytes()) {
             return Ok(());
         }
 
         Err(MempoolError::MempoolFull)
     }
 
+    // Returns true if the given transaction is to be suspended, and will exceed the capacity of
+    // the suspended transactions.
+    fn exceeds_suspended_capacity(
+        &self,
+        tx: &InternalRpcTransaction,
+        account_nonce: Nonce,
+    ) -> bool {
+        if let InternalRpcTransactionWithoutTxHash::Declare(_) = &tx.tx {
+            // Declares are delayed rather than suspended.
+            return false;
+        }
+
+        let address = tx.contract_address();
+        let account_nonce = self.state.resolve_nonce(address, account_nonce);
+        let lowest_nonce = self
+            .tx_pool
+            .get_lowest_nonce(address)
+            .map_or(tx.nonce(), |nonce| nonce.min(tx.nonce()));
+        let is_suspended =
+            !self.delayed_declares.contains(address, account_nonce) && account_nonce < lowest_nonce;
+
+        is_suspended && self.suspended_tx_pool.exceeds_capacity(tx.total_bytes())
+    }
+
+    // Makes space for the given transaction among the suspended transactions, by evicting those of
+    // other accounts.
+    fn handle_suspended_capacity_overflow(
+        &mut self,
+        tx: &InternalRpcTransaction,
+    ) -> MempoolResult<()> {
+        let address = tx.contract_address();
+        let capacity = self.config.suspended_capacity_in_bytes;
+        while self.suspended_tx_pool.exceeds_capacity(tx.total_bytes()) {
+            let other_accounts: Vec<_> =
+                self.suspended_tx_pool.accounts().filter(|&account| account != address).collect();
+            if other_accounts.is_empty() {
+                return Err(MempoolError::MempoolFull);
+            }
+            let evicted_account = other_accounts[thread_rng().gen_range(0..other_accounts.len())];
+            let size_before_eviction = self.suspended_tx_pool.size_in_bytes();
+            let required_space = size_before_eviction + tx.total_bytes() - capacity;
+            self.evict_suspended_account_txs(evicted_account, required_space);
+            if self.suspended_tx_pool.size_in_bytes() == size_before_eviction {
+                // Nothing could be evicted.
+                return Err(MempoolError::MempoolFull);
+            }
+        }
+
+        Ok(())
+    }
+
     #[cfg(test)]
     fn content(&self) -> MempoolContent {
         MempoolContent {
@@ -779,8 +1357,8 @@ impl Mempool {
     }
 
     #[cfg(test)]
-    fn accounts_with_gap(&self) -> &AccountsWithGap {
-        &self.accounts_with_gap
+    fn accounts_with_gap(&self) -> AccountsWithGap {
+        self.suspended_tx_pool.accounts().collect()
     }
 
     fn update_state_metrics(&self) {
@@ -789,6 +1367,8 @@ impl Mempool {
         MEMPOOL_PENDING_QUEUE_SIZE.set_lossy(self.tx_queue.pending_queue_len());
         MEMPOOL_DELAYED_DECLARES_SIZE.set_lossy(self.delayed_declares.len());
         MEMPOOL_TOTAL_SIZE_BYTES.set_lossy(self.size_in_bytes());
+        MEMPOOL_SUSPENDED_POOL_SIZE.set_lossy(self.suspended_tx_pool.len());
+        MEMPOOL_SUSPENDED_SIZE_BYTES.set_lossy(self.suspended_tx_pool.size_in_bytes());
     }
 }
 
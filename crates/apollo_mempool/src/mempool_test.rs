@@ -9,7 +9,12 @@ use apollo_mempool_p2p_types::communication::{
 };
 use apollo_mempool_types::communication::AddTransactionArgsWrapper;
 use apollo_mempool_types::errors::MempoolError;
-use apollo_mempool_types::mempool_types::{AccountState, AddTransactionArgs};
+use apollo_mempool_types::mempool_types::{
+    AccountState,
+    AddTransactionArgs,
+    EvictionReason,
+    MempoolTransactionStatus,
+};
 use apollo_metrics::metrics::HistogramValue;
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_test_utils::{get_rng, GetTestInstance};
@@ -27,17 +32,16 @@ use starknet_api::transaction::fields::TransactionSignature;
 use starknet_api::transaction::TransactionHash;
 use starknet_api::{contract_address, declare_tx_args, felt, invoke_tx_args, nonce, tx_hash};
 
-use super::AddTransactionQueue;
+use super::{AddTransactionQueue, RemovedTransactions};
 use crate::communication::MempoolCommunicationWrapper;
-use crate::mempool::{
-    AccountsWithGap,
-    Mempool,
-    MempoolConfig,
-    MempoolContent,
-    MempoolState,
-    TransactionReference,
+use crate::config::EvictionPolicy;
+use crate::mempool::{Mempool, MempoolConfig, MempoolContent, MempoolState, TransactionReference};
+use crate::metrics::{
+    register_metrics,
+    MEMPOOL_SUSPENDED_POOL_SIZE,
+    MEMPOOL_SUSPENDED_PROMOTIONS_COUNT,
 };
-use crate::metrics::register_metrics;
+use crate::suspended_transaction_pool::SuspendedTransactionPool;
 use crate::test_utils::{
     add_tx,
     add_tx_expect_error,
@@ -136,18 +140,28 @@ impl MempoolTestContentBuilder {
     }
 
     fn build_full_mempool(self) -> Mempool {
+        let clock = Arc::new(FakeClock::default());
+        let mut tx_pool = TransactionPool::new(clock.clone());
+        for tx in self.content.tx_pool.unwrap_or_default().into_values() {
+            tx_pool.insert(tx).unwrap();
+        }
         Mempool {
             config: self.config.clone(),
             delayed_declares: AddTransactionQueue::new(),
-            tx_pool: self.content.tx_pool.unwrap_or_default().into_values().collect(),
+            tx_pool,
             tx_queue: TransactionQueue::new(
                 self.content.priority_txs.unwrap_or_default(),
                 self.content.pending_txs.unwrap_or_default(),
                 self.gas_price_threshold,
             ),
-            accounts_with_gap: AccountsWithGap::new(),
+            suspended_tx_pool: SuspendedTransactionPool::new(
+                self.config.suspended_capacity_in_bytes,
+            ),
+            removed_txs: RemovedTransactions::new(self.config.removed_tx_status_retention_count),
             state: MempoolState::new(self.config.committed_nonce_retention_block_count),
-            clock: Arc::new(FakeClock::default()),
+            clock,
+            journal: None,
+            journal_removals: Vec::new(),
         }
     }
 }
@@ -944,8 +958,11 @@ async fn test_new_tx_sent_to_p2p(mempool: Mempool) {
         .times(1)
         .with(eq(tx_args.tx))
         .returning(|_| Ok(()));
-    let mut mempool_wrapper =
-        MempoolCommunicationWrapper::new(mempool, Arc::new(mock_mempool_p2p_propagator_client));
+    let mut mempool_wrapper = MempoolCommunicationWrapper::new(
+        mempool,
+        Arc::new(mock_mempool_p2p_propagator_client),
+        None,
+    );
 
     mempool_wrapper.add_tx(propagateor_args).await.unwrap();
 }
@@ -968,8 +985,11 @@ async fn test_propagated_tx_sent_to_p2p(mempool: Mempool) {
         .with(eq(expected_message_metadata.clone()))
         .returning(|_| Ok(()));
 
-    let mut mempool_wrapper =
-        MempoolCommunicationWrapper::new(mempool, Arc::new(mock_mempool_p2p_propagator_client));
+    let mut mempool_wrapper = MempoolCommunicationWrapper::new(
+        mempool,
+        Arc::new(mock_mempool_p2p_propagator_client),
+        None,
+    );
 
     mempool_wrapper.add_tx(propagated_args).await.unwrap();
 }
@@ -1240,6 +1260,9 @@ fn metrics_correctness() {
         delayed_declares_size: 1,
         total_size_in_bytes: 1952,
         evictions_count: 1,
+        suspended_pool_size: 0,
+        suspended_size_in_bytes: 0,
+        suspended_promotions_count: 0,
         transaction_time_spent_in_mempool: HistogramValue {
             sum: 65.0,
             count: 4,
@@ -1426,7 +1449,7 @@ async fn add_tx_tolerates_p2p_propagation_error(mempool: Mempool) {
             "".to_string(),
         )))
     });
-    let mut mempool_wrapper = MempoolCommunicationWrapper::new(mempool, Arc::new(mock_p2p));
+    let mut mempool_wrapper = MempoolCommunicationWrapper::new(mempool, Arc::new(mock_p2p), None);
 
     let result = mempool_wrapper.add_tx(tx_args_wrapper).await;
 
@@ -1724,3 +1747,426 @@ fn rejects_or_accepts_tx_based_on_freed_space(
     // We do not revert the eviction attempt even if adding large_tx ultimately fails.
     assert!(!mempool.tx_pool.contains_account(contract_address!("0x1")));
 }
+
+#[rstest]
+fn rejects_tx_exceeding_max_txs_per_account() {
+    let mut mempool = Mempool::new(
+        MempoolConfig { max_txs_per_account: Some(2), ..Default::default() },
+        Arc::new(FakeClock::default()),
+    );
+    let tx_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let tx_nonce_1 = add_tx_input!(
+        tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0, tip: 10, max_l2_gas_price: 10
+    );
+    for tx in [&tx_nonce_0, &tx_nonce_1] {
+        add_tx(&mut mempool, tx);
+    }
+
+    let tx_nonce_2 = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 2, account_nonce: 0);
+    add_tx_expect_error(
+        &mut mempool,
+        &tx_nonce_2,
+        MempoolError::AccountTransactionLimitExceeded {
+            address: contract_address!("0x0"),
+            max_txs: 2,
+        },
+    );
+
+    // Replacing an existing transaction doesn't count towards the limit.
+    let replacing_tx = add_tx_input!(
+        tx_hash: 4, address: "0x0", tx_nonce: 1, account_nonce: 0, tip: 20, max_l2_gas_price: 20
+    );
+    add_tx(&mut mempool, &replacing_tx);
+
+    // Other accounts are not affected.
+    let other_account_tx = add_tx_input!(tx_hash: 5, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &other_account_tx);
+}
+
+#[rstest]
+fn rejects_tx_exceeding_max_bytes_per_account() {
+    let tx_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let tx_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let max_bytes = tx_nonce_0.tx.total_bytes() + tx_nonce_1.tx.total_bytes() - 1;
+    let mut mempool = Mempool::new(
+        MempoolConfig { max_bytes_per_account: Some(max_bytes), ..Default::default() },
+        Arc::new(FakeClock::default()),
+    );
+    add_tx(&mut mempool, &tx_nonce_0);
+
+    add_tx_expect_error(
+        &mut mempool,
+        &tx_nonce_1,
+        MempoolError::AccountCapacityExceeded { address: contract_address!("0x0"), max_bytes },
+    );
+}
+
+#[rstest]
+fn rejects_tx_with_nonce_too_far_ahead() {
+    let mut mempool = Mempool::new(
+        MempoolConfig { max_future_nonces: Some(2), ..Default::default() },
+        Arc::new(FakeClock::default()),
+    );
+    let furthest_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 3, account_nonce: 1);
+    add_tx(&mut mempool, &furthest_tx);
+
+    let too_far_tx = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 4, account_nonce: 1);
+    add_tx_expect_error(
+        &mut mempool,
+        &too_far_tx,
+        MempoolError::NonceTooFarAhead {
+            address: contract_address!("0x0"),
+            tx_nonce: nonce!(4),
+            account_nonce: nonce!(1),
+            max_future_nonces: 2,
+        },
+    );
+}
+
+#[rstest]
+fn account_limits_are_off_by_default() {
+    let mut mempool = Mempool::new(MempoolConfig::default(), Arc::new(FakeClock::default()));
+    let far_ahead_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 1000, account_nonce: 0);
+    add_tx(&mut mempool, &far_ahead_tx);
+    for tx_nonce in 0..300 {
+        let tx = add_tx_input!(tx_hash: 2 + tx_nonce, address: "0x1", tx_nonce: tx_nonce, account_nonce: 0);
+        add_tx(&mut mempool, &tx);
+    }
+}
+
+#[rstest]
+#[case::gap_only(EvictionPolicy::GapOnly, None)]
+#[case::lowest_tip(EvictionPolicy::LowestTip, Some(3))]
+#[case::oldest_first(EvictionPolicy::OldestFirst, Some(2))]
+#[case::largest_account(EvictionPolicy::LargestAccount, Some(2))]
+fn evicts_tx_according_to_policy(
+    #[case] eviction_policy: EvictionPolicy,
+    #[case] expected_evicted_tx_hash: Option<u8>,
+) {
+    let large_account_txs = [
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0, tip: 50),
+        add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0, tip: 50),
+    ];
+    let low_tip_tx =
+        add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 5);
+    let incoming_tx =
+        add_tx_input!(tx_hash: 4, address: "0x2", tx_nonce: 0, account_nonce: 0, tip: 10);
+
+    let clock = Arc::new(FakeClock::default());
+    let capacity = large_account_txs.iter().map(|tx| tx.tx.total_bytes()).sum::<u64>()
+        + low_tip_tx.tx.total_bytes();
+    let mut mempool = Mempool::new(
+        MempoolConfig { capacity_in_bytes: capacity, eviction_policy, ..Default::default() },
+        clock.clone(),
+    );
+    for tx in &large_account_txs {
+        add_tx(&mut mempool, tx);
+    }
+    clock.advance(Duration::from_secs(1));
+    add_tx(&mut mempool, &low_tip_tx);
+
+    let Some(expected_evicted_tx_hash) = expected_evicted_tx_hash else {
+        add_tx_expect_error(&mut mempool, &incoming_tx, MempoolError::MempoolFull);
+        return;
+    };
+    add_tx(&mut mempool, &incoming_tx);
+    let evicted_tx_hash = tx_hash!(expected_evicted_tx_hash);
+    assert!(mempool.tx_pool.get_by_tx_hash(evicted_tx_hash).is_err());
+    assert!(!mempool.tx_queue.iter_over_ready_txs().any(|tx| tx.tx_hash == evicted_tx_hash));
+    assert_eq!(mempool.tx_pool.len(), 3);
+}
+
+#[rstest]
+fn lowest_tip_policy_does_not_evict_higher_tips() {
+    let tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0, tip: 10);
+    let mut mempool = Mempool::new(
+        MempoolConfig {
+            capacity_in_bytes: tx.tx.total_bytes(),
+            eviction_policy: EvictionPolicy::LowestTip,
+            ..Default::default()
+        },
+        Arc::new(FakeClock::default()),
+    );
+    add_tx(&mut mempool, &tx);
+
+    let incoming_tx =
+        add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 10);
+    add_tx_expect_error(&mut mempool, &incoming_tx, MempoolError::MempoolFull);
+}
+
+#[rstest]
+fn eviction_policy_does_not_evict_staged_txs() {
+    let staged_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let mut mempool = Mempool::new(
+        MempoolConfig {
+            capacity_in_bytes: staged_tx.tx.total_bytes(),
+            eviction_policy: EvictionPolicy::OldestFirst,
+            ..Default::default()
+        },
+        Arc::new(FakeClock::default()),
+    );
+    add_tx(&mut mempool, &staged_tx);
+    get_txs_and_assert_expected(&mut mempool, 1, &[staged_tx.tx]);
+
+    let incoming_tx = add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    add_tx_expect_error(&mut mempool, &incoming_tx, MempoolError::MempoolFull);
+}
+
+#[rstest]
+fn gapped_txs_are_suspended_until_add_tx_fills_the_gap() {
+    let recorder = PrometheusBuilder::new().build_recorder();
+    let _recorder_guard = metrics::set_default_local_recorder(&recorder);
+    register_metrics();
+
+    let mut mempool = Mempool::new(MempoolConfig::default(), Arc::new(FakeClock::default()));
+    let tx_nonce_1 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let tx_nonce_2 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 2, account_nonce: 0);
+    for tx in [&tx_nonce_1, &tx_nonce_2] {
+        add_tx(&mut mempool, tx);
+    }
+    assert_eq!(
+        mempool.mempool_snapshot().unwrap().suspended_transactions,
+        vec![tx_nonce_1.tx.tx_hash, tx_nonce_2.tx.tx_hash]
+    );
+    get_txs_and_assert_expected(&mut mempool, 3, &[]);
+
+    let tx_nonce_0 = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &tx_nonce_0);
+
+    assert!(mempool.mempool_snapshot().unwrap().suspended_transactions.is_empty());
+    let metrics = &recorder.handle().render();
+    MEMPOOL_SUSPENDED_PROMOTIONS_COUNT.assert_eq(metrics, 2);
+    MEMPOOL_SUSPENDED_POOL_SIZE.assert_eq(metrics, 0);
+    get_txs_and_assert_expected(&mut mempool, 3, &[tx_nonce_0.tx, tx_nonce_1.tx, tx_nonce_2.tx]);
+}
+
+#[rstest]
+fn gapped_txs_are_promoted_when_commit_block_fills_the_gap() {
+    let mut mempool = Mempool::new(MempoolConfig::default(), Arc::new(FakeClock::default()));
+    let tx_nonce_2 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 2, account_nonce: 0);
+    let tx_nonce_3 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 3, account_nonce: 0);
+    for tx in [&tx_nonce_2, &tx_nonce_3] {
+        add_tx(&mut mempool, tx);
+    }
+
+    // The gap is only partially filled.
+    commit_block(&mut mempool, [("0x0", 1)], []);
+    assert_eq!(mempool.mempool_snapshot().unwrap().suspended_transactions.len(), 2);
+
+    commit_block(&mut mempool, [("0x0", 2)], []);
+    assert!(mempool.mempool_snapshot().unwrap().suspended_transactions.is_empty());
+    get_txs_and_assert_expected(&mut mempool, 2, &[tx_nonce_2.tx, tx_nonce_3.tx]);
+}
+
+#[rstest]
+fn suspended_txs_expire_by_their_own_ttl() {
+    let clock = Arc::new(FakeClock::default());
+    let mut mempool = Mempool::new(
+        MempoolConfig {
+            transaction_ttl: Duration::from_secs(60),
+            suspended_transaction_ttl: Duration::from_secs(10),
+            ..Default::default()
+        },
+        clock.clone(),
+    );
+    let suspended_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let queued_tx = add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    for tx in [&suspended_tx, &queued_tx] {
+        add_tx(&mut mempool, tx);
+    }
+
+    clock.advance(Duration::from_secs(11));
+    let trigger_tx = add_tx_input!(tx_hash: 3, address: "0x2", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &trigger_tx);
+
+    let snapshot = mempool.mempool_snapshot().unwrap();
+    assert!(snapshot.suspended_transactions.is_empty());
+    assert!(!snapshot.transactions.contains(&suspended_tx.tx.tx_hash));
+    assert!(snapshot.transactions.contains(&queued_tx.tx.tx_hash));
+}
+
+#[rstest]
+fn suspended_capacity_overflow_evicts_other_suspended_accounts() {
+    let suspended_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let mut mempool = Mempool::new(
+        MempoolConfig {
+            suspended_capacity_in_bytes: suspended_tx.tx.total_bytes(),
+            ..Default::default()
+        },
+        Arc::new(FakeClock::default()),
+    );
+    add_tx(&mut mempool, &suspended_tx);
+
+    // There is no other suspended account to evict.
+    let same_account_tx = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 2, account_nonce: 0);
+    add_tx_expect_error(&mut mempool, &same_account_tx, MempoolError::MempoolFull);
+
+    // Transactions which aren't suspended are not limited.
+    let queued_tx = add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &queued_tx);
+
+    let other_account_tx = add_tx_input!(tx_hash: 4, address: "0x2", tx_nonce: 1, account_nonce: 0);
+    add_tx(&mut mempool, &other_account_tx);
+    assert_eq!(
+        mempool.mempool_snapshot().unwrap().suspended_transactions,
+        vec![other_account_tx.tx.tx_hash]
+    );
+    assert!(mempool.tx_pool.get_by_tx_hash(suspended_tx.tx.tx_hash).is_err());
+}
+
+#[rstest]
+fn suspending_txs_on_commit_respects_suspended_capacity() {
+    let rejected_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let tx_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let tx_nonce_2 = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 2, account_nonce: 0);
+    let mut mempool = Mempool::new(
+        MempoolConfig {
+            suspended_capacity_in_bytes: tx_nonce_1.tx.total_bytes(),
+            ..Default::default()
+        },
+        Arc::new(FakeClock::default()),
+    );
+    for tx in [&rejected_tx, &tx_nonce_1, &tx_nonce_2] {
+        add_tx(&mut mempool, tx);
+    }
+
+    // Rejecting the first transaction creates a gap; only the next one fits among the suspended
+    // transactions, and the one depending on it is evicted.
+    commit_block(&mut mempool, [], [rejected_tx.tx.tx_hash]);
+
+    assert_eq!(
+        mempool.mempool_snapshot().unwrap().suspended_transactions,
+        vec![tx_nonce_1.tx.tx_hash]
+    );
+    assert_eq!(
+        mempool.get_tx_status(tx_nonce_2.tx.tx_hash),
+        Ok(MempoolTransactionStatus::Evicted(EvictionReason::CapacityExceeded))
+    );
+}
+
+#[rstest]
+fn get_tx_status_of_txs_in_mempool() {
+    let mut mempool = Mempool::new(MempoolConfig::default(), Arc::new(FakeClock::default()));
+    mempool.update_gas_price(GasPrice(100));
+    let queued_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let waiting_tx = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let pending_tx = add_tx_input!(
+        tx_hash: 3, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 0, max_l2_gas_price: 99
+    );
+    let gapped_tx = add_tx_input!(tx_hash: 4, address: "0x2", tx_nonce: 1, account_nonce: 0);
+    let delayed_declare = declare_add_tx_input(
+        declare_tx_args!(resource_bounds: test_valid_resource_bounds(), sender_address: contract_address!("0x3"), tx_hash: tx_hash!(5)),
+    );
+    for tx in [&queued_tx, &waiting_tx, &pending_tx, &gapped_tx, &delayed_declare] {
+        add_tx(&mut mempool, tx);
+    }
+
+    for (tx, expected_status) in [
+        (&queued_tx, MempoolTransactionStatus::Queued),
+        (&waiting_tx, MempoolTransactionStatus::Queued),
+        (&pending_tx, MempoolTransactionStatus::PendingBelowThreshold),
+        (&gapped_tx, MempoolTransactionStatus::Gapped),
+        (&delayed_declare, MempoolTransactionStatus::Queued),
+    ] {
+        assert_eq!(mempool.get_tx_status(tx.tx.tx_hash), Ok(expected_status));
+    }
+
+    // Transactions handed out for the block in progress are still queued.
+    get_txs_and_assert_expected(&mut mempool, 1, &[queued_tx.tx.clone()]);
+    assert_eq!(mempool.get_tx_status(queued_tx.tx.tx_hash), Ok(MempoolTransactionStatus::Queued));
+}
+
+#[rstest]
+fn get_tx_status_of_removed_txs() {
+    let clock = Arc::new(FakeClock::default());
+    let mut mempool = Mempool::new(
+        MempoolConfig { transaction_ttl: Duration::from_secs(60), ..Default::default() },
+        clock.clone(),
+    );
+    let expired_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &expired_tx);
+    clock.advance(Duration::from_secs(61));
+
+    let replaced_tx = add_tx_input!(
+        tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 10, max_l2_gas_price: 10
+    );
+    let replacing_tx = add_tx_input!(
+        tx_hash: 3, address: "0x1", tx_nonce: 0, account_nonce: 0, tip: 20, max_l2_gas_price: 20
+    );
+    let rejected_tx = add_tx_input!(tx_hash: 4, address: "0x2", tx_nonce: 0, account_nonce: 0);
+    for tx in [&replaced_tx, &replacing_tx, &rejected_tx] {
+        add_tx(&mut mempool, tx);
+    }
+    commit_block(&mut mempool, [], [rejected_tx.tx.tx_hash]);
+
+    // Fill the mempool, and evict a transaction with a gap to make space.
+    let evicted_tx = add_tx_input!(tx_hash: 5, address: "0x3", tx_nonce: 1, account_nonce: 0);
+    add_tx(&mut mempool, &evicted_tx);
+    mempool.config.capacity_in_bytes = mempool.tx_pool.size_in_bytes();
+    let incoming_tx = add_tx_input!(tx_hash: 6, address: "0x4", tx_nonce: 0, account_nonce: 0);
+    add_tx(&mut mempool, &incoming_tx);
+
+    for (tx, expected_status) in [
+        (&expired_tx, MempoolTransactionStatus::Expired),
+        (&replaced_tx, MempoolTransactionStatus::Evicted(EvictionReason::Replaced)),
+        (&rejected_tx, MempoolTransactionStatus::Evicted(EvictionReason::Rejected)),
+        (&evicted_tx, MempoolTransactionStatus::Evicted(EvictionReason::CapacityExceeded)),
+    ] {
+        assert_eq!(mempool.get_tx_status(tx.tx.tx_hash), Ok(expected_status));
+    }
+    assert_eq!(
+        mempool.get_tx_status(tx_hash!(7)),
+        Err(MempoolError::TransactionNotFound { tx_hash: tx_hash!(7) })
+    );
+}
+
+#[rstest]
+fn removed_tx_statuses_are_retained_up_to_the_configured_count() {
+    let mut mempool = Mempool::new(
+        MempoolConfig { removed_tx_status_retention_count: 1, ..Default::default() },
+        Arc::new(FakeClock::default()),
+    );
+    let first_rejected_tx =
+        add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let second_rejected_tx =
+        add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    for tx in [&first_rejected_tx, &second_rejected_tx] {
+        add_tx(&mut mempool, tx);
+        commit_block(&mut mempool, [], [tx.tx.tx_hash]);
+    }
+
+    assert_eq!(
+        mempool.get_tx_status(first_rejected_tx.tx.tx_hash),
+        Err(MempoolError::TransactionNotFound { tx_hash: first_rejected_tx.tx.tx_hash })
+    );
+    assert_eq!(
+        mempool.get_tx_status(second_rejected_tx.tx.tx_hash),
+        Ok(MempoolTransactionStatus::Evicted(EvictionReason::Rejected))
+    );
+}
+
+#[rstest]
+fn get_tx_by_hash_and_account_txs() {
+    let mut mempool = Mempool::new(MempoolConfig::default(), Arc::new(FakeClock::default()));
+    let delayed_declare = declare_add_tx_input(
+        declare_tx_args!(resource_bounds: test_valid_resource_bounds(), sender_address: contract_address!("0x0"), tx_hash: tx_hash!(1)),
+    );
+    let tx_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let other_account_tx = add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    for tx in [&tx_nonce_1, &delayed_declare, &other_account_tx] {
+        add_tx(&mut mempool, tx);
+    }
+
+    assert_eq!(mempool.get_tx_by_hash(tx_nonce_1.tx.tx_hash), Ok(&tx_nonce_1.tx));
+    assert_eq!(mempool.get_tx_by_hash(delayed_declare.tx.tx_hash), Ok(&delayed_declare.tx));
+    assert_eq!(
+        mempool.get_tx_by_hash(tx_hash!(4)),
+        Err(MempoolError::TransactionNotFound { tx_hash: tx_hash!(4) })
+    );
+
+    assert_eq!(
+        mempool.get_account_txs(contract_address!("0x0")),
+        vec![&delayed_declare.tx, &tx_nonce_1.tx]
+    );
+    assert!(mempool.get_account_txs(contract_address!("0x2")).is_empty());
+}
@@ -0,0 +1,137 @@
+//! Persistence of the mempool's transactions across restarts.
+//!
+//! Transactions accepted by the mempool are appended to a journal on disk, along with their
+//! submission times and the updates of the gas price threshold. Transactions leaving the mempool
+//! (committed, rejected, evicted, replaced or expired) are journaled as removals. Once the journal
+//! grows well beyond the mempool's content, it is compacted into a snapshot of that content.
+//!
+//! On startup, the journal is replayed into the mempool. Each restored transaction keeps its
+//! original submission time and is revalidated against the committed nonce of its account.
+
+#[cfg(test)]
+#[path = "persistence_test.rs"]
+mod persistence_test;
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use apollo_infra_utils::json_lines::{
+    read_records,
+    rewrite_records,
+    write_record,
+    JsonLinesError,
+    JsonLinesResult,
+};
+use apollo_mempool_types::mempool_types::AddTransactionArgs;
+use apollo_time::time::DateTime;
+use async_trait::async_trait;
+#[cfg(any(feature = "testing", test))]
+use mockall::automock;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::GasPrice;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::transaction::TransactionHash;
+
+/// A change to the mempool's content, which must survive a restart.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JournalEntry {
+    /// A transaction accepted by the mempool.
+    AddTransaction {
+        // Milliseconds since the Unix epoch.
+        submission_time: i64,
+        args: AddTransactionArgs,
+    },
+    /// The gas price threshold was updated.
+    GasPriceThreshold(GasPrice),
+    /// Transactions were removed from the mempool.
+    RemoveTransactions(Vec<TransactionHash>),
+}
+
+impl JournalEntry {
+    pub fn add_transaction(submission_time: DateTime, args: AddTransactionArgs) -> Self {
+        JournalEntry::AddTransaction { submission_time: submission_time.timestamp_millis(), args }
+    }
+}
+
+pub(crate) fn submission_time_from_millis(millis: i64) -> DateTime {
+    DateTime::from_timestamp_millis(millis).expect("Submission time should be in range.")
+}
+
+pub type JournalError = JsonLinesError;
+
+pub type JournalResult<T> = JsonLinesResult<T>;
+
+/// The on-disk journal of the mempool. Entries are stored one per line, as JSON.
+#[derive(Debug)]
+pub struct MempoolJournal {
+    path: PathBuf,
+    file: File,
+    // The number of entries in the journal.
+    n_entries: usize,
+}
+
+impl MempoolJournal {
+    /// Opens the journal at `path`, creating it if needed.
+    ///
+    /// Returns the entries previously written to it, in the order they were written.
+    pub fn open(path: &Path) -> JournalResult<(Self, Vec<JournalEntry>)> {
+        let entries: Vec<JournalEntry> = read_records(path)?;
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok((Self { path: path.to_path_buf(), file, n_entries: entries.len() }, entries))
+    }
+
+    /// Appends an entry to the journal. Returns only once the entry is persisted.
+    pub fn append(&mut self, entry: &JournalEntry) -> JournalResult<()> {
+        write_record(&mut self.file, entry)?;
+        self.file.sync_data()?;
+        self.n_entries += 1;
+        Ok(())
+    }
+
+    /// Replaces the contents of the journal with `entries`, atomically.
+    pub fn rewrite(&mut self, entries: &[JournalEntry]) -> JournalResult<()> {
+        self.file = rewrite_records(&self.path, entries)?;
+        self.n_entries = entries.len();
+        Ok(())
+    }
+
+    pub fn n_entries(&self) -> usize {
+        self.n_entries
+    }
+}
+
+/// Returns the entries which are still relevant, in their original order: the transactions which
+/// were added and not removed afterwards, and the updates of the gas price threshold.
+pub(crate) fn live_entries(entries: Vec<JournalEntry>) -> Vec<JournalEntry> {
+    let mut live_entries: Vec<Option<JournalEntry>> = Vec::with_capacity(entries.len());
+    let mut added_tx_indices = HashMap::new();
+    for entry in entries {
+        match entry {
+            JournalEntry::AddTransaction { ref args, .. } => {
+                added_tx_indices.insert(args.tx.tx_hash, live_entries.len());
+                live_entries.push(Some(entry));
+            }
+            JournalEntry::GasPriceThreshold(_) => live_entries.push(Some(entry)),
+            JournalEntry::RemoveTransactions(tx_hashes) => {
+                for tx_hash in tx_hashes {
+                    if let Some(index) = added_tx_indices.remove(&tx_hash) {
+                        live_entries[index] = None;
+                    }
+                }
+            }
+        }
+    }
+    live_entries.into_iter().flatten().collect()
+}
+
+/// Provides the nonces of accounts as of the latest committed block, used to revalidate the
+/// transactions restored from the journal.
+#[cfg_attr(any(feature = "testing", test), automock)]
+#[async_trait]
+pub trait AccountNonceReader: Send + Sync {
+    async fn get_account_nonce(&self, address: ContractAddress) -> Result<Nonce, String>;
+}
+
+pub type SharedAccountNonceReader = Arc<dyn AccountNonceReader>;
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use apollo_infra::component_definitions::ComponentRequestHandler;
+use apollo_mempool_p2p_types::communication::MockMempoolP2pPropagatorClient;
+use apollo_mempool_types::communication::{MempoolRequest, MempoolResponse};
+use apollo_time::test_utils::FakeClock;
+use apollo_time::time::Clock;
+use pretty_assertions::assert_eq;
+use starknet_api::block::GasPrice;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::{contract_address, nonce, tx_hash};
+
+use crate::add_tx_input;
+use crate::communication::MempoolCommunicationWrapper;
+use crate::config::MempoolConfig;
+use crate::mempool::Mempool;
+use crate::persistence::{live_entries, JournalEntry, MempoolJournal, MockAccountNonceReader};
+use crate::test_utils::{add_tx, commit_block, get_txs_and_assert_expected};
+
+/// Returns the hashes of the transactions the journal would restore.
+fn journal_tx_hashes(path: &Path) -> Vec<u8> {
+    let (_, entries) = MempoolJournal::open(path).unwrap();
+    live_entries(entries)
+        .into_iter()
+        .filter_map(|entry| match entry {
+            JournalEntry::AddTransaction { args, .. } => {
+                Some(args.tx.tx_hash.0.try_into().expect("Test tx hashes fit in a byte."))
+            }
+            JournalEntry::GasPriceThreshold(_) | JournalEntry::RemoveTransactions(_) => None,
+        })
+        .collect()
+}
+
+fn journal_n_entries(path: &Path) -> usize {
+    MempoolJournal::open(path).unwrap().0.n_entries()
+}
+
+/// Creates a mempool which restores from, and persists to, the journal at `path`.
+fn restored_mempool(
+    path: &Path,
+    clock: Arc<FakeClock>,
+    account_nonces: &HashMap<ContractAddress, Nonce>,
+) -> Mempool {
+    let config = MempoolConfig {
+        transaction_ttl: Duration::from_secs(60),
+        persistence_path: Some(path.to_path_buf()),
+        ..Default::default()
+    };
+    let mut mempool = Mempool::new(config, clock);
+    let (journal, entries) = MempoolJournal::open(path).unwrap();
+    mempool.restore(journal, entries, account_nonces);
+    mempool
+}
+
+#[test]
+fn journal_reopens_with_appended_and_rewritten_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let clock = FakeClock::default();
+    let tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let add_tx_entry = JournalEntry::add_transaction(clock.now(), tx);
+    let threshold_entry = JournalEntry::GasPriceThreshold(GasPrice(7));
+
+    let (mut journal, entries) = MempoolJournal::open(&path).unwrap();
+    assert!(entries.is_empty());
+    journal.append(&add_tx_entry).unwrap();
+    journal.append(&threshold_entry).unwrap();
+    drop(journal);
+
+    let (mut journal, entries) = MempoolJournal::open(&path).unwrap();
+    assert_eq!(entries, vec![add_tx_entry.clone(), threshold_entry.clone()]);
+
+    journal.rewrite(&[threshold_entry.clone()]).unwrap();
+    journal.append(&add_tx_entry).unwrap();
+    drop(journal);
+
+    let (_, entries) = MempoolJournal::open(&path).unwrap();
+    assert_eq!(entries, vec![threshold_entry, add_tx_entry]);
+}
+
+#[test]
+fn restart_restores_txs_and_gas_price_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let clock = Arc::new(FakeClock::default());
+    let tx_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let tx_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let tx_with_gap = add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 5, account_nonce: 0);
+
+    let mut mempool = restored_mempool(&path, clock.clone(), &HashMap::new());
+    for tx in [&tx_nonce_0, &tx_nonce_1, &tx_with_gap] {
+        add_tx(&mut mempool, tx);
+    }
+    mempool.update_gas_price(GasPrice(1));
+    let snapshot = mempool.mempool_snapshot().unwrap();
+
+    let mut restarted_mempool = restored_mempool(&path, clock, &HashMap::new());
+
+    assert_eq!(restarted_mempool.mempool_snapshot().unwrap(), snapshot);
+    get_txs_and_assert_expected(&mut restarted_mempool, 3, &[tx_nonce_0.tx, tx_nonce_1.tx]);
+}
+
+#[test]
+fn restart_keeps_original_submission_times() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let clock = Arc::new(FakeClock::default());
+    let old_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let new_tx = add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0);
+
+    let mut mempool = restored_mempool(&path, clock.clone(), &HashMap::new());
+    add_tx(&mut mempool, &old_tx);
+    clock.advance(Duration::from_secs(30));
+    add_tx(&mut mempool, &new_tx);
+
+    // The old transaction expires while the node is down, and the new one shortly after it is up.
+    clock.advance(Duration::from_secs(40));
+    let mut restarted_mempool = restored_mempool(&path, clock.clone(), &HashMap::new());
+    assert_eq!(restarted_mempool.mempool_snapshot().unwrap().transactions, vec![new_tx.tx.tx_hash]);
+
+    clock.advance(Duration::from_secs(30));
+    get_txs_and_assert_expected(&mut restarted_mempool, 2, &[]);
+}
+
+#[test]
+fn restart_revalidates_txs_against_committed_nonces() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let clock = Arc::new(FakeClock::default());
+    let tx_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let tx_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let tx_nonce_2 = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 2, account_nonce: 0);
+
+    let mut mempool = restored_mempool(&path, clock.clone(), &HashMap::new());
+    for tx in [&tx_nonce_0, &tx_nonce_1, &tx_nonce_2] {
+        add_tx(&mut mempool, tx);
+    }
+
+    // The first two transactions were included in blocks this node didn't see.
+    let account_nonces = HashMap::from([(contract_address!("0x0"), nonce!(2))]);
+    let mut restarted_mempool = restored_mempool(&path, clock, &account_nonces);
+
+    get_txs_and_assert_expected(&mut restarted_mempool, 3, &[tx_nonce_2.tx]);
+    assert_eq!(journal_tx_hashes(&path), vec![3]);
+}
+
+#[test]
+fn removed_txs_are_not_restored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let removed_tx = add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    let journal_entries = vec![
+        JournalEntry::add_transaction(FakeClock::default().now(), tx.clone()),
+        JournalEntry::add_transaction(FakeClock::default().now(), removed_tx),
+        JournalEntry::RemoveTransactions(vec![tx_hash!(2)]),
+    ];
+    let (mut journal, _) = MempoolJournal::open(&path).unwrap();
+    journal.rewrite(&journal_entries).unwrap();
+    drop(journal);
+
+    let restarted_mempool =
+        restored_mempool(&path, Arc::new(FakeClock::default()), &HashMap::new());
+
+    assert_eq!(restarted_mempool.mempool_snapshot().unwrap().transactions, vec![tx.tx.tx_hash]);
+}
+
+#[test]
+fn expired_txs_are_journaled_as_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let clock = Arc::new(FakeClock::default());
+    let old_tx = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let new_tx = add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0);
+
+    let mut mempool = restored_mempool(&path, clock.clone(), &HashMap::new());
+    add_tx(&mut mempool, &old_tx);
+    clock.advance(Duration::from_secs(70));
+    add_tx(&mut mempool, &new_tx);
+    get_txs_and_assert_expected(&mut mempool, 2, &[new_tx.tx]);
+
+    // The returned transaction stays in the pool until it is committed.
+    assert_eq!(journal_tx_hashes(&path), vec![2]);
+}
+
+#[test]
+fn commit_block_journals_removals_without_compacting() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let clock = Arc::new(FakeClock::default());
+    let tx_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let tx_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+    let rejected_tx = add_tx_input!(tx_hash: 3, address: "0x1", tx_nonce: 0, account_nonce: 0);
+
+    let mut mempool = restored_mempool(&path, clock, &HashMap::new());
+    for tx in [&tx_nonce_0, &tx_nonce_1, &rejected_tx] {
+        add_tx(&mut mempool, tx);
+    }
+    assert_eq!(journal_tx_hashes(&path), vec![1, 2, 3]);
+
+    let n_entries_before_commit = journal_n_entries(&path);
+
+    commit_block(&mut mempool, [("0x0", 1)], [rejected_tx.tx.tx_hash]);
+
+    assert_eq!(journal_tx_hashes(&path), vec![2]);
+    // The removals are appended as a single entry, rather than rewriting the journal.
+    assert_eq!(journal_n_entries(&path), n_entries_before_commit + 1);
+}
+
+#[tokio::test]
+async fn start_restores_txs_with_nonces_from_reader() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mempool_journal");
+    let clock = Arc::new(FakeClock::default());
+    let tx_nonce_0 = add_tx_input!(tx_hash: 1, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    let tx_nonce_1 = add_tx_input!(tx_hash: 2, address: "0x0", tx_nonce: 1, account_nonce: 0);
+
+    let mut mempool = restored_mempool(&path, clock.clone(), &HashMap::new());
+    add_tx(&mut mempool, &tx_nonce_0);
+    add_tx(&mut mempool, &tx_nonce_1);
+
+    let mut account_nonce_reader = MockAccountNonceReader::new();
+    account_nonce_reader
+        .expect_get_account_nonce()
+        .withf(|address| *address == contract_address!("0x0"))
+        .times(1)
+        .returning(|_| Ok(nonce!(1)));
+    let config = MempoolConfig { persistence_path: Some(path), ..Default::default() };
+    let mut mempool_wrapper = MempoolCommunicationWrapper::new(
+        Mempool::new(config, clock),
+        Arc::new(MockMempoolP2pPropagatorClient::new()),
+        Some(Arc::new(account_nonce_reader)),
+    );
+    mempool_wrapper.restore_persisted_txs().await;
+
+    let response = mempool_wrapper.handle_request(MempoolRequest::GetMempoolSnapshot()).await;
+    let MempoolResponse::GetMempoolSnapshot(Ok(snapshot)) = response else {
+        panic!("Unexpected response: {response:?}");
+    };
+    assert_eq!(snapshot.transactions, vec![tx_nonce_1.tx.tx_hash]);
+}
@@ -1,30 +1,200 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
+use apollo_time::time::DateTime;
+use indexmap::IndexMap;
 use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::transaction::TransactionHash;
 
 use crate::mempool::TransactionReference;
 
-type _AddressNonceToTransaction = HashMap<(ContractAddress, Nonce), TransactionReference>;
+#[derive(Clone, Copy, Debug)]
+struct SuspendedTransaction {
+    tx_reference: TransactionReference,
+    size_in_bytes: u64,
+    suspension_time: DateTime,
+}
 
+/// Tracks the transactions of accounts with a nonce gap, which cannot be sequenced until the gap is
+/// filled. The transactions themselves remain in the transaction pool; this tier holds them apart
+/// from the sequenceable ones, with its own TTL and capacity, until they are promoted.
 #[derive(Debug)]
-pub struct _SuspendedTransactionPool {
-    suspended_tx_pool: _AddressNonceToTransaction,
+pub struct SuspendedTransactionPool {
+    // Indexed by account, so that a random account can be chosen for eviction.
+    txs_by_account: IndexMap<ContractAddress, BTreeMap<Nonce, SuspendedTransaction>>,
+    txs_by_suspension_time: BTreeMap<(DateTime, TransactionHash), TransactionReference>,
+    size_in_bytes: u64,
+    capacity_in_bytes: u64,
 }
 
-impl _SuspendedTransactionPool {
-    pub fn _contains(&self, address: ContractAddress, nonce: Nonce) -> bool {
-        self.suspended_tx_pool.contains_key(&(address, nonce))
+impl SuspendedTransactionPool {
+    pub fn new(capacity_in_bytes: u64) -> Self {
+        SuspendedTransactionPool {
+            txs_by_account: IndexMap::new(),
+            txs_by_suspension_time: BTreeMap::new(),
+            size_in_bytes: 0,
+            capacity_in_bytes,
+        }
+    }
+
+    pub fn contains(&self, tx_reference: &TransactionReference) -> bool {
+        self.txs_by_account.get(&tx_reference.address).is_some_and(|account_txs| {
+            account_txs
+                .get(&tx_reference.nonce)
+                .is_some_and(|tx| tx.tx_reference.tx_hash == tx_reference.tx_hash)
+        })
+    }
+
+    pub fn contains_account(&self, address: ContractAddress) -> bool {
+        self.txs_by_account.contains_key(&address)
+    }
+
+    /// Returns true if suspending a transaction of the given size would exceed the capacity.
+    pub fn exceeds_capacity(&self, size_in_bytes: u64) -> bool {
+        self.size_in_bytes + size_in_bytes > self.capacity_in_bytes
     }
 
-    pub fn _insert(&mut self, tx: TransactionReference) {
-        assert_eq!(
-            self.suspended_tx_pool.insert((tx.address, tx.nonce), tx),
-            None,
-            "Keys should be unique; duplicates are checked prior."
+    /// Suspends the given transaction. Returns false if it was already suspended.
+    ///
+    /// Panics if the transaction doesn't fit in the capacity; callers make space beforehand.
+    pub fn insert(
+        &mut self,
+        tx_reference: TransactionReference,
+        size_in_bytes: u64,
+        suspension_time: DateTime,
+    ) -> bool {
+        let account_txs = self.txs_by_account.entry(tx_reference.address).or_default();
+        if let Some(existing_tx) = account_txs.get(&tx_reference.nonce) {
+            assert_eq!(
+                existing_tx.tx_reference.tx_hash, tx_reference.tx_hash,
+                "Replaced transactions should be removed prior to suspending their replacement."
+            );
+            return false;
+        }
+        assert!(
+            self.size_in_bytes + size_in_bytes <= self.capacity_in_bytes,
+            "Suspended transactions should not exceed their capacity."
+        );
+
+        account_txs.insert(
+            tx_reference.nonce,
+            SuspendedTransaction { tx_reference, size_in_bytes, suspension_time },
         );
+        self.txs_by_suspension_time.insert((suspension_time, tx_reference.tx_hash), tx_reference);
+        self.size_in_bytes = self
+            .size_in_bytes
+            .checked_add(size_in_bytes)
+            .expect("Overflow when adding to the suspended transaction pool size.");
+        true
+    }
+
+    /// Removes the given transaction, if suspended. Returns true if it was removed.
+    pub fn remove(&mut self, tx_reference: &TransactionReference) -> bool {
+        let Some(account_txs) = self.txs_by_account.get_mut(&tx_reference.address) else {
+            return false;
+        };
+        if account_txs
+            .get(&tx_reference.nonce)
+            .is_none_or(|tx| tx.tx_reference.tx_hash != tx_reference.tx_hash)
+        {
+            return false;
+        }
+
+        let removed_tx =
+            account_txs.remove(&tx_reference.nonce).expect("Suspended transaction should exist.");
+        if account_txs.is_empty() {
+            self.txs_by_account.swap_remove(&tx_reference.address);
+        }
+        self.remove_from_timed_mapping(&removed_tx);
+        true
+    }
+
+    /// Removes the suspended transaction of the given account with the highest nonce, and returns
+    /// it along with its size.
+    pub fn remove_last_account_tx(
+        &mut self,
+        address: ContractAddress,
+    ) -> Option<(TransactionReference, u64)> {
+        let account_txs = self.txs_by_account.get_mut(&address)?;
+        let (_, removed_tx) =
+            account_txs.pop_last().expect("Suspended accounts should have transactions.");
+        if account_txs.is_empty() {
+            self.txs_by_account.swap_remove(&address);
+        }
+        self.remove_from_timed_mapping(&removed_tx);
+        Some((removed_tx.tx_reference, removed_tx.size_in_bytes))
+    }
+
+    /// Removes all the suspended transactions of the given account, and returns them.
+    pub fn remove_account(&mut self, address: ContractAddress) -> Vec<TransactionReference> {
+        let Some(account_txs) = self.txs_by_account.swap_remove(&address) else {
+            return Vec::new();
+        };
+        account_txs
+            .into_values()
+            .map(|removed_tx| {
+                self.remove_from_timed_mapping(&removed_tx);
+                removed_tx.tx_reference
+            })
+            .collect()
+    }
+
+    /// Removes the transactions suspended before the given time, and returns them.
+    pub fn remove_txs_suspended_before(
+        &mut self,
+        cutoff_time: DateTime,
+    ) -> Vec<TransactionReference> {
+        let expired_txs: Vec<_> = self
+            .txs_by_suspension_time
+            .range(..(cutoff_time, TransactionHash::default()))
+            .map(|(_, tx_reference)| *tx_reference)
+            .collect();
+        for tx_reference in &expired_txs {
+            self.remove(tx_reference);
+        }
+        expired_txs
+    }
+
+    pub fn account_txs(
+        &self,
+        address: ContractAddress,
+    ) -> impl Iterator<Item = &TransactionReference> {
+        self.txs_by_account.get(&address).into_iter().flat_map(|account_txs| {
+            account_txs.values().map(|suspended_tx| &suspended_tx.tx_reference)
+        })
+    }
+
+    pub fn accounts(&self) -> impl Iterator<Item = ContractAddress> + '_ {
+        self.txs_by_account.keys().copied()
+    }
+
+    pub fn n_accounts(&self) -> usize {
+        self.txs_by_account.len()
+    }
+
+    pub fn get_account_by_index(&self, index: usize) -> Option<ContractAddress> {
+        self.txs_by_account.get_index(index).map(|(address, _)| *address)
+    }
+
+    pub fn len(&self) -> usize {
+        self.txs_by_suspension_time.len()
+    }
+
+    pub fn size_in_bytes(&self) -> u64 {
+        self.size_in_bytes
+    }
+
+    /// Returns the hashes of the suspended transactions, ordered by suspension time (oldest first).
+    pub fn chronological_txs_hashes(&self) -> Vec<TransactionHash> {
+        self.txs_by_suspension_time.keys().map(|(_, tx_hash)| *tx_hash).collect()
     }
 
-    pub fn _remove(&mut self, tx: &TransactionReference) -> bool {
-        self.suspended_tx_pool.remove(&(tx.address, tx.nonce)).is_some()
+    fn remove_from_timed_mapping(&mut self, removed_tx: &SuspendedTransaction) {
+        self.txs_by_suspension_time
+            .remove(&(removed_tx.suspension_time, removed_tx.tx_reference.tx_hash))
+            .expect("Suspended transaction should appear in the timed mapping.");
+        self.size_in_bytes = self
+            .size_in_bytes
+            .checked_sub(removed_tx.size_in_bytes)
+            .expect("Underflow when subtracting from the suspended transaction pool size.");
     }
 }
@@ -20,6 +20,9 @@ use crate::metrics::{
     MEMPOOL_PENDING_QUEUE_SIZE,
     MEMPOOL_POOL_SIZE,
     MEMPOOL_PRIORITY_QUEUE_SIZE,
+    MEMPOOL_SUSPENDED_POOL_SIZE,
+    MEMPOOL_SUSPENDED_PROMOTIONS_COUNT,
+    MEMPOOL_SUSPENDED_SIZE_BYTES,
     MEMPOOL_TOTAL_SIZE_BYTES,
     MEMPOOL_TRANSACTIONS_COMMITTED,
     MEMPOOL_TRANSACTIONS_DROPPED,
@@ -296,6 +299,9 @@ pub struct MempoolMetrics {
     pub delayed_declares_size: u64,
     pub total_size_in_bytes: u64,
     pub evictions_count: u64,
+    pub suspended_pool_size: u64,
+    pub suspended_size_in_bytes: u64,
+    pub suspended_promotions_count: u64,
     pub transaction_time_spent_in_mempool: HistogramValue,
 }
 
@@ -340,6 +346,9 @@ impl MempoolMetrics {
         MEMPOOL_GET_TXS_SIZE.assert_eq(metrics, self.get_txs_size);
         MEMPOOL_DELAYED_DECLARES_SIZE.assert_eq(metrics, self.delayed_declares_size);
         MEMPOOL_TOTAL_SIZE_BYTES.assert_eq(metrics, self.total_size_in_bytes);
+        MEMPOOL_SUSPENDED_POOL_SIZE.assert_eq(metrics, self.suspended_pool_size);
+        MEMPOOL_SUSPENDED_SIZE_BYTES.assert_eq(metrics, self.suspended_size_in_bytes);
+        MEMPOOL_SUSPENDED_PROMOTIONS_COUNT.assert_eq(metrics, self.suspended_promotions_count);
         TRANSACTION_TIME_SPENT_IN_MEMPOOL
             .assert_eq(metrics, &self.transaction_time_spent_in_mempool);
     }
@@ -33,6 +33,8 @@ pub struct TransactionPool {
     txs_by_submission_time: TimedTransactionMap,
     // Tracks the size of the pool.
     size: PoolSize,
+    // Tracks the size of the transactions of each account in the pool.
+    size_by_account: HashMap<ContractAddress, PoolSize>,
 }
 
 impl TransactionPool {
@@ -42,6 +44,7 @@ impl TransactionPool {
             txs_by_account: AccountTransactionIndex::default(),
             txs_by_submission_time: TimedTransactionMap::new(clock),
             size: PoolSize::default(),
+            size_by_account: HashMap::new(),
         }
     }
 
@@ -53,7 +56,19 @@ impl TransactionPool {
         self.size.size_in_bytes()
     }
 
+    #[cfg(test)]
     pub fn insert(&mut self, tx: InternalRpcTransaction) -> MempoolResult<()> {
+        let submission_time = self.txs_by_submission_time.clock.now();
+        self.insert_with_submission_time(tx, submission_time)
+    }
+
+    /// Inserts a transaction which was submitted at the given time, e.g., one restored after a
+    /// restart.
+    pub fn insert_with_submission_time(
+        &mut self,
+        tx: InternalRpcTransaction,
+        submission_time: DateTime,
+    ) -> MempoolResult<()> {
         let tx_reference = TransactionReference::new(&tx);
         let tx_hash = tx_reference.tx_hash;
         let tx_size = tx.total_bytes();
@@ -76,7 +91,8 @@ impl TransactionPool {
         };
 
         // Insert to timed mapping.
-        let unexpected_existing_tx = self.txs_by_submission_time.insert(tx_reference);
+        let unexpected_existing_tx =
+            self.txs_by_submission_time.insert(tx_reference, submission_time);
         if unexpected_existing_tx.is_some() {
             panic!(
                 "Transaction pool consistency error: transaction with hash {tx_hash} does not
@@ -86,6 +102,7 @@ impl TransactionPool {
         };
 
         self.size.add(tx_size);
+        self.size_by_account.entry(tx_reference.address).or_default().add(tx_size);
 
         Ok(())
     }
@@ -100,18 +117,22 @@ impl TransactionPool {
         self.remove_from_account_mapping(&removed_tx);
         self.remove_from_timed_mapping(&removed_tx);
 
-        self.size.remove(tx.total_bytes());
+        self.remove_size(tx.contract_address(), tx.total_bytes());
 
         Ok(tx)
     }
 
-    pub fn remove_up_to_nonce(&mut self, address: ContractAddress, nonce: Nonce) -> usize {
+    pub fn remove_up_to_nonce(
+        &mut self,
+        address: ContractAddress,
+        nonce: Nonce,
+    ) -> Vec<TransactionReference> {
         let removed_txs = self.txs_by_account.remove_up_to_nonce(address, nonce);
 
         self.remove_from_main_mapping(&removed_txs);
         self.remove_from_timed_mapping(&removed_txs);
 
-        removed_txs.len()
+        removed_txs
     }
 
     pub fn remove_txs_older_than(
@@ -174,6 +195,21 @@ impl TransactionPool {
         self.account_txs_sorted_by_nonce(address).next().map(|tx_ref| tx_ref.nonce)
     }
 
+    /// Returns the number of transactions of the given account in the pool.
+    pub fn account_len(&self, address: ContractAddress) -> usize {
+        self.txs_by_account.account_len(address)
+    }
+
+    /// Returns the total size of the transactions of the given account in the pool, in bytes.
+    pub fn account_size_in_bytes(&self, address: ContractAddress) -> u64 {
+        self.size_by_account.get(&address).map_or(0, PoolSize::size_in_bytes)
+    }
+
+    /// Returns the transaction with the highest nonce of each account in the pool.
+    pub fn last_account_txs(&self) -> impl Iterator<Item = &TransactionReference> {
+        self.txs_by_account.last_account_txs()
+    }
+
     fn remove_from_main_mapping(&mut self, removed_txs: &Vec<TransactionReference>) {
         for TransactionReference { tx_hash, .. } in removed_txs {
             let tx = self.tx_pool.remove(tx_hash).unwrap_or_else(|| {
@@ -182,7 +218,19 @@ impl TransactionPool {
                      appear in the main mapping.",
                 )
             });
-            self.size.remove(tx.total_bytes());
+            self.remove_size(tx.contract_address(), tx.total_bytes());
+        }
+    }
+
+    fn remove_size(&mut self, address: ContractAddress, tx_size: u64) {
+        self.size.remove(tx_size);
+        let hash_map::Entry::Occupied(mut account_size) = self.size_by_account.entry(address)
+        else {
+            panic!("Transaction pool consistency error: account {address} has no size tracked.");
+        };
+        account_size.get_mut().remove(tx_size);
+        if account_size.get().size_in_bytes() == 0 {
+            account_size.remove();
         }
     }
 
@@ -280,6 +328,14 @@ impl AccountTransactionIndex {
     fn contains(&self, address: ContractAddress) -> bool {
         self.0.contains_key(&address)
     }
+
+    fn account_len(&self, address: ContractAddress) -> usize {
+        self.0.get(&address).map_or(0, BTreeMap::len)
+    }
+
+    fn last_account_txs(&self) -> impl Iterator<Item = &TransactionReference> {
+        self.0.values().filter_map(|nonce_to_tx_ref| nonce_to_tx_ref.values().next_back())
+    }
 }
 
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
@@ -350,8 +406,12 @@ impl TimedTransactionMap {
 
     /// If a transaction with the same transaction hash already exists in the mapping, the previous
     /// submission ID is returned.
-    fn insert(&mut self, tx: TransactionReference) -> Option<SubmissionID> {
-        let submission_id = SubmissionID { submission_time: self.clock.now(), tx_hash: tx.tx_hash };
+    fn insert(
+        &mut self,
+        tx: TransactionReference,
+        submission_time: DateTime,
+    ) -> Option<SubmissionID> {
+        let submission_id = SubmissionID { submission_time, tx_hash: tx.tx_hash };
         self.txs_by_submission_time.insert(submission_id.clone(), tx);
         self.hash_to_submission_id.insert(tx.tx_hash, submission_id)
     }
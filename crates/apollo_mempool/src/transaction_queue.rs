@@ -78,6 +78,12 @@ impl TransactionQueue {
         self.priority_queue.iter().rev().map(|tx| &tx.0)
     }
 
+    /// Returns true if the given transaction is in the pending queue, i.e., below the gas price
+    /// threshold.
+    pub fn is_pending(&self, tx_reference: &TransactionReference) -> bool {
+        self.pending_queue.contains(&(*tx_reference).into())
+    }
+
     pub fn get_nonce(&self, address: ContractAddress) -> Option<Nonce> {
         self.address_to_tx.get(&address).map(|tx| tx.nonce)
     }
@@ -111,6 +117,10 @@ impl TransactionQueue {
         !self.priority_queue.is_empty()
     }
 
+    pub fn gas_price_threshold(&self) -> GasPrice {
+        self.gas_price_threshold
+    }
+
     pub fn update_gas_price_threshold(&mut self, threshold: GasPrice) {
         match threshold.cmp(&self.gas_price_threshold) {
             Ordering::Less => self.promote_txs_to_priority(threshold),
@@ -12,11 +12,17 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::GasPrice;
 use starknet_api::core::ContractAddress;
 use starknet_api::rpc_transaction::InternalRpcTransaction;
+use starknet_api::transaction::TransactionHash;
 use strum_macros::AsRefStr;
 use thiserror::Error;
 
 use crate::errors::MempoolError;
-use crate::mempool_types::{AddTransactionArgs, CommitBlockArgs, MempoolSnapshot};
+use crate::mempool_types::{
+    AddTransactionArgs,
+    CommitBlockArgs,
+    MempoolSnapshot,
+    MempoolTransactionStatus,
+};
 
 pub type LocalMempoolClient = LocalComponentClient<MempoolRequest, MempoolResponse>;
 pub type RemoteMempoolClient = RemoteComponentClient<MempoolRequest, MempoolResponse>;
@@ -48,6 +54,19 @@ pub trait MempoolClient: Send + Sync {
     ) -> MempoolClientResult<bool>;
     async fn update_gas_price(&self, gas_price: GasPrice) -> MempoolClientResult<()>;
     async fn get_mempool_snapshot(&self) -> MempoolClientResult<MempoolSnapshot>;
+    async fn get_tx_by_hash(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<InternalRpcTransaction>;
+    /// Returns the transactions of the given account held in the mempool, sorted by nonce.
+    async fn get_account_txs(
+        &self,
+        contract_address: ContractAddress,
+    ) -> MempoolClientResult<Vec<InternalRpcTransaction>>;
+    async fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<MempoolTransactionStatus>;
 }
 
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
@@ -59,6 +78,9 @@ pub enum MempoolRequest {
     // TODO(yair): Rename to `StartBlock` and add cleanup of staged txs.
     UpdateGasPrice(GasPrice),
     GetMempoolSnapshot(),
+    GetTransactionByHash(TransactionHash),
+    GetAccountTransactions(ContractAddress),
+    GetTransactionStatus(TransactionHash),
 }
 impl_debug_for_infra_requests_and_responses!(MempoolRequest);
 
@@ -70,6 +92,9 @@ pub enum MempoolResponse {
     AccountTxInPoolOrRecentBlock(MempoolResult<bool>),
     UpdateGasPrice(MempoolResult<()>),
     GetMempoolSnapshot(MempoolResult<MempoolSnapshot>),
+    GetTransactionByHash(MempoolResult<InternalRpcTransaction>),
+    GetAccountTransactions(MempoolResult<Vec<InternalRpcTransaction>>),
+    GetTransactionStatus(MempoolResult<MempoolTransactionStatus>),
 }
 impl_debug_for_infra_requests_and_responses!(MempoolResponse);
 
@@ -154,4 +179,46 @@ where
             Direct
         )
     }
+
+    async fn get_tx_by_hash(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<InternalRpcTransaction> {
+        let request = MempoolRequest::GetTransactionByHash(tx_hash);
+        handle_all_response_variants!(
+            MempoolResponse,
+            GetTransactionByHash,
+            MempoolClientError,
+            MempoolError,
+            Direct
+        )
+    }
+
+    async fn get_account_txs(
+        &self,
+        contract_address: ContractAddress,
+    ) -> MempoolClientResult<Vec<InternalRpcTransaction>> {
+        let request = MempoolRequest::GetAccountTransactions(contract_address);
+        handle_all_response_variants!(
+            MempoolResponse,
+            GetAccountTransactions,
+            MempoolClientError,
+            MempoolError,
+            Direct
+        )
+    }
+
+    async fn get_tx_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> MempoolClientResult<MempoolTransactionStatus> {
+        let request = MempoolRequest::GetTransactionStatus(tx_hash);
+        handle_all_response_variants!(
+            MempoolResponse,
+            GetTransactionStatus,
+            MempoolClientError,
+            MempoolError,
+            Direct
+        )
+    }
 }
@@ -19,4 +19,24 @@ pub enum MempoolError {
     TransactionNotFound { tx_hash: TransactionHash },
     #[error("Transaction rejected: mempool capacity exceeded.")]
     MempoolFull,
+    #[error(
+        "Transaction rejected: account {address} exceeds the limit of {max_txs} transactions in \
+         the mempool."
+    )]
+    AccountTransactionLimitExceeded { address: ContractAddress, max_txs: usize },
+    #[error(
+        "Transaction rejected: account {address} exceeds the limit of {max_bytes} bytes in the \
+         mempool."
+    )]
+    AccountCapacityExceeded { address: ContractAddress, max_bytes: u64 },
+    #[error(
+        "Transaction nonce is too far ahead of the account nonce. Account nonce: {account_nonce}, \
+         got: {tx_nonce}, at most {max_future_nonces} nonces ahead are allowed."
+    )]
+    NonceTooFarAhead {
+        address: ContractAddress,
+        tx_nonce: Nonce,
+        account_nonce: Nonce,
+        max_future_nonces: u64,
+    },
 }
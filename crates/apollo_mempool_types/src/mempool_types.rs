@@ -41,6 +41,8 @@ pub type MempoolResult<T> = Result<T, MempoolError>;
 pub struct MempoolSnapshot {
     pub transactions: Vec<TransactionHash>,
     pub delayed_declares: Vec<TransactionHash>,
+    // Transactions of accounts with a nonce gap, ordered by suspension time (oldest first).
+    pub suspended_transactions: Vec<TransactionHash>,
     pub transaction_queue: TransactionQueueSnapshot,
     pub mempool_state: MempoolStateSnapshot,
 }
@@ -57,3 +59,31 @@ pub struct MempoolStateSnapshot {
     pub committed: HashMap<ContractAddress, Nonce>,
     pub staged: HashMap<ContractAddress, Nonce>,
 }
+
+/// The status of a transaction received by the mempool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MempoolTransactionStatus {
+    /// Waiting to be sequenced: ready, behind lower nonces of its account, or already handed out
+    /// for the block in progress.
+    Queued,
+    /// Next to be sequenced for its account, but its max L2 gas price is below the gas price
+    /// threshold.
+    PendingBelowThreshold,
+    /// Waiting for a missing lower nonce of its account.
+    Gapped,
+    /// Removed from the mempool before being included in a block.
+    Evicted(EvictionReason),
+    /// Removed from the mempool since it was not included in a block within its time-to-live.
+    Expired,
+}
+
+/// The reason a transaction was removed from the mempool before being included in a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionReason {
+    /// Evicted to make space for other transactions.
+    CapacityExceeded,
+    /// Replaced by a transaction with the same nonce and higher fees.
+    Replaced,
+    /// Rejected during block building.
+    Rejected,
+}
@@ -1,17 +1,22 @@
 use std::net::SocketAddr;
 
+use apollo_consensus::evidence::{Evidence, EvidencePool};
+use apollo_consensus::types::ValidatorId;
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_infra_utils::type_name::short_type_name;
 use apollo_l1_provider_types::{L1ProviderSnapshot, SharedL1ProviderClient};
 use apollo_mempool_types::communication::SharedMempoolClient;
 use apollo_mempool_types::mempool_types::MempoolSnapshot;
 use apollo_metrics::metrics::COLLECT_SEQUENCER_PROFILING_METRICS;
+use axum::extract::Query;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum::{async_trait, Json, Router, Server};
 use hyper::Error;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use serde::Deserialize;
+use starknet_api::block::BlockNumber;
 use tracing::{error, info, instrument};
 
 use crate::config::MonitoringEndpointConfig;
@@ -27,6 +32,7 @@ pub(crate) const VERSION: &str = "nodeVersion";
 pub(crate) const METRICS: &str = "metrics";
 pub(crate) const MEMPOOL_SNAPSHOT: &str = "mempoolSnapshot";
 pub(crate) const L1_PROVIDER_SNAPSHOT: &str = "l1ProviderSnapshot";
+pub(crate) const CONSENSUS_EVIDENCE: &str = "consensusEvidence";
 
 const HISTOGRAM_BUCKETS: &[f64] =
     &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0];
@@ -37,6 +43,7 @@ pub struct MonitoringEndpoint {
     prometheus_handle: Option<PrometheusHandle>,
     mempool_client: Option<SharedMempoolClient>,
     l1_provider_client: Option<SharedL1ProviderClient>,
+    evidence_pool: Option<EvidencePool>,
 }
 
 impl MonitoringEndpoint {
@@ -45,6 +52,7 @@ impl MonitoringEndpoint {
         version: &'static str,
         mempool_client: Option<SharedMempoolClient>,
         l1_provider_client: Option<SharedL1ProviderClient>,
+        evidence_pool: Option<EvidencePool>,
     ) -> Self {
         // TODO(Tsabary): consider error handling
         let prometheus_handle = if config.collect_metrics {
@@ -70,6 +78,7 @@ impl MonitoringEndpoint {
             prometheus_handle,
             mempool_client,
             l1_provider_client,
+            evidence_pool,
         }
     }
 
@@ -95,6 +104,7 @@ impl MonitoringEndpoint {
         let prometheus_handle = self.prometheus_handle.clone();
         let mempool_client = self.mempool_client.clone();
         let l1_provider_client = self.l1_provider_client.clone();
+        let evidence_pool = self.evidence_pool.clone();
 
         Router::new()
             .route(
@@ -121,6 +131,10 @@ impl MonitoringEndpoint {
                 format!("/{MONITORING_PREFIX}/{L1_PROVIDER_SNAPSHOT}").as_str(),
                 get(move || get_l1_provider_snapshot(l1_provider_client)),
             )
+            .route(
+                format!("/{MONITORING_PREFIX}/{CONSENSUS_EVIDENCE}").as_str(),
+                get(move |query| consensus_evidence(evidence_pool, query)),
+            )
     }
 }
 
@@ -129,8 +143,9 @@ pub fn create_monitoring_endpoint(
     version: &'static str,
     mempool_client: Option<SharedMempoolClient>,
     l1_provider_client: Option<SharedL1ProviderClient>,
+    evidence_pool: Option<EvidencePool>,
 ) -> MonitoringEndpoint {
-    MonitoringEndpoint::new(config, version, mempool_client, l1_provider_client)
+    MonitoringEndpoint::new(config, version, mempool_client, l1_provider_client, evidence_pool)
 }
 
 #[async_trait]
@@ -206,3 +221,29 @@ async fn get_l1_provider_snapshot(
         None => Err(StatusCode::METHOD_NOT_ALLOWED),
     }
 }
+
+#[derive(Debug, Deserialize)]
+struct EvidenceQuery {
+    height: Option<BlockNumber>,
+    voter: Option<ValidatorId>,
+}
+
+// Returns the evidence of validators equivocating in consensus, optionally filtered by height and
+// voter.
+#[instrument(level = "debug", skip(evidence_pool))]
+async fn consensus_evidence(
+    evidence_pool: Option<EvidencePool>,
+    Query(query): Query<EvidenceQuery>,
+) -> Result<Json<Vec<Evidence>>, StatusCode> {
+    let evidence_pool = evidence_pool.ok_or(StatusCode::METHOD_NOT_ALLOWED)?;
+    let evidence = match (query.height, query.voter) {
+        (Some(height), voter) => evidence_pool
+            .evidence_at_height(height)
+            .into_iter()
+            .filter(|evidence| voter.is_none_or(|voter| evidence.voter() == voter))
+            .collect(),
+        (None, Some(voter)) => evidence_pool.evidence_against(voter),
+        (None, None) => evidence_pool.evidence(),
+    };
+    Ok(Json(evidence))
+}
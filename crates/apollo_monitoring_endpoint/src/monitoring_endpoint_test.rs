@@ -2,6 +2,8 @@ use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::Arc;
 
+use apollo_consensus::evidence::{Evidence, EvidencePool};
+use apollo_consensus::types::ValidatorId;
 use apollo_l1_provider_types::{L1ProviderSnapshot, MockL1ProviderClient};
 use apollo_mempool_types::communication::MockMempoolClient;
 use apollo_mempool_types::mempool_types::{
@@ -9,6 +11,7 @@ use apollo_mempool_types::mempool_types::{
     MempoolStateSnapshot,
     TransactionQueueSnapshot,
 };
+use apollo_protobuf::consensus::{Vote, VoteType};
 use axum::http::StatusCode;
 use axum::response::Response;
 use axum::Router;
@@ -17,9 +20,10 @@ use hyper::Client;
 use metrics::{counter, describe_counter};
 use pretty_assertions::assert_eq;
 use serde_json::{from_slice, to_value, Value};
-use starknet_api::block::{BlockNumber, GasPrice};
+use starknet_api::block::{BlockHash, BlockNumber, GasPrice};
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::{nonce, tx_hash};
+use starknet_types_core::felt::Felt;
 use tokio::spawn;
 use tokio::task::yield_now;
 use tower::ServiceExt;
@@ -30,6 +34,7 @@ use crate::monitoring_endpoint::{
     create_monitoring_endpoint,
     MonitoringEndpoint,
     ALIVE,
+    CONSENSUS_EVIDENCE,
     L1_PROVIDER_SNAPSHOT,
     MEMPOOL_SNAPSHOT,
     METRICS,
@@ -51,7 +56,7 @@ const CONFIG_WITHOUT_METRICS: MonitoringEndpointConfig = MonitoringEndpointConfi
 
 fn setup_monitoring_endpoint(config: Option<MonitoringEndpointConfig>) -> MonitoringEndpoint {
     let config = config.unwrap_or(CONFIG_WITHOUT_METRICS);
-    create_monitoring_endpoint(config, TEST_VERSION, None, None)
+    create_monitoring_endpoint(config, TEST_VERSION, None, None, None)
 }
 
 async fn request_app(app: Router, method: &str) -> Response {
@@ -134,6 +139,7 @@ fn setup_monitoring_endpoint_with_mempool_client() -> MonitoringEndpoint {
         TEST_VERSION,
         Some(shared_mock_mempool_client),
         None,
+        None,
     )
 }
 
@@ -147,6 +153,7 @@ fn create_hash_map(i: u32, j: u32) -> HashMap<ContractAddress, Nonce> {
 fn expected_mempool_snapshot() -> MempoolSnapshot {
     let expected_chronological_hashes = (1..10).map(|i| tx_hash!(i)).collect::<Vec<_>>();
     let expected_delayed_declares = (10..15).map(|i| tx_hash!(i)).collect::<Vec<_>>();
+    let expected_suspended_transactions = (15..17).map(|i| tx_hash!(i)).collect::<Vec<_>>();
     let expected_transaction_queue = TransactionQueueSnapshot {
         gas_price_threshold: GasPrice(1),
         priority_queue: (1..5).map(|i| tx_hash!(i)).collect::<Vec<_>>(),
@@ -157,6 +164,7 @@ fn expected_mempool_snapshot() -> MempoolSnapshot {
     MempoolSnapshot {
         transactions: expected_chronological_hashes,
         delayed_declares: expected_delayed_declares,
+        suspended_transactions: expected_suspended_transactions,
         transaction_queue: expected_transaction_queue,
         mempool_state,
     }
@@ -196,6 +204,7 @@ fn setup_monitoring_endpoint_with_l1_provider_client() -> MonitoringEndpoint {
         TEST_VERSION,
         None,
         Some(shared_mock_l1_provider_client),
+        None,
     )
 }
 
@@ -244,3 +253,58 @@ async fn l1_provider_not_present() {
     let response = request_app(app, L1_PROVIDER_SNAPSHOT).await;
     assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
 }
+
+fn evidence(height: u64, voter: ValidatorId) -> Evidence {
+    let vote = |block_hash| Vote {
+        vote_type: VoteType::Prevote,
+        height,
+        round: 0,
+        block_hash: Some(BlockHash(block_hash)),
+        voter,
+        signature: None,
+    };
+    Evidence::from_conflicting_votes(vote(Felt::ONE), vote(Felt::TWO)).unwrap()
+}
+
+async fn request_evidence(app: Router, query: &str) -> Vec<Evidence> {
+    let response = request_app(app, &format!("{CONSENSUS_EVIDENCE}{query}")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    from_slice(&body_bytes).expect("Failed to parse JSON string")
+}
+
+#[tokio::test]
+async fn consensus_evidence() {
+    let voter_1 = ValidatorId::from(1_u8);
+    let voter_2 = ValidatorId::from(2_u8);
+    let evidence_pool = EvidencePool::default();
+    for evidence in [evidence(1, voter_1), evidence(1, voter_2), evidence(2, voter_1)] {
+        assert!(evidence_pool.add(evidence));
+    }
+    let app = create_monitoring_endpoint(
+        CONFIG_WITHOUT_METRICS,
+        TEST_VERSION,
+        None,
+        None,
+        Some(evidence_pool),
+    )
+    .app();
+
+    assert_eq!(request_evidence(app.clone(), "").await.len(), 3);
+    assert_eq!(
+        request_evidence(app.clone(), "?height=1").await,
+        vec![evidence(1, voter_1), evidence(1, voter_2)]
+    );
+    assert_eq!(
+        request_evidence(app.clone(), "?voter=0x1").await,
+        vec![evidence(1, voter_1), evidence(2, voter_1)]
+    );
+    assert_eq!(request_evidence(app, "?height=2&voter=0x2").await, vec![]);
+}
+
+#[tokio::test]
+async fn consensus_evidence_not_present() {
+    let app = setup_monitoring_endpoint(None).app();
+    let response = request_app(app, CONSENSUS_EVIDENCE).await;
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
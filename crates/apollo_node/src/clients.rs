@@ -14,6 +14,13 @@ use apollo_class_manager_types::{
     RemoteClassManagerClient,
     SharedClassManagerClient,
 };
+use apollo_committer_types::{
+    CommitterRequest,
+    CommitterResponse,
+    LocalCommitterClient,
+    RemoteCommitterClient,
+    SharedCommitterClient,
+};
 use apollo_compile_to_casm_types::{
     LocalSierraCompilerClient,
     RemoteSierraCompilerClient,
@@ -33,6 +40,7 @@ use apollo_infra::metrics::{
     RemoteClientMetrics,
     BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS,
     CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS,
+    COMMITTER_REMOTE_CLIENT_SEND_ATTEMPTS,
     GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS,
     L1_ENDPOINT_MONITOR_SEND_ATTEMPTS,
     L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS,
@@ -93,6 +101,7 @@ use crate::config::node_config::SequencerNodeConfig;
 pub struct SequencerNodeClients {
     batcher_client: Client<BatcherRequest, BatcherResponse>,
     class_manager_client: Client<ClassManagerRequest, ClassManagerResponse>,
+    committer_client: Client<CommitterRequest, CommitterResponse>,
     gateway_client: Client<GatewayRequest, GatewayResponse>,
     l1_endpoint_monitor_client: Client<L1EndpointMonitorRequest, L1EndpointMonitorResponse>,
     l1_provider_client: Client<L1ProviderRequest, L1ProviderResponse>,
@@ -168,6 +177,16 @@ impl SequencerNodeClients {
         get_shared_client!(self, class_manager_client)
     }
 
+    pub fn get_committer_local_client(
+        &self,
+    ) -> Option<LocalComponentClient<CommitterRequest, CommitterResponse>> {
+        self.committer_client.get_local_client()
+    }
+
+    pub fn get_committer_shared_client(&self) -> Option<SharedCommitterClient> {
+        get_shared_client!(self, committer_client)
+    }
+
     pub fn get_gateway_local_client(
         &self,
     ) -> Option<LocalComponentClient<GatewayRequest, GatewayResponse>> {
@@ -356,6 +375,18 @@ pub fn create_node_clients(
         class_manager_remote_metrics
     );
 
+    let committer_remote_metrics = RemoteClientMetrics::new(&COMMITTER_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let committer_client = create_client!(
+        &config.components.committer.execution_mode,
+        LocalCommitterClient,
+        RemoteCommitterClient,
+        channels.take_committer_tx(),
+        &config.components.committer.remote_client_config,
+        &config.components.committer.url,
+        config.components.committer.port,
+        committer_remote_metrics
+    );
+
     let gateway_remote_metrics = RemoteClientMetrics::new(&GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS);
     let gateway_client = create_client!(
         &config.components.gateway.execution_mode,
@@ -474,6 +505,7 @@ pub fn create_node_clients(
     SequencerNodeClients {
         batcher_client,
         class_manager_client,
+        committer_client,
         gateway_client,
         l1_endpoint_monitor_client,
         l1_provider_client,
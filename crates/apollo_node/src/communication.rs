@@ -1,5 +1,6 @@
 use apollo_batcher_types::communication::BatcherRequestAndResponseSender;
 use apollo_class_manager_types::ClassManagerRequestAndResponseSender;
+use apollo_committer_types::CommitterRequestAndResponseSender;
 use apollo_compile_to_casm_types::SierraCompilerRequestAndResponseSender;
 use apollo_gateway_types::communication::GatewayRequestAndResponseSender;
 use apollo_infra::component_definitions::ComponentCommunication;
@@ -18,6 +19,7 @@ use crate::config::node_config::SequencerNodeConfig;
 pub struct SequencerNodeCommunication {
     batcher_channel: ComponentCommunication<BatcherRequestAndResponseSender>,
     class_manager_channel: ComponentCommunication<ClassManagerRequestAndResponseSender>,
+    committer_channel: ComponentCommunication<CommitterRequestAndResponseSender>,
     gateway_channel: ComponentCommunication<GatewayRequestAndResponseSender>,
     l1_endpoint_monitor_channel: ComponentCommunication<L1EndpointMonitorRequestAndResponseSender>,
     l1_provider_channel: ComponentCommunication<L1ProviderRequestAndResponseSender>,
@@ -47,6 +49,14 @@ impl SequencerNodeCommunication {
         self.class_manager_channel.take_rx()
     }
 
+    pub fn take_committer_tx(&mut self) -> Sender<CommitterRequestAndResponseSender> {
+        self.committer_channel.take_tx()
+    }
+
+    pub fn take_committer_rx(&mut self) -> Receiver<CommitterRequestAndResponseSender> {
+        self.committer_channel.take_rx()
+    }
+
     pub fn take_gateway_tx(&mut self) -> Sender<GatewayRequestAndResponseSender> {
         self.gateway_channel.take_tx()
     }
@@ -140,6 +150,10 @@ pub fn create_node_channels(config: &SequencerNodeConfig) -> SequencerNodeCommun
         config.components.class_manager.local_server_config.channel_capacity,
     );
 
+    let (tx_committer, rx_committer) = channel::<CommitterRequestAndResponseSender>(
+        config.components.committer.local_server_config.channel_capacity,
+    );
+
     let (tx_gateway, rx_gateway) = channel::<GatewayRequestAndResponseSender>(
         config.components.gateway.local_server_config.channel_capacity,
     );
@@ -185,6 +199,7 @@ pub fn create_node_channels(config: &SequencerNodeConfig) -> SequencerNodeCommun
             Some(tx_class_manager),
             Some(rx_class_manager),
         ),
+        committer_channel: ComponentCommunication::new(Some(tx_committer), Some(rx_committer)),
         gateway_channel: ComponentCommunication::new(Some(tx_gateway), Some(rx_gateway)),
         l1_endpoint_monitor_channel: ComponentCommunication::new(
             Some(tx_l1_endpoint_monitor),
@@ -1,7 +1,11 @@
+use std::sync::Arc;
+
 use apollo_batcher::batcher::{create_batcher, Batcher};
 use apollo_batcher::pre_confirmed_cende_client::PreconfirmedCendeClient;
 use apollo_class_manager::class_manager::create_class_manager;
 use apollo_class_manager::ClassManager;
+use apollo_committer::committer::Committer;
+use apollo_committer::create_committer;
 use apollo_compile_to_casm::{create_sierra_compiler, SierraCompiler};
 use apollo_consensus_manager::consensus_manager::ConsensusManager;
 use apollo_gateway::gateway::{create_gateway, Gateway};
@@ -13,6 +17,7 @@ use apollo_l1_provider::event_identifiers_to_track;
 use apollo_l1_provider::l1_provider::{L1Provider, L1ProviderBuilder};
 use apollo_l1_provider::l1_scraper::{fetch_start_block, L1Scraper};
 use apollo_mempool::communication::{create_mempool, MempoolCommunicationWrapper};
+use apollo_mempool::persistence::AccountNonceReader;
 use apollo_mempool_p2p::create_p2p_propagator_and_runner;
 use apollo_mempool_p2p::propagator::MempoolP2pPropagator;
 use apollo_mempool_p2p::runner::MempoolP2pRunner;
@@ -23,9 +28,17 @@ use apollo_monitoring_endpoint::monitoring_endpoint::{
 use apollo_signature_manager::{create_signature_manager, SignatureManager};
 use apollo_state_sync::runner::StateSyncRunner;
 use apollo_state_sync::{create_state_sync_and_runner, StateSync};
+use apollo_state_sync_types::communication::{
+    SharedStateSyncClient,
+    StateSyncClient,
+    StateSyncClientError,
+};
+use apollo_state_sync_types::errors::StateSyncError;
+use async_trait::async_trait;
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerContract;
 use papyrus_base_layer::monitored_base_layer::MonitoredEthereumBaseLayer;
 use papyrus_base_layer::BaseLayerContract;
+use starknet_api::core::{ContractAddress, Nonce};
 use tracing::{debug, info, warn};
 
 use crate::clients::SequencerNodeClients;
@@ -39,6 +52,7 @@ use crate::version::VERSION_FULL;
 pub struct SequencerNodeComponents {
     pub batcher: Option<Batcher>,
     pub class_manager: Option<ClassManager>,
+    pub committer: Option<Committer>,
     pub consensus_manager: Option<ConsensusManager>,
     pub gateway: Option<Gateway>,
     pub http_server: Option<HttpServer>,
@@ -86,6 +100,7 @@ pub async fn create_node_components(
                 mempool_client,
                 l1_provider_client,
                 class_manager_client,
+                clients.get_committer_shared_client(),
                 pre_confirmed_cende_client,
             ))
         }
@@ -111,10 +126,26 @@ pub async fn create_node_components(
         }
     };
 
+    let committer = match config.components.committer.execution_mode {
+        ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled
+        | ReactiveComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
+            let committer_config =
+                config.committer_config.as_ref().expect("Committer config should be set");
+            let committer = create_committer(committer_config.clone())
+                .unwrap_or_else(|err| panic!("Failed to create the committer: {err}"));
+            Some(committer)
+        }
+        ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => None,
+    };
+
     let signature_manager = match config.components.signature_manager.execution_mode {
         ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ReactiveComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
-            Some(create_signature_manager())
+            let signature_manager_config = config
+                .signature_manager_config
+                .as_ref()
+                .expect("Signature manager config should be set");
+            Some(create_signature_manager(signature_manager_config.clone()))
         }
         ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => None,
     };
@@ -228,7 +259,14 @@ pub async fn create_node_components(
             let mempool_p2p_propagator_client = clients
                 .get_mempool_p2p_propagator_shared_client()
                 .expect("Propagator Client should be available");
-            let mempool = create_mempool(mempool_config.clone(), mempool_p2p_propagator_client);
+            let account_nonce_reader = clients.get_state_sync_shared_client().map(|client| {
+                Arc::new(StateSyncAccountNonceReader(client)) as Arc<dyn AccountNonceReader>
+            });
+            let mempool = create_mempool(
+                mempool_config.clone(),
+                mempool_p2p_propagator_client,
+                account_nonce_reader,
+            );
             Some(mempool)
         }
         ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => {
@@ -266,11 +304,14 @@ pub async fn create_node_components(
                 | ReactiveComponentExecutionMode::Remote => None,
             };
 
+            let evidence_pool = consensus_manager.as_ref().map(ConsensusManager::evidence_pool);
+
             Some(create_monitoring_endpoint(
                 monitoring_endpoint_config.clone(),
                 VERSION_FULL,
                 mempool_client,
                 l1_provider_client,
+                evidence_pool,
             ))
         }
         ActiveComponentExecutionMode::Disabled => {
@@ -287,8 +328,11 @@ pub async fn create_node_components(
             let class_manager_client = clients
                 .get_class_manager_shared_client()
                 .expect("Class Manager Client should be available");
-            let (state_sync, state_sync_runner) =
-                create_state_sync_and_runner(state_sync_config.clone(), class_manager_client);
+            let (state_sync, state_sync_runner) = create_state_sync_and_runner(
+                state_sync_config.clone(),
+                class_manager_client,
+                clients.get_committer_shared_client(),
+            );
             (Some(state_sync), Some(state_sync_runner))
         }
         ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => {
@@ -366,9 +410,9 @@ pub async fn create_node_components(
             let base_layer_config =
                 config.base_layer_config.as_ref().expect("Base Layer config should be set");
             let l1_provider_config =
-                config.l1_provider_config.expect("L1 Provider config should be set");
+                config.l1_provider_config.as_ref().expect("L1 Provider config should be set");
             let mut l1_provider_builder = L1ProviderBuilder::new(
-                l1_provider_config,
+                l1_provider_config.clone(),
                 clients.get_l1_provider_shared_client().unwrap(),
                 clients.get_batcher_shared_client().unwrap(),
                 clients.get_state_sync_shared_client().unwrap(),
@@ -508,6 +552,7 @@ pub async fn create_node_components(
     SequencerNodeComponents {
         batcher,
         class_manager,
+        committer,
         consensus_manager,
         gateway,
         http_server,
@@ -526,3 +571,25 @@ pub async fn create_node_components(
         state_sync_runner,
     }
 }
+
+/// Reads the account nonces used by the mempool to revalidate its persisted transactions from the
+/// latest block synced by the state sync.
+struct StateSyncAccountNonceReader(SharedStateSyncClient);
+
+#[async_trait]
+impl AccountNonceReader for StateSyncAccountNonceReader {
+    async fn get_account_nonce(&self, address: ContractAddress) -> Result<Nonce, String> {
+        let Some(latest_block_number) =
+            self.0.get_latest_block_number().await.map_err(|e| e.to_string())?
+        else {
+            return Ok(Nonce::default());
+        };
+        match self.0.get_nonce_at(latest_block_number, address).await {
+            Ok(nonce) => Ok(nonce),
+            Err(StateSyncClientError::StateSyncError(StateSyncError::ContractNotFound(_))) => {
+                Ok(Nonce::default())
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
@@ -19,6 +19,8 @@ pub struct ComponentConfig {
     #[validate]
     pub class_manager: ReactiveComponentExecutionConfig,
     #[validate]
+    pub committer: ReactiveComponentExecutionConfig,
+    #[validate]
     pub gateway: ReactiveComponentExecutionConfig,
     #[validate]
     pub mempool: ReactiveComponentExecutionConfig,
@@ -55,6 +57,7 @@ impl SerializeConfig for ComponentConfig {
         let sub_configs = vec![
             prepend_sub_config_name(self.batcher.dump(), "batcher"),
             prepend_sub_config_name(self.class_manager.dump(), "class_manager"),
+            prepend_sub_config_name(self.committer.dump(), "committer"),
             prepend_sub_config_name(self.consensus_manager.dump(), "consensus_manager"),
             prepend_sub_config_name(self.gateway.dump(), "gateway"),
             prepend_sub_config_name(self.http_server.dump(), "http_server"),
@@ -80,6 +83,7 @@ impl ComponentConfig {
         ComponentConfig {
             batcher: ReactiveComponentExecutionConfig::disabled(),
             class_manager: ReactiveComponentExecutionConfig::disabled(),
+            committer: ReactiveComponentExecutionConfig::disabled(),
             gateway: ReactiveComponentExecutionConfig::disabled(),
             mempool: ReactiveComponentExecutionConfig::disabled(),
             mempool_p2p: ReactiveComponentExecutionConfig::disabled(),
@@ -101,6 +105,7 @@ impl ComponentConfig {
     pub fn set_urls_to_localhost(&mut self) {
         self.batcher.set_url_to_localhost();
         self.class_manager.set_url_to_localhost();
+        self.committer.set_url_to_localhost();
         self.gateway.set_url_to_localhost();
         self.mempool.set_url_to_localhost();
         self.mempool_p2p.set_url_to_localhost();
@@ -4,7 +4,7 @@ use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
 use apollo_config::dumping::{prepend_sub_config_name, ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_infra::component_client::RemoteClientConfig;
-use apollo_infra::component_server::LocalServerConfig;
+use apollo_infra::component_server::{LocalServerConfig, RemoteServerConfig};
 use serde::{Deserialize, Serialize};
 use tracing::error;
 use validator::{Validate, ValidationError};
@@ -38,6 +38,7 @@ pub struct ReactiveComponentExecutionConfig {
     pub execution_mode: ReactiveComponentExecutionMode,
     pub local_server_config: LocalServerConfig,
     pub remote_client_config: RemoteClientConfig,
+    pub remote_server_config: RemoteServerConfig,
     #[validate(custom = "validate_max_concurrency")]
     pub max_concurrency: usize,
     pub url: String,
@@ -83,6 +84,7 @@ impl SerializeConfig for ReactiveComponentExecutionConfig {
             members,
             prepend_sub_config_name(self.local_server_config.dump(), "local_server_config"),
             prepend_sub_config_name(self.remote_client_config.dump(), "remote_client_config"),
+            prepend_sub_config_name(self.remote_server_config.dump(), "remote_server_config"),
         ]
         .into_iter()
         .flatten()
@@ -102,6 +104,7 @@ impl ReactiveComponentExecutionConfig {
             execution_mode: ReactiveComponentExecutionMode::Disabled,
             local_server_config: LocalServerConfig::default(),
             remote_client_config: RemoteClientConfig::default(),
+            remote_server_config: RemoteServerConfig::default(),
             max_concurrency: MAX_CONCURRENCY,
             url: DEFAULT_URL.to_string(),
             ip: DEFAULT_IP,
@@ -115,6 +118,7 @@ impl ReactiveComponentExecutionConfig {
             local_server_config: LocalServerConfig::default(),
             max_concurrency: MAX_CONCURRENCY,
             remote_client_config: RemoteClientConfig::default(),
+            remote_server_config: RemoteServerConfig::default(),
             url,
             ip,
             port,
@@ -126,6 +130,7 @@ impl ReactiveComponentExecutionConfig {
             execution_mode: ReactiveComponentExecutionMode::LocalExecutionWithRemoteEnabled,
             local_server_config: LocalServerConfig::default(),
             remote_client_config: RemoteClientConfig::default(),
+            remote_server_config: RemoteServerConfig::default(),
             max_concurrency: MAX_CONCURRENCY,
             url,
             ip,
@@ -138,6 +143,7 @@ impl ReactiveComponentExecutionConfig {
             execution_mode: ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled,
             local_server_config: LocalServerConfig::default(),
             remote_client_config: RemoteClientConfig::default(),
+            remote_server_config: RemoteServerConfig::default(),
             max_concurrency: MAX_CONCURRENCY,
             url: DEFAULT_URL.to_string(),
             ip: DEFAULT_IP,
@@ -2,7 +2,7 @@ use std::net::{IpAddr, Ipv4Addr};
 
 use apollo_config::dumping::{combine_config_map_and_pointers, SerializeConfig};
 use apollo_infra::component_client::RemoteClientConfig;
-use apollo_infra::component_server::LocalServerConfig;
+use apollo_infra::component_server::{LocalServerConfig, RemoteServerConfig};
 use apollo_infra_utils::dumping::serialize_to_file_test;
 use rstest::rstest;
 use validator::Validate;
@@ -79,6 +79,7 @@ fn valid_component_execution_config(
         execution_mode,
         local_server_config,
         remote_client_config,
+        remote_server_config: RemoteServerConfig::default(),
         max_concurrency: 1,
         url: url.to_string(),
         ip,
@@ -6,6 +6,7 @@ use std::vec::Vec;
 use apollo_batcher::config::BatcherConfig;
 use apollo_batcher::VersionedConstantsOverrides;
 use apollo_class_manager::config::FsClassManagerConfig;
+use apollo_committer::committer::CommitterConfig;
 use apollo_compile_to_casm::config::SierraCompilationConfig;
 use apollo_config::dumping::{
     generate_struct_pointer,
@@ -22,6 +23,7 @@ use apollo_config::{ConfigError, ParamPath, SerializedParam};
 use apollo_consensus_manager::config::ConsensusManagerConfig;
 use apollo_gateway::config::GatewayConfig;
 use apollo_http_server::config::HttpServerConfig;
+use apollo_infra::tls_utils::TlsConfig;
 use apollo_infra_utils::path::resolve_project_relative_path;
 use apollo_l1_endpoint_monitor::monitor::L1EndpointMonitorConfig;
 use apollo_l1_gas_price::l1_gas_price_provider::L1GasPriceProviderConfig;
@@ -32,6 +34,7 @@ use apollo_mempool::config::MempoolConfig;
 use apollo_mempool_p2p::config::MempoolP2pConfig;
 use apollo_monitoring_endpoint::config::MonitoringEndpointConfig;
 use apollo_reverts::RevertConfig;
+use apollo_signature_manager::config::SignatureManagerConfig;
 use apollo_state_sync::config::StateSyncConfig;
 use clap::Command;
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
@@ -164,6 +167,39 @@ pub static CONFIG_POINTERS: LazyLock<ConfigPointers> = LazyLock::new(|| {
         ]),
     );
     pointers.append(&mut common_execution_config);
+
+    // The remote clients and servers of all the components of a node share its certificates.
+    let mut remote_component_tls_config = generate_struct_pointer(
+        "remote_component_tls_config".to_owned(),
+        &TlsConfig::default(),
+        set_pointing_param_paths(&[
+            "components.batcher.remote_client_config.tls_config",
+            "components.batcher.remote_server_config.tls_config",
+            "components.class_manager.remote_client_config.tls_config",
+            "components.class_manager.remote_server_config.tls_config",
+            "components.committer.remote_client_config.tls_config",
+            "components.committer.remote_server_config.tls_config",
+            "components.gateway.remote_client_config.tls_config",
+            "components.gateway.remote_server_config.tls_config",
+            "components.l1_endpoint_monitor.remote_client_config.tls_config",
+            "components.l1_endpoint_monitor.remote_server_config.tls_config",
+            "components.l1_gas_price_provider.remote_client_config.tls_config",
+            "components.l1_gas_price_provider.remote_server_config.tls_config",
+            "components.l1_provider.remote_client_config.tls_config",
+            "components.l1_provider.remote_server_config.tls_config",
+            "components.mempool.remote_client_config.tls_config",
+            "components.mempool.remote_server_config.tls_config",
+            "components.mempool_p2p.remote_client_config.tls_config",
+            "components.mempool_p2p.remote_server_config.tls_config",
+            "components.sierra_compiler.remote_client_config.tls_config",
+            "components.sierra_compiler.remote_server_config.tls_config",
+            "components.signature_manager.remote_client_config.tls_config",
+            "components.signature_manager.remote_server_config.tls_config",
+            "components.state_sync.remote_client_config.tls_config",
+            "components.state_sync.remote_server_config.tls_config",
+        ]),
+    );
+    pointers.append(&mut remote_component_tls_config);
     pointers
 });
 
@@ -188,6 +224,8 @@ pub struct SequencerNodeConfig {
     #[validate]
     pub class_manager_config: Option<FsClassManagerConfig>,
     #[validate]
+    pub committer_config: Option<CommitterConfig>,
+    #[validate]
     pub consensus_manager_config: Option<ConsensusManagerConfig>,
     #[validate]
     pub gateway_config: Option<GatewayConfig>,
@@ -212,6 +250,8 @@ pub struct SequencerNodeConfig {
     #[validate]
     pub sierra_compiler_config: Option<SierraCompilationConfig>,
     #[validate]
+    pub signature_manager_config: Option<SignatureManagerConfig>,
+    #[validate]
     pub state_sync_config: Option<StateSyncConfig>,
 }
 
@@ -225,6 +265,7 @@ impl SerializeConfig for SequencerNodeConfig {
             ser_optional_sub_config(&self.base_layer_config, "base_layer_config"),
             ser_optional_sub_config(&self.batcher_config, "batcher_config"),
             ser_optional_sub_config(&self.class_manager_config, "class_manager_config"),
+            ser_optional_sub_config(&self.committer_config, "committer_config"),
             ser_optional_sub_config(&self.consensus_manager_config, "consensus_manager_config"),
             ser_optional_sub_config(&self.gateway_config, "gateway_config"),
             ser_optional_sub_config(&self.http_server_config, "http_server_config"),
@@ -243,6 +284,7 @@ impl SerializeConfig for SequencerNodeConfig {
             ser_optional_sub_config(&self.l1_provider_config, "l1_provider_config"),
             ser_optional_sub_config(&self.l1_scraper_config, "l1_scraper_config"),
             ser_optional_sub_config(&self.sierra_compiler_config, "sierra_compiler_config"),
+            ser_optional_sub_config(&self.signature_manager_config, "signature_manager_config"),
             ser_optional_sub_config(&self.state_sync_config, "state_sync_config"),
         ];
 
@@ -260,6 +302,7 @@ impl Default for SequencerNodeConfig {
             base_layer_config: Some(EthereumBaseLayerConfig::default()),
             batcher_config: Some(BatcherConfig::default()),
             class_manager_config: Some(FsClassManagerConfig::default()),
+            committer_config: Some(CommitterConfig::default()),
             consensus_manager_config: Some(ConsensusManagerConfig::default()),
             gateway_config: Some(GatewayConfig::default()),
             http_server_config: Some(HttpServerConfig::default()),
@@ -272,6 +315,7 @@ impl Default for SequencerNodeConfig {
             mempool_p2p_config: Some(MempoolP2pConfig::default()),
             monitoring_endpoint_config: Some(MonitoringEndpointConfig::default()),
             sierra_compiler_config: Some(SierraCompilationConfig::default()),
+            signature_manager_config: Some(SignatureManagerConfig::default()),
             state_sync_config: Some(StateSyncConfig::default()),
         }
     }
@@ -3,6 +3,7 @@ use std::pin::Pin;
 
 use apollo_batcher::communication::{LocalBatcherServer, RemoteBatcherServer};
 use apollo_class_manager::communication::{LocalClassManagerServer, RemoteClassManagerServer};
+use apollo_committer::communication::{LocalCommitterServer, RemoteCommitterServer};
 use apollo_compile_to_casm::communication::{
     LocalSierraCompilerServer,
     RemoteSierraCompilerServer,
@@ -32,6 +33,12 @@ use apollo_infra::metrics::{
     CLASS_MANAGER_REMOTE_MSGS_PROCESSED,
     CLASS_MANAGER_REMOTE_MSGS_RECEIVED,
     CLASS_MANAGER_REMOTE_VALID_MSGS_RECEIVED,
+    COMMITTER_LOCAL_MSGS_PROCESSED,
+    COMMITTER_LOCAL_MSGS_RECEIVED,
+    COMMITTER_LOCAL_QUEUE_DEPTH,
+    COMMITTER_REMOTE_MSGS_PROCESSED,
+    COMMITTER_REMOTE_MSGS_RECEIVED,
+    COMMITTER_REMOTE_VALID_MSGS_RECEIVED,
     GATEWAY_LOCAL_MSGS_PROCESSED,
     GATEWAY_LOCAL_MSGS_RECEIVED,
     GATEWAY_LOCAL_QUEUE_DEPTH,
@@ -132,6 +139,7 @@ use crate::config::node_config::SequencerNodeConfig;
 struct LocalServers {
     pub(crate) batcher: Option<Box<LocalBatcherServer>>,
     pub(crate) class_manager: Option<Box<LocalClassManagerServer>>,
+    pub(crate) committer: Option<Box<LocalCommitterServer>>,
     pub(crate) gateway: Option<Box<LocalGatewayServer>>,
     pub(crate) l1_endpoint_monitor: Option<Box<LocalL1EndpointMonitorServer>>,
     pub(crate) l1_provider: Option<Box<LocalL1ProviderServer>>,
@@ -160,6 +168,7 @@ struct WrapperServers {
 pub struct RemoteServers {
     pub batcher: Option<Box<RemoteBatcherServer>>,
     pub class_manager: Option<Box<RemoteClassManagerServer>>,
+    pub committer: Option<Box<RemoteCommitterServer>>,
     pub gateway: Option<Box<RemoteGatewayServer>>,
     pub l1_endpoint_monitor: Option<Box<RemoteL1EndpointMonitorServer>>,
     pub l1_provider: Option<Box<RemoteL1ProviderServer>>,
@@ -186,6 +195,7 @@ pub struct SequencerNodeServers {
 /// * `$execution_mode` - Component execution mode reference.
 /// * `$local_client_getter` - Local client getter function, used for the remote server
 ///   initialization if needed.
+/// * `$remote_server_config` - Configuration for the remote server.
 /// * `$ip` - Remote component server binding address, default "0.0.0.0".
 /// * `$port` - Remote component server listening port.
 /// * `$max_concurrency` - the maximum number of concurrent connections the server will handle.
@@ -202,6 +212,7 @@ pub struct SequencerNodeServers {
 /// let batcher_remote_server = create_remote_server!(
 ///     &config.components.batcher.execution_mode,
 ///     || {clients.get_gateway_local_client()},
+///     &config.components.batcher.remote_server_config,
 ///     config.components.batcher.ip,
 ///     config.components.batcher.port,
 ///     config.components.batcher.max_concurrency
@@ -216,6 +227,7 @@ macro_rules! create_remote_server {
     (
         $execution_mode:expr,
         $local_client_getter:expr,
+        $remote_server_config:expr,
         $url:expr,
         $port:expr,
         $max_concurrency:expr,
@@ -228,6 +240,7 @@ macro_rules! create_remote_server {
 
                 Some(Box::new(RemoteComponentServer::new(
                     local_client,
+                    $remote_server_config.clone(),
                     $url,
                     $port,
                     $max_concurrency,
@@ -379,6 +392,19 @@ fn create_local_servers(
         class_manager_metrics,
         config.components.class_manager.max_concurrency
     );
+    let committer_metrics = LocalServerMetrics::new(
+        &COMMITTER_LOCAL_MSGS_RECEIVED,
+        &COMMITTER_LOCAL_MSGS_PROCESSED,
+        &COMMITTER_LOCAL_QUEUE_DEPTH,
+    );
+    let committer_server = create_local_server!(
+        CONCURRENT_LOCAL_SERVER,
+        &config.components.committer.execution_mode,
+        &mut components.committer,
+        communication.take_committer_rx(),
+        committer_metrics,
+        config.components.committer.max_concurrency
+    );
     let gateway_metrics = LocalServerMetrics::new(
         &GATEWAY_LOCAL_MSGS_RECEIVED,
         &GATEWAY_LOCAL_MSGS_PROCESSED,
@@ -497,6 +523,7 @@ fn create_local_servers(
     LocalServers {
         batcher: batcher_server,
         class_manager: class_manager_server,
+        committer: committer_server,
         gateway: gateway_server,
         l1_endpoint_monitor: l1_endpoint_monitor_server,
         l1_provider: l1_provider_server,
@@ -524,6 +551,7 @@ impl LocalServers {
         create_servers(vec![
             server_future_and_label(self.batcher, "Local Batcher"),
             server_future_and_label(self.class_manager, "Local Class Manager"),
+            server_future_and_label(self.committer, "Local Committer"),
             server_future_and_label(self.gateway, "Local Gateway"),
             server_future_and_label(self.l1_endpoint_monitor, "Local L1 Endpoint Monitor"),
             server_future_and_label(self.l1_provider, "Local L1 Provider"),
@@ -550,6 +578,7 @@ pub fn create_remote_servers(
     let batcher_server = create_remote_server!(
         &config.components.batcher.execution_mode,
         || { clients.get_batcher_local_client() },
+        &config.components.batcher.remote_server_config,
         config.components.batcher.ip,
         config.components.batcher.port,
         config.components.batcher.max_concurrency,
@@ -564,12 +593,28 @@ pub fn create_remote_servers(
     let class_manager_server = create_remote_server!(
         &config.components.class_manager.execution_mode,
         || { clients.get_class_manager_local_client() },
+        &config.components.class_manager.remote_server_config,
         config.components.class_manager.ip,
         config.components.class_manager.port,
         config.components.class_manager.max_concurrency,
         class_manager_metrics
     );
 
+    let committer_metrics = RemoteServerMetrics::new(
+        &COMMITTER_REMOTE_MSGS_RECEIVED,
+        &COMMITTER_REMOTE_VALID_MSGS_RECEIVED,
+        &COMMITTER_REMOTE_MSGS_PROCESSED,
+    );
+    let committer_server = create_remote_server!(
+        &config.components.committer.execution_mode,
+        || { clients.get_committer_local_client() },
+        &config.components.committer.remote_server_config,
+        config.components.committer.ip,
+        config.components.committer.port,
+        config.components.committer.max_concurrency,
+        committer_metrics
+    );
+
     let gateway_metrics = RemoteServerMetrics::new(
         &GATEWAY_REMOTE_MSGS_RECEIVED,
         &GATEWAY_REMOTE_VALID_MSGS_RECEIVED,
@@ -578,6 +623,7 @@ pub fn create_remote_servers(
     let gateway_server = create_remote_server!(
         &config.components.gateway.execution_mode,
         || { clients.get_gateway_local_client() },
+        &config.components.gateway.remote_server_config,
         config.components.gateway.ip,
         config.components.gateway.port,
         config.components.gateway.max_concurrency,
@@ -592,6 +638,7 @@ pub fn create_remote_servers(
     let l1_endpoint_monitor_server = create_remote_server!(
         &config.components.l1_endpoint_monitor.execution_mode,
         || { clients.get_l1_endpoint_monitor_local_client() },
+        &config.components.l1_endpoint_monitor.remote_server_config,
         config.components.l1_endpoint_monitor.ip,
         config.components.l1_endpoint_monitor.port,
         config.components.l1_endpoint_monitor.max_concurrency,
@@ -606,6 +653,7 @@ pub fn create_remote_servers(
     let l1_provider_server = create_remote_server!(
         &config.components.l1_provider.execution_mode,
         || { clients.get_l1_provider_local_client() },
+        &config.components.l1_provider.remote_server_config,
         config.components.l1_provider.ip,
         config.components.l1_provider.port,
         config.components.l1_provider.max_concurrency,
@@ -619,6 +667,7 @@ pub fn create_remote_servers(
     let l1_gas_price_provider_server = create_remote_server!(
         &config.components.l1_gas_price_provider.execution_mode,
         || { clients.get_l1_gas_price_provider_local_client() },
+        &config.components.l1_gas_price_provider.remote_server_config,
         config.components.l1_gas_price_provider.ip,
         config.components.l1_gas_price_provider.port,
         config.components.l1_gas_price_provider.max_concurrency,
@@ -634,6 +683,7 @@ pub fn create_remote_servers(
     let mempool_server = create_remote_server!(
         &config.components.mempool.execution_mode,
         || { clients.get_mempool_local_client() },
+        &config.components.mempool.remote_server_config,
         config.components.mempool.ip,
         config.components.mempool.port,
         config.components.mempool.max_concurrency,
@@ -648,6 +698,7 @@ pub fn create_remote_servers(
     let mempool_p2p_propagator_server = create_remote_server!(
         &config.components.mempool_p2p.execution_mode,
         || { clients.get_mempool_p2p_propagator_local_client() },
+        &config.components.mempool_p2p.remote_server_config,
         config.components.mempool_p2p.ip,
         config.components.mempool_p2p.port,
         config.components.mempool_p2p.max_concurrency,
@@ -662,6 +713,7 @@ pub fn create_remote_servers(
     let sierra_compiler_server = create_remote_server!(
         &config.components.sierra_compiler.execution_mode,
         || { clients.get_sierra_compiler_local_client() },
+        &config.components.sierra_compiler.remote_server_config,
         config.components.sierra_compiler.ip,
         config.components.sierra_compiler.port,
         config.components.sierra_compiler.max_concurrency,
@@ -676,6 +728,7 @@ pub fn create_remote_servers(
     let signature_manager_server = create_remote_server!(
         &config.components.signature_manager.execution_mode,
         || { clients.get_signature_manager_local_client() },
+        &config.components.signature_manager.remote_server_config,
         config.components.signature_manager.ip,
         config.components.signature_manager.port,
         config.components.signature_manager.max_concurrency,
@@ -690,6 +743,7 @@ pub fn create_remote_servers(
     let state_sync_server = create_remote_server!(
         &config.components.state_sync.execution_mode,
         || { clients.get_state_sync_local_client() },
+        &config.components.state_sync.remote_server_config,
         config.components.state_sync.ip,
         config.components.state_sync.port,
         config.components.state_sync.max_concurrency,
@@ -699,6 +753,7 @@ pub fn create_remote_servers(
     RemoteServers {
         batcher: batcher_server,
         class_manager: class_manager_server,
+        committer: committer_server,
         gateway: gateway_server,
         l1_endpoint_monitor: l1_endpoint_monitor_server,
         l1_provider: l1_provider_server,
@@ -716,6 +771,7 @@ impl RemoteServers {
         create_servers(vec![
             server_future_and_label(self.batcher, "Remote Batcher"),
             server_future_and_label(self.class_manager, "Remote Class Manager"),
+            server_future_and_label(self.committer, "Remote Committer"),
             server_future_and_label(self.gateway, "Remote Gateway"),
             server_future_and_label(self.l1_endpoint_monitor, "Remote L1 Endpoint Monitor"),
             server_future_and_label(self.l1_provider, "Remote L1 Provider"),
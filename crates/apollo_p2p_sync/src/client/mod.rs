@@ -16,6 +16,7 @@ mod transaction;
 #[cfg(test)]
 mod transaction_test;
 
+use std::cmp::min;
 use std::collections::BTreeMap;
 use std::time::Duration;
 
@@ -34,7 +35,9 @@ use apollo_protobuf::sync::{
     TransactionQuery,
 };
 use apollo_state_sync_types::state_sync_types::SyncBlock;
-use apollo_storage::{StorageError, StorageReader, StorageWriter};
+use apollo_storage::body::BodyStorageReader;
+use apollo_storage::state::StateStorageReader;
+use apollo_storage::{StorageError, StorageReader, StorageScope, StorageWriter};
 use block_data_stream_builder::{BlockDataResult, BlockDataStreamBuilder};
 use class::ClassStreamBuilder;
 use futures::channel::mpsc::{Receiver, SendError, Sender};
@@ -43,18 +46,23 @@ use futures::stream::BoxStream;
 use futures::{SinkExt as _, Stream};
 use header::HeaderStreamBuilder;
 use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_common::sync_notifications::SyncNotification;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
 use starknet_api::core::ClassHash;
 use starknet_api::transaction::FullTransaction;
 use state_diff::StateDiffStreamBuilder;
+use tokio::sync::broadcast;
 use tokio_stream::StreamExt;
-use tracing::{info, instrument};
+use tracing::{debug, info, instrument};
 use transaction::TransactionStreamFactory;
 use validator::Validate;
 
 const STEP: u64 = 1;
 const ALLOWED_SIGNATURES_LENGTH: usize = 1;
+// The interval between pruning rounds of the history that is outside of the storage history
+// retention.
+const HISTORY_PRUNING_INTERVAL: Duration = Duration::from_secs(10);
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Validate)]
 pub struct P2pSyncClientConfig {
@@ -229,6 +237,7 @@ pub struct P2pSyncClient {
     p2p_sync_channels: P2pSyncClientChannels,
     internal_blocks_receiver: BoxStream<'static, SyncBlock>,
     class_manager_client: SharedClassManagerClient,
+    sync_notifications: broadcast::Sender<SyncNotification>,
 }
 
 impl P2pSyncClient {
@@ -239,6 +248,7 @@ impl P2pSyncClient {
         p2p_sync_channels: P2pSyncClientChannels,
         internal_blocks_receiver: BoxStream<'static, SyncBlock>,
         class_manager_client: SharedClassManagerClient,
+        sync_notifications: broadcast::Sender<SyncNotification>,
     ) -> Self {
         Self {
             config,
@@ -247,6 +257,7 @@ impl P2pSyncClient {
             p2p_sync_channels,
             internal_blocks_receiver,
             class_manager_client,
+            sync_notifications,
         }
     }
 
@@ -265,9 +276,15 @@ impl P2pSyncClient {
             p2p_sync_channels,
             mut internal_blocks_receiver,
             mut class_manager_client,
+            sync_notifications,
         } = self;
-        let mut data_stream =
-            p2p_sync_channels.create_stream(storage_reader, config, internal_blocks_receivers);
+        let mut data_stream = p2p_sync_channels.create_stream(
+            storage_reader.clone(),
+            config,
+            internal_blocks_receivers,
+        );
+        let mut stored_blocks_marker = get_stored_blocks_marker(&storage_reader)?;
+        let mut history_pruning_interval = tokio::time::interval(HISTORY_PRUNING_INTERVAL);
 
         loop {
             tokio::select! {
@@ -278,12 +295,52 @@ impl P2pSyncClient {
                 data = data_stream.next() => {
                     let data = data.expect("Sync data stream should never end")?;
                     data.write_to_storage(&mut storage_writer, &mut class_manager_client).await?;
+                    let new_stored_blocks_marker = get_stored_blocks_marker(&storage_reader)?;
+                    for block_number in stored_blocks_marker.iter_up_to(new_stored_blocks_marker) {
+                        // Sending fails only when there are no receivers, which is fine.
+                        let _ = sync_notifications.send(SyncNotification::NewBlock(block_number));
+                    }
+                    stored_blocks_marker = new_stored_blocks_marker;
+                }
+                _ = history_pruning_interval.tick() => {
+                    // The writer is moved to a blocking thread and back, so nothing else is written
+                    // meanwhile.
+                    let (writer, result) = tokio::task::spawn_blocking(move || {
+                        let result = prune_history_and_backfill_index(&mut storage_writer);
+                        (storage_writer, result)
+                    })
+                    .await
+                    .expect("History pruning should not panic.");
+                    storage_writer = writer;
+                    result?;
                 }
             }
         }
     }
 }
 
+// Prunes the history outside the retention, and indexes the event keys of blocks written before
+// the index existed.
+fn prune_history_and_backfill_index(
+    storage_writer: &mut StorageWriter,
+) -> Result<(), StorageError> {
+    let pruning_marker = storage_writer.prune_history_outside_retention()?;
+    debug!("Pruned the history of the blocks before block {pruning_marker}.");
+    storage_writer.backfill_event_keys_index()?;
+    Ok(())
+}
+
+// Returns the marker of the blocks that were fully stored, i.e. the blocks that have both their
+// state diff and (unless the storage is state-only) their body in the storage.
+fn get_stored_blocks_marker(storage_reader: &StorageReader) -> Result<BlockNumber, StorageError> {
+    let txn = storage_reader.begin_ro_txn()?;
+    let state_marker = txn.get_state_marker()?;
+    match storage_reader.get_scope() {
+        StorageScope::StateOnly => Ok(state_marker),
+        StorageScope::FullArchive => Ok(min(state_marker, txn.get_body_marker()?)),
+    }
+}
+
 pub(crate) struct InternalBlocksReceivers {
     header_receiver: Receiver<SyncBlock>,
     state_diff_receiver: Receiver<SyncBlock>,
@@ -33,6 +33,7 @@ use futures::future::BoxFuture;
 use futures::{FutureExt, SinkExt, StreamExt};
 use lazy_static::lazy_static;
 use papyrus_common::pending_classes::ApiContractClass;
+use papyrus_common::sync_notifications::SYNC_NOTIFICATIONS_CHANNEL_CAPACITY;
 use rand::{Rng, RngCore};
 use rand_chacha::ChaCha8Rng;
 use starknet_api::block::{
@@ -47,7 +48,7 @@ use starknet_api::crypto::utils::Signature;
 use starknet_api::hash::StarkHash;
 use starknet_api::transaction::FullTransaction;
 use starknet_types_core::felt::Felt;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 
 use super::{P2pSyncClient, P2pSyncClientChannels, P2pSyncClientConfig};
 
@@ -122,6 +123,7 @@ pub fn setup() -> TestArgs {
         p2p_sync_channels,
         futures::stream::pending().boxed(),
         class_manager_client,
+        broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY).0,
     );
     TestArgs {
         p2p_sync,
@@ -219,6 +221,7 @@ pub async fn run_test(
         p2p_sync_channels,
         internal_block_receiver.boxed(),
         class_manager_client,
+        broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY).0,
     );
 
     let mut headers_current_query_responses_manager = None;
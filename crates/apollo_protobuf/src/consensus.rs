@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockNumber, GasPrice};
 use starknet_api::consensus_transaction::ConsensusTransaction;
 use starknet_api::core::ContractAddress;
+use starknet_api::crypto::utils::Signature;
 use starknet_api::data_availability::L1DataAvailabilityMode;
 
 use crate::converters::ProtobufConversionError;
@@ -34,6 +35,8 @@ pub struct Vote {
     pub round: u32,
     pub block_hash: Option<BlockHash>,
     pub voter: ContractAddress,
+    /// The voter's signature over the rest of the vote. `None` for votes that were not yet signed.
+    pub signature: Option<Signature>,
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -7,6 +7,7 @@ use std::convert::{TryFrom, TryInto};
 use prost::Message;
 use starknet_api::block::{BlockHash, BlockNumber, GasPrice};
 use starknet_api::consensus_transaction::ConsensusTransaction;
+use starknet_api::crypto::utils::Signature;
 use starknet_api::hash::StarkHash;
 
 use super::common::{
@@ -60,8 +61,9 @@ impl TryFrom<protobuf::Vote> for Vote {
         let block_hash: Option<BlockHash> =
             value.block_hash.map(|block_hash| block_hash.try_into()).transpose()?.map(BlockHash);
         let voter = value.voter.ok_or(missing("voter"))?.try_into()?;
+        let signature = value.signature.map(|signature| signature.try_into()).transpose()?;
 
-        Ok(Vote { vote_type, height, round, block_hash, voter })
+        Ok(Vote { vote_type, height, round, block_hash, voter, signature })
     }
 }
 
@@ -78,12 +80,30 @@ impl From<Vote> for protobuf::Vote {
             round: value.round,
             block_hash: value.block_hash.map(|hash| hash.0.into()),
             voter: Some(value.voter.into()),
+            signature: value.signature.map(|signature| signature.into()),
         }
     }
 }
 
 auto_impl_into_and_try_from_vec_u8!(Vote, protobuf::Vote);
 
+impl TryFrom<protobuf::ConsensusSignature> for Signature {
+    type Error = ProtobufConversionError;
+
+    fn try_from(value: protobuf::ConsensusSignature) -> Result<Self, Self::Error> {
+        Ok(Signature {
+            r: value.r.ok_or(missing("ConsensusSignature::r"))?.try_into()?,
+            s: value.s.ok_or(missing("ConsensusSignature::s"))?.try_into()?,
+        })
+    }
+}
+
+impl From<Signature> for protobuf::ConsensusSignature {
+    fn from(value: Signature) -> Self {
+        Self { r: Some(value.r.into()), s: Some(value.s.into()) }
+    }
+}
+
 impl<T, StreamId> TryFrom<protobuf::StreamMessage> for StreamMessage<T, StreamId>
 where
     T: IntoFromProto,
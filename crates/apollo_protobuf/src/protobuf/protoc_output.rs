@@ -371,6 +371,9 @@ pub struct Vote {
     pub block_hash: ::core::option::Option<Hash>,
     #[prost(message, optional, tag = "6")]
     pub voter: ::core::option::Option<Address>,
+    /// The voter's signature over all of the above fields.
+    #[prost(message, optional, tag = "7")]
+    pub signature: ::core::option::Option<ConsensusSignature>,
 }
 /// Nested message and enum types in `Vote`.
 pub mod vote {
@@ -1,17 +1,19 @@
 use std::sync::Arc;
 
 use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_committer_types::SharedCommitterClient;
 use apollo_rpc_execution::ExecutionConfig;
 use apollo_starknet_client::reader::PendingData;
 use apollo_starknet_client::writer::StarknetWriter;
 use apollo_storage::StorageReader;
 use jsonrpsee::{Methods, RpcModule};
 use papyrus_common::pending_classes::PendingClasses;
+use papyrus_common::sync_notifications::SyncNotification;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
 use starknet_api::core::{ChainId, ContractAddress, EntryPointSelector};
 use starknet_api::transaction::fields::Calldata;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 use crate::v0_8::api::api_impl::JsonRpcServerImpl as JsonRpcServerV0_8Impl;
 use crate::version_config;
@@ -58,12 +60,15 @@ pub fn get_methods_from_supported_apis(
     storage_reader: StorageReader,
     max_events_chunk_size: usize,
     max_events_keys: usize,
+    max_storage_proof_keys: usize,
     starting_block: BlockHashAndNumber,
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     starknet_writer: Arc<dyn StarknetWriter>,
     class_manager_client: Option<SharedClassManagerClient>,
+    committer_client: Option<SharedCommitterClient>,
 ) -> Methods {
     let mut methods: Methods = Methods::new();
     let server_gen = JsonRpcServerImplGenerator {
@@ -72,12 +77,15 @@ pub fn get_methods_from_supported_apis(
         storage_reader,
         max_events_chunk_size,
         max_events_keys,
+        max_storage_proof_keys,
         starting_block,
         shared_highest_block,
         pending_data,
         pending_classes,
+        sync_notifications,
         starknet_writer,
         class_manager_client,
+        committer_client,
     };
     version_config::VERSION_CONFIG
         .iter()
@@ -112,12 +120,15 @@ pub trait JsonRpcServerTrait: Sized {
         storage_reader: StorageReader,
         max_events_chunk_size: usize,
         max_events_keys: usize,
+        max_storage_proof_keys: usize,
         starting_block: BlockHashAndNumber,
         shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
         pending_data: Arc<RwLock<PendingData>>,
         pending_classes: Arc<RwLock<PendingClasses>>,
+        sync_notifications: broadcast::Sender<SyncNotification>,
         starknet_writer: Arc<dyn StarknetWriter>,
         class_manager_client: Option<SharedClassManagerClient>,
+        committer_client: Option<SharedCommitterClient>,
     ) -> Self;
 
     fn into_rpc_module(self) -> RpcModule<Self>;
@@ -130,13 +141,16 @@ struct JsonRpcServerImplGenerator {
     storage_reader: StorageReader,
     max_events_chunk_size: usize,
     max_events_keys: usize,
+    max_storage_proof_keys: usize,
     starting_block: BlockHashAndNumber,
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     // TODO(shahak): Change this struct to be with a generic type of StarknetWriter.
     starknet_writer: Arc<dyn StarknetWriter>,
     class_manager_client: Option<SharedClassManagerClient>,
+    committer_client: Option<SharedCommitterClient>,
 }
 
 type JsonRpcServerImplParams = (
@@ -145,12 +159,15 @@ type JsonRpcServerImplParams = (
     StorageReader,
     usize,
     usize,
+    usize,
     BlockHashAndNumber,
     Arc<RwLock<Option<BlockHashAndNumber>>>,
     Arc<RwLock<PendingData>>,
     Arc<RwLock<PendingClasses>>,
+    broadcast::Sender<SyncNotification>,
     Arc<dyn StarknetWriter>,
     Option<SharedClassManagerClient>,
+    Option<SharedCommitterClient>,
 );
 
 impl JsonRpcServerImplGenerator {
@@ -161,12 +178,15 @@ impl JsonRpcServerImplGenerator {
             self.storage_reader,
             self.max_events_chunk_size,
             self.max_events_keys,
+            self.max_storage_proof_keys,
             self.starting_block,
             self.shared_highest_block,
             self.pending_data,
             self.pending_classes,
+            self.sync_notifications,
             self.starknet_writer,
             self.class_manager_client,
+            self.committer_client,
         )
     }
 
@@ -180,12 +200,15 @@ impl JsonRpcServerImplGenerator {
             storage_reader,
             max_events_chunk_size,
             max_events_keys,
+            max_storage_proof_keys,
             starting_block,
             shared_highest_block,
             pending_data,
             pending_classes,
+            sync_notifications,
             starknet_writer,
             class_manager_client,
+            committer_client,
         ) = self.get_params();
         Into::<Methods>::into(
             T::new(
@@ -194,12 +217,15 @@ impl JsonRpcServerImplGenerator {
                 storage_reader,
                 max_events_chunk_size,
                 max_events_keys,
+                max_storage_proof_keys,
                 starting_block,
                 shared_highest_block,
                 pending_data,
                 pending_classes,
+                sync_notifications,
                 starknet_writer,
                 class_manager_client,
+                committer_client,
             )
             .into_rpc_module(),
         )
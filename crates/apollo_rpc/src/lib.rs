@@ -19,6 +19,7 @@ use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
 use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_committer_types::SharedCommitterClient;
 use apollo_config::dumping::{prepend_sub_config_name, ser_param, SerializeConfig};
 use apollo_config::validators::validate_ascii;
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
@@ -38,11 +39,12 @@ use jsonrpsee::types::error::INTERNAL_ERROR_MSG;
 use jsonrpsee::types::ErrorObjectOwned;
 pub use latest::error;
 use papyrus_common::pending_classes::PendingClasses;
+use papyrus_common::sync_notifications::SyncNotification;
 use rpc_metrics::MetricLogger;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHashAndNumber, BlockNumber, BlockStatus};
 use starknet_api::core::ChainId;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, error, info, instrument};
 // Aliasing the latest version of the RPC.
 use v0_8 as latest;
@@ -75,6 +77,7 @@ pub struct RpcConfig {
     pub port: u16,
     pub max_events_chunk_size: usize,
     pub max_events_keys: usize,
+    pub max_storage_proof_keys: usize,
     // TODO(lev,shahak): remove once we remove papyrus.
     pub collect_metrics: bool,
     pub starknet_url: String,
@@ -90,6 +93,7 @@ impl Default for RpcConfig {
             port: 8090,
             max_events_chunk_size: 1000,
             max_events_keys: 100,
+            max_storage_proof_keys: 100,
             collect_metrics: false,
             starknet_url: String::from("https://alpha-mainnet.starknet.io/"),
             apollo_gateway_retry_config: RetryConfig {
@@ -134,6 +138,12 @@ impl SerializeConfig for RpcConfig {
                 "Maximum number of keys supported by the node in get_events requests.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_storage_proof_keys",
+                &self.max_storage_proof_keys,
+                "Maximum number of keys supported by the node in get_storage_proof requests.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "collect_metrics",
                 &self.collect_metrics,
@@ -215,9 +225,11 @@ pub async fn run_server(
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     storage_reader: StorageReader,
     node_version: &'static str,
     class_manager_client: Option<SharedClassManagerClient>,
+    committer_client: Option<SharedCommitterClient>,
 ) -> anyhow::Result<(SocketAddr, ServerHandle)> {
     let starting_block = get_last_synced_block(storage_reader.clone())?;
     debug!("Starting JSON-RPC.");
@@ -227,16 +239,19 @@ pub async fn run_server(
         storage_reader,
         config.max_events_chunk_size,
         config.max_events_keys,
+        config.max_storage_proof_keys,
         starting_block,
         shared_highest_block,
         pending_data,
         pending_classes,
+        sync_notifications,
         Arc::new(StarknetGatewayClient::new(
             &config.starknet_url,
             node_version,
             config.apollo_gateway_retry_config,
         )?),
         class_manager_client,
+        committer_client,
     );
     let addr;
     let handle;
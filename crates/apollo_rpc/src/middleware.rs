@@ -1,3 +1,4 @@
+use hyper::header::UPGRADE;
 use hyper::{Body, Request};
 use jsonrpsee::core::http_helpers::read_body;
 use regex::Regex;
@@ -12,6 +13,10 @@ use crate::SERVER_MAX_BODY_SIZE;
 /// method name with the appropriate version identifier. It returns a new [`hyper::Request`] object
 /// with the new method name.
 ///
+/// WebSocket upgrade requests are passed as is, since the messages of a WebSocket connection don't
+/// go through the middleware. Over WebSocket, the subscription methods are served under their
+/// unversioned names and the rest of the methods under their versioned names.
+///
 /// # Arguments
 /// * req - [`hyper::Request`] object passed by the server.
 ///
@@ -23,6 +28,9 @@ pub(crate) async fn proxy_rpc_request(req: Request<Body>) -> Result<Request<Body
     if !is_supported_path(req.uri().path()) {
         return Err(BoxError::from("Unsupported path for request"));
     }
+    if is_websocket_upgrade_request(&req) {
+        return Ok(req);
+    }
 
     let prefix = VERSION_0_8.name;
     let (parts, body) = req.into_parts();
@@ -74,6 +82,13 @@ fn strip_starknet_from_method(method: &str) -> Option<&str> {
     split_method_name.get(1).copied()
 }
 
+fn is_websocket_upgrade_request(req: &Request<Body>) -> bool {
+    req.headers()
+        .get(UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+}
+
 fn is_supported_path(path: &str) -> bool {
     let re = Regex::new((r"^\/rpc(\/".to_string() + VERSION_PATTERN + ")?$").as_str())
         .expect("should be a valid regex");
@@ -10,6 +10,8 @@ use jsonrpsee::types::Params;
 use jsonrpsee::Methods;
 use metrics::{counter, histogram};
 
+use crate::version_config::VERSION_0_8;
+
 // Name of the metrics.
 const INCOMING_REQUEST: &str = "rpc_incoming_requests";
 const FAILED_REQUESTS: &str = "rpc_failed_requests";
@@ -20,6 +22,9 @@ const METHOD_LABEL: &str = "method";
 const VERSION_LABEL: &str = "version";
 const ILLEGAL_METHOD: &str = "illegal_method";
 
+// The length of "starknet_".
+const STARKNET_PREFIX_LENGTH: usize = 9;
+
 // Register the metrics and returns a set of the method names.
 fn init_metrics(methods: &Methods) -> HashSet<String> {
     let mut methods_set: HashSet<String> = HashSet::new();
@@ -108,15 +113,24 @@ impl Logger for MetricLogger {
 
 // Given method_name returns (method, version).
 // Example: method_name: starknet_V0_6_0_blockNumber; output: (blockNumber, V0_6_0).
+// The subscription methods aren't versioned, and are labeled with the latest version.
+// Example: method_name: starknet_subscribeNewHeads; output: (subscribeNewHeads, V0_8).
 fn get_method_and_version(method_name: &str) -> (String, String) {
-    // The structure of method_name is in the following format: "starknet_V0_6_0_blockNumber".
+    // The structure of method_name is in the following format: "starknet_V0_6_0_blockNumber",
+    // or "starknet_subscribeNewHeads" for subscription methods.
     // Only method in this format will arrive to this point in the code.
     let last_underscore_index = method_name
         .rfind('_')
         .expect("method_name should be in the following format: starknet_V0_6_0_blockNumber");
+    if last_underscore_index < STARKNET_PREFIX_LENGTH {
+        return (
+            method_name[last_underscore_index + 1..].to_string(),
+            VERSION_0_8.name.to_string(),
+        );
+    }
 
     (
         method_name[last_underscore_index + 1..].to_string(),
-        method_name[9..last_underscore_index].to_string(),
+        method_name[STARKNET_PREFIX_LENGTH..last_underscore_index].to_string(),
     )
 }
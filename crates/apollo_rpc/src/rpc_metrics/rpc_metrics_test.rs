@@ -30,6 +30,7 @@ use crate::test_utils::{
     get_test_pending_classes,
     get_test_pending_data,
     get_test_rpc_config,
+    get_test_sync_notifications,
 };
 
 #[test]
@@ -38,6 +39,11 @@ fn get_method_and_version_test() {
     let (method, version) = get_method_and_version(method_name);
     assert_eq!(method, "blockNumber");
     assert_eq!(version, "V0_8_0");
+
+    let method_name = "starknet_subscribeNewHeads";
+    let (method, version) = get_method_and_version(method_name);
+    assert_eq!(method, "subscribeNewHeads");
+    assert_eq!(version, "V0_8");
 }
 
 // Ignored because server_metrics test is running in parallel and we are unable to install multiple
@@ -166,9 +172,11 @@ async fn server_metrics() {
         get_test_highest_block(),
         get_test_pending_data(),
         get_test_pending_classes(),
+        get_test_sync_notifications(),
         storage_reader,
         "NODE VERSION",
         None,
+        None,
     )
     .await
     .unwrap();
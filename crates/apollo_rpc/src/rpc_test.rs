@@ -22,6 +22,7 @@ use crate::test_utils::{
     get_test_pending_classes,
     get_test_pending_data,
     get_test_rpc_config,
+    get_test_sync_notifications,
 };
 use crate::{get_block_status, run_server};
 
@@ -37,9 +38,11 @@ async fn run_server_no_blocks() {
         shared_highest_block,
         pending_data,
         pending_classes,
+        get_test_sync_notifications(),
         storage_reader,
         "NODE VERSION",
         None,
+        None,
     )
     .await
     .unwrap();
@@ -1,6 +1,7 @@
 use std::path::Path;
 use std::sync::Arc;
 
+use apollo_committer_types::{MockCommitterClient, SharedCommitterClient};
 use apollo_rpc_execution::ExecutionConfig;
 use apollo_starknet_client::reader::PendingData;
 use apollo_starknet_client::writer::MockStarknetWriter;
@@ -11,6 +12,7 @@ use jsonrpsee::server::RpcModule;
 use jsonrpsee::types::ErrorObjectOwned;
 use jsonschema::JSONSchema;
 use papyrus_common::pending_classes::PendingClasses;
+use papyrus_common::sync_notifications::{SyncNotification, SYNC_NOTIFICATIONS_CHANNEL_CAPACITY};
 use pretty_assertions::assert_eq;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -20,7 +22,7 @@ use starknet_api::contract_address;
 use starknet_api::core::ChainId;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 use crate::api::JsonRpcServerTrait;
 use crate::version_config::{VersionId, VERSION_PATTERN};
@@ -38,6 +40,7 @@ pub fn get_test_rpc_config() -> RpcConfig {
         port: 0,
         max_events_chunk_size: 10,
         max_events_keys: 10,
+        max_storage_proof_keys: 10,
         collect_metrics: false,
         ..Default::default()
     }
@@ -55,9 +58,13 @@ pub(crate) fn get_test_pending_classes() -> Arc<RwLock<PendingClasses>> {
     Arc::new(RwLock::new(PendingClasses::default()))
 }
 
+pub(crate) fn get_test_sync_notifications() -> broadcast::Sender<SyncNotification> {
+    broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY).0
+}
+
 pub(crate) fn get_test_rpc_server_and_storage_writer<T: JsonRpcServerTrait>()
 -> (RpcModule<T>, StorageWriter) {
-    get_test_rpc_server_and_storage_writer_from_params(None, None, None, None, None)
+    get_test_rpc_server_and_storage_writer_from_params(None, None, None, None, None, None, None)
 }
 
 pub(crate) fn get_test_rpc_server_and_storage_writer_from_params<T: JsonRpcServerTrait>(
@@ -66,12 +73,16 @@ pub(crate) fn get_test_rpc_server_and_storage_writer_from_params<T: JsonRpcServe
     pending_data: Option<Arc<RwLock<PendingData>>>,
     pending_classes: Option<Arc<RwLock<PendingClasses>>>,
     storage_scope: Option<StorageScope>,
+    committer_client: Option<MockCommitterClient>,
+    sync_notifications: Option<broadcast::Sender<SyncNotification>>,
 ) -> (RpcModule<T>, StorageWriter) {
     let mock_client = mock_client.unwrap_or_default();
     let shared_highest_block = shared_highest_block.unwrap_or(get_test_highest_block());
     let pending_data = pending_data.unwrap_or(get_test_pending_data());
     let pending_classes = pending_classes.unwrap_or(get_test_pending_classes());
     let storage_scope = storage_scope.unwrap_or_default();
+    let committer_client = committer_client.map(|client| Arc::new(client) as SharedCommitterClient);
+    let sync_notifications = sync_notifications.unwrap_or_else(get_test_sync_notifications);
 
     let ((storage_reader, storage_writer), _temp_dir) = get_test_storage_by_scope(storage_scope);
     let config = get_test_rpc_config();
@@ -83,12 +94,15 @@ pub(crate) fn get_test_rpc_server_and_storage_writer_from_params<T: JsonRpcServe
             storage_reader,
             config.max_events_chunk_size,
             config.max_events_keys,
+            config.max_storage_proof_keys,
             BlockHashAndNumber::default(),
             shared_highest_block,
             pending_data,
             pending_classes,
+            sync_notifications,
             mock_client_arc,
             None,
+            committer_client,
         )
         .into_rpc_module(),
         storage_writer,
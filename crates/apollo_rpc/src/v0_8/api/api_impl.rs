@@ -1,6 +1,13 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_committer_types::{
+    CommitterClientError,
+    CommitterError,
+    SharedCommitterClient,
+    StorageProofRequest,
+};
 use apollo_rpc_execution::objects::{FeeEstimation, PendingData as ExecutionPendingData};
 use apollo_rpc_execution::{
     estimate_fee as exec_estimate_fee,
@@ -22,17 +29,20 @@ use apollo_starknet_client::reader::objects::transaction::{
 use apollo_starknet_client::reader::PendingData;
 use apollo_starknet_client::writer::{StarknetWriter, WriterClientError};
 use apollo_starknet_client::ClientError;
-use apollo_storage::body::events::{EventIndex, EventsReader};
+use apollo_storage::body::events::{EventIndex, EventIter, EventsReader};
 use apollo_storage::body::{BodyStorageReader, TransactionIndex};
 use apollo_storage::compiled_class::CasmStorageReader;
 use apollo_storage::db::{TransactionKind, RO};
+use apollo_storage::pruning::PruningStorageReader;
 use apollo_storage::state::StateStorageReader;
 use apollo_storage::{StorageError, StorageReader, StorageTxn};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::core::{RpcResult, SubscriptionResult};
 use jsonrpsee::types::ErrorObjectOwned;
-use jsonrpsee::RpcModule;
+use jsonrpsee::{PendingSubscriptionSink, RpcModule, SubscriptionSink};
 use papyrus_common::pending_classes::{PendingClasses, PendingClassesTrait};
+use papyrus_common::sync_notifications::SyncNotification;
+use serde::Serialize;
 use starknet_api::block::{
     BlockHash,
     BlockHeaderWithoutHash,
@@ -56,6 +66,7 @@ use starknet_api::transaction::fields::Fee;
 use starknet_api::transaction::{
     EventContent,
     EventIndexInTransactionOutput,
+    EventKey,
     Transaction as StarknetApiTransaction,
     TransactionHash,
     TransactionOffsetInBlock,
@@ -63,7 +74,8 @@ use starknet_api::transaction::{
 };
 use starknet_types_core::felt::Felt;
 use tokio::runtime::Handle;
-use tokio::sync::RwLock;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::{broadcast, RwLock};
 use tracing::{instrument, trace, warn};
 
 use super::super::block::{
@@ -90,11 +102,31 @@ use super::super::error::{
     INVALID_TRANSACTION_INDEX,
     NO_BLOCKS,
     PAGE_SIZE_TOO_BIG,
+    STORAGE_PROOF_NOT_SUPPORTED,
+    TOO_MANY_ADDRESSES_IN_FILTER,
+    TOO_MANY_BLOCKS_BACK,
     TOO_MANY_KEYS_IN_FILTER,
+    TOO_MANY_KEYS_IN_STORAGE_PROOF,
     TRANSACTION_HASH_NOT_FOUND,
 };
 use super::super::execution::TransactionTrace;
-use super::super::state::{AcceptedStateUpdate, PendingStateUpdate, StateUpdate};
+use super::super::state::{
+    AcceptedStateUpdate,
+    ContractStorageKeys,
+    PendingStateUpdate,
+    StateUpdate,
+    StorageProof,
+};
+use super::super::subscriptions::{
+    next_notification,
+    register_unsubscribe_method,
+    send_notification,
+    send_reorg_notification,
+    PendingTransaction,
+    TransactionStatusNotification,
+    MAX_BLOCKS_BACK,
+    MAX_SENDER_ADDRESSES_IN_FILTER,
+};
 use super::super::transaction::{
     get_block_tx_hashes_by_number,
     get_block_txs_by_number,
@@ -166,12 +198,15 @@ pub struct JsonRpcServerImpl {
     pub storage_reader: StorageReader,
     pub max_events_chunk_size: usize,
     pub max_events_keys: usize,
+    pub max_storage_proof_keys: usize,
     pub starting_block: BlockHashAndNumber,
     pub shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pub pending_data: Arc<RwLock<PendingData>>,
     pub pending_classes: Arc<RwLock<PendingClasses>>,
+    pub sync_notifications: broadcast::Sender<SyncNotification>,
     pub writer_client: Arc<dyn StarknetWriter>,
     pub class_manager_client: Option<SharedClassManagerClient>,
+    pub committer_client: Option<SharedCommitterClient>,
 }
 
 async fn create_class_manager_client(
@@ -499,15 +534,13 @@ impl JsonRpcServer for JsonRpcServerImpl {
         let block_number = get_accepted_block_number(&txn, block_id)?;
         let header: BlockHeader = get_block_header_by_number(&txn, block_number)?.into();
 
-        // Get the old root.
-        let old_root = match get_accepted_block_number(
-            &txn,
-            BlockId::HashOrNumber(BlockHashOrNumber::Hash(header.parent_hash)),
-        ) {
-            Ok(parent_block_number) => {
+        // Get the old root. The header of the parent block is kept even if its history was
+        // pruned.
+        let old_root = match block_number.prev() {
+            Some(parent_block_number) => {
                 BlockHeader::from(get_block_header_by_number(&txn, parent_block_number)?).new_root
             }
-            Err(_) => GlobalRoot(StarkHash::from_hex_unchecked(GENESIS_HASH)),
+            None => GlobalRoot(StarkHash::from_hex_unchecked(GENESIS_HASH)),
         };
 
         // Get the block state diff.
@@ -737,8 +770,10 @@ impl JsonRpcServer for JsonRpcServerImpl {
             // There are no blocks.
             return Ok(EventsChunk { events: vec![], continuation_token: None });
         };
+        // Events of pruned blocks were deleted, so by default the events are taken from the first
+        // block that wasn't pruned.
         let from_block_number = match filter.from_block {
-            None => BlockNumber(0),
+            None => txn.get_pruning_marker().map_err(internal_server_error)?,
             Some(BlockId::Tag(Tag::Pending)) => latest_block_number.unchecked_next(),
             Some(block_id) => get_accepted_block_number(&txn, block_id)?,
         };
@@ -774,10 +809,13 @@ impl JsonRpcServer for JsonRpcServerImpl {
         // pointing to the next relevant event. Otherwise, we return a continuation token None.
         let mut filtered_events = vec![];
         if start_event_index.0.0 <= latest_block_number {
-            for ((from_address, event_index), content) in txn
-                .iter_events(filter.address, start_event_index, to_block_number)
-                .map_err(internal_server_error)?
-            {
+            for ((from_address, event_index), content) in iter_events_for_filter(
+                &txn,
+                filter.address,
+                &filter.keys,
+                start_event_index,
+                to_block_number,
+            )? {
                 let block_number = (event_index.0).0;
                 if block_number > to_block_number {
                     break;
@@ -791,7 +829,7 @@ impl JsonRpcServer for JsonRpcServerImpl {
                     }
                 }
                 // TODO(Shahak): Consider changing empty sets in the filer keys to None.
-                if do_event_keys_match_filter(&content, &filter) {
+                if do_event_keys_match_filter(&content, &filter.keys) {
                     if filtered_events.len() == filter.chunk_size {
                         return Ok(EventsChunk {
                             events: filtered_events,
@@ -850,7 +888,7 @@ impl JsonRpcServer for JsonRpcServerImpl {
                             )?),
                         });
                     }
-                    if !do_event_keys_match_filter(&event.content, &filter) {
+                    if !do_event_keys_match_filter(&event.content, &filter.keys) {
                         continue;
                     }
                     if let Some(filter_address) = filter.address {
@@ -1551,6 +1589,215 @@ impl JsonRpcServer for JsonRpcServerImpl {
             SierraVersion::DEPRECATED,
         ))
     }
+
+    #[instrument(skip(self), level = "debug", err)]
+    async fn get_storage_proof(
+        &self,
+        block_id: BlockId,
+        class_hashes: Option<Vec<ClassHash>>,
+        contract_addresses: Option<Vec<ContractAddress>>,
+        contracts_storage_keys: Option<Vec<ContractStorageKeys>>,
+    ) -> RpcResult<StorageProof> {
+        // Proofs are served from the committer's tries, which are only kept for accepted blocks.
+        let Some(committer_client) = &self.committer_client else {
+            return Err(ErrorObjectOwned::from(STORAGE_PROOF_NOT_SUPPORTED));
+        };
+        if let BlockId::Tag(Tag::Pending) = block_id {
+            return Err(ErrorObjectOwned::from(STORAGE_PROOF_NOT_SUPPORTED));
+        }
+        let class_hashes = class_hashes.unwrap_or_default();
+        let contract_addresses = contract_addresses.unwrap_or_default();
+        let contracts_storage_keys = contracts_storage_keys.unwrap_or_default();
+        let n_keys = class_hashes.len()
+            + contract_addresses.len()
+            + contracts_storage_keys.iter().map(|keys| keys.storage_keys.len()).sum::<usize>();
+        if n_keys > self.max_storage_proof_keys {
+            return Err(ErrorObjectOwned::from(TOO_MANY_KEYS_IN_STORAGE_PROOF));
+        }
+
+        let (block_number, block_hash) = {
+            let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+            let block_number = get_accepted_block_number(&txn, block_id)?;
+            (block_number, get_block_header_by_number(&txn, block_number)?.block_hash)
+        };
+
+        let request = StorageProofRequest {
+            class_hashes,
+            contract_addresses,
+            contracts_storage_keys: contracts_storage_keys.into_iter().map(Into::into).collect(),
+        };
+        let proof = committer_client.get_storage_proof(block_number, request).await.map_err(
+            |err| match err {
+                CommitterClientError::CommitterError(CommitterError::BlockNotCommitted(_)) => {
+                    ErrorObjectOwned::from(STORAGE_PROOF_NOT_SUPPORTED)
+                }
+                err => internal_server_error(err),
+            },
+        )?;
+        Ok(StorageProof::new(proof, block_hash))
+    }
+
+    async fn subscribe_new_heads(
+        &self,
+        pending: PendingSubscriptionSink,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult {
+        // Subscribe before reading the storage, so that no block is missed.
+        let receiver = self.sync_notifications.subscribe();
+        let next_block = match self.get_subscription_starting_block(block_id) {
+            Ok(next_block) => next_block,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(());
+            }
+        };
+        let sink = pending.accept().await?;
+        self.run_block_subscription(sink, receiver, next_block, |txn, block_number| {
+            Ok(vec![BlockHeader::from(get_block_header_by_number(txn, block_number)?)])
+        })
+        .await
+    }
+
+    async fn subscribe_events(
+        &self,
+        pending: PendingSubscriptionSink,
+        from_address: Option<ContractAddress>,
+        keys: Option<Vec<HashSet<EventKey>>>,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult {
+        let keys = keys.unwrap_or_default();
+        let receiver = self.sync_notifications.subscribe();
+        let next_block = match verify_storage_scope(&self.storage_reader)
+            .and_then(|()| {
+                if keys.len() > self.max_events_keys {
+                    return Err(ErrorObjectOwned::from(TOO_MANY_KEYS_IN_FILTER));
+                }
+                Ok(())
+            })
+            .and_then(|()| self.get_subscription_starting_block(block_id))
+        {
+            Ok(next_block) => next_block,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(());
+            }
+        };
+        let sink = pending.accept().await?;
+        self.run_block_subscription(sink, receiver, next_block, |txn, block_number| {
+            get_block_events(txn, block_number, from_address, &keys)
+        })
+        .await
+    }
+
+    async fn subscribe_transaction_status(
+        &self,
+        pending: PendingSubscriptionSink,
+        transaction_hash: TransactionHash,
+    ) -> SubscriptionResult {
+        if let Err(err) = verify_storage_scope(&self.storage_reader) {
+            pending.reject(err).await;
+            return Ok(());
+        }
+        let mut receiver = self.sync_notifications.subscribe();
+        let sink = pending.accept().await?;
+
+        let mut last_sent_status = None;
+        // The block that contained the transaction when its status was last sent.
+        let mut last_block_number = None;
+        loop {
+            if let Some((status, block_number)) =
+                self.get_transaction_status_and_block_number(transaction_hash).await?
+            {
+                if last_sent_status.as_ref() != Some(&status) {
+                    send_notification(
+                        &sink,
+                        &TransactionStatusNotification { transaction_hash, status: status.clone() },
+                    )
+                    .await?;
+                    last_sent_status = Some(status);
+                }
+                if block_number.is_some() {
+                    last_block_number = block_number;
+                }
+            }
+
+            let Some(notification) = next_notification(&sink, &mut receiver).await? else {
+                return Ok(());
+            };
+            if let SyncNotification::Reorg { first_reverted, last_reverted } = notification {
+                if last_block_number.is_some_and(|block_number| {
+                    first_reverted.number <= block_number && block_number <= last_reverted.number
+                }) {
+                    send_reorg_notification(&sink, first_reverted, last_reverted).await?;
+                    last_sent_status = None;
+                    last_block_number = None;
+                }
+            }
+        }
+    }
+
+    async fn subscribe_pending_transactions(
+        &self,
+        pending: PendingSubscriptionSink,
+        transaction_details: Option<bool>,
+        sender_address: Option<Vec<ContractAddress>>,
+    ) -> SubscriptionResult {
+        let sender_addresses = sender_address.unwrap_or_default();
+        if sender_addresses.len() > MAX_SENDER_ADDRESSES_IN_FILTER {
+            pending.reject(ErrorObjectOwned::from(TOO_MANY_ADDRESSES_IN_FILTER)).await;
+            return Ok(());
+        }
+        let sender_addresses = sender_addresses.into_iter().collect::<HashSet<_>>();
+        let transaction_details = transaction_details.unwrap_or(false);
+        let mut receiver = self.sync_notifications.subscribe();
+        // Only the transactions that are added to the pending block after subscribing are sent.
+        let pending_block = match self.read_latest_pending_data().await {
+            Ok(pending_data) => pending_data.block,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(());
+            }
+        };
+        let sink = pending.accept().await?;
+
+        let mut parent_block_hash = pending_block.parent_block_hash();
+        let mut n_seen_transactions = pending_block.transactions().len();
+        loop {
+            let Some(notification) = next_notification(&sink, &mut receiver).await? else {
+                return Ok(());
+            };
+            if notification != SyncNotification::PendingDataUpdated {
+                continue;
+            }
+            let pending_block = self.read_latest_pending_data().await?.block;
+            if pending_block.parent_block_hash() != parent_block_hash {
+                parent_block_hash = pending_block.parent_block_hash();
+                n_seen_transactions = 0;
+            }
+            for client_transaction in pending_block.transactions().iter().skip(n_seen_transactions)
+            {
+                if !sender_addresses.is_empty()
+                    && !get_sender_address(client_transaction)
+                        .is_some_and(|address| sender_addresses.contains(&address))
+                {
+                    continue;
+                }
+                let transaction_hash = client_transaction.transaction_hash();
+                let pending_transaction = if transaction_details {
+                    let starknet_api_transaction: StarknetApiTransaction =
+                        client_transaction.clone().try_into().map_err(internal_server_error)?;
+                    PendingTransaction::Full(TransactionWithHash {
+                        transaction: starknet_api_transaction.try_into()?,
+                        transaction_hash,
+                    })
+                } else {
+                    PendingTransaction::Hash(transaction_hash)
+                };
+                send_notification(&sink, &pending_transaction).await?;
+            }
+            n_seen_transactions = pending_block.transactions().len();
+        }
+    }
 }
 
 async fn read_pending_data<Mode: TransactionKind>(
@@ -1709,6 +1956,99 @@ impl JsonRpcServerImpl {
         });
         Ok(ThinStateDiff::from(thin_state_diff, replaced_classes))
     }
+
+    // Returns the first block a block subscription should notify about, which is the given block
+    // or the latest block by default.
+    fn get_subscription_starting_block(&self, block_id: Option<BlockId>) -> RpcResult<BlockNumber> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let Some(latest_block_number) = get_latest_block_number(&txn)? else {
+            // There are no blocks, so the subscription starts from the first block.
+            return match block_id {
+                None | Some(BlockId::Tag(_)) => Ok(BlockNumber(0)),
+                Some(BlockId::HashOrNumber(_)) => Err(ErrorObjectOwned::from(BLOCK_NOT_FOUND)),
+            };
+        };
+        let starting_block_number = match block_id {
+            None => latest_block_number,
+            Some(block_id) => get_accepted_block_number(&txn, block_id)?,
+        };
+        if latest_block_number.0 - starting_block_number.0 > MAX_BLOCKS_BACK {
+            return Err(ErrorObjectOwned::from(TOO_MANY_BLOCKS_BACK));
+        }
+        Ok(starting_block_number)
+    }
+
+    // Sends the items of each block from the given block, as the blocks are synced. When blocks
+    // that were already notified about are reverted, sends a reorg notification and continues from
+    // the first reverted block.
+    async fn run_block_subscription<Item: Serialize + Send>(
+        &self,
+        sink: SubscriptionSink,
+        mut receiver: Receiver<SyncNotification>,
+        mut next_block: BlockNumber,
+        get_block_items: impl Fn(&StorageTxn<'_, RO>, BlockNumber) -> RpcResult<Vec<Item>> + Send + Sync,
+    ) -> SubscriptionResult {
+        loop {
+            for item in self.get_new_blocks_items(&mut next_block, &get_block_items)? {
+                send_notification(&sink, &item).await?;
+            }
+
+            let Some(notification) = next_notification(&sink, &mut receiver).await? else {
+                return Ok(());
+            };
+            if let SyncNotification::Reorg { first_reverted, last_reverted } = notification {
+                if first_reverted.number < next_block {
+                    send_reorg_notification(&sink, first_reverted, last_reverted).await?;
+                    next_block = first_reverted.number;
+                }
+            }
+        }
+    }
+
+    // Returns the items of the blocks from the given block up to the latest block, and advances the
+    // given block past the latest block.
+    fn get_new_blocks_items<Item>(
+        &self,
+        next_block: &mut BlockNumber,
+        get_block_items: &impl Fn(&StorageTxn<'_, RO>, BlockNumber) -> RpcResult<Vec<Item>>,
+    ) -> RpcResult<Vec<Item>> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let Some(latest_block_number) = get_latest_block_number(&txn)? else {
+            return Ok(vec![]);
+        };
+        let mut items = vec![];
+        while *next_block <= latest_block_number {
+            items.extend(get_block_items(&txn, *next_block)?);
+            *next_block = next_block.unchecked_next();
+        }
+        Ok(items)
+    }
+
+    // Returns the status of the given transaction and the block that contains it, or None if the
+    // transaction is unknown.
+    async fn get_transaction_status_and_block_number(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<Option<(TransactionStatus, Option<BlockNumber>)>> {
+        match self.get_transaction_receipt(transaction_hash).await {
+            Ok(receipt) => {
+                let block_number = match &receipt {
+                    GeneralTransactionReceipt::TransactionReceipt(receipt) => {
+                        Some(receipt.block_number)
+                    }
+                    GeneralTransactionReceipt::PendingTransactionReceipt(_) => None,
+                };
+                Ok(Some((receipt.transaction_status(), block_number)))
+            }
+            Err(err) if err.code() == TRANSACTION_HASH_NOT_FOUND.code => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn read_latest_pending_data(&self) -> RpcResult<PendingData> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        read_pending_data(&self.pending_data, &txn).await
+    }
 }
 
 fn get_non_pending_receipt<Mode: TransactionKind>(
@@ -1771,8 +2111,84 @@ fn client_receipt_to_rpc_pending_receipt(
     }))
 }
 
-fn do_event_keys_match_filter(event_content: &EventContent, filter: &EventFilter) -> bool {
-    filter.keys.iter().enumerate().all(|(i, keys)| {
+// Returns the address of the account that sent the given transaction, if it was sent by an
+// account.
+fn get_sender_address(client_transaction: &ClientTransaction) -> Option<ContractAddress> {
+    match client_transaction {
+        ClientTransaction::Declare(transaction) => Some(transaction.sender_address),
+        ClientTransaction::DeployAccount(transaction) => Some(transaction.sender_address),
+        ClientTransaction::Invoke(transaction) => Some(transaction.sender_address),
+        ClientTransaction::Deploy(_) | ClientTransaction::L1Handler(_) => None,
+    }
+}
+
+// Returns the events of the given block that match the given address and keys.
+fn get_block_events(
+    txn: &StorageTxn<'_, RO>,
+    block_number: BlockNumber,
+    from_address: Option<ContractAddress>,
+    keys: &[HashSet<EventKey>],
+) -> RpcResult<Vec<Event>> {
+    let header = get_block_header_by_number(txn, block_number)?;
+    let start_event_index = EventIndex(
+        TransactionIndex(block_number, TransactionOffsetInBlock(0)),
+        EventIndexInTransactionOutput(0),
+    );
+    let mut events = vec![];
+    for ((event_from_address, event_index), content) in
+        iter_events_for_filter(txn, from_address, keys, start_event_index, block_number)?
+    {
+        if event_index.0.0 > block_number {
+            break;
+        }
+        // When filtering by address, the iterator outputs events of other addresses only after
+        // the events of the filter's address.
+        if from_address.is_some_and(|from_address| from_address != event_from_address) {
+            break;
+        }
+        if !do_event_keys_match_filter(&content, keys) {
+            continue;
+        }
+        let transaction_hash = txn
+            .get_transaction_hash_by_idx(&event_index.0)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| internal_server_error("Unknown internal error."))?;
+        events.push(Event {
+            block_hash: Some(header.block_hash),
+            block_number: Some(block_number),
+            transaction_hash,
+            event: starknet_api::transaction::Event { from_address: event_from_address, content },
+        });
+    }
+    Ok(events)
+}
+
+// Returns an iterator over the events from the given index that may match the given address and
+// keys. When filtering only by keys, the first keys are looked up in the event keys index instead
+// of scanning all the events, unless the index is still backfilled for the requested blocks.
+fn iter_events_for_filter<'txn, 'env>(
+    txn: &'env StorageTxn<'env, RO>,
+    address: Option<ContractAddress>,
+    keys: &[HashSet<EventKey>],
+    start_event_index: EventIndex,
+    to_block_number: BlockNumber,
+) -> RpcResult<EventIter<'txn, 'env>> {
+    let is_indexed =
+        txn.get_event_keys_index_marker().map_err(internal_server_error)? <= start_event_index.0.0;
+    match (address, keys.first()) {
+        (None, Some(first_keys)) if is_indexed && !first_keys.is_empty() => {
+            txn.iter_events_by_first_keys(first_keys.clone(), start_event_index, to_block_number)
+        }
+        _ => txn.iter_events(address, start_event_index, to_block_number),
+    }
+    .map_err(internal_server_error)
+}
+
+fn do_event_keys_match_filter(
+    event_content: &EventContent,
+    filter_keys: &[HashSet<EventKey>],
+) -> bool {
+    filter_keys.iter().enumerate().all(|(i, keys)| {
         event_content.keys.len() > i && (keys.is_empty() || keys.contains(&event_content.keys[i]))
     })
 }
@@ -1784,12 +2200,15 @@ impl JsonRpcServerTrait for JsonRpcServerImpl {
         storage_reader: StorageReader,
         max_events_chunk_size: usize,
         max_events_keys: usize,
+        max_storage_proof_keys: usize,
         starting_block: BlockHashAndNumber,
         shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
         pending_data: Arc<RwLock<PendingData>>,
         pending_classes: Arc<RwLock<PendingClasses>>,
+        sync_notifications: broadcast::Sender<SyncNotification>,
         writer_client: Arc<dyn StarknetWriter>,
         class_manager_client: Option<SharedClassManagerClient>,
+        committer_client: Option<SharedCommitterClient>,
     ) -> Self {
         Self {
             chain_id,
@@ -1797,16 +2216,21 @@ impl JsonRpcServerTrait for JsonRpcServerImpl {
             storage_reader,
             max_events_chunk_size,
             max_events_keys,
+            max_storage_proof_keys,
             starting_block,
             shared_highest_block,
             pending_data,
             pending_classes,
+            sync_notifications,
             writer_client,
             class_manager_client,
+            committer_client,
         }
     }
 
     fn into_rpc_module(self) -> RpcModule<Self> {
-        self.into_rpc()
+        let mut module = self.into_rpc();
+        register_unsubscribe_method(&mut module);
+        module
     }
 }
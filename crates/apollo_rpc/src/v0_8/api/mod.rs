@@ -11,7 +11,7 @@ use apollo_storage::state::StateStorageReader;
 use apollo_storage::StorageTxn;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use flate2::bufread::GzDecoder;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::core::{RpcResult, SubscriptionResult};
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::types::ErrorObjectOwned;
 use papyrus_common::deprecated_class_abi::calculate_deprecated_class_abi_length;
@@ -30,7 +30,7 @@ use starknet_api::transaction::{EventKey, TransactionHash, TransactionOffsetInBl
 use starknet_types_core::felt::Felt;
 use tracing::debug;
 
-use super::block::Block;
+use super::block::{Block, BlockHeader};
 use super::broadcasted_transaction::{
     BroadcastedDeclareTransaction,
     BroadcastedDeclareV1Transaction,
@@ -45,7 +45,8 @@ use super::error::{
     INVALID_CONTINUATION_TOKEN,
 };
 use super::execution::TransactionTrace;
-use super::state::{ContractClass, StateUpdate};
+use super::state::{ContractClass, ContractStorageKeys, StateUpdate, StorageProof};
+use super::subscriptions::{PendingTransaction, TransactionStatusNotification};
 use super::transaction::{
     DeployAccountTransaction,
     DeployAccountTransactionV1,
@@ -265,6 +266,64 @@ pub trait JsonRpc {
         block_id: BlockId,
         class_hash: ClassHash,
     ) -> RpcResult<(CompiledContractClass, SierraVersion)>;
+
+    /// Returns Merkle proofs of the given classes, contracts and contract storage entries in the
+    /// state tries of the given block.
+    #[method(name = "getStorageProof")]
+    async fn get_storage_proof(
+        &self,
+        block_id: BlockId,
+        class_hashes: Option<Vec<ClassHash>>,
+        contract_addresses: Option<Vec<ContractAddress>>,
+        contracts_storage_keys: Option<Vec<ContractStorageKeys>>,
+    ) -> RpcResult<StorageProof>;
+
+    /// Subscribes to the headers of new blocks, starting from the given block (the latest block by
+    /// default).
+    #[subscription(
+        name = "subscribeNewHeads" => "subscriptionNewHeads",
+        unsubscribe = "unsubscribeNewHeads",
+        item = BlockHeader
+    )]
+    async fn subscribe_new_heads(&self, block_id: Option<BlockId>) -> SubscriptionResult;
+
+    /// Subscribes to the events of new blocks that match the given address and keys, starting from
+    /// the given block (the latest block by default).
+    #[subscription(
+        name = "subscribeEvents" => "subscriptionEvents",
+        unsubscribe = "unsubscribeEvents",
+        item = Event
+    )]
+    async fn subscribe_events(
+        &self,
+        from_address: Option<ContractAddress>,
+        keys: Option<Vec<HashSet<EventKey>>>,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult;
+
+    /// Subscribes to the changes in the status of the given transaction.
+    #[subscription(
+        name = "subscribeTransactionStatus" => "subscriptionTransactionStatus",
+        unsubscribe = "unsubscribeTransactionStatus",
+        item = TransactionStatusNotification
+    )]
+    async fn subscribe_transaction_status(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> SubscriptionResult;
+
+    /// Subscribes to the transactions that are added to the pending block, optionally only the
+    /// ones sent by the given addresses.
+    #[subscription(
+        name = "subscribePendingTransactions" => "subscriptionPendingTransactions",
+        unsubscribe = "unsubscribePendingTransactions",
+        item = PendingTransaction
+    )]
+    async fn subscribe_pending_transactions(
+        &self,
+        transaction_details: Option<bool>,
+        sender_address: Option<Vec<ContractAddress>>,
+    ) -> SubscriptionResult;
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
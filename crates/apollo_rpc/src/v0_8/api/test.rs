@@ -4,6 +4,17 @@ use std::iter;
 use std::net::SocketAddr;
 use std::ops::Index;
 
+use apollo_committer_types::{
+    CommitterClientError,
+    CommitterError,
+    ContractLeafData as CommitterContractLeafData,
+    ContractStorageKeys as CommitterContractStorageKeys,
+    MerkleNode as CommitterMerkleNode,
+    MockCommitterClient,
+    StorageProof as CommitterStorageProof,
+    StorageProofRequest,
+    TrieProof,
+};
 use apollo_starknet_client::reader::objects::pending_data::{
     DeprecatedPendingBlock,
     PendingBlockOrDeprecated,
@@ -43,9 +54,10 @@ use apollo_storage::body::{BodyStorageWriter, TransactionIndex};
 use apollo_storage::class::ClassStorageWriter;
 use apollo_storage::compiled_class::CasmStorageWriter;
 use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::pruning::PruningStorageWriter;
 use apollo_storage::state::StateStorageWriter;
 use apollo_storage::test_utils::get_test_storage;
-use apollo_storage::StorageScope;
+use apollo_storage::{StorageScope, StorageWriter};
 use apollo_test_utils::{
     auto_impl_get_test_instance,
     get_number_of_variants,
@@ -62,11 +74,12 @@ use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use indexmap::{indexmap, IndexMap};
 use itertools::Itertools;
 use jsonrpsee::core::Error;
-use jsonrpsee::Methods;
+use jsonrpsee::{rpc_params, Methods};
 use jsonschema::JSONSchema;
 use lazy_static::lazy_static;
 use mockall::predicate::eq;
 use papyrus_common::pending_classes::{ApiContractClass, PendingClassesTrait};
+use papyrus_common::sync_notifications::SyncNotification;
 use pretty_assertions::assert_eq;
 use rand::{random, RngCore};
 use rand_chacha::ChaCha8Rng;
@@ -74,6 +87,7 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{
     Block as StarknetApiBlock,
+    BlockBody,
     BlockHash,
     BlockHashAndNumber,
     BlockHeader,
@@ -113,41 +127,66 @@ use starknet_api::transaction::{
     TransactionOffsetInBlock,
     TransactionOutput as StarknetApiTransactionOutput,
 };
-use starknet_api::{class_hash, contract_address, felt, storage_key, tx_hash};
+use starknet_api::{class_hash, contract_address, felt, nonce, storage_key, tx_hash};
 use starknet_types_core::felt::Felt;
 
 use super::super::api::EventsChunk;
-use super::super::block::{Block, GeneralBlockHeader, PendingBlockHeader};
+use super::super::block::{
+    Block,
+    BlockHeader as RpcBlockHeader,
+    GeneralBlockHeader,
+    PendingBlockHeader,
+};
 use super::super::broadcasted_transaction::BroadcastedDeclareTransaction;
 use super::super::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use super::super::error::{
     unexpected_error,
     JsonRpcError,
     BLOCK_NOT_FOUND,
+    BLOCK_PRUNED,
     CLASS_HASH_NOT_FOUND,
     COMPILATION_FAILED,
     CONTRACT_NOT_FOUND,
     DUPLICATE_TX,
     INVALID_CONTINUATION_TOKEN,
+    INVALID_SUBSCRIPTION_ID,
     INVALID_TRANSACTION_INDEX,
     NO_BLOCKS,
     PAGE_SIZE_TOO_BIG,
+    STORAGE_PROOF_NOT_SUPPORTED,
+    TOO_MANY_ADDRESSES_IN_FILTER,
+    TOO_MANY_BLOCKS_BACK,
     TOO_MANY_KEYS_IN_FILTER,
+    TOO_MANY_KEYS_IN_STORAGE_PROOF,
     TRANSACTION_HASH_NOT_FOUND,
 };
 use super::super::state::{
     AcceptedStateUpdate,
     ClassHashes,
     ContractClass,
+    ContractLeafData,
     ContractNonce,
+    ContractStorageKeys,
+    ContractsProof,
     DeployedContract,
+    GlobalRoots,
+    MerkleNode,
+    NodeHashToNode,
     PendingStateUpdate,
     ReplacedClass,
     StateUpdate,
     StorageDiff,
     StorageEntry,
+    StorageProof,
     ThinStateDiff,
 };
+use super::super::subscriptions::{
+    PendingTransaction,
+    ReorgData,
+    TransactionStatusNotification,
+    MAX_BLOCKS_BACK,
+    MAX_SENDER_ADDRESSES_IN_FILTER,
+};
 use super::super::transaction::{
     DeployAccountTransaction,
     Event,
@@ -191,9 +230,11 @@ use crate::test_utils::{
     get_test_rpc_config,
     get_test_rpc_server_and_storage_writer,
     get_test_rpc_server_and_storage_writer_from_params,
+    get_test_sync_notifications,
     method_name_to_spec_method_name,
     raw_call,
     validate_schema,
+    SerializeJsonValue,
     SpecFile,
 };
 use crate::v0_8::api::CompiledContractClass;
@@ -369,6 +410,8 @@ async fn syncing() {
         None,
         None,
         None,
+        None,
+        None,
     );
 
     call_api_then_assert_and_validate_schema_for_result(
@@ -398,9 +441,16 @@ async fn syncing() {
 async fn get_block_transaction_count() {
     let method_name = "starknet_V0_8_getBlockTransactionCount";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let transaction_count = 5;
     let block = get_test_block(transaction_count, None, None, None);
     storage_writer
@@ -485,13 +535,75 @@ async fn get_block_transaction_count() {
     assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
 }
 
+#[tokio::test]
+async fn get_block_transaction_count_of_pruned_block() {
+    let method_name = "starknet_V0_8_getBlockTransactionCount";
+    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
+        JsonRpcServerImpl,
+    >(None, None, None, None, None, None, None);
+    let block = get_test_block(1, None, None, None);
+    let header1 = BlockHeader {
+        block_hash: BlockHash(felt!("0x1")),
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: BlockNumber(1),
+            parent_hash: block.header.block_hash,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &block.header)
+        .unwrap()
+        .append_body(BlockNumber(0), block.body)
+        .unwrap()
+        .append_state_diff(BlockNumber(0), starknet_api::state::ThinStateDiff::default())
+        .unwrap()
+        .append_header(BlockNumber(1), &header1)
+        .unwrap()
+        .append_body(BlockNumber(1), BlockBody::default())
+        .unwrap()
+        .append_state_diff(BlockNumber(1), starknet_api::state::ThinStateDiff::default())
+        .unwrap()
+        .prune_history(BlockNumber(1))
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let err = module
+        .call::<_, usize>(
+            method_name,
+            [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(0)))],
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_PRUNED.into());
+
+    let res = module
+        .call::<_, usize>(
+            method_name,
+            [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(1)))],
+        )
+        .await
+        .unwrap();
+    assert_eq!(res, 0);
+}
+
 #[tokio::test]
 async fn get_block_w_full_transactions() {
     let method_name = "starknet_V0_8_getBlockWithTxs";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
 
     let mut block = get_test_block(1, None, None, None);
     let block_hash = BlockHash(random::<u64>().into());
@@ -675,9 +787,16 @@ async fn get_block_w_full_transactions() {
 async fn get_block_w_full_transactions_and_receipts() {
     let method_name = "starknet_V0_8_getBlockWithReceipts";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
 
     let mut block = get_test_block(1, None, None, None);
     let block_hash = BlockHash(random::<u64>().into());
@@ -877,9 +996,16 @@ async fn get_block_w_full_transactions_and_receipts() {
 async fn get_block_w_transaction_hashes() {
     let method_name = "starknet_V0_8_getBlockWithTxHashes";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
 
     let mut block = get_test_block(1, None, None, None);
     let block_hash = BlockHash(random::<u64>().into());
@@ -1064,9 +1190,16 @@ async fn get_block_w_transaction_hashes() {
 async fn get_class() {
     let method_name = "starknet_V0_8_getClass";
     let pending_classes = get_test_pending_classes();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, None, Some(pending_classes.clone()), None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            None,
+            Some(pending_classes.clone()),
+            None,
+            None,
+            None,
+        );
     let parent_header = BlockHeader::default();
     let header = BlockHeader {
         block_hash: BlockHash(felt!("0x1")),
@@ -1246,9 +1379,16 @@ async fn get_class() {
 async fn get_transaction_status() {
     let method_name = "starknet_V0_8_getTransactionStatus";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let block = get_test_block(1, None, None, None);
     storage_writer
         .begin_rw_txn()
@@ -1365,9 +1505,16 @@ async fn get_transaction_status() {
 async fn get_transaction_receipt() {
     let method_name = "starknet_V0_8_getTransactionReceipt";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let block = get_test_block(1, None, None, None);
     storage_writer
         .begin_rw_txn()
@@ -1497,6 +1644,8 @@ async fn get_class_at() {
             Some(pending_data.clone()),
             Some(pending_classes.clone()),
             None,
+            None,
+            None,
         );
     let parent_header = BlockHeader::default();
     let header = BlockHeader {
@@ -1704,9 +1853,16 @@ async fn get_class_at() {
 async fn get_class_hash_at() {
     let method_name = "starknet_V0_8_getClassHashAt";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let header = BlockHeader::default();
     let diff = starknet_api::state::ThinStateDiff::from(get_test_state_diff());
     storage_writer
@@ -1867,9 +2023,16 @@ async fn get_class_hash_at() {
 async fn get_nonce() {
     let method_name = "starknet_V0_8_getNonce";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let header = BlockHeader::default();
     let diff = starknet_api::state::ThinStateDiff::from(get_test_state_diff());
     storage_writer
@@ -2008,57 +2171,278 @@ async fn get_nonce() {
 }
 
 #[tokio::test]
-async fn get_storage_at() {
-    let method_name = "starknet_V0_8_getStorageAt";
-    let pending_data = get_test_pending_data();
+async fn get_storage_proof() {
+    let method_name = "starknet_V0_8_getStorageProof";
+    let class_hash = class_hash!("0x2");
+    let contract_address = contract_address!("0x1");
+    let storage_key = storage_key!("0x3");
+
+    let mut committer_client = MockCommitterClient::new();
+    let expected_request = StorageProofRequest {
+        class_hashes: vec![class_hash],
+        contract_addresses: vec![contract_address],
+        contracts_storage_keys: vec![CommitterContractStorageKeys {
+            contract_address,
+            storage_keys: vec![storage_key],
+        }],
+    };
+    committer_client
+        .expect_get_storage_proof()
+        .withf(move |height, request| *height == BlockNumber(0) && *request == expected_request)
+        .returning(move |_, _| {
+            Ok(CommitterStorageProof {
+                classes_proof: TrieProof::from([(
+                    felt!("0x10"),
+                    CommitterMerkleNode::Edge {
+                        path: felt!("0x2"),
+                        length: 251,
+                        child: felt!("0x4"),
+                    },
+                )]),
+                contracts_proof: TrieProof::from([(
+                    felt!("0x20"),
+                    CommitterMerkleNode::Binary { left: felt!("0x21"), right: felt!("0x22") },
+                )]),
+                contract_leaves_data: vec![CommitterContractLeafData {
+                    nonce: nonce!(1),
+                    class_hash,
+                    storage_root: felt!("0x30"),
+                }],
+                contracts_storage_proofs: vec![TrieProof::from([(
+                    felt!("0x30"),
+                    CommitterMerkleNode::Edge {
+                        path: felt!("0x3"),
+                        length: 251,
+                        child: felt!("0x5"),
+                    },
+                )])],
+                contracts_tree_root: felt!("0x20"),
+                classes_tree_root: felt!("0x10"),
+            })
+        });
+    // The committer lags behind the second block.
+    committer_client
+        .expect_get_storage_proof()
+        .withf(|height, _| *height == BlockNumber(1))
+        .returning(|height, _| {
+            Err(CommitterClientError::CommitterError(CommitterError::BlockNotCommitted(height)))
+        });
     let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
         JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    >(
+        None, None, None, None, None, Some(committer_client), None
+    );
+
     let header = BlockHeader::default();
-    let diff = starknet_api::state::ThinStateDiff::from(get_test_state_diff());
+    let second_header = BlockHeader {
+        block_hash: BlockHash(random::<u64>().into()),
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: BlockNumber(1),
+            parent_hash: header.block_hash,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
     storage_writer
         .begin_rw_txn()
         .unwrap()
-        .append_header(header.block_header_without_hash.block_number, &header)
+        .append_header(BlockNumber(0), &header)
         .unwrap()
-        .append_state_diff(header.block_header_without_hash.block_number, diff.clone())
+        .append_state_diff(BlockNumber(0), starknet_api::state::ThinStateDiff::default())
+        .unwrap()
+        .append_header(BlockNumber(1), &second_header)
+        .unwrap()
+        .append_state_diff(BlockNumber(1), starknet_api::state::ThinStateDiff::default())
         .unwrap()
         .commit()
         .unwrap();
 
-    let (address, storage_entries) = diff.storage_diffs.get_index(0).unwrap();
-    let (key, expected_value) = storage_entries.get_index(0).unwrap();
+    let params = |block_id: BlockId| -> Vec<Box<dyn SerializeJsonValue>> {
+        vec![
+            Box::new(block_id),
+            Box::new(vec![class_hash]),
+            Box::new(vec![contract_address]),
+            Box::new(vec![ContractStorageKeys {
+                contract_address,
+                storage_keys: vec![storage_key],
+            }]),
+        ]
+    };
 
-    // Get storage by block hash.
     call_api_then_assert_and_validate_schema_for_result(
         &module,
         method_name,
-        vec![
-            Box::new(*address),
-            Box::new(*key),
-            Box::new(BlockId::HashOrNumber(BlockHashOrNumber::Hash(header.block_hash))),
-        ],
+        params(BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(0)))),
         &VERSION,
         SpecFile::StarknetApiOpenrpc,
-        expected_value,
+        &StorageProof {
+            classes_proof: vec![NodeHashToNode {
+                node_hash: felt!("0x10"),
+                node: MerkleNode::EdgeNode { path: felt!("0x2"), length: 251, child: felt!("0x4") },
+            }],
+            contracts_proof: ContractsProof {
+                nodes: vec![NodeHashToNode {
+                    node_hash: felt!("0x20"),
+                    node: MerkleNode::BinaryNode { left: felt!("0x21"), right: felt!("0x22") },
+                }],
+                contract_leaves_data: vec![ContractLeafData {
+                    nonce: nonce!(1),
+                    class_hash,
+                    storage_root: felt!("0x30"),
+                }],
+            },
+            contracts_storage_proofs: vec![vec![NodeHashToNode {
+                node_hash: felt!("0x30"),
+                node: MerkleNode::EdgeNode { path: felt!("0x3"), length: 251, child: felt!("0x5") },
+            }]],
+            global_roots: GlobalRoots {
+                contracts_tree_root: felt!("0x20"),
+                classes_tree_root: felt!("0x10"),
+                block_hash: header.block_hash,
+            },
+        },
     )
     .await;
 
-    // Get storage by block number.
-    let res = module
-        .call::<_, Felt>(
+    // Ask for a block that the committer didn't commit yet.
+    call_api_then_assert_and_validate_schema_for_err::<_, StorageProof>(
+        &module,
+        method_name,
+        params(BlockId::Tag(Tag::Latest)),
+        &VERSION,
+        SpecFile::StarknetApiOpenrpc,
+        &STORAGE_PROOF_NOT_SUPPORTED.into(),
+    )
+    .await;
+
+    // Ask for the pending block, whose state isn't committed.
+    call_api_then_assert_and_validate_schema_for_err::<_, StorageProof>(
+        &module,
+        method_name,
+        params(BlockId::Tag(Tag::Pending)),
+        &VERSION,
+        SpecFile::StarknetApiOpenrpc,
+        &STORAGE_PROOF_NOT_SUPPORTED.into(),
+    )
+    .await;
+
+    // Ask for an invalid block number.
+    call_api_then_assert_and_validate_schema_for_err::<_, StorageProof>(
+        &module,
+        method_name,
+        params(BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(2)))),
+        &VERSION,
+        SpecFile::StarknetApiOpenrpc,
+        &BLOCK_NOT_FOUND.into(),
+    )
+    .await;
+
+    // A node without a committer doesn't support storage proofs.
+    let (module, _) = get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    call_api_then_assert_and_validate_schema_for_err::<_, StorageProof>(
+        &module,
+        method_name,
+        params(BlockId::Tag(Tag::Latest)),
+        &VERSION,
+        SpecFile::StarknetApiOpenrpc,
+        &STORAGE_PROOF_NOT_SUPPORTED.into(),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn get_storage_proof_with_too_many_keys() {
+    let method_name = "starknet_V0_8_getStorageProof";
+    // The committer must not be reached.
+    let committer_client = MockCommitterClient::new();
+    let (module, _) = get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(committer_client),
+        None,
+    );
+
+    // The test config allows 10 keys: 4 classes, 4 contracts and 3 storage keys exceed it.
+    let err = module
+        .call::<_, StorageProof>(
             method_name,
-            (
-                *address,
-                *key,
-                BlockId::HashOrNumber(BlockHashOrNumber::Number(
-                    header.block_header_without_hash.block_number,
-                )),
-            ),
+            rpc_params![
+                BlockId::Tag(Tag::Latest),
+                vec![class_hash!("0x1"); 4],
+                vec![contract_address!("0x1"); 4],
+                vec![ContractStorageKeys {
+                    contract_address: contract_address!("0x1"),
+                    storage_keys: vec![storage_key!("0x1"); 3],
+                }]
+            ],
         )
         .await
-        .unwrap();
-    assert_eq!(res, *expected_value);
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == TOO_MANY_KEYS_IN_STORAGE_PROOF.into());
+}
+
+#[tokio::test]
+async fn get_storage_at() {
+    let method_name = "starknet_V0_8_getStorageAt";
+    let pending_data = get_test_pending_data();
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
+    let header = BlockHeader::default();
+    let diff = starknet_api::state::ThinStateDiff::from(get_test_state_diff());
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(header.block_header_without_hash.block_number, &header)
+        .unwrap()
+        .append_state_diff(header.block_header_without_hash.block_number, diff.clone())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let (address, storage_entries) = diff.storage_diffs.get_index(0).unwrap();
+    let (key, expected_value) = storage_entries.get_index(0).unwrap();
+
+    // Get storage by block hash.
+    call_api_then_assert_and_validate_schema_for_result(
+        &module,
+        method_name,
+        vec![
+            Box::new(*address),
+            Box::new(*key),
+            Box::new(BlockId::HashOrNumber(BlockHashOrNumber::Hash(header.block_hash))),
+        ],
+        &VERSION,
+        SpecFile::StarknetApiOpenrpc,
+        expected_value,
+    )
+    .await;
+
+    // Get storage by block number.
+    let res = module
+        .call::<_, Felt>(
+            method_name,
+            (
+                *address,
+                *key,
+                BlockId::HashOrNumber(BlockHashOrNumber::Number(
+                    header.block_header_without_hash.block_number,
+                )),
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res, *expected_value);
 
     // Ask for storage in pending block when contract's storage wasn't changed in pending block.
     let res = module
@@ -2276,9 +2660,16 @@ fn generate_client_transaction_and_rpc_transaction(
 async fn get_transaction_by_hash() {
     let method_name = "starknet_V0_8_getTransactionByHash";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let mut block = get_test_block(1, None, None, None);
     // Change the transaction hash from 0 to a random value, so that later on we can add a
     // transaction with 0 hash to the pending block.
@@ -2354,6 +2745,8 @@ async fn get_transaction_by_hash_state_only() {
         None,
         None,
         Some(StorageScope::StateOnly),
+        None,
+        None,
     );
 
     let (_, err) = raw_call::<_, _, TransactionWithHash>(&module, method_name, &params).await;
@@ -2367,9 +2760,16 @@ async fn get_transaction_by_hash_state_only() {
 async fn get_transaction_by_block_id_and_index() {
     let method_name = "starknet_V0_8_getTransactionByBlockIdAndIndex";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let block = get_test_block(1, None, None, None);
     storage_writer
         .begin_rw_txn()
@@ -2500,9 +2900,16 @@ async fn get_transaction_by_block_id_and_index() {
 async fn get_state_update() {
     let method_name = "starknet_V0_8_getStateUpdate";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let parent_header = BlockHeader::default();
     let expected_pending_old_root = GlobalRoot(felt!("0x1234"));
     let header = BlockHeader {
@@ -2665,9 +3072,16 @@ async fn get_state_update() {
 async fn get_state_update_with_replaced_class() {
     let method_name = "starknet_V0_8_getStateUpdate";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let parent_header = BlockHeader::default();
     let expected_pending_old_root = GlobalRoot(felt!("0x1234"));
     let header = BlockHeader {
@@ -2749,9 +3163,16 @@ async fn get_state_update_with_replaced_class() {
 async fn get_state_update_with_empty_storage_diff() {
     let method_name = "starknet_V0_8_getStateUpdate";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let state_diff = starknet_api::state::ThinStateDiff {
         storage_diffs: indexmap!(ContractAddress::default() => indexmap![]),
         ..Default::default()
@@ -2888,9 +3309,16 @@ async fn test_get_events(
 ) {
     let method_name = "starknet_V0_8_getEvents";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            None,
+        );
     let mut rng = get_rng();
 
     let mut event_index_to_event = HashMap::<EventIndex, Event>::new();
@@ -3490,6 +3918,374 @@ async fn get_events_invalid_ct() {
     .await;
 }
 
+// Appends blocks with the events described by the given metadata on top of the given blocks.
+fn append_blocks_with_events(
+    storage_writer: &mut StorageWriter,
+    rng: &mut ChaCha8Rng,
+    blocks: &mut Vec<StarknetApiBlock>,
+    block_metadatas: &[BlockMetadata],
+) {
+    let mut rw_txn = storage_writer.begin_rw_txn().unwrap();
+    for block_metadata in block_metadatas {
+        let (parent_hash, block_number) = match blocks.last() {
+            Some(block) => (
+                block.header.block_hash,
+                block.header.block_header_without_hash.block_number.unchecked_next(),
+            ),
+            None => (BlockHash(felt!(GENESIS_HASH)), BlockNumber(0)),
+        };
+        let block = block_metadata.generate_block(rng, parent_hash, block_number);
+        rw_txn = rw_txn
+            .append_header(block_number, &block.header)
+            .unwrap()
+            .append_body(block_number, block.body.clone())
+            .unwrap()
+            .append_state_diff(block_number, starknet_api::state::ThinStateDiff::default())
+            .unwrap();
+        blocks.push(block);
+    }
+    rw_txn.commit().unwrap();
+}
+
+#[tokio::test]
+async fn subscribe_new_heads() {
+    let method_name = "starknet_subscribeNewHeads";
+    let sync_notifications = get_test_sync_notifications();
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(sync_notifications.clone()),
+        );
+    let mut rng = get_rng();
+    let mut blocks = vec![];
+    append_blocks_with_events(
+        &mut storage_writer,
+        &mut rng,
+        &mut blocks,
+        &[BlockMetadata::default(), BlockMetadata::default()],
+    );
+
+    // The headers of the blocks from the given block are sent once subscribing.
+    let mut subscription = module
+        .subscribe_unbounded(
+            method_name,
+            [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(0)))],
+        )
+        .await
+        .unwrap();
+    for block in &blocks {
+        let (header, _) = subscription.next::<RpcBlockHeader>().await.unwrap().unwrap();
+        assert_eq!(header, RpcBlockHeader::from(block.header.clone()));
+    }
+
+    // The header of a new block is sent once the sync notifies about it.
+    append_blocks_with_events(
+        &mut storage_writer,
+        &mut rng,
+        &mut blocks,
+        &[BlockMetadata::default()],
+    );
+    sync_notifications.send(SyncNotification::NewBlock(BlockNumber(2))).unwrap();
+    let (header, _) = subscription.next::<RpcBlockHeader>().await.unwrap().unwrap();
+    assert_eq!(header, RpcBlockHeader::from(blocks[2].header.clone()));
+
+    // Revert the last block. The subscriber was notified about it, so it gets a reorg
+    // notification.
+    let reverted_block = blocks.pop().unwrap();
+    let reverted_block_number = reverted_block.header.block_header_without_hash.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .revert_state_diff(reverted_block_number)
+        .unwrap()
+        .0
+        .revert_body(reverted_block_number)
+        .unwrap()
+        .0
+        .revert_header(reverted_block_number)
+        .unwrap()
+        .0
+        .commit()
+        .unwrap();
+    let reverted = BlockHashAndNumber {
+        hash: reverted_block.header.block_hash,
+        number: reverted_block_number,
+    };
+    sync_notifications
+        .send(SyncNotification::Reorg { first_reverted: reverted, last_reverted: reverted })
+        .unwrap();
+    let (reorg_data, _) = subscription.next::<ReorgData>().await.unwrap().unwrap();
+    assert_eq!(reorg_data, ReorgData::new(reverted, reverted));
+
+    // The header of the block that replaced the reverted block is sent.
+    append_blocks_with_events(
+        &mut storage_writer,
+        &mut rng,
+        &mut blocks,
+        &[BlockMetadata::default()],
+    );
+    sync_notifications.send(SyncNotification::NewBlock(reverted_block_number)).unwrap();
+    let (header, _) = subscription.next::<RpcBlockHeader>().await.unwrap().unwrap();
+    assert_eq!(header, RpcBlockHeader::from(blocks[2].header.clone()));
+}
+
+#[tokio::test]
+async fn subscribe_events() {
+    let method_name = "starknet_subscribeEvents";
+    let sync_notifications = get_test_sync_notifications();
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(sync_notifications.clone()),
+        );
+    let mut rng = get_rng();
+    let address = contract_address!("0x22");
+    let key = EventKey(felt!("0x33"));
+    let matching_event_metadata = EventMetadata { address: Some(address), keys: Some(vec![key]) };
+    let block_metadata = BlockMetadata(vec![vec![
+        DEFAULT_EVENT_METADATA,
+        matching_event_metadata.clone(),
+        EventMetadata { address: Some(address), keys: None },
+    ]]);
+    let mut blocks = vec![];
+    append_blocks_with_events(
+        &mut storage_writer,
+        &mut rng,
+        &mut blocks,
+        &[block_metadata.clone()],
+    );
+    let get_matching_event = |block: &StarknetApiBlock| Event {
+        block_hash: Some(block.header.block_hash),
+        block_number: Some(block.header.block_header_without_hash.block_number),
+        transaction_hash: block.body.transaction_hashes[0],
+        event: block.body.transaction_outputs[0].events()[1].clone(),
+    };
+
+    let mut subscription = module
+        .subscribe_unbounded(
+            method_name,
+            (
+                Some(address),
+                Some(vec![HashSet::from([key])]),
+                Some(BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(0)))),
+            ),
+        )
+        .await
+        .unwrap();
+    let (event, _) = subscription.next::<Event>().await.unwrap().unwrap();
+    assert_eq!(event, get_matching_event(&blocks[0]));
+
+    // The matching events of a new block are sent once the sync notifies about it.
+    append_blocks_with_events(&mut storage_writer, &mut rng, &mut blocks, &[block_metadata]);
+    sync_notifications.send(SyncNotification::NewBlock(BlockNumber(1))).unwrap();
+    let (event, _) = subscription.next::<Event>().await.unwrap().unwrap();
+    assert_eq!(event, get_matching_event(&blocks[1]));
+}
+
+#[tokio::test]
+async fn subscribe_transaction_status() {
+    let method_name = "starknet_subscribeTransactionStatus";
+    let pending_data = get_test_pending_data();
+    let sync_notifications = get_test_sync_notifications();
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data.clone()),
+            None,
+            None,
+            None,
+            Some(sync_notifications.clone()),
+        );
+    let mut rng = get_rng();
+    let (client_transaction, client_transaction_receipt, _, pending_receipt) =
+        generate_client_transaction_client_receipt_rpc_transaction_and_rpc_receipt(&mut rng);
+    let transaction_hash = client_transaction.transaction_hash();
+    {
+        let pending_block = &mut pending_data.write().await.block;
+        pending_block.transactions_mutable().push(client_transaction);
+        pending_block.transaction_receipts_mutable().push(client_transaction_receipt);
+    }
+
+    // The current status of the transaction is sent once subscribing.
+    let mut subscription =
+        module.subscribe_unbounded(method_name, [transaction_hash]).await.unwrap();
+    let (notification, _) =
+        subscription.next::<TransactionStatusNotification>().await.unwrap().unwrap();
+    assert_eq!(
+        notification,
+        TransactionStatusNotification {
+            transaction_hash,
+            status: TransactionStatus {
+                finality_status: TransactionFinalityStatus::AcceptedOnL2,
+                execution_status: pending_receipt.output.execution_status().clone(),
+            },
+        }
+    );
+
+    // The transaction is included in a block that is accepted on L1.
+    let mut block = get_test_block(1, None, None, None);
+    block.body.transaction_hashes[0] = transaction_hash;
+    let block_number = block.header.block_header_without_hash.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &block.header)
+        .unwrap()
+        .append_body(block_number, block.body)
+        .unwrap()
+        .append_state_diff(block_number, starknet_api::state::ThinStateDiff::default())
+        .unwrap()
+        .update_base_layer_block_marker(&block_number.unchecked_next())
+        .unwrap()
+        .commit()
+        .unwrap();
+    sync_notifications.send(SyncNotification::AcceptedOnL1(block_number.unchecked_next())).unwrap();
+    let (notification, _) =
+        subscription.next::<TransactionStatusNotification>().await.unwrap().unwrap();
+    assert_eq!(notification.transaction_hash, transaction_hash);
+    assert_eq!(notification.status.finality_status, TransactionFinalityStatus::AcceptedOnL1);
+}
+
+#[tokio::test]
+async fn subscribe_pending_transactions() {
+    let method_name = "starknet_subscribePendingTransactions";
+    let pending_data = get_test_pending_data();
+    let sync_notifications = get_test_sync_notifications();
+    let (module, _) = get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+        None,
+        None,
+        Some(pending_data.clone()),
+        None,
+        None,
+        None,
+        Some(sync_notifications.clone()),
+    );
+    let mut rng = get_rng();
+    // A transaction that is in the pending block before subscribing isn't sent.
+    let (old_transaction, _) = generate_client_transaction_and_rpc_transaction(&mut rng);
+    pending_data.write().await.block.transactions_mutable().push(old_transaction);
+
+    let (invoke_transaction, rpc_invoke_transaction) = loop {
+        let (client_transaction, rpc_transaction) =
+            generate_client_transaction_and_rpc_transaction(&mut rng);
+        if let ClientTransaction::Invoke(_) = client_transaction {
+            break (client_transaction, rpc_transaction);
+        }
+    };
+    let ClientTransaction::Invoke(invoke) = &invoke_transaction else {
+        unreachable!("The transaction should be an invoke transaction.");
+    };
+    let sender_address = invoke.sender_address;
+
+    let mut hashes_subscription = module.subscribe_unbounded(method_name, [false]).await.unwrap();
+    let mut details_subscription = module.subscribe_unbounded(method_name, [true]).await.unwrap();
+    let mut filtered_subscription = module
+        .subscribe_unbounded(method_name, (Option::<bool>::None, Some(vec![sender_address])))
+        .await
+        .unwrap();
+
+    let (other_transaction, rpc_other_transaction) =
+        generate_client_transaction_and_rpc_transaction(&mut rng);
+    pending_data
+        .write()
+        .await
+        .block
+        .transactions_mutable()
+        .extend([other_transaction, invoke_transaction]);
+    sync_notifications.send(SyncNotification::PendingDataUpdated).unwrap();
+
+    for rpc_transaction in [rpc_other_transaction, rpc_invoke_transaction.clone()] {
+        let (notification, _) =
+            hashes_subscription.next::<PendingTransaction>().await.unwrap().unwrap();
+        assert_eq!(notification, PendingTransaction::Hash(rpc_transaction.transaction_hash));
+        let (notification, _) =
+            details_subscription.next::<PendingTransaction>().await.unwrap().unwrap();
+        assert_eq!(notification, PendingTransaction::Full(rpc_transaction));
+    }
+    let (notification, _) =
+        filtered_subscription.next::<PendingTransaction>().await.unwrap().unwrap();
+    assert_eq!(notification, PendingTransaction::Hash(rpc_invoke_transaction.transaction_hash));
+}
+
+#[tokio::test]
+async fn subscribe_invalid_params() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+
+    // Subscribe to events with too many keys.
+    let keys = vec![HashSet::<EventKey>::new(); get_test_rpc_config().max_events_keys + 1];
+    let err = module
+        .subscribe_unbounded(
+            "starknet_subscribeEvents",
+            (Option::<ContractAddress>::None, Some(keys), Option::<BlockId>::None),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == TOO_MANY_KEYS_IN_FILTER.into());
+
+    // Subscribe to pending transactions with too many sender addresses.
+    let sender_addresses = vec![ContractAddress::default(); MAX_SENDER_ADDRESSES_IN_FILTER + 1];
+    let err = module
+        .subscribe_unbounded(
+            "starknet_subscribePendingTransactions",
+            (Option::<bool>::None, Some(sender_addresses)),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == TOO_MANY_ADDRESSES_IN_FILTER.into());
+
+    // Subscribe to new heads from a block that doesn't exist.
+    let err = module
+        .subscribe_unbounded(
+            "starknet_subscribeNewHeads",
+            [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(1)))],
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+
+    // Subscribe to new heads from a block that is too far behind the latest block.
+    let block_metadatas =
+        vec![BlockMetadata::default(); usize::try_from(MAX_BLOCKS_BACK).unwrap() + 2];
+    append_blocks_with_events(&mut storage_writer, &mut get_rng(), &mut vec![], &block_metadatas);
+    let err = module
+        .subscribe_unbounded(
+            "starknet_subscribeNewHeads",
+            [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(0)))],
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == TOO_MANY_BLOCKS_BACK.into());
+}
+
+#[tokio::test]
+async fn unsubscribe() {
+    let method_name = "starknet_unsubscribe";
+    let (module, _) = get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    let subscription = module
+        .subscribe_unbounded("starknet_subscribePendingTransactions", rpc_params![])
+        .await
+        .unwrap();
+    let subscription_id = subscription.subscription_id().clone();
+
+    assert!(module.call::<_, bool>(method_name, [subscription_id.clone()]).await.unwrap());
+
+    // The subscription was already cancelled.
+    let err = module.call::<_, bool>(method_name, [subscription_id]).await.unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == INVALID_SUBSCRIPTION_ID.into());
+}
+
 #[tokio::test]
 async fn serialize_returns_valid_json() {
     let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
@@ -3561,9 +4357,11 @@ async fn serialize_returns_valid_json() {
         get_test_highest_block(),
         get_test_pending_data(),
         get_test_pending_classes(),
+        get_test_sync_notifications(),
         storage_reader,
         NODE_VERSION,
         None,
+        None,
     )
     .await
     .unwrap();
@@ -3773,7 +4571,7 @@ async fn get_compiled_class() {
     let method_name = "starknet_V0_8_getCompiledContractClass";
     let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
         JsonRpcServerImpl,
-    >(None, None, None, None, None);
+    >(None, None, None, None, None, None, None);
     let cairo1_contract_class = CasmContractClass::get_test_instance(&mut get_rng());
     // We need to save the Sierra component of the Cairo 1 contract in storage to maintain
     // consistency.
@@ -3886,6 +4684,8 @@ where
             None,
             None,
             None,
+            None,
+            None,
         );
         call_api_then_assert_and_validate_schema_for_result(
             &module,
@@ -3920,6 +4720,8 @@ where
             None,
             None,
             None,
+            None,
+            None,
         );
         let result = module.call::<_, Self::Response>(Self::METHOD_NAME, [tx]).await;
         let jsonrpsee::core::Error::Call(error) = result.unwrap_err() else {
@@ -3954,6 +4756,8 @@ where
             None,
             None,
             None,
+            None,
+            None,
         );
         let result = module.call::<_, Self::Response>(Self::METHOD_NAME, [tx]).await;
         let jsonrpsee::core::Error::Call(error) = result.unwrap_err() else {
@@ -3985,6 +4789,8 @@ where
             None,
             None,
             None,
+            None,
+            None,
         );
         let result = module.call::<_, Self::Response>(Self::METHOD_NAME, [tx]).await;
         let jsonrpsee::core::Error::Call(error) = result.unwrap_err() else {
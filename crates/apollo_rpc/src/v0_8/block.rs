@@ -1,5 +1,6 @@
 use apollo_storage::db::TransactionKind;
 use apollo_storage::header::HeaderStorageReader;
+use apollo_storage::pruning::PruningStorageReader;
 use apollo_storage::{StorageError, StorageReader, StorageTxn};
 use jsonrpsee::types::ErrorObjectOwned;
 use serde::{Deserialize, Serialize};
@@ -7,7 +8,7 @@ use starknet_api::block::{BlockHash, BlockNumber, BlockStatus, BlockTimestamp, G
 use starknet_api::core::{GlobalRoot, SequencerContractAddress};
 use starknet_api::data_availability::L1DataAvailabilityMode;
 
-use super::error::BLOCK_NOT_FOUND;
+use super::error::{BLOCK_NOT_FOUND, BLOCK_PRUNED};
 use super::transaction::Transactions;
 use crate::api::{BlockHashOrNumber, BlockId, Tag};
 use crate::{get_latest_block_number, internal_server_error};
@@ -97,12 +98,12 @@ pub fn get_block_header_by_number<Mode: TransactionKind>(
 
 /// Return the closest block number that corresponds to the given block id and is accepted (i.e not
 /// pending). Latest block means the most advanced block that we've downloaded and that we've
-/// downloaded its state diff.
+/// downloaded its state diff. Returns an error if the history of the block was pruned.
 pub(crate) fn get_accepted_block_number<Mode: TransactionKind>(
     txn: &StorageTxn<'_, Mode>,
     block_id: BlockId,
 ) -> Result<BlockNumber, ErrorObjectOwned> {
-    Ok(match block_id {
+    let block_number = match block_id {
         BlockId::HashOrNumber(BlockHashOrNumber::Hash(block_hash)) => {
             let block_number = txn
                 .get_block_number_by_hash(&block_hash)
@@ -129,7 +130,11 @@ pub(crate) fn get_accepted_block_number<Mode: TransactionKind>(
         BlockId::Tag(Tag::Latest | Tag::Pending) => {
             get_latest_block_number(txn)?.ok_or_else(|| ErrorObjectOwned::from(BLOCK_NOT_FOUND))?
         }
-    })
+    };
+    if block_number < txn.get_pruning_marker().map_err(internal_server_error)? {
+        return Err(ErrorObjectOwned::from(BLOCK_PRUNED));
+    }
+    Ok(block_number)
 }
 
 /// Validates that a given block wasn't reverted. Given an instance of this class, we can call its
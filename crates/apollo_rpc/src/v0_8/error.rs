@@ -72,6 +72,13 @@ impl From<TransactionExecutionError> for JsonRpcError<TransactionExecutionError>
         Self { code: 41, message: "Transaction execution error", data: Some(tx_execution_error) }
     }
 }
+
+pub const STORAGE_PROOF_NOT_SUPPORTED: JsonRpcError<String> = JsonRpcError {
+    code: 42,
+    message: "the node doesn't support storage proofs for blocks that are too far in the past",
+    data: None,
+};
+
 pub const CLASS_ALREADY_DECLARED: JsonRpcError<String> =
     JsonRpcError { code: 51, message: "Class already declared", data: None };
 
@@ -125,6 +132,45 @@ pub fn unexpected_error(data: String) -> JsonRpcError<String> {
     JsonRpcError { code: 63, message: "An unexpected error occurred", data: Some(data) }
 }
 
+pub const INVALID_SUBSCRIPTION_ID: JsonRpcError<String> =
+    JsonRpcError { code: 66, message: "Invalid subscription id", data: None };
+
+pub const TOO_MANY_ADDRESSES_IN_FILTER: JsonRpcError<String> = JsonRpcError {
+    code: 67,
+    message: "Too many addresses in filter sender_address filter",
+    data: None,
+};
+
+pub const TOO_MANY_BLOCKS_BACK: JsonRpcError<String> =
+    JsonRpcError { code: 68, message: "Cannot go back more than 1024 blocks", data: None };
+
+pub const BLOCK_PRUNED: JsonRpcError<String> = JsonRpcError {
+    code: 69,
+    message: "The history of the requested block was pruned by the node",
+    data: None,
+};
+
+pub const NONCE_TOO_FAR_AHEAD: JsonRpcError<String> = JsonRpcError {
+    code: 70,
+    message: "The transaction nonce is too far ahead of the account nonce",
+    data: None,
+};
+
+pub const ACCOUNT_TRANSACTION_LIMIT_EXCEEDED: JsonRpcError<String> = JsonRpcError {
+    code: 71,
+    message: "The account has too many transactions in the mempool",
+    data: None,
+};
+
+pub const ACCOUNT_CAPACITY_EXCEEDED: JsonRpcError<String> = JsonRpcError {
+    code: 72,
+    message: "The account's transactions in the mempool exceed its capacity",
+    data: None,
+};
+
+pub const TOO_MANY_KEYS_IN_STORAGE_PROOF: JsonRpcError<String> =
+    JsonRpcError { code: 73, message: "Too many keys requested in a storage proof", data: None };
+
 impl<T: Serialize> From<JsonRpcError<T>> for ErrorObjectOwned {
     fn from(err: JsonRpcError<T>) -> Self {
         ErrorObjectOwned::owned(err.code, err.message, err.data)
@@ -328,11 +328,16 @@ async fn pending_execution_call() {
     let pending_data = get_test_pending_data();
     let pending_classes = get_test_pending_classes();
     write_block_0_as_pending(pending_data.clone(), pending_classes.clone()).await;
-    let (module, storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(
-        None, None, Some(pending_data), Some(pending_classes), None
-    );
+    let (module, storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data),
+            Some(pending_classes),
+            None,
+            None,
+            None,
+        );
     write_empty_block(storage_writer);
 
     let key = felt!(1234_u16);
@@ -509,11 +514,16 @@ async fn pending_call_estimate_fee() {
     let pending_data = get_test_pending_data();
     let pending_classes = get_test_pending_classes();
     write_block_0_as_pending(pending_data.clone(), pending_classes.clone()).await;
-    let (module, storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(
-        None, None, Some(pending_data), Some(pending_classes), None
-    );
+    let (module, storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data),
+            Some(pending_classes),
+            None,
+            None,
+            None,
+        );
     write_empty_block(storage_writer);
 
     let account_address = contract_address!("0x444");
@@ -564,11 +574,16 @@ async fn pending_call_simulate() {
     let pending_data = get_test_pending_data();
     let pending_classes = get_test_pending_classes();
     write_block_0_as_pending(pending_data.clone(), pending_classes.clone()).await;
-    let (module, storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(
-        None, None, Some(pending_data), Some(pending_classes), None
-    );
+    let (module, storage_writer) =
+        get_test_rpc_server_and_storage_writer_from_params::<JsonRpcServerImpl>(
+            None,
+            None,
+            Some(pending_data),
+            Some(pending_classes),
+            None,
+            None,
+            None,
+        );
     write_empty_block(storage_writer);
 
     test_call_simulate(&module, BlockId::Tag(Tag::Pending), BlockNumber(1)).await;
@@ -911,7 +926,7 @@ async fn trace_block_transactions_regular_and_pending() {
 
     let (module, storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
         JsonRpcServerImpl,
-    >(None, None, Some(pending_data), None, None);
+    >(None, None, Some(pending_data), None, None, None, None);
 
     prepare_storage_for_execution(storage_writer);
 
@@ -1163,7 +1178,7 @@ async fn pending_trace_block_transactions_and_trace_transaction_execution_contex
 
     let (module, storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
         JsonRpcServerImpl,
-    >(None, None, Some(pending_data), None, None);
+    >(None, None, Some(pending_data), None, None, None, None);
 
     prepare_storage_for_execution(storage_writer);
 
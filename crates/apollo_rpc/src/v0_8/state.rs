@@ -1,5 +1,12 @@
 use std::collections::HashMap;
 
+use apollo_committer_types::{
+    ContractLeafData as CommitterContractLeafData,
+    ContractStorageKeys as CommitterContractStorageKeys,
+    MerkleNode as CommitterMerkleNode,
+    StorageProof as CommitterStorageProof,
+    TrieProof,
+};
 use apollo_starknet_client::reader::objects::state::{
     DeclaredClassHashEntry as ClientDeclaredClassHashEntry,
     DeployedContract as ClientDeployedContract,
@@ -250,3 +257,105 @@ pub struct ReplacedClass {
     pub contract_address: ContractAddress,
     pub class_hash: ClassHash,
 }
+
+/// The storage keys of a contract to prove.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ContractStorageKeys {
+    pub contract_address: ContractAddress,
+    pub storage_keys: Vec<StorageKey>,
+}
+
+impl From<ContractStorageKeys> for CommitterContractStorageKeys {
+    fn from(keys: ContractStorageKeys) -> Self {
+        Self { contract_address: keys.contract_address, storage_keys: keys.storage_keys }
+    }
+}
+
+/// Merkle proofs in the state tries, as returned by `starknet_getStorageProof`.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct StorageProof {
+    pub classes_proof: Vec<NodeHashToNode>,
+    pub contracts_proof: ContractsProof,
+    pub contracts_storage_proofs: Vec<Vec<NodeHashToNode>>,
+    pub global_roots: GlobalRoots,
+}
+
+impl StorageProof {
+    pub fn new(proof: CommitterStorageProof, block_hash: BlockHash) -> Self {
+        Self {
+            classes_proof: node_hash_to_node_mapping(proof.classes_proof),
+            contracts_proof: ContractsProof {
+                nodes: node_hash_to_node_mapping(proof.contracts_proof),
+                contract_leaves_data: proof
+                    .contract_leaves_data
+                    .into_iter()
+                    .map(|CommitterContractLeafData { nonce, class_hash, storage_root }| {
+                        ContractLeafData { nonce, class_hash, storage_root }
+                    })
+                    .collect(),
+            },
+            contracts_storage_proofs: proof
+                .contracts_storage_proofs
+                .into_iter()
+                .map(node_hash_to_node_mapping)
+                .collect(),
+            global_roots: GlobalRoots {
+                contracts_tree_root: proof.contracts_tree_root,
+                classes_tree_root: proof.classes_tree_root,
+                block_hash,
+            },
+        }
+    }
+}
+
+fn node_hash_to_node_mapping(proof: TrieProof) -> Vec<NodeHashToNode> {
+    proof
+        .into_iter()
+        .map(|(node_hash, node)| {
+            let node = match node {
+                CommitterMerkleNode::Binary { left, right } => {
+                    MerkleNode::BinaryNode { left, right }
+                }
+                CommitterMerkleNode::Edge { path, length, child } => {
+                    MerkleNode::EdgeNode { path, length, child }
+                }
+            };
+            NodeHashToNode { node_hash, node }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct NodeHashToNode {
+    pub node_hash: Felt,
+    pub node: MerkleNode,
+}
+
+/// A node in a Merkle-Patricia trie.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum MerkleNode {
+    BinaryNode { left: Felt, right: Felt },
+    EdgeNode { path: Felt, length: u8, child: Felt },
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ContractsProof {
+    pub nodes: Vec<NodeHashToNode>,
+    pub contract_leaves_data: Vec<ContractLeafData>,
+}
+
+/// The contents of a contracts trie leaf, which are needed to compute its hash.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ContractLeafData {
+    pub nonce: Nonce,
+    pub class_hash: ClassHash,
+    pub storage_root: Felt,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct GlobalRoots {
+    pub contracts_tree_root: Felt,
+    pub classes_tree_root: Felt,
+    pub block_hash: BlockHash,
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::{MethodCallback, MethodResponse, RpcModule, SubscriptionMessage, SubscriptionSink};
+use papyrus_common::sync_notifications::SyncNotification;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
+use starknet_api::transaction::TransactionHash;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+
+use super::error::INVALID_SUBSCRIPTION_ID;
+use super::transaction::{TransactionStatus, TransactionWithHash};
+
+/// The maximal number of blocks behind the latest block that a block subscription can start from.
+pub const MAX_BLOCKS_BACK: u64 = 1024;
+
+/// The maximal number of addresses in the sender address filter of a pending transactions
+/// subscription.
+pub const MAX_SENDER_ADDRESSES_IN_FILTER: usize = 1024;
+
+const REORG_NOTIFICATION_METHOD: &str = "starknet_subscriptionReorg";
+const UNSUBSCRIBE_METHOD: &str = "starknet_unsubscribe";
+// The unsubscribe methods that jsonrpsee registers for each subscription kind.
+const UNSUBSCRIBE_METHODS_BY_KIND: [&str; 4] = [
+    "starknet_unsubscribeNewHeads",
+    "starknet_unsubscribeEvents",
+    "starknet_unsubscribeTransactionStatus",
+    "starknet_unsubscribePendingTransactions",
+];
+
+/// The result of a notification of a transaction status subscription.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TransactionStatusNotification {
+    pub transaction_hash: TransactionHash,
+    pub status: TransactionStatus,
+}
+
+/// The result of a notification of a pending transactions subscription. Contains the full
+/// transaction only if the subscriber asked for the transaction details.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PendingTransaction {
+    Hash(TransactionHash),
+    Full(TransactionWithHash),
+}
+
+/// The result of a reorg notification, sent to a subscriber that was notified about blocks that
+/// were reverted.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ReorgData {
+    pub starting_block_hash: BlockHash,
+    pub starting_block_number: BlockNumber,
+    pub ending_block_hash: BlockHash,
+    pub ending_block_number: BlockNumber,
+}
+
+impl ReorgData {
+    pub fn new(first_reverted: BlockHashAndNumber, last_reverted: BlockHashAndNumber) -> Self {
+        Self {
+            starting_block_hash: first_reverted.hash,
+            starting_block_number: first_reverted.number,
+            ending_block_hash: last_reverted.hash,
+            ending_block_number: last_reverted.number,
+        }
+    }
+}
+
+/// Waits for the next sync notification. Returns None if the subscriber unsubscribed or if the
+/// sync stopped publishing notifications, and an error if the subscriber fell behind and lost
+/// notifications.
+pub(crate) async fn next_notification(
+    sink: &SubscriptionSink,
+    receiver: &mut Receiver<SyncNotification>,
+) -> Result<Option<SyncNotification>, String> {
+    tokio::select! {
+        _ = sink.closed() => Ok(None),
+        notification = receiver.recv() => match notification {
+            Ok(notification) => Ok(Some(notification)),
+            Err(RecvError::Closed) => Ok(None),
+            Err(RecvError::Lagged(n_lost_notifications)) => Err(format!(
+                "The subscription fell behind the node and lost {n_lost_notifications} \
+                 notifications."
+            )),
+        },
+    }
+}
+
+pub(crate) async fn send_notification(
+    sink: &SubscriptionSink,
+    result: &impl Serialize,
+) -> Result<(), String> {
+    let message = SubscriptionMessage::from_json(result).map_err(|err| err.to_string())?;
+    sink.send(message).await.map_err(|err| err.to_string())
+}
+
+pub(crate) async fn send_reorg_notification(
+    sink: &SubscriptionSink,
+    first_reverted: BlockHashAndNumber,
+    last_reverted: BlockHashAndNumber,
+) -> Result<(), String> {
+    let message = SubscriptionMessage::new(
+        REORG_NOTIFICATION_METHOD,
+        sink.subscription_id(),
+        &ReorgData::new(first_reverted, last_reverted),
+    )
+    .map_err(|err| err.to_string())?;
+    sink.send(message).await.map_err(|err| err.to_string())
+}
+
+/// Registers the `starknet_unsubscribe` method, which cancels a subscription of any kind. jsonrpsee
+/// registers a separate unsubscribe method for each subscription kind, so this method tries all of
+/// them.
+pub(crate) fn register_unsubscribe_method<Context>(module: &mut RpcModule<Context>) {
+    let unsubscribe_callbacks = UNSUBSCRIBE_METHODS_BY_KIND
+        .iter()
+        .map(|method_name| match module.method(method_name) {
+            Some(MethodCallback::Unsubscription(callback)) => callback.clone(),
+            _ => panic!("{method_name} should be registered as an unsubscribe method."),
+        })
+        .collect::<Vec<_>>();
+    module
+        .verify_and_insert(
+            UNSUBSCRIBE_METHOD,
+            MethodCallback::Unsubscription(Arc::new(
+                move |id, params, connection_id, max_response_size| {
+                    for callback in &unsubscribe_callbacks {
+                        let response =
+                            callback(id.clone(), params.clone(), connection_id, max_response_size);
+                        if is_unsubscribe_successful(&response) {
+                            return response;
+                        }
+                    }
+                    MethodResponse::error(id, ErrorObjectOwned::from(INVALID_SUBSCRIPTION_ID))
+                },
+            )),
+        )
+        .expect("starknet_unsubscribe should be registered once.");
+}
+
+// The unsubscribe methods that jsonrpsee registers return false when the subscription doesn't
+// exist.
+fn is_unsubscribe_successful(response: &MethodResponse) -> bool {
+    serde_json::from_str::<serde_json::Value>(&response.result)
+        .is_ok_and(|response| response["result"] == serde_json::Value::Bool(true))
+}
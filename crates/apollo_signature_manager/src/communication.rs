@@ -30,6 +30,9 @@ impl ComponentRequestHandler<SignatureManagerRequest, SignatureManagerResponse>
                     self.sign_precommit_vote(block_hash).await,
                 )
             }
+            SignatureManagerRequest::SignVote(vote) => {
+                SignatureManagerResponse::SignVote(self.sign_vote(&vote).await)
+            }
         }
     }
 }
@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use serde::{Deserialize, Serialize};
+use starknet_api::crypto::utils::PrivateKey;
+use validator::Validate;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate, PartialEq)]
+pub struct SignatureManagerConfig {
+    /// The private key with which the node signs, e.g. its consensus votes. Zero means unset, and
+    /// must be replaced before the signature manager runs.
+    pub private_key: PrivateKey,
+}
+
+impl SerializeConfig for SignatureManagerConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from([ser_param(
+            "private_key",
+            &self.private_key,
+            "The private key with which the node signs, e.g. its consensus votes.",
+            ParamPrivacyInput::Private,
+        )])
+    }
+}
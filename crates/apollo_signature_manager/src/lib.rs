@@ -1,25 +1,22 @@
 pub mod communication;
+pub mod config;
 pub mod signature_manager;
 
 use std::ops::Deref;
 
 use apollo_infra::component_definitions::ComponentStarter;
 use async_trait::async_trait;
+use starknet_api::crypto::utils::PrivateKey;
 
+use crate::config::SignatureManagerConfig;
 use crate::signature_manager::{LocalKeyStore, SignatureManager as GenericSignatureManager};
 
 #[derive(Clone, Debug)]
 pub struct LocalKeyStoreSignatureManager(pub GenericSignatureManager<LocalKeyStore>);
 
 impl LocalKeyStoreSignatureManager {
-    pub fn new() -> Self {
-        Self(GenericSignatureManager::new(LocalKeyStore::new_for_testing()))
-    }
-}
-
-impl Default for LocalKeyStoreSignatureManager {
-    fn default() -> Self {
-        Self::new()
+    pub fn new(private_key: PrivateKey) -> Self {
+        Self(GenericSignatureManager::new(LocalKeyStore::new(private_key)))
     }
 }
 
@@ -35,8 +32,13 @@ pub use LocalKeyStoreSignatureManager as SignatureManager;
 
 // TODO(Elin): understand how key store would look in production and better define the way the
 // signature manager is created.
-pub fn create_signature_manager() -> SignatureManager {
-    SignatureManager::new()
+pub fn create_signature_manager(config: SignatureManagerConfig) -> SignatureManager {
+    assert_ne!(
+        config.private_key,
+        PrivateKey::default(),
+        "The signature manager's private key should be set."
+    );
+    SignatureManager::new(config.private_key)
 }
 
 #[async_trait]
@@ -2,6 +2,7 @@ use std::ops::Deref;
 
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_network_types::network_types::PeerId;
+use apollo_protobuf::consensus::{Vote, VoteType};
 use apollo_signature_manager_types::{
     KeyStore,
     KeyStoreResult,
@@ -25,6 +26,8 @@ pub mod signature_manager_test;
 // Message domain separators.
 pub(crate) const INIT_PEER_ID: &[u8] = b"INIT_PEER_ID";
 pub(crate) const PRECOMMIT_VOTE: &[u8] = b"PRECOMMIT_VOTE";
+pub(crate) const CONSENSUS_PREVOTE: &[u8] = b"CONSENSUS_PREVOTE";
+pub(crate) const CONSENSUS_PRECOMMIT: &[u8] = b"CONSENSUS_PRECOMMIT";
 
 pub type SignatureVerificationResult<T> = Result<T, SignatureVerificationError>;
 
@@ -67,6 +70,11 @@ impl<KS: KeyStore> SignatureManager<KS> {
         self.sign(message_digest).await
     }
 
+    pub async fn sign_vote(&self, vote: &Vote) -> SignatureManagerResult<RawSignature> {
+        let message_digest = build_vote_message_digest(vote);
+        self.sign(message_digest).await
+    }
+
     async fn sign(&self, message_digest: MessageDigest) -> SignatureManagerResult<RawSignature> {
         let private_key = self.keystore.get_key().await?;
         let signature = ecdsa_sign(&private_key, &message_digest)
@@ -87,12 +95,13 @@ pub struct LocalKeyStore {
 }
 
 impl LocalKeyStore {
-    fn _new(private_key: PrivateKey) -> Self {
+    pub fn new(private_key: PrivateKey) -> Self {
         let public_key = PublicKey(get_public_key(&private_key));
         Self { private_key, public_key }
     }
 
-    pub(crate) const fn new_for_testing() -> Self {
+    #[cfg(any(test, feature = "testing"))]
+    pub const fn new_for_testing() -> Self {
         // Created using `cairo-lang`.
         const PRIVATE_KEY: PrivateKey = PrivateKey(Felt::from_hex_unchecked(
             "0x608bf2cdb1ad4138e72d2f82b8c5db9fa182d1883868ae582ed373429b7a133",
@@ -134,6 +143,32 @@ fn build_precommit_vote_message_digest(block_hash: BlockHash) -> MessageDigest {
     MessageDigest(blake2s_to_felt(&message))
 }
 
+// The signature field of the vote is not part of the signed message.
+fn build_vote_message_digest(vote: &Vote) -> MessageDigest {
+    let domain_separator = match vote.vote_type {
+        VoteType::Prevote => CONSENSUS_PREVOTE,
+        VoteType::Precommit => CONSENSUS_PRECOMMIT,
+    };
+    let height = vote.height.to_be_bytes();
+    let round = vote.round.to_be_bytes();
+    let voter = Felt::from(vote.voter).to_bytes_be();
+    // A NIL vote has no block hash. Since it is the last (fixed size) element, the message remains
+    // unambiguous.
+    let block_hash = vote.block_hash.map(|block_hash| block_hash.to_bytes_be());
+    let block_hash: &[u8] = block_hash.as_ref().map_or(&[], |block_hash| block_hash.as_slice());
+
+    let mut message = Vec::with_capacity(
+        domain_separator.len() + height.len() + round.len() + voter.len() + block_hash.len(),
+    );
+    message.extend_from_slice(domain_separator);
+    message.extend_from_slice(&height);
+    message.extend_from_slice(&round);
+    message.extend_from_slice(&voter);
+    message.extend_from_slice(block_hash);
+
+    MessageDigest(blake2s_to_felt(&message))
+}
+
 fn verify_signature(
     message_digest: MessageDigest,
     signature: RawSignature,
@@ -174,3 +209,12 @@ pub fn verify_precommit_vote_signature(
     let message_digest = build_precommit_vote_message_digest(block_hash);
     verify_signature(message_digest, signature, public_key)
 }
+
+pub fn verify_vote_signature(
+    vote: &Vote,
+    signature: RawSignature,
+    public_key: PublicKey,
+) -> SignatureVerificationResult<bool> {
+    let message_digest = build_vote_message_digest(vote);
+    verify_signature(message_digest, signature, public_key)
+}
@@ -1,9 +1,10 @@
 use apollo_network_types::network_types::PeerId;
+use apollo_protobuf::consensus::{Vote, VoteType};
 use hex::FromHex;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
 use starknet_api::block::BlockHash;
-use starknet_api::core::Nonce;
+use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::{felt, nonce};
 use starknet_core::crypto::Signature;
 use starknet_core::types::Felt;
@@ -11,6 +12,7 @@ use starknet_core::types::Felt;
 use crate::signature_manager::{
     verify_identity,
     verify_precommit_vote_signature,
+    verify_vote_signature,
     LocalKeyStore,
     SignatureManager,
 };
@@ -113,3 +115,48 @@ async fn test_sign_precommit_vote() {
         true
     );
 }
+
+#[rstest]
+#[case::prevote(VoteType::Prevote, Some(BlockHash(felt!("0x1234"))))]
+#[case::precommit(VoteType::Precommit, Some(BlockHash(felt!("0x1234"))))]
+#[case::nil_precommit(VoteType::Precommit, None)]
+#[tokio::test]
+async fn test_sign_vote(#[case] vote_type: VoteType, #[case] block_hash: Option<BlockHash>) {
+    let key_store = LocalKeyStore::new_for_testing();
+    let signature_manager = SignatureManager::new(key_store);
+
+    let vote = Vote {
+        vote_type,
+        height: 7,
+        round: 1,
+        block_hash,
+        voter: ContractAddress::from(100_u64),
+        signature: None,
+    };
+    let signature = signature_manager.sign_vote(&vote).await.unwrap();
+
+    // Test alignment with verification function.
+    assert_eq!(
+        verify_vote_signature(&vote, signature.clone(), key_store.public_key).unwrap(),
+        true
+    );
+
+    // The signature must not be valid for a vote that differs in any of the signed fields.
+    let other_vote_type = match vote.vote_type {
+        VoteType::Prevote => VoteType::Precommit,
+        VoteType::Precommit => VoteType::Prevote,
+    };
+    let modified_votes = [
+        Vote { vote_type: other_vote_type, ..vote.clone() },
+        Vote { height: vote.height + 1, ..vote.clone() },
+        Vote { round: vote.round + 1, ..vote.clone() },
+        Vote { block_hash: Some(BlockHash(felt!("0x5678"))), ..vote.clone() },
+        Vote { voter: ContractAddress::from(101_u64), ..vote.clone() },
+    ];
+    for modified_vote in modified_votes {
+        assert_eq!(
+            verify_vote_signature(&modified_vote, signature.clone(), key_store.public_key).unwrap(),
+            false
+        );
+    }
+}
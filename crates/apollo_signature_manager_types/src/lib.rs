@@ -5,6 +5,7 @@ use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndRe
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_network_types::network_types::PeerId;
 use apollo_proc_macros::handle_all_response_variants;
+use apollo_protobuf::consensus::Vote;
 use async_trait::async_trait;
 #[cfg(any(feature = "testing", test))]
 use mockall::automock;
@@ -57,6 +58,10 @@ pub trait SignatureManagerClient: Send + Sync {
         &self,
         block_hash: BlockHash,
     ) -> SignatureManagerClientResult<RawSignature>;
+
+    /// Signs a consensus vote (prevote or precommit), covering all of its fields except for the
+    /// signature itself.
+    async fn sign_vote(&self, vote: Vote) -> SignatureManagerClientResult<RawSignature>;
 }
 
 #[derive(Clone, Debug, Error, Eq, PartialEq, Serialize, Deserialize)]
@@ -83,6 +88,7 @@ pub enum SignatureManagerClientError {
 pub enum SignatureManagerRequest {
     Identify(PeerId, Nonce),
     SignPrecommitVote(BlockHash),
+    SignVote(Vote),
 }
 impl_debug_for_infra_requests_and_responses!(SignatureManagerRequest);
 
@@ -90,6 +96,7 @@ impl_debug_for_infra_requests_and_responses!(SignatureManagerRequest);
 pub enum SignatureManagerResponse {
     Identify(SignatureManagerResult<RawSignature>),
     SignPrecommitVote(SignatureManagerResult<RawSignature>),
+    SignVote(SignatureManagerResult<RawSignature>),
 }
 impl_debug_for_infra_requests_and_responses!(SignatureManagerResponse);
 
@@ -127,4 +134,15 @@ where
             Direct
         )
     }
+
+    async fn sign_vote(&self, vote: Vote) -> SignatureManagerClientResult<RawSignature> {
+        let request = SignatureManagerRequest::SignVote(vote);
+        handle_all_response_variants!(
+            SignatureManagerResponse,
+            SignVote,
+            SignatureManagerClientError,
+            SignatureManagerError,
+            Direct
+        )
+    }
 }
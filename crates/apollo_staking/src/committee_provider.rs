@@ -5,19 +5,21 @@ use apollo_state_sync_types::communication::{SharedStateSyncClient, StateSyncCli
 use async_trait::async_trait;
 use blockifier::context::BlockContext;
 use blockifier::execution::errors::EntryPointExecutionError;
+use blockifier::state::errors::StateError;
 use blockifier::state::state_api::StateReader;
-use starknet_api::block::BlockNumber;
+use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::core::ContractAddress;
 use starknet_api::staking::StakingWeight;
 use starknet_types_core::felt::Felt;
 use thiserror::Error;
 
 use crate::contract_types::RetdataDeserializationError;
+use crate::staking_manager::CommitteeData;
+use crate::utils::BlockRandomGenerator;
 
 pub type Committee = Vec<Staker>;
 
-#[cfg_attr(test, derive(Clone))]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Staker {
     // A contract address of the staker, to which rewards are sent.
     pub address: ContractAddress,
@@ -35,6 +37,8 @@ pub enum CommitteeProviderError {
     #[error(transparent)]
     RetdataDeserializationError(#[from] RetdataDeserializationError),
     #[error(transparent)]
+    StateError(#[from] StateError),
+    #[error(transparent)]
     StateSyncClientError(#[from] StateSyncClientError),
     #[error("Committee is empty.")]
     EmptyCommittee,
@@ -42,6 +46,49 @@ pub enum CommitteeProviderError {
 
 pub type CommitteeProviderResult<T> = Result<T, CommitteeProviderError>;
 
+/// The committee of a height, along with the randomness from which the proposers of the height are
+/// selected. Selecting the proposer of any round requires no further queries.
+#[derive(Clone)]
+pub struct HeightCommittee {
+    height: BlockNumber,
+    committee_data: Arc<CommitteeData>,
+    randomness_block_hash: Option<BlockHash>,
+    random_generator: Arc<dyn BlockRandomGenerator>,
+}
+
+impl HeightCommittee {
+    pub(crate) fn new(
+        height: BlockNumber,
+        committee_data: Arc<CommitteeData>,
+        randomness_block_hash: Option<BlockHash>,
+        random_generator: Arc<dyn BlockRandomGenerator>,
+    ) -> Self {
+        Self { height, committee_data, randomness_block_hash, random_generator }
+    }
+
+    pub fn height(&self) -> BlockNumber {
+        self.height
+    }
+
+    pub fn committee(&self) -> &Arc<Committee> {
+        &self.committee_data.committee_members
+    }
+
+    /// Returns the address of the proposer for the given round.
+    ///
+    /// The proposer is selected with probability proportional to its weight. The selection is
+    /// deterministic, seeded by the height, the round and the hash of a past block.
+    pub fn proposer(&self, round: Round) -> CommitteeProviderResult<ContractAddress> {
+        let random_value = self.random_generator.generate(
+            self.height,
+            round,
+            self.randomness_block_hash,
+            self.committee_data.total_weight(),
+        );
+        Ok(self.committee_data.choose_proposer(random_value)?.address)
+    }
+}
+
 #[cfg_attr(test, derive(Clone))]
 pub struct ExecutionContext<S: StateReader> {
     pub state_reader: S,
@@ -73,4 +120,12 @@ pub trait CommitteeProvider {
         round: Round,
         execution_context: ExecutionContext<S>,
     ) -> CommitteeProviderResult<ContractAddress>;
+
+    /// Returns the committee of the epoch associated with the given height, from which the
+    /// proposer of any round at that height can be selected.
+    async fn get_height_committee<S: StateReader + Send>(
+        &mut self,
+        height: BlockNumber,
+        execution_context: ExecutionContext<S>,
+    ) -> CommitteeProviderResult<HeightCommittee>;
 }
@@ -1,5 +1,5 @@
 pub mod committee_provider;
 pub mod contract_types;
 pub mod staking_manager;
-
-mod utils;
+pub mod static_committee_provider;
+pub mod utils;
@@ -2,11 +2,10 @@ use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use apollo_consensus::types::Round;
-use apollo_state_sync_types::communication::SharedStateSyncClient;
 use async_trait::async_trait;
 use blockifier::execution::entry_point::call_view_entry_point;
 use blockifier::state::state_api::StateReader;
-use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::ContractAddress;
 use starknet_api::transaction::fields::Calldata;
 use starknet_types_core::felt::Felt;
@@ -17,10 +16,11 @@ use crate::committee_provider::{
     CommitteeProviderError,
     CommitteeProviderResult,
     ExecutionContext,
+    HeightCommittee,
     Staker,
 };
-use crate::contract_types::{EPOCH_LENGTH, GET_STAKERS_ENTRY_POINT};
-use crate::utils::BlockRandomGenerator;
+use crate::contract_types::GET_STAKERS_ENTRY_POINT;
+use crate::utils::{epoch_of, proposer_randomness_block_hash, BlockRandomGenerator};
 
 pub type StakerSet = Vec<Staker>;
 
@@ -44,8 +44,8 @@ pub struct StakingManagerConfig {
     pub proposer_prediction_window_in_heights: u64,
 }
 
-struct CommitteeData {
-    committee_members: Arc<Committee>,
+pub(crate) struct CommitteeData {
+    pub(crate) committee_members: Arc<Committee>,
     cumulative_weights: Vec<u128>,
     total_weight: u128,
 }
@@ -63,7 +63,7 @@ struct CommitteeDataCache {
 // the consensus at a given epoch, responsible for proposing blocks and voting on them.
 pub struct StakingManager {
     committee_data_cache: CommitteeDataCache,
-    random_generator: Box<dyn BlockRandomGenerator>,
+    random_generator: Arc<dyn BlockRandomGenerator>,
     config: StakingManagerConfig,
 }
 
@@ -84,6 +84,61 @@ impl CommitteeDataCache {
     }
 }
 
+impl CommitteeData {
+    // Builds the committee data, including the cumulative weights used for proposer selection.
+    pub(crate) fn new(committee_members: Committee) -> Self {
+        let cumulative_weights: Vec<u128> = committee_members
+            .iter()
+            .scan(0, |acc, staker| {
+                *acc = u128::checked_add(*acc, staker.weight.0).expect("Total weight overflow.");
+                Some(*acc)
+            })
+            .collect();
+        let total_weight = *cumulative_weights.last().unwrap_or(&0);
+
+        Self { committee_members: Arc::new(committee_members), cumulative_weights, total_weight }
+    }
+
+    pub(crate) fn total_weight(&self) -> u128 {
+        self.total_weight
+    }
+
+    // Chooses a proposer from the committee using a weighted random selection.
+    // The selection is based on the provided random value, where a staker's chance of selection is
+    // proportional to its weight.
+    // Note: the random value must be in the range [0, total_weight).
+    pub(crate) fn choose_proposer(&self, random: u128) -> CommitteeProviderResult<&Staker> {
+        if self.committee_members.is_empty() {
+            return Err(CommitteeProviderError::EmptyCommittee);
+        }
+
+        let total_weight = self.total_weight;
+        assert!(
+            random < total_weight,
+            "Invalid random value {random}: exceeds total weight limit of {total_weight}."
+        );
+
+        // Iterates over stakers and selects staker `i` if `random < cumulative_weights[i]`.
+        // Each staker occupies a range of values proportional to their weight, defined as:
+        //     [cumulative_weights[i - 1], cumulative_weights[i])
+        // Since we iterate in order, the first staker whose cumulative weight exceeds `random`
+        // is the one whose range contains it.
+        for (i, cum_weight) in self.cumulative_weights.iter().enumerate() {
+            if random < *cum_weight {
+                return self.committee_members.get(i).ok_or_else(|| {
+                    panic!(
+                        "Inconsistent committee data; cumulative_weights and committee_members \
+                         are not the same length."
+                    )
+                });
+            }
+        }
+
+        // We should never reach this point.
+        panic!("Inconsistent committee data; cumulative_weights inconsistent with total weight.")
+    }
+}
+
 impl StakingManager {
     pub fn new(
         random_generator: Box<dyn BlockRandomGenerator>,
@@ -91,7 +146,7 @@ impl StakingManager {
     ) -> Self {
         Self {
             committee_data_cache: CommitteeDataCache::new(config.max_cached_epochs),
-            random_generator,
+            random_generator: Arc::from(random_generator),
             config,
         }
     }
@@ -135,21 +190,7 @@ impl StakingManager {
         let stakers = Staker::from_retdata_many(call_info.execution.retdata)?;
         let committee_members = self.select_committee(stakers);
 
-        // Prepare the data needed for proposer selection.
-        let cumulative_weights: Vec<u128> = committee_members
-            .iter()
-            .scan(0, |acc, staker| {
-                *acc = u128::checked_add(*acc, staker.weight.0).expect("Total weight overflow.");
-                Some(*acc)
-            })
-            .collect();
-        let total_weight = *cumulative_weights.last().unwrap_or(&0);
-
-        Ok(CommitteeData {
-            committee_members: Arc::new(committee_members),
-            cumulative_weights,
-            total_weight,
-        })
+        Ok(CommitteeData::new(committee_members))
     }
 
     // Selects the committee from the provided stakers and ensures a canonical ordering.
@@ -162,65 +203,6 @@ impl StakingManager {
         // Take the top `committee_size` stakers by weight.
         stakers.into_iter().rev().take(self.config.committee_size).collect()
     }
-
-    async fn proposer_randomness_block_hash(
-        &self,
-        current_block_number: BlockNumber,
-        state_sync_client: SharedStateSyncClient,
-    ) -> CommitteeProviderResult<Option<BlockHash>> {
-        let randomness_source_block =
-            current_block_number.0.checked_sub(self.config.proposer_prediction_window_in_heights);
-
-        match randomness_source_block {
-            None => {
-                Ok(None) // Not enough history to look back; return None.
-            }
-            Some(block_number) => {
-                let block_hash =
-                    state_sync_client.get_block_hash(BlockNumber(block_number)).await?;
-                Ok(Some(block_hash))
-            }
-        }
-    }
-
-    // Chooses a proposer from the committee using a weighted random selection.
-    // The selection is based on the provided random value, where a staker's chance of selection is
-    // proportional to its weight.
-    // Note: the random value must be in the range [0, committee_data.total_weight).
-    fn choose_proposer<'a>(
-        &self,
-        committee_data: &'a CommitteeData,
-        random: u128,
-    ) -> CommitteeProviderResult<&'a Staker> {
-        if committee_data.committee_members.is_empty() {
-            return Err(CommitteeProviderError::EmptyCommittee);
-        }
-
-        let total_weight = committee_data.total_weight;
-        assert!(
-            random < committee_data.total_weight,
-            "Invalid random value {random}: exceeds total weight limit of {total_weight}."
-        );
-
-        // Iterates over stakers and selects staker `i` if `random < cumulative_weights[i]`.
-        // Each staker occupies a range of values proportional to their weight, defined as:
-        //     [cumulative_weights[i - 1], cumulative_weights[i])
-        // Since we iterate in order, the first staker whose cumulative weight exceeds `random`
-        // is the one whose range contains it.
-        for (i, cum_weight) in committee_data.cumulative_weights.iter().enumerate() {
-            if random < *cum_weight {
-                return committee_data.committee_members.get(i).ok_or_else(|| {
-                    panic!(
-                        "Inconsistent committee data; cumulative_weights and committee_members \
-                         are not the same length."
-                    )
-                });
-            }
-        }
-
-        // We should never reach this point.
-        panic!("Inconsistent committee data; cumulative_weights inconsistent with total weight.")
-    }
 }
 
 #[async_trait]
@@ -244,22 +226,25 @@ impl CommitteeProvider for StakingManager {
         round: Round,
         execution_context: ExecutionContext<S>,
     ) -> CommitteeProviderResult<ContractAddress> {
+        self.get_height_committee(height, execution_context).await?.proposer(round)
+    }
+
+    async fn get_height_committee<S: StateReader + Send>(
+        &mut self,
+        height: BlockNumber,
+        execution_context: ExecutionContext<S>,
+    ) -> CommitteeProviderResult<HeightCommittee> {
         // Try to get the hash of the block used for proposer selection randomness.
-        let block_hash = self
-            .proposer_randomness_block_hash(height, execution_context.state_sync_client.clone())
-            .await?;
+        let block_hash = proposer_randomness_block_hash(
+            height,
+            self.config.proposer_prediction_window_in_heights,
+            execution_context.state_sync_client.clone(),
+        )
+        .await?;
 
         // Get the committee for the epoch this height belongs to.
-        let epoch = height.0 / EPOCH_LENGTH; // TODO(Dafna): export to a utility function.
-        let committee_data = self.committee_data_at_epoch(epoch, execution_context)?;
-
-        // Generate a pseudorandom value in the range [0, total_weight) based on the height, round,
-        // and block hash.
-        let random_value =
-            self.random_generator.generate(height, round, block_hash, committee_data.total_weight);
+        let committee_data = self.committee_data_at_epoch(epoch_of(height), execution_context)?;
 
-        // Select a proposer from the committee using the generated random.
-        let proposer = self.choose_proposer(&committee_data, random_value)?;
-        Ok(proposer.address)
+        Ok(HeightCommittee::new(height, committee_data, block_hash, self.random_generator.clone()))
     }
 }
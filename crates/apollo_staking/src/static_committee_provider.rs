@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use apollo_consensus::types::Round;
+use apollo_state_sync_types::communication::SharedStateSyncClient;
+use async_trait::async_trait;
+use blockifier::state::state_api::StateReader;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ContractAddress;
+
+use crate::committee_provider::{
+    Committee,
+    CommitteeProvider,
+    CommitteeProviderResult,
+    ExecutionContext,
+    HeightCommittee,
+};
+use crate::staking_manager::CommitteeData;
+use crate::utils::{
+    epoch_of,
+    proposer_randomness_block_hash,
+    BlockPseudorandomGenerator,
+    BlockRandomGenerator,
+};
+
+#[cfg(test)]
+#[path = "static_committee_provider_test.rs"]
+mod static_committee_provider_test;
+
+// Provides committees which are known in advance (e.g. set by configuration), instead of reading
+// them from the staking contract. Since it doesn't depend on the state, the committees can be
+// queried without an execution context.
+// The committees rotate per epoch: epoch `e` is assigned `committees[e % committees.len()]`.
+pub struct StaticCommitteeProvider {
+    committees: Vec<Arc<CommitteeData>>,
+    random_generator: Arc<dyn BlockRandomGenerator>,
+    // Defines how many heights back the block whose hash seeds the proposer selection is.
+    proposer_prediction_window_in_heights: u64,
+}
+
+impl StaticCommitteeProvider {
+    pub fn new(committees: Vec<Committee>, proposer_prediction_window_in_heights: u64) -> Self {
+        Self::new_with_random_generator(
+            committees,
+            proposer_prediction_window_in_heights,
+            Box::new(BlockPseudorandomGenerator),
+        )
+    }
+
+    pub fn new_with_random_generator(
+        committees: Vec<Committee>,
+        proposer_prediction_window_in_heights: u64,
+        random_generator: Box<dyn BlockRandomGenerator>,
+    ) -> Self {
+        assert!(!committees.is_empty(), "At least one committee must be provided.");
+        Self {
+            committees: committees
+                .into_iter()
+                .map(|committee| Arc::new(CommitteeData::new(committee)))
+                .collect(),
+            random_generator: Arc::from(random_generator),
+            proposer_prediction_window_in_heights,
+        }
+    }
+
+    fn committee_data_at_epoch(&self, epoch: u64) -> &Arc<CommitteeData> {
+        let n_committees =
+            u64::try_from(self.committees.len()).expect("Number of committees should fit in u64.");
+        let index = usize::try_from(epoch % n_committees).expect("Index should fit in usize.");
+        &self.committees[index]
+    }
+
+    /// Returns the committee of the epoch to which the given height belongs.
+    pub fn committee_at_height(&self, height: BlockNumber) -> Arc<Committee> {
+        self.committee_data_at_epoch(epoch_of(height)).committee_members.clone()
+    }
+
+    /// Returns the committee of the given height, along with the randomness from which its
+    /// proposers are selected. Only the hash of the randomness block is read from the state sync.
+    pub async fn height_committee(
+        &self,
+        height: BlockNumber,
+        state_sync_client: SharedStateSyncClient,
+    ) -> CommitteeProviderResult<HeightCommittee> {
+        let block_hash = proposer_randomness_block_hash(
+            height,
+            self.proposer_prediction_window_in_heights,
+            state_sync_client,
+        )
+        .await?;
+        Ok(HeightCommittee::new(
+            height,
+            self.committee_data_at_epoch(epoch_of(height)).clone(),
+            block_hash,
+            self.random_generator.clone(),
+        ))
+    }
+}
+
+#[async_trait]
+impl CommitteeProvider for StaticCommitteeProvider {
+    fn get_committee<S: StateReader>(
+        &mut self,
+        epoch: u64,
+        _execution_context: ExecutionContext<S>,
+    ) -> CommitteeProviderResult<Arc<Committee>> {
+        Ok(self.committee_data_at_epoch(epoch).committee_members.clone())
+    }
+
+    async fn get_proposer<S: StateReader + Send>(
+        &mut self,
+        height: BlockNumber,
+        round: Round,
+        execution_context: ExecutionContext<S>,
+    ) -> CommitteeProviderResult<ContractAddress> {
+        self.get_height_committee(height, execution_context).await?.proposer(round)
+    }
+
+    async fn get_height_committee<S: StateReader + Send>(
+        &mut self,
+        height: BlockNumber,
+        execution_context: ExecutionContext<S>,
+    ) -> CommitteeProviderResult<HeightCommittee> {
+        self.height_committee(height, execution_context.state_sync_client).await
+    }
+}
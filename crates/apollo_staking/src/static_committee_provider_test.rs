@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use apollo_consensus::types::Round;
+use apollo_state_sync_types::communication::{MockStateSyncClient, SharedStateSyncClient};
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::staking::StakingWeight;
+use starknet_types_core::felt::Felt;
+
+use crate::committee_provider::{CommitteeProviderError, CommitteeProviderResult, Staker};
+use crate::contract_types::EPOCH_LENGTH;
+use crate::static_committee_provider::StaticCommitteeProvider;
+use crate::utils::MockBlockRandomGenerator;
+
+const STAKER_1: Staker = Staker {
+    address: ContractAddress(PatriciaKey::from_hex_unchecked("0x1")),
+    weight: StakingWeight(1000),
+    public_key: Felt::ONE,
+};
+const STAKER_2: Staker = Staker {
+    address: ContractAddress(PatriciaKey::from_hex_unchecked("0x2")),
+    weight: StakingWeight(3000),
+    public_key: Felt::TWO,
+};
+const STAKER_3: Staker = Staker {
+    address: ContractAddress(PatriciaKey::from_hex_unchecked("0x3")),
+    weight: StakingWeight(6000),
+    public_key: Felt::THREE,
+};
+
+const PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS: u64 = 10;
+
+fn state_sync_client() -> SharedStateSyncClient {
+    Arc::new(MockStateSyncClient::new())
+}
+
+async fn proposer(
+    provider: &StaticCommitteeProvider,
+    height: BlockNumber,
+    round: Round,
+) -> CommitteeProviderResult<ContractAddress> {
+    provider.height_committee(height, state_sync_client()).await?.proposer(round)
+}
+
+#[tokio::test]
+async fn committees_rotate_per_epoch() {
+    let provider = StaticCommitteeProvider::new(
+        vec![vec![STAKER_1, STAKER_2], vec![STAKER_3]],
+        PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS,
+    );
+
+    assert_eq!(*provider.committee_at_height(BlockNumber(0)), vec![STAKER_1, STAKER_2]);
+    assert_eq!(
+        *provider.committee_at_height(BlockNumber(EPOCH_LENGTH - 1)),
+        vec![STAKER_1, STAKER_2]
+    );
+    assert_eq!(*provider.committee_at_height(BlockNumber(EPOCH_LENGTH)), vec![STAKER_3]);
+    assert_eq!(
+        *provider.committee_at_height(BlockNumber(2 * EPOCH_LENGTH)),
+        vec![STAKER_1, STAKER_2]
+    );
+
+    // The proposer is always taken from the committee of the height's epoch.
+    let height = BlockNumber(EPOCH_LENGTH);
+    let mut state_sync_client = MockStateSyncClient::new();
+    state_sync_client.expect_get_block_hash().returning(|_| Ok(BlockHash::default()));
+    let height_committee =
+        provider.height_committee(height, Arc::new(state_sync_client)).await.unwrap();
+    assert_eq!(**height_committee.committee(), vec![STAKER_3]);
+    for round in 0..10 {
+        assert_eq!(height_committee.proposer(round).unwrap(), STAKER_3.address);
+    }
+}
+
+#[rstest]
+#[case(0, STAKER_1)]
+#[case(999, STAKER_1)]
+#[case(1000, STAKER_2)]
+#[case(3999, STAKER_2)]
+#[case(4000, STAKER_3)]
+#[case(9999, STAKER_3)]
+#[tokio::test]
+async fn proposer_is_weighted(#[case] random_value: u128, #[case] expected_proposer: Staker) {
+    let mut random_generator = MockBlockRandomGenerator::new();
+    random_generator
+        .expect_generate()
+        .withf(|_, _, _, range| *range == 10000)
+        .returning(move |_, _, _, _| random_value);
+    let provider = StaticCommitteeProvider::new_with_random_generator(
+        vec![vec![STAKER_1, STAKER_2, STAKER_3]],
+        PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS,
+        Box::new(random_generator),
+    );
+
+    assert_eq!(proposer(&provider, BlockNumber(1), 0).await.unwrap(), expected_proposer.address);
+}
+
+#[tokio::test]
+async fn proposer_is_deterministic() {
+    let committee = vec![STAKER_1, STAKER_2, STAKER_3];
+    let provider = StaticCommitteeProvider::new(
+        vec![committee.clone()],
+        PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS,
+    );
+    let other_provider =
+        StaticCommitteeProvider::new(vec![committee], PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS);
+
+    for height in 0..PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS {
+        for round in 0..3 {
+            assert_eq!(
+                proposer(&provider, BlockNumber(height), round).await.unwrap(),
+                proposer(&other_provider, BlockNumber(height), round).await.unwrap()
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn proposer_randomness_uses_past_block_hash() {
+    let height = BlockNumber(PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS + 5);
+    let block_hash = BlockHash(Felt::from(7_u8));
+
+    let mut state_sync_client = MockStateSyncClient::new();
+    state_sync_client
+        .expect_get_block_hash()
+        .times(1)
+        .withf(|block_number| *block_number == BlockNumber(5))
+        .returning(move |_| Ok(block_hash));
+    let mut random_generator = MockBlockRandomGenerator::new();
+    random_generator
+        .expect_generate()
+        .withf(move |_, _, randomness_block_hash, _| *randomness_block_hash == Some(block_hash))
+        .returning(|_, _, _, _| 0);
+    let provider = StaticCommitteeProvider::new_with_random_generator(
+        vec![vec![STAKER_1, STAKER_2, STAKER_3]],
+        PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS,
+        Box::new(random_generator),
+    );
+
+    let height_committee =
+        provider.height_committee(height, Arc::new(state_sync_client)).await.unwrap();
+    assert_eq!(height_committee.proposer(0).unwrap(), STAKER_1.address);
+}
+
+#[tokio::test]
+async fn proposer_empty_committee() {
+    let provider =
+        StaticCommitteeProvider::new(vec![vec![]], PROPOSER_PREDICTION_WINDOW_IN_HEIGHTS);
+    assert_matches!(
+        proposer(&provider, BlockNumber(1), 0).await,
+        Err(CommitteeProviderError::EmptyCommittee)
+    );
+}
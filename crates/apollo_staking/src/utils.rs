@@ -1,8 +1,37 @@
 use apollo_consensus::types::Round;
+use apollo_state_sync_types::communication::SharedStateSyncClient;
 #[cfg(test)]
 use mockall::automock;
+use sha2::{Digest, Sha256};
 use starknet_api::block::{BlockHash, BlockNumber};
 
+use crate::committee_provider::CommitteeProviderResult;
+use crate::contract_types::EPOCH_LENGTH;
+
+#[cfg(test)]
+#[path = "utils_test.rs"]
+mod utils_test;
+
+/// Returns the epoch to which the given height belongs.
+pub(crate) fn epoch_of(height: BlockNumber) -> u64 {
+    height.0 / EPOCH_LENGTH
+}
+
+/// Returns the hash of the block from which the randomness of the proposer selection at the given
+/// height is derived, `proposer_prediction_window_in_heights` blocks back. Returns None if there
+/// isn't enough history to look back.
+pub(crate) async fn proposer_randomness_block_hash(
+    height: BlockNumber,
+    proposer_prediction_window_in_heights: u64,
+    state_sync_client: SharedStateSyncClient,
+) -> CommitteeProviderResult<Option<BlockHash>> {
+    let Some(randomness_source_block) = height.0.checked_sub(proposer_prediction_window_in_heights)
+    else {
+        return Ok(None);
+    };
+    Ok(Some(state_sync_client.get_block_hash(BlockNumber(randomness_source_block)).await?))
+}
+
 #[cfg_attr(test, automock)]
 pub trait BlockRandomGenerator: Send + Sync {
     fn generate(
@@ -14,17 +43,29 @@ pub trait BlockRandomGenerator: Send + Sync {
     ) -> u128;
 }
 
-#[allow(dead_code)]
+/// Generates a value deterministically from the hash of its inputs, so that all nodes agree on it.
 pub struct BlockPseudorandomGenerator;
 
 impl BlockRandomGenerator for BlockPseudorandomGenerator {
     fn generate(
         &self,
-        _height: BlockNumber,
-        _round: Round,
-        _block_hash: Option<BlockHash>,
-        _range: u128,
+        height: BlockNumber,
+        round: Round,
+        block_hash: Option<BlockHash>,
+        range: u128,
     ) -> u128 {
-        todo!()
+        let mut hasher = Sha256::new();
+        hasher.update(height.0.to_be_bytes());
+        hasher.update(round.to_be_bytes());
+        if let Some(block_hash) = block_hash {
+            hasher.update(block_hash.0.to_bytes_be());
+        }
+        let digest = hasher.finalize();
+        let value = u128::from_be_bytes(
+            digest[..16].try_into().expect("A SHA-256 digest is longer than 16 bytes."),
+        );
+        // The modulo bias is negligible as long as `range` is much smaller than u128::MAX.
+        // An empty range yields 0, leaving it to the caller to handle the empty committee.
+        value.checked_rem(range).unwrap_or(0)
     }
 }
@@ -0,0 +1,32 @@
+use rstest::rstest;
+use starknet_api::block::{BlockHash, BlockNumber};
+use starknet_types_core::felt::Felt;
+
+use crate::utils::{BlockPseudorandomGenerator, BlockRandomGenerator};
+
+const RANGE: u128 = 10_000;
+
+#[rstest]
+#[case::without_block_hash(None)]
+#[case::with_block_hash(Some(BlockHash(Felt::ONE)))]
+fn pseudorandom_generator_is_deterministic(#[case] block_hash: Option<BlockHash>) {
+    let value = BlockPseudorandomGenerator.generate(BlockNumber(1), 0, block_hash, RANGE);
+    assert!(value < RANGE);
+    assert_eq!(value, BlockPseudorandomGenerator.generate(BlockNumber(1), 0, block_hash, RANGE));
+}
+
+#[test]
+fn pseudorandom_generator_depends_on_inputs() {
+    let generate = |height, round, block_hash| {
+        BlockPseudorandomGenerator.generate(BlockNumber(height), round, block_hash, u128::MAX)
+    };
+    let value = generate(1, 0, None);
+    assert_ne!(value, generate(2, 0, None));
+    assert_ne!(value, generate(1, 1, None));
+    assert_ne!(value, generate(1, 0, Some(BlockHash(Felt::ONE))));
+}
+
+#[test]
+fn pseudorandom_generator_empty_range() {
+    assert_eq!(BlockPseudorandomGenerator.generate(BlockNumber(1), 0, None, 0), 0);
+}
@@ -1 +1,2 @@
 pub mod papyrus_state;
+pub mod sync_state_reader;
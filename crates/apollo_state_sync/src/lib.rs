@@ -6,6 +6,7 @@ mod test;
 use std::cmp::min;
 
 use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_committer_types::SharedCommitterClient;
 use apollo_infra::component_definitions::{ComponentRequestHandler, ComponentStarter};
 use apollo_infra::component_server::{LocalComponentServer, RemoteComponentServer};
 use apollo_state_sync_types::communication::{StateSyncRequest, StateSyncResponse};
@@ -33,10 +34,11 @@ const BUFFER_SIZE: usize = 100000;
 pub fn create_state_sync_and_runner(
     config: StateSyncConfig,
     class_manager_client: SharedClassManagerClient,
+    committer_client: Option<SharedCommitterClient>,
 ) -> (StateSync, StateSyncRunner) {
     let (new_block_sender, new_block_receiver) = channel(BUFFER_SIZE);
     let (state_sync_runner, storage_reader) =
-        StateSyncRunner::new(config, new_block_receiver, class_manager_client);
+        StateSyncRunner::new(config, new_block_receiver, class_manager_client, committer_client);
     (StateSync { storage_reader, new_block_sender }, state_sync_runner)
 }
 
@@ -11,6 +11,7 @@ use apollo_central_sync::{
     GENESIS_HASH,
 };
 use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_committer_types::SharedCommitterClient;
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_infra::component_server::WrapperServer;
 use apollo_network::network_manager::metrics::{NetworkMetrics, SqmrNetworkMetrics};
@@ -49,9 +50,10 @@ use futures::future::{self, pending, BoxFuture};
 use futures::never::Never;
 use futures::{FutureExt, StreamExt};
 use papyrus_common::pending_classes::PendingClasses;
+use papyrus_common::sync_notifications::{SyncNotification, SYNC_NOTIFICATIONS_CHANNEL_CAPACITY};
 use starknet_api::block::{BlockHash, BlockHashAndNumber};
 use starknet_api::felt;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tracing::info_span;
 use tracing::instrument::Instrument;
 
@@ -101,6 +103,7 @@ pub struct StateSyncResources {
     pub shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pub pending_data: Arc<RwLock<PendingData>>,
     pub pending_classes: Arc<RwLock<PendingClasses>>,
+    pub sync_notifications: broadcast::Sender<SyncNotification>,
 }
 
 impl StateSyncResources {
@@ -118,7 +121,15 @@ impl StateSyncResources {
             ..Default::default()
         }));
         let pending_classes = Arc::new(RwLock::new(PendingClasses::default()));
-        Self { storage_reader, storage_writer, shared_highest_block, pending_data, pending_classes }
+        let (sync_notifications, _) = broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY);
+        Self {
+            storage_reader,
+            storage_writer,
+            shared_highest_block,
+            pending_data,
+            pending_classes,
+            sync_notifications,
+        }
     }
 }
 
@@ -127,6 +138,7 @@ impl StateSyncRunner {
         config: StateSyncConfig,
         new_block_receiver: Receiver<SyncBlock>,
         class_manager_client: SharedClassManagerClient,
+        committer_client: Option<SharedCommitterClient>,
     ) -> (Self, StorageReader) {
         let StateSyncConfig {
             storage_config,
@@ -143,6 +155,7 @@ impl StateSyncRunner {
             shared_highest_block,
             pending_data,
             pending_classes,
+            sync_notifications,
         } = StateSyncResources::new(&storage_config);
 
         let register_metrics_fn = Self::create_register_metrics_fn(storage_reader.clone());
@@ -226,6 +239,7 @@ impl StateSyncRunner {
                         network_manager,
                         new_block_receiver,
                         class_manager_client.clone(),
+                        sync_notifications.clone(),
                     );
 
                     let p2p_sync_client_future = p2p_sync_client.run().boxed();
@@ -240,6 +254,7 @@ impl StateSyncRunner {
                         shared_highest_block.clone(),
                         pending_data.clone(),
                         pending_classes.clone(),
+                        sync_notifications.clone(),
                         central_sync_client_config,
                         class_manager_client.clone(),
                     );
@@ -289,8 +304,10 @@ impl StateSyncRunner {
             shared_highest_block.clone(),
             pending_data.clone(),
             pending_classes.clone(),
+            sync_notifications,
             storage_reader.clone(),
             Some(class_manager_client.clone()),
+            committer_client,
         );
 
         (
@@ -314,6 +331,7 @@ impl StateSyncRunner {
         network_manager: &mut NetworkManager,
         new_block_receiver: Receiver<SyncBlock>,
         class_manager_client: SharedClassManagerClient,
+        sync_notifications: broadcast::Sender<SyncNotification>,
     ) -> P2pSyncClient {
         let header_client_sender = network_manager
             .register_sqmr_protocol_client(Protocol::SignedBlockHeader.into(), BUFFER_SIZE);
@@ -336,6 +354,7 @@ impl StateSyncRunner {
             p2p_sync_client_channels,
             new_block_receiver.boxed(),
             class_manager_client.clone(),
+            sync_notifications,
         )
     }
 
@@ -370,6 +389,7 @@ impl StateSyncRunner {
         shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
         pending_data: Arc<RwLock<PendingData>>,
         pending_classes: Arc<RwLock<PendingClasses>>,
+        sync_notifications: broadcast::Sender<SyncNotification>,
         central_sync_client_config: CentralSyncClientConfig,
         class_manager_client: SharedClassManagerClient,
     ) -> CentralStateSync {
@@ -388,6 +408,7 @@ impl StateSyncRunner {
             shared_highest_block,
             pending_data,
             pending_classes,
+            sync_notifications,
             central_source,
             pending_source,
             base_layer_source,
@@ -424,8 +445,10 @@ fn spawn_rpc_server(
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     storage_reader: StorageReader,
     class_manager_client: Option<SharedClassManagerClient>,
+    committer_client: Option<SharedCommitterClient>,
 ) -> BoxFuture<'static, ()> {
     let rpc_config = rpc_config.clone();
     async move {
@@ -434,9 +457,11 @@ fn spawn_rpc_server(
             shared_highest_block,
             pending_data,
             pending_classes,
+            sync_notifications,
             storage_reader,
             VERSION_FULL,
             class_manager_client,
+            committer_client,
         )
         .await
         .expect("Failed running JSON-RPC server");
@@ -0,0 +1,93 @@
+use apollo_storage::db::DbConfig;
+use apollo_storage::integrity::verify_storage_integrity;
+use apollo_storage::{StorageConfig, StorageScope};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
+
+// Verifies the integrity of a storage in a range of blocks. Exits with a non-zero code if a
+// mismatch was found. The storage is opened for reading only, so it must exist and be of the
+// current version.
+pub fn main() {
+    let matches = get_cli_matches();
+    let storage_config = get_storage_config(&matches);
+    let from_block = get_block_number(&matches, "from_block").unwrap_or_default();
+    let to_block = get_block_number(&matches, "to_block");
+
+    println!("Opening storage");
+    let reader = apollo_storage::open_storage_read_only(storage_config)
+        .expect("Should be able to open storage");
+    println!("Verifying the storage integrity");
+    match verify_storage_integrity(&reader, from_block, to_block)
+        .expect("Should be able to verify the storage integrity")
+    {
+        Some(mismatch) => {
+            println!("{mismatch}");
+            std::process::exit(1);
+        }
+        None => println!("The storage is consistent"),
+    }
+}
+
+fn get_cli_matches() -> ArgMatches {
+    Command::new("Storage integrity")
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path to the database"),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        )
+        .arg(
+            Arg::new("state_only")
+                .long("state_only")
+                .action(ArgAction::SetTrue)
+                .help("Whether the storage is of the StateOnly scope"),
+        )
+        .arg(
+            Arg::new("from_block")
+                .short('f')
+                .long("from_block")
+                .help("The first block to verify, 0 by default"),
+        )
+        .arg(
+            Arg::new("to_block")
+                .short('t')
+                .long("to_block")
+                .help("The verification stops below this block, the header marker by default"),
+        )
+        .get_matches()
+}
+
+fn get_storage_config(matches: &ArgMatches) -> StorageConfig {
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id =
+        matches.get_one::<String>("chain_id").expect("Missing parse chain_id").to_string();
+    let scope = if matches.get_flag("state_only") {
+        StorageScope::StateOnly
+    } else {
+        StorageScope::FullArchive
+    };
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id: ChainId::from(chain_id),
+        enforce_file_exists: true,
+        ..Default::default()
+    };
+    StorageConfig { db_config, scope, ..Default::default() }
+}
+
+fn get_block_number(matches: &ArgMatches, name: &str) -> Option<BlockNumber> {
+    matches.get_one::<String>(name).map(|block_number| {
+        BlockNumber(
+            block_number.parse::<u64>().unwrap_or_else(|_| panic!("{name} should be a number")),
+        )
+    })
+}
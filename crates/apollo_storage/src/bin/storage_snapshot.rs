@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use apollo_storage::db::DbConfig;
+use apollo_storage::header::HeaderStorageReader;
+use apollo_storage::snapshot::{export_snapshot, import_snapshot};
+use apollo_storage::{StorageConfig, StorageScope};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
+
+// Exports a snapshot of a storage, or imports a snapshot into a new storage.
+pub fn main() {
+    let matches = get_cli_matches();
+    match matches.subcommand() {
+        Some(("export", export_matches)) => {
+            let storage_config = get_storage_config(export_matches);
+            let block_number = export_matches
+                .get_one::<String>("block_number")
+                .expect("Missing block_number")
+                .parse::<u64>()
+                .expect("block_number should be a number");
+            let snapshot_dir = get_snapshot_dir(export_matches);
+            let compress = export_matches.get_flag("compress");
+
+            println!("Opening storage");
+            let (reader, _writer) = apollo_storage::open_storage(storage_config.clone())
+                .expect("Should be able to open storage");
+            println!("Exporting the blocks below {block_number}");
+            let manifest = export_snapshot(
+                &reader,
+                &storage_config,
+                BlockNumber(block_number),
+                &snapshot_dir,
+                compress,
+            )
+            .expect("Should be able to export the snapshot");
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&manifest)
+                    .expect("Should be able to serialize the manifest")
+            );
+        }
+        Some(("import", import_matches)) => {
+            let storage_config = get_storage_config(import_matches);
+            let snapshot_dir = get_snapshot_dir(import_matches);
+
+            println!("Importing the snapshot");
+            let (reader, _writer) = import_snapshot(&snapshot_dir, storage_config)
+                .expect("Should be able to import the snapshot");
+            let header_marker = reader
+                .begin_ro_txn()
+                .expect("Should be able to begin read only transaction")
+                .get_header_marker()
+                .expect("Should be able to read the header marker");
+            println!("Imported the blocks below {header_marker}");
+        }
+        _ => unreachable!("A subcommand is required"),
+    }
+}
+
+fn get_cli_matches() -> ArgMatches {
+    let storage_args = [
+        Arg::new("db_path")
+            .short('d')
+            .long("db_path")
+            .required(true)
+            .help("The path to the database"),
+        Arg::new("chain_id")
+            .short('c')
+            .long("chain_id")
+            .required(true)
+            .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        Arg::new("snapshot_dir")
+            .short('s')
+            .long("snapshot_dir")
+            .required(true)
+            .help("The path to the snapshot directory"),
+        Arg::new("state_only")
+            .long("state_only")
+            .action(ArgAction::SetTrue)
+            .help("Whether the storage is of the StateOnly scope"),
+    ];
+    Command::new("Storage snapshot")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("export")
+                .about("Exports a snapshot of the storage")
+                .args(storage_args.clone())
+                .arg(
+                    Arg::new("block_number")
+                        .short('b')
+                        .long("block_number")
+                        .required(true)
+                        .help("The snapshot contains the blocks below this block number"),
+                )
+                .arg(
+                    Arg::new("compress")
+                        .long("compress")
+                        .action(ArgAction::SetTrue)
+                        .help("Whether to compress the snapshot files"),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Imports a snapshot into a new storage")
+                .args(storage_args),
+        )
+        .get_matches()
+}
+
+fn get_storage_config(matches: &ArgMatches) -> StorageConfig {
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id =
+        matches.get_one::<String>("chain_id").expect("Missing parse chain_id").to_string();
+    let scope = if matches.get_flag("state_only") {
+        StorageScope::StateOnly
+    } else {
+        StorageScope::FullArchive
+    };
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id: ChainId::from(chain_id),
+        ..Default::default()
+    };
+    StorageConfig { db_config, scope, ..Default::default() }
+}
+
+fn get_snapshot_dir(matches: &ArgMatches) -> PathBuf {
+    matches.get_one::<String>("snapshot_dir").expect("Missing snapshot_dir").into()
+}
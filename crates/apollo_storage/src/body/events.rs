@@ -13,7 +13,8 @@
 //! # use starknet_api::block::BlockNumber;
 //! use starknet_api::core::ContractAddress;
 //! use starknet_api::transaction::TransactionOffsetInBlock;
-//! use starknet_api::transaction::EventIndexInTransactionOutput;
+//! use starknet_api::transaction::{EventIndexInTransactionOutput, EventKey};
+//! use std::collections::HashSet;
 //!
 //! # let dir_handle = tempfile::tempdir().unwrap();
 //! # let dir = dir_handle.path().to_path_buf();
@@ -43,12 +44,18 @@
 //! for ((contract_address, event_index), event_content) in contract_events_iterator {
 //!    // Do something with the event.
 //! }
+//! // iterate events whose first key is one of the given keys.
+//! let first_keys = HashSet::from([EventKey::default()]);
+//! let keys_events_iterator = txn.iter_events_by_first_keys(first_keys, event_index, BlockNumber(0))?;
+//! for ((contract_address, event_index), event_content) in keys_events_iterator {
+//!    // Do something with the event.
+//! }
 //! # Ok::<(), apollo_storage::StorageError>(())
 #[cfg(test)]
 #[path = "events_test.rs"]
 mod events_test;
 
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, HashSet, VecDeque};
 
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
@@ -57,15 +64,31 @@ use starknet_api::transaction::{
     Event,
     EventContent,
     EventIndexInTransactionOutput,
+    EventKey,
+    TransactionOffsetInBlock,
     TransactionOutput,
 };
+use tracing::debug;
 
 use super::TransactionMetadataTable;
-use crate::body::{EventsTableKey, TransactionIndex};
+use crate::body::{BodyStorageReader, EventKeysTableKey, EventsTableKey, TransactionIndex};
 use crate::db::serialization::{NoVersionValueWrapper, VersionZeroWrapper};
 use crate::db::table_types::{CommonPrefix, DbCursor, DbCursorTrait, NoValue, SimpleTable, Table};
-use crate::db::{DbTransaction, RO};
-use crate::{FileHandlers, StorageResult, StorageTxn, TransactionMetadata};
+use crate::db::{DbTransaction, TransactionKind, RO, RW};
+use crate::pruning::PruningStorageReader;
+use crate::{
+    FileHandlers,
+    MarkerKind,
+    StorageResult,
+    StorageScope,
+    StorageTxn,
+    StorageWriter,
+    TransactionMetadata,
+};
+
+// The maximal number of blocks whose events are indexed in a single transaction of the backfill, so
+// that the backfill doesn't block the writer for a long time.
+const MAX_INDEXED_BLOCKS_PER_TXN: u64 = 100;
 
 /// An identifier of an event.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize, Serialize, PartialOrd, Ord)]
@@ -91,6 +114,32 @@ pub trait EventsReader<'txn, 'env> {
         event_index: EventIndex,
         to_block_number: BlockNumber,
     ) -> StorageResult<EventIter<'txn, 'env>>;
+
+    /// Returns an iterator over the events whose first key is one of the given keys, by the order
+    /// of the event index. The first key of an event is usually its selector. The iterator goes
+    /// only over the transactions that emitted such events, so it doesn't scan all the events in
+    /// the range.
+    ///
+    /// Only the events from the [event keys index marker](Self::get_event_keys_index_marker) are
+    /// indexed, so the events of earlier blocks are skipped.
+    ///
+    /// # Arguments
+    /// * first_keys - keys that the first key of the iterated events is one of.
+    /// * event_index - event index to start iterate from it.
+    /// * to_block_number - block number to stop iterate at it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_first_keys(
+        &'env self,
+        first_keys: HashSet<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<EventIter<'txn, 'env>>;
+
+    /// The event keys index marker is the first block whose events are indexed by their keys. It
+    /// is above zero while the blocks that were written before the index existed are backfilled.
+    fn get_event_keys_index_marker(&self) -> StorageResult<BlockNumber>;
 }
 
 // TODO(DanB): support all read transactions (including RW).
@@ -109,20 +158,100 @@ impl<'txn, 'env> EventsReader<'txn, 'env> for StorageTxn<'env, RO> {
 
         Ok(EventIter::ByEventIndex(self.iter_events_by_event_index(event_index, to_block_number)?))
     }
+
+    fn iter_events_by_first_keys(
+        &'env self,
+        first_keys: HashSet<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<EventIter<'txn, 'env>> {
+        Ok(EventIter::ByFirstKeys(self.iter_events_by_event_keys(
+            first_keys,
+            event_index,
+            to_block_number,
+        )?))
+    }
+
+    fn get_event_keys_index_marker(&self) -> StorageResult<BlockNumber> {
+        self.event_keys_index_marker()
+    }
+}
+
+impl<Mode: TransactionKind> StorageTxn<'_, Mode> {
+    fn event_keys_index_marker(&self) -> StorageResult<BlockNumber> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &MarkerKind::EventKeysIndex)?.unwrap_or_default())
+    }
+}
+
+impl StorageTxn<'_, RW> {
+    // Sets the first block whose events are indexed by their keys.
+    pub(crate) fn set_event_keys_index_marker(
+        self,
+        block_number: &BlockNumber,
+    ) -> StorageResult<Self> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        markers_table.upsert(&self.txn, &MarkerKind::EventKeysIndex, block_number)?;
+        Ok(self)
+    }
+
+    // Indexes the events of the blocks in the given range by their keys.
+    fn index_event_keys(&self, from: BlockNumber, to: BlockNumber) -> StorageResult<()> {
+        let event_keys_table = self.open_table(&self.tables.event_keys)?;
+        for block_number in from.iter_up_to(to) {
+            let transaction_outputs = self
+                .get_block_transaction_outputs(block_number)?
+                .unwrap_or_else(|| panic!("Missing transaction outputs for block {block_number}."));
+            for (offset, tx_output) in transaction_outputs.iter().enumerate() {
+                let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+                for first_key in
+                    tx_output.events().iter().filter_map(|event| event.content.keys.first())
+                {
+                    event_keys_table.upsert(&self.txn, &(first_key.clone(), tx_index), &NoValue)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StorageWriter {
+    /// Indexes by their keys the events of the blocks that were written before the event keys
+    /// index existed, from the latest of them backwards. Indexes a limited number of blocks in
+    /// each call, so it should be called periodically until the event keys index marker reaches
+    /// zero. Returns the event keys index marker.
+    pub fn backfill_event_keys_index(&mut self) -> StorageResult<BlockNumber> {
+        let txn = self.begin_rw_txn()?;
+        let marker = txn.event_keys_index_marker()?;
+        if marker == BlockNumber(0) || txn.scope == StorageScope::StateOnly {
+            return Ok(marker);
+        }
+        // The bodies of the pruned blocks were deleted, so there are no events to index in them.
+        let pruning_marker = txn.get_pruning_marker()?;
+        let from =
+            BlockNumber(marker.0.saturating_sub(MAX_INDEXED_BLOCKS_PER_TXN)).max(pruning_marker);
+        txn.index_event_keys(from, marker)?;
+        let new_marker = if from == pruning_marker { BlockNumber(0) } else { from };
+        txn.set_event_keys_index_marker(&new_marker)?.commit()?;
+        debug!("Indexed the event keys of blocks {new_marker} to {marker} (exclusive).");
+        Ok(new_marker)
+    }
 }
 
 // TODO(dvir): add transaction hash to the return value. In the RPC when returning events this is
 // with the transaction hash. We can do it efficiently here because we anyway read the relevant
 // entry in the transaction_metadata table..
 #[allow(missing_docs)]
-/// A wrapper of two iterators [`EventIterByContractAddress`] and [`EventIterByEventIndex`].
+/// A wrapper of the iterators [`EventIterByContractAddress`], [`EventIterByEventIndex`] and
+/// [`EventIterByFirstKeys`].
 pub enum EventIter<'txn, 'env> {
     ByContractAddress(EventIterByContractAddress<'env, 'txn>),
     ByEventIndex(EventIterByEventIndex<'txn>),
+    ByFirstKeys(EventIterByFirstKeys<'env, 'txn>),
 }
 
-/// This iterator is a wrapper of two iterators [`EventIterByContractAddress`]
-/// and [`EventIterByEventIndex`].
+/// This iterator is a wrapper of the iterators [`EventIterByContractAddress`],
+/// [`EventIterByEventIndex`] and [`EventIterByFirstKeys`].
 /// With this wrapper we can execute the same code, regardless the
 /// type of iteration used.
 impl Iterator for EventIter<'_, '_> {
@@ -132,6 +261,7 @@ impl Iterator for EventIter<'_, '_> {
         match self {
             EventIter::ByContractAddress(it) => it.next(),
             EventIter::ByEventIndex(it) => it.next(),
+            EventIter::ByFirstKeys(it) => it.next(),
         }
         .unwrap_or(None)
     }
@@ -249,6 +379,86 @@ impl EventIterByEventIndex<'_> {
     }
 }
 
+/// This iterator goes over the events whose first key is one of the given keys, in the order of
+/// the event index. It goes over the event keys table to find the transactions that emitted such
+/// events, and reads only their outputs.
+pub struct EventIterByFirstKeys<'env, 'txn> {
+    txn: &'txn DbTransaction<'env, RO>,
+    file_handlers: &'txn FileHandlers<RO>,
+    first_keys: HashSet<EventKey>,
+    start_event_index: EventIndex,
+    to_block_number: BlockNumber,
+    // A cursor of the event keys table for each of the given keys.
+    cursors: Vec<(EventKey, EventKeysTableCursor<'txn>)>,
+    // The transactions the cursors point to, each with the index of its cursor. The first entry is
+    // the next transaction to search for relevant events.
+    next_transactions: BTreeSet<(TransactionIndex, usize)>,
+    // Queue of events to return from the iterator. When this queue is empty, we need to fetch more
+    // events.
+    events_queue: VecDeque<((ContractAddress, EventIndex), EventContent)>,
+    transaction_metadata_table: TransactionMetadataTable<'env>,
+}
+
+impl EventIterByFirstKeys<'_, '_> {
+    /// Returns the next event. If there are no more events, returns None.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn next(&mut self) -> StorageResult<Option<((ContractAddress, EventIndex), EventContent)>> {
+        while self.events_queue.is_empty() {
+            let Some((tx_index, cursor_index)) = self.next_transactions.pop_first() else {
+                return Ok(None);
+            };
+            if tx_index.0 > self.to_block_number {
+                self.next_transactions.clear();
+                return Ok(None);
+            }
+            self.advance_cursor(cursor_index)?;
+            // A transaction that emitted events with several of the keys is pointed to by several
+            // cursors.
+            while let Some((_, cursor_index)) = self
+                .next_transactions
+                .first()
+                .filter(|(next_tx_index, _)| *next_tx_index == tx_index)
+                .copied()
+            {
+                self.next_transactions.pop_first();
+                self.advance_cursor(cursor_index)?;
+            }
+
+            let tx_metadata =
+                self.transaction_metadata_table.get(self.txn, &tx_index)?.unwrap_or_else(|| {
+                    panic!("Transaction metadata not found for transaction index: {tx_index:?}")
+                });
+            let tx_output = self
+                .file_handlers
+                .get_transaction_output_unchecked(tx_metadata.tx_output_location)?;
+            let start_index =
+                if tx_index == self.start_event_index.0 { self.start_event_index.1.0 } else { 0 };
+            // TODO(dvir): don't clone the events here.
+            self.events_queue = get_events_from_tx_by_first_keys(
+                tx_output.events().into(),
+                tx_index,
+                &self.first_keys,
+                start_index,
+            );
+        }
+
+        Ok(self.events_queue.pop_front())
+    }
+
+    // Moves the given cursor to the next transaction that emitted an event with the cursor's key.
+    fn advance_cursor(&mut self, cursor_index: usize) -> StorageResult<()> {
+        let (key, cursor) = &mut self.cursors[cursor_index];
+        if let Some(((next_key, tx_index), _)) = cursor.next()? {
+            if next_key == *key {
+                self.next_transactions.insert((tx_index, cursor_index));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<'txn, 'env> StorageTxn<'env, RO>
 where
     'env: 'txn,
@@ -339,6 +549,52 @@ where
         it.find_next_event_by_event_index()?;
         Ok(it)
     }
+
+    /// Returns an events iterator that iterates the events whose first key is one of the given
+    /// keys by event index from the given event index.
+    ///
+    /// # Arguments
+    /// * first_keys - keys that the first key of the iterated events is one of.
+    /// * event_index - event index to start from the first event with an index greater or equals
+    ///   to.
+    /// * to_block_number - block number to stop iterate at it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_event_keys(
+        &'env self,
+        first_keys: HashSet<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<EventIterByFirstKeys<'env, 'txn>> {
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let event_keys_table = self.open_table(&self.tables.event_keys)?;
+        let mut cursors = Vec::with_capacity(first_keys.len());
+        let mut next_transactions = BTreeSet::new();
+        for key in &first_keys {
+            let mut cursor = event_keys_table.cursor(&self.txn)?;
+            if let Some(((first_key, tx_index), _)) =
+                cursor.lower_bound(&(key.clone(), event_index.0))?
+            {
+                if first_key == *key {
+                    next_transactions.insert((tx_index, cursors.len()));
+                }
+            }
+            cursors.push((key.clone(), cursor));
+        }
+
+        Ok(EventIterByFirstKeys {
+            txn: &self.txn,
+            file_handlers: &self.file_handlers,
+            first_keys,
+            start_event_index: event_index,
+            to_block_number,
+            cursors,
+            next_transactions,
+            events_queue: VecDeque::new(),
+            transaction_metadata_table,
+        })
+    }
 }
 
 fn get_events_from_tx(
@@ -357,9 +613,28 @@ fn get_events_from_tx(
     events
 }
 
+fn get_events_from_tx_by_first_keys(
+    events_list: Vec<Event>,
+    tx_index: TransactionIndex,
+    first_keys: &HashSet<EventKey>,
+    start_index: usize,
+) -> VecDeque<((ContractAddress, EventIndex), EventContent)> {
+    let mut events = VecDeque::new();
+    for (i, event) in events_list.into_iter().enumerate().skip(start_index) {
+        if event.content.keys.first().is_some_and(|first_key| first_keys.contains(first_key)) {
+            let key = (event.from_address, EventIndex(tx_index, EventIndexInTransactionOutput(i)));
+            events.push_back((key, event.content));
+        }
+    }
+    events
+}
+
 /// A cursor of the events table.
 type EventsTableCursor<'txn> =
     DbCursor<'txn, RO, EventsTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
+/// A cursor of the event keys table.
+type EventKeysTableCursor<'txn> =
+    DbCursor<'txn, RO, EventKeysTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
 /// A cursor of the transaction outputs table.
 type TransactionMetadataTableCursor<'txn> =
     DbCursor<'txn, RO, TransactionIndex, VersionZeroWrapper<TransactionMetadata>, SimpleTable>;
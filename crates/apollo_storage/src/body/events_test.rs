@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::vec;
 
 use apollo_test_utils::get_test_block;
@@ -9,6 +10,7 @@ use starknet_api::transaction::{
     EventContent,
     EventData,
     EventIndexInTransactionOutput,
+    EventKey,
     TransactionOffsetInBlock,
 };
 
@@ -129,6 +131,97 @@ fn iter_events_by_index() {
     assert_eq!(event_iter.into_iter().collect::<Vec<_>>(), emitted_events);
 }
 
+#[test]
+fn iter_events_by_first_keys() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let key1 = EventKey(1u32.into());
+    let key2 = EventKey(2u32.into());
+    let key3 = EventKey(3u32.into());
+    let block = get_test_block(
+        4,
+        Some(5),
+        None,
+        Some(vec![vec![key1.clone(), key2.clone(), key3.clone()]]),
+    );
+    let block_number = block.header.block_header_without_hash.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &block.header)
+        .unwrap()
+        .append_body(block_number, block.body.clone())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    // Create the events emitted with the first key key1 or key3, by the order of the event index.
+    let first_keys = HashSet::from([key1, key3]);
+    let mut expected_events = vec![];
+    for (tx_i, tx_output) in block.body.transaction_outputs.iter().enumerate() {
+        for (event_i, event) in tx_output.events().iter().enumerate() {
+            if !first_keys.contains(&event.content.keys[0]) {
+                continue;
+            }
+            let event_index = EventIndex(
+                TransactionIndex(block_number, TransactionOffsetInBlock(tx_i)),
+                EventIndexInTransactionOutput(event_i),
+            );
+            expected_events.push(((event.from_address, event_index), event.content.clone()))
+        }
+    }
+
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    let event_index = EventIndex(
+        TransactionIndex(block_number, TransactionOffsetInBlock(0)),
+        EventIndexInTransactionOutput(0),
+    );
+    let event_iter =
+        txn.iter_events_by_first_keys(first_keys.clone(), event_index, block_number).unwrap();
+    assert_eq!(event_iter.into_iter().collect::<Vec<_>>(), expected_events);
+
+    // Start from the event after the first matching event.
+    let ((_, first_event_index), _) = expected_events[0].clone();
+    let event_index =
+        EventIndex(first_event_index.0, EventIndexInTransactionOutput(first_event_index.1.0 + 1));
+    let event_iter = txn.iter_events_by_first_keys(first_keys, event_index, block_number).unwrap();
+    assert_eq!(event_iter.into_iter().collect::<Vec<_>>(), expected_events[1..]);
+
+    // A key that no event has.
+    let event_iter = txn
+        .iter_events_by_first_keys(
+            HashSet::from([EventKey(4u32.into())]),
+            event_index,
+            block_number,
+        )
+        .unwrap();
+    assert_eq!(event_iter.into_iter().collect::<Vec<_>>(), vec![]);
+
+    // Reverting the block removes its events from the index.
+    drop(txn);
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .revert_header(block_number)
+        .unwrap()
+        .0
+        .revert_body(block_number)
+        .unwrap()
+        .0
+        .commit()
+        .unwrap();
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    let event_keys_table = txn.txn.open_table(&txn.tables.event_keys).unwrap();
+    for (tx_idx, tx_output) in block.body.transaction_outputs.iter().enumerate() {
+        let transaction_index = TransactionIndex(block_number, TransactionOffsetInBlock(tx_idx));
+        for event in tx_output.events().iter() {
+            assert_matches!(
+                event_keys_table.get(&txn.txn, &(event.content.keys[0].clone(), transaction_index)),
+                Ok(None)
+            );
+        }
+    }
+}
+
 #[test]
 fn revert_events() {
     let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
@@ -253,3 +346,60 @@ fn get_events_from_tx_test() {
     assert_eq!(get_events_from_tx(events.clone(), tx_index, ca1, 3), vec![]);
     assert_eq!(get_events_from_tx(events.clone(), tx_index, ca2, 3), vec![]);
 }
+
+#[test]
+fn backfill_event_keys_index() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+    let key = EventKey(1u32.into());
+    let block = get_test_block(2, Some(3), None, Some(vec![vec![key.clone()]]));
+    let block_number = block.header.block_header_without_hash.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &block.header)
+        .unwrap()
+        .append_body(block_number, block.body.clone())
+        .unwrap()
+        .commit()
+        .unwrap();
+    let event_index = EventIndex(
+        TransactionIndex(block_number, TransactionOffsetInBlock(0)),
+        EventIndexInTransactionOutput(0),
+    );
+    let first_keys = HashSet::from([key.clone()]);
+    let expected_events = storage_reader
+        .begin_ro_txn()
+        .unwrap()
+        .iter_events_by_first_keys(first_keys.clone(), event_index, block_number)
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert!(!expected_events.is_empty());
+
+    // Drop the block from the index, as if it was written before the index existed.
+    let txn = storage_writer.begin_rw_txn().unwrap();
+    let event_keys_table = txn.txn.open_table(&txn.tables.event_keys).unwrap();
+    for tx_offset in 0..block.body.transaction_outputs.len() {
+        let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(tx_offset));
+        event_keys_table.delete(&txn.txn, &(key.clone(), tx_index)).unwrap();
+    }
+    txn.set_event_keys_index_marker(&block_number.unchecked_next()).unwrap().commit().unwrap();
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_event_keys_index_marker().unwrap(), block_number.unchecked_next());
+    assert_eq!(
+        txn.iter_events_by_first_keys(first_keys.clone(), event_index, block_number)
+            .unwrap()
+            .count(),
+        0
+    );
+    drop(txn);
+
+    assert_eq!(storage_writer.backfill_event_keys_index().unwrap(), BlockNumber(0));
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_event_keys_index_marker().unwrap(), BlockNumber(0));
+    assert_eq!(
+        txn.iter_events_by_first_keys(first_keys, event_index, block_number)
+            .unwrap()
+            .collect::<Vec<_>>(),
+        expected_events
+    );
+}
@@ -50,6 +50,7 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockBody, BlockNumber};
 use starknet_api::core::ContractAddress;
 use starknet_api::transaction::{
+    EventKey,
     Transaction,
     TransactionHash,
     TransactionOffsetInBlock,
@@ -60,6 +61,7 @@ use tracing::debug;
 use crate::db::serialization::{NoVersionValueWrapper, VersionZeroWrapper};
 use crate::db::table_types::{CommonPrefix, DbCursorTrait, NoValue, SimpleTable, Table};
 use crate::db::{DbTransaction, TableHandle, TransactionKind, RW};
+use crate::pruning::PruningStorageReader;
 use crate::{
     FileHandlers,
     MarkerKind,
@@ -81,6 +83,9 @@ type TransactionHashToIdxTable<'env> =
 type EventsTableKey = (ContractAddress, TransactionIndex);
 type EventsTable<'env> =
     TableHandle<'env, EventsTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
+type EventKeysTableKey = (EventKey, TransactionIndex);
+type EventKeysTable<'env> =
+    TableHandle<'env, EventKeysTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
 
 /// The index of a transaction in a block.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, PartialOrd, Ord)]
@@ -261,7 +266,7 @@ impl<Mode: TransactionKind> BodyStorageReader for StorageTxn<'_, Mode> {
     ) -> StorageResult<Option<usize>> {
         // After this condition, we know that the block exists, so if something goes wrong is only
         // because there are no transactions in it.
-        if self.get_body_marker()? <= block_number {
+        if self.get_body_marker()? <= block_number || block_number < self.get_pruning_marker()? {
             return Ok(None);
         }
 
@@ -293,7 +298,8 @@ impl<'env, Mode: TransactionKind> StorageTxn<'env, Mode> {
         transaction_metadata_table: TransactionMetadataTable<'env>,
         tx_metadata_to_tx_object: fn(TransactionMetadata, &FileHandlers<Mode>) -> StorageResult<T>,
     ) -> StorageResult<Option<Vec<T>>> {
-        if self.get_body_marker()? <= block_number {
+        // The bodies of pruned blocks were deleted.
+        if self.get_body_marker()? <= block_number || block_number < self.get_pruning_marker()? {
             return Ok(None);
         }
         let mut cursor = transaction_metadata_table.cursor(&self.txn)?;
@@ -379,6 +385,7 @@ impl BodyStorageWriter for StorageTxn<'_, RW> {
 
         if self.scope != StorageScope::StateOnly {
             let events_table = self.open_table(&self.tables.events)?;
+            let event_keys_table = self.open_table(&self.tables.event_keys)?;
             let transaction_hash_to_idx_table =
                 self.open_table(&self.tables.transaction_hash_to_idx)?;
             let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
@@ -392,6 +399,7 @@ impl BodyStorageWriter for StorageTxn<'_, RW> {
                 &transaction_hash_to_idx_table,
                 &transaction_metadata_table,
                 &events_table,
+                &event_keys_table,
                 block_number,
             )?;
         }
@@ -424,11 +432,6 @@ impl BodyStorageWriter for StorageTxn<'_, RW> {
                 break 'reverted_block_body None;
             }
 
-            let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
-            let transaction_hash_to_idx_table =
-                self.open_table(&self.tables.transaction_hash_to_idx)?;
-            let events_table = self.open_table(&self.tables.events)?;
-
             let transactions = self
                 .get_block_transactions(block_number)?
                 .unwrap_or_else(|| panic!("Missing transactions for block {block_number}."));
@@ -439,27 +442,58 @@ impl BodyStorageWriter for StorageTxn<'_, RW> {
                 .get_block_transaction_hashes(block_number)?
                 .unwrap_or_else(|| panic!("Missing transaction hashes for block {block_number}."));
 
-            // Delete the transactions data.
-            for (offset, (tx_hash, tx_output)) in
-                transaction_hashes.iter().zip(transaction_outputs.iter()).enumerate()
-            {
-                let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
-
-                for event in tx_output.events().iter() {
-                    events_table.delete(&self.txn, &(event.from_address, tx_index))?;
-                }
-                transaction_hash_to_idx_table.delete(&self.txn, tx_hash)?;
-                transaction_metadata_table.delete(&self.txn, &tx_index)?;
-            }
+            self.delete_block_transactions(
+                block_number,
+                &transaction_hashes,
+                &transaction_outputs,
+            )?;
             Some((transactions, transaction_outputs, transaction_hashes))
         };
 
         markers_table.upsert(&self.txn, &MarkerKind::Body, &block_number)?;
         markers_table.upsert(&self.txn, &MarkerKind::Event, &block_number)?;
+        // The block is written again with its events indexed.
+        if markers_table.get(&self.txn, &MarkerKind::EventKeysIndex)?.unwrap_or_default()
+            > block_number
+        {
+            markers_table.upsert(&self.txn, &MarkerKind::EventKeysIndex, &block_number)?;
+        }
         Ok((self, reverted_block_body))
     }
 }
 
+impl StorageTxn<'_, RW> {
+    // Deletes the transactions data of the given block, including their events.
+    pub(crate) fn delete_block_transactions(
+        &self,
+        block_number: BlockNumber,
+        transaction_hashes: &[TransactionHash],
+        transaction_outputs: &[TransactionOutput],
+    ) -> StorageResult<()> {
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let transaction_hash_to_idx_table =
+            self.open_table(&self.tables.transaction_hash_to_idx)?;
+        let events_table = self.open_table(&self.tables.events)?;
+        let event_keys_table = self.open_table(&self.tables.event_keys)?;
+
+        for (offset, (tx_hash, tx_output)) in
+            transaction_hashes.iter().zip(transaction_outputs.iter()).enumerate()
+        {
+            let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+
+            for event in tx_output.events().iter() {
+                events_table.delete(&self.txn, &(event.from_address, tx_index))?;
+                if let Some(first_key) = event.content.keys.first() {
+                    event_keys_table.delete(&self.txn, &(first_key.clone(), tx_index))?;
+                }
+            }
+            transaction_hash_to_idx_table.delete(&self.txn, tx_hash)?;
+            transaction_metadata_table.delete(&self.txn, &tx_index)?;
+        }
+        Ok(())
+    }
+}
+
 // TODO(dvir): consider enforcing that the block_body transactions, transaction_outputs and
 // transaction_hashes to be the same size.
 #[allow(clippy::too_many_arguments)]
@@ -471,6 +505,7 @@ fn write_transactions<'env>(
     transaction_hash_to_idx_table: &'env TransactionHashToIdxTable<'env>,
     transaction_metadata_table: &'env TransactionMetadataTable<'env>,
     events_table: &'env EventsTable<'env>,
+    event_keys_table: &'env EventKeysTable<'env>,
     block_number: BlockNumber,
 ) -> StorageResult<()> {
     for (index, ((tx, tx_output), tx_hash)) in block_body
@@ -484,7 +519,7 @@ fn write_transactions<'env>(
         let transaction_index = TransactionIndex(block_number, tx_offset_in_block);
         let tx_location = file_handlers.append_transaction(tx);
         let tx_output_location = file_handlers.append_transaction_output(tx_output);
-        write_events(tx_output, txn, events_table, transaction_index)?;
+        write_events(tx_output, txn, events_table, event_keys_table, transaction_index)?;
         transaction_hash_to_idx_table.insert(txn, tx_hash, &transaction_index)?;
         transaction_metadata_table.append(
             txn,
@@ -511,12 +546,17 @@ fn write_events<'env>(
     tx_output: &TransactionOutput,
     txn: &DbTransaction<'env, RW>,
     events_table: &'env EventsTable<'env>,
+    event_keys_table: &'env EventKeysTable<'env>,
     transaction_index: TransactionIndex,
 ) -> StorageResult<()> {
     let mut contract_addresses_set = HashSet::new();
+    let mut first_keys_set = HashSet::new();
 
     for event in tx_output.events().iter() {
         contract_addresses_set.insert(event.from_address);
+        if let Some(first_key) = event.content.keys.first() {
+            first_keys_set.insert(first_key.clone());
+        }
     }
 
     for contract_address in contract_addresses_set {
@@ -525,6 +565,9 @@ fn write_events<'env>(
         // is a table.
         events_table.append_greater_sub_key(txn, &key, &NoValue)?;
     }
+    for first_key in first_keys_set {
+        event_keys_table.append_greater_sub_key(txn, &(first_key, transaction_index), &NoValue)?;
+    }
     Ok(())
 }
 
@@ -33,7 +33,7 @@ use apollo_config::dumping::{ser_param, SerializeConfig};
 use apollo_config::validators::validate_ascii;
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_proc_macros::latency_histogram;
-use libmdbx::{DatabaseFlags, Geometry, PageSize, WriteMap};
+use libmdbx::{DatabaseFlags, Geometry, PageSize, TableFlags, WriteMap};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::ChainId;
 use validator::Validate;
@@ -43,7 +43,7 @@ use self::table_types::{DbCursor, DbCursorTrait};
 use crate::db::table_types::TableType;
 
 // Maximum number of Sub-Databases.
-const MAX_DBS: usize = 21;
+const MAX_DBS: usize = 22;
 
 // Note that NO_TLS mode is used by default.
 type EnvironmentKind = WriteMap;
@@ -217,7 +217,7 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
             })
             .open(&config.path())?,
     );
-    Ok((DbReader { env: env.clone() }, DbWriter { env }))
+    Ok((DbReader { env: env.clone() }, DbWriter { env, read_only: false }))
 }
 
 // Size in bytes.
@@ -243,6 +243,9 @@ pub(crate) struct DbReader {
 #[derive(Debug)]
 pub(crate) struct DbWriter {
     env: Arc<Environment>,
+    // When set, tables are only opened and never created, so that opening the storage doesn't
+    // write to it.
+    read_only: bool,
 }
 
 impl DbReader {
@@ -257,6 +260,24 @@ impl DbWriter {
     pub(crate) fn begin_rw_txn(&mut self) -> DbResult<DbWriteTransaction<'_>> {
         Ok(DbWriteTransaction { txn: self.env.begin_rw_txn()? })
     }
+
+    /// Returns a writer whose `create_*_table` functions fail for missing tables instead of
+    /// creating them.
+    pub(crate) fn into_read_only(self) -> Self {
+        Self { read_only: true, ..self }
+    }
+
+    fn create_table(&mut self, name: &'static str, flags: TableFlags) -> DbResult<()> {
+        if self.read_only {
+            // Fails if the table doesn't exist.
+            self.env.begin_ro_txn()?.open_table(Some(name))?;
+            return Ok(());
+        }
+        let txn = self.env.begin_rw_txn()?;
+        txn.create_table(Some(name), flags)?;
+        txn.commit()?;
+        Ok(())
+    }
 }
 
 type DbWriteTransaction<'env> = DbTransaction<'env, RW>;
@@ -142,9 +142,7 @@ impl DbWriter {
     where
         (MainKey, SubKey): KeyTrait + Debug,
     {
-        let txn = self.env.begin_rw_txn()?;
-        txn.create_table(Some(name), TableFlags::DUP_SORT)?;
-        txn.commit()?;
+        self.create_table(name, TableFlags::DUP_SORT)?;
         Ok(TableIdentifier {
             name,
             _key_type: PhantomData {},
@@ -35,9 +35,7 @@ impl DbWriter {
         &mut self,
         name: &'static str,
     ) -> DbResult<TableIdentifier<K, V, SimpleTable>> {
-        let txn = self.env.begin_rw_txn()?;
-        txn.create_table(Some(name), TableFlags::empty())?;
-        txn.commit()?;
+        self.create_table(name, TableFlags::empty())?;
         Ok(TableIdentifier {
             name,
             _key_type: PhantomData {},
@@ -0,0 +1,529 @@
+//! Interface for verifying the integrity of the storage.
+//!
+//! The verification checks that the markers are consistent with each other, and walks over a
+//! range of blocks. For each block it checks that the header is linked to its parent, that the
+//! state diff, the classes and the body decode from their files, and that the events are indexed
+//! by their contract addresses and, from the event keys index marker and on, by their keys. It then
+//! recomputes the state diff, transaction, event and receipt commitments and the block hash, and
+//! compares them to the ones in the header. The verification stops at the first mismatch and
+//! reports its block and table.
+//!
+//! The blocks are verified in chunks, each in its own read transaction, so that a long verification
+//! of a running node doesn't keep the database from reclaiming the pages that were freed since it
+//! started. The markers are read and verified again in each chunk.
+//!
+//! Blocks whose history was pruned are verified only by their headers. The block hash is
+//! recomputed only for blocks whose Starknet version is 0.13.2 or later, and only under the
+//! [`FullArchive`](crate::StorageScope::FullArchive) scope.
+//!
+//! # Example
+//! ```
+//! # use apollo_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use apollo_storage::header::HeaderStorageWriter;
+//! use apollo_storage::integrity::verify_storage_integrity;
+//! use apollo_storage::open_storage;
+//! use starknet_api::block::{BlockHeader, BlockNumber};
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! writer.begin_rw_txn()?.append_header(BlockNumber(0), &BlockHeader::default())?.commit()?;
+//!
+//! // Verify all the blocks.
+//! let mismatch = verify_storage_integrity(&reader, BlockNumber(0), None)?;
+//! assert_eq!(mismatch, None);
+//! # Ok::<(), apollo_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "integrity_test.rs"]
+mod integrity_test;
+
+use std::fmt::Display;
+
+use starknet_api::block::{BlockHeader, BlockNumber};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_commitments,
+    calculate_block_hash,
+    BlockHashVersion,
+    TransactionHashingData,
+    TransactionOutputForHash,
+};
+use starknet_api::block_hash::state_diff_hash::calculate_state_diff_hash;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::fields::TransactionSignature;
+use starknet_api::transaction::{
+    Transaction,
+    TransactionHash,
+    TransactionOffsetInBlock,
+    TransactionOutput,
+};
+use tracing::{debug, info};
+
+use crate::base_layer::BaseLayerStorageReader;
+use crate::body::events::EventsReader;
+use crate::body::{BodyStorageReader, TransactionIndex};
+use crate::class::ClassStorageReader;
+use crate::compiled_class::CasmStorageReader;
+use crate::db::table_types::Table;
+use crate::db::RO;
+use crate::header::HeaderStorageReader;
+use crate::pruning::PruningStorageReader;
+use crate::state::StateStorageReader;
+use crate::{StorageReader, StorageResult, StorageScope, StorageTxn};
+
+/// A mismatch that was found in the storage by the integrity verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityMismatch {
+    /// The block whose data mismatches. For a mismatch between markers, the value of the marker
+    /// that is too high.
+    pub block_number: BlockNumber,
+    /// The table that holds the mismatching data.
+    pub table: &'static str,
+    /// A description of the mismatch.
+    pub description: String,
+}
+
+impl Display for IntegrityMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Mismatch in table {} at block {}: {}",
+            self.table, self.block_number, self.description
+        )
+    }
+}
+
+type IntegrityResult = Result<(), IntegrityMismatch>;
+
+// The maximal number of blocks that are verified in a single read transaction.
+const MAX_VERIFIED_BLOCKS_PER_TXN: u64 = 1000;
+
+/// Verifies the integrity of the storage in the blocks from `from` up to `to` (exclusive), or up
+/// to the header marker if `to` is None. Returns the first mismatch that was found, if any.
+pub fn verify_storage_integrity(
+    reader: &StorageReader,
+    from: BlockNumber,
+    to: Option<BlockNumber>,
+) -> StorageResult<Option<IntegrityMismatch>> {
+    info!("Verifying the integrity of the storage in the blocks from {from}.");
+    let mut chunk_start = from;
+    loop {
+        let txn = reader.begin_ro_txn()?;
+        let markers = Markers::new(&txn)?;
+        if let Err(mismatch) = markers.verify(txn.scope) {
+            return Ok(Some(mismatch));
+        }
+
+        // The header marker is read in each chunk, since blocks may be reverted in the meantime.
+        let end = to.map_or(markers.header, |to| to.min(markers.header));
+        if chunk_start >= end {
+            info!("The storage in the blocks {from} to {end} is consistent.");
+            return Ok(None);
+        }
+        let chunk_end =
+            BlockNumber(chunk_start.0.saturating_add(MAX_VERIFIED_BLOCKS_PER_TXN)).min(end);
+        debug!("Verifying the integrity of the blocks {chunk_start} to {chunk_end}.");
+        for block_number in chunk_start.iter_up_to(chunk_end) {
+            if let Err(mismatch) = verify_block(&txn, &markers, block_number) {
+                return Ok(Some(mismatch));
+            }
+        }
+        chunk_start = chunk_end;
+    }
+}
+
+// The markers of the storage.
+struct Markers {
+    header: BlockNumber,
+    body: BlockNumber,
+    state: BlockNumber,
+    class: BlockNumber,
+    compiled_class: BlockNumber,
+    base_layer_block: BlockNumber,
+    pruning: BlockNumber,
+    event_keys_index: BlockNumber,
+}
+
+impl Markers {
+    fn new(txn: &StorageTxn<'_, RO>) -> StorageResult<Self> {
+        Ok(Self {
+            header: txn.get_header_marker()?,
+            body: txn.get_body_marker()?,
+            state: txn.get_state_marker()?,
+            class: txn.get_class_marker()?,
+            compiled_class: txn.get_compiled_class_marker()?,
+            base_layer_block: txn.get_base_layer_block_marker()?,
+            pruning: txn.get_pruning_marker()?,
+            event_keys_index: txn.get_event_keys_index_marker()?,
+        })
+    }
+
+    // Verifies the invariants that are documented in MarkerKind.
+    fn verify(&self, scope: StorageScope) -> IntegrityResult {
+        let mut invariants = vec![
+            ("CompiledClass", self.compiled_class, "Class", self.class),
+            ("Class", self.class, "State", self.state),
+            ("State", self.state, "Header", self.header),
+            ("Body", self.body, "Header", self.header),
+            ("BaseLayerBlock", self.base_layer_block, "Header", self.header),
+            ("Pruning", self.pruning, "State", self.state),
+            ("EventKeysIndex", self.event_keys_index, "Body", self.body),
+        ];
+        if scope == StorageScope::FullArchive {
+            invariants.push(("Pruning", self.pruning, "Body", self.body));
+        }
+        for (lower_name, lower, upper_name, upper) in invariants {
+            if lower > upper {
+                return Err(mismatch(
+                    lower,
+                    "markers",
+                    format!("The {lower_name} marker is beyond the {upper_name} marker {upper}."),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn mismatch(
+    block_number: BlockNumber,
+    table: &'static str,
+    description: impl Into<String>,
+) -> IntegrityMismatch {
+    IntegrityMismatch { block_number, table, description: description.into() }
+}
+
+// Returns the value that was read, or a mismatch if the read failed or the value is missing.
+fn read<T>(
+    block_number: BlockNumber,
+    table: &'static str,
+    name: &str,
+    result: StorageResult<Option<T>>,
+) -> Result<T, IntegrityMismatch> {
+    match result {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(mismatch(block_number, table, format!("The {name} is missing."))),
+        Err(err) => Err(mismatch(block_number, table, format!("Failed to read the {name}: {err}"))),
+    }
+}
+
+fn verify_block(
+    txn: &StorageTxn<'_, RO>,
+    markers: &Markers,
+    block_number: BlockNumber,
+) -> IntegrityResult {
+    let header = verify_header(txn, block_number)?;
+    if block_number < markers.pruning {
+        return Ok(());
+    }
+
+    let state_diff = if block_number < markers.state {
+        Some(verify_state_diff(txn, markers, block_number, &header)?)
+    } else {
+        None
+    };
+
+    if txn.scope == StorageScope::StateOnly || block_number >= markers.body {
+        return Ok(());
+    }
+    let transactions_data = verify_body(txn, markers, block_number, &header)?;
+
+    let starknet_version = &header.block_header_without_hash.starknet_version;
+    let commitments = calculate_block_commitments(
+        &transactions_data,
+        state_diff.as_ref().unwrap_or(&ThinStateDiff::default()),
+        header.block_header_without_hash.l1_da_mode,
+        starknet_version,
+    );
+    let computed_commitments = [
+        (
+            "transaction_metadata",
+            "transaction",
+            header.transaction_commitment.map(|c| c.0),
+            commitments.transaction_commitment.0,
+        ),
+        ("events", "event", header.event_commitment.map(|c| c.0), commitments.event_commitment.0),
+        (
+            "transaction_metadata",
+            "receipt",
+            header.receipt_commitment.map(|c| c.0),
+            commitments.receipt_commitment.0,
+        ),
+    ];
+    for (table, name, header_commitment, computed_commitment) in computed_commitments {
+        if header_commitment.is_some_and(|commitment| commitment != computed_commitment) {
+            return Err(mismatch(
+                block_number,
+                table,
+                format!(
+                    "The {name} commitment in the header doesn't match the recomputed commitment \
+                     {computed_commitment}."
+                ),
+            ));
+        }
+    }
+
+    // Older blocks have a different block hash mechanism.
+    if state_diff.is_none() || BlockHashVersion::try_from(*starknet_version).is_err() {
+        return Ok(());
+    }
+    let block_hash = calculate_block_hash(header.block_header_without_hash.clone(), commitments)
+        .map_err(|err| {
+            mismatch(block_number, "headers", format!("Failed to compute the hash: {err}"))
+        })?;
+    if block_hash != header.block_hash {
+        return Err(mismatch(
+            block_number,
+            "headers",
+            format!("The block hash doesn't match the recomputed hash {block_hash}."),
+        ));
+    }
+    Ok(())
+}
+
+// Verifies that the header is indexed by its hash and linked to the header of its parent.
+fn verify_header(
+    txn: &StorageTxn<'_, RO>,
+    block_number: BlockNumber,
+) -> Result<BlockHeader, IntegrityMismatch> {
+    let header = read(block_number, "headers", "header", txn.get_block_header(block_number))?;
+    if header.block_header_without_hash.block_number != block_number {
+        return Err(mismatch(
+            block_number,
+            "headers",
+            format!("The header is of block {}.", header.block_header_without_hash.block_number),
+        ));
+    }
+
+    let number_by_hash = read(
+        block_number,
+        "block_hash_to_number",
+        "block number of the hash",
+        txn.get_block_number_by_hash(&header.block_hash),
+    )?;
+    if number_by_hash != block_number {
+        return Err(mismatch(
+            block_number,
+            "block_hash_to_number",
+            format!("The block hash is mapped to block {number_by_hash}."),
+        ));
+    }
+
+    if let Some(parent_block_number) = block_number.prev() {
+        let parent_header = read(
+            parent_block_number,
+            "headers",
+            "header",
+            txn.get_block_header(parent_block_number),
+        )?;
+        if parent_header.block_hash != header.block_header_without_hash.parent_hash {
+            return Err(mismatch(
+                block_number,
+                "headers",
+                "The parent hash doesn't match the hash of the previous block.",
+            ));
+        }
+    }
+    Ok(header)
+}
+
+// Verifies that the state diff and its classes decode, and that the state diff matches its
+// commitment in the header.
+fn verify_state_diff(
+    txn: &StorageTxn<'_, RO>,
+    markers: &Markers,
+    block_number: BlockNumber,
+    header: &BlockHeader,
+) -> Result<ThinStateDiff, IntegrityMismatch> {
+    let state_diff =
+        read(block_number, "state_diffs", "state diff", txn.get_state_diff(block_number))?;
+    if header.state_diff_length.is_some_and(|length| length != state_diff.len()) {
+        return Err(mismatch(
+            block_number,
+            "state_diffs",
+            format!("The state diff length {} doesn't match the header.", state_diff.len()),
+        ));
+    }
+    let state_diff_commitment = calculate_state_diff_hash(&state_diff);
+    if header.state_diff_commitment.is_some_and(|commitment| commitment != state_diff_commitment) {
+        return Err(mismatch(
+            block_number,
+            "state_diffs",
+            "The state diff commitment in the header doesn't match the recomputed commitment.",
+        ));
+    }
+
+    // The classes may be stored outside of the storage, so only decoding errors are mismatches.
+    if block_number < markers.class {
+        for class_hash in state_diff.declared_classes.keys() {
+            if let Err(err) = txn.get_class(class_hash) {
+                return Err(mismatch(
+                    block_number,
+                    "declared_classes",
+                    format!("Failed to read the class {class_hash}: {err}"),
+                ));
+            }
+        }
+        for class_hash in &state_diff.deprecated_declared_classes {
+            if let Err(err) = txn.get_deprecated_class(class_hash) {
+                return Err(mismatch(
+                    block_number,
+                    "deprecated_declared_classes",
+                    format!("Failed to read the deprecated class {class_hash}: {err}"),
+                ));
+            }
+        }
+    }
+    if block_number < markers.compiled_class {
+        for class_hash in state_diff.declared_classes.keys() {
+            if let Err(err) = txn.get_casm(class_hash) {
+                return Err(mismatch(
+                    block_number,
+                    "casms",
+                    format!("Failed to read the CASM of class {class_hash}: {err}"),
+                ));
+            }
+        }
+    }
+    Ok(state_diff)
+}
+
+// Verifies that the transactions and their outputs decode, that their events are indexed and that
+// their counts match the header. Returns the data of the transactions for the block hash.
+fn verify_body(
+    txn: &StorageTxn<'_, RO>,
+    markers: &Markers,
+    block_number: BlockNumber,
+    header: &BlockHeader,
+) -> Result<Vec<TransactionHashingData>, IntegrityMismatch> {
+    let transactions = read(
+        block_number,
+        "transaction_metadata",
+        "transactions",
+        txn.get_block_transactions(block_number),
+    )?;
+    let transaction_outputs = read(
+        block_number,
+        "transaction_metadata",
+        "transaction outputs",
+        txn.get_block_transaction_outputs(block_number),
+    )?;
+    let transaction_hashes = read(
+        block_number,
+        "transaction_metadata",
+        "transaction hashes",
+        txn.get_block_transaction_hashes(block_number),
+    )?;
+
+    let events_table = txn.open_table(&txn.tables.events).map_err(|err| {
+        mismatch(block_number, "events", format!("Failed to open the table: {err}"))
+    })?;
+    let event_keys_table = txn.open_table(&txn.tables.event_keys).map_err(|err| {
+        mismatch(block_number, "event_keys", format!("Failed to open the table: {err}"))
+    })?;
+    // The events of the blocks below the event keys index marker aren't indexed by their keys yet.
+    let event_keys_indexed = block_number >= markers.event_keys_index;
+    for (offset, transaction_output) in transaction_outputs.iter().enumerate() {
+        let transaction_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+        for event in transaction_output.events() {
+            let indexed = events_table
+                .get(&txn.txn, &(event.from_address, transaction_index))
+                .map_err(|err| {
+                    mismatch(block_number, "events", format!("Failed to read the index: {err}"))
+                })?;
+            if indexed.is_none() {
+                return Err(mismatch(
+                    block_number,
+                    "events",
+                    format!(
+                        "The events of transaction {offset} from {} aren't indexed.",
+                        event.from_address
+                    ),
+                ));
+            }
+            if !event_keys_indexed {
+                continue;
+            }
+            let Some(first_key) = event.content.keys.first() else {
+                continue;
+            };
+            let indexed = event_keys_table
+                .get(&txn.txn, &(first_key.clone(), transaction_index))
+                .map_err(|err| {
+                mismatch(block_number, "event_keys", format!("Failed to read the index: {err}"))
+            })?;
+            if indexed.is_none() {
+                return Err(mismatch(
+                    block_number,
+                    "event_keys",
+                    format!(
+                        "The events of transaction {offset} with the key {} aren't indexed.",
+                        first_key.0
+                    ),
+                ));
+            }
+        }
+    }
+
+    // The counts are stored together with the commitments.
+    if header.transaction_commitment.is_some() {
+        let n_events: usize = transaction_outputs.iter().map(|output| output.events().len()).sum();
+        if header.n_transactions != transactions.len() || header.n_events != n_events {
+            return Err(mismatch(
+                block_number,
+                "transaction_metadata",
+                format!(
+                    "The block has {} transactions and {n_events} events, which doesn't match the \
+                     header.",
+                    transactions.len()
+                ),
+            ));
+        }
+    }
+
+    Ok(get_transactions_hashing_data(&transactions, &transaction_outputs, &transaction_hashes))
+}
+
+// Returns the data of the transactions of a block that is used to calculate the block hash.
+pub(crate) fn get_transactions_hashing_data(
+    transactions: &[Transaction],
+    transaction_outputs: &[TransactionOutput],
+    transaction_hashes: &[TransactionHash],
+) -> Vec<TransactionHashingData> {
+    transactions
+        .iter()
+        .zip(transaction_outputs)
+        .zip(transaction_hashes)
+        .map(|((transaction, transaction_output), transaction_hash)| TransactionHashingData {
+            transaction_signature: get_transaction_signature(transaction),
+            transaction_output: TransactionOutputForHash {
+                actual_fee: transaction_output.actual_fee(),
+                events: transaction_output.events().to_vec(),
+                execution_status: transaction_output.execution_status().clone(),
+                gas_consumed: transaction_output.execution_resources().gas_consumed,
+                messages_sent: transaction_output.messages_sent().clone(),
+            },
+            transaction_hash: *transaction_hash,
+        })
+        .collect()
+}
+
+fn get_transaction_signature(transaction: &Transaction) -> TransactionSignature {
+    match transaction {
+        Transaction::Declare(tx) => tx.signature(),
+        Transaction::DeployAccount(tx) => tx.signature(),
+        Transaction::Invoke(tx) => tx.signature(),
+        Transaction::Deploy(_) | Transaction::L1Handler(_) => TransactionSignature::default(),
+    }
+}
@@ -0,0 +1,143 @@
+use apollo_test_utils::get_test_body;
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_commitments,
+    calculate_block_hash,
+};
+use starknet_api::felt;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::{EventKey, TransactionOffsetInBlock};
+
+use crate::base_layer::BaseLayerStorageWriter;
+use crate::body::{BodyStorageWriter, TransactionIndex};
+use crate::db::table_types::Table;
+use crate::header::HeaderStorageWriter;
+use crate::integrity::{get_transactions_hashing_data, verify_storage_integrity};
+use crate::state::StateStorageWriter;
+use crate::test_utils::get_test_storage;
+use crate::{StorageReader, StorageWriter};
+
+const N_BLOCKS: u64 = 3;
+
+// Appends N_BLOCKS blocks whose headers contain their commitments and their computed hash. If
+// `corrupted_block` is given, its hash is replaced.
+fn append_blocks(writer: &mut StorageWriter, corrupted_block: Option<BlockNumber>) {
+    let mut txn = writer.begin_rw_txn().unwrap();
+    let mut parent_hash = BlockHash::default();
+    for block_number in BlockNumber(0).iter_up_to(BlockNumber(N_BLOCKS)) {
+        let body = get_test_body(2, Some(1), None, None);
+        let state_diff = ThinStateDiff::default();
+        let block_header_without_hash =
+            BlockHeaderWithoutHash { block_number, parent_hash, ..Default::default() };
+        let commitments = calculate_block_commitments(
+            &get_transactions_hashing_data(
+                &body.transactions,
+                &body.transaction_outputs,
+                &body.transaction_hashes,
+            ),
+            &state_diff,
+            block_header_without_hash.l1_da_mode,
+            &block_header_without_hash.starknet_version,
+        );
+        let mut block_hash =
+            calculate_block_hash(block_header_without_hash.clone(), commitments.clone()).unwrap();
+        if corrupted_block == Some(block_number) {
+            block_hash = BlockHash(block_hash.0 + felt!(1_u8));
+        }
+        let header = BlockHeader {
+            block_hash,
+            block_header_without_hash,
+            state_diff_commitment: Some(commitments.state_diff_commitment),
+            state_diff_length: Some(state_diff.len()),
+            transaction_commitment: Some(commitments.transaction_commitment),
+            event_commitment: Some(commitments.event_commitment),
+            receipt_commitment: Some(commitments.receipt_commitment),
+            n_transactions: body.transactions.len(),
+            n_events: body.transaction_outputs.iter().map(|output| output.events().len()).sum(),
+        };
+        txn = txn
+            .append_header(block_number, &header)
+            .unwrap()
+            .append_body(block_number, body)
+            .unwrap()
+            .append_state_diff(block_number, state_diff)
+            .unwrap();
+        parent_hash = block_hash;
+    }
+    txn.commit().unwrap();
+}
+
+fn get_test_storage_with_blocks(
+    corrupted_block: Option<BlockNumber>,
+) -> (StorageReader, StorageWriter, tempfile::TempDir) {
+    let ((reader, mut writer), temp_dir) = get_test_storage();
+    append_blocks(&mut writer, corrupted_block);
+    (reader, writer, temp_dir)
+}
+
+#[test]
+fn verify_consistent_storage() {
+    let (reader, _writer, _temp_dir) = get_test_storage_with_blocks(None);
+    assert_eq!(verify_storage_integrity(&reader, BlockNumber(0), None).unwrap(), None);
+    assert_eq!(
+        verify_storage_integrity(&reader, BlockNumber(1), Some(BlockNumber(N_BLOCKS + 1))).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn verify_wrong_block_hash() {
+    let (reader, _writer, _temp_dir) = get_test_storage_with_blocks(Some(BlockNumber(1)));
+    let mismatch = verify_storage_integrity(&reader, BlockNumber(0), None).unwrap().unwrap();
+    assert_eq!(mismatch.block_number, BlockNumber(1));
+    assert_eq!(mismatch.table, "headers");
+
+    // The corrupted block is outside of the range.
+    assert_eq!(
+        verify_storage_integrity(&reader, BlockNumber(0), Some(BlockNumber(1))).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn verify_inconsistent_markers() {
+    let (reader, mut writer, _temp_dir) = get_test_storage_with_blocks(None);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .update_base_layer_block_marker(&BlockNumber(N_BLOCKS + 1))
+        .unwrap()
+        .commit()
+        .unwrap();
+    let mismatch = verify_storage_integrity(&reader, BlockNumber(0), None).unwrap();
+    assert_matches!(
+        mismatch,
+        Some(mismatch)
+        if mismatch.table == "markers" && mismatch.block_number == BlockNumber(N_BLOCKS + 1)
+    );
+}
+
+#[test]
+fn verify_missing_event_keys_index() {
+    let (reader, mut writer, _temp_dir) = get_test_storage_with_blocks(None);
+    let txn = writer.begin_rw_txn().unwrap();
+    let event_keys_table = txn.open_table(&txn.tables.event_keys).unwrap();
+    let transaction_index = TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(0));
+    event_keys_table.delete(&txn.txn, &(EventKey::default(), transaction_index)).unwrap();
+    txn.commit().unwrap();
+    let mismatch = verify_storage_integrity(&reader, BlockNumber(0), None).unwrap().unwrap();
+    assert_eq!(mismatch.block_number, BlockNumber(1));
+    assert_eq!(mismatch.table, "event_keys");
+
+    // The events of the blocks below the event keys index marker aren't expected to be indexed.
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .set_event_keys_index_marker(&BlockNumber(2))
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert_eq!(verify_storage_integrity(&reader, BlockNumber(0), None).unwrap(), None);
+}
@@ -89,8 +89,11 @@ pub mod storage_metrics;
 pub mod compression_utils;
 pub mod db;
 pub mod header;
+pub mod integrity;
 pub mod mmap_file;
+pub mod pruning;
 mod serialization;
+pub mod snapshot;
 pub mod state;
 mod version;
 
@@ -107,7 +110,12 @@ use std::fmt::Debug;
 use std::fs;
 use std::sync::Arc;
 
-use apollo_config::dumping::{prepend_sub_config_name, ser_param, SerializeConfig};
+use apollo_config::dumping::{
+    prepend_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_proc_macros::latency_histogram;
 use body::events::EventIndex;
@@ -124,18 +132,20 @@ use mmap_file::{
     Reader,
     Writer,
 };
+use pruning::DEFAULT_HISTORY_RETENTION;
 use serde::{Deserialize, Serialize};
+use snapshot::SnapshotError;
 use starknet_api::block::{BlockHash, BlockNumber, BlockSignature, StarknetVersion};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{SierraContractClass, StateNumber, StorageKey, ThinStateDiff};
-use starknet_api::transaction::{Transaction, TransactionHash, TransactionOutput};
+use starknet_api::transaction::{EventKey, Transaction, TransactionHash, TransactionOutput};
 use starknet_types_core::felt::Felt;
 use tracing::{debug, info, warn};
 use validator::Validate;
 use version::{StorageVersionError, Version};
 
-use crate::body::TransactionIndex;
+use crate::body::{BodyStorageReader, TransactionIndex};
 use crate::db::table_types::SimpleTable;
 use crate::db::{
     open_env,
@@ -152,6 +162,7 @@ use crate::db::{
 };
 use crate::header::StorageBlockHeader;
 use crate::mmap_file::MMapFileStats;
+use crate::pruning::{ReadTxnGuard, ReadTxnTracker, UnprunedFileLocations};
 use crate::state::data::IndexedDeprecatedContractClass;
 use crate::version::{VersionStorageReader, VersionStorageWriter};
 
@@ -159,11 +170,41 @@ use crate::version::{VersionStorageReader, VersionStorageWriter};
 /// The current version of the storage state code.
 pub const STORAGE_VERSION_STATE: Version = Version { major: 6, minor: 0 };
 /// The current version of the storage blocks code.
-pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 6, minor: 0 };
+pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 6, minor: 1 };
+// The first blocks version with the event keys index. Storages of older versions are indexed in the
+// background, see [`StorageWriter::backfill_event_keys_index`].
+const EVENT_KEYS_INDEX_BLOCKS_VERSION: Version = Version { major: 6, minor: 1 };
 
 /// Opens a storage and returns a [`StorageReader`] and a [`StorageWriter`].
 pub fn open_storage(
     storage_config: StorageConfig,
+) -> StorageResult<(StorageReader, StorageWriter)> {
+    let (reader, writer) = open_storage_internal(storage_config, false)?;
+    let writer = set_version_if_needed(reader.clone(), writer)?;
+    verify_storage_version(reader.clone())?;
+    Ok((reader, writer))
+}
+
+/// Opens an existing storage for reading only and returns a [`StorageReader`]. Unlike
+/// [`open_storage`], nothing is written to the storage: it isn't created, initialized or migrated,
+/// so opening fails if its version or scope differ from the crate's.
+pub fn open_storage_read_only(mut storage_config: StorageConfig) -> StorageResult<StorageReader> {
+    storage_config.db_config.enforce_file_exists = true;
+    let (reader, _) = open_storage_internal(storage_config, true)?;
+    match get_storage_version(reader.clone())? {
+        None => return Err(StorageVersionError::Uninitialized.into()),
+        Some(StorageVersion::StateOnly(_)) if reader.scope == StorageScope::FullArchive => {
+            return Err(StorageVersionError::InconsistentStorageScope.into());
+        }
+        Some(_) => {}
+    }
+    verify_storage_version(reader.clone())?;
+    Ok(reader)
+}
+
+fn open_storage_internal(
+    storage_config: StorageConfig,
+    read_only: bool,
 ) -> StorageResult<(StorageReader, StorageWriter)> {
     info!("Opening storage: {}", storage_config.db_config.path_prefix.display());
     if !storage_config.db_config.path_prefix.exists()
@@ -174,6 +215,9 @@ pub fn open_storage(
     }
 
     let (db_reader, mut db_writer) = open_env(&storage_config.db_config)?;
+    if read_only {
+        db_writer = db_writer.into_read_only();
+    }
     let tables = Arc::new(Tables {
         block_hash_to_number: db_writer.create_simple_table("block_hash_to_number")?,
         block_signatures: db_writer.create_simple_table("block_signatures")?,
@@ -186,6 +230,7 @@ pub fn open_storage(
         deprecated_declared_classes_block: db_writer
             .create_simple_table("deprecated_declared_classes_block")?,
         deployed_contracts: db_writer.create_simple_table("deployed_contracts")?,
+        event_keys: db_writer.create_common_prefix_table("event_keys")?,
         events: db_writer.create_common_prefix_table("events")?,
         headers: db_writer.create_simple_table("headers")?,
         markers: db_writer.create_simple_table("markers")?,
@@ -211,16 +256,23 @@ pub fn open_storage(
         &tables.file_offsets,
     )?;
 
+    let read_txn_tracker = Arc::new(ReadTxnTracker::default());
     let reader = StorageReader {
         db_reader,
         tables: tables.clone(),
         scope: storage_config.scope,
         file_readers,
+        read_txn_tracker: read_txn_tracker.clone(),
+    };
+    let writer = StorageWriter {
+        db_writer,
+        tables,
+        scope: storage_config.scope,
+        file_writers,
+        history_retention: storage_config.history_retention,
+        read_txn_tracker,
+        pending_pruned_file_ranges: Vec::new(),
     };
-    let writer = StorageWriter { db_writer, tables, scope: storage_config.scope, file_writers };
-
-    let writer = set_version_if_needed(reader.clone(), writer)?;
-    verify_storage_version(reader.clone())?;
     Ok((reader, writer))
 }
 
@@ -291,6 +343,13 @@ fn set_version_if_needed(
                     "Updating the storage blocks version from {:?} to {:?}",
                     blocks_version, STORAGE_VERSION_BLOCKS
                 );
+                if blocks_version.minor < EVENT_KEYS_INDEX_BLOCKS_VERSION.minor {
+                    // The blocks that were already written aren't indexed yet. The blocks from the
+                    // body marker are indexed when they are written.
+                    let body_marker = wtxn.get_body_marker()?;
+                    debug!("Indexing the event keys from block {body_marker}.");
+                    wtxn = wtxn.set_event_keys_index_marker(&body_marker)?;
+                }
                 wtxn = wtxn.set_blocks_version(&STORAGE_VERSION_BLOCKS)?;
             }
         }
@@ -423,17 +482,21 @@ pub struct StorageReader {
     file_readers: FileHandlers<RO>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    read_txn_tracker: Arc<ReadTxnTracker>,
 }
 
 impl StorageReader {
     /// Takes a snapshot of the current state of the storage and returns a [`StorageTxn`] for
     /// reading data from the storage.
     pub fn begin_ro_txn(&self) -> StorageResult<StorageTxn<'_, RO>> {
+        // Registered before the snapshot is taken, so that the objects it reads aren't freed.
+        let read_txn_guard = self.read_txn_tracker.register();
         Ok(StorageTxn {
             txn: self.db_reader.begin_ro_txn()?,
             file_handlers: self.file_readers.clone(),
             tables: self.tables.clone(),
             scope: self.scope,
+            _read_txn_guard: Some(read_txn_guard),
         })
     }
 
@@ -465,6 +528,11 @@ pub struct StorageWriter {
     file_writers: FileHandlers<RW>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    history_retention: Option<u64>,
+    read_txn_tracker: Arc<ReadTxnTracker>,
+    // The file ranges of pruned objects that may still be read, with the epoch of the read
+    // transactions that can't read them.
+    pending_pruned_file_ranges: Vec<(u64, UnprunedFileLocations)>,
 }
 
 impl StorageWriter {
@@ -476,6 +544,7 @@ impl StorageWriter {
             file_handlers: self.file_writers.clone(),
             tables: self.tables.clone(),
             scope: self.scope,
+            _read_txn_guard: None,
         })
     }
 }
@@ -487,6 +556,9 @@ pub struct StorageTxn<'env, Mode: TransactionKind> {
     file_handlers: FileHandlers<Mode>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    // Keeps the read transaction registered until it ends. Declared after the transaction, so that
+    // it is dropped after it.
+    _read_txn_guard: Option<ReadTxnGuard>,
 }
 
 impl StorageTxn<'_, RW> {
@@ -505,6 +577,7 @@ impl<Mode: TransactionKind> StorageTxn<'_, Mode> {
     ) -> StorageResult<TableHandle<'_, K, V, T>> {
         if self.scope == StorageScope::StateOnly {
             let unused_tables = [
+                self.tables.event_keys.name,
                 self.tables.events.name,
                 self.tables.transaction_hash_to_idx.name,
                 self.tables.transaction_metadata.name,
@@ -539,6 +612,9 @@ struct_field_names! {
         deprecated_declared_classes_block: TableIdentifier<ClassHash, NoVersionValueWrapper<BlockNumber>, SimpleTable>,
         // TODO(dvir): consider use here also the CommonPrefix table type.
         deployed_contracts: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<ClassHash>, SimpleTable>,
+        // An index of the transactions that emitted an event by the first key of the event, which is
+        // usually the event selector.
+        event_keys: TableIdentifier<(EventKey, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>,
         events: TableIdentifier<(ContractAddress, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>,
         headers: TableIdentifier<BlockNumber, VersionZeroWrapper<StorageBlockHeader>, SimpleTable>,
         markers: TableIdentifier<MarkerKind, VersionZeroWrapper<BlockNumber>, SimpleTable>,
@@ -625,6 +701,13 @@ pub enum StorageError {
          {block_number}."
     )]
     BlockSignatureForNonExistingBlock { block_number: BlockNumber, block_signature: BlockSignature },
+    #[error(
+        "Attempt to prune the history up to block {up_to} which is beyond the marker {marker} of \
+         the pruned data."
+    )]
+    PruneBeyondMarker { up_to: BlockNumber, marker: BlockNumber },
+    #[error(transparent)]
+    SnapshotError(#[from] SnapshotError),
 }
 
 /// A type alias that maps to std::result::Result<T, StorageError>.
@@ -639,6 +722,7 @@ pub struct StorageConfig {
     #[validate]
     pub mmap_file_config: MmapFileConfig,
     pub scope: StorageScope,
+    pub history_retention: Option<u64>,
 }
 
 impl SerializeConfig for StorageConfig {
@@ -649,6 +733,14 @@ impl SerializeConfig for StorageConfig {
             "The categories of data saved in storage.",
             ParamPrivacyInput::Public,
         )]);
+        dumped_config.extend(ser_optional_param(
+            &self.history_retention,
+            DEFAULT_HISTORY_RETENTION,
+            "history_retention",
+            "The number of latest blocks whose history (state versions, bodies and state diffs) \
+             is kept. Older history is pruned in the background.",
+            ParamPrivacyInput::Public,
+        ));
         dumped_config
             .extend(prepend_sub_config_name(self.mmap_file_config.dump(), "mmap_file_config"));
         dumped_config.extend(prepend_sub_config_name(self.db_config.dump(), "db_config"));
@@ -671,6 +763,9 @@ pub struct DbStats {
 // - CompiledClass <= Class <= State <= Header
 // - Body <= Header
 // - BaseLayerBlock <= Header
+// - Pruning <= State
+// - Pruning <= Body (under the FullArchive scope)
+// - EventKeysIndex <= Body
 // Event is currently unsupported.
 pub(crate) enum MarkerKind {
     Header,
@@ -684,6 +779,10 @@ pub(crate) enum MarkerKind {
     /// Marks the block beyond the last block that its classes can't be compiled with the current
     /// compiler version used in the class manager. Determined by starknet version.
     CompilerBackwardCompatibility,
+    /// Marks the first block whose history wasn't pruned.
+    Pruning,
+    /// Marks the first block whose events are indexed by their keys.
+    EventKeysIndex,
 }
 
 pub(crate) type MarkersTable<'env> =
@@ -760,6 +859,25 @@ impl<Mode: TransactionKind> FileHandlers<Mode> {
         ])
     }
 
+    // Writes the first len bytes of the file of the given kind into the given writer.
+    fn write_file_prefix(
+        &self,
+        offset_kind: OffsetKind,
+        len: usize,
+        writer: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        match offset_kind {
+            OffsetKind::ThinStateDiff => self.thin_state_diff.write_prefix(len, writer),
+            OffsetKind::ContractClass => self.contract_class.write_prefix(len, writer),
+            OffsetKind::Casm => self.casm.write_prefix(len, writer),
+            OffsetKind::DeprecatedContractClass => {
+                self.deprecated_contract_class.write_prefix(len, writer)
+            }
+            OffsetKind::TransactionOutput => self.transaction_output.write_prefix(len, writer),
+            OffsetKind::Transaction => self.transaction.write_prefix(len, writer),
+        }
+    }
+
     // Returns the thin state diff at the given location or an error in case it doesn't exist.
     fn get_thin_state_diff_unchecked(
         &self,
@@ -831,7 +949,7 @@ fn open_storage_files(
         table.get(&db_transaction, &OffsetKind::ThinStateDiff)?.unwrap_or_default();
     let (thin_state_diff_writer, thin_state_diff_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("thin_state_diff.dat"),
+        db_config.path().join(OffsetKind::ThinStateDiff.file_name()),
         thin_state_diff_offset,
     )?;
 
@@ -839,19 +957,22 @@ fn open_storage_files(
         table.get(&db_transaction, &OffsetKind::ContractClass)?.unwrap_or_default();
     let (contract_class_writer, contract_class_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("contract_class.dat"),
+        db_config.path().join(OffsetKind::ContractClass.file_name()),
         contract_class_offset,
     )?;
 
     let casm_offset = table.get(&db_transaction, &OffsetKind::Casm)?.unwrap_or_default();
-    let (casm_writer, casm_reader) =
-        open_file(mmap_file_config.clone(), db_config.path().join("casm.dat"), casm_offset)?;
+    let (casm_writer, casm_reader) = open_file(
+        mmap_file_config.clone(),
+        db_config.path().join(OffsetKind::Casm.file_name()),
+        casm_offset,
+    )?;
 
     let deprecated_contract_class_offset =
         table.get(&db_transaction, &OffsetKind::DeprecatedContractClass)?.unwrap_or_default();
     let (deprecated_contract_class_writer, deprecated_contract_class_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("deprecated_contract_class.dat"),
+        db_config.path().join(OffsetKind::DeprecatedContractClass.file_name()),
         deprecated_contract_class_offset,
     )?;
 
@@ -859,14 +980,17 @@ fn open_storage_files(
         table.get(&db_transaction, &OffsetKind::TransactionOutput)?.unwrap_or_default();
     let (transaction_output_writer, transaction_output_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("transaction_output.dat"),
+        db_config.path().join(OffsetKind::TransactionOutput.file_name()),
         transaction_output_offset,
     )?;
 
     let transaction_offset =
         table.get(&db_transaction, &OffsetKind::Transaction)?.unwrap_or_default();
-    let (transaction_writer, transaction_reader) =
-        open_file(mmap_file_config, db_config.path().join("transaction.dat"), transaction_offset)?;
+    let (transaction_writer, transaction_reader) = open_file(
+        mmap_file_config,
+        db_config.path().join(OffsetKind::Transaction.file_name()),
+        transaction_offset,
+    )?;
 
     Ok((
         FileHandlers {
@@ -905,6 +1029,20 @@ pub enum OffsetKind {
     Transaction,
 }
 
+impl OffsetKind {
+    // Returns the name of the file in the storage directory.
+    pub(crate) fn file_name(&self) -> &'static str {
+        match self {
+            OffsetKind::ThinStateDiff => "thin_state_diff.dat",
+            OffsetKind::ContractClass => "contract_class.dat",
+            OffsetKind::Casm => "casm.dat",
+            OffsetKind::DeprecatedContractClass => "deprecated_contract_class.dat",
+            OffsetKind::TransactionOutput => "transaction_output.dat",
+            OffsetKind::Transaction => "transaction.dat",
+        }
+    }
+}
+
 /// A storage query. Used for benchmarking in the storage_benchmark binary.
 // TODO(dvir): add more queries (especially get casm).
 // TODO(dvir): consider move this, maybe to test_utils.
@@ -210,6 +210,10 @@ pub(crate) struct FileHandler<V: ValueSerde, Mode: TransactionKind> {
 unsafe impl<V: ValueSerde, Mode: TransactionKind> Send for FileHandler<V, Mode> {}
 unsafe impl<V: ValueSerde, Mode: TransactionKind> Sync for FileHandler<V, Mode> {}
 
+// The granularity of the freed file ranges. A multiple of the page size on all the supported
+// platforms.
+const FREED_RANGE_ALIGNMENT: usize = 1 << 20; // 1MB.
+
 impl<V: ValueSerde> FileHandler<V, RW> {
     fn grow_file_if_needed(&mut self, offset: usize) {
         let mut mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
@@ -221,6 +225,29 @@ impl<V: ValueSerde> FileHandler<V, RW> {
             mmap_file.grow();
         }
     }
+
+    /// Frees the disk space of the objects that are located before the given location. Only a
+    /// prefix whose length is a multiple of 1MB is freed, and reading from it returns zeros.
+    /// Freeing is best effort: it is supported only on Linux, and only on file systems that
+    /// support punching holes in files.
+    /// The caller must make sure that the freed objects are not read anymore.
+    pub(crate) fn free_prefix(&self, location: LocationInFile) {
+        let len = location.offset - location.offset % FREED_RANGE_ALIGNMENT;
+        if len == 0 {
+            return;
+        }
+        debug!("Freeing the first {} bytes of the file.", len);
+        #[cfg(target_os = "linux")]
+        {
+            let mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
+            // Safety: the caller guarantees that the freed objects are not borrowed.
+            if let Err(err) =
+                mmap_file.mmap.advise_range(unsafe { memmap2::Advice::remove() }, 0, len)
+            {
+                tracing::warn!("Failed to free the first {} bytes of the file: {}.", len, err);
+            }
+        }
+    }
 }
 
 impl<V: ValueSerde + Debug> Writer<V> for FileHandler<V, RW> {
@@ -284,6 +311,22 @@ impl<V: ValueSerde, Mode: TransactionKind> FileHandler<V, Mode> {
         let mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
         MMapFileStats { size: mmap_file.size, offset: mmap_file.offset }
     }
+
+    /// Writes the first len bytes of the file into the given writer. The caller must make sure
+    /// that len is not beyond the written data of the file.
+    pub(crate) fn write_prefix(
+        &self,
+        len: usize,
+        writer: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        trace!("Writing the first {} bytes of the file.", len);
+        // SAFETY: memory_ptr points to the start of the mapped memory of the file, which is at
+        // least as long as its written data, and the caller makes sure that len is within the
+        // written data. The file is append only, so these bytes aren't modified while they are
+        // borrowed.
+        let bytes = unsafe { std::slice::from_raw_parts(self.memory_ptr, len) };
+        writer.write_all(bytes)
+    }
 }
 
 // This serialization writes the offset as 6 bytes and the length as 4 bytes.
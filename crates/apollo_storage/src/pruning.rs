@@ -0,0 +1,402 @@
+//! Interface for pruning the history of the storage.
+//!
+//! The storage keeps every version of the state (contract storage, nonces, class hashes and
+//! compiled class hashes), together with the state diffs and the bodies of all the blocks. Pruning
+//! the history up to a block compacts the versions that were written before it into a single base
+//! version, which is the value at that block, and drops the state diffs and the bodies of the
+//! pruned blocks. After pruning, the state can't be queried at the pruned blocks.
+//!
+//! Import [`PruningStorageReader`] and [`PruningStorageWriter`] to prune the history using a
+//! [`StorageTxn`], or call [`StorageWriter::prune_history_outside_retention`] to prune the history
+//! according to the retention configured in [`StorageConfig`](crate::StorageConfig).
+//!
+//! # Example
+//! ```
+//! use apollo_storage::open_storage;
+//! use apollo_storage::pruning::{PruningStorageReader, PruningStorageWriter};
+//! use apollo_storage::state::StateStorageWriter;
+//! # use apollo_storage::{db::DbConfig, StorageConfig, StorageScope};
+//! # use starknet_api::block::BlockNumber;
+//! # use starknet_api::core::ChainId;
+//! use starknet_api::state::ThinStateDiff;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config =
+//! #     StorageConfig{db_config, scope: StorageScope::StateOnly, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! writer
+//!     .begin_rw_txn()?
+//!     .append_state_diff(BlockNumber(0), ThinStateDiff::default())?
+//!     .append_state_diff(BlockNumber(1), ThinStateDiff::default())?
+//!     .prune_history(BlockNumber(1))? // Prune the history of block 0.
+//!     .commit()?;
+//!
+//! let pruning_marker = reader.begin_ro_txn()?.get_pruning_marker()?;
+//! assert_eq!(pruning_marker, BlockNumber(1));
+//! # Ok::<(), apollo_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "pruning_test.rs"]
+mod pruning_test;
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionOffsetInBlock;
+use tracing::debug;
+
+use crate::body::{BodyStorageReader, TransactionIndex};
+use crate::compiled_class::CasmStorageReader;
+use crate::db::serialization::{Key as KeyTrait, ValueSerde};
+use crate::db::table_types::{DbCursor, DbCursorTrait, Table, TableType};
+use crate::db::{DbTransaction, TableHandle, TransactionKind, RW};
+use crate::mmap_file::LocationInFile;
+use crate::state::StateStorageReader;
+use crate::{MarkerKind, StorageError, StorageResult, StorageScope, StorageTxn, StorageWriter};
+
+/// The default number of latest blocks whose history is kept.
+pub const DEFAULT_HISTORY_RETENTION: u64 = 1000;
+
+// The maximal number of blocks that are pruned in a single transaction, so that pruning a long
+// history doesn't block the writer for a long time.
+const MAX_PRUNED_BLOCKS_PER_TXN: u64 = 100;
+
+/// Interface for reading data related to the pruning of the history.
+pub trait PruningStorageReader {
+    /// The pruning marker is the first block whose history wasn't pruned.
+    fn get_pruning_marker(&self) -> StorageResult<BlockNumber>;
+}
+
+/// Interface for pruning the history of the storage.
+pub trait PruningStorageWriter
+where
+    Self: Sized,
+{
+    /// Prunes the history of the blocks before the given block. Returns an error if the given
+    /// block is beyond the state marker, or beyond the body marker under the
+    /// [`FullArchive`](StorageScope::FullArchive) scope.
+    ///
+    /// The state diffs of blocks whose compiled classes weren't written yet are kept until they
+    /// are, since they are needed for advancing the compiled class marker.
+    // To enforce that no commit happen after a failure, we consume and return Self on success.
+    fn prune_history(self, up_to: BlockNumber) -> StorageResult<Self>;
+}
+
+impl<Mode: TransactionKind> PruningStorageReader for StorageTxn<'_, Mode> {
+    fn get_pruning_marker(&self) -> StorageResult<BlockNumber> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &MarkerKind::Pruning)?.unwrap_or_default())
+    }
+}
+
+impl PruningStorageWriter for StorageTxn<'_, RW> {
+    fn prune_history(self, up_to: BlockNumber) -> StorageResult<Self> {
+        let state_marker = self.get_state_marker()?;
+        if up_to > state_marker {
+            return Err(StorageError::PruneBeyondMarker { up_to, marker: state_marker });
+        }
+        if self.scope == StorageScope::FullArchive {
+            let body_marker = self.get_body_marker()?;
+            if up_to > body_marker {
+                return Err(StorageError::PruneBeyondMarker { up_to, marker: body_marker });
+            }
+        }
+        let pruning_marker = self.get_pruning_marker()?;
+        if up_to <= pruning_marker {
+            return Ok(self);
+        }
+        debug!("Pruning the history of blocks {pruning_marker} to {up_to} (exclusive).");
+
+        // Collect the keys that were written in the pruned blocks. Only their versions can be
+        // compacted.
+        let mut storage_keys = HashSet::new();
+        let mut nonce_addresses = HashSet::new();
+        let mut deployed_addresses = HashSet::new();
+        let mut declared_class_hashes = HashSet::new();
+        for block_number in pruning_marker.iter_up_to(up_to) {
+            let state_diff = self
+                .get_state_diff(block_number)?
+                .unwrap_or_else(|| panic!("Missing state diff for block {block_number}."));
+            for (address, storage_diffs) in state_diff.storage_diffs {
+                storage_keys.extend(storage_diffs.into_keys().map(|key| (address, key)));
+            }
+            // Deployed contracts get a default nonce when they are deployed.
+            nonce_addresses.extend(state_diff.nonces.into_keys());
+            nonce_addresses.extend(state_diff.deployed_contracts.keys().copied());
+            deployed_addresses.extend(state_diff.deployed_contracts.into_keys());
+            declared_class_hashes.extend(state_diff.declared_classes.into_keys());
+
+            if self.scope == StorageScope::FullArchive {
+                let transaction_hashes =
+                    self.get_block_transaction_hashes(block_number)?.unwrap_or_else(|| {
+                        panic!("Missing transaction hashes for block {block_number}.")
+                    });
+                let transaction_outputs =
+                    self.get_block_transaction_outputs(block_number)?.unwrap_or_else(|| {
+                        panic!("Missing transaction outputs for block {block_number}.")
+                    });
+                self.delete_block_transactions(
+                    block_number,
+                    &transaction_hashes,
+                    &transaction_outputs,
+                )?;
+            }
+        }
+
+        let contract_storage_table = self.open_table(&self.tables.contract_storage)?;
+        for storage_key in &storage_keys {
+            compact_versions(&self.txn, &contract_storage_table, storage_key, up_to)?;
+        }
+        let nonces_table = self.open_table(&self.tables.nonces)?;
+        for address in &nonce_addresses {
+            compact_versions(&self.txn, &nonces_table, address, up_to)?;
+        }
+        let deployed_contracts_table = self.open_table(&self.tables.deployed_contracts)?;
+        for address in &deployed_addresses {
+            compact_versions(&self.txn, &deployed_contracts_table, address, up_to)?;
+        }
+        let compiled_class_hash_table = self.open_table(&self.tables.compiled_class_hash)?;
+        for class_hash in &declared_class_hashes {
+            compact_versions(&self.txn, &compiled_class_hash_table, class_hash, up_to)?;
+        }
+
+        // Delete the state diffs that are no longer needed, including state diffs of previously
+        // pruned blocks that were kept for the compiled class marker.
+        let state_diffs_up_to = up_to.min(self.get_compiled_class_marker()?);
+        let state_diffs_table = self.open_table(&self.tables.state_diffs)?;
+        let mut cursor = state_diffs_table.cursor(&self.txn)?;
+        let mut current = cursor.lower_bound(&BlockNumber(0))?;
+        let mut pruned_state_diffs = vec![];
+        while let Some((block_number, _location)) = current {
+            if block_number >= state_diffs_up_to {
+                break;
+            }
+            pruned_state_diffs.push(block_number);
+            current = cursor.next()?;
+        }
+        for block_number in pruned_state_diffs {
+            state_diffs_table.delete(&self.txn, &block_number)?;
+        }
+
+        let markers_table = self.open_table(&self.tables.markers)?;
+        markers_table.upsert(&self.txn, &MarkerKind::Pruning, &up_to)?;
+        Ok(self)
+    }
+}
+
+impl<Mode: TransactionKind> StorageTxn<'_, Mode> {
+    // Returns the locations of the first objects in the files that weren't pruned. The objects
+    // before them were pruned.
+    fn first_unpruned_file_locations(&self) -> StorageResult<UnprunedFileLocations> {
+        let state_diffs_table = self.open_table(&self.tables.state_diffs)?;
+        let thin_state_diff = state_diffs_table
+            .cursor(&self.txn)?
+            .lower_bound(&BlockNumber(0))?
+            .map(|(_block_number, location)| location);
+
+        let mut transaction = None;
+        let mut transaction_output = None;
+        if self.scope == StorageScope::FullArchive {
+            let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+            if let Some((_tx_index, tx_metadata)) = transaction_metadata_table
+                .cursor(&self.txn)?
+                .lower_bound(&TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0)))?
+            {
+                transaction = Some(tx_metadata.tx_location);
+                transaction_output = Some(tx_metadata.tx_output_location);
+            }
+        }
+        Ok(UnprunedFileLocations { thin_state_diff, transaction, transaction_output })
+    }
+}
+
+impl StorageTxn<'_, RW> {
+    // Frees the space in the files of the objects that are located before the given locations.
+    // Must be called only after no read transaction can read the freed objects.
+    fn free_pruned_file_ranges(&self, locations: &UnprunedFileLocations) {
+        if let Some(location) = locations.thin_state_diff {
+            self.file_handlers.thin_state_diff.free_prefix(location);
+        }
+        if let Some(location) = locations.transaction {
+            self.file_handlers.transaction.free_prefix(location);
+        }
+        if let Some(location) = locations.transaction_output {
+            self.file_handlers.transaction_output.free_prefix(location);
+        }
+    }
+}
+
+impl StorageWriter {
+    /// Prunes the history of the blocks that are outside of the configured history retention, and
+    /// frees the space they took in the files. Does nothing if no retention is configured.
+    /// Prunes a limited number of blocks in each call, so it should be called periodically.
+    /// Returns the pruning marker.
+    ///
+    /// The space of the pruned objects is freed only once no read transaction that began before
+    /// they were pruned is alive, possibly in a later call.
+    pub fn prune_history_outside_retention(&mut self) -> StorageResult<BlockNumber> {
+        self.free_unread_pruned_file_ranges()?;
+        let history_retention = self.history_retention;
+        let scope = self.scope;
+        let txn = self.begin_rw_txn()?;
+        let pruning_marker = txn.get_pruning_marker()?;
+        let Some(history_retention) = history_retention else {
+            return Ok(pruning_marker);
+        };
+
+        let mut last_retained_marker = txn.get_state_marker()?;
+        if scope == StorageScope::FullArchive {
+            last_retained_marker = last_retained_marker.min(txn.get_body_marker()?);
+        }
+        let up_to = BlockNumber(last_retained_marker.0.saturating_sub(history_retention))
+            .min(BlockNumber(pruning_marker.0 + MAX_PRUNED_BLOCKS_PER_TXN));
+        if up_to <= pruning_marker {
+            return Ok(pruning_marker);
+        }
+        txn.prune_history(up_to)?.commit()?;
+
+        // The file ranges are freed only after the pruning was committed, so that a failed
+        // pruning doesn't leave the storage pointing to freed objects, and only after the read
+        // transactions that began before the commit ended.
+        let locations = self.begin_rw_txn()?.first_unpruned_file_locations()?;
+        let epoch = self.read_txn_tracker.advance_epoch();
+        self.pending_pruned_file_ranges.push((epoch, locations));
+        self.free_unread_pruned_file_ranges()?;
+        Ok(up_to)
+    }
+
+    // Frees the pruned file ranges that no live read transaction can read.
+    fn free_unread_pruned_file_ranges(&mut self) -> StorageResult<()> {
+        let oldest_read_epoch = self.read_txn_tracker.oldest_live_epoch();
+        // The ranges of each pruning contain the ranges of the earlier ones, so only the latest
+        // freeable ones are freed.
+        let n_freeable = self
+            .pending_pruned_file_ranges
+            .iter()
+            .take_while(|(epoch, _)| oldest_read_epoch.is_none_or(|oldest| oldest >= *epoch))
+            .count();
+        let Some((_epoch, locations)) =
+            self.pending_pruned_file_ranges.drain(..n_freeable).next_back()
+        else {
+            return Ok(());
+        };
+        self.begin_rw_txn()?.free_pruned_file_ranges(&locations);
+        Ok(())
+    }
+}
+
+// The locations of the first objects in the files that weren't pruned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct UnprunedFileLocations {
+    thin_state_diff: Option<LocationInFile>,
+    transaction: Option<LocationInFile>,
+    transaction_output: Option<LocationInFile>,
+}
+
+/// Tracks the live read transactions, so that the space of pruned objects is freed only after the
+/// read transactions that could still read them ended. Each read transaction is registered with
+/// the epoch in which it began, and the epoch advances whenever a pruning is committed.
+#[derive(Debug, Default)]
+pub(crate) struct ReadTxnTracker {
+    state: Mutex<ReadTxnTrackerState>,
+}
+
+#[derive(Debug, Default)]
+struct ReadTxnTrackerState {
+    epoch: u64,
+    // The number of live read transactions that began in each epoch.
+    live_txns: BTreeMap<u64, usize>,
+}
+
+impl ReadTxnTracker {
+    // Registers a read transaction that begins now. Must be called before the transaction takes
+    // its snapshot of the storage.
+    pub(crate) fn register(self: &Arc<Self>) -> ReadTxnGuard {
+        let mut state = self.state.lock().expect("Lock should not be poisoned");
+        let epoch = state.epoch;
+        *state.live_txns.entry(epoch).or_default() += 1;
+        ReadTxnGuard { tracker: self.clone(), epoch }
+    }
+
+    // Advances the epoch and returns the new one. Only the read transactions of earlier epochs
+    // may have taken their snapshot before the last commit.
+    fn advance_epoch(&self) -> u64 {
+        let mut state = self.state.lock().expect("Lock should not be poisoned");
+        state.epoch += 1;
+        state.epoch
+    }
+
+    // Returns the epoch of the oldest live read transaction, if any.
+    fn oldest_live_epoch(&self) -> Option<u64> {
+        let state = self.state.lock().expect("Lock should not be poisoned");
+        state.live_txns.keys().next().copied()
+    }
+}
+
+/// Unregisters a read transaction from the [`ReadTxnTracker`] when the transaction ends.
+#[derive(Debug)]
+pub(crate) struct ReadTxnGuard {
+    tracker: Arc<ReadTxnTracker>,
+    epoch: u64,
+}
+
+impl Drop for ReadTxnGuard {
+    fn drop(&mut self) {
+        let mut state = self.tracker.state.lock().expect("Lock should not be poisoned");
+        if let Some(n_txns) = state.live_txns.get_mut(&self.epoch) {
+            *n_txns -= 1;
+            if *n_txns == 0 {
+                state.live_txns.remove(&self.epoch);
+            }
+        }
+    }
+}
+
+// Deletes the versions of the given key that were written before the given block, except for the
+// latest of them, which holds the value of the key at that block.
+fn compact_versions<'env, K, V, T>(
+    txn: &DbTransaction<'env, RW>,
+    table: &'env TableHandle<'env, (K, BlockNumber), V, T>,
+    key: &K,
+    first_retained_block: BlockNumber,
+) -> StorageResult<()>
+where
+    K: Clone + PartialEq + Debug,
+    (K, BlockNumber): KeyTrait + Debug,
+    V: ValueSerde + Debug,
+    T: TableType,
+    TableHandle<'env, (K, BlockNumber), V, T>:
+        Table<'env, Key = (K, BlockNumber), Value = V, TableVariant = T>,
+    for<'txn> DbCursor<'txn, RW, (K, BlockNumber), V, T>:
+        DbCursorTrait<Key = (K, BlockNumber), Value = V>,
+{
+    let mut cursor = table.cursor(txn)?;
+    let mut current = cursor.lower_bound(&(key.clone(), BlockNumber(0)))?;
+    let mut latest_version = None;
+    let mut overridden_versions = vec![];
+    while let Some(((current_key, block_number), _value)) = current {
+        if current_key != *key || block_number >= first_retained_block {
+            break;
+        }
+        if let Some(overridden_version) = latest_version.replace(block_number) {
+            overridden_versions.push(overridden_version);
+        }
+        current = cursor.next()?;
+    }
+    for block_number in overridden_versions {
+        table.delete(txn, &(key.clone(), block_number))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use apollo_test_utils::get_test_body;
+use assert_matches::assert_matches;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockNumber};
+use starknet_api::core::Nonce;
+use starknet_api::state::{StateNumber, ThinStateDiff};
+use starknet_api::transaction::TransactionOffsetInBlock;
+use starknet_api::{class_hash, compiled_class_hash, contract_address, felt, storage_key};
+use starknet_types_core::felt::Felt;
+
+use crate::body::{BodyStorageReader, BodyStorageWriter, TransactionIndex};
+use crate::db::table_types::Table;
+use crate::pruning::{PruningStorageReader, PruningStorageWriter};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::{get_test_storage, get_test_storage_by_scope, TestStorageBuilder};
+use crate::{StorageError, StorageScope};
+
+#[test]
+fn prune_history_compacts_state_versions() {
+    let contract = contract_address!("0x11");
+    let class_0 = class_hash!("0x4");
+    let class_1 = class_hash!("0x5");
+    let key = storage_key!("0x1001");
+    let diff0 = ThinStateDiff {
+        deployed_contracts: IndexMap::from([(contract, class_0)]),
+        storage_diffs: IndexMap::from([(contract, IndexMap::from([(key, felt!("0x1"))]))]),
+        ..Default::default()
+    };
+    let diff1 = ThinStateDiff {
+        storage_diffs: IndexMap::from([(contract, IndexMap::from([(key, felt!("0x2"))]))]),
+        nonces: IndexMap::from([(contract, Nonce(Felt::ONE))]),
+        ..Default::default()
+    };
+    let diff2 = ThinStateDiff {
+        deployed_contracts: IndexMap::from([(contract, class_1)]),
+        storage_diffs: IndexMap::from([(contract, IndexMap::from([(key, felt!("0x3"))]))]),
+        nonces: IndexMap::from([(contract, Nonce(Felt::TWO))]),
+        declared_classes: IndexMap::from([(class_1, compiled_class_hash!(1_u8))]),
+        ..Default::default()
+    };
+
+    let ((reader, mut writer), _temp_dir) = get_test_storage_by_scope(StorageScope::StateOnly);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), diff0)
+        .unwrap()
+        .append_state_diff(BlockNumber(1), diff1)
+        .unwrap()
+        .append_state_diff(BlockNumber(2), diff2.clone())
+        .unwrap()
+        .prune_history(BlockNumber(2))
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_pruning_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_state_diff(BlockNumber(0)).unwrap(), None);
+    assert_eq!(txn.get_state_diff(BlockNumber(1)).unwrap(), None);
+    assert_eq!(txn.get_state_diff(BlockNumber(2)).unwrap(), Some(diff2));
+
+    // The versions that were overridden before the pruning marker were deleted.
+    let contract_storage_table = txn.txn.open_table(&txn.tables.contract_storage).unwrap();
+    assert_eq!(
+        contract_storage_table.get(&txn.txn, &((contract, key), BlockNumber(0))).unwrap(),
+        None
+    );
+    let nonces_table = txn.txn.open_table(&txn.tables.nonces).unwrap();
+    assert_eq!(nonces_table.get(&txn.txn, &(contract, BlockNumber(0))).unwrap(), None);
+
+    // The state at the pruning marker and after it is kept.
+    let state_reader = txn.get_state_reader().unwrap();
+    let state1 = StateNumber::right_before_block(BlockNumber(2));
+    let state2 = StateNumber::right_before_block(BlockNumber(3));
+    assert_eq!(state_reader.get_storage_at(state1, &contract, &key).unwrap(), felt!("0x2"));
+    assert_eq!(state_reader.get_storage_at(state2, &contract, &key).unwrap(), felt!("0x3"));
+    assert_eq!(state_reader.get_nonce_at(state1, &contract).unwrap(), Some(Nonce(Felt::ONE)));
+    assert_eq!(state_reader.get_nonce_at(state2, &contract).unwrap(), Some(Nonce(Felt::TWO)));
+    assert_eq!(state_reader.get_class_hash_at(state1, &contract).unwrap(), Some(class_0));
+    assert_eq!(state_reader.get_class_hash_at(state2, &contract).unwrap(), Some(class_1));
+}
+
+#[test]
+fn prune_history_beyond_marker() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    let txn = writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), ThinStateDiff::default())
+        .unwrap();
+
+    // The body of block 0 wasn't written.
+    let Err(err) = txn.prune_history(BlockNumber(1)) else {
+        panic!("Unexpected Ok.");
+    };
+    assert_matches!(
+        err,
+        StorageError::PruneBeyondMarker { up_to, marker }
+        if up_to == BlockNumber(1) && marker == BlockNumber(0)
+    );
+}
+
+#[test]
+fn prune_history_deletes_bodies() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let body0 = get_test_body(2, Some(2), None, None);
+    let body1 = get_test_body(1, Some(1), None, None);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_body(BlockNumber(0), body0.clone())
+        .unwrap()
+        .append_body(BlockNumber(1), body1.clone())
+        .unwrap()
+        .append_state_diff(BlockNumber(0), ThinStateDiff::default())
+        .unwrap()
+        .append_state_diff(BlockNumber(1), ThinStateDiff::default())
+        .unwrap()
+        .prune_history(BlockNumber(1))
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_block_transactions(BlockNumber(0)).unwrap(), None);
+    assert_eq!(txn.get_transaction_idx_by_hash(&body0.transaction_hashes[0]).unwrap(), None);
+    let events_table = txn.txn.open_table(&txn.tables.events).unwrap();
+    let tx_index = TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0));
+    let from_address = body0.transaction_outputs[0].events()[0].from_address;
+    assert_eq!(events_table.get(&txn.txn, &(from_address, tx_index)).unwrap(), None);
+
+    let BlockBody { transactions, transaction_hashes, .. } = body1;
+    assert_eq!(txn.get_block_transactions(BlockNumber(1)).unwrap(), Some(transactions));
+    assert_eq!(
+        txn.get_transaction_idx_by_hash(&transaction_hashes[0]).unwrap(),
+        Some(TransactionIndex(BlockNumber(1), TransactionOffsetInBlock(0)))
+    );
+}
+
+#[test]
+fn prune_history_outside_retention() {
+    let ((reader, mut writer), _config, _temp_dir) =
+        TestStorageBuilder::new(None).scope(StorageScope::StateOnly).history_retention(1).build();
+
+    // Nothing to prune.
+    assert_eq!(writer.prune_history_outside_retention().unwrap(), BlockNumber(0));
+
+    let mut txn = writer.begin_rw_txn().unwrap();
+    for block_number in BlockNumber(0).iter_up_to(BlockNumber(3)) {
+        txn = txn.append_state_diff(block_number, ThinStateDiff::default()).unwrap();
+    }
+    txn.commit().unwrap();
+
+    assert_eq!(writer.prune_history_outside_retention().unwrap(), BlockNumber(2));
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_pruning_marker().unwrap(), BlockNumber(2));
+    assert_eq!(txn.get_state_diff(BlockNumber(1)).unwrap(), None);
+    assert_eq!(txn.get_state_diff(BlockNumber(2)).unwrap(), Some(ThinStateDiff::default()));
+}
+
+#[test]
+fn pruned_file_ranges_are_freed_after_older_read_txns_end() {
+    let ((reader, mut writer), _config, _temp_dir) =
+        TestStorageBuilder::new(None).scope(StorageScope::StateOnly).history_retention(1).build();
+    let mut txn = writer.begin_rw_txn().unwrap();
+    for block_number in BlockNumber(0).iter_up_to(BlockNumber(4)) {
+        txn = txn.append_state_diff(block_number, ThinStateDiff::default()).unwrap();
+    }
+    txn.commit().unwrap();
+
+    // A read transaction that began before the pruning may still read the pruned state diffs.
+    let old_txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(writer.prune_history_outside_retention().unwrap(), BlockNumber(3));
+    assert_eq!(writer.pending_pruned_file_ranges.len(), 1);
+    assert_eq!(old_txn.get_state_diff(BlockNumber(0)).unwrap(), Some(ThinStateDiff::default()));
+
+    // Read transactions that began after the pruning don't hold the freeing.
+    let new_txn = reader.begin_ro_txn().unwrap();
+    drop(old_txn);
+    assert_eq!(writer.prune_history_outside_retention().unwrap(), BlockNumber(3));
+    assert!(writer.pending_pruned_file_ranges.is_empty());
+    drop(new_txn);
+}
@@ -329,6 +329,8 @@ auto_storage_serde! {
         BaseLayerBlock = 6,
         ClassManagerBlock = 7,
         CompilerBackwardCompatibility = 8,
+        EventKeysIndex = 9,
+        Pruning = 10,
     }
     pub struct MessageToL1 {
         pub to_address: EthAddress,
@@ -518,6 +520,7 @@ auto_storage_serde! {
     (ContractAddress, Nonce);
     (ContractAddress, StorageKey);
     (ContractAddress, TransactionIndex);
+    (EventKey, TransactionIndex);
     ((ContractAddress, StorageKey), BlockNumber);
     (usize, Vec<Hint>);
     (usize, Vec<String>);
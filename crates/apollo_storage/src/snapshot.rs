@@ -0,0 +1,555 @@
+//! Interface for exporting a snapshot of the storage and importing it, to bootstrap a node without
+//! syncing from genesis.
+//!
+//! A snapshot is a directory with the database file, the written prefixes of the memory mapped
+//! files and a manifest. The manifest holds the storage versions and markers of the exported
+//! storage, and the size and checksum of each file. The files can be compressed with zstd while
+//! they are streamed into the snapshot.
+//!
+//! The snapshot is consistent: the entries of the blocks below the requested block number and the
+//! files are copied as they are seen by a single read transaction.
+//!
+//! ```
+//! # use apollo_storage::{db::DbConfig, StorageConfig};
+//! use apollo_storage::header::{HeaderStorageReader, HeaderStorageWriter};
+//! use apollo_storage::open_storage;
+//! use apollo_storage::snapshot::{export_snapshot, import_snapshot};
+//! use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+//! use starknet_api::core::{ChainId, ClassHash};
+//! use starknet_api::felt;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir.join("storage"),
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config.clone())?;
+//! writer
+//!     .begin_rw_txn()?
+//!     .append_header(BlockNumber(0), &BlockHeader::default())?
+//!     .append_header(
+//!         BlockNumber(1),
+//!         &BlockHeader { block_hash: BlockHash(felt!("0x1")), ..Default::default() },
+//!     )?
+//!     .commit()?;
+//!
+//! // Export the blocks below block 1, compressed.
+//! let snapshot_dir = dir.join("snapshot");
+//! export_snapshot(&reader, &storage_config, BlockNumber(1), &snapshot_dir, true)?;
+//!
+//! let mut imported_config = storage_config.clone();
+//! imported_config.db_config.path_prefix = dir.join("imported_storage");
+//! let (imported_reader, _imported_writer) = import_snapshot(&snapshot_dir, imported_config)?;
+//! assert_eq!(imported_reader.begin_ro_txn()?.get_header_marker()?, BlockNumber(1));
+//! # Ok::<(), apollo_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "snapshot_test.rs"]
+mod snapshot_test;
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ChainId, ClassHash};
+use tracing::{debug, info};
+
+use crate::db::table_types::{DbCursorTrait, Table};
+use crate::db::RO;
+use crate::header::HeaderStorageReader;
+use crate::pruning::PruningStorageReader;
+use crate::version::{Version, VersionStorageReader};
+use crate::{
+    open_storage,
+    MarkerKind,
+    OffsetKind,
+    StorageConfig,
+    StorageError,
+    StorageReader,
+    StorageResult,
+    StorageScope,
+    StorageTxn,
+    StorageWriter,
+    STORAGE_VERSION_BLOCKS,
+    STORAGE_VERSION_STATE,
+};
+
+/// The name of the manifest file in the snapshot directory.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+const DB_FILE_NAME: &str = "mdbx.dat";
+const COMPRESSED_FILE_SUFFIX: &str = ".zst";
+// The directory inside the snapshot directory in which the exported storage is built before its
+// files are written into the snapshot.
+const STAGING_DIR_NAME: &str = "staging";
+const COPY_BUFFER_SIZE: usize = 1 << 20; // 1MB.
+const MMAP_FILES: [OffsetKind; 6] = [
+    OffsetKind::ThinStateDiff,
+    OffsetKind::ContractClass,
+    OffsetKind::Casm,
+    OffsetKind::DeprecatedContractClass,
+    OffsetKind::TransactionOutput,
+    OffsetKind::Transaction,
+];
+
+/// The description of a snapshot, stored in its manifest file.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotManifest {
+    /// The chain of the exported storage.
+    pub chain_id: ChainId,
+    /// The snapshot contains the data of the blocks below this block number.
+    pub block_number: BlockNumber,
+    /// The scope of the exported storage.
+    pub scope: StorageScope,
+    /// The state version of the exported storage.
+    pub state_version: Version,
+    /// The blocks version of the exported storage. Exists only under the FullArchive scope.
+    pub blocks_version: Option<Version>,
+    /// The markers of the exported storage by their names.
+    pub markers: BTreeMap<String, BlockNumber>,
+    /// Whether the files of the snapshot are compressed with zstd.
+    pub compressed: bool,
+    /// The files of the snapshot.
+    pub files: Vec<SnapshotFile>,
+}
+
+/// A file of a snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotFile {
+    /// The name of the file in the storage directory.
+    pub name: String,
+    /// The size of the file before compression.
+    pub size: u64,
+    /// The hex encoded SHA-256 checksum of the file before compression.
+    pub checksum: String,
+}
+
+/// Errors related to exporting and importing snapshots.
+#[allow(missing_docs)]
+#[derive(thiserror::Error, Debug)]
+pub enum SnapshotError {
+    #[error("A snapshot already exists in {0}.")]
+    SnapshotExists(PathBuf),
+    #[error("A storage already exists in {0}.")]
+    StorageExists(PathBuf),
+    #[error(
+        "Can't export the blocks below {block_number} since it is beyond the header marker \
+         {header_marker}."
+    )]
+    BlockNumberBeyondHeaderMarker { block_number: BlockNumber, header_marker: BlockNumber },
+    #[error(
+        "Can't export the blocks below {block_number} since the history below {pruning_marker} \
+         was pruned."
+    )]
+    BlockNumberBelowPruningMarker { block_number: BlockNumber, pruning_marker: BlockNumber },
+    #[error("The snapshot is of chain {snapshot_chain_id} but the storage is of chain {chain_id}.")]
+    ChainIdMismatch { snapshot_chain_id: ChainId, chain_id: ChainId },
+    #[error(
+        "The snapshot is of the {snapshot_scope:?} scope but the storage is of the {scope:?} \
+         scope."
+    )]
+    ScopeMismatch { snapshot_scope: StorageScope, scope: StorageScope },
+    #[error(
+        "The snapshot storage version (state = {state_version}, blocks = {blocks_version:?}) is \
+         inconsistent with the crate storage version."
+    )]
+    VersionMismatch { state_version: Version, blocks_version: Option<Version> },
+    #[error("The snapshot files {found:?} don't match the expected files {expected:?}.")]
+    UnexpectedFiles { expected: Vec<String>, found: Vec<String> },
+    #[error("The snapshot file {file_name} doesn't match its size or checksum in the manifest.")]
+    ChecksumMismatch { file_name: String },
+    #[error(
+        "The markers of the imported storage {found:?} don't match the markers in the manifest \
+         {expected:?}."
+    )]
+    MarkersMismatch {
+        expected: BTreeMap<String, BlockNumber>,
+        found: BTreeMap<String, BlockNumber>,
+    },
+}
+
+/// Exports a snapshot of the blocks below `block_number` into `snapshot_dir`.
+/// `storage_config` is the configuration the storage of `reader` was opened with. If `compress` is
+/// set, the files of the snapshot are compressed with zstd.
+pub fn export_snapshot(
+    reader: &StorageReader,
+    storage_config: &StorageConfig,
+    block_number: BlockNumber,
+    snapshot_dir: &Path,
+    compress: bool,
+) -> StorageResult<SnapshotManifest> {
+    let manifest_path = snapshot_dir.join(MANIFEST_FILE_NAME);
+    if manifest_path.exists() {
+        return Err(SnapshotError::SnapshotExists(snapshot_dir.to_path_buf()).into());
+    }
+    info!(
+        "Exporting a snapshot of the blocks below {block_number} into {}.",
+        snapshot_dir.display()
+    );
+
+    let mut staging_config = storage_config.clone();
+    staging_config.db_config.path_prefix = snapshot_dir.join(STAGING_DIR_NAME);
+    staging_config.db_config.enforce_file_exists = false;
+    staging_config.history_retention = None;
+    let staging_path = staging_config.db_config.path();
+    fs::create_dir_all(&staging_path)?;
+
+    let txn = reader.begin_ro_txn()?;
+    validate_block_number(&txn, block_number)?;
+    let (state_version, blocks_version, markers) = {
+        let (staging_reader, mut staging_writer) = open_storage(staging_config)?;
+        copy_tables(&txn, &mut staging_writer, block_number)?;
+        get_versions_and_markers(&staging_reader)?
+    };
+    let file_offsets = copy_files(&txn, &staging_path)?;
+    drop(txn);
+
+    let db_file_len = fs::metadata(staging_path.join(DB_FILE_NAME))?.len();
+    let mut files =
+        vec![export_file(&staging_path, DB_FILE_NAME, db_file_len, snapshot_dir, compress)?];
+    for (offset_kind, len) in file_offsets {
+        let len = u64::try_from(len).expect("usize should fit in u64");
+        files.push(export_file(
+            &staging_path,
+            offset_kind.file_name(),
+            len,
+            snapshot_dir,
+            compress,
+        )?);
+    }
+    fs::remove_dir_all(snapshot_dir.join(STAGING_DIR_NAME))?;
+
+    let manifest = SnapshotManifest {
+        chain_id: storage_config.db_config.chain_id.clone(),
+        block_number,
+        scope: storage_config.scope,
+        state_version,
+        blocks_version,
+        markers,
+        compressed: compress,
+        files,
+    };
+    let mut manifest_file = BufWriter::new(File::create(manifest_path)?);
+    serde_json::to_writer_pretty(&mut manifest_file, &manifest)?;
+    manifest_file.flush()?;
+    info!("Exported a snapshot of the blocks below {block_number}.");
+    Ok(manifest)
+}
+
+/// Imports the snapshot in `snapshot_dir` into a new storage described by `storage_config`, and
+/// opens it. The files are validated against the manifest while they are copied, and the opened
+/// storage is validated against the markers in the manifest.
+pub fn import_snapshot(
+    snapshot_dir: &Path,
+    storage_config: StorageConfig,
+) -> StorageResult<(StorageReader, StorageWriter)> {
+    let manifest = read_manifest(snapshot_dir)?;
+    validate_manifest(&manifest, &storage_config)?;
+    let storage_path = storage_config.db_config.path();
+    if storage_path.join(DB_FILE_NAME).exists() {
+        return Err(SnapshotError::StorageExists(storage_path).into());
+    }
+    info!(
+        "Importing a snapshot of the blocks below {} into {}.",
+        manifest.block_number,
+        storage_path.display()
+    );
+
+    fs::create_dir_all(&storage_path)?;
+    for file in &manifest.files {
+        import_file(snapshot_dir, file, manifest.compressed, &storage_path)?;
+    }
+
+    let (reader, writer) = open_storage(storage_config)?;
+    let (_state_version, _blocks_version, markers) = get_versions_and_markers(&reader)?;
+    if markers != manifest.markers {
+        return Err(
+            SnapshotError::MarkersMismatch { expected: manifest.markers, found: markers }.into()
+        );
+    }
+    info!("Imported a snapshot of the blocks below {}.", manifest.block_number);
+    Ok((reader, writer))
+}
+
+/// Reads the manifest of the snapshot in the given directory.
+pub fn read_manifest(snapshot_dir: &Path) -> StorageResult<SnapshotManifest> {
+    let manifest_file = File::open(snapshot_dir.join(MANIFEST_FILE_NAME))?;
+    Ok(serde_json::from_reader(BufReader::new(manifest_file))?)
+}
+
+fn validate_manifest(
+    manifest: &SnapshotManifest,
+    storage_config: &StorageConfig,
+) -> Result<(), SnapshotError> {
+    if manifest.chain_id != storage_config.db_config.chain_id {
+        return Err(SnapshotError::ChainIdMismatch {
+            snapshot_chain_id: manifest.chain_id.clone(),
+            chain_id: storage_config.db_config.chain_id.clone(),
+        });
+    }
+    if manifest.scope != storage_config.scope {
+        return Err(SnapshotError::ScopeMismatch {
+            snapshot_scope: manifest.scope,
+            scope: storage_config.scope,
+        });
+    }
+    let expected_blocks_version =
+        (manifest.scope == StorageScope::FullArchive).then_some(STORAGE_VERSION_BLOCKS);
+    if manifest.state_version != STORAGE_VERSION_STATE
+        || manifest.blocks_version != expected_blocks_version
+    {
+        return Err(SnapshotError::VersionMismatch {
+            state_version: manifest.state_version.clone(),
+            blocks_version: manifest.blocks_version.clone(),
+        });
+    }
+
+    // Also makes sure that the files are written only into the storage directory.
+    let mut expected = std::iter::once(DB_FILE_NAME)
+        .chain(MMAP_FILES.iter().map(OffsetKind::file_name))
+        .map(String::from)
+        .collect::<Vec<_>>();
+    let mut found = manifest.files.iter().map(|file| file.name.clone()).collect::<Vec<_>>();
+    expected.sort();
+    found.sort();
+    if expected != found {
+        return Err(SnapshotError::UnexpectedFiles { expected, found });
+    }
+    Ok(())
+}
+
+// Copies the entries of the given table that the predicate keeps from the source transaction into
+// the target transaction.
+macro_rules! copy_table_entries {
+    (
+        $source:expr, $target:expr, $table:ident, | $key:pat_param, $value:pat_param | $keep:expr
+    ) => {{
+        let source_table = $source.open_table(&$source.tables.$table)?;
+        let target_table = $target.open_table(&$target.tables.$table)?;
+        let mut cursor = source_table.cursor(&$source.txn)?;
+        while let Some((key, value)) = cursor.next()? {
+            let keep = {
+                let ($key, $value) = (&key, &value);
+                $keep
+            };
+            if keep {
+                target_table.upsert(&$target.txn, &key, &value)?;
+            }
+        }
+    }};
+}
+
+fn validate_block_number(txn: &StorageTxn<'_, RO>, block_number: BlockNumber) -> StorageResult<()> {
+    let header_marker = txn.get_header_marker()?;
+    if block_number > header_marker {
+        return Err(
+            SnapshotError::BlockNumberBeyondHeaderMarker { block_number, header_marker }.into()
+        );
+    }
+    let pruning_marker = txn.get_pruning_marker()?;
+    if block_number < pruning_marker {
+        return Err(
+            SnapshotError::BlockNumberBelowPruningMarker { block_number, pruning_marker }.into()
+        );
+    }
+    Ok(())
+}
+
+// Copies the entries of the blocks below the given block number from the source transaction into
+// the storage of the given writer, and lowers the markers of the copy to the block number.
+fn copy_tables(
+    source: &StorageTxn<'_, RO>,
+    writer: &mut StorageWriter,
+    block_number: BlockNumber,
+) -> StorageResult<()> {
+    debug!("Copying the storage tables of the blocks below {block_number}.");
+    let target = writer.begin_rw_txn()?;
+
+    copy_table_entries!(source, target, headers, |key, _| *key < block_number);
+    copy_table_entries!(source, target, block_hash_to_number, |_, value| *value < block_number);
+    copy_table_entries!(source, target, block_signatures, |key, _| *key < block_number);
+    copy_table_entries!(source, target, starknet_version, |key, _| *key < block_number);
+    if source.scope == StorageScope::FullArchive {
+        copy_table_entries!(source, target, transaction_metadata, |key, _| key.0 < block_number);
+        copy_table_entries!(source, target, transaction_hash_to_idx, |_, value| {
+            value.0 < block_number
+        });
+        copy_table_entries!(source, target, events, |(_, index), _| index.0 < block_number);
+        copy_table_entries!(source, target, event_keys, |(_, index), _| index.0 < block_number);
+    }
+
+    copy_table_entries!(source, target, state_diffs, |key, _| *key < block_number);
+    copy_table_entries!(source, target, contract_storage, |(_, key_block), _| {
+        *key_block < block_number
+    });
+    copy_table_entries!(source, target, nonces, |(_, key_block), _| *key_block < block_number);
+    copy_table_entries!(source, target, deployed_contracts, |(_, key_block), _| {
+        *key_block < block_number
+    });
+    copy_table_entries!(source, target, compiled_class_hash, |(_, key_block), _| {
+        *key_block < block_number
+    });
+    copy_table_entries!(source, target, declared_classes_block, |_, value| *value < block_number);
+    copy_table_entries!(source, target, deprecated_declared_classes_block, |_, value| {
+        *value < block_number
+    });
+    copy_table_entries!(source, target, deprecated_declared_classes, |_, class| {
+        class.block_number < block_number
+    });
+    // The classes and their compiled classes are kept by the block the classes were declared in.
+    let declared_classes_block_table = source.open_table(&source.tables.declared_classes_block)?;
+    let declared_below_block_number = |class_hash: &ClassHash| -> StorageResult<bool> {
+        Ok(declared_classes_block_table
+            .get(&source.txn, class_hash)?
+            .is_some_and(|declared_block_number| declared_block_number < block_number))
+    };
+    copy_table_entries!(source, target, declared_classes, |key, _| {
+        declared_below_block_number(key)?
+    });
+    copy_table_entries!(source, target, casms, |key, _| declared_below_block_number(key)?);
+    copy_table_entries!(source, target, stateless_compiled_class_hash_v2, |key, _| {
+        declared_below_block_number(key)?
+    });
+
+    // The files are copied up to their offsets in the source transaction, so the offsets are kept
+    // as they are. The objects of the blocks from the block number and on in the files aren't
+    // referenced by the copied entries.
+    copy_table_entries!(source, target, file_offsets, |_, _| true);
+    // The marker of the compiler backward compatibility doesn't describe the progress of the
+    // storage, so it isn't lowered.
+    let markers_table = source.open_table(&source.tables.markers)?;
+    let target_markers_table = target.open_table(&target.tables.markers)?;
+    let mut cursor = markers_table.cursor(&source.txn)?;
+    while let Some((marker_kind, marker)) = cursor.next()? {
+        let marker = match marker_kind {
+            MarkerKind::CompilerBackwardCompatibility => marker,
+            _ => marker.min(block_number),
+        };
+        target_markers_table.upsert(&target.txn, &marker_kind, &marker)?;
+    }
+
+    target.commit()
+}
+
+// Writes the data of the files, as seen by the given transaction, into the given directory. The
+// files are append only, so their prefixes up to the offsets in the transaction are consistent with
+// its tables. Returns these offsets.
+fn copy_files(txn: &StorageTxn<'_, RO>, path: &Path) -> StorageResult<Vec<(OffsetKind, usize)>> {
+    debug!("Copying the storage files.");
+    let file_offsets_table = txn.open_table(&txn.tables.file_offsets)?;
+    let mut file_offsets = Vec::new();
+    for offset_kind in MMAP_FILES {
+        let len = file_offsets_table.get(&txn.txn, &offset_kind)?.unwrap_or_default();
+        let mut file = BufWriter::new(File::create(path.join(offset_kind.file_name()))?);
+        txn.file_handlers.write_file_prefix(offset_kind, len, &mut file)?;
+        file.flush()?;
+        file_offsets.push((offset_kind, len));
+    }
+    Ok(file_offsets)
+}
+
+// Returns the state version, the blocks version and the markers of the storage.
+fn get_versions_and_markers(
+    reader: &StorageReader,
+) -> StorageResult<(Version, Option<Version>, BTreeMap<String, BlockNumber>)> {
+    let txn = reader.begin_ro_txn()?;
+    let state_version = txn.get_state_version()?.ok_or(StorageError::DBInconsistency {
+        msg: "The state version of the storage is missing.".to_string(),
+    })?;
+    let blocks_version = txn.get_blocks_version()?;
+
+    let markers_table = txn.open_table(&txn.tables.markers)?;
+    let mut cursor = markers_table.cursor(&txn.txn)?;
+    let mut markers = BTreeMap::new();
+    while let Some((marker_kind, marker)) = cursor.next()? {
+        markers.insert(format!("{marker_kind:?}"), marker);
+    }
+    Ok((state_version, blocks_version, markers))
+}
+
+fn snapshot_file_name(name: &str, compressed: bool) -> String {
+    if compressed { format!("{name}{COMPRESSED_FILE_SUFFIX}") } else { name.to_string() }
+}
+
+// Writes the first len bytes of the file with the given name in the storage directory into the
+// snapshot directory.
+fn export_file(
+    storage_path: &Path,
+    name: &str,
+    len: u64,
+    snapshot_dir: &Path,
+    compress: bool,
+) -> StorageResult<SnapshotFile> {
+    debug!("Exporting the file {name}.");
+    let mut source = BufReader::new(File::open(storage_path.join(name))?).take(len);
+    let destination =
+        BufWriter::new(File::create(snapshot_dir.join(snapshot_file_name(name, compress)))?);
+    let (size, checksum) = if compress {
+        let mut encoder = zstd::stream::Encoder::new(destination, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+        let size_and_checksum = copy_and_hash(&mut source, &mut encoder)?;
+        encoder.finish()?.flush()?;
+        size_and_checksum
+    } else {
+        let mut destination = destination;
+        let size_and_checksum = copy_and_hash(&mut source, &mut destination)?;
+        destination.flush()?;
+        size_and_checksum
+    };
+    Ok(SnapshotFile { name: name.to_string(), size, checksum })
+}
+
+// Writes the given snapshot file into the storage directory, and validates it against the
+// manifest.
+fn import_file(
+    snapshot_dir: &Path,
+    file: &SnapshotFile,
+    compressed: bool,
+    storage_path: &Path,
+) -> StorageResult<()> {
+    debug!("Importing the file {}.", file.name);
+    let source = File::open(snapshot_dir.join(snapshot_file_name(&file.name, compressed)))?;
+    let mut destination = BufWriter::new(File::create(storage_path.join(&file.name))?);
+    let (size, checksum) = if compressed {
+        copy_and_hash(&mut zstd::stream::Decoder::new(source)?, &mut destination)?
+    } else {
+        copy_and_hash(&mut BufReader::new(source), &mut destination)?
+    };
+    destination.flush()?;
+    if size != file.size || checksum != file.checksum {
+        return Err(SnapshotError::ChecksumMismatch { file_name: file.name.clone() }.into());
+    }
+    Ok(())
+}
+
+// Copies the source into the destination, and returns the size and the checksum of the copied
+// data.
+fn copy_and_hash(
+    source: &mut impl Read,
+    destination: &mut impl Write,
+) -> io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+    let mut size = 0;
+    loop {
+        let len = match source.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        hasher.update(&buffer[..len]);
+        destination.write_all(&buffer[..len])?;
+        size += u64::try_from(len).expect("usize should fit in u64");
+    }
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
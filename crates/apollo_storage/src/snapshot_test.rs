@@ -0,0 +1,148 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use apollo_test_utils::get_test_body;
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockNumber};
+use starknet_api::core::ChainId;
+use starknet_api::felt;
+use starknet_api::state::ThinStateDiff;
+use tempfile::tempdir;
+
+use crate::body::{BodyStorageReader, BodyStorageWriter};
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::snapshot::{
+    export_snapshot,
+    import_snapshot,
+    read_manifest,
+    SnapshotError,
+    MANIFEST_FILE_NAME,
+};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::TestStorageBuilder;
+use crate::{StorageConfig, StorageError, StorageReader, StorageWriter};
+
+const N_BLOCKS: u64 = 3;
+
+// Returns a storage with N_BLOCKS blocks and its config.
+fn get_test_storage_with_blocks() -> (StorageReader, StorageWriter, StorageConfig, tempfile::TempDir)
+{
+    let ((reader, mut writer), config, temp_dir) = TestStorageBuilder::new(None).build();
+    let mut txn = writer.begin_rw_txn().unwrap();
+    for block_number in BlockNumber(0).iter_up_to(BlockNumber(N_BLOCKS)) {
+        let header =
+            BlockHeader { block_hash: BlockHash(felt!(block_number.0)), ..Default::default() };
+        txn = txn
+            .append_header(block_number, &header)
+            .unwrap()
+            .append_body(block_number, get_test_body(2, Some(1), None, None))
+            .unwrap()
+            .append_state_diff(block_number, ThinStateDiff::default())
+            .unwrap();
+    }
+    txn.commit().unwrap();
+    (reader, writer, config, temp_dir.unwrap())
+}
+
+#[test]
+fn export_and_import_snapshot() {
+    let (reader, _writer, config, _temp_dir) = get_test_storage_with_blocks();
+    let snapshot_dir = tempdir().unwrap();
+    let manifest =
+        export_snapshot(&reader, &config, BlockNumber(2), snapshot_dir.path(), true).unwrap();
+    assert_eq!(read_manifest(snapshot_dir.path()).unwrap(), manifest);
+    assert_eq!(manifest.block_number, BlockNumber(2));
+    assert_eq!(manifest.markers["Header"], BlockNumber(2));
+    assert_eq!(manifest.markers["Body"], BlockNumber(2));
+    assert_eq!(manifest.markers["State"], BlockNumber(2));
+
+    let imported_dir = tempdir().unwrap();
+    let mut imported_config = config.clone();
+    imported_config.db_config.path_prefix = imported_dir.path().to_path_buf();
+    let (imported_reader, _imported_writer) =
+        import_snapshot(snapshot_dir.path(), imported_config).unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    let imported_txn = imported_reader.begin_ro_txn().unwrap();
+    assert_eq!(imported_txn.get_header_marker().unwrap(), BlockNumber(2));
+    assert_eq!(imported_txn.get_block_header(BlockNumber(2)).unwrap(), None);
+    // The entries of the blocks from the exported block number aren't copied.
+    for transaction_hash in txn.get_block_transaction_hashes(BlockNumber(2)).unwrap().unwrap() {
+        assert_eq!(imported_txn.get_transaction_idx_by_hash(&transaction_hash).unwrap(), None);
+    }
+    for block_number in BlockNumber(0).iter_up_to(BlockNumber(2)) {
+        assert_eq!(
+            imported_txn.get_block_header(block_number).unwrap(),
+            txn.get_block_header(block_number).unwrap()
+        );
+        assert_eq!(
+            imported_txn.get_block_transactions(block_number).unwrap(),
+            txn.get_block_transactions(block_number).unwrap()
+        );
+        assert_eq!(
+            imported_txn.get_state_diff(block_number).unwrap(),
+            txn.get_state_diff(block_number).unwrap()
+        );
+    }
+}
+
+#[test]
+fn export_snapshot_beyond_header_marker() {
+    let (reader, _writer, config, _temp_dir) = get_test_storage_with_blocks();
+    let snapshot_dir = tempdir().unwrap();
+    let result =
+        export_snapshot(&reader, &config, BlockNumber(N_BLOCKS + 1), snapshot_dir.path(), false);
+    assert_matches!(
+        result,
+        Err(StorageError::SnapshotError(SnapshotError::BlockNumberBeyondHeaderMarker {
+            block_number,
+            header_marker,
+        })) if block_number == BlockNumber(N_BLOCKS + 1) && header_marker == BlockNumber(N_BLOCKS)
+    );
+}
+
+#[test]
+fn import_corrupted_snapshot() {
+    let (reader, _writer, config, _temp_dir) = get_test_storage_with_blocks();
+    let snapshot_dir = tempdir().unwrap();
+    let manifest =
+        export_snapshot(&reader, &config, BlockNumber(N_BLOCKS), snapshot_dir.path(), false)
+            .unwrap();
+    let corrupted_file = &manifest.files.last().unwrap().name;
+    OpenOptions::new()
+        .append(true)
+        .open(snapshot_dir.path().join(corrupted_file))
+        .unwrap()
+        .write_all(&[1])
+        .unwrap();
+
+    let imported_dir = tempdir().unwrap();
+    let mut imported_config = config.clone();
+    imported_config.db_config.path_prefix = imported_dir.path().to_path_buf();
+    let Err(err) = import_snapshot(snapshot_dir.path(), imported_config) else {
+        panic!("Unexpected Ok.");
+    };
+    assert_matches!(
+        err,
+        StorageError::SnapshotError(SnapshotError::ChecksumMismatch { file_name })
+        if &file_name == corrupted_file
+    );
+}
+
+#[test]
+fn import_snapshot_of_another_chain() {
+    let (reader, _writer, config, _temp_dir) = get_test_storage_with_blocks();
+    let snapshot_dir = tempdir().unwrap();
+    export_snapshot(&reader, &config, BlockNumber(N_BLOCKS), snapshot_dir.path(), false).unwrap();
+    assert!(snapshot_dir.path().join(MANIFEST_FILE_NAME).exists());
+
+    let imported_dir = tempdir().unwrap();
+    let mut imported_config = config.clone();
+    imported_config.db_config.path_prefix = imported_dir.path().to_path_buf();
+    imported_config.db_config.chain_id = ChainId::Other("other_chain".to_owned());
+    let Err(err) = import_snapshot(snapshot_dir.path(), imported_config) else {
+        panic!("Unexpected Ok.");
+    };
+    assert_matches!(err, StorageError::SnapshotError(SnapshotError::ChainIdMismatch { .. }));
+}
@@ -24,6 +24,7 @@ fn build_storage_config(storage_scope: StorageScope, path_prefix: PathBuf) -> St
         },
         scope: storage_scope,
         mmap_file_config: get_mmap_file_test_config(),
+        history_retention: None,
     }
 }
 
@@ -107,6 +108,12 @@ impl TestStorageBuilder {
         self
     }
 
+    /// Sets the number of latest blocks whose history is kept.
+    pub fn history_retention(mut self, history_retention: u64) -> Self {
+        self.config.history_retention = Some(history_retention);
+        self
+    }
+
     /// Sets the chain id.
     pub fn chain_id(mut self, chain_id: ChainId) -> Self {
         self.config.db_config.chain_id = chain_id;
@@ -3,7 +3,9 @@ use pretty_assertions::assert_eq;
 use rand::Rng;
 
 use crate::db::table_types::Table;
+use crate::db::DbError;
 use crate::test_utils::{
+    get_test_config,
     get_test_storage,
     get_test_storage_by_scope,
     get_test_storage_with_config_by_scope,
@@ -18,6 +20,8 @@ use crate::version::{
 };
 use crate::{
     open_storage,
+    open_storage_internal,
+    open_storage_read_only,
     set_version_if_needed,
     verify_storage_version,
     StorageError,
@@ -115,6 +119,55 @@ fn version_migration() {
     assert_eq!(version_blocks.unwrap(), STORAGE_VERSION_BLOCKS);
 }
 
+#[test]
+fn open_storage_read_only_good_flow() {
+    let ((reader, writer), config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    drop(reader);
+    drop(writer);
+
+    let reader = open_storage_read_only(config).unwrap();
+    let version_blocks = reader.begin_ro_txn().unwrap().get_blocks_version().unwrap();
+    assert_eq!(version_blocks.unwrap(), STORAGE_VERSION_BLOCKS);
+}
+
+#[test]
+fn open_storage_read_only_does_not_migrate() {
+    let ((reader, mut writer), config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    let lower_minor_version =
+        Version { major: STORAGE_VERSION_BLOCKS.major, minor: STORAGE_VERSION_BLOCKS.minor - 1 };
+    change_storage_version(&mut writer, VERSION_BLOCKS_KEY, &lower_minor_version);
+    drop(reader);
+    drop(writer);
+
+    let Err(err) = open_storage_read_only(config.clone()) else {
+        panic!("Unexpected Ok.");
+    };
+    assert_matches!(
+        err,
+        StorageError::StorageVersionInconsistency(StorageVersionError::InconsistentStorageVersion {
+            crate_version,
+            storage_version
+        })
+        if crate_version == STORAGE_VERSION_BLOCKS && storage_version == lower_minor_version
+    );
+    let (reader, _) = open_storage_internal(config, true).unwrap();
+    let version_blocks = reader.begin_ro_txn().unwrap().get_blocks_version().unwrap();
+    assert_eq!(version_blocks.unwrap(), lower_minor_version);
+}
+
+#[test]
+fn open_storage_read_only_does_not_create_storage() {
+    let (config, _temp_dir) = get_test_config(None);
+
+    let Err(err) = open_storage_read_only(config.clone()) else {
+        panic!("Unexpected Ok.");
+    };
+    assert_matches!(err, StorageError::InnerError(DbError::FileDoesNotExist(_)));
+    assert!(!config.db_config.path().exists());
+}
+
 #[test]
 fn open_storage_full_archive_different_state_major_versions() {
     let ((reader, mut writer), config, _temp_dir) =
@@ -5,6 +5,7 @@ use std::time::Instant;
 use apollo_compilation_utils::class_utils::into_contract_class_for_compilation;
 use apollo_compilation_utils::errors::CompilationUtilError;
 use apollo_compile_to_native::compiler::SierraToNativeCompiler;
+use apollo_compile_to_native::disk_cache::NativeDiskCache;
 #[cfg(any(feature = "testing", test))]
 use cached::Cached;
 use log;
@@ -54,6 +55,8 @@ pub struct NativeClassManager {
     sender: Option<SyncSender<CompilationRequest>>,
     /// The sierra-to-native compiler.
     compiler: Option<Arc<SierraToNativeCompiler>>,
+    /// The on-disk cache of compiled classes. Set to `None` if it's disabled.
+    disk_cache: Option<Arc<NativeDiskCache>>,
 }
 
 impl NativeClassManager {
@@ -75,10 +78,21 @@ impl NativeClassManager {
                 cache,
                 sender: None,
                 compiler: None,
+                disk_cache: None,
             };
         }
 
         let compiler_config = config.native_compiler_config.clone();
+        let disk_cache = compiler_config.cache_dir.clone().map(|cache_dir| {
+            Arc::new(
+                NativeDiskCache::new(
+                    cache_dir,
+                    compiler_config.max_cache_size,
+                    compiler_config.optimization_level,
+                )
+                .expect("Failed to create the native disk cache directory."),
+            )
+        });
         let compiler = Arc::new(SierraToNativeCompiler::new(compiler_config));
         if cairo_native_run_config.wait_on_native_compilation {
             // Compilation requests are processed synchronously. No need to start the worker.
@@ -87,6 +101,7 @@ impl NativeClassManager {
                 cache,
                 sender: None,
                 compiler: Some(compiler),
+                disk_cache,
             };
         }
 
@@ -94,18 +109,26 @@ impl NativeClassManager {
 
         std::thread::spawn({
             let cache = cache.clone();
+            let disk_cache = disk_cache.clone();
             move || {
                 run_compilation_worker(
                     cache,
                     receiver,
                     compiler,
+                    disk_cache,
                     cairo_native_run_config.panic_on_compilation_failure,
                 )
             }
         });
 
         // TODO(AVIV): Add private constructor with default values.
-        NativeClassManager { cairo_native_run_config, cache, sender: Some(sender), compiler: None }
+        NativeClassManager {
+            cairo_native_run_config,
+            cache,
+            sender: Some(sender),
+            compiler: None,
+            disk_cache,
+        }
     }
 
     /// Returns the runnable compiled class for the given class hash, if it exists in cache.
@@ -151,6 +174,7 @@ impl NativeClassManager {
                     process_compilation_request(
                         self.cache.clone(),
                         compiler.clone(),
+                        self.disk_cache.clone(),
                         (class_hash, sierra_contract_class, compiled_class_v1),
                         self.cairo_native_run_config.panic_on_compilation_failure,
                     )
@@ -237,6 +261,7 @@ fn run_compilation_worker(
     cache: RawClassCache,
     receiver: Receiver<CompilationRequest>,
     compiler: Arc<SierraToNativeCompiler>,
+    disk_cache: Option<Arc<NativeDiskCache>>,
     panic_on_compilation_failure: bool,
 ) {
     log::info!("Compilation worker started.");
@@ -244,6 +269,7 @@ fn run_compilation_worker(
         process_compilation_request(
             cache.clone(),
             compiler.clone(),
+            disk_cache.clone(),
             compilation_request,
             panic_on_compilation_failure,
         )
@@ -252,10 +278,12 @@ fn run_compilation_worker(
     log::info!("Compilation worker terminated.");
 }
 
-/// Processes a compilation request and caches the result.
+/// Processes a compilation request and caches the result. If the disk cache is enabled, the class
+/// is loaded from it instead of compiled when possible, and stored in it otherwise.
 fn process_compilation_request(
     cache: RawClassCache,
     compiler: Arc<SierraToNativeCompiler>,
+    disk_cache: Option<Arc<NativeDiskCache>>,
     compilation_request: CompilationRequest,
     panic_on_compilation_failure: bool,
 ) -> Result<(), CompilationUtilError> {
@@ -264,9 +292,20 @@ fn process_compilation_request(
         // The contract class is already compiled to native - skip the compilation.
         return Ok(());
     }
-    let sierra_for_compilation = into_contract_class_for_compilation(sierra.as_ref());
     let start = Instant::now();
-    let compilation_result = compiler.compile(sierra_for_compilation);
+    let compilation_result =
+        match disk_cache.as_ref().and_then(|disk_cache| disk_cache.get(class_hash)) {
+            Some(executor) => Ok(executor),
+            None => {
+                let sierra_for_compilation = into_contract_class_for_compilation(sierra.as_ref());
+                match disk_cache {
+                    Some(disk_cache) => {
+                        disk_cache.compile(&compiler, class_hash, sierra_for_compilation)
+                    }
+                    None => compiler.compile(sierra_for_compilation),
+                }
+            }
+        };
     let duration = start.elapsed();
     log::debug!(
         "Compiling to native contract with class hash: {}. Duration: {:.3} seconds",
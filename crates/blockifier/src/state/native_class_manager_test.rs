@@ -163,6 +163,7 @@ fn test_send_compilation_request_channel_disconnected() {
         cache: RawClassCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
         sender: Some(sender),
         compiler: None,
+        disk_cache: None,
     };
     // Disconnect the channel by dropping the receiver.
     drop(receiver);
@@ -213,6 +214,7 @@ fn test_process_compilation_request(
     let res = process_compilation_request(
         manager.clone().cache,
         manager.clone().compiler.unwrap(),
+        None,
         request.clone(),
         manager.cairo_native_run_config.panic_on_compilation_failure,
     );
@@ -240,6 +240,8 @@ impl From<PySierraCompilationConfig> for SierraCompilationConfig {
             max_cpu_time: Some(py_sierra_compilation_config.max_cpu_time),
             max_memory_usage: Some(py_sierra_compilation_config.max_memory_usage),
             optimization_level: py_sierra_compilation_config.optimization_level,
+            // The on-disk cache isn't configurable from Python.
+            ..Default::default()
         }
     }
 }
@@ -20,6 +20,8 @@ pub mod monitored_base_layer;
 
 pub(crate) mod eth_events;
 
+#[cfg(any(feature = "testing", test))]
+pub mod simulated_base_layer;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
 
@@ -82,7 +84,7 @@ pub trait BaseLayerContract {
 }
 
 /// Reference to an L1 block, extend as needed.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct L1BlockReference {
     pub number: L1BlockNumber,
     pub hash: L1BlockHash,
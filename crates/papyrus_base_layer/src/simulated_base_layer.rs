@@ -0,0 +1,369 @@
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use alloy::primitives::FixedBytes;
+use async_trait::async_trait;
+use starknet_api::block::{BlockHashAndNumber, BlockTimestamp};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::L1HandlerTransaction;
+use url::Url;
+
+use crate::constants::{
+    EventIdentifier,
+    CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+};
+use crate::{
+    BaseLayerContract,
+    EventData,
+    L1BlockHash,
+    L1BlockHeader,
+    L1BlockNumber,
+    L1BlockReference,
+    L1Event,
+};
+
+#[cfg(test)]
+#[path = "simulated_base_layer_test.rs"]
+mod simulated_base_layer_test;
+
+pub type SimulatedBaseLayerResult<T> = Result<T, SimulatedBaseLayerError>;
+
+/// Parameters of the blocks produced by the simulated L1 chain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimulatedBaseLayerConfig {
+    /// The timestamp of the genesis block.
+    pub genesis_timestamp: BlockTimestamp,
+    /// The difference between the timestamps of consecutive blocks.
+    pub block_time_seconds: u64,
+    /// The base fee of the genesis block, used until changed by `set_gas_prices`.
+    pub base_fee_per_gas: u128,
+    /// The blob fee of the genesis block, used until changed by `set_gas_prices`.
+    pub blob_fee: u128,
+}
+
+impl Default for SimulatedBaseLayerConfig {
+    fn default() -> Self {
+        Self {
+            genesis_timestamp: BlockTimestamp(1_700_000_000),
+            block_time_seconds: 12,
+            base_fee_per_gas: 10_000_000_000,
+            blob_fee: 1,
+        }
+    }
+}
+
+/// A deterministic, in-process L1 chain that plays the role of the Starknet core contract.
+///
+/// Blocks are only produced when explicitly mined. Events emitted and Starknet blocks proved are
+/// included in the next mined block. Clones share the same chain, so a test can keep a handle to
+/// drive the chain while a component under test owns another one.
+#[derive(Clone, Debug)]
+pub struct SimulatedBaseLayer {
+    chain: Arc<Mutex<SimulatedL1Chain>>,
+}
+
+impl SimulatedBaseLayer {
+    /// Creates a chain that contains only the genesis block.
+    pub fn new(config: SimulatedBaseLayerConfig) -> Self {
+        Self { chain: Arc::new(Mutex::new(SimulatedL1Chain::new(config))) }
+    }
+
+    /// Mines a block containing all pending events and proofs, and returns a reference to it.
+    pub fn mine_block(&self) -> L1BlockReference {
+        self.chain().mine_block()
+    }
+
+    /// Mines `n_blocks` blocks, and returns a reference to the last one.
+    pub fn mine_blocks(&self, n_blocks: usize) -> L1BlockReference {
+        let mut chain = self.chain();
+        for _ in 0..n_blocks {
+            chain.mine_block();
+        }
+        chain.tip().reference()
+    }
+
+    /// Emits a `LogMessageToL2` event in the next mined block.
+    pub fn send_message_to_l2(&self, tx: L1HandlerTransaction, fee: Fee) {
+        let mut chain = self.chain();
+        let l1_tx_hash = chain.next_l1_tx_hash();
+        chain.pending_events.push(PendingEvent::LogMessageToL2 { tx, fee, l1_tx_hash })
+    }
+
+    /// Emits a `MessageToL2CancellationStarted` event in the next mined block.
+    pub fn start_message_to_l2_cancellation(&self, cancelled_tx: L1HandlerTransaction) {
+        self.chain()
+            .pending_events
+            .push(PendingEvent::MessageToL2CancellationStarted { cancelled_tx })
+    }
+
+    /// Emits a `MessageToL2Canceled` event in the next mined block.
+    pub fn cancel_message_to_l2(&self, event_data: EventData) {
+        self.chain().pending_events.push(PendingEvent::MessageToL2Canceled(event_data))
+    }
+
+    /// Emits a `ConsumedMessageToL2` event in the next mined block.
+    pub fn consume_message_to_l2(&self, tx: L1HandlerTransaction) {
+        self.chain().pending_events.push(PendingEvent::ConsumedMessageToL2(tx))
+    }
+
+    /// Updates the state of the Starknet contract to the given proved Starknet block, starting
+    /// from the next mined block.
+    pub fn prove_starknet_block(&self, proved_block: BlockHashAndNumber) {
+        self.chain().pending_proved_block = Some(proved_block);
+    }
+
+    /// Sets the gas prices of the blocks mined from now on.
+    pub fn set_gas_prices(&self, base_fee_per_gas: u128, blob_fee: u128) {
+        let mut chain = self.chain();
+        chain.base_fee_per_gas = base_fee_per_gas;
+        chain.blob_fee = blob_fee;
+    }
+
+    /// Drops the last `depth` blocks, along with their events and proofs. Blocks mined afterwards
+    /// get hashes different from those of the dropped blocks, so the dropped blocks are observed
+    /// as reorged out. Pending events are unaffected.
+    ///
+    /// Panics if `depth` reaches the genesis block.
+    pub fn reorg(&self, depth: u64) {
+        let mut chain = self.chain();
+        let tip_number = chain.tip().header.number;
+        assert!(depth <= tip_number, "Cannot reorg {depth} blocks out of {tip_number} blocks.");
+        let new_len = chain.blocks.len() - usize::try_from(depth).expect("Depth fits in usize.");
+        chain.blocks.truncate(new_len);
+    }
+
+    fn chain(&self) -> MutexGuard<'_, SimulatedL1Chain> {
+        self.chain.lock().expect("Simulated L1 chain lock is poisoned.")
+    }
+}
+
+impl Default for SimulatedBaseLayer {
+    fn default() -> Self {
+        Self::new(SimulatedBaseLayerConfig::default())
+    }
+}
+
+#[async_trait]
+impl BaseLayerContract for SimulatedBaseLayer {
+    type Error = SimulatedBaseLayerError;
+
+    async fn get_proved_block_at(
+        &self,
+        l1_block: L1BlockNumber,
+    ) -> SimulatedBaseLayerResult<BlockHashAndNumber> {
+        let chain = self.chain();
+        let block =
+            chain.block_at(l1_block).ok_or(SimulatedBaseLayerError::BlockNotFound(l1_block))?;
+        Ok(block.proved_block)
+    }
+
+    async fn latest_proved_block(
+        &self,
+        finality: u64,
+    ) -> SimulatedBaseLayerResult<Option<BlockHashAndNumber>> {
+        let Some(l1_block_number) = self.latest_l1_block_number(finality).await? else {
+            return Ok(None);
+        };
+        self.get_proved_block_at(l1_block_number).await.map(Some)
+    }
+
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> SimulatedBaseLayerResult<Option<L1BlockNumber>> {
+        Ok(self.chain().tip().header.number.checked_sub(finality))
+    }
+
+    async fn latest_l1_block(
+        &self,
+        finality: u64,
+    ) -> SimulatedBaseLayerResult<Option<L1BlockReference>> {
+        let Some(l1_block_number) = self.latest_l1_block_number(finality).await? else {
+            return Ok(None);
+        };
+        self.l1_block_at(l1_block_number).await
+    }
+
+    async fn l1_block_at(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> SimulatedBaseLayerResult<Option<L1BlockReference>> {
+        Ok(self.chain().block_at(block_number).map(SimulatedL1Block::reference))
+    }
+
+    async fn events<'a>(
+        &'a self,
+        block_range: RangeInclusive<L1BlockNumber>,
+        event_identifiers: &'a [&'a str],
+    ) -> SimulatedBaseLayerResult<Vec<L1Event>> {
+        let chain = self.chain();
+        let events = chain
+            .blocks
+            .iter()
+            .filter(|block| block_range.contains(&block.header.number))
+            .flat_map(|block| &block.events)
+            .filter(|(identifier, _)| event_identifiers.contains(identifier))
+            .map(|(_, event)| event.clone())
+            .collect();
+        Ok(events)
+    }
+
+    async fn get_block_header(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> SimulatedBaseLayerResult<Option<L1BlockHeader>> {
+        Ok(self.chain().block_at(block_number).map(|block| block.header.clone()))
+    }
+
+    /// The simulated chain has no provider, so this is a noop.
+    async fn set_provider_url(&mut self, _url: Url) -> SimulatedBaseLayerResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SimulatedBaseLayerError {
+    #[error("L1 block {0} does not exist.")]
+    BlockNotFound(L1BlockNumber),
+}
+
+#[derive(Debug)]
+struct SimulatedL1Chain {
+    config: SimulatedBaseLayerConfig,
+    /// Indexed by block number, starting from the genesis block.
+    blocks: Vec<SimulatedL1Block>,
+    pending_events: Vec<PendingEvent>,
+    pending_proved_block: Option<BlockHashAndNumber>,
+    base_fee_per_gas: u128,
+    blob_fee: u128,
+    // Counters that are never reset, not even by reorgs, in order to keep hashes unique.
+    n_blocks_mined: u64,
+    n_l1_txs_sent: u64,
+}
+
+impl SimulatedL1Chain {
+    fn new(config: SimulatedBaseLayerConfig) -> Self {
+        let genesis = SimulatedL1Block {
+            header: L1BlockHeader {
+                number: 0,
+                hash: unique_hash(0),
+                parent_hash: L1BlockHash::default(),
+                timestamp: config.genesis_timestamp,
+                base_fee_per_gas: config.base_fee_per_gas,
+                blob_fee: config.blob_fee,
+            },
+            events: vec![],
+            proved_block: BlockHashAndNumber::default(),
+        };
+
+        Self {
+            base_fee_per_gas: config.base_fee_per_gas,
+            blob_fee: config.blob_fee,
+            config,
+            blocks: vec![genesis],
+            pending_events: vec![],
+            pending_proved_block: None,
+            n_blocks_mined: 1,
+            n_l1_txs_sent: 0,
+        }
+    }
+
+    fn mine_block(&mut self) -> L1BlockReference {
+        let parent = self.tip();
+        let (parent_hash, parent_proved_block) = (parent.header.hash, parent.proved_block);
+        let number = parent.header.number + 1;
+        let timestamp = BlockTimestamp(
+            self.config.genesis_timestamp.0 + number * self.config.block_time_seconds,
+        );
+        let header = L1BlockHeader {
+            number,
+            hash: unique_hash(self.n_blocks_mined),
+            parent_hash,
+            timestamp,
+            base_fee_per_gas: self.base_fee_per_gas,
+            blob_fee: self.blob_fee,
+        };
+        let proved_block = self.pending_proved_block.take().unwrap_or(parent_proved_block);
+        let events = std::mem::take(&mut self.pending_events)
+            .into_iter()
+            .map(|event| event.into_l1_event(timestamp))
+            .collect();
+        self.n_blocks_mined += 1;
+
+        let block = SimulatedL1Block { header, events, proved_block };
+        let reference = block.reference();
+        self.blocks.push(block);
+        reference
+    }
+
+    fn next_l1_tx_hash(&mut self) -> FixedBytes<32> {
+        let l1_tx_hash = FixedBytes(unique_hash(self.n_l1_txs_sent));
+        self.n_l1_txs_sent += 1;
+        l1_tx_hash
+    }
+
+    fn tip(&self) -> &SimulatedL1Block {
+        self.blocks.last().expect("The genesis block is never removed.")
+    }
+
+    fn block_at(&self, block_number: L1BlockNumber) -> Option<&SimulatedL1Block> {
+        self.blocks.get(usize::try_from(block_number).ok()?)
+    }
+}
+
+#[derive(Debug)]
+struct SimulatedL1Block {
+    header: L1BlockHeader,
+    events: Vec<(EventIdentifier, L1Event)>,
+    /// The latest proved Starknet block, as stored in the Starknet contract at this block.
+    proved_block: BlockHashAndNumber,
+}
+
+impl SimulatedL1Block {
+    fn reference(&self) -> L1BlockReference {
+        L1BlockReference { number: self.header.number, hash: self.header.hash }
+    }
+}
+
+/// An event that is not included in a block yet, so its timestamp is unknown.
+#[derive(Debug)]
+enum PendingEvent {
+    LogMessageToL2 { tx: L1HandlerTransaction, fee: Fee, l1_tx_hash: FixedBytes<32> },
+    MessageToL2CancellationStarted { cancelled_tx: L1HandlerTransaction },
+    MessageToL2Canceled(EventData),
+    ConsumedMessageToL2(L1HandlerTransaction),
+}
+
+impl PendingEvent {
+    fn into_l1_event(self, timestamp: BlockTimestamp) -> (EventIdentifier, L1Event) {
+        match self {
+            PendingEvent::LogMessageToL2 { tx, fee, l1_tx_hash } => (
+                LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+                L1Event::LogMessageToL2 { tx, fee, l1_tx_hash: Some(l1_tx_hash), timestamp },
+            ),
+            PendingEvent::MessageToL2CancellationStarted { cancelled_tx } => (
+                MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+                L1Event::MessageToL2CancellationStarted {
+                    cancelled_tx,
+                    cancellation_request_timestamp: timestamp,
+                },
+            ),
+            PendingEvent::MessageToL2Canceled(event_data) => {
+                (MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER, L1Event::MessageToL2Canceled(event_data))
+            }
+            PendingEvent::ConsumedMessageToL2(tx) => {
+                (CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER, L1Event::ConsumedMessageToL2(tx))
+            }
+        }
+    }
+}
+
+fn unique_hash(seed: u64) -> [u8; 32] {
+    let mut hash = [0; 32];
+    // Offset by one to avoid the zero hash.
+    hash[24..].copy_from_slice(&(seed + 1).to_be_bytes());
+    hash
+}
@@ -0,0 +1,133 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber, BlockTimestamp};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::L1HandlerTransaction;
+use starknet_api::{felt, nonce};
+
+use crate::constants::{
+    CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+};
+use crate::simulated_base_layer::{
+    SimulatedBaseLayer,
+    SimulatedBaseLayerConfig,
+    SimulatedBaseLayerError,
+};
+use crate::{BaseLayerContract, L1Event};
+
+fn tx(nonce: u8) -> L1HandlerTransaction {
+    L1HandlerTransaction { nonce: nonce!(nonce), ..Default::default() }
+}
+
+#[tokio::test]
+async fn mined_blocks_are_chained() {
+    let config = SimulatedBaseLayerConfig::default();
+    let base_layer = SimulatedBaseLayer::new(config.clone());
+    let genesis = base_layer.get_block_header(0).await.unwrap().unwrap();
+
+    base_layer.set_gas_prices(7, 3);
+    let first = base_layer.mine_block();
+    let second = base_layer.mine_block();
+
+    assert_eq!(base_layer.latest_l1_block(0).await.unwrap(), Some(second));
+    assert_eq!(base_layer.latest_l1_block(1).await.unwrap(), Some(first));
+    assert_eq!(base_layer.latest_l1_block_number(3).await.unwrap(), None);
+    assert_eq!(base_layer.l1_block_at(3).await.unwrap(), None);
+
+    let second_header = base_layer.get_block_header(2).await.unwrap().unwrap();
+    assert_eq!(second_header.hash, second.hash);
+    assert_eq!(second_header.parent_hash, first.hash);
+    assert_ne!(first.hash, genesis.hash);
+    assert_eq!(
+        second_header.timestamp,
+        BlockTimestamp(config.genesis_timestamp.0 + 2 * config.block_time_seconds)
+    );
+    assert_eq!((genesis.base_fee_per_gas, genesis.blob_fee), (config.base_fee_per_gas, 1));
+    assert_eq!((second_header.base_fee_per_gas, second_header.blob_fee), (7, 3));
+}
+
+#[tokio::test]
+async fn events_are_included_in_the_next_mined_block() {
+    let base_layer = SimulatedBaseLayer::default();
+    base_layer.send_message_to_l2(tx(0), Fee(1));
+    base_layer.send_message_to_l2(tx(1), Fee(1));
+    base_layer.mine_block();
+    base_layer.start_message_to_l2_cancellation(tx(0));
+    base_layer.consume_message_to_l2(tx(1));
+    base_layer.mine_block();
+    let first = base_layer.get_block_header(1).await.unwrap().unwrap();
+    let second = base_layer.get_block_header(2).await.unwrap().unwrap();
+
+    let all_identifiers = [
+        LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+        MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+        CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    ];
+    let events = base_layer.events(0..=2, &all_identifiers).await.unwrap();
+    assert_eq!(events.len(), 4);
+    assert_matches!(
+        &events[0],
+        L1Event::LogMessageToL2 { tx: sent_tx, fee: Fee(1), l1_tx_hash: Some(_), timestamp }
+        if *sent_tx == tx(0) && *timestamp == first.timestamp
+    );
+    assert_matches!(
+        &events[1],
+        L1Event::LogMessageToL2 { tx: sent_tx, .. } if *sent_tx == tx(1)
+    );
+    assert_eq!(
+        events[2],
+        L1Event::MessageToL2CancellationStarted {
+            cancelled_tx: tx(0),
+            cancellation_request_timestamp: second.timestamp,
+        }
+    );
+    assert_eq!(events[3], L1Event::ConsumedMessageToL2(tx(1)));
+
+    // Filtered by block range and by event identifier.
+    let events = base_layer.events(2..=2, &[LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER]).await.unwrap();
+    assert_eq!(events, vec![]);
+    let events =
+        base_layer.events(1..=1, &[MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER]).await;
+    assert_eq!(events, Ok(vec![]));
+}
+
+#[tokio::test]
+async fn reorg_replaces_blocks_and_their_events() {
+    let base_layer = SimulatedBaseLayer::default();
+    base_layer.mine_block();
+    base_layer.send_message_to_l2(tx(0), Fee(1));
+    let reorged_out = base_layer.mine_block();
+
+    base_layer.reorg(1);
+    assert_eq!(base_layer.latest_l1_block_number(0).await.unwrap(), Some(1));
+    let replacement = base_layer.mine_block();
+
+    assert_eq!(replacement.number, reorged_out.number);
+    assert_ne!(replacement.hash, reorged_out.hash);
+    let events = base_layer.events(0..=2, &[LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER]).await.unwrap();
+    assert_eq!(events, vec![]);
+}
+
+#[tokio::test]
+async fn proved_blocks_follow_the_chain() {
+    let base_layer = SimulatedBaseLayer::default();
+    let proved_block =
+        BlockHashAndNumber { number: BlockNumber(100), hash: BlockHash(felt!("0x100")) };
+
+    base_layer.prove_starknet_block(proved_block);
+    assert_eq!(base_layer.latest_proved_block(0).await.unwrap(), Some(Default::default()));
+    base_layer.mine_blocks(2);
+
+    assert_eq!(base_layer.latest_proved_block(0).await.unwrap(), Some(proved_block));
+    assert_eq!(base_layer.get_proved_block_at(1).await.unwrap(), proved_block);
+    assert_eq!(base_layer.get_proved_block_at(0).await.unwrap(), Default::default());
+    assert_eq!(
+        base_layer.get_proved_block_at(3).await,
+        Err(SimulatedBaseLayerError::BlockNotFound(3))
+    );
+
+    base_layer.reorg(2);
+    assert_eq!(base_layer.latest_proved_block(0).await.unwrap(), Some(Default::default()));
+}
@@ -4,3 +4,4 @@ pub mod pending_classes;
 pub mod python_json;
 pub mod state;
 pub mod storage_query;
+pub mod sync_notifications;
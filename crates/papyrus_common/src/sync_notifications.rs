@@ -0,0 +1,19 @@
+use starknet_api::block::{BlockHashAndNumber, BlockNumber};
+
+/// The capacity of the channel of sync notifications. A receiver that falls behind by more
+/// notifications loses the oldest ones.
+pub const SYNC_NOTIFICATIONS_CHANNEL_CAPACITY: usize = 1000;
+
+/// A change in the chain that the node synced, published by the sync after committing it to the
+/// storage (or to the pending data), so that readers of the storage can react to it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncNotification {
+    /// The body and state diff of the block were committed, so it's now the latest block.
+    NewBlock(BlockNumber),
+    /// All the blocks below the given block number were accepted on L1.
+    AcceptedOnL1(BlockNumber),
+    /// The blocks in the given range (inclusive) were reverted.
+    Reorg { first_reverted: BlockHashAndNumber, last_reverted: BlockHashAndNumber },
+    /// The pending data was updated.
+    PendingDataUpdated,
+}
@@ -32,10 +32,11 @@ use futures::StreamExt;
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
 use papyrus_common::metrics::COLLECT_PROFILING_METRICS;
 use papyrus_common::pending_classes::PendingClasses;
+use papyrus_common::sync_notifications::{SyncNotification, SYNC_NOTIFICATIONS_CHANNEL_CAPACITY};
 use papyrus_monitoring_gateway::MonitoringServer;
 use starknet_api::block::{BlockHash, BlockHashAndNumber};
 use starknet_api::felt;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tokio::task::JoinHandle;
 use tracing::metadata::LevelFilter;
 use tracing::{debug_span, error, info, warn, Instrument};
@@ -65,6 +66,7 @@ pub struct PapyrusResources {
     pub shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pub pending_data: Arc<RwLock<PendingData>>,
     pub pending_classes: Arc<RwLock<PendingClasses>>,
+    pub sync_notifications: broadcast::Sender<SyncNotification>,
     pub class_manager_client: SharedClassManagerClient,
 }
 
@@ -97,6 +99,7 @@ impl PapyrusResources {
             ..Default::default()
         }));
         let pending_classes = Arc::new(RwLock::new(PendingClasses::default()));
+        let (sync_notifications, _) = broadcast::channel(SYNC_NOTIFICATIONS_CHANNEL_CAPACITY);
         // TODO(noamsp): Remove this and use the real client instead once implemented.
         let class_manager_client = Arc::new(EmptyClassManagerClient);
         Ok(Self {
@@ -107,6 +110,7 @@ impl PapyrusResources {
             shared_highest_block,
             pending_data,
             pending_classes,
+            sync_notifications,
             class_manager_client,
         })
     }
@@ -134,6 +138,7 @@ async fn spawn_rpc_server(
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     storage_reader: StorageReader,
 ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
     let (_, server_handle) = run_server(
@@ -141,9 +146,11 @@ async fn spawn_rpc_server(
         shared_highest_block,
         pending_data,
         pending_classes,
+        sync_notifications,
         storage_reader,
         VERSION_FULL,
         None,
+        None,
     )
     .await?;
     Ok(tokio::spawn(async move {
@@ -158,6 +165,7 @@ async fn spawn_rpc_server(
     _shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     _pending_data: Arc<RwLock<PendingData>>,
     _pending_classes: Arc<RwLock<PendingClasses>>,
+    _sync_notifications: broadcast::Sender<SyncNotification>,
     _storage_reader: StorageReader,
 ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
     Ok(tokio::spawn(future::pending()))
@@ -184,6 +192,7 @@ async fn run_sync(
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     storage: (StorageReader, StorageWriter),
 ) -> anyhow::Result<()> {
     let (sync_config, central_config, base_layer_config) = configs;
@@ -200,6 +209,7 @@ async fn run_sync(
         shared_highest_block,
         pending_data,
         pending_classes,
+        sync_notifications,
         central_source,
         pending_source,
         base_layer_source,
@@ -219,6 +229,7 @@ async fn spawn_sync_client(
     shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
+    sync_notifications: broadcast::Sender<SyncNotification>,
     class_manager_client: SharedClassManagerClient,
 ) -> JoinHandle<anyhow::Result<()>> {
     match (config.sync, config.p2p_sync) {
@@ -234,6 +245,7 @@ async fn spawn_sync_client(
                 shared_highest_block,
                 pending_data,
                 pending_classes,
+                sync_notifications,
                 storage,
             ))
         }
@@ -261,6 +273,7 @@ async fn spawn_sync_client(
                 p2p_sync_client_channels,
                 futures::stream::pending().boxed(),
                 class_manager_client,
+                sync_notifications,
             );
             tokio::spawn(async move { Ok(p2p_sync.run().await.map(|_never| ())?) })
         }
@@ -338,6 +351,7 @@ async fn run_threads(
             resources.shared_highest_block.clone(),
             resources.pending_data.clone(),
             resources.pending_classes.clone(),
+            resources.sync_notifications.clone(),
             resources.storage_reader.clone(),
         )
         .await?
@@ -366,6 +380,7 @@ async fn run_threads(
             resources.shared_highest_block,
             resources.pending_data,
             resources.pending_classes,
+            resources.sync_notifications,
             resources.class_manager_client.clone(),
         )
         .await
@@ -172,3 +172,18 @@ impl TryFrom<RawSignature> for starknet_crypto::Signature {
         Ok(starknet_crypto::Signature { r, s })
     }
 }
+
+impl From<Signature> for RawSignature {
+    fn from(signature: Signature) -> Self {
+        Self(vec![signature.r, signature.s])
+    }
+}
+
+impl TryFrom<RawSignature> for Signature {
+    type Error = SignatureConversionError;
+
+    fn try_from(signature: RawSignature) -> Result<Self, Self::Error> {
+        let starknet_crypto::Signature { r, s } = signature.try_into()?;
+        Ok(Signature { r, s })
+    }
+}
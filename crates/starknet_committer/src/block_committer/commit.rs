@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_patricia::patricia_merkle_tree::types::{NodeIndex, SortedLeafIndices};
-use starknet_patricia_storage::map_storage::{BorrowedMapStorage, MapStorage};
+use starknet_patricia_storage::storage_trait::Storage;
 use tracing::{info, warn};
 
 use crate::block_committer::errors::BlockCommitmentError;
@@ -24,7 +24,7 @@ type BlockCommitmentResult<T> = Result<T, BlockCommitmentError>;
 
 pub async fn commit_block(
     input: Input<ConfigImpl>,
-    storage: &mut MapStorage,
+    storage: &impl Storage,
 ) -> BlockCommitmentResult<FilledForest> {
     let (mut storage_tries_indices, mut contracts_trie_indices, mut classes_trie_indices) =
         get_all_modified_indices(&input.state_diff);
@@ -39,7 +39,7 @@ pub async fn commit_block(
     let actual_storage_updates = input.state_diff.actual_storage_updates();
     let actual_classes_updates = input.state_diff.actual_classes_updates();
     let (mut original_forest, original_contracts_trie_leaves) = OriginalSkeletonForest::create(
-        BorrowedMapStorage { storage },
+        storage,
         input.contracts_trie_root_hash,
         input.classes_trie_root_hash,
         &actual_storage_updates,
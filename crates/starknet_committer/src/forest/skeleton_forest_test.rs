@@ -311,7 +311,7 @@ fn test_create_original_skeleton_forest(
     };
 
     let (actual_forest, original_contracts_trie_leaves) = OriginalSkeletonForest::create(
-        BorrowedMapStorage { storage: &mut storage },
+        &BorrowedMapStorage { storage: &mut storage },
         input.contracts_trie_root_hash,
         input.classes_trie_root_hash,
         &input.state_diff.actual_storage_updates(),
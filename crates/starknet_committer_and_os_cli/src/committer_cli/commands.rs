@@ -35,7 +35,9 @@ pub async fn parse_and_commit(
 
 pub async fn commit(input: InputImpl, output_path: String, mut storage: MapStorage) {
     let serialized_filled_forest = SerializedForest(
-        commit_block(input, &mut storage).await.expect("Failed to commit the given block."),
+        commit_block(input, &BorrowedMapStorage { storage: &mut storage })
+            .await
+            .expect("Failed to commit the given block."),
     );
     // Create an empty storage for the new facts.
     let mut empty_storage = HashMap::new();
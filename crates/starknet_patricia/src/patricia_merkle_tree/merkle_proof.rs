@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use starknet_patricia_storage::errors::{DeserializationError, StorageError};
+use starknet_patricia_storage::storage_trait::{create_db_key, Storage};
+use thiserror::Error;
+
+use crate::hash::hash_trait::HashOutput;
+use crate::patricia_merkle_tree::filled_tree::node::FilledNode;
+use crate::patricia_merkle_tree::filled_tree::node_serde::PatriciaPrefix;
+use crate::patricia_merkle_tree::node_data::inner_node::{BinaryData, EdgeData, NodeData};
+use crate::patricia_merkle_tree::node_data::leaf::Leaf;
+use crate::patricia_merkle_tree::types::NodeIndex;
+
+#[cfg(test)]
+#[path = "merkle_proof_test.rs"]
+pub mod merkle_proof_test;
+
+/// An inner node of a Patricia-Merkle tree, as it appears in a Merkle proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofNode {
+    Binary(BinaryData),
+    Edge(EdgeData),
+}
+
+/// The inner nodes of a Merkle proof, by hash.
+pub type ProofNodes = HashMap<HashOutput, ProofNode>;
+
+#[derive(Debug, Error)]
+pub enum MerkleProofError {
+    #[error("Failed to deserialize the storage value: {0:?} while fetching a Merkle proof.")]
+    Deserialization(#[from] DeserializationError),
+    #[error("Unable to read from storage the storage key: {0:?} while fetching a Merkle proof.")]
+    StorageRead(#[from] StorageError),
+}
+
+pub type MerkleProofResult<T> = Result<T, MerkleProofError>;
+
+/// A Merkle proof of a set of leaves.
+#[derive(Debug, PartialEq)]
+pub struct MerkleProof<L: Leaf> {
+    /// The inner nodes on the paths from the root to the leaves.
+    pub nodes: ProofNodes,
+    /// The non-empty leaves among the proven ones.
+    pub leaves: HashMap<NodeIndex, L>,
+}
+
+/// Fetches a Merkle proof of the given leaves from the tree with the given root.
+///
+/// A path ends either at the leaf, whose value the verifier hashes to the bottom of the path, or
+/// at an edge node diverging from it, which proves that the leaf is empty. The nodes of the paths
+/// are merged, so that shared nodes appear once.
+pub fn fetch_merkle_proof<L: Leaf>(
+    storage: &impl Storage,
+    root_hash: HashOutput,
+    leaf_indices: &[NodeIndex],
+) -> MerkleProofResult<MerkleProof<L>> {
+    let mut proof = MerkleProof { nodes: ProofNodes::new(), leaves: HashMap::new() };
+    if root_hash == HashOutput::ROOT_OF_EMPTY_TREE {
+        return Ok(proof);
+    }
+
+    for leaf_index in leaf_indices {
+        let mut index = NodeIndex::ROOT;
+        let mut hash = root_hash;
+        let mut reached_leaf = true;
+        while !index.is_leaf() {
+            let node = match proof.nodes.get(&hash) {
+                Some(node) => node.clone(),
+                None => {
+                    let node = read_node::<L>(storage, hash, false)?;
+                    let node = match node {
+                        NodeData::Binary(binary_data) => ProofNode::Binary(binary_data),
+                        NodeData::Edge(edge_data) => ProofNode::Edge(edge_data),
+                        NodeData::Leaf(_) => unreachable!("Inner nodes are not read as leaves."),
+                    };
+                    proof.nodes.insert(hash, node.clone());
+                    node
+                }
+            };
+            // The number of levels between the node and the leaves.
+            let height = NodeIndex::BITS - index.bit_length();
+            match node {
+                ProofNode::Binary(BinaryData { left_hash, right_hash }) => {
+                    let child_index = *leaf_index >> (height - 1);
+                    hash = if child_index == index << 1 { left_hash } else { right_hash };
+                    index = child_index;
+                }
+                ProofNode::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+                    let bottom_index = path_to_bottom.bottom_index(index);
+                    if *leaf_index >> (height - u8::from(path_to_bottom.length)) != bottom_index {
+                        // The edge diverges from the path to the leaf, so the leaf is empty.
+                        reached_leaf = false;
+                        break;
+                    }
+                    hash = bottom_hash;
+                    index = bottom_index;
+                }
+            }
+        }
+        if reached_leaf {
+            let NodeData::Leaf(leaf) = read_node::<L>(storage, hash, true)? else {
+                unreachable!("Leaves are read as leaves.");
+            };
+            proof.leaves.insert(*leaf_index, leaf);
+        }
+    }
+    Ok(proof)
+}
+
+fn read_node<L: Leaf>(
+    storage: &impl Storage,
+    hash: HashOutput,
+    is_leaf: bool,
+) -> MerkleProofResult<NodeData<L>> {
+    let prefix = if is_leaf {
+        PatriciaPrefix::Leaf(L::get_static_prefix())
+    } else {
+        PatriciaPrefix::InnerNode
+    };
+    let db_key = create_db_key(prefix.into(), &hash.0.to_bytes_be());
+    let value = storage.get(&db_key)?.ok_or(StorageError::MissingKey(db_key))?;
+    Ok(FilledNode::<L>::deserialize(hash, &value, is_leaf)?.data)
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use rstest::rstest;
+use starknet_patricia_storage::map_storage::{BorrowedMapStorage, MapStorage};
+use starknet_types_core::felt::Felt;
+
+use crate::hash::hash_trait::HashOutput;
+use crate::patricia_merkle_tree::external_test_utils::tree_computation_flow;
+use crate::patricia_merkle_tree::filled_tree::tree::FilledTree;
+use crate::patricia_merkle_tree::internal_test_utils::{
+    MockLeaf,
+    OriginalSkeletonMockTrieConfig,
+    TestTreeHashFunction,
+};
+use crate::patricia_merkle_tree::merkle_proof::{
+    fetch_merkle_proof,
+    MerkleProof,
+    MerkleProofError,
+    ProofNode,
+    ProofNodes,
+};
+use crate::patricia_merkle_tree::node_data::inner_node::{BinaryData, EdgeData, NodeData};
+use crate::patricia_merkle_tree::types::NodeIndex;
+use crate::patricia_merkle_tree::updated_skeleton_tree::hash_function::TreeHashFunction;
+
+/// Creates a tree with the given leaves, and returns its storage and root hash.
+async fn create_tree(leaves: &[(u128, u128)]) -> (MapStorage, HashOutput) {
+    let leaf_modifications = leaves
+        .iter()
+        .map(|(index, value)| (NodeIndex::FIRST_LEAF + *index, MockLeaf(Felt::from(*value))))
+        .collect();
+    let mut storage = MapStorage::new();
+    let filled_tree = tree_computation_flow::<MockLeaf, TestTreeHashFunction>(
+        leaf_modifications,
+        &BorrowedMapStorage { storage: &mut storage },
+        HashOutput::ROOT_OF_EMPTY_TREE,
+        OriginalSkeletonMockTrieConfig::new(false),
+    )
+    .await;
+    (filled_tree.serialize(), filled_tree.get_root_hash())
+}
+
+/// Verifies the path from the root to the given leaf, as a light client would. Returns the hash of
+/// the leaf, or `None` if the proof shows the leaf is empty.
+fn verify_leaf(
+    proof_nodes: &ProofNodes,
+    root_hash: HashOutput,
+    leaf_index: NodeIndex,
+) -> Option<HashOutput> {
+    let mut index = NodeIndex::ROOT;
+    let mut hash = root_hash;
+    while !index.is_leaf() {
+        let node = proof_nodes.get(&hash).expect("The proof should contain the path to the leaf.");
+        let node_data = match node {
+            ProofNode::Binary(binary_data) => NodeData::<MockLeaf>::Binary(binary_data.clone()),
+            ProofNode::Edge(edge_data) => NodeData::Edge(*edge_data),
+        };
+        assert_eq!(TestTreeHashFunction::compute_node_hash(&node_data), hash);
+
+        let height = NodeIndex::BITS - index.bit_length();
+        match node {
+            ProofNode::Binary(BinaryData { left_hash, right_hash }) => {
+                let child_index = leaf_index >> (height - 1);
+                hash = if child_index == index << 1 { *left_hash } else { *right_hash };
+                index = child_index;
+            }
+            ProofNode::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+                let bottom_index = path_to_bottom.bottom_index(index);
+                if leaf_index >> (height - u8::from(path_to_bottom.length)) != bottom_index {
+                    return None;
+                }
+                hash = *bottom_hash;
+                index = bottom_index;
+            }
+        }
+    }
+    Some(hash)
+}
+
+#[rstest]
+#[case::single_leaf(&[(7, 0x10)], &[7])]
+#[case::siblings(&[(2, 0x10), (3, 0x20), (1 << 100, 0x1000)], &[2, 3])]
+#[case::all_leaves(&[(2, 0x10), (3, 0x20), (1 << 100, 0x1000)], &[2, 3, 1 << 100])]
+#[case::empty_leaves(&[(2, 0x10), (3, 0x20), (1 << 100, 0x1000)], &[0, 5, 1 << 99, (1 << 100) + 1])]
+#[case::mixed_leaves(&[(2, 0x10), (3, 0x20), (1 << 100, 0x1000)], &[3, 4, 1 << 100])]
+#[tokio::test]
+async fn proof_verifies_leaves(#[case] leaves: &[(u128, u128)], #[case] proven_leaves: &[u128]) {
+    let (mut storage, root_hash) = create_tree(leaves).await;
+    let storage = BorrowedMapStorage { storage: &mut storage };
+    let leaf_values: HashMap<u128, u128> = leaves.iter().copied().collect();
+    let leaf_indices: Vec<NodeIndex> =
+        proven_leaves.iter().map(|index| NodeIndex::FIRST_LEAF + *index).collect();
+
+    let proof = fetch_merkle_proof::<MockLeaf>(&storage, root_hash, &leaf_indices).unwrap();
+
+    for (leaf, leaf_index) in proven_leaves.iter().zip(leaf_indices) {
+        let expected_leaf = leaf_values.get(leaf).map(|value| MockLeaf(Felt::from(*value)));
+        assert_eq!(proof.leaves.get(&leaf_index), expected_leaf.as_ref());
+        let expected_hash =
+            expected_leaf.map(|leaf| TestTreeHashFunction::compute_leaf_hash(&leaf));
+        assert_eq!(verify_leaf(&proof.nodes, root_hash, leaf_index), expected_hash);
+    }
+}
+
+#[tokio::test]
+async fn proof_contains_only_the_path_nodes() {
+    let (mut storage, root_hash) = create_tree(&[(2, 0x10), (3, 0x20), (1 << 100, 0x1000)]).await;
+    let storage = BorrowedMapStorage { storage: &mut storage };
+
+    // The root is an edge to a binary node, whose children are edges to the parent of leaves 2 and
+    // 3, and to leaf 2^100.
+    let proof_nodes =
+        fetch_merkle_proof::<MockLeaf>(&storage, root_hash, &[NodeIndex::FIRST_LEAF + (1 << 100)])
+            .unwrap()
+            .nodes;
+    assert_eq!(proof_nodes.len(), 3);
+
+    let all_proof_nodes = fetch_merkle_proof::<MockLeaf>(
+        &storage,
+        root_hash,
+        &[NodeIndex::FIRST_LEAF + 2, NodeIndex::FIRST_LEAF + (1 << 100)],
+    )
+    .unwrap()
+    .nodes;
+    assert_eq!(all_proof_nodes.len(), 5);
+    assert!(proof_nodes.iter().all(|(hash, node)| all_proof_nodes.get(hash) == Some(node)));
+}
+
+#[test]
+fn empty_tree_has_empty_proof() {
+    let proof = fetch_merkle_proof::<MockLeaf>(
+        &BorrowedMapStorage { storage: &mut MapStorage::new() },
+        HashOutput::ROOT_OF_EMPTY_TREE,
+        &[NodeIndex::FIRST_LEAF],
+    )
+    .unwrap();
+    assert_eq!(proof, MerkleProof { nodes: ProofNodes::new(), leaves: HashMap::new() });
+}
+
+#[test]
+fn missing_node_fails() {
+    let result = fetch_merkle_proof::<MockLeaf>(
+        &BorrowedMapStorage { storage: &mut MapStorage::new() },
+        HashOutput(Felt::ONE),
+        &[NodeIndex::FIRST_LEAF],
+    );
+    assert!(matches!(result, Err(MerkleProofError::StorageRead(_))));
+}
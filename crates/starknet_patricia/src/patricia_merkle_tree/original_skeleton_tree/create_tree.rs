@@ -236,12 +236,16 @@ impl<'a> OriginalSkeletonTreeImpl<'a> {
             })
             .collect();
 
-        let db_vals = storage.mget(&db_keys);
+        let db_vals = storage.mget(&db_keys)?;
         for ((subtree, optional_val), db_key) in
-            subtrees.iter().zip(db_vals.iter()).zip(db_keys.into_iter())
+            subtrees.iter().zip(db_vals.into_iter()).zip(db_keys.into_iter())
         {
             let val = optional_val.ok_or(StorageError::MissingKey(db_key))?;
-            subtrees_roots.push(FilledNode::deserialize(subtree.root_hash, val, subtree.is_leaf())?)
+            subtrees_roots.push(FilledNode::deserialize(
+                subtree.root_hash,
+                &val,
+                subtree.is_leaf(),
+            )?)
         }
         Ok(subtrees_roots)
     }
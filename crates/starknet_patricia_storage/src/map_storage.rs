@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use serde::Serialize;
 
+use crate::errors::StorageError;
 use crate::storage_trait::{DbKey, DbValue, Storage};
 
 pub type MapStorage = HashMap<DbKey, DbValue>;
@@ -12,23 +13,23 @@ pub struct BorrowedMapStorage<'a> {
 }
 
 impl Storage for BorrowedMapStorage<'_> {
-    fn set(&mut self, key: DbKey, value: DbValue) -> Option<DbValue> {
-        self.storage.insert(key, value)
+    fn set(&mut self, key: DbKey, value: DbValue) {
+        self.storage.insert(key, value);
     }
 
     fn mset(&mut self, key_to_value: MapStorage) {
         self.storage.extend(key_to_value);
     }
 
-    fn delete(&mut self, key: &DbKey) -> Option<DbValue> {
-        self.storage.remove(key)
+    fn delete(&mut self, key: &DbKey) {
+        self.storage.remove(key);
     }
 
-    fn get(&self, key: &DbKey) -> Option<&DbValue> {
-        self.storage.get(key)
+    fn get(&self, key: &DbKey) -> Result<Option<DbValue>, StorageError> {
+        Ok(self.storage.get(key).map(|value| DbValue(value.0.clone())))
     }
 
-    fn mget(&self, keys: &[DbKey]) -> Vec<Option<&DbValue>> {
-        keys.iter().map(|key| self.storage.get(key)).collect()
+    fn mget(&self, keys: &[DbKey]) -> Result<Vec<Option<DbValue>>, StorageError> {
+        keys.iter().map(|key| self.get(key)).collect()
     }
 }
@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use libmdbx::{Geometry, WriteFlags, WriteMap};
+
+use crate::errors::StorageError;
+use crate::map_storage::MapStorage;
+use crate::storage_trait::{DbKey, DbValue, Storage};
+
+type Environment = libmdbx::Database<WriteMap>;
+
+// The database grows on demand, by this many bytes at a time, up to its maximal size.
+const GROWTH_STEP: isize = 1 << 30; // 1GB
+const MAX_SIZE: usize = 1 << 40; // 1TB
+
+/// A persistent storage, backed by an MDBX database.
+///
+/// Reads are served from the database, which pages the content in lazily, so only the content in
+/// use is held in memory. Writes are held in memory until [`MdbxStorage::flush`], which writes them
+/// in a single transaction, so a crash while flushing loses the whole batch and never part of it.
+/// Pages freed by overwritten and deleted entries are reused by later writes.
+#[derive(Debug)]
+pub struct MdbxStorage {
+    env: Environment,
+    // The writes since the last flush, by key. `None` stands for a deletion.
+    pending_writes: HashMap<DbKey, Option<DbValue>>,
+}
+
+impl MdbxStorage {
+    /// Opens the storage in the directory at `path`, creating it if needed.
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        std::fs::create_dir_all(path)?;
+        let env = Environment::new()
+            .set_geometry(Geometry {
+                size: Some(0..MAX_SIZE),
+                growth_step: Some(GROWTH_STEP),
+                ..Default::default()
+            })
+            .open(path)?;
+        Ok(Self { env, pending_writes: HashMap::new() })
+    }
+
+    /// Persists the writes made since the last flush. Returns only once they are on disk.
+    pub fn flush(&mut self) -> Result<(), StorageError> {
+        if self.pending_writes.is_empty() {
+            return Ok(());
+        }
+
+        let txn = self.env.begin_rw_txn()?;
+        let table = txn.open_table(None)?;
+        for (key, value) in &self.pending_writes {
+            match value {
+                Some(value) => txn.put(&table, &key.0, &value.0, WriteFlags::UPSERT)?,
+                None => {
+                    txn.del(&table, &key.0, None)?;
+                }
+            }
+        }
+        txn.commit()?;
+        self.pending_writes.clear();
+        Ok(())
+    }
+
+    /// Reads the keys that have no pending writes from the database, in a single transaction.
+    fn read_flushed(&self, keys: &[&DbKey]) -> Result<Vec<Option<DbValue>>, StorageError> {
+        let txn = self.env.begin_ro_txn()?;
+        let table = txn.open_table(None)?;
+        keys.iter()
+            .map(|key| {
+                let value = txn.get::<Cow<'_, [u8]>>(&table, &key.0)?;
+                Ok(value.map(|value| DbValue(value.into_owned())))
+            })
+            .collect()
+    }
+}
+
+impl Storage for MdbxStorage {
+    fn get(&self, key: &DbKey) -> Result<Option<DbValue>, StorageError> {
+        Ok(self.mget(std::slice::from_ref(key))?.pop().expect("A value is read per key."))
+    }
+
+    fn set(&mut self, key: DbKey, value: DbValue) {
+        self.pending_writes.insert(key, Some(value));
+    }
+
+    fn mget(&self, keys: &[DbKey]) -> Result<Vec<Option<DbValue>>, StorageError> {
+        let flushed_keys: Vec<&DbKey> =
+            keys.iter().filter(|key| !self.pending_writes.contains_key(key)).collect();
+        let mut flushed_values = self.read_flushed(&flushed_keys)?.into_iter();
+        Ok(keys
+            .iter()
+            .map(|key| match self.pending_writes.get(key) {
+                Some(pending_value) => pending_value.as_ref().map(|value| DbValue(value.0.clone())),
+                None => flushed_values.next().expect("A value is read per flushed key."),
+            })
+            .collect())
+    }
+
+    fn mset(&mut self, key_to_value: MapStorage) {
+        self.pending_writes.extend(key_to_value.into_iter().map(|(key, value)| (key, Some(value))));
+    }
+
+    fn delete(&mut self, key: &DbKey) {
+        self.pending_writes.insert(DbKey(key.0.clone()), None);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Serialize, Serializer};
 use starknet_types_core::felt::Felt;
 
+use crate::errors::StorageError;
 use crate::map_storage::MapStorage;
 
 #[derive(Debug, Eq, Hash, PartialEq)]
@@ -13,21 +14,20 @@ pub struct DbValue(pub Vec<u8>);
 
 pub trait Storage {
     /// Returns value from storage, if it exists.
-    fn get(&self, key: &DbKey) -> Option<&DbValue>;
+    fn get(&self, key: &DbKey) -> Result<Option<DbValue>, StorageError>;
 
-    /// Sets value in storage. If key already exists, its value is overwritten and the old value is
-    /// returned.
-    fn set(&mut self, key: DbKey, value: DbValue) -> Option<DbValue>;
+    /// Sets value in storage. If key already exists, its value is overwritten.
+    fn set(&mut self, key: DbKey, value: DbValue);
 
     /// Returns values from storage in same order of given keys. Value is None for keys that do not
     /// exist.
-    fn mget(&self, keys: &[DbKey]) -> Vec<Option<&DbValue>>;
+    fn mget(&self, keys: &[DbKey]) -> Result<Vec<Option<DbValue>>, StorageError>;
 
     /// Sets values in storage.
     fn mset(&mut self, key_to_value: MapStorage);
 
-    /// Deletes value from storage and returns its value if it exists. Returns None if not.
-    fn delete(&mut self, key: &DbKey) -> Option<DbValue>;
+    /// Deletes value from storage, if it exists.
+    fn delete(&mut self, key: &DbKey);
 }
 
 #[derive(Debug)]